@@ -0,0 +1,86 @@
+//! Compares the `json` and `simd-json` parsing backends on a large document.
+//!
+//! Run with `cargo bench --features simd-json --bench parse`.
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::time::{Duration, Instant};
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	util::parse_json
+};
+
+const ITERATIONS: usize = 10;
+
+/// Document with the given number of nodes, using an embedded context.
+fn document(nodes: usize) -> String {
+	let mut graph = Vec::with_capacity(nodes);
+	for i in 0..nodes {
+		graph.push(format!(r#"{{
+			"@id": "http://example.org/people/{i}",
+			"@type": "Person",
+			"name": "Person number {i}, with a somewhat long name é",
+			"knows": ["http://example.org/people/{next}", "http://example.org/people/{prev}"],
+			"age": {age},
+			"score": {score},
+			"address": {{"street": "{i} Main Street", "city": "Springfield", "tags": ["a", "b", "c"]}}
+		}}"#, i = i, next = (i + 1) % nodes, prev = (i + nodes - 1) % nodes, age = i % 100, score = i as f64 / 7.0))
+	}
+
+	format!(r#"{{
+		"@context": {{
+			"@vocab": "http://schema.org/",
+			"knows": {{"@type": "@id"}},
+			"age": {{"@type": "http://www.w3.org/2001/XMLSchema#integer"}}
+		}},
+		"@graph": [{}]
+	}}"#, graph.join(",\n"))
+}
+
+/// Median duration of the given task.
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+	let mut durations: Vec<Duration> = (0..ITERATIONS).map(|_| {
+		let start = Instant::now();
+		f();
+		start.elapsed()
+	}).collect();
+
+	durations.sort();
+	durations[ITERATIONS / 2]
+}
+
+fn expand(doc: &JsonValue) -> ExpandedDocument<IriBuf> {
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn report(name: &str, json: Duration, simd: Duration) {
+	println!("{:<24} json: {:>9.2?}  simd-json: {:>9.2?}  speedup: {:.2}x", name, json, simd, json.as_secs_f64() / simd.as_secs_f64())
+}
+
+fn main() {
+	for nodes in &[1_000, 10_000, 50_000] {
+		let source = document(*nodes);
+		println!("{} nodes, {} bytes", nodes, source.len());
+		assert_eq!(parse_json(&source).unwrap(), json::parse(&source).unwrap());
+
+		report("parse", measure(|| {
+			json::parse(&source).unwrap();
+		}), measure(|| {
+			parse_json(&source).unwrap();
+		}));
+
+		report("parse and expand", measure(|| {
+			expand(&json::parse(&source).unwrap());
+		}), measure(|| {
+			expand(&parse_json(&source).unwrap());
+		}));
+	}
+}
@@ -0,0 +1,250 @@
+//! Derive macros for the [`json-ld`](https://crates.io/crates/json-ld) crate.
+//!
+//! This crate is not meant to be used directly, but through the `derive` feature of the
+//! `json-ld` crate.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+	parse_macro_input,
+	spanned::Spanned,
+	Attribute,
+	Data,
+	DeriveInput,
+	Error,
+	Field,
+	Fields,
+	Lit,
+	LitStr,
+	Meta,
+	NestedMeta,
+	Type,
+	punctuated::Punctuated,
+	token::Comma
+};
+
+/// How a field is bound.
+enum Binding {
+	/// The field is bound to the node identifier.
+	Id,
+
+	/// The field is bound to the given property.
+	///
+	/// The flag is set if the values are stored in a list.
+	Property(String, bool)
+}
+
+/// How many values a field holds.
+enum Multiplicity {
+	Required,
+	Optional,
+	Multiple
+}
+
+impl Multiplicity {
+	fn of(ty: &Type) -> Multiplicity {
+		if let Type::Path(path) = ty {
+			if let Some(segment) = path.path.segments.last() {
+				if segment.ident == "Option" {
+					return Multiplicity::Optional
+				}
+
+				if segment.ident == "Vec" {
+					return Multiplicity::Multiple
+				}
+			}
+		}
+
+		Multiplicity::Required
+	}
+}
+
+/// Parse the `ld` attributes, returning their nested items.
+fn ld_attributes(attrs: &[Attribute]) -> Result<Vec<NestedMeta>, Error> {
+	let mut result = Vec::new();
+	for attr in attrs {
+		if attr.path.is_ident("ld") {
+			match attr.parse_meta()? {
+				Meta::List(list) => result.extend(list.nested),
+				meta => return Err(Error::new(meta.span(), "expected `#[ld(...)]`"))
+			}
+		}
+	}
+
+	Ok(result)
+}
+
+/// Checks that the given literal is a valid IRI.
+fn iri(lit: &LitStr) -> Result<String, Error> {
+	let value = lit.value();
+	match iref::Iri::new(&value) {
+		Ok(_) => Ok(value),
+		Err(_) => Err(Error::new(lit.span(), "invalid IRI"))
+	}
+}
+
+fn field_binding(field: &Field) -> Result<Binding, Error> {
+	let mut binding = None;
+	let mut list = false;
+	for item in ld_attributes(&field.attrs)? {
+		let b = match item {
+			NestedMeta::Lit(Lit::Str(ref lit)) => Binding::Property(iri(lit)?, false),
+			NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("id") => Binding::Id,
+			NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("list") => {
+				list = true;
+				continue
+			},
+			item => return Err(Error::new(item.span(), "expected a property IRI, `id` or `list`"))
+		};
+
+		if binding.is_some() {
+			return Err(Error::new(field.span(), "multiple bindings for the same field"))
+		}
+
+		binding = Some(b)
+	}
+
+	match binding {
+		Some(Binding::Property(iri, _)) => Ok(Binding::Property(iri, list)),
+		Some(Binding::Id) if list => Err(Error::new(field.span(), "the node identifier cannot be a list")),
+		Some(binding) => Ok(binding),
+		None => Err(Error::new(field.span(), "missing `#[ld(...)]` attribute"))
+	}
+}
+
+fn struct_type(input: &DeriveInput) -> Result<Option<String>, Error> {
+	let mut ty = None;
+	for item in ld_attributes(&input.attrs)? {
+		match item {
+			NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("type") => {
+				match &nv.lit {
+					Lit::Str(lit) => ty = Some(iri(lit)?),
+					lit => return Err(Error::new(lit.span(), "expected a type IRI"))
+				}
+			},
+			item => return Err(Error::new(item.span(), "expected `type = \"...\"`"))
+		}
+	}
+
+	Ok(ty)
+}
+
+fn named_fields<'a>(input: &'a DeriveInput, name: &str) -> Result<&'a Punctuated<Field, Comma>, Error> {
+	match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => Ok(&fields.named),
+			_ => Err(Error::new(input.span(), format!("`{}` can only be derived for structures with named fields", name)))
+		},
+		_ => Err(Error::new(input.span(), format!("`{}` can only be derived for structures", name)))
+	}
+}
+
+fn derive_from_linked_data(input: DeriveInput) -> Result<TokenStream2, Error> {
+	let name = &input.ident;
+	let fields = named_fields(&input, "FromLinkedData")?;
+
+	let type_check = match struct_type(&input)? {
+		Some(ty) => quote! { ::json_ld::binding::check_type(node, #ty)?; },
+		None => quote! {}
+	};
+
+	let mut field_values = Vec::new();
+	for field in fields {
+		let ident = field.ident.as_ref().unwrap();
+		let value = match (field_binding(field)?, Multiplicity::of(&field.ty)) {
+			(Binding::Id, Multiplicity::Required) => quote! { ::json_ld::binding::id(node, options)? },
+			(Binding::Id, Multiplicity::Optional) => quote! { ::json_ld::binding::optional_id(node, options)? },
+			(Binding::Id, Multiplicity::Multiple) => return Err(Error::new(field.span(), "a node has at most one identifier")),
+			(Binding::Property(iri, _), Multiplicity::Required) => quote! { ::json_ld::binding::required(node, #iri, options)? },
+			(Binding::Property(iri, _), Multiplicity::Optional) => quote! { ::json_ld::binding::optional(node, #iri, options)? },
+			(Binding::Property(iri, _), Multiplicity::Multiple) => quote! { ::json_ld::binding::all(node, #iri, options)? }
+		};
+
+		field_values.push(quote! { #ident: #value })
+	}
+
+	let mut generics = input.generics.clone();
+	generics.params.push(syn::parse_quote! { __T: ::json_ld::Id });
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, ty_generics, _) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::json_ld::binding::FromLinkedData<__T> for #name #ty_generics #where_clause {
+			fn from_node(node: &::json_ld::Node<__T>, options: ::json_ld::binding::Options) -> ::std::result::Result<Self, ::json_ld::binding::Error> {
+				#type_check
+				Ok(#name {
+					#(#field_values),*
+				})
+			}
+		}
+	})
+}
+
+fn derive_into_linked_data(input: DeriveInput) -> Result<TokenStream2, Error> {
+	let name = &input.ident;
+	let fields = named_fields(&input, "IntoLinkedData")?;
+
+	let add_type = match struct_type(&input)? {
+		Some(ty) => quote! { ::json_ld::binding::add_type(&mut node, #ty); },
+		None => quote! {}
+	};
+
+	let mut statements = Vec::new();
+	for field in fields {
+		let ident = field.ident.as_ref().unwrap();
+		let statement = match (field_binding(field)?, Multiplicity::of(&field.ty)) {
+			(Binding::Id, Multiplicity::Required) => quote! { ::json_ld::binding::set_id(&mut node, self.#ident); },
+			(Binding::Id, Multiplicity::Optional) => quote! { ::json_ld::binding::set_optional_id(&mut node, self.#ident); },
+			(Binding::Id, Multiplicity::Multiple) => return Err(Error::new(field.span(), "a node has at most one identifier")),
+			(Binding::Property(iri, _), Multiplicity::Required) => quote! { ::json_ld::binding::insert(&mut node, #iri, self.#ident); },
+			(Binding::Property(iri, _), Multiplicity::Optional) => quote! { ::json_ld::binding::insert_optional(&mut node, #iri, self.#ident); },
+			(Binding::Property(iri, false), Multiplicity::Multiple) => quote! { ::json_ld::binding::insert_all(&mut node, #iri, self.#ident); },
+			(Binding::Property(iri, true), Multiplicity::Multiple) => quote! { ::json_ld::binding::insert_list(&mut node, #iri, self.#ident); }
+		};
+
+		statements.push(statement)
+	}
+
+	let mut generics = input.generics.clone();
+	generics.params.push(syn::parse_quote! { __T: ::json_ld::Id });
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, ty_generics, _) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::json_ld::binding::IntoLinkedData<__T> for #name #ty_generics #where_clause {
+			fn into_object(self) -> ::json_ld::Indexed<::json_ld::Object<__T>> {
+				let mut node = ::json_ld::Node::new();
+				#add_type
+				#(#statements)*
+				::json_ld::Object::Node(Box::new(node)).into()
+			}
+		}
+	})
+}
+
+/// Derive the `FromLinkedData` trait.
+///
+/// See the `binding` module of the `json-ld` crate for more details.
+#[proc_macro_derive(FromLinkedData, attributes(ld))]
+pub fn from_linked_data(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match derive_from_linked_data(input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into()
+	}
+}
+
+/// Derive the `IntoLinkedData` trait.
+///
+/// See the `binding` module of the `json-ld` crate for more details.
+#[proc_macro_derive(IntoLinkedData, attributes(ld))]
+pub fn into_linked_data(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match derive_into_linked_data(input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into()
+	}
+}
@@ -0,0 +1,37 @@
+//! Expansion of arbitrary JSON documents.
+//!
+//! Run with `cargo fuzz run expand` from the repository root.
+//! Expansion must never panic, and must only fail with a valid error code.
+#![no_main]
+use std::convert::TryFrom;
+use libfuzzer_sys::fuzz_target;
+use iref::IriBuf;
+use json_ld::{
+	ErrorCode,
+	Document,
+	JsonContext,
+	NoLoader,
+	util::AsJson
+};
+
+fuzz_target!(|data: &[u8]| {
+	let doc = match std::str::from_utf8(data).ok().and_then(|data| json::parse(data).ok()) {
+		Some(doc) => doc,
+		None => return
+	};
+
+	let context: JsonContext<IriBuf> = JsonContext::new(None);
+	match futures::executor::block_on(doc.expand(&context, &mut NoLoader)) {
+		Ok(expanded) => {
+			// The expanded form of a document is itself a valid document.
+			let json = expanded.as_json();
+			if let Err(e) = futures::executor::block_on(json.expand(&context, &mut NoLoader)) {
+				panic!("expanded form {} of {} is invalid: {}", json.dump(), doc.dump(), e.code())
+			}
+		},
+		Err(e) => {
+			let code = e.code();
+			assert_eq!(ErrorCode::try_from(code.as_str()), Ok(code))
+		}
+	}
+});
@@ -0,0 +1,47 @@
+#ifndef JSONLD_H
+#define JSONLD_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes. A panic of the processor is reported as JSONLD_ERROR_PANIC, with a NULL output. */
+#define JSONLD_OK 0
+#define JSONLD_ERROR_NULL 1
+#define JSONLD_ERROR_INVALID_INPUT 2
+#define JSONLD_ERROR_INVALID_JSON 3
+#define JSONLD_ERROR_PROCESSING 4
+#define JSONLD_ERROR_PANIC 5
+
+/*
+ * Expand the UTF-8 encoded JSON-LD document of `input_len` bytes pointed by `input`.
+ *
+ * `base_url` is an optional NUL-terminated base URL (it can be NULL).
+ * On success (JSONLD_OK), the expanded document is written to `output`.
+ * On processing error (JSONLD_ERROR_PROCESSING), the JSON-LD error code is written to `output`.
+ * The output string must be released with `jsonld_free`.
+ */
+int jsonld_expand(const char *input, size_t input_len, const char *base_url, char **output);
+
+/*
+ * Compact the UTF-8 encoded JSON-LD document of `input_len` bytes pointed by `input`,
+ * against the UTF-8 encoded context of `context_len` bytes pointed by `context`
+ * (a context definition, or a document with an `@context` entry).
+ *
+ * `base_url` is an optional NUL-terminated base URL (it can be NULL).
+ * On success (JSONLD_OK), the compacted document is written to `output`.
+ * On processing error (JSONLD_ERROR_PROCESSING), the JSON-LD error code is written to `output`.
+ * The output string must be released with `jsonld_free`.
+ */
+int jsonld_compact(const char *input, size_t input_len, const char *context, size_t context_len, const char *base_url, char **output);
+
+/* Release a string returned by this API. */
+void jsonld_free(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
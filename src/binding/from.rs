@@ -0,0 +1,145 @@
+use std::convert::TryFrom;
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Id,
+	LangString,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Value,
+	object::value::Literal
+};
+use super::{
+	Error,
+	Options
+};
+
+/// Types that can be built from expanded JSON-LD objects.
+///
+/// Structures binding nodes should implement [`from_node`](FromLinkedData::from_node), while
+/// types binding literal values should implement [`from_value`](FromLinkedData::from_value).
+pub trait FromLinkedData<T: Id = IriBuf>: Sized {
+	/// Build a value from a node object.
+	fn from_node(_node: &Node<T>, _options: Options) -> Result<Self, Error> {
+		Err(Error::UnexpectedNode)
+	}
+
+	/// Build a value from a value object.
+	fn from_value(_value: &Value<T>, _options: Options) -> Result<Self, Error> {
+		Err(Error::UnexpectedValue)
+	}
+
+	/// Build a value from any object.
+	fn from_object(object: &Object<T>, options: Options) -> Result<Self, Error> {
+		match object {
+			Object::Node(node) => Self::from_node(node, options),
+			Object::Value(value) => Self::from_value(value, options),
+			Object::List(_) => Err(Error::UnexpectedList)
+		}
+	}
+}
+
+fn node_id<T: Id>(node: &Node<T>) -> Result<&Reference<T>, Error> {
+	match &node.id {
+		Some(Lenient::Ok(id)) => Ok(id),
+		_ => Err(Error::MissingId)
+	}
+}
+
+/// Binds the identifier of a node.
+impl<T: Id> FromLinkedData<T> for Reference<T> {
+	fn from_node(node: &Node<T>, _options: Options) -> Result<Self, Error> {
+		Ok(node_id(node)?.clone())
+	}
+}
+
+/// Binds the IRI of a node.
+impl<T: Id> FromLinkedData<T> for IriBuf {
+	fn from_node(node: &Node<T>, _options: Options) -> Result<Self, Error> {
+		match node_id(node)? {
+			Reference::Id(id) => Ok(id.as_iri().into()),
+			Reference::Blank(_) => Err(Error::MissingId)
+		}
+	}
+}
+
+/// Binds a string value, or the identifier of a node.
+impl<T: Id> FromLinkedData<T> for String {
+	fn from_node(node: &Node<T>, _options: Options) -> Result<Self, Error> {
+		Ok(node_id(node)?.as_str().to_string())
+	}
+
+	fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+		match value.as_str() {
+			Some(str) => Ok(str.to_string()),
+			None => Err(Error::InvalidLiteral)
+		}
+	}
+}
+
+/// Binds a string value, with its language and direction.
+impl<T: Id> FromLinkedData<T> for LangString {
+	fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+		match value {
+			Value::LangString(str) => Ok(str.clone()),
+			Value::Literal(Literal::String(str), _) => Ok(LangString::new(str.clone(), None, None)),
+			_ => Err(Error::InvalidLiteral)
+		}
+	}
+}
+
+/// Binds a boolean value, or a literal typed with `xsd:boolean`.
+impl<T: Id> FromLinkedData<T> for bool {
+	fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+		value.as_boolean().ok_or(Error::InvalidLiteral)
+	}
+}
+
+/// Binds a JSON literal.
+impl<T: Id> FromLinkedData<T> for JsonValue {
+	fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+		match value {
+			Value::Literal(Literal::Json(json), _) => Ok(json.value().clone()),
+			_ => Err(Error::InvalidLiteral)
+		}
+	}
+}
+
+macro_rules! from_integer {
+	($($ty:ty),*) => {
+		$(
+			/// Binds an integer number, or a literal typed with `xsd:integer` or one of its
+			/// derived datatypes.
+			impl<T: Id> FromLinkedData<T> for $ty {
+				fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+					match value.as_integer() {
+						Some(i) => <$ty>::try_from(i).map_err(|_| Error::InvalidLiteral),
+						None => match value.as_unsigned_integer() {
+							Some(u) => <$ty>::try_from(u).map_err(|_| Error::InvalidLiteral),
+							None => Err(Error::InvalidLiteral)
+						}
+					}
+				}
+			}
+		)*
+	};
+}
+
+from_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! from_float {
+	($($ty:ty),*) => {
+		$(
+			/// Binds a number, or a literal typed with a numeric `xsd` datatype.
+			impl<T: Id> FromLinkedData<T> for $ty {
+				fn from_value(value: &Value<T>, _options: Options) -> Result<Self, Error> {
+					value.as_double().map(|d| d as $ty).ok_or(Error::InvalidLiteral)
+				}
+			}
+		)*
+	};
+}
+
+from_float!(f32, f64);
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Id,
+	LangString,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	Value,
+	object::value::Literal,
+	vocab::Constant
+};
+
+/// Types that can be turned into expanded JSON-LD objects.
+///
+/// This is the inverse of [`FromLinkedData`](super::FromLinkedData).
+pub trait IntoLinkedData<T: Id = IriBuf> {
+	/// Turn the value into an expanded object.
+	fn into_object(self) -> Indexed<Object<T>>;
+}
+
+fn node_reference<T: Id>(id: Reference<T>) -> Indexed<Object<T>> {
+	let mut node = Node::new();
+	node.id = Some(Lenient::Ok(id));
+	Object::Node(Box::new(node)).into()
+}
+
+fn literal<T: Id>(lit: Literal) -> Indexed<Object<T>> {
+	Object::Value(Value::Literal(lit, HashSet::new())).into()
+}
+
+/// Produces a node reference.
+impl<T: Id> IntoLinkedData<T> for Reference<T> {
+	fn into_object(self) -> Indexed<Object<T>> {
+		node_reference(self)
+	}
+}
+
+/// Produces a node reference.
+impl<T: Id> IntoLinkedData<T> for IriBuf {
+	fn into_object(self) -> Indexed<Object<T>> {
+		node_reference(Reference::Id(T::from_iri(self.as_iri())))
+	}
+}
+
+/// Produces a node reference.
+impl<T: Id> IntoLinkedData<T> for Constant {
+	fn into_object(self) -> Indexed<Object<T>> {
+		node_reference(self.into())
+	}
+}
+
+/// Produces a string value.
+impl<T: Id> IntoLinkedData<T> for String {
+	fn into_object(self) -> Indexed<Object<T>> {
+		Object::Value(Value::LangString(LangString::new(self, None, None))).into()
+	}
+}
+
+/// Produces a string value.
+impl<T: Id> IntoLinkedData<T> for &str {
+	fn into_object(self) -> Indexed<Object<T>> {
+		self.to_string().into_object()
+	}
+}
+
+/// Produces a string value, with its language and direction.
+impl<T: Id> IntoLinkedData<T> for LangString {
+	fn into_object(self) -> Indexed<Object<T>> {
+		Object::Value(Value::LangString(self)).into()
+	}
+}
+
+/// Produces a boolean value.
+impl<T: Id> IntoLinkedData<T> for bool {
+	fn into_object(self) -> Indexed<Object<T>> {
+		literal(Literal::Boolean(self))
+	}
+}
+
+/// Produces a JSON literal.
+impl<T: Id> IntoLinkedData<T> for JsonValue {
+	fn into_object(self) -> Indexed<Object<T>> {
+		literal(Literal::Json(self.into()))
+	}
+}
+
+/// Objects are already linked data.
+impl<T: Id> IntoLinkedData<T> for Indexed<Object<T>> {
+	fn into_object(self) -> Indexed<Object<T>> {
+		self
+	}
+}
+
+/// Produces a node object.
+impl<T: Id> IntoLinkedData<T> for Node<T> {
+	fn into_object(self) -> Indexed<Object<T>> {
+		Object::Node(Box::new(self)).into()
+	}
+}
+
+macro_rules! into_number {
+	($($ty:ty),*) => {
+		$(
+			/// Produces a number value.
+			impl<T: Id> IntoLinkedData<T> for $ty {
+				fn into_object(self) -> Indexed<Object<T>> {
+					literal(Literal::Number(self.into()))
+				}
+			}
+		)*
+	};
+}
+
+into_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
@@ -0,0 +1,305 @@
+//! Data binding.
+//!
+//! This module maps expanded nodes into Rust types through the [`FromLinkedData`] trait, and
+//! Rust types into expanded nodes through the [`IntoLinkedData`] trait.
+//! With the `derive` feature enabled, both traits can be derived for structures whose fields are
+//! bound to properties by IRI:
+//! ```ignore
+//! #[derive(FromLinkedData, IntoLinkedData)]
+//! #[ld(type = "http://xmlns.com/foaf/0.1/Person")]
+//! struct Person {
+//!     #[ld(id)]
+//!     id: Option<IriBuf>,
+//!
+//!     #[ld("http://xmlns.com/foaf/0.1/name")]
+//!     name: String,
+//!
+//!     #[ld("http://xmlns.com/foaf/0.1/knows")]
+//!     knows: Vec<Person>,
+//!
+//!     #[ld("http://example.org/favoriteColors", list)]
+//!     favorite_colors: Vec<String>
+//! }
+//! ```
+//! Fields of type `Option<_>` are optional, fields of type `Vec<_>` collect every value of the
+//! property (including list items), and other fields are required.
+//! When producing linked data, `Vec<_>` fields marked with `list` are serialized as a list
+//! object (`@list`) instead of a set of values.
+
+mod from;
+mod into;
+
+use std::fmt;
+use std::convert::TryFrom;
+use iref::Iri;
+use crate::{
+	Id,
+	BlankId,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Value
+};
+
+pub use from::*;
+pub use into::*;
+
+#[cfg(feature = "derive")]
+pub use json_ld_derive::{
+	FromLinkedData,
+	IntoLinkedData
+};
+
+/// Data binding error.
+#[derive(Debug)]
+pub enum Error {
+	/// A required property is missing.
+	MissingProperty(String),
+
+	/// The node does not have the required type.
+	MissingType(String),
+
+	/// The node has no identifier.
+	MissingId,
+
+	/// An invalid IRI has been given as property.
+	InvalidIri(String),
+
+	/// A node object has been found where a value was expected.
+	UnexpectedNode,
+
+	/// A value object has been found where a node was expected.
+	UnexpectedValue,
+
+	/// A list object has been found where a single value was expected.
+	UnexpectedList,
+
+	/// A literal value cannot be converted into the expected type.
+	InvalidLiteral,
+
+	/// A value of the given property cannot be converted.
+	Property(String, Box<Error>)
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Property(_, e) => Some(e.as_ref()),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::MissingProperty(prop) => write!(f, "missing property `{}`", prop),
+			Error::MissingType(ty) => write!(f, "missing type `{}`", ty),
+			Error::MissingId => write!(f, "missing node identifier"),
+			Error::InvalidIri(iri) => write!(f, "invalid IRI `{}`", iri),
+			Error::UnexpectedNode => write!(f, "unexpected node object"),
+			Error::UnexpectedValue => write!(f, "unexpected value object"),
+			Error::UnexpectedList => write!(f, "unexpected list object"),
+			Error::InvalidLiteral => write!(f, "invalid literal value"),
+			Error::Property(prop, e) => write!(f, "property `{}`: {}", prop, e)
+		}
+	}
+}
+
+/// Data binding options.
+#[derive(Clone, Copy, Default)]
+pub struct Options<'a> {
+	/// Preferred language.
+	///
+	/// When a single value is expected but a property has multiple values, the language string
+	/// with this language is selected first.
+	pub language: Option<&'a str>
+}
+
+fn property<T: Id>(prop: &str) -> Result<Reference<T>, Error> {
+	match Iri::new(prop) {
+		Ok(iri) => Ok(Reference::Id(T::from_iri(iri))),
+		Err(_) => Err(Error::InvalidIri(prop.to_string()))
+	}
+}
+
+/// Collect the values of a property, including the items of lists.
+fn values<'a, T: Id>(node: &'a Node<T>, prop: &str) -> Result<Vec<&'a Object<T>>, Error> {
+	fn collect<'a, T: Id>(object: &'a Object<T>, result: &mut Vec<&'a Object<T>>) {
+		match object {
+			Object::List(items) => {
+				for item in items {
+					collect(item, result)
+				}
+			},
+			object => result.push(object)
+		}
+	}
+
+	let mut result = Vec::new();
+	for value in node.get(&property::<T>(prop)?) {
+		collect(value, &mut result)
+	}
+
+	Ok(result)
+}
+
+/// Select one value among many, according to the preferred language.
+///
+/// Without preferred language, or if no value has the preferred language, the first value
+/// without language is selected, or else the first value.
+fn select<'a, T: Id>(values: &[&'a Object<T>], options: Options) -> Option<&'a Object<T>> {
+	let language = |object: &Object<T>| match object {
+		Object::Value(Value::LangString(str)) => str.language().map(|lang| lang.to_string()),
+		_ => None
+	};
+
+	if let Some(preferred) = options.language {
+		if let Some(value) = values.iter().find(|v| language(v).map(|lang| lang.eq_ignore_ascii_case(preferred)).unwrap_or(false)) {
+			return Some(value)
+		}
+	}
+
+	values.iter().find(|v| language(v).is_none()).or_else(|| values.first()).copied()
+}
+
+fn in_property<V>(prop: &str, result: Result<V, Error>) -> Result<V, Error> {
+	result.map_err(|e| Error::Property(prop.to_string(), Box::new(e)))
+}
+
+/// Bind the value of a required property.
+///
+/// If the property has multiple values, one is selected according to the preferred language.
+pub fn required<T: Id, V: FromLinkedData<T>>(node: &Node<T>, prop: &str, options: Options) -> Result<V, Error> {
+	match optional(node, prop, options)? {
+		Some(value) => Ok(value),
+		None => Err(Error::MissingProperty(prop.to_string()))
+	}
+}
+
+/// Bind the value of an optional property.
+///
+/// If the property has multiple values, one is selected according to the preferred language.
+pub fn optional<T: Id, V: FromLinkedData<T>>(node: &Node<T>, prop: &str, options: Options) -> Result<Option<V>, Error> {
+	let values = values(node, prop)?;
+	match select(&values, options) {
+		Some(value) => in_property(prop, V::from_object(value, options)).map(Some),
+		None => Ok(None)
+	}
+}
+
+/// Bind every value of a property.
+pub fn all<T: Id, V: FromLinkedData<T>>(node: &Node<T>, prop: &str, options: Options) -> Result<Vec<V>, Error> {
+	values(node, prop)?.into_iter().map(|value| in_property(prop, V::from_object(value, options))).collect()
+}
+
+/// Bind the identifier of a node.
+pub fn id<T: Id, V: FromLinkedData<T>>(node: &Node<T>, options: Options) -> Result<V, Error> {
+	V::from_node(node, options)
+}
+
+/// Bind the identifier of a node, if any.
+pub fn optional_id<T: Id, V: FromLinkedData<T>>(node: &Node<T>, options: Options) -> Result<Option<V>, Error> {
+	match &node.id {
+		Some(Lenient::Ok(_)) => V::from_node(node, options).map(Some),
+		_ => Ok(None)
+	}
+}
+
+/// Checks that the node has the given type.
+pub fn check_type<T: Id>(node: &Node<T>, ty: &str) -> Result<(), Error> {
+	let ty_ref = Lenient::Ok(property::<T>(ty)?);
+	if node.types.contains(&ty_ref) {
+		Ok(())
+	} else {
+		Err(Error::MissingType(ty.to_string()))
+	}
+}
+
+/// Parse a property or type IRI, panicking if it is invalid.
+fn expect_property<T: Id>(prop: &str) -> Reference<T> {
+	match property(prop) {
+		Ok(prop) => prop,
+		Err(_) => panic!("invalid IRI `{}`", prop)
+	}
+}
+
+/// Set the identifier of a node.
+///
+/// The value must produce a node reference (such as [`IriBuf`](iref::IriBuf) or
+/// [`Reference`]), or a string that is then parsed as an IRI or blank node identifier.
+pub fn set_id<T: Id, V: IntoLinkedData<T>>(node: &mut Node<T>, id: V) {
+	let object = id.into_object();
+	node.id = match object.inner() {
+		Object::Node(reference) => reference.id.clone(),
+		Object::Value(value) => value.as_str().map(|id| match BlankId::try_from(id) {
+			Ok(blank) => Lenient::Ok(Reference::Blank(blank)),
+			Err(_) => match Iri::new(id) {
+				Ok(iri) => Lenient::Ok(Reference::Id(T::from_iri(iri))),
+				Err(_) => Lenient::Unknown(id.to_string())
+			}
+		}),
+		Object::List(_) => None
+	}
+}
+
+/// Set the identifier of a node, if any.
+pub fn set_optional_id<T: Id, V: IntoLinkedData<T>>(node: &mut Node<T>, id: Option<V>) {
+	if let Some(id) = id {
+		set_id(node, id)
+	}
+}
+
+/// Add a type to a node, unless it already has it.
+///
+/// ## Panics
+///
+/// Panics if `ty` is not a valid IRI.
+pub fn add_type<T: Id>(node: &mut Node<T>, ty: &str) {
+	node.insert_type(Lenient::Ok(expect_property(ty)));
+}
+
+/// Add a value to a property of a node.
+///
+/// ## Panics
+///
+/// Panics if `prop` is not a valid IRI.
+pub fn insert<T: Id, V: IntoLinkedData<T>>(node: &mut Node<T>, prop: &str, value: V) {
+	node.insert(expect_property(prop), value.into_object())
+}
+
+/// Add a value to a property of a node, if any.
+///
+/// ## Panics
+///
+/// Panics if `prop` is not a valid IRI.
+pub fn insert_optional<T: Id, V: IntoLinkedData<T>>(node: &mut Node<T>, prop: &str, value: Option<V>) {
+	if let Some(value) = value {
+		insert(node, prop, value)
+	}
+}
+
+/// Add every given value to a property of a node.
+///
+/// Nothing is added if there are no values.
+///
+/// ## Panics
+///
+/// Panics if `prop` is not a valid IRI.
+pub fn insert_all<T: Id, V: IntoLinkedData<T>, I: IntoIterator<Item = V>>(node: &mut Node<T>, prop: &str, values: I) {
+	let prop = expect_property(prop);
+	for value in values {
+		node.insert(prop.clone(), value.into_object())
+	}
+}
+
+/// Add a list of values to a property of a node.
+///
+/// ## Panics
+///
+/// Panics if `prop` is not a valid IRI.
+pub fn insert_list<T: Id, V: IntoLinkedData<T>, I: IntoIterator<Item = V>>(node: &mut Node<T>, prop: &str, values: I) {
+	let list = Object::List(values.into_iter().map(IntoLinkedData::into_object).collect());
+	node.insert(expect_property(prop), list.into())
+}
@@ -0,0 +1,124 @@
+//! Build-script helpers.
+//!
+//! Contexts known at compile time can be processed by a build script and embedded in the
+//! binary, so that embedded and command-line applications skip their parsing and processing
+//! at startup. In `build.rs`:
+//! ```ignore
+//! fn main() {
+//!     json_ld::build::precompile_context("contexts/schema.jsonld", &mut json_ld::NoLoader).unwrap();
+//! }
+//! ```
+//!
+//! The precompiled context is then embedded with the [`static_context`](crate::static_context)
+//! macro, given the same path:
+//! ```ignore
+//! let context: PrecompiledContext = static_context!("contexts/schema.jsonld");
+//! ```
+
+use std::{
+	env,
+	fmt,
+	fs,
+	io,
+	path::{
+		Path,
+		PathBuf
+	}
+};
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	JsonContext,
+	context::{
+		Local,
+		Loader,
+		PrecompiledContext
+	}
+};
+
+/// Embed a context precompiled with [`precompile_context`].
+///
+/// The path must be the one given to [`precompile_context`] in the build script.
+/// Evaluates to a [`PrecompiledContext`](crate::context::PrecompiledContext), decoded from
+/// the embedded bytes without any parsing or context processing.
+#[macro_export]
+macro_rules! static_context {
+	($path:literal) => {
+		$crate::context::PrecompiledContext::from_bytes(include_bytes!(concat!(env!("OUT_DIR"), "/", $path, ".jldp"))).expect("invalid precompiled context")
+	};
+}
+
+/// Context precompilation error.
+#[derive(Debug)]
+pub enum Error {
+	/// Unable to read the context or write the precompiled context.
+	Io(io::Error),
+
+	/// The context file is not valid JSON.
+	InvalidJson(json::Error),
+
+	/// Context processing error.
+	Processing(crate::Error)
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Io(e) => write!(f, "I/O error: {}", e),
+			Error::InvalidJson(e) => write!(f, "invalid JSON: {}", e),
+			Error::Processing(e) => write!(f, "context processing failed: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Error {
+		Error::Io(e)
+	}
+}
+
+impl From<crate::Error> for Error {
+	fn from(e: crate::Error) -> Error {
+		Error::Processing(e)
+	}
+}
+
+/// Precompile the given context file from a build script.
+///
+/// The path is relative to the package root. The file may be a context definition, or a
+/// context document with a `@context` entry. Remote contexts it refers to are loaded with
+/// the given loader.
+/// The precompiled context is written in `OUT_DIR`, and the build script is re-run when the
+/// file changes.
+pub fn precompile_context<P: AsRef<Path>, L: Send + Sync + Loader>(path: P, loader: &mut L) -> Result<PathBuf, Error> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+	let out_dir = env::var_os("OUT_DIR").ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "`OUT_DIR` is not set"))?;
+	println!("cargo:rerun-if-changed={}", path.as_ref().display());
+	precompile_context_into(path, loader, Path::new(&out_dir))
+}
+
+/// Precompile the given context file into the given directory.
+///
+/// The precompiled context is written at the path of the context file in this directory,
+/// with the `.jldp` extension appended. Returns the path of the precompiled context.
+pub fn precompile_context_into<P: AsRef<Path>, L: Send + Sync + Loader>(path: P, loader: &mut L, out_dir: &Path) -> Result<PathBuf, Error> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+	let path = path.as_ref();
+	let document = json::parse(&fs::read_to_string(path)?).map_err(Error::InvalidJson)?;
+	let local_context = match &document {
+		JsonValue::Object(object) => object.get("@context").unwrap_or(&document),
+		_ => &document
+	};
+
+	let context: JsonContext<IriBuf> = futures::executor::block_on(local_context.process(&JsonContext::new(None), loader, None))?;
+
+	let mut target = out_dir.join(path).into_os_string();
+	target.push(".jldp");
+	let target = PathBuf::from(target);
+	if let Some(parent) = target.parent() {
+		fs::create_dir_all(parent)?
+	}
+
+	fs::write(&target, PrecompiledContext::new(context).to_bytes())?;
+	Ok(target)
+}
@@ -0,0 +1,144 @@
+//! Cancellation and deadlines.
+//!
+//! A [`Token`] can be cancelled from any thread, or expire after a timeout.
+//! Wrapping a loader with the [`CancelLayer`] passes the token to the processor, which then
+//! checks it between processing steps (before expanding each element and processing each
+//! context), and before each document load, aborting with a `Cancelled` error:
+//! ```ignore
+//! let token = Token::with_timeout(Duration::from_secs(5));
+//! let mut loader = loader.with(CancelLayer::new(token.clone()));
+//! let result = doc.expand(&context, &mut loader).await;
+//! ```
+//!
+//! Cancellation is cooperative: a load already in progress is not interrupted.
+//! To bound the duration of the fetches themselves, use the timeout of the underlying
+//! HTTP client or runtime.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use crate::{
+	Error,
+	ErrorCode,
+	RemoteDocument,
+	Loader,
+	layer::LoaderLayer,
+	usage::TermUse,
+	util::Timer
+};
+
+/// Cancellation token.
+///
+/// Clones of a token share the same cancellation state.
+#[derive(Clone, Default)]
+pub struct Token {
+	cancelled: Arc<AtomicBool>,
+	deadline: Option<Arc<(Timer, Duration)>>
+}
+
+impl Token {
+	/// Create a new token, that is only cancelled by calling [`cancel`](Token::cancel).
+	pub fn new() -> Token {
+		Token::default()
+	}
+
+	/// Create a new token, automatically cancelled after the given duration.
+	///
+	/// Deadlines are not supported on `wasm32` targets, where time is not measured.
+	pub fn with_timeout(timeout: Duration) -> Token {
+		Token {
+			cancelled: Arc::new(AtomicBool::new(false)),
+			deadline: Some(Arc::new((Timer::start(), timeout)))
+		}
+	}
+
+	/// Cancel the token, and all its clones.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed)
+	}
+
+	/// Checks if the token has been cancelled, or its deadline has passed.
+	pub fn is_cancelled(&self) -> bool {
+		if self.cancelled.load(Ordering::Relaxed) {
+			return true
+		}
+
+		match &self.deadline {
+			Some(deadline) => match deadline.0.elapsed() {
+				Some(elapsed) => elapsed >= deadline.1,
+				None => false
+			},
+			None => false
+		}
+	}
+}
+
+/// Cancellation layer.
+#[derive(Clone, Default)]
+pub struct CancelLayer {
+	token: Token
+}
+
+impl CancelLayer {
+	/// Create a layer cancelling processing with the given token.
+	pub fn new(token: Token) -> CancelLayer {
+		CancelLayer {
+			token
+		}
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for CancelLayer {
+	type Loader = Cancel<L>;
+
+	fn layer(self, inner: L) -> Cancel<L> {
+		Cancel {
+			inner,
+			token: self.token
+		}
+	}
+}
+
+/// Loader produced by the [`CancelLayer`].
+pub struct Cancel<L> {
+	inner: L,
+	token: Token
+}
+
+impl<L> Cancel<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Cancellation token.
+	pub fn token(&self) -> &Token {
+		&self.token
+	}
+}
+
+impl<L: Send + Loader> Loader for Cancel<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			if self.is_cancelled() {
+				debug!("cancelled loading of `{}'", url);
+				return Err(ErrorCode::Cancelled.into())
+			}
+
+			self.inner.load(url.as_iri()).await
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.token.is_cancelled() || self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
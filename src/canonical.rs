@@ -0,0 +1,86 @@
+use json::JsonValue;
+use crate::{
+	Id,
+	ExpandedDocument,
+	util::AsJson
+};
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Canonical serialization of the document.
+	///
+	/// Object keys are sorted, and the items of unordered sets (including the top-level objects)
+	/// are sorted by their own canonical serialization, while lists (`@list`) and JSON literals
+	/// keep their order.
+	/// This is stable across the different ways a same document can be written in compact form,
+	/// but blank node identifiers are kept as is: documents that only differ by their blank node
+	/// labels have different canonical forms.
+	pub fn canonical_form(&self) -> String {
+		let mut items: Vec<String> = self.iter().map(|object| {
+			let mut item = String::new();
+			write_canonical(&object.as_json(), Mode::Set, &mut item);
+			item
+		}).collect();
+		items.sort();
+
+		format!("[{}]", items.join(","))
+	}
+}
+
+/// How the arrays of a JSON value are serialized.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+	/// Arrays are unordered sets, their items are sorted.
+	Set,
+
+	/// The value is a list, its order is preserved.
+	List,
+
+	/// The value is a JSON literal, every order is preserved.
+	Literal
+}
+
+/// Write the canonical serialization of a JSON value.
+fn write_canonical(value: &JsonValue, mode: Mode, out: &mut String) {
+	match value {
+		JsonValue::Array(items) => {
+			let item_mode = if mode == Mode::Literal { Mode::Literal } else { Mode::Set };
+			let mut items: Vec<String> = items.iter().map(|item| {
+				let mut s = String::new();
+				write_canonical(item, item_mode, &mut s);
+				s
+			}).collect();
+
+			if mode == Mode::Set {
+				items.sort()
+			}
+
+			out.push('[');
+			out.push_str(&items.join(","));
+			out.push(']')
+		},
+		JsonValue::Object(obj) => {
+			let mut entries: Vec<(&str, &JsonValue)> = obj.iter().collect();
+			entries.sort_by_key(|(key, _)| *key);
+
+			out.push('{');
+			for (i, (key, value)) in entries.into_iter().enumerate() {
+				if i > 0 {
+					out.push(',')
+				}
+
+				let value_mode = match key {
+					_ if mode == Mode::Literal => Mode::Literal,
+					"@value" => Mode::Literal,
+					"@list" => Mode::List,
+					_ => Mode::Set
+				};
+
+				out.push_str(&JsonValue::from(key).dump());
+				out.push(':');
+				write_canonical(value, value_mode, out)
+			}
+			out.push('}')
+		},
+		value => out.push_str(&value.dump())
+	}
+}
@@ -0,0 +1,203 @@
+//! C API.
+//!
+//! This module exposes a small C-compatible API, enabled by the `capi` feature, so that
+//! non-Rust applications can use this processor.
+//! A shared library can be built with:
+//! ```text
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//! The associated header is `include/jsonld.h`.
+//!
+//! Every function takes UTF-8 encoded buffers, and returns a status code.
+//! Panics are caught at the boundary and reported as [`JSONLD_ERROR_PANIC`], since unwinding
+//! into the calling C code is undefined behavior.
+//! On success or on processing error, a newly allocated NUL-terminated string is written to the
+//! `output` parameter:
+//! it contains the result (on success) or the JSON-LD error code (such as
+//! `invalid @id value`, on processing error).
+//! It must be released with [`jsonld_free`].
+//!
+//! Remote documents are not loaded: processing fails if a remote context is referenced.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, UnwindSafe};
+use std::ptr;
+use std::slice;
+use futures::executor::block_on;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Document,
+	Error,
+	JsonContext,
+	NoLoader,
+	RemoteDocument,
+	ExpandedDocument,
+	compaction,
+	util::{
+		AsJson,
+		parse_json
+	}
+};
+
+/// Success.
+pub const JSONLD_OK: c_int = 0;
+
+/// A required pointer argument is null.
+pub const JSONLD_ERROR_NULL: c_int = 1;
+
+/// An input buffer is not valid UTF-8, or the base URL is not a valid IRI.
+pub const JSONLD_ERROR_INVALID_INPUT: c_int = 2;
+
+/// The input document is not valid JSON.
+pub const JSONLD_ERROR_INVALID_JSON: c_int = 3;
+
+/// A JSON-LD processing error occurred.
+///
+/// The JSON-LD error code is written to the output string.
+pub const JSONLD_ERROR_PROCESSING: c_int = 4;
+
+/// The processor panicked.
+///
+/// Nothing is written to the output string, which is null.
+pub const JSONLD_ERROR_PANIC: c_int = 5;
+
+/// Write a string to the output parameter.
+unsafe fn write_output(output: *mut *mut c_char, value: String) {
+	// JSON and JSON-LD error codes never contain NUL characters.
+	let value = CString::new(value).unwrap_or_default();
+	*output = value.into_raw()
+}
+
+/// Run the body of an API function, catching panics.
+fn guard<F: FnOnce() -> c_int + UnwindSafe>(f: F) -> c_int {
+	panic::catch_unwind(f).unwrap_or(JSONLD_ERROR_PANIC)
+}
+
+/// Read an UTF-8 encoded input buffer.
+unsafe fn read_input<'a>(input: *const c_char, input_len: usize) -> Result<&'a str, c_int> {
+	std::str::from_utf8(slice::from_raw_parts(input as *const u8, input_len)).map_err(|_| JSONLD_ERROR_INVALID_INPUT)
+}
+
+/// Read an UTF-8 encoded JSON input buffer.
+unsafe fn read_json(input: *const c_char, input_len: usize) -> Result<JsonValue, c_int> {
+	parse_json(read_input(input, input_len)?).map_err(|_| JSONLD_ERROR_INVALID_JSON)
+}
+
+/// Read the optional base URL.
+unsafe fn read_base_url(base_url: *const c_char) -> Result<Option<IriBuf>, c_int> {
+	if base_url.is_null() {
+		Ok(None)
+	} else {
+		match CStr::from_ptr(base_url).to_str().ok().and_then(|url| Iri::new(url).ok()) {
+			Some(url) => Ok(Some(IriBuf::from(url))),
+			None => Err(JSONLD_ERROR_INVALID_INPUT)
+		}
+	}
+}
+
+/// Expand the given document, retrieved from `base_url` if any.
+fn expand(doc: JsonValue, base_url: Option<Iri>) -> Result<ExpandedDocument<IriBuf>, Error> {
+	let context: JsonContext = JsonContext::new(base_url);
+	match base_url {
+		Some(url) => block_on(RemoteDocument::new(doc, url).expand(&context, &mut NoLoader)),
+		None => block_on(doc.expand(&context, &mut NoLoader))
+	}
+}
+
+/// Write the processing result to the output parameter, and return the status code.
+unsafe fn write_result(output: *mut *mut c_char, result: Result<JsonValue, Error>) -> c_int {
+	match result {
+		Ok(value) => {
+			write_output(output, value.dump());
+			JSONLD_OK
+		},
+		Err(e) => {
+			write_output(output, e.code().as_str().to_string());
+			JSONLD_ERROR_PROCESSING
+		}
+	}
+}
+
+/// Expand the given JSON-LD document.
+///
+/// `input` must point to a buffer of `input_len` bytes containing the UTF-8 encoded document.
+/// `base_url` is an optional NUL-terminated base URL (it can be null).
+/// On success, the expanded document is written to `output` as a JSON string.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `input_len` bytes, `base_url` must be null or point to
+/// a NUL-terminated string, and `output` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jsonld_expand(input: *const c_char, input_len: usize, base_url: *const c_char, output: *mut *mut c_char) -> c_int {
+	guard(|| {
+		if input.is_null() || output.is_null() {
+			return JSONLD_ERROR_NULL
+		}
+
+		*output = ptr::null_mut();
+
+		let (doc, base_url) = match (read_json(input, input_len), read_base_url(base_url)) {
+			(Err(status), _) | (_, Err(status)) => return status,
+			(Ok(doc), Ok(base_url)) => (doc, base_url)
+		};
+
+		let result = expand(doc, base_url.as_ref().map(|url| url.as_iri()));
+		write_result(output, result.map(|expanded| expanded.as_json()))
+	})
+}
+
+/// Compact the given JSON-LD document.
+///
+/// `input` must point to a buffer of `input_len` bytes containing the UTF-8 encoded document,
+/// and `context` to a buffer of `context_len` bytes containing the UTF-8 encoded context
+/// (a context definition, or a document with an `@context` entry).
+/// `base_url` is an optional NUL-terminated base URL (it can be null).
+/// The document is expanded, then compacted against the context.
+/// On success, the compacted document is written to `output` as a JSON string.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `input_len` bytes, `context` must be valid for reads of
+/// `context_len` bytes, `base_url` must be null or point to a NUL-terminated string, and
+/// `output` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jsonld_compact(input: *const c_char, input_len: usize, context: *const c_char, context_len: usize, base_url: *const c_char, output: *mut *mut c_char) -> c_int {
+	guard(|| {
+		if input.is_null() || context.is_null() || output.is_null() {
+			return JSONLD_ERROR_NULL
+		}
+
+		*output = ptr::null_mut();
+
+		let (doc, context, base_url) = match (read_json(input, input_len), read_json(context, context_len), read_base_url(base_url)) {
+			(Err(status), _, _) | (_, Err(status), _) | (_, _, Err(status)) => return status,
+			(Ok(doc), Ok(context), Ok(base_url)) => (doc, context, base_url)
+		};
+
+		let base_url = base_url.as_ref().map(|url| url.as_iri());
+		let result = expand(doc, base_url).and_then(|expanded| {
+			block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, &context, base_url, &mut NoLoader, compaction::Options::default()))
+		});
+
+		write_result(output, result)
+	})
+}
+
+/// Release a string returned by this API.
+///
+/// # Safety
+///
+/// `value` must be null, or a string returned by this API that has not already been released.
+#[no_mangle]
+pub unsafe extern "C" fn jsonld_free(value: *mut c_char) {
+	guard(|| {
+		if !value.is_null() {
+			drop(CString::from_raw(value))
+		}
+
+		JSONLD_OK
+	});
+}
@@ -0,0 +1,748 @@
+//! CBOR-LD compression.
+//!
+//! [CBOR-LD](https://json-ld.github.io/cbor-ld-spec/) encodes compacted JSON-LD documents into
+//! [CBOR](https://www.rfc-editor.org/rfc/rfc8949), where the terms defined by the context of the
+//! document, and some of their values, are replaced by small integers.
+//! This makes documents such as credentials small enough for constrained transports,
+//! like QR codes.
+//!
+//! The term dictionary is derived from the top-level context of the document, which the decoder
+//! loads again: keywords have the fixed codes of [`KEYWORDS`], and the terms defined by the
+//! context (including its imported and scoped contexts) are given even codes from `100`, in
+//! lexicographic order. A code is incremented when the value of the entry is an array.
+//! Values are compressed according to the definition of their term:
+//!
+//! - context URLs listed in [`CONTEXTS`] are replaced by their code;
+//! - `@type` values, and values of terms typed `@vocab`, that are terms are replaced by their code;
+//! - `http://` and `https://` IRIs, in `@id` values and values of terms typed `@id`, are replaced
+//!   by `[1, rest]` and `[2, rest]`;
+//! - dates of the form `YYYY-MM-DDThh:mm:ssZ`, in values of terms typed `xsd:dateTime`, are
+//!   replaced by their number of seconds since the Unix epoch.
+//!
+//! Terms defined differently by different contexts, terms only defined by embedded contexts,
+//! and JSON literals are not compressed. The encoded document is tagged `0x0501`; uncompressed
+//! documents tagged `0x0500` are also decoded.
+//!
+//! This follows the layout of the CBOR-LD draft, but interoperability with other implementations
+//! is not tested.
+//!
+//! ```ignore
+//! let bytes = cbor_ld::encode(&compacted, &mut loader).await?;
+//! let decoded = cbor_ld::decode(&bytes, &mut loader).await?;
+//! assert_eq!(decoded, compacted);
+//! ```
+
+use std::fmt;
+use std::convert::TryFrom;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use futures::future::{BoxFuture, FutureExt};
+use ciborium::value::{Integer, Value};
+use iref::{Iri, IriBuf, IriRef};
+use json::{JsonValue, number::Number, object::Object as JsonObject};
+use crate::{
+	context::{
+		Loader,
+		resolve_iri
+	},
+	object::xsd,
+	util::json_float
+};
+
+/// CBOR tag of compressed CBOR-LD documents.
+pub const COMPRESSED: u64 = 0x0501;
+
+/// CBOR tag of uncompressed CBOR-LD documents.
+pub const UNCOMPRESSED: u64 = 0x0500;
+
+/// Code of the first term defined by a context.
+pub const FIRST_TERM: u64 = 100;
+
+/// Keywords and their codes.
+pub const KEYWORDS: &[(&str, u64)] = &[
+	("@context", 0),
+	("@type", 2),
+	("@id", 4),
+	("@value", 6),
+	("@direction", 8),
+	("@graph", 10),
+	("@included", 12),
+	("@index", 14),
+	("@json", 16),
+	("@language", 18),
+	("@list", 20),
+	("@nest", 22),
+	("@reverse", 24),
+	("@base", 26),
+	("@container", 28),
+	("@default", 30),
+	("@embed", 32),
+	("@explicit", 34),
+	("@none", 36),
+	("@omitDefault", 38),
+	("@prefix", 40),
+	("@preserve", 42),
+	("@protected", 44),
+	("@requireAll", 46),
+	("@set", 48),
+	("@version", 50),
+	("@vocab", 52)
+];
+
+/// Well-known context URLs and their codes.
+pub const CONTEXTS: &[(&str, u64)] = &[
+	("https://www.w3.org/ns/activitystreams", 0x10),
+	("https://www.w3.org/2018/credentials/v1", 0x11),
+	("https://www.w3.org/ns/did/v1", 0x12),
+	("https://w3id.org/security/suites/ed25519-2018/v1", 0x13),
+	("https://w3id.org/security/suites/ed25519-2020/v1", 0x14),
+	("https://w3id.org/cit/v1", 0x15),
+	("https://w3id.org/age/v1", 0x16),
+	("https://w3id.org/security/suites/x25519-2020/v1", 0x17),
+	("https://w3id.org/veres-one/v1", 0x18),
+	("https://w3id.org/webkms/v1", 0x19),
+	("https://w3id.org/zcap/v1", 0x1a),
+	("https://w3id.org/security/suites/hmac-2019/v1", 0x1b),
+	("https://w3id.org/security/suites/aes-2019/v1", 0x1c),
+	("https://w3id.org/vaccination/v1", 0x1d),
+	("https://w3id.org/vc-revocation-list-2020/v1", 0x1e),
+	("https://w3id.org/dcc/v1", 0x1f),
+	("https://w3id.org/vc/status-list/v1", 0x20)
+];
+
+/// CBOR-LD error.
+#[derive(Debug)]
+pub enum Error {
+	/// Unable to load a context of the document.
+	Loading(crate::Error),
+
+	/// A context of the document is not a valid context.
+	InvalidContext,
+
+	/// The input is not valid CBOR.
+	InvalidCbor,
+
+	/// The input is not tagged as a CBOR-LD document.
+	NotCborLd,
+
+	/// The input is not a valid CBOR-LD document: it uses non-JSON values (such as byte strings
+	/// or non-finite floats), non-string keys outside of compressed maps, or malformed entries.
+	Invalid,
+
+	/// The input uses an unknown term or context code.
+	UnknownCode(u64),
+
+	/// The value of the given entry cannot be encoded unambiguously, such as an integer where a
+	/// compressed date is expected.
+	Ambiguous(String)
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Loading(e) => write!(f, "unable to load context: {}", e.code().as_str()),
+			Error::InvalidContext => write!(f, "invalid context"),
+			Error::InvalidCbor => write!(f, "invalid CBOR"),
+			Error::NotCborLd => write!(f, "not a CBOR-LD document"),
+			Error::Invalid => write!(f, "invalid CBOR-LD document"),
+			Error::UnknownCode(code) => write!(f, "unknown code {}", code),
+			Error::Ambiguous(key) => write!(f, "value of `{}` cannot be encoded unambiguously", key)
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Compression applied to the values of an entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Codec {
+	/// Nodes and plain values.
+	Plain,
+
+	/// Contexts: well-known URLs are compressed, context definitions are kept as they are.
+	Context,
+
+	/// Node identifiers: `http` and `https` IRIs are compressed.
+	Id,
+
+	/// Vocabulary: terms are compressed.
+	Vocab,
+
+	/// Dates: `xsd:dateTime` values are compressed.
+	DateTime,
+
+	/// JSON values, kept as they are.
+	Json
+}
+
+impl Codec {
+	fn of_keyword(keyword: &str) -> Codec {
+		match keyword {
+			"@context" => Codec::Context,
+			"@id" => Codec::Id,
+			"@type" => Codec::Vocab,
+			"@value" | "@language" | "@direction" | "@index" => Codec::Json,
+			_ => Codec::Plain
+		}
+	}
+}
+
+/// Term definition, as written in a context.
+#[derive(Clone, PartialEq, Eq)]
+struct Definition {
+	/// IRI mapping, or aliased keyword.
+	iri: Option<String>,
+
+	/// Type mapping.
+	ty: Option<String>
+}
+
+impl Definition {
+	fn new(value: &JsonValue) -> Definition {
+		match value {
+			JsonValue::Object(obj) => Definition {
+				iri: obj.get("@id").and_then(JsonValue::as_str).map(String::from),
+				ty: obj.get("@type").and_then(JsonValue::as_str).map(String::from)
+			},
+			value => Definition {
+				iri: value.as_str().map(String::from),
+				ty: None
+			}
+		}
+	}
+}
+
+/// Term definitions found in the contexts.
+///
+/// A term defined differently by different contexts has no definition.
+#[derive(Default)]
+struct Definitions(HashMap<String, Option<Definition>>);
+
+impl Definitions {
+	fn define(&mut self, term: &str, value: &JsonValue) {
+		let definition = Definition::new(value);
+		match self.0.get_mut(term) {
+			Some(current) => {
+				if current.as_ref() != Some(&definition) {
+					*current = None
+				}
+			},
+			None => {
+				self.0.insert(term.to_string(), Some(definition));
+			}
+		}
+	}
+
+	fn get(&self, term: &str) -> Option<&Definition> {
+		self.0.get(term).and_then(Option::as_ref)
+	}
+
+	/// Expand the given type mapping, using the prefixes and terms of the contexts.
+	fn expand_type(&self, ty: &str) -> String {
+		let (term, suffix) = match ty.find(':') {
+			Some(i) => (&ty[..i], &ty[(i + 1)..]),
+			None => (ty, "")
+		};
+
+		match self.get(term).and_then(|definition| definition.iri.as_ref()) {
+			Some(iri) if !suffix.starts_with("//") && !iri.starts_with('@') => format!("{}{}", iri, suffix),
+			_ => ty.to_string()
+		}
+	}
+
+	fn codec(&self, term: &str) -> Codec {
+		match self.get(term) {
+			Some(definition) => match &definition.iri {
+				Some(iri) if iri.starts_with('@') => Codec::of_keyword(iri),
+				_ => match definition.ty.as_deref() {
+					Some("@id") => Codec::Id,
+					Some("@vocab") => Codec::Vocab,
+					Some("@json") => Codec::Json,
+					Some(ty) => {
+						if self.expand_type(ty).strip_prefix(xsd::XSD) == Some("dateTime") {
+							Codec::DateTime
+						} else {
+							Codec::Plain
+						}
+					},
+					None => Codec::Plain
+				}
+			},
+			None => Codec::Plain
+		}
+	}
+}
+
+/// Collect the term definitions of the given context.
+fn collect<'a, L: Send + Sync + Loader>(context: &'a JsonValue, base_url: Option<Iri<'a>>, loader: &'a mut L, loaded: &'a mut HashSet<IriBuf>, definitions: &'a mut Definitions) -> BoxFuture<'a, Result<(), Error>> where L::Output: Into<JsonValue> {
+	async move {
+		match context {
+			JsonValue::Null => Ok(()),
+			JsonValue::Short(_) | JsonValue::String(_) => {
+				let url = match IriRef::new(context.as_str().unwrap()) {
+					Ok(iri_ref) => resolve_iri(iri_ref, base_url).ok_or(Error::InvalidContext)?,
+					Err(_) => return Err(Error::InvalidContext)
+				};
+
+				if loaded.insert(url.clone()) {
+					let remote_context = loader.load_context(url.as_iri()).await.map_err(Error::Loading)?.cast::<JsonValue>();
+					collect(remote_context.context(), Some(remote_context.url()), loader, loaded, definitions).await?
+				}
+
+				Ok(())
+			},
+			JsonValue::Array(contexts) => {
+				for context in contexts {
+					collect(context, base_url, loader, loaded, definitions).await?
+				}
+
+				Ok(())
+			},
+			JsonValue::Object(context) => {
+				for (key, value) in context.iter() {
+					if key == "@import" {
+						collect(value, base_url, loader, loaded, definitions).await?
+					} else if !key.starts_with('@') {
+						definitions.define(key, value);
+						if let JsonValue::Object(definition) = value {
+							if let Some(scoped) = definition.get("@context") {
+								collect(scoped, base_url, loader, loaded, definitions).await?
+							}
+						}
+					}
+				}
+
+				Ok(())
+			},
+			_ => Err(Error::InvalidContext)
+		}
+	}.boxed()
+}
+
+/// Term dictionary.
+pub struct Dictionary {
+	codes: HashMap<String, u64>,
+	terms: HashMap<u64, String>,
+	definitions: Definitions
+}
+
+impl Dictionary {
+	/// Derive the term dictionary of the given context.
+	///
+	/// Remote contexts are loaded with the given loader.
+	pub async fn new<L: Send + Sync + Loader>(context: &JsonValue, loader: &mut L) -> Result<Dictionary, Error> where L::Output: Into<JsonValue> {
+		let mut definitions = Definitions::default();
+		collect(context, None, loader, &mut HashSet::new(), &mut definitions).await?;
+
+		let sorted: BTreeSet<&String> = definitions.0.keys().collect();
+		let mut codes = HashMap::new();
+		let mut terms = HashMap::new();
+		for (i, term) in sorted.into_iter().enumerate() {
+			let code = FIRST_TERM + 2 * i as u64;
+			codes.insert(term.clone(), code);
+			terms.insert(code, term.clone());
+		}
+
+		Ok(Dictionary {
+			codes,
+			terms,
+			definitions
+		})
+	}
+
+	/// Code of the given term or keyword.
+	pub fn code(&self, term: &str) -> Option<u64> {
+		match KEYWORDS.iter().find(|(keyword, _)| *keyword == term) {
+			Some((_, code)) => Some(*code),
+			None => self.codes.get(term).cloned()
+		}
+	}
+
+	/// Term or keyword of the given code.
+	pub fn term(&self, code: u64) -> Option<&str> {
+		match KEYWORDS.iter().find(|(_, c)| *c == code) {
+			Some((keyword, _)) => Some(keyword),
+			None => self.terms.get(&code).map(String::as_str)
+		}
+	}
+
+	fn codec(&self, key: &str) -> Codec {
+		if key.starts_with('@') {
+			Codec::of_keyword(key)
+		} else {
+			self.definitions.codec(key)
+		}
+	}
+
+	/// Compress the given JSON value.
+	fn encode(&self, key: &str, value: &JsonValue, codec: Codec) -> Result<Value, Error> {
+		match (codec, value) {
+			(Codec::Json, value) => encode_json(value),
+			(_, JsonValue::Object(obj)) if codec != Codec::Context => {
+				let mut entries = Vec::with_capacity(obj.len());
+				for (key, value) in obj.iter() {
+					let codec = self.codec(key);
+					let (code, value) = match value {
+						JsonValue::Array(items) => (self.code(key).map(|code| code + 1), self.encode_items(key, items, codec)?),
+						value => (self.code(key), self.encode(key, value, codec)?)
+					};
+
+					let key = match code {
+						Some(code) => Value::Integer(code.into()),
+						None => Value::Text(key.to_string())
+					};
+
+					entries.push((key, value))
+				}
+
+				Ok(Value::Map(entries))
+			},
+			(_, JsonValue::Array(items)) => {
+				if codec == Codec::Id && is_compressed_iri(items) {
+					return Err(Error::Ambiguous(key.to_string()))
+				}
+
+				self.encode_items(key, items, codec)
+			},
+			(Codec::Context, value) => match value.as_str().and_then(|url| CONTEXTS.iter().find(|(u, _)| *u == url)) {
+				Some((_, code)) => Ok(Value::Integer((*code).into())),
+				None => encode_json(value)
+			},
+			(Codec::Id, value) => match value.as_str() {
+				Some(iri) => match iri.strip_prefix("https://") {
+					Some(rest) => Ok(Value::Array(vec![Value::Integer(2.into()), Value::Text(rest.to_string())])),
+					None => match iri.strip_prefix("http://") {
+						Some(rest) => Ok(Value::Array(vec![Value::Integer(1.into()), Value::Text(rest.to_string())])),
+						None => encode_json(value)
+					}
+				},
+				None => encode_json(value)
+			},
+			(Codec::Vocab, value) => match value.as_str().and_then(|term| self.codes.get(term)) {
+				Some(code) => Ok(Value::Integer((*code).into())),
+				None => self.encode_literal(key, value)
+			},
+			(Codec::DateTime, value) => match value.as_str().and_then(parse_date_time) {
+				Some(seconds) => Ok(Value::Integer(seconds.into())),
+				None => self.encode_literal(key, value)
+			},
+			(_, value) => encode_json(value)
+		}
+	}
+
+	fn encode_items(&self, key: &str, items: &[JsonValue], codec: Codec) -> Result<Value, Error> {
+		let mut encoded = Vec::with_capacity(items.len());
+		for item in items {
+			encoded.push(self.encode(key, item, codec)?)
+		}
+
+		Ok(Value::Array(encoded))
+	}
+
+	/// Encode a value that has not been compressed, while integers denote compressed values.
+	fn encode_literal(&self, key: &str, value: &JsonValue) -> Result<Value, Error> {
+		match encode_json(value)? {
+			Value::Integer(_) => Err(Error::Ambiguous(key.to_string())),
+			value => Ok(value)
+		}
+	}
+
+	/// Decompress the given CBOR value.
+	fn decode(&self, value: Value, codec: Codec) -> Result<JsonValue, Error> {
+		match (codec, value) {
+			(Codec::Json, value) => decode_json(value),
+			(Codec::Context, value) => decode_context(value),
+			(_, Value::Map(entries)) => {
+				let mut obj = JsonObject::with_capacity(entries.len());
+				for (key, value) in entries {
+					let (key, plural) = match key {
+						Value::Text(key) => (key, false),
+						Value::Integer(code) => {
+							let code = u64::try_from(code).map_err(|_| Error::Invalid)?;
+							match self.term(code & !1) {
+								Some(term) => (term.to_string(), code & 1 == 1),
+								None => return Err(Error::UnknownCode(code))
+							}
+						},
+						_ => return Err(Error::Invalid)
+					};
+
+					let codec = self.codec(&key);
+					let value = match value {
+						Value::Array(items) if plural => self.decode_items(items, codec)?,
+						_ if plural => return Err(Error::Invalid),
+						value => self.decode(value, codec)?
+					};
+
+					obj.insert(&key, value)
+				}
+
+				Ok(JsonValue::Object(obj))
+			},
+			(Codec::Id, Value::Array(items)) if is_compressed_iri_value(&items) => {
+				let mut items = items.into_iter();
+				let scheme = match items.next() {
+					Some(Value::Integer(code)) if i128::from(code) == 1 => "http://",
+					_ => "https://"
+				};
+
+				match items.next() {
+					Some(Value::Text(rest)) => Ok(format!("{}{}", scheme, rest).into()),
+					_ => unreachable!()
+				}
+			},
+			(_, Value::Array(items)) => self.decode_items(items, codec),
+			(Codec::Vocab, Value::Integer(code)) => {
+				let code = u64::try_from(code).map_err(|_| Error::Invalid)?;
+				match self.terms.get(&code) {
+					Some(term) => Ok(term.as_str().into()),
+					None => Err(Error::UnknownCode(code))
+				}
+			},
+			(Codec::DateTime, Value::Integer(seconds)) => {
+				let seconds = i64::try_from(seconds).map_err(|_| Error::Invalid)?;
+				format_date_time(seconds).map(JsonValue::from).ok_or(Error::Invalid)
+			},
+			(_, value) => decode_json(value)
+		}
+	}
+
+	fn decode_items(&self, items: Vec<Value>, codec: Codec) -> Result<JsonValue, Error> {
+		let mut decoded = Vec::with_capacity(items.len());
+		for item in items {
+			decoded.push(self.decode(item, codec)?)
+		}
+
+		Ok(JsonValue::Array(decoded))
+	}
+}
+
+/// Checks if the given JSON array would be read back as a compressed IRI.
+fn is_compressed_iri(items: &[JsonValue]) -> bool {
+	items.len() == 2 && (items[0] == 1 || items[0] == 2) && items[1].is_string()
+}
+
+/// Checks if the given CBOR array is a compressed IRI.
+fn is_compressed_iri_value(items: &[Value]) -> bool {
+	match items {
+		[Value::Integer(code), Value::Text(_)] => {
+			let code = i128::from(*code);
+			code == 1 || code == 2
+		},
+		_ => false
+	}
+}
+
+/// Decode a context, where well-known URLs are compressed.
+fn decode_context(value: Value) -> Result<JsonValue, Error> {
+	match value {
+		Value::Integer(code) => {
+			let code = u64::try_from(code).map_err(|_| Error::Invalid)?;
+			match CONTEXTS.iter().find(|(_, c)| *c == code) {
+				Some((url, _)) => Ok((*url).into()),
+				None => Err(Error::UnknownCode(code))
+			}
+		},
+		Value::Array(items) => {
+			let mut decoded = Vec::with_capacity(items.len());
+			for item in items {
+				decoded.push(decode_context(item)?)
+			}
+
+			Ok(JsonValue::Array(decoded))
+		},
+		value => decode_json(value)
+	}
+}
+
+/// Encode a JSON value as it is.
+fn encode_json(value: &JsonValue) -> Result<Value, Error> {
+	match value {
+		JsonValue::Null => Ok(Value::Null),
+		JsonValue::Boolean(b) => Ok(Value::Bool(*b)),
+		JsonValue::Number(n) => Ok(encode_number(*n)),
+		JsonValue::Short(_) | JsonValue::String(_) => Ok(Value::Text(value.as_str().unwrap().to_string())),
+		JsonValue::Array(items) => {
+			let mut encoded = Vec::with_capacity(items.len());
+			for item in items {
+				encoded.push(encode_json(item)?)
+			}
+
+			Ok(Value::Array(encoded))
+		},
+		JsonValue::Object(obj) => {
+			let mut entries = Vec::with_capacity(obj.len());
+			for (key, value) in obj.iter() {
+				entries.push((Value::Text(key.to_string()), encode_json(value)?))
+			}
+
+			Ok(Value::Map(entries))
+		}
+	}
+}
+
+/// Encode a JSON number, as an integer when possible.
+fn encode_number(n: Number) -> Value {
+	let (positive, mantissa, exponent) = n.as_parts();
+	if exponent == 0 && (positive || mantissa != 0) {
+		let n = if positive { mantissa as i128 } else { -(mantissa as i128) };
+		if let Ok(n) = Integer::try_from(n) {
+			return Value::Integer(n)
+		}
+	}
+
+	Value::Float(n.into())
+}
+
+/// Decode a CBOR value into the JSON value it encodes as it is.
+fn decode_json(value: Value) -> Result<JsonValue, Error> {
+	match value {
+		Value::Null => Ok(JsonValue::Null),
+		Value::Bool(b) => Ok(b.into()),
+		Value::Integer(n) => {
+			let n = i128::from(n);
+			let mantissa = u64::try_from(n.unsigned_abs()).map_err(|_| Error::Invalid)?;
+			Ok(Number::from_parts(n >= 0, mantissa, 0).into())
+		},
+		Value::Float(n) if n.is_finite() => Ok(json_float(n)),
+		Value::Text(s) => Ok(s.into()),
+		Value::Array(items) => {
+			let mut decoded = Vec::with_capacity(items.len());
+			for item in items {
+				decoded.push(decode_json(item)?)
+			}
+
+			Ok(JsonValue::Array(decoded))
+		},
+		Value::Map(entries) => {
+			let mut obj = JsonObject::with_capacity(entries.len());
+			for (key, value) in entries {
+				match key {
+					Value::Text(key) => obj.insert(&key, decode_json(value)?),
+					_ => return Err(Error::Invalid)
+				}
+			}
+
+			Ok(JsonValue::Object(obj))
+		},
+		_ => Err(Error::Invalid)
+	}
+}
+
+/// Days since the Unix epoch of the given date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let year_of_era = year - era * 400;
+	let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	era * 146097 + day_of_era - 719468
+}
+
+/// Date of the given number of days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+	let days = days + 719468;
+	let era = days.div_euclid(146097);
+	let day_of_era = days - era * 146097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let mp = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+	(year, month, day)
+}
+
+/// Parse a `YYYY-MM-DDThh:mm:ssZ` date into its number of seconds since the Unix epoch.
+///
+/// Any other form is not compressed, so that the date is decoded as it was written.
+fn parse_date_time(value: &str) -> Option<i64> {
+	let bytes = value.as_bytes();
+	if bytes.len() != 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' || bytes[13] != b':' || bytes[16] != b':' || bytes[19] != b'Z' {
+		return None
+	}
+
+	let number = |range: std::ops::Range<usize>| -> Option<i64> {
+		let digits = &value[range];
+		if digits.bytes().all(|b| b.is_ascii_digit()) {
+			digits.parse().ok()
+		} else {
+			None
+		}
+	};
+
+	let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+	let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+	if !(1..=12).contains(&month) || day < 1 || hour > 23 || minute > 59 || second > 59 {
+		return None
+	}
+
+	let days = days_from_civil(year, month, day);
+	if civil_from_days(days) != (year, month, day) {
+		return None
+	}
+
+	Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Format a number of seconds since the Unix epoch as a `YYYY-MM-DDThh:mm:ssZ` date.
+fn format_date_time(seconds: i64) -> Option<String> {
+	let (days, time) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+	let (year, month, day) = civil_from_days(days);
+	if !(0..=9999).contains(&year) {
+		return None
+	}
+
+	Some(format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, time / 3600, time % 3600 / 60, time % 60))
+}
+
+/// Encode the given compacted document.
+///
+/// The term dictionary is derived from the `@context` entry of the document, whose remote
+/// contexts are loaded with the given loader.
+pub async fn encode<L: Send + Sync + Loader>(document: &JsonValue, loader: &mut L) -> Result<Vec<u8>, Error> where L::Output: Into<JsonValue> {
+	let context = match document {
+		JsonValue::Object(obj) => obj.get("@context").unwrap_or(&JsonValue::Null),
+		_ => &JsonValue::Null
+	};
+
+	let dictionary = Dictionary::new(context, loader).await?;
+
+	let value = Value::Tag(COMPRESSED, Box::new(dictionary.encode("", document, Codec::Plain)?));
+	let mut bytes = Vec::new();
+	ciborium::ser::into_writer(&value, &mut bytes).expect("writing into a vector cannot fail");
+	Ok(bytes)
+}
+
+/// Decode the given CBOR-LD document.
+///
+/// The remote contexts of the document are loaded with the given loader, to derive its term
+/// dictionary.
+pub async fn decode<L: Send + Sync + Loader>(bytes: &[u8], loader: &mut L) -> Result<JsonValue, Error> where L::Output: Into<JsonValue> {
+	let value: Value = ciborium::de::from_reader(bytes).map_err(|_| Error::InvalidCbor)?;
+	match value {
+		Value::Tag(COMPRESSED, value) => {
+			let context = match value.as_ref() {
+				Value::Map(entries) => match entries.iter().find(|(key, _)| is_context_key(key)) {
+					Some((_, context)) => decode_context(context.clone())?,
+					None => JsonValue::Null
+				},
+				_ => JsonValue::Null
+			};
+
+			Dictionary::new(&context, loader).await?.decode(*value, Codec::Plain)
+		},
+		Value::Tag(UNCOMPRESSED, value) => decode_json(*value),
+		_ => Err(Error::NotCborLd)
+	}
+}
+
+/// Checks if the given key is the compressed `@context` keyword.
+fn is_context_key(key: &Value) -> bool {
+	match key {
+		Value::Integer(code) => {
+			let code = i128::from(*code);
+			code == 0 || code == 1
+		},
+		_ => false
+	}
+}
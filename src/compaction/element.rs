@@ -0,0 +1,656 @@
+use std::collections::HashSet;
+use futures::future::{BoxFuture, FutureExt};
+use mown::Mown;
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	Indexed,
+	Object,
+	Value,
+	Node,
+	Lenient,
+	Reference,
+	ContextMut,
+	context::{
+		Loader,
+		Local,
+		ProcessingStack,
+		ProcessingOptions
+	},
+	expansion::expand_iri,
+	syntax::{
+		Keyword,
+		Term,
+		Container,
+		ContainerType
+	},
+	util::AsJson
+};
+use super::{
+	Options,
+	InverseContext,
+	PRESERVE,
+	compact_iri,
+	compact_value,
+	compact_node_reference,
+	is_graph_object,
+	literal_as_json,
+	as_node_reference
+};
+
+/// Compaction environment.
+#[derive(Clone, Copy)]
+pub(crate) struct Env<'a> {
+	/// Compaction options.
+	pub options: Options,
+
+	/// Blank node identifiers to omit.
+	pub pruned: &'a HashSet<String>
+}
+
+/// Active context along with its inverse context.
+struct Scope<'a, C> {
+	context: Mown<'a, C>,
+	inverse: Mown<'a, InverseContext>
+}
+
+impl<'a, C> Scope<'a, C> {
+	fn borrowed(context: &'a C, inverse: &'a InverseContext) -> Scope<'a, C> {
+		Scope {
+			context: Mown::Borrowed(context),
+			inverse: Mown::Borrowed(inverse)
+		}
+	}
+
+	fn owned<T: Id>(context: C) -> Scope<'a, C> where C: ContextMut<T> {
+		let inverse = InverseContext::new(&context);
+		Scope {
+			context: Mown::Owned(context),
+			inverse: Mown::Owned(inverse)
+		}
+	}
+
+	fn context(&self) -> &C {
+		self.context.as_ref()
+	}
+
+	fn inverse(&self) -> &InverseContext {
+		self.inverse.as_ref()
+	}
+}
+
+/// Add a value to an entry of a JSON object.
+///
+/// If `as_array` is `true`, the entry is always an array.
+fn add_value(map: &mut json::object::Object, key: &str, value: JsonValue, as_array: bool) {
+	if as_array {
+		match map.get(key) {
+			Some(JsonValue::Array(_)) => (),
+			Some(_) => {
+				let original = map.remove(key).unwrap();
+				map.insert(key, JsonValue::Array(vec![original]))
+			},
+			None => map.insert(key, JsonValue::Array(Vec::new()))
+		}
+	}
+
+	match value {
+		JsonValue::Array(items) => {
+			for item in items {
+				add_value(map, key, item, false)
+			}
+		},
+		value => match map.get_mut(key) {
+			Some(JsonValue::Array(items)) => items.push(value),
+			Some(original) => {
+				let original = original.take();
+				map.insert(key, JsonValue::Array(vec![original, value]))
+			},
+			None => map.insert(key, value)
+		}
+	}
+}
+
+/// Get the object entry with the given key, created if necessary.
+fn object_entry<'r>(map: &'r mut json::object::Object, key: &str) -> &'r mut json::object::Object {
+	if !matches!(map.get(key), Some(JsonValue::Object(_))) {
+		map.insert(key, JsonValue::Object(json::object::Object::new()))
+	}
+
+	match map.get_mut(key) {
+		Some(JsonValue::Object(entry)) => entry,
+		_ => unreachable!()
+	}
+}
+
+/// Nest term of the given term, if any.
+fn nest_term<T: Id, C: ContextMut<T>>(active_context: &C, term: &str) -> Result<Option<String>, Error> {
+	match active_context.get(term).and_then(|definition| definition.nest.as_ref()) {
+		Some(nest) => {
+			// The nest term must be `@nest` or a term expanding to `@nest`.
+			match expand_iri::<T, C>(active_context, nest, false, true) {
+				Lenient::Ok(Term::Keyword(Keyword::Nest)) => Ok(Some(nest.clone())),
+				_ => Err(ErrorCode::InvalidNestValue.into())
+			}
+		},
+		None => Ok(None)
+	}
+}
+
+/// Object in which the entries of a term are added, according to its nest term.
+fn nest_target<'r>(result: &'r mut json::object::Object, nest: Option<&str>) -> &'r mut json::object::Object {
+	match nest {
+		Some(nest) => object_entry(result, nest),
+		None => result
+	}
+}
+
+/// Remove the values of an entry, keeping all but the first one, returned if it is a string.
+fn take_first_string(map: &mut JsonValue, key: &str) -> Option<String> {
+	let map = match map {
+		JsonValue::Object(map) => map,
+		_ => return None
+	};
+
+	let mut values = match map.remove(key) {
+		Some(JsonValue::Array(values)) => values,
+		Some(value) => vec![value],
+		None => return None
+	};
+
+	let first = if values.first().map(|value| value.is_string()).unwrap_or(false) {
+		values.remove(0).as_str().map(|s| s.to_string())
+	} else {
+		None
+	};
+
+	for value in values {
+		add_value(map, key, value, false)
+	}
+
+	first
+}
+
+/// Sort objects by their JSON representation.
+fn sorted<'a, T: 'a + Id, I: IntoIterator<Item = &'a Indexed<Object<T>>>>(objects: I) -> Vec<&'a Indexed<Object<T>>> {
+	let mut objects: Vec<_> = objects.into_iter().collect();
+	objects.sort_by_cached_key(|object| object.as_json().dump());
+	objects
+}
+
+/// Convert indexed nodes into indexed objects.
+fn nodes_as_objects<'a, T: 'a + Id, I: IntoIterator<Item = &'a Indexed<Node<T>>>>(nodes: I) -> Vec<Indexed<Object<T>>> {
+	nodes.into_iter().map(|node| Indexed::new(Object::Node(Box::new(node.inner().clone())), node.index().map(|index| index.to_string()))).collect()
+}
+
+/// Compact a sequence of objects.
+///
+/// Returns an array, unless `compact_arrays` is set and the result has a single item, not
+/// required to be in an array by the active property.
+pub(crate) fn compact_array<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, inverse: &'a InverseContext, active_property: Option<&'a str>, items: Vec<&'a Indexed<Object<T>>>, loader: &'a mut L, env: Env<'a>) -> BoxFuture<'a, Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let mut result = Vec::with_capacity(items.len());
+		for item in items {
+			result.push(compact_object(active_context, inverse, active_property, item, loader, env).await?)
+		}
+
+		let container = active_context.get_opt(active_property).map(|definition| &definition.container);
+		let keep_array = !env.options.compact_arrays
+			|| result.len() != 1
+			|| active_property == Some("@graph")
+			|| active_property == Some("@set")
+			|| container.map(|container| container.contains(ContainerType::List) || container.contains(ContainerType::Set)).unwrap_or(false);
+
+		if keep_array {
+			Ok(JsonValue::Array(result))
+		} else {
+			Ok(result.into_iter().next().unwrap())
+		}
+	}.boxed()
+}
+
+/// Compact a single object.
+///
+/// This is the [Compaction algorithm](https://www.w3.org/TR/json-ld11-api/#compaction-algorithm)
+/// applied on a value, node or list object.
+pub(crate) fn compact_object<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, inverse: &'a InverseContext, active_property: Option<&'a str>, element: &'a Indexed<Object<T>>, loader: &'a mut L, env: Env<'a>) -> BoxFuture<'a, Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		if loader.is_cancelled() {
+			return Err(ErrorCode::Cancelled.into())
+		}
+
+		let options = env.options;
+
+		// The type-scoped context is the context before reverting to the previous context.
+		let type_scoped_context = active_context;
+		let type_scoped_inverse = inverse;
+
+		// Revert to the previous context, unless the element is a value or node reference.
+		let mut scope = match active_context.previous_context() {
+			Some(previous_context) if !element.is_value() && as_node_reference(element).is_none() => Scope::owned(previous_context.clone()),
+			_ => Scope::borrowed(active_context, inverse)
+		};
+
+		// If the term definition for `active_property` has a local context, process it.
+		if let Some(definition) = active_context.get_opt(active_property) {
+			if let Some(local_context) = &definition.context {
+				let processing_options: ProcessingOptions = options.into();
+				let base_url = definition.base_url.as_ref().map(|url| url.as_iri());
+				scope = Scope::owned(local_context.process_with(scope.context(), ProcessingStack::new(), loader, base_url, processing_options.with_override()).await?)
+			}
+		}
+
+		// Values wrapped by framing are replaced by their compacted value.
+		if let Object::Node(node) = element.inner() {
+			if let Some(preserved) = node.extension(PRESERVE) {
+				let preserved: Vec<_> = preserved.iter().collect();
+				return compact_preserved(scope.context(), scope.inverse(), active_property, preserved, loader, env).await
+			}
+		}
+
+		// Compact values and node references into scalars when possible.
+		match element.inner() {
+			Object::Value(value) => {
+				let value = Indexed::new(value.clone(), element.index().map(|index| index.to_string()));
+				if let Some(result) = compact_value(scope.context(), active_property, &value) {
+					return Ok(result)
+				}
+			},
+			Object::Node(node) if node.has_only_id() => {
+				if let Some(result) = compact_node_reference(scope.context(), scope.inverse(), active_property, node, options)? {
+					return Ok(result)
+				}
+			},
+			_ => ()
+		}
+
+		let container = scope.context().get_opt(active_property).map(|definition| definition.container.clone()).unwrap_or_else(Container::new);
+
+		// Lists in a list container are compacted into arrays.
+		if let Object::List(items) = element.inner() {
+			if container.contains(ContainerType::List) {
+				return compact_array(scope.context(), scope.inverse(), active_property, items.iter().collect(), loader, env).await
+			}
+		}
+
+		let mut result = json::object::Object::new();
+
+		match element.inner() {
+			Object::Value(value) => {
+				compact_value_object(&mut result, scope.context(), scope.inverse(), type_scoped_context, type_scoped_inverse, value, options)?;
+			},
+			Object::List(items) => {
+				let key = compact_iri(scope.context(), scope.inverse(), "@list", None, true, false, options)?;
+				let compacted = compact_array(scope.context(), scope.inverse(), active_property, items.iter().collect(), loader, env).await?;
+				add_value(&mut result, &key, compacted, true)
+			},
+			Object::Node(node) => {
+				// Apply the type-scoped contexts of the node types.
+				if !node.types().is_empty() {
+					let mut compacted_types = Vec::with_capacity(node.types().len());
+					for ty in node.types() {
+						compacted_types.push(compact_iri(scope.context(), scope.inverse(), ty.as_str(), None, true, false, options)?)
+					}
+					compacted_types.sort();
+
+					let mut type_scoped = None;
+					for term in &compacted_types {
+						if let Some(definition) = type_scoped_context.get(term) {
+							if let Some(local_context) = &definition.context {
+								let processing_options: ProcessingOptions = options.into();
+								let base_url = definition.base_url.as_ref().map(|url| url.as_iri());
+								let context = type_scoped.as_ref().unwrap_or_else(|| scope.context());
+								type_scoped = Some(local_context.process_with(context, ProcessingStack::new(), loader, base_url, processing_options.without_propagation()).await?)
+							}
+						}
+					}
+
+					if let Some(context) = type_scoped {
+						scope = Scope::owned(context)
+					}
+				}
+
+				compact_node(&mut result, scope.context(), scope.inverse(), type_scoped_context, type_scoped_inverse, node, loader, env).await?
+			}
+		}
+
+		// The index is dropped when the element is compacted in an index container, which holds
+		// it as map key, unless the map is indexed by a property.
+		if let Some(index) = element.index() {
+			let property_index = scope.context().get_opt(active_property).map(|definition| definition.index.is_some()).unwrap_or(false);
+			if !container.contains(ContainerType::Index) || property_index {
+				let key = compact_iri(scope.context(), scope.inverse(), "@index", None, true, false, options)?;
+				result.insert(&key, index.into())
+			}
+		}
+
+		Ok(JsonValue::Object(result))
+	}.boxed()
+}
+
+/// Compact the values of a `@preserve` wrapper.
+///
+/// The `null` value is preserved as is.
+fn compact_preserved<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, inverse: &'a InverseContext, active_property: Option<&'a str>, preserved: Vec<&'a Indexed<Object<T>>>, loader: &'a mut L, env: Env<'a>) -> BoxFuture<'a, Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let mut result = Vec::with_capacity(preserved.len());
+		for item in &preserved {
+			match item.inner() {
+				Object::Value(Value::Literal(crate::object::Literal::Null, types)) if types.is_empty() => result.push(JsonValue::Null),
+				_ => result.push(compact_object(active_context, inverse, active_property, item, loader, env).await?)
+			}
+		}
+
+		if result.len() == 1 && env.options.compact_arrays {
+			Ok(result.into_iter().next().unwrap())
+		} else {
+			Ok(JsonValue::Array(result))
+		}
+	}.boxed()
+}
+
+/// Compact a value object that cannot be compacted into a scalar.
+fn compact_value_object<T: Id, C: ContextMut<T>>(result: &mut json::object::Object, active_context: &C, inverse: &InverseContext, type_scoped_context: &C, type_scoped_inverse: &InverseContext, value: &Value<T>, options: Options) -> Result<(), Error> {
+	let value_key = compact_iri(active_context, inverse, "@value", None, true, false, options)?;
+
+	match value {
+		Value::Literal(literal, types) => {
+			result.insert(&value_key, literal_as_json(literal));
+
+			let mut compacted_types = Vec::new();
+			for ty in types {
+				compacted_types.push(JsonValue::String(compact_iri(type_scoped_context, type_scoped_inverse, ty.as_iri().into_str(), None, true, false, options)?))
+			}
+
+			if let crate::object::Literal::Json(_) = literal {
+				compacted_types.push(JsonValue::String(compact_iri(type_scoped_context, type_scoped_inverse, "@json", None, true, false, options)?))
+			}
+
+			if !compacted_types.is_empty() {
+				let type_key = compact_iri(active_context, inverse, "@type", None, true, false, options)?;
+				add_value(result, &type_key, JsonValue::Array(compacted_types), !options.compact_arrays)
+			}
+		},
+		Value::LangString(str) => {
+			result.insert(&value_key, str.as_str().into());
+
+			if let Some(language) = str.language() {
+				let key = compact_iri(active_context, inverse, "@language", None, true, false, options)?;
+				result.insert(&key, language.into())
+			}
+
+			if let Some(direction) = str.direction() {
+				let key = compact_iri(active_context, inverse, "@direction", None, true, false, options)?;
+				result.insert(&key, direction.as_json())
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Compact the entries of a node object.
+#[allow(clippy::too_many_arguments)]
+fn compact_node<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(result: &'a mut json::object::Object, active_context: &'a C, inverse: &'a InverseContext, type_scoped_context: &'a C, type_scoped_inverse: &'a InverseContext, node: &'a Node<T>, loader: &'a mut L, env: Env<'a>) -> BoxFuture<'a, Result<(), Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let options = env.options;
+
+		// Identifier, unless it is a pruned blank node identifier.
+		if let Some(id) = node.id() {
+			let pruned = match id {
+				Lenient::Ok(Reference::Blank(id)) => env.pruned.contains(id.as_str()),
+				_ => false
+			};
+
+			if !pruned {
+				let key = compact_iri(active_context, inverse, "@id", None, true, false, options)?;
+				let compacted = compact_iri(active_context, inverse, id.as_str(), None, false, false, options)?;
+				result.insert(&key, compacted.into())
+			}
+		}
+
+		// Types, compacted with the type-scoped context.
+		if !node.types().is_empty() {
+			let mut compacted_types = Vec::with_capacity(node.types().len());
+			for ty in node.types() {
+				compacted_types.push(JsonValue::String(compact_iri(type_scoped_context, type_scoped_inverse, ty.as_str(), None, true, false, options)?))
+			}
+
+			let key = compact_iri(active_context, inverse, "@type", None, true, false, options)?;
+			let is_set = active_context.get(&key).map(|definition| definition.container.contains(ContainerType::Set)).unwrap_or(false);
+			let as_array = (is_set && options.processing_mode != crate::ProcessingMode::JsonLd1_0) || !options.compact_arrays;
+			add_value(result, &key, JsonValue::Array(compacted_types), as_array)
+		}
+
+		// Reverse properties.
+		if !node.reverse_properties.is_empty() {
+			let mut reverse_properties: Vec<_> = node.reverse_properties.iter().collect();
+			if options.ordered {
+				reverse_properties.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+			}
+
+			let mut reverse_result = json::object::Object::new();
+			for (property, nodes) in reverse_properties {
+				let objects = nodes_as_objects(nodes);
+				compact_property(&mut reverse_result, active_context, inverse, property.as_str(), objects.iter().collect(), true, loader, env).await?
+			}
+
+			// Reverse property terms are moved to the node.
+			let mut remaining = json::object::Object::new();
+			for (key, value) in reverse_result.iter() {
+				let is_reverse = active_context.get(key).map(|definition| definition.reverse_property).unwrap_or(false);
+				if is_reverse {
+					let is_set = active_context.get(key).map(|definition| definition.container.contains(ContainerType::Set)).unwrap_or(false);
+					add_value(result, key, value.clone(), is_set || !options.compact_arrays)
+				} else {
+					remaining.insert(key, value.clone())
+				}
+			}
+
+			if !remaining.is_empty() {
+				let key = compact_iri(active_context, inverse, "@reverse", None, true, false, options)?;
+				result.insert(&key, JsonValue::Object(remaining))
+			}
+		}
+
+		// Graph.
+		if let Some(graph) = node.graph() {
+			compact_property(result, active_context, inverse, "@graph", sorted(graph), false, loader, env).await?
+		}
+
+		// Included nodes.
+		if let Some(included) = node.included() {
+			let objects = nodes_as_objects(included);
+			compact_property(result, active_context, inverse, "@included", sorted(&objects), false, loader, env).await?
+		}
+
+		// Properties.
+		let mut properties: Vec<_> = node.properties.iter().collect();
+		if options.ordered {
+			properties.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		}
+
+		for (property, values) in properties {
+			compact_property(result, active_context, inverse, property.as_str(), values.iter().collect(), false, loader, env).await?
+		}
+
+		// Extension entries.
+		let mut extensions: Vec<_> = node.extensions().collect();
+		extensions.sort_by_key(|(keyword, _)| *keyword);
+		for (keyword, values) in extensions {
+			compact_property(result, active_context, inverse, keyword, values.iter().collect(), false, loader, env).await?
+		}
+
+		Ok(())
+	}.boxed()
+}
+
+/// Compact the values of an expanded property into the given result.
+#[allow(clippy::too_many_arguments)]
+fn compact_property<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(result: &'a mut json::object::Object, active_context: &'a C, inverse: &'a InverseContext, expanded_property: &'a str, values: Vec<&'a Indexed<Object<T>>>, inside_reverse: bool, loader: &'a mut L, env: Env<'a>) -> BoxFuture<'a, Result<(), Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let options = env.options;
+
+		if values.is_empty() {
+			let item_active_property = compact_iri(active_context, inverse, expanded_property, None, true, inside_reverse, options)?;
+			let nest = nest_term(active_context, &item_active_property)?;
+			add_value(nest_target(result, nest.as_deref()), &item_active_property, JsonValue::Array(Vec::new()), true);
+			return Ok(())
+		}
+
+		for expanded_item in values {
+			let item_active_property = compact_iri(active_context, inverse, expanded_property, Some(expanded_item), true, inside_reverse, options)?;
+			let definition = active_context.get(&item_active_property);
+			let container = definition.map(|definition| definition.container.clone()).unwrap_or_else(Container::new);
+			let nest = nest_term(active_context, &item_active_property)?;
+			let as_array = container.contains(ContainerType::Set) || item_active_property == "@graph" || item_active_property == "@list" || !options.compact_arrays;
+
+			// Compact the list items, the graph or the expanded item.
+			let mut compacted_item = match expanded_item.inner() {
+				Object::List(items) => compact_array(active_context, inverse, Some(&item_active_property), items.iter().collect(), loader, env).await?,
+				Object::Node(node) if is_graph_object(expanded_item) => compact_array(active_context, inverse, Some(&item_active_property), sorted(node.graph().unwrap()), loader, env).await?,
+				_ => compact_object(active_context, inverse, Some(&item_active_property), expanded_item, loader, env).await?
+			};
+
+			match expanded_item.inner() {
+				Object::List(_) => {
+					if !compacted_item.is_array() {
+						compacted_item = JsonValue::Array(vec![compacted_item])
+					}
+
+					let nest_result = nest_target(result, nest.as_deref());
+					if container.contains(ContainerType::List) {
+						nest_result.insert(&item_active_property, compacted_item)
+					} else {
+						let mut list = json::object::Object::new();
+						list.insert(&compact_iri(active_context, inverse, "@list", None, true, false, options)?, compacted_item);
+
+						if let Some(index) = expanded_item.index() {
+							list.insert(&compact_iri(active_context, inverse, "@index", None, true, false, options)?, index.into())
+						}
+
+						add_value(nest_result, &item_active_property, JsonValue::Object(list), as_array)
+					}
+				},
+				Object::Node(node) if is_graph_object(expanded_item) => {
+					let nest_result = nest_target(result, nest.as_deref());
+					if container.contains(ContainerType::Graph) && container.contains(ContainerType::Id) {
+						let map_key = match node.id() {
+							Some(id) => compact_iri(active_context, inverse, id.as_str(), None, false, false, options)?,
+							None => compact_iri(active_context, inverse, "@none", None, true, false, options)?
+						};
+
+						let map_object = object_entry(nest_result, &item_active_property);
+						add_value(map_object, &map_key, compacted_item, as_array)
+					} else if container.contains(ContainerType::Graph) && container.contains(ContainerType::Index) && node.id().is_none() {
+						let map_key = match expanded_item.index() {
+							Some(index) => index.to_string(),
+							None => compact_iri(active_context, inverse, "@none", None, true, false, options)?
+						};
+
+						let map_object = object_entry(nest_result, &item_active_property);
+						add_value(map_object, &map_key, compacted_item, as_array)
+					} else if container.contains(ContainerType::Graph) && node.id().is_none() {
+						if let JsonValue::Array(items) = &compacted_item {
+							if items.len() > 1 {
+								let mut included = json::object::Object::new();
+								included.insert(&compact_iri(active_context, inverse, "@included", None, true, false, options)?, compacted_item);
+								compacted_item = JsonValue::Object(included)
+							}
+						}
+
+						add_value(nest_result, &item_active_property, compacted_item, as_array)
+					} else {
+						let mut graph = json::object::Object::new();
+						graph.insert(&compact_iri(active_context, inverse, "@graph", None, true, false, options)?, compacted_item);
+
+						if let Some(id) = node.id() {
+							graph.insert(&compact_iri(active_context, inverse, "@id", None, true, false, options)?, compact_iri(active_context, inverse, id.as_str(), None, false, false, options)?.into())
+						}
+
+						if let Some(index) = expanded_item.index() {
+							graph.insert(&compact_iri(active_context, inverse, "@index", None, true, false, options)?, index.into())
+						}
+
+						add_value(nest_result, &item_active_property, JsonValue::Object(graph), as_array)
+					}
+				},
+				_ if !container.contains(ContainerType::Graph) && (container.contains(ContainerType::Language) || container.contains(ContainerType::Index) || container.contains(ContainerType::Id) || container.contains(ContainerType::Type)) => {
+					let container_type = if container.contains(ContainerType::Language) {
+						ContainerType::Language
+					} else if container.contains(ContainerType::Index) {
+						ContainerType::Index
+					} else if container.contains(ContainerType::Id) {
+						ContainerType::Id
+					} else {
+						ContainerType::Type
+					};
+
+					let mut container_key = compact_iri(active_context, inverse, container_type.as_str(), None, true, false, options)?;
+					let index_key = definition.and_then(|definition| definition.index.as_deref()).unwrap_or("@index");
+
+					let mut map_key = match container_type {
+						ContainerType::Language => {
+							let value_key = compact_iri(active_context, inverse, "@value", None, true, false, options)?;
+							if let JsonValue::Object(map) = &mut compacted_item {
+								if let Some(value) = map.remove(&value_key) {
+									compacted_item = value
+								}
+							}
+
+							match expanded_item.inner() {
+								Object::Value(Value::LangString(str)) => str.language().map(|language| language.to_string()),
+								_ => None
+							}
+						},
+						ContainerType::Index if index_key == "@index" => {
+							expanded_item.index().map(|index| index.to_string())
+						},
+						ContainerType::Index => {
+							let expanded_index_key = expand_iri(active_context, index_key, false, true);
+							container_key = compact_iri(active_context, inverse, expanded_index_key.as_str(), None, true, false, options)?;
+							take_first_string(&mut compacted_item, &container_key)
+						},
+						ContainerType::Id => {
+							match &mut compacted_item {
+								JsonValue::Object(map) => map.remove(&container_key).and_then(|value| value.as_str().map(|s| s.to_string())),
+								_ => None
+							}
+						},
+						_ => {
+							let map_key = take_first_string(&mut compacted_item, &container_key);
+
+							// A node with no other entry than its identifier is compacted as a
+							// node reference.
+							if let JsonValue::Object(map) = &compacted_item {
+								if map.len() == 1 {
+									let (key, _) = map.iter().next().unwrap();
+									if let Lenient::Ok(Term::Keyword(Keyword::Id)) = expand_iri::<T, C>(active_context, key, false, true) {
+										let mut reference = Node::new();
+										reference.id = expanded_item.id().cloned();
+										let reference = Indexed::new(Object::Node(Box::new(reference)), None);
+										compacted_item = compact_object(active_context, inverse, Some(&item_active_property), &reference, loader, env).await?
+									}
+								}
+							}
+
+							map_key
+						}
+					};
+
+					if map_key.is_none() {
+						map_key = Some(compact_iri(active_context, inverse, "@none", None, true, false, options)?)
+					}
+
+					let map_object = object_entry(nest_target(result, nest.as_deref()), &item_active_property);
+					add_value(map_object, &map_key.unwrap(), compacted_item, as_array)
+				},
+				_ => add_value(nest_target(result, nest.as_deref()), &item_active_property, compacted_item, as_array)
+			}
+		}
+
+		Ok(())
+	}.boxed()
+}
@@ -0,0 +1,263 @@
+use std::io;
+use std::collections::HashMap;
+use crate::{
+	Id,
+	Context,
+	syntax::{
+		Container,
+		Type
+	},
+	util::{
+		Encoder,
+		Decoder
+	}
+};
+
+/// Kind of the term selection key.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TypeLanguage {
+	/// Select terms by type mapping.
+	Type,
+
+	/// Select terms by language (and direction) mapping.
+	Language,
+
+	/// Select any term.
+	Any
+}
+
+/// Terms of a container mapping, by type and language mapping.
+#[derive(Clone)]
+struct TypeLanguageMap {
+	language: HashMap<String, String>,
+	typ: HashMap<String, String>,
+	any: String
+}
+
+impl TypeLanguageMap {
+	fn new(term: &str) -> TypeLanguageMap {
+		TypeLanguageMap {
+			language: HashMap::new(),
+			typ: HashMap::new(),
+			any: term.to_string()
+		}
+	}
+
+	fn set_language(&mut self, language: String, term: &str) {
+		self.language.entry(language).or_insert_with(|| term.to_string());
+	}
+
+	fn set_type(&mut self, typ: String, term: &str) {
+		self.typ.entry(typ).or_insert_with(|| term.to_string());
+	}
+
+	fn get(&self, type_language: TypeLanguage, value: &str) -> Option<&str> {
+		match type_language {
+			TypeLanguage::Language => self.language.get(value).map(String::as_str),
+			TypeLanguage::Type => self.typ.get(value).map(String::as_str),
+			TypeLanguage::Any => if value == "@none" {
+				Some(self.any.as_str())
+			} else {
+				None
+			}
+		}
+	}
+
+	fn encode(&self, encoder: &mut Encoder) {
+		encode_map(encoder, &self.language);
+		encode_map(encoder, &self.typ);
+		encoder.str(&self.any)
+	}
+
+	fn decode(decoder: &mut Decoder) -> io::Result<TypeLanguageMap> {
+		Ok(TypeLanguageMap {
+			language: decode_map(decoder)?,
+			typ: decode_map(decoder)?,
+			any: decoder.str()?.to_string()
+		})
+	}
+}
+
+/// Encode a map of strings, sorted by key.
+fn encode_map(encoder: &mut Encoder, map: &HashMap<String, String>) {
+	let mut entries: Vec<_> = map.iter().collect();
+	entries.sort();
+	encoder.len(entries.len());
+	for (key, value) in entries {
+		encoder.str(key);
+		encoder.str(value)
+	}
+}
+
+fn decode_map(decoder: &mut Decoder) -> io::Result<HashMap<String, String>> {
+	let mut map = HashMap::new();
+	for _ in 0..decoder.len()? {
+		let key = decoder.str()?.to_string();
+		map.insert(key, decoder.str()?.to_string());
+	}
+
+	Ok(map)
+}
+
+/// Key of a container mapping in the inverse context.
+///
+/// This is the concatenation of the container types, sorted lexicographically, or `@none`.
+fn container_key(container: &Container) -> String {
+	if container.is_empty() {
+		"@none".to_string()
+	} else {
+		let mut types: Vec<_> = container.iter().collect();
+		types.sort();
+		types.into_iter().map(|t| t.as_str()).collect()
+	}
+}
+
+/// Inverse context.
+///
+/// Maps each IRI (or keyword) to the terms of an active context expanding to it,
+/// by container, type and language mapping.
+/// It is built by the [Inverse Context Creation algorithm](https://www.w3.org/TR/json-ld11-api/#inverse-context-creation)
+/// and used to select the best term to compact an IRI.
+#[derive(Clone)]
+pub struct InverseContext {
+	map: HashMap<String, HashMap<String, TypeLanguageMap>>
+}
+
+impl InverseContext {
+	/// Create the inverse of the given active context.
+	pub fn new<T: Id, C: Context<T>>(active_context: &C) -> InverseContext {
+		let mut map: HashMap<String, HashMap<String, TypeLanguageMap>> = HashMap::new();
+
+		let default_language = match active_context.default_language() {
+			Some(language) => language.to_lowercase(),
+			None => "@none".to_string()
+		};
+
+		// Terms are processed shortest first, then lexicographically.
+		let mut definitions: Vec<_> = active_context.definitions().collect();
+		definitions.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+		for (term, definition) in definitions {
+			let var = match &definition.value {
+				Some(value) if !value.as_str().is_empty() => value.as_str(),
+				_ => continue
+			};
+
+			let container = container_key(&definition.container);
+			let type_language_map = map.entry(var.to_string()).or_default().entry(container).or_insert_with(|| TypeLanguageMap::new(term));
+
+			if definition.reverse_property {
+				type_language_map.set_type("@reverse".to_string(), term)
+			} else {
+				match &definition.typ {
+					Some(Type::None) => {
+						type_language_map.set_language("@any".to_string(), term);
+						type_language_map.set_type("@any".to_string(), term)
+					},
+					Some(typ) => {
+						type_language_map.set_type(typ.as_str().to_string(), term)
+					},
+					None => match (&definition.language, &definition.direction) {
+						(Some(language), Some(direction)) => {
+							let key = match (language, direction) {
+								(Some(language), Some(direction)) => format!("{}_{}", language, direction).to_lowercase(),
+								(Some(language), None) => language.to_lowercase(),
+								(None, Some(direction)) => format!("_{}", direction),
+								(None, None) => "@null".to_string()
+							};
+							type_language_map.set_language(key, term)
+						},
+						(Some(language), None) => {
+							let key = match language {
+								Some(language) => language.to_lowercase(),
+								None => "@null".to_string()
+							};
+							type_language_map.set_language(key, term)
+						},
+						(None, Some(direction)) => {
+							let key = match direction {
+								Some(direction) => format!("_{}", direction),
+								None => "@none".to_string()
+							};
+							type_language_map.set_language(key, term)
+						},
+						(None, None) => {
+							if let Some(direction) = active_context.default_base_direction() {
+								let language = active_context.default_language().unwrap_or("");
+								type_language_map.set_language(format!("{}_{}", language, direction).to_lowercase(), term);
+							} else {
+								type_language_map.set_language(default_language.clone(), term);
+							}
+							type_language_map.set_language("@none".to_string(), term);
+							type_language_map.set_type("@none".to_string(), term)
+						}
+					}
+				}
+			}
+		}
+
+		InverseContext {
+			map
+		}
+	}
+
+	pub(crate) fn encode(&self, encoder: &mut Encoder) {
+		let mut entries: Vec<_> = self.map.iter().collect();
+		entries.sort_by_key(|(var, _)| *var);
+		encoder.len(entries.len());
+		for (var, container_map) in entries {
+			encoder.str(var);
+
+			let mut containers: Vec<_> = container_map.iter().collect();
+			containers.sort_by_key(|(container, _)| *container);
+			encoder.len(containers.len());
+			for (container, type_language_map) in containers {
+				encoder.str(container);
+				type_language_map.encode(encoder)
+			}
+		}
+	}
+
+	pub(crate) fn decode(decoder: &mut Decoder) -> io::Result<InverseContext> {
+		let mut map = HashMap::new();
+		for _ in 0..decoder.len()? {
+			let var = decoder.str()?.to_string();
+
+			let mut container_map = HashMap::new();
+			for _ in 0..decoder.len()? {
+				let container = decoder.str()?.to_string();
+				container_map.insert(container, TypeLanguageMap::decode(decoder)?);
+			}
+
+			map.insert(var, container_map);
+		}
+
+		Ok(InverseContext {
+			map
+		})
+	}
+
+	/// Checks if some term of the active context expands to the given IRI or keyword.
+	pub fn contains(&self, var: &str) -> bool {
+		self.map.contains_key(var)
+	}
+
+	/// Select the term expanding to `var` that best matches the given containers, in order of
+	/// preference, and the given type or language preferred values.
+	///
+	/// This is the [Term Selection algorithm](https://www.w3.org/TR/json-ld11-api/#term-selection).
+	pub fn select(&self, var: &str, containers: &[&str], type_language: TypeLanguage, preferred_values: &[String]) -> Option<&str> {
+		let container_map = self.map.get(var)?;
+		for container in containers {
+			if let Some(type_language_map) = container_map.get(*container) {
+				for value in preferred_values {
+					if let Some(term) = type_language_map.get(type_language, value) {
+						return Some(term)
+					}
+				}
+			}
+		}
+
+		None
+	}
+}
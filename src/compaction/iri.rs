@@ -0,0 +1,347 @@
+use iref::{Iri, IriRef};
+use crate::{
+	ProcessingMode,
+	Error,
+	ErrorCode,
+	Id,
+	Indexed,
+	Object,
+	Value,
+	Context,
+	object::Literal,
+	syntax::is_keyword_like
+};
+use super::{
+	Options,
+	InverseContext,
+	TypeLanguage,
+	is_graph_object,
+	preserved
+};
+
+/// Language and direction selection key of a language string.
+fn language_direction(language: Option<&str>, direction: Option<impl std::fmt::Display>) -> String {
+	match direction {
+		Some(direction) => format!("{}_{}", language.unwrap_or(""), direction).to_lowercase(),
+		None => match language {
+			Some(language) => language.to_lowercase(),
+			None => "@null".to_string()
+		}
+	}
+}
+
+/// Type selection key of a typed value, if it has a type.
+fn value_type<T: Id>(value: &Value<T>) -> Option<&str> {
+	match value {
+		Value::Literal(Literal::Json(_), _) => Some("@json"),
+		Value::Literal(_, types) => types.iter().next().map(|ty| ty.as_iri().into_str()),
+		Value::LangString(_) => None
+	}
+}
+
+/// Checks if the given object is a value object with only an `@value` entry, holding a string.
+///
+/// Other values cannot appear in a language map.
+fn is_simple_value<T: Id>(value: &Indexed<Object<T>>) -> bool {
+	value.index().is_none() && match value.inner() {
+		Object::Value(Value::Literal(Literal::String(_), types)) => types.is_empty(),
+		Object::Value(Value::LangString(str)) => str.language().is_none() && str.direction().is_none(),
+		_ => false
+	}
+}
+
+/// Compact an IRI, keyword or blank node identifier against the given active context.
+///
+/// This is the [IRI Compaction algorithm](https://www.w3.org/TR/json-ld11-api/#iri-compaction).
+/// The optional `value` is the expanded value associated to `var`, used to select the most
+/// appropriate term.
+/// If `vocab` is `true`, terms and the vocabulary mapping are used.
+/// Otherwise `var` may be made relative to the base IRI of the active context.
+/// If `reverse` is `true`, only reverse property terms are selected.
+pub fn compact_iri<T: Id, C: Context<T>>(active_context: &C, inverse: &InverseContext, var: &str, value: Option<&Indexed<Object<T>>>, vocab: bool, reverse: bool, options: Options) -> Result<String, Error> {
+	// If `vocab` is true and `var` is an entry of the inverse context, select a term.
+	if vocab && inverse.contains(var) {
+		let default_language = match (active_context.default_language(), active_context.default_base_direction()) {
+			(language, Some(direction)) => language_direction(language, Some(direction)),
+			(Some(language), None) => language.to_lowercase(),
+			(None, None) => "@none".to_string()
+		};
+
+		// The value of a `@preserve` wrapper is used instead of the wrapper.
+		let value = value.map(preserved);
+
+		let mut containers = Vec::new();
+		let mut type_language = TypeLanguage::Language;
+		let mut type_language_value = "@null".to_string();
+
+		// Lists and graph objects are never compacted into index maps.
+		let indexable = value.map(|value| !is_graph_object(value) && !matches!(value.inner(), Object::List(_))).unwrap_or(true);
+		let has_index = value.map(|value| value.index().is_some()).unwrap_or(false);
+		if has_index && indexable {
+			containers.push("@index");
+			containers.push("@index@set");
+		}
+
+		if reverse {
+			type_language = TypeLanguage::Type;
+			type_language_value = "@reverse".to_string();
+			containers.push("@set");
+		} else {
+			match value.map(|value| value.inner()) {
+				Some(Object::List(items)) => {
+					if !has_index {
+						containers.push("@list");
+					}
+
+					let mut common_type: Option<String> = None;
+					let mut common_language = if items.is_empty() {
+						Some(default_language.clone())
+					} else {
+						None
+					};
+
+					for item in items {
+						let mut item_language = "@none".to_string();
+						let mut item_type = "@none".to_string();
+						let is_value = item.is_value();
+
+						match item.inner() {
+							Object::Value(Value::LangString(str)) => {
+								item_language = language_direction(str.language(), str.direction())
+							},
+							Object::Value(value) => match value_type(value) {
+								Some(ty) => item_type = ty.to_string(),
+								None => item_language = "@null".to_string()
+							},
+							_ => item_type = "@id".to_string()
+						}
+
+						match &common_language {
+							None => common_language = Some(item_language),
+							Some(language) if *language != item_language && is_value => common_language = Some("@none".to_string()),
+							_ => ()
+						}
+
+						match &common_type {
+							None => common_type = Some(item_type),
+							Some(ty) if *ty != item_type => common_type = Some("@none".to_string()),
+							_ => ()
+						}
+
+						if common_language.as_deref() == Some("@none") && common_type.as_deref() == Some("@none") {
+							break
+						}
+					}
+
+					let common_language = common_language.unwrap_or_else(|| "@none".to_string());
+					let common_type = common_type.unwrap_or_else(|| "@none".to_string());
+
+					if common_type != "@none" {
+						type_language = TypeLanguage::Type;
+						type_language_value = common_type;
+					} else {
+						type_language_value = common_language;
+					}
+				},
+				Some(Object::Node(node)) if is_graph_object(value.unwrap()) => {
+					if has_index {
+						containers.push("@graph@index");
+						containers.push("@graph@index@set");
+					}
+
+					if node.id().is_some() {
+						containers.push("@graph@id");
+						containers.push("@graph@id@set");
+					}
+
+					containers.push("@graph");
+					containers.push("@graph@set");
+					containers.push("@set");
+
+					if !has_index {
+						containers.push("@graph@index");
+						containers.push("@graph@index@set");
+					}
+
+					if node.id().is_none() {
+						containers.push("@graph@id");
+						containers.push("@graph@id@set");
+					}
+
+					type_language = TypeLanguage::Type;
+					type_language_value = "@id".to_string();
+				},
+				Some(Object::Value(value)) => {
+					match value {
+						Value::LangString(str) if !has_index && (str.language().is_some() || str.direction().is_some()) => {
+							type_language_value = language_direction(str.language(), str.direction());
+							containers.push("@language");
+							containers.push("@language@set");
+						},
+						value => if let Some(ty) = value_type(value) {
+							type_language = TypeLanguage::Type;
+							type_language_value = ty.to_string();
+						}
+					}
+
+					containers.push("@set");
+				},
+				_ => {
+					type_language = TypeLanguage::Type;
+					type_language_value = "@id".to_string();
+					containers.push("@id");
+					containers.push("@id@set");
+					containers.push("@type");
+					containers.push("@set@type");
+					containers.push("@set");
+				}
+			}
+		}
+
+		containers.push("@none");
+
+		if options.processing_mode != ProcessingMode::JsonLd1_0 {
+			if !has_index && indexable {
+				containers.push("@index");
+				containers.push("@index@set");
+			}
+
+			if value.map(|value| is_simple_value(value)).unwrap_or(false) {
+				containers.push("@language");
+				containers.push("@language@set");
+			}
+		}
+
+		let mut preferred_values = Vec::new();
+
+		if type_language_value == "@reverse" {
+			preferred_values.push("@reverse".to_string());
+		}
+
+		let id = value.and_then(|value| value.id()).map(|id| id.as_str());
+		match id {
+			Some(id) if type_language_value == "@id" || type_language_value == "@reverse" => {
+				let compacted_id = compact_iri(active_context, inverse, id, None, true, false, options)?;
+				let is_term = match active_context.get(&compacted_id) {
+					Some(definition) => definition.value.as_ref().map(|value| value.as_str() == id).unwrap_or(false),
+					None => false
+				};
+
+				if is_term {
+					preferred_values.push("@vocab".to_string());
+					preferred_values.push("@id".to_string());
+				} else {
+					preferred_values.push("@id".to_string());
+					preferred_values.push("@vocab".to_string());
+				}
+
+				preferred_values.push("@none".to_string());
+			},
+			_ => {
+				preferred_values.push(type_language_value);
+				preferred_values.push("@none".to_string());
+
+				if let Some(Object::List(items)) = value.map(|value| value.inner()) {
+					if items.is_empty() {
+						type_language = TypeLanguage::Any;
+					}
+				}
+			}
+		}
+
+		preferred_values.push("@any".to_string());
+
+		// Language and direction values also match terms with the same direction.
+		let directions: Vec<_> = preferred_values.iter().filter_map(|value| value.find('_').map(|i| value[i..].to_string())).collect();
+		preferred_values.extend(directions);
+
+		if let Some(term) = inverse.select(var, &containers, type_language, &preferred_values) {
+			return Ok(term.to_string())
+		}
+	}
+
+	// If `vocab` is true and the active context has a vocabulary mapping, use the suffix of
+	// `var` if it has no term definition.
+	if vocab {
+		if let Some(vocabulary) = active_context.vocabulary() {
+			let vocabulary = vocabulary.as_str();
+			if var.starts_with(vocabulary) && var.len() > vocabulary.len() {
+				let suffix = &var[vocabulary.len()..];
+				if active_context.get(suffix).is_none() {
+					return Ok(suffix.to_string())
+				}
+			}
+		}
+	}
+
+	// Try to build a compact IRI, choosing the shortest candidate, then the lexicographically
+	// least.
+	let mut compact_iri: Option<String> = None;
+	for (term, definition) in active_context.definitions() {
+		let prefix = match &definition.value {
+			Some(prefix) if definition.prefix => prefix.as_str(),
+			_ => continue
+		};
+
+		if prefix.is_empty() || prefix == var || !var.starts_with(prefix) {
+			continue
+		}
+
+		let candidate = format!("{}:{}", term, &var[prefix.len()..]);
+		let is_better = match &compact_iri {
+			Some(current) => candidate.len() < current.len() || (candidate.len() == current.len() && candidate < *current),
+			None => true
+		};
+
+		if is_better {
+			let is_usable = match active_context.get(&candidate) {
+				Some(definition) => value.is_none() && definition.value.as_ref().map(|value| value.as_str() == var).unwrap_or(false),
+				None => true
+			};
+
+			if is_usable {
+				compact_iri = Some(candidate)
+			}
+		}
+	}
+
+	if let Some(compact_iri) = compact_iri {
+		return Ok(compact_iri)
+	}
+
+	// Make sure that `var` is not confused with a compact IRI.
+	if let Ok(iri) = Iri::new(var) {
+		if !var[iri.scheme().as_str().len() + 1..].starts_with("//") {
+			if let Some(definition) = active_context.get(iri.scheme().as_str()) {
+				if definition.prefix {
+					return Err(ErrorCode::IriConfusedWithPrefix.into())
+				}
+			}
+		}
+
+		// If `vocab` is false, make `var` relative to the base IRI.
+		if !vocab && options.compact_to_relative {
+			if let Some(base_iri) = active_context.base_iri() {
+				return Ok(relative_iri(iri, base_iri))
+			}
+		}
+	}
+
+	Ok(var.to_string())
+}
+
+/// Make an IRI relative to the given base IRI, if it can be resolved back to the same IRI.
+fn relative_iri(iri: Iri, base_iri: Iri) -> String {
+	let relative = iri.as_iri_ref().relative_to(base_iri.as_iri_ref());
+	let mut relative = relative.as_str().to_string();
+
+	// A relative reference with the form of a keyword would be ignored.
+	if is_keyword_like(&relative) {
+		relative.insert_str(0, "./")
+	}
+
+	match IriRef::new(&relative) {
+		Ok(iri_ref) if iri_ref.resolved(base_iri).as_str() == iri.as_str() => relative,
+		_ => iri.as_str().to_string()
+	}
+}
@@ -0,0 +1,272 @@
+//! Compaction algorithm and types.
+//!
+//! Compaction is the inverse of expansion: it turns an [`ExpandedDocument`] into a compact
+//! JSON-LD document, using the terms, compact IRIs, containers and coercion rules of a context.
+//! Expanding the result with the same context gives back the original expanded document.
+//!
+//! ```ignore
+//! let context = json::parse(r#"{
+//!     "@context": { "name": "http://xmlns.com/foaf/0.1/name" }
+//! }"#)?;
+//!
+//! // Gives `{ "@context": ..., "name": "Timothée Haudebourg" }`.
+//! let compacted = task::block_on(compaction::compact(&expanded, &context, None, &mut loader, compaction::Options::default()))?;
+//! ```
+//!
+//! ## Framing
+//!
+//! The compaction step of the framing algorithm is supported from the start:
+//! - Values wrapped by framing in a [`PRESERVE`] extension entry (see
+//!   [`Node::insert_extension`](crate::Node::insert_extension)) are replaced by their
+//!   compacted value, `null` values being preserved.
+//! - The [`omit_graph`](Options::omit_graph) option controls the top-level `@graph` entry.
+//! - The [`prune_blank_node_identifiers`](Options::prune_blank_node_identifiers) option removes
+//!   the identifier of blank nodes appearing only once.
+//!
+//! ## Streaming
+//!
+//! Large documents can be compacted into an [`io::Write`](std::io::Write) one top-level object
+//! at a time with a [`Writer`], without building the whole compacted JSON document in memory.
+
+mod inverse;
+mod iri;
+mod value;
+mod element;
+mod writer;
+
+use std::collections::{HashMap, HashSet};
+use futures::Future;
+use iref::Iri;
+use json::JsonValue;
+use crate::{
+	ProcessingMode,
+	Error,
+	Id,
+	Indexed,
+	Object,
+	Node,
+	Lenient,
+	Reference,
+	ExpandedDocument,
+	ContextMut,
+	context::{
+		Local,
+		Loader,
+		ProcessingStack,
+		ProcessingOptions
+	},
+};
+
+pub use inverse::*;
+pub use iri::*;
+pub use value::*;
+pub use writer::*;
+use element::*;
+
+/// Extension keyword wrapping the values preserved by framing.
+pub const PRESERVE: &str = "@preserve";
+
+/// Compaction options.
+#[derive(Clone, Copy)]
+pub struct Options {
+	/// Sets the processing mode.
+	pub processing_mode: ProcessingMode,
+
+	/// If set to `true`, IRIs are made relative to the base IRI of the context when possible.
+	///
+	/// Default is `true`.
+	pub compact_to_relative: bool,
+
+	/// If set to `true`, arrays with a single item are replaced by this item, unless the
+	/// context requires an array.
+	///
+	/// Default is `true`.
+	pub compact_arrays: bool,
+
+	/// If set to `true`, properties are processed lexicographically.
+	///
+	/// Default is `false`.
+	pub ordered: bool,
+
+	/// If set to `false`, the result always has a top-level `@graph` entry.
+	/// Otherwise, this entry is only used when the document has more than one top-level node.
+	///
+	/// This is the `omitGraph` framing option. Default is `true`.
+	pub omit_graph: bool,
+
+	/// If set to `true`, the identifier of blank nodes appearing only once is removed.
+	///
+	/// This is the `pruneBlankNodeIdentifiers` framing option. Default is `false`.
+	pub prune_blank_node_identifiers: bool
+}
+
+impl Default for Options {
+	fn default() -> Options {
+		Options {
+			processing_mode: ProcessingMode::default(),
+			compact_to_relative: true,
+			compact_arrays: true,
+			ordered: false,
+			omit_graph: true,
+			prune_blank_node_identifiers: false
+		}
+	}
+}
+
+impl From<Options> for ProcessingOptions {
+	fn from(options: Options) -> ProcessingOptions {
+		ProcessingOptions {
+			processing_mode: options.processing_mode,
+			..ProcessingOptions::default()
+		}
+	}
+}
+
+/// Checks if the given object is a graph object: a node with a graph, and no other entry
+/// than `@id` and `@index`.
+pub(crate) fn is_graph_object<T: Id>(object: &Indexed<Object<T>>) -> bool {
+	match object.inner() {
+		Object::Node(node) => {
+			node.graph.is_some()
+			&& node.types.is_empty()
+			&& node.included.is_none()
+			&& node.properties.is_empty()
+			&& node.reverse_properties.is_empty()
+			&& node.extensions.is_empty()
+		},
+		_ => false
+	}
+}
+
+/// Get the value wrapped in a `@preserve` extension entry, or the object itself.
+pub(crate) fn preserved<T: Id>(object: &Indexed<Object<T>>) -> &Indexed<Object<T>> {
+	match object.inner() {
+		Object::Node(node) => match node.extension(PRESERVE).and_then(|values| values.first()) {
+			Some(value) => value,
+			None => object
+		},
+		_ => object
+	}
+}
+
+/// Count the occurrences of each blank node identifier.
+fn count_blank_ids<'a, T: Id>(object: &'a Indexed<Object<T>>, counts: &mut HashMap<&'a str, usize>) {
+	match object.inner() {
+		Object::Value(_) => (),
+		Object::List(items) => {
+			for item in items {
+				count_blank_ids(item, counts)
+			}
+		},
+		Object::Node(node) => count_node_blank_ids(node, counts)
+	}
+}
+
+fn count_node_blank_ids<'a, T: Id>(node: &'a Node<T>, counts: &mut HashMap<&'a str, usize>) {
+	for id in node.id.iter().chain(&node.types) {
+		if let Lenient::Ok(Reference::Blank(id)) = id {
+			*counts.entry(id.as_str()).or_default() += 1
+		}
+	}
+
+	for object in node.graph().into_iter().flatten().chain(node.properties.values().flatten()).chain(node.extensions.values().flatten()) {
+		count_blank_ids(object, counts)
+	}
+
+	for node in node.included().into_iter().flatten().chain(node.reverse_properties.values().flatten()) {
+		count_node_blank_ids(node, counts)
+	}
+}
+
+/// Compact the given document using an already processed active context.
+///
+/// The result has no `@context` entry.
+/// See [`compact`] to compact a document with a local context.
+pub fn compact_with<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(document: &'a ExpandedDocument<T>, active_context: &'a C, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let inverse = InverseContext::new(active_context);
+		compact_with_inverse(document, active_context, &inverse, loader, options).await
+	}
+}
+
+/// Compact the given document using an already processed active context and its inverse
+/// context, such as the ones of a [`PrecompiledContext`](crate::context::PrecompiledContext).
+///
+/// The inverse context must be the one of the active context.
+pub fn compact_with_inverse<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(document: &'a ExpandedDocument<T>, active_context: &'a C, inverse: &'a InverseContext, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+	async move {
+		let mut pruned = HashSet::new();
+		if options.prune_blank_node_identifiers {
+			let mut counts = HashMap::new();
+			for object in document {
+				count_blank_ids(object, &mut counts)
+			}
+
+			pruned.extend(counts.into_iter().filter(|(_, count)| *count == 1).map(|(id, _)| id.to_string()))
+		}
+
+		let env = Env {
+			options,
+			pruned: &pruned
+		};
+
+		let compacted = compact_array(active_context, inverse, None, document.iter().collect(), loader, env).await?;
+
+		let graph_key = compact_iri(active_context, inverse, "@graph", None, true, false, options)?;
+		let result = match compacted {
+			JsonValue::Array(items) if items.is_empty() && options.omit_graph => JsonValue::new_object(),
+			JsonValue::Object(object) if options.omit_graph || object.get(&graph_key).is_some() => JsonValue::Object(object),
+			compacted => {
+				let items = match compacted {
+					JsonValue::Array(items) => items,
+					item => vec![item]
+				};
+
+				let mut result = json::object::Object::new();
+				result.insert(&graph_key, JsonValue::Array(items));
+				JsonValue::Object(result)
+			}
+		};
+
+		Ok(result)
+	}
+}
+
+/// Compact the given document with a local context.
+///
+/// The context may be given with its `@context` wrapper.
+/// It is processed against a new active context with the given base URL, used to make IRIs
+/// relative, and added to the result in a `@context` entry, unless it is empty.
+pub fn compact<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(document: &'a ExpandedDocument<T>, context: &'a JsonValue, base_url: Option<Iri<'a>>, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<JsonValue, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<C::LocalContext> {
+	async move {
+		let context = match context {
+			JsonValue::Object(object) if object.get("@context").is_some() => &object["@context"],
+			context => context
+		};
+
+		let local_context: C::LocalContext = context.clone().into();
+		let active_context: C = local_context.process_with(&C::new(base_url), ProcessingStack::new(), loader, base_url, options.into()).await?;
+		let mut result = compact_with(document, &active_context, loader, options).await?;
+
+		let is_empty = match context {
+			JsonValue::Null => true,
+			JsonValue::Object(object) => object.is_empty(),
+			JsonValue::Array(items) => items.is_empty(),
+			_ => false
+		};
+
+		if !is_empty {
+			if let JsonValue::Object(object) = &mut result {
+				let mut with_context = json::object::Object::new();
+				with_context.insert("@context", context.clone());
+				for (key, value) in object.iter() {
+					with_context.insert(key, value.clone())
+				}
+
+				*object = with_context
+			}
+		}
+
+		Ok(result)
+	}
+}
@@ -0,0 +1,126 @@
+use json::JsonValue;
+use crate::{
+	Error,
+	Id,
+	Indexed,
+	Object,
+	Value,
+	Node,
+	Context,
+	object::Literal,
+	syntax::{
+		Type,
+		ContainerType
+	}
+};
+use super::{
+	Options,
+	InverseContext,
+	compact_iri
+};
+
+/// JSON representation of a literal value.
+pub(crate) fn literal_as_json(literal: &Literal) -> JsonValue {
+	match literal {
+		Literal::Null => JsonValue::Null,
+		Literal::Boolean(b) => JsonValue::Boolean(*b),
+		Literal::Number(n) => JsonValue::Number(*n),
+		Literal::String(s) => JsonValue::String(s.clone()),
+		Literal::Json(json) => json.value().clone()
+	}
+}
+
+/// Compact a node reference (a node with no other entry than `@id`) into a string, if the type
+/// mapping of the active property allows it.
+pub fn compact_node_reference<T: Id, C: Context<T>>(active_context: &C, inverse: &InverseContext, active_property: Option<&str>, node: &Node<T>, options: Options) -> Result<Option<JsonValue>, Error> {
+	let id = match node.id() {
+		Some(id) => id.as_str(),
+		None => return Ok(None)
+	};
+
+	match active_context.get_opt(active_property).and_then(|definition| definition.typ.as_ref()) {
+		Some(Type::Id) => Ok(Some(compact_iri(active_context, inverse, id, None, false, false, options)?.into())),
+		Some(Type::Vocab) => Ok(Some(compact_iri(active_context, inverse, id, None, true, false, options)?.into())),
+		_ => Ok(None)
+	}
+}
+
+/// Compact a value object into a scalar, if the mappings of the active property allow it.
+///
+/// This is the [Value Compaction algorithm](https://www.w3.org/TR/json-ld11-api/#value-compaction),
+/// returning `None` when the value must be kept as a value object.
+/// When the active property has the `@json` type mapping, the value of JSON literals is returned
+/// even if it is not a scalar.
+pub fn compact_value<T: Id, C: Context<T>>(active_context: &C, active_property: Option<&str>, value: &Indexed<Value<T>>) -> Option<JsonValue> {
+	let definition = active_context.get_opt(active_property);
+
+	let language = match definition.and_then(|definition| definition.language.as_ref()) {
+		Some(language) => language.as_deref(),
+		None => active_context.default_language()
+	};
+
+	let direction = match definition.and_then(|definition| definition.direction) {
+		Some(direction) => direction,
+		None => active_context.default_base_direction()
+	};
+
+	let type_mapping = definition.and_then(|definition| definition.typ.as_ref());
+	// Property-valued index maps do not hold the `@index` of their values.
+	let has_index_container = definition.map(|definition| definition.container.contains(ContainerType::Index) && definition.index.is_none()).unwrap_or(false);
+	let index_allows = value.index().is_none() || has_index_container;
+
+	match value.inner() {
+		Value::Literal(literal, types) => {
+			let ty = match literal {
+				Literal::Json(_) => Some("@json"),
+				_ => types.iter().next().map(|ty| ty.as_iri().into_str())
+			};
+
+			match (ty, type_mapping) {
+				// The type of the value matches the type mapping.
+				(Some(ty), Some(mapping)) if mapping.as_str() == ty => Some(literal_as_json(literal)),
+				// Value compaction is disabled.
+				(_, Some(Type::None)) | (Some(_), _) => None,
+				_ => match literal {
+					Literal::String(_) => {
+						if language.is_none() && direction.is_none() && index_allows {
+							Some(literal_as_json(literal))
+						} else {
+							None
+						}
+					},
+					literal => if index_allows {
+						Some(literal_as_json(literal))
+					} else {
+						None
+					}
+				}
+			}
+		},
+		Value::LangString(str) => {
+			if let Some(Type::None) = type_mapping {
+				return None
+			}
+
+			let language_matches = match (str.language(), language) {
+				(Some(a), Some(b)) => a.to_lowercase() == b.to_lowercase(),
+				(None, None) => true,
+				_ => false
+			};
+
+			if language_matches && str.direction() == direction && index_allows {
+				Some(str.as_str().into())
+			} else {
+				None
+			}
+		}
+	}
+}
+
+/// Checks if the given object is a node reference.
+pub(crate) fn as_node_reference<T: Id>(object: &Indexed<Object<T>>) -> Option<&Node<T>> {
+	match object.inner() {
+		Object::Node(node) if node.has_only_id() => Some(node),
+		_ => None
+	}
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+use json::JsonValue;
+use crate::{
+	Error,
+	Id,
+	Indexed,
+	Object,
+	ExpandedDocument,
+	ContextMut,
+	context::Loader
+};
+use super::{
+	Options,
+	InverseContext,
+	Env,
+	compact_iri,
+	compact_object
+};
+
+/// Streamed compaction error.
+#[derive(Debug)]
+pub enum WriteError {
+	/// Unable to write the compacted document.
+	Io(io::Error),
+
+	/// Compaction error.
+	Compaction(Error)
+}
+
+impl fmt::Display for WriteError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			WriteError::Io(e) => write!(f, "I/O error: {}", e),
+			WriteError::Compaction(e) => write!(f, "compaction failed: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for WriteError {}
+
+impl From<io::Error> for WriteError {
+	fn from(e: io::Error) -> WriteError {
+		WriteError::Io(e)
+	}
+}
+
+impl From<Error> for WriteError {
+	fn from(e: Error) -> WriteError {
+		WriteError::Compaction(e)
+	}
+}
+
+/// Streamed compaction writer.
+///
+/// Writes a compacted document directly into an [`io::Write`], one top-level object at a time,
+/// instead of building the JSON tree of the whole document, for large exports.
+/// Since the number of top-level objects is not known in advance, they are always written in
+/// a top-level `@graph` entry, as with the [`omit_graph`](Options::omit_graph) option set to
+/// `false`. The [`prune_blank_node_identifiers`](Options::prune_blank_node_identifiers)
+/// option, which requires the whole document, is ignored.
+/// ```ignore
+/// let mut writer = compaction::Writer::new(file, &active_context, Some(&context), compaction::Options::default())?;
+/// for object in objects {
+///     writer.write(&object, &mut loader).await?
+/// }
+/// writer.finish()?;
+/// ```
+pub struct Writer<'c, W, C> {
+	writer: W,
+	active_context: &'c C,
+	inverse: InverseContext,
+	pruned: HashSet<String>,
+	options: Options,
+	count: usize
+}
+
+impl<'c, W: Write, C: Send + Sync> Writer<'c, W, C> {
+	/// Start writing a document compacted with the given active context.
+	///
+	/// The `context` is the value of the `@context` entry written at the beginning of the
+	/// document, if any.
+	pub fn new<T: Id>(mut writer: W, active_context: &'c C, context: Option<&JsonValue>, options: Options) -> Result<Writer<'c, W, C>, WriteError> where C: ContextMut<T> {
+		let inverse = InverseContext::new(active_context);
+		let graph_key = compact_iri(active_context, &inverse, "@graph", None, true, false, options)?;
+
+		writer.write_all(b"{")?;
+		if let Some(context) = context {
+			writer.write_all(b"\"@context\":")?;
+			writer.write_all(context.dump().as_bytes())?;
+			writer.write_all(b",")?;
+		}
+
+		writer.write_all(JsonValue::from(graph_key).dump().as_bytes())?;
+		writer.write_all(b":[")?;
+
+		Ok(Writer {
+			writer,
+			active_context,
+			inverse,
+			pruned: HashSet::new(),
+			options,
+			count: 0
+		})
+	}
+
+	/// Number of top-level objects written.
+	pub fn len(&self) -> usize {
+		self.count
+	}
+
+	/// Checks if no top-level object has been written.
+	pub fn is_empty(&self) -> bool {
+		self.count == 0
+	}
+
+	/// Compact and write a top-level object.
+	pub async fn write<T: Send + Sync + Id, L: Send + Sync + Loader>(&mut self, object: &Indexed<Object<T>>, loader: &mut L) -> Result<(), WriteError> where C: ContextMut<T>, C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+		let env = Env {
+			options: self.options,
+			pruned: &self.pruned
+		};
+
+		let compacted = compact_object(self.active_context, &self.inverse, None, object, loader, env).await?;
+		if self.count > 0 {
+			self.writer.write_all(b",")?;
+		}
+
+		self.writer.write_all(compacted.dump().as_bytes())?;
+		self.count += 1;
+		Ok(())
+	}
+
+	/// Compact and write every top-level object of the given document.
+	pub async fn write_all<T: Send + Sync + Id, L: Send + Sync + Loader>(&mut self, document: &ExpandedDocument<T>, loader: &mut L) -> Result<(), WriteError> where C: ContextMut<T>, C::LocalContext: Send + Sync + From<L::Output>, L::Output: Into<C::LocalContext> {
+		for object in document {
+			self.write(object, loader).await?
+		}
+
+		Ok(())
+	}
+
+	/// Terminate the document, and return the underlying writer.
+	pub fn finish(mut self) -> Result<W, WriteError> {
+		self.writer.write_all(b"]}")?;
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+}
@@ -0,0 +1,353 @@
+use std::io;
+use std::convert::TryFrom;
+use iref::{Iri, IriBuf};
+use crate::{
+	Id,
+	Direction,
+	Reference,
+	BlankId,
+	compaction::InverseContext,
+	syntax::{
+		Keyword,
+		Term,
+		Type,
+		Container,
+		ContainerType
+	},
+	util::{
+		Encoder,
+		Decoder,
+		invalid_data
+	}
+};
+use super::{
+	JsonContext,
+	TermDefinition
+};
+
+/// Header of serialized contexts, followed by the format version.
+const CONTEXT_HEADER: &[u8] = b"JLDC\x01";
+
+/// Header of serialized contexts with their inverse context, followed by the format version.
+const PRECOMPILED_HEADER: &[u8] = b"JLDP\x01";
+
+fn encode_term<T: Id>(encoder: &mut Encoder, term: &Term<T>) {
+	match term {
+		Term::Null => encoder.u8(0),
+		Term::Ref(Reference::Id(id)) => {
+			encoder.u8(1);
+			encoder.str(id.as_iri().as_str())
+		},
+		Term::Ref(Reference::Blank(id)) => {
+			encoder.u8(2);
+			encoder.str(id.as_str())
+		},
+		Term::Keyword(keyword) => {
+			encoder.u8(3);
+			encoder.str(keyword.into_str())
+		}
+	}
+}
+
+fn decode_id<T: Id>(decoder: &mut Decoder) -> io::Result<T> {
+	Ok(T::from_iri(Iri::new(decoder.str()?).map_err(|_| invalid_data())?))
+}
+
+fn decode_iri(value: &str) -> io::Result<IriBuf> {
+	IriBuf::new(value).map_err(|_| invalid_data())
+}
+
+fn decode_term<T: Id>(decoder: &mut Decoder) -> io::Result<Term<T>> {
+	match decoder.u8()? {
+		0 => Ok(Term::Null),
+		1 => Ok(Term::Ref(Reference::Id(decode_id(decoder)?))),
+		2 => Ok(Term::Ref(Reference::Blank(BlankId::try_from(decoder.str()?).map_err(|_| invalid_data())?))),
+		3 => Ok(Term::Keyword(Keyword::try_from(decoder.str()?).map_err(|_| invalid_data())?)),
+		_ => Err(invalid_data())
+	}
+}
+
+fn encode_direction(encoder: &mut Encoder, direction: Option<Direction>) {
+	match direction {
+		None => encoder.u8(0),
+		Some(Direction::Ltr) => encoder.u8(1),
+		Some(Direction::Rtl) => encoder.u8(2)
+	}
+}
+
+fn decode_direction(decoder: &mut Decoder) -> io::Result<Option<Direction>> {
+	match decoder.u8()? {
+		0 => Ok(None),
+		1 => Ok(Some(Direction::Ltr)),
+		2 => Ok(Some(Direction::Rtl)),
+		_ => Err(invalid_data())
+	}
+}
+
+fn encode_definition<T: Id>(encoder: &mut Encoder, definition: &TermDefinition<T, JsonContext<T>>) {
+	match &definition.value {
+		Some(value) => {
+			encoder.bool(true);
+			encode_term(encoder, value)
+		},
+		None => encoder.bool(false)
+	}
+
+	encoder.bool(definition.prefix);
+	encoder.bool(definition.protected);
+	encoder.bool(definition.reverse_property);
+	encoder.opt_str(definition.base_url.as_ref().map(|url| url.as_str()));
+	encoder.opt_str(definition.context.as_ref().map(|context| context.dump()).as_deref());
+
+	encoder.len(definition.container.len());
+	for container_type in definition.container.iter() {
+		encoder.str(container_type.as_str())
+	}
+
+	match definition.direction {
+		Some(direction) => {
+			encoder.bool(true);
+			encode_direction(encoder, direction)
+		},
+		None => encoder.bool(false)
+	}
+
+	encoder.opt_str(definition.index.as_deref());
+
+	match &definition.language {
+		Some(language) => {
+			encoder.bool(true);
+			encoder.opt_str(language.as_deref())
+		},
+		None => encoder.bool(false)
+	}
+
+	encoder.opt_str(definition.nest.as_deref());
+
+	match &definition.typ {
+		None => encoder.u8(0),
+		Some(Type::Id) => encoder.u8(1),
+		Some(Type::Json) => encoder.u8(2),
+		Some(Type::None) => encoder.u8(3),
+		Some(Type::Vocab) => encoder.u8(4),
+		Some(Type::Ref(id)) => {
+			encoder.u8(5);
+			encoder.str(id.as_iri().as_str())
+		}
+	}
+}
+
+fn decode_definition<T: Id>(decoder: &mut Decoder) -> io::Result<TermDefinition<T, JsonContext<T>>> {
+	let mut definition = TermDefinition::default();
+
+	if decoder.bool()? {
+		definition.value = Some(decode_term(decoder)?)
+	}
+
+	definition.prefix = decoder.bool()?;
+	definition.protected = decoder.bool()?;
+	definition.reverse_property = decoder.bool()?;
+	definition.base_url = decoder.opt_str()?.map(decode_iri).transpose()?;
+	definition.context = decoder.opt_str()?.map(|context| json::parse(context).map_err(|_| invalid_data())).transpose()?;
+
+	let mut container = Container::new();
+	for _ in 0..decoder.len()? {
+		let container_type = ContainerType::try_from(decoder.str()?).map_err(|_| invalid_data())?;
+		if !container.add(container_type) {
+			return Err(invalid_data())
+		}
+	}
+	definition.container = container;
+
+	if decoder.bool()? {
+		definition.direction = Some(decode_direction(decoder)?)
+	}
+
+	definition.index = decoder.opt_str()?.map(str::to_string);
+
+	if decoder.bool()? {
+		definition.language = Some(decoder.opt_str()?.map(str::to_string))
+	}
+
+	definition.nest = decoder.opt_str()?.map(str::to_string);
+
+	definition.typ = match decoder.u8()? {
+		0 => None,
+		1 => Some(Type::Id),
+		2 => Some(Type::Json),
+		3 => Some(Type::None),
+		4 => Some(Type::Vocab),
+		5 => Some(Type::Ref(decode_id(decoder)?)),
+		_ => return Err(invalid_data())
+	};
+
+	Ok(definition)
+}
+
+impl<T: Id> JsonContext<T> {
+	fn encode(&self, encoder: &mut Encoder) {
+		encoder.opt_str(self.original_base_url.as_ref().map(|url| url.as_str()));
+		encoder.opt_str(self.base_iri.as_ref().map(|iri| iri.as_str()));
+
+		match &self.vocabulary {
+			Some(vocabulary) => {
+				encoder.bool(true);
+				encode_term(encoder, vocabulary)
+			},
+			None => encoder.bool(false)
+		}
+
+		encoder.opt_str(self.default_language.as_deref());
+		encode_direction(encoder, self.default_base_direction);
+
+		// Definitions are sorted so that the same context is always encoded the same way.
+		let mut definitions: Vec<_> = self.definitions.iter().collect();
+		definitions.sort_by_key(|(term, _)| *term);
+		encoder.len(definitions.len());
+		for (term, definition) in definitions {
+			encoder.str(term);
+			encode_definition(encoder, definition)
+		}
+
+		match &self.previous_context {
+			Some(previous) => {
+				encoder.bool(true);
+				previous.encode(encoder)
+			},
+			None => encoder.bool(false)
+		}
+	}
+
+	fn decode(decoder: &mut Decoder) -> io::Result<JsonContext<T>> {
+		let original_base_url = decoder.opt_str()?.map(decode_iri).transpose()?;
+		let base_iri = decoder.opt_str()?.map(decode_iri).transpose()?;
+
+		let vocabulary = if decoder.bool()? {
+			Some(decode_term(decoder)?)
+		} else {
+			None
+		};
+
+		let default_language = decoder.opt_str()?.map(str::to_string);
+		let default_base_direction = decode_direction(decoder)?;
+
+		let mut context = JsonContext {
+			original_base_url,
+			base_iri,
+			vocabulary,
+			default_language,
+			default_base_direction,
+			previous_context: None,
+			definitions: Default::default()
+		};
+
+		for _ in 0..decoder.len()? {
+			let term = decoder.str()?.to_string();
+			let definition = decode_definition(decoder)?;
+			context.definitions.insert(term, definition);
+		}
+
+		if decoder.bool()? {
+			context.previous_context = Some(Box::new(Self::decode(decoder)?))
+		}
+
+		Ok(context)
+	}
+
+	/// Serialize this processed context into a compact binary form.
+	///
+	/// The result can be reloaded with [`JsonContext::from_bytes`], skipping context processing.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut encoder = Encoder::new(CONTEXT_HEADER);
+		self.encode(&mut encoder);
+		encoder.into_bytes()
+	}
+
+	/// Reload a processed context serialized with [`JsonContext::to_bytes`].
+	///
+	/// Returns an `InvalidData` error if the bytes are not a valid serialized context.
+	pub fn from_bytes(bytes: &[u8]) -> io::Result<JsonContext<T>> {
+		let mut decoder = Decoder::new(bytes, CONTEXT_HEADER)?;
+		let context = Self::decode(&mut decoder)?;
+		decoder.end()?;
+		Ok(context)
+	}
+}
+
+/// Processed context along with its inverse context, ready for expansion and compaction.
+///
+/// It can be serialized into a compact binary form, so that contexts can be processed
+/// ahead-of-time and shipped with an application, skipping parsing and processing at startup:
+/// ```ignore
+/// // At build time.
+/// let context: JsonContext = local_context.process(&JsonContext::new(None), &mut loader, None).await?;
+/// std::fs::write("schema.org.jldc", PrecompiledContext::new(context).to_bytes())?;
+///
+/// // At startup.
+/// let precompiled = PrecompiledContext::from_bytes(include_bytes!("schema.org.jldc"))?;
+/// ```
+#[derive(Clone)]
+pub struct PrecompiledContext<T: Id = IriBuf> {
+	context: JsonContext<T>,
+	inverse: InverseContext
+}
+
+impl<T: Id> PrecompiledContext<T> {
+	/// Build the inverse context of the given processed context.
+	pub fn new(context: JsonContext<T>) -> PrecompiledContext<T> {
+		let inverse = InverseContext::new(&context);
+		PrecompiledContext {
+			context,
+			inverse
+		}
+	}
+
+	/// Processed context.
+	pub fn context(&self) -> &JsonContext<T> {
+		&self.context
+	}
+
+	/// Inverse context, for [`compaction::compact_with_inverse`](crate::compaction::compact_with_inverse).
+	pub fn inverse(&self) -> &InverseContext {
+		&self.inverse
+	}
+
+	/// Returns the processed context, dropping the inverse context.
+	pub fn into_context(self) -> JsonContext<T> {
+		self.context
+	}
+
+	/// Serialize the processed context and its inverse context into a compact binary form.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut encoder = Encoder::new(PRECOMPILED_HEADER);
+		self.context.encode(&mut encoder);
+		self.inverse.encode(&mut encoder);
+		encoder.into_bytes()
+	}
+
+	/// Reload a context serialized with [`PrecompiledContext::to_bytes`].
+	///
+	/// Returns an `InvalidData` error if the bytes are not a valid serialized context.
+	pub fn from_bytes(bytes: &[u8]) -> io::Result<PrecompiledContext<T>> {
+		let mut decoder = Decoder::new(bytes, PRECOMPILED_HEADER)?;
+		let context = JsonContext::decode(&mut decoder)?;
+		let inverse = InverseContext::decode(&mut decoder)?;
+		decoder.end()?;
+		Ok(PrecompiledContext {
+			context,
+			inverse
+		})
+	}
+}
+
+impl<T: Id> From<JsonContext<T>> for PrecompiledContext<T> {
+	fn from(context: JsonContext<T>) -> PrecompiledContext<T> {
+		Self::new(context)
+	}
+}
+
+impl<T: Id> AsRef<JsonContext<T>> for PrecompiledContext<T> {
+	fn as_ref(&self) -> &JsonContext<T> {
+		&self.context
+	}
+}
@@ -22,6 +22,9 @@ pub struct TermDefinition<T: Id, C: Context<T>> {
 	// Protected flag.
 	pub protected: bool,
 
+	// Enforced flag, set on the definitions of application overrides.
+	pub enforced: bool,
+
 	// Reverse property flag.
 	pub reverse_property: bool,
 
@@ -56,6 +59,7 @@ impl<T: Id, C: Context<T>> Default for TermDefinition<T, C> {
 			value: None,
 			prefix: false,
 			protected: false,
+			enforced: false,
 			reverse_property: false,
 			base_url: None,
 			typ: None,
@@ -71,7 +75,7 @@ impl<T: Id, C: Context<T>> Default for TermDefinition<T, C> {
 
 impl<T: Id, C: Context<T>> PartialEq for TermDefinition<T, C> {
 	fn eq(&self, other: &TermDefinition<T, C>) -> bool {
-		// NOTE we ignore the `protected` flag.
+		// NOTE we ignore the `protected` and `enforced` flags.
 		self.prefix == other.prefix &&
 		self.reverse_property == other.reverse_property &&
 		self.language == other.language &&
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::future::Future;
+use futures::future::{BoxFuture, FutureExt};
+use iref::{Iri, IriBuf, IriRef};
+use json::{JsonValue, object::Object as JsonObject};
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	Document,
+	ExpandedDocument,
+	expansion
+};
+use super::{
+	JsonContext,
+	Local,
+	Loader,
+	ProcessingStack,
+	ProcessingOptions,
+	define,
+	resolve_iri
+};
+
+/// Context entries processed eagerly by a lazy context.
+const KEYWORD_ENTRIES: &[&str] = &["@base", "@direction", "@language", "@propagate", "@protected", "@version", "@vocab"];
+
+/// Active context whose term definitions are processed on first use.
+///
+/// Processing a very large context (such as schema.org) defines thousands of terms, while most
+/// documents only use a few of them.
+/// A lazy context only processes the keyword entries of the context (`@vocab`, `@language`, etc.)
+/// and keeps the term definitions as they are.
+/// Terms are then defined on demand, either explicitly with [`LazyContext::define`], or for all
+/// the terms a document may use with [`LazyContext::resolve`] or [`LazyContext::expand`]:
+/// ```ignore
+/// let mut context = LazyContext::new(&json::parse("\"https://schema.org/\"")?, &JsonContext::new(None), &mut loader, None, ProcessingOptions::default()).await?;
+/// let expanded = context.expand(&doc, None, &mut loader, expansion::Options::default()).await?;
+/// ```
+///
+/// Only a (possibly remote) context definition is processed lazily.
+/// Arrays of contexts and contexts with an `@import` entry are processed eagerly.
+/// Note that invalid term definitions are only detected when the term is defined.
+pub struct LazyContext<T: Id = IriBuf> {
+	/// Context with the terms defined so far.
+	context: JsonContext<T>,
+
+	/// Context definition with the term definitions to process.
+	pending: JsonObject,
+
+	/// Terms defined (or being defined), as in the Create Term Definition algorithm.
+	defined: HashMap<String, bool>,
+
+	remote_contexts: ProcessingStack,
+	base_url: Option<IriBuf>,
+	protected: bool,
+	options: ProcessingOptions
+}
+
+impl<T: Send + Sync + Id> LazyContext<T> {
+	/// Process the keyword entries of the given local context on top of the active context.
+	pub fn new<'a, L: Send + Sync + Loader>(local_context: &'a JsonValue, active_context: &'a JsonContext<T>, loader: &'a mut L, base_url: Option<Iri<'a>>, options: ProcessingOptions) -> impl 'a + Send + Future<Output = Result<LazyContext<T>, Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+		async move {
+			let mut remote_contexts = ProcessingStack::new();
+			let mut base_url: Option<IriBuf> = base_url.map(IriBuf::from);
+			let mut local_context = local_context.clone();
+			let mut options = options;
+
+			// Dereference remote contexts.
+			while let Some(url) = local_context.as_str() {
+				let url = match IriRef::new(url) {
+					Ok(iri_ref) => resolve_iri(iri_ref, base_url.as_ref().map(|url| url.as_iri())).ok_or_else(|| Error::from(ErrorCode::LoadingRemoteContextFailed))?,
+					Err(_) => return Err(ErrorCode::LoadingDocumentFailed.into())
+				};
+
+				if !remote_contexts.push(url.as_iri()) {
+					local_context = JsonValue::Array(Vec::new());
+					break
+				}
+
+				let remote_context = loader.load_context(url.as_iri()).await?.cast::<JsonValue>();
+				base_url = Some(remote_context.url().into());
+				local_context = remote_context.into_context();
+				options = ProcessingOptions {
+					processing_mode: options.processing_mode,
+					override_protected: false,
+					propagate: true,
+					enforce: options.enforce,
+					keyword_policy: options.keyword_policy
+				};
+			}
+
+			let base_iri = base_url.as_ref().map(|url| url.as_iri());
+
+			match local_context {
+				JsonValue::Object(object) if object.get("@import").is_none() => {
+					let mut keywords = JsonObject::new();
+					let mut pending = JsonObject::new();
+					for (key, value) in object.iter() {
+						if KEYWORD_ENTRIES.contains(&key) {
+							keywords.insert(key, value.clone())
+						} else {
+							pending.insert(key, value.clone())
+						}
+					}
+
+					let protected = matches!(keywords.get("@protected"), Some(JsonValue::Boolean(true)));
+					let context = JsonValue::Object(keywords).process_with(active_context, remote_contexts.clone(), loader, base_iri, options).await?;
+
+					Ok(LazyContext {
+						context,
+						pending,
+						defined: HashMap::new(),
+						remote_contexts,
+						base_url,
+						protected,
+						options
+					})
+				},
+				local_context => {
+					let context = local_context.process_with(active_context, remote_contexts.clone(), loader, base_iri, options).await?;
+
+					Ok(LazyContext {
+						context,
+						pending: JsonObject::new(),
+						defined: HashMap::new(),
+						remote_contexts,
+						base_url,
+						protected: false,
+						options
+					})
+				}
+			}
+		}
+	}
+
+	/// Context with the terms defined so far.
+	pub fn context(&self) -> &JsonContext<T> {
+		&self.context
+	}
+
+	/// Returns the context with the terms defined so far.
+	pub fn into_context(self) -> JsonContext<T> {
+		self.context
+	}
+
+	/// Checks if the given term has a definition waiting to be processed.
+	pub fn is_pending(&self, term: &str) -> bool {
+		self.pending.get(term).is_some() && self.defined.get(term) != Some(&true)
+	}
+
+	/// Number of term definitions waiting to be processed.
+	pub fn pending_len(&self) -> usize {
+		self.pending.iter().filter(|(term, _)| self.defined.get(*term) != Some(&true)).count()
+	}
+
+	/// Define the given term, along with the terms its definition depends on.
+	///
+	/// Returns `false` if the term has no pending definition.
+	pub fn define<'a, L: Send + Sync + Loader>(&'a mut self, term: &'a str, loader: &'a mut L) -> BoxFuture<'a, Result<bool, Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+		async move {
+			if !self.is_pending(term) {
+				return Ok(false)
+			}
+
+			let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+			define(&mut self.context, &self.pending, term, &mut self.defined, self.remote_contexts.clone(), loader, base_url, self.protected, self.options).await?;
+			Ok(true)
+		}.boxed()
+	}
+
+	/// Define every pending term, giving the same context as eager processing.
+	///
+	/// This is required before compacting with this context.
+	pub fn define_all<'a, L: Send + Sync + Loader>(&'a mut self, loader: &'a mut L) -> impl 'a + Send + Future<Output = Result<(), Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+		async move {
+			let terms: Vec<String> = self.pending.iter().map(|(term, _)| term.to_string()).collect();
+			for term in &terms {
+				self.define(term, loader).await?;
+			}
+
+			Ok(())
+		}
+	}
+
+	/// Define every pending term the given document may use.
+	///
+	/// Terms are searched in the keys and string values of the document (including embedded
+	/// contexts), and in the prefix of compact IRIs.
+	/// The definition of each defined term is searched in turn, to find the terms used by
+	/// scoped contexts.
+	pub fn resolve<'a, L: Send + Sync + Loader>(&'a mut self, document: &'a JsonValue, loader: &'a mut L) -> impl 'a + Send + Future<Output = Result<(), Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+		async move {
+			let mut candidates = Vec::new();
+			candidate_terms(document, &self.pending, &mut candidates);
+
+			while let Some(term) = candidates.pop() {
+				if self.define(&term, loader).await? {
+					if let Some(definition) = self.pending.get(&term) {
+						candidate_terms(definition, &self.pending, &mut candidates)
+					}
+				}
+			}
+
+			Ok(())
+		}
+	}
+
+	/// Define the terms used by the given document, then expand it.
+	pub fn expand<'a, L: Send + Sync + Loader>(&'a mut self, document: &'a JsonValue, base_url: Option<Iri<'a>>, loader: &'a mut L, options: expansion::Options) -> impl 'a + Send + Future<Output = Result<ExpandedDocument<T>, Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+		async move {
+			self.resolve(document, loader).await?;
+			document.expand_with(base_url, &self.context, loader, options).await
+		}
+	}
+}
+
+/// Collect the strings of the given JSON value that may be pending terms.
+fn candidate_terms(value: &JsonValue, pending: &JsonObject, candidates: &mut Vec<String>) {
+	fn add(str: &str, pending: &JsonObject, candidates: &mut Vec<String>) {
+		if pending.get(str).is_some() {
+			candidates.push(str.to_string())
+		}
+
+		if let Some(i) = str.find(':') {
+			if pending.get(&str[..i]).is_some() {
+				candidates.push(str[..i].to_string())
+			}
+		}
+	}
+
+	match value {
+		JsonValue::String(_) | JsonValue::Short(_) => add(value.as_str().unwrap(), pending, candidates),
+		JsonValue::Array(items) => {
+			for item in items {
+				candidate_terms(item, pending, candidates)
+			}
+		},
+		JsonValue::Object(object) => {
+			for (key, value) in object.iter() {
+				add(key, pending, candidates);
+				candidate_terms(value, pending, candidates)
+			}
+		},
+		_ => ()
+	}
+}
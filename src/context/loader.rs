@@ -1,6 +1,9 @@
 use futures::future::BoxFuture;
 use iref::{Iri, IriBuf};
-use crate::Error;
+use crate::{
+	Error,
+	usage::TermUse
+};
 
 pub struct RemoteContext<C> {
 	url: IriBuf,
@@ -46,4 +49,16 @@ pub trait Loader {
 	type Output;
 
 	fn load_context<'a>(&'a mut self, url: Iri) -> BoxFuture<'a, Result<RemoteContext<Self::Output>, Error>>;
+
+	/// Checks if processing has been cancelled.
+	///
+	/// This is checked between processing steps.
+	fn is_cancelled(&self) -> bool {
+		false
+	}
+
+	/// Called by expansion for each entry key and node object type it expands.
+	///
+	/// Does nothing by default. See the [`usage`](crate::usage) module.
+	fn term_used(&mut self, _term: &str, _usage: TermUse) {}
 }
@@ -3,6 +3,8 @@
 mod definition;
 mod loader;
 mod processing;
+mod binary;
+mod lazy;
 
 use std::collections::HashMap;
 use futures::future::BoxFuture;
@@ -10,6 +12,7 @@ use iref::{Iri, IriBuf};
 use json::JsonValue;
 use crate::{
 	ProcessingMode,
+	KeywordPolicy,
 	Error,
 	Direction,
 	Id,
@@ -20,6 +23,8 @@ use crate::{
 pub use definition::*;
 pub use loader::*;
 pub use processing::*;
+pub use binary::*;
+pub use lazy::*;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct ProcessingOptions {
@@ -30,7 +35,18 @@ pub struct ProcessingOptions {
 	pub override_protected: bool,
 
 	/// Propagate the processed context.
-	pub propagate: bool
+	pub propagate: bool,
+
+	/// Enforce the defined terms.
+	///
+	/// Enforced term definitions have the final say: the contexts processed afterward,
+	/// such as the contexts of a third-party document, cannot redefine them
+	/// (their definitions are silently ignored) nor clear them with `null`.
+	pub enforce: bool,
+
+	/// Policy applied to terms, and IRI mappings of term definitions, having the form of a
+	/// keyword.
+	pub keyword_policy: KeywordPolicy
 }
 
 impl ProcessingOptions {
@@ -54,6 +70,13 @@ impl ProcessingOptions {
 		opt.propagate = false;
 		opt
 	}
+
+	/// Return the same set of options, but with `enforce` set to `true`.
+	pub fn with_enforcement(&self) -> ProcessingOptions {
+		let mut opt = *self;
+		opt.enforce = true;
+		opt
+	}
 }
 
 impl Default for ProcessingOptions {
@@ -61,7 +84,9 @@ impl Default for ProcessingOptions {
 		ProcessingOptions {
 			processing_mode: ProcessingMode::default(),
 			override_protected: false,
-			propagate: true
+			propagate: true,
+			enforce: false,
+			keyword_policy: KeywordPolicy::default()
 		}
 	}
 }
@@ -129,7 +154,7 @@ pub trait ContextMut<T: Id = IriBuf>: Context<T> {
 ///
 /// Local contexts can be seen as "abstract contexts" that can be processed to enrich an
 /// existing active context.
-pub trait Local<T: Id = IriBuf>: Sized + PartialEq + util::AsJson {
+pub trait Local<T: Id = IriBuf>: Sized + PartialEq + Clone + util::AsJson {
 	/// Process the local context with specific options.
 	fn process_with<'a, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(&'a self, active_context: &'a C, stack: ProcessingStack, loader: &'a mut L, base_url: Option<Iri>, options: ProcessingOptions) -> BoxFuture<'a, Result<C, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<Self>, L::Output: Into<Self>, T: Send + Sync;
 
@@ -138,6 +163,14 @@ pub trait Local<T: Id = IriBuf>: Sized + PartialEq + util::AsJson {
 	fn process<'a, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(&'a self, active_context: &'a C, loader: &'a mut L, base_url: Option<Iri>) -> BoxFuture<'a, Result<C, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<Self>, L::Output: Into<Self>, T: Send + Sync {
 		self.process_with(active_context, ProcessingStack::new(), loader, base_url, ProcessingOptions::default())
 	}
+
+	/// Process the local context as application overrides.
+	///
+	/// The resulting term definitions are enforced (see [`ProcessingOptions::enforce`]):
+	/// the contexts of documents expanded with the returned context cannot change them.
+	fn enforce<'a, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(&'a self, active_context: &'a C, loader: &'a mut L, base_url: Option<Iri>) -> BoxFuture<'a, Result<C, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<Self>, L::Output: Into<Self>, T: Send + Sync {
+		self.process_with(active_context, ProcessingStack::new(), loader, base_url, ProcessingOptions::default().with_enforcement())
+	}
 }
 
 #[derive(Clone, PartialEq, Eq)]
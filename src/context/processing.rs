@@ -6,7 +6,7 @@ use std::sync::Arc;
 use futures::future::{BoxFuture, FutureExt};
 use json::{JsonValue, object::Object as JsonObject};
 use iref::{Iri, IriBuf, IriRef};
-use crate::util::as_array;
+use crate::util::{as_array, Timer};
 use crate::{
 	ProcessingMode,
 	Error,
@@ -52,7 +52,7 @@ pub fn has_protected_items<T: Id, C: Context<T>>(active_context: &C) -> bool {
 	false
 }
 
-fn resolve_iri(iri_ref: IriRef, base_iri: Option<Iri>) -> Option<IriBuf> {
+pub(crate) fn resolve_iri(iri_ref: IriRef, base_iri: Option<Iri>) -> Option<IriBuf> {
 	match base_iri {
 		Some(base_iri) => Some(iri_ref.resolved(base_iri)),
 		None => match iri_ref.into_iri() {
@@ -146,7 +146,7 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 		// its value MUST be boolean true or false, set `propagate` to that value.
 		if let JsonValue::Object(obj) = local_context {
 			if let Some(propagate_value) = obj.get(Keyword::Propagate.into()) {
-				if options.processing_mode == ProcessingMode::JsonLd1_0 {
+				if !Keyword::Propagate.is_valid_in(options.processing_mode) {
 					return Err(ErrorCode::InvalidContextEntry.into())
 				}
 
@@ -169,6 +169,10 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 
 		// 5) For each item context in local context:
 		for context in local_context {
+			if loader.is_cancelled() {
+				return Err(ErrorCode::Cancelled.into())
+			}
+
 			match context {
 				// 5.1) If context is null:
 				JsonValue::Null => {
@@ -188,6 +192,15 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 						// active context, ...
 						result = C::new(active_context.original_base_url());
 
+						// Enforced term definitions survive the nullification.
+						let enforced: Vec<_> = previous_result.definitions()
+							.filter(|(_, definition)| definition.enforced)
+							.map(|(term, definition)| (term.clone(), definition.clone()))
+							.collect();
+						for (term, definition) in enforced {
+							result.set(&term, Some(definition));
+						}
+
 						// ... and, if `propagate` is `false`, `previous_context` in `result` to the
 						// previous value of `result`.
 						if !options.propagate {
@@ -238,10 +251,14 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 						let new_options = ProcessingOptions {
 							processing_mode: options.processing_mode,
 							override_protected: false,
-							propagate: true
+							propagate: true,
+							enforce: options.enforce,
+							keyword_policy: options.keyword_policy
 						};
 
-						result = loaded_context.process_with(&result, remote_contexts.clone(), loader, Some(context_document.url()), new_options).await?;
+						let timer = Timer::start();
+						result = instrument!(loaded_context.process_with(&result, remote_contexts.clone(), loader, Some(context_document.url()), new_options), "process_context", url = %context_document.url()).await?;
+						debug!("processed remote context `{}' in {}", context_document.url(), timer);
 						// result = process_context(&result, loaded_context, remote_contexts, loader, Some(context_document.url()), new_options).await?
 					}
 				},
@@ -264,10 +281,11 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 					}
 
 					// 5.6) If context has an @import entry:
-					let context = if let Some(import_value) = context.get(Keyword::Import.into()) {
+					// Entries coming from the imported context, if any, along with its URL.
+					let (context, imported) = if let Some(import_value) = context.get(Keyword::Import.into()) {
 						// 5.6.1) If processing mode is json-ld-1.0, an invalid context entry error
 						// has been detected.
-						if options.processing_mode == ProcessingMode::JsonLd1_0 {
+						if !Keyword::Import.is_valid_in(options.processing_mode) {
 							return Err(ErrorCode::InvalidContextEntry.into())
 						}
 
@@ -282,6 +300,7 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 
 							// 5.6.4) Dereference import.
 							let context_document = loader.load_context(import.as_iri()).await?.cast::<JsonValue>();
+							let import_url = IriBuf::from(context_document.url());
 							let import_context = context_document.into_context();
 
 							// If the dereferenced document has no top-level map with an @context
@@ -300,13 +319,15 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 								// `import context`, replacing common entries with those from
 								// `context`.
 								let mut context = context.clone();
+								let mut imported_keys = Vec::new();
 								for (key, value) in import_context.iter() {
 									if context.get(key).is_none() {
 										context.insert(key, value.clone());
+										imported_keys.push(key.to_string());
 									}
 								}
 
-								JsonObjectRef::Owned(context)
+								(JsonObjectRef::Owned(context), Some((import_url, imported_keys)))
 							} else {
 								return Err(ErrorCode::InvalidRemoteContext.into())
 							}
@@ -316,7 +337,7 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 							return Err(ErrorCode::InvalidImportValue.into())
 						}
 					} else {
-						JsonObjectRef::Borrowed(context)
+						(JsonObjectRef::Borrowed(context), None)
 					};
 
 					// 5.7) If context has a @base entry and remote contexts is empty, i.e.,
@@ -397,7 +418,7 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 					if let Some(value) = context.get(Keyword::Direction.into()) {
 						// 5.10.1) If processing mode is json-ld-1.0, an invalid context entry error
 						// has been detected and processing is aborted.
-						if options.processing_mode == ProcessingMode::JsonLd1_0 {
+						if !Keyword::Direction.is_valid_in(options.processing_mode) {
 							return Err(ErrorCode::InvalidContextEntry.into())
 						}
 
@@ -437,6 +458,13 @@ fn process_context<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: S
 						match key {
 							"@base" | "@direction" | "@import" | "@language" | "@propagate" | "@protected" | "@version" | "@vocab" => (),
 							_ => {
+								// Imported term definitions keep the URL of the imported context as
+								// base URL, against which their scoped contexts are resolved.
+								let base_url = match &imported {
+									Some((import_url, keys)) if keys.iter().any(|k| k == key) => Some(import_url.as_iri()),
+									_ => base_url
+								};
+
 								define(&mut result, context.as_ref(), key, &mut defined, remote_contexts.clone(), loader, base_url, protected, options).await?
 							}
 						}
@@ -527,6 +555,12 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 					return Err(ErrorCode::InvalidTermDefinition.into())
 				}
 
+				// Enforced term definitions cannot be redefined, unless enforcing new ones.
+				if !options.enforce && local_context.get(term).is_some() && active_context.get(term).is_some_and(|definition| definition.enforced) {
+					defined.insert(term.to_string(), true);
+					return Ok(())
+				}
+
 				// Initialize `value` to a copy of the value associated with the entry `term` in
 				// `local_context`.
 				if let Some(value) = local_context.get(term) {
@@ -573,8 +607,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 							// If term has the form of a keyword (i.e., it matches the ABNF rule "@"1*ALPHA
 							// from [RFC5234]), return; processors SHOULD generate a warning.
 							if is_keyword_like(term) {
-
-								// TODO warning
+								options.keyword_policy.check(term)?;
 								return Ok(())
 							}
 						}
@@ -614,6 +647,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 					// `false`, `protected` to `protected`, and `reverse_property` to `false`.
 					let mut definition = TermDefinition::<T, C>::default();
 					definition.protected = protected;
+					definition.enforced = options.enforce;
 
 					// If the @protected entry in value is true set the protected flag in
 					// definition to true.
@@ -628,7 +662,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 
 						// If processing mode is json-ld-1.0, an invalid term definition has
 						// been detected and processing is aborted.
-						if options.processing_mode == ProcessingMode::JsonLd1_0 {
+						if !Keyword::Protected.is_valid_in(options.processing_mode) {
 							return Err(ErrorCode::InvalidTermDefinition.into())
 						}
 					}
@@ -678,7 +712,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 							// If the value associated with the @reverse entry is a string having
 							// the form of a keyword, return; processors SHOULD generate a warning.
 							if is_keyword_like(reverse_value) {
-								// TODO warning
+								options.keyword_policy.check(reverse_value)?;
 								return Ok(())
 							}
 
@@ -752,7 +786,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 								// keyword, but has the form of a keyword, return;
 								// processors SHOULD generate a warning.
 								if is_keyword_like(id_value) && !is_keyword(id_value) {
-									// TODO warning
+									options.keyword_policy.check(id_value)?;
 									return Ok(())
 								}
 
@@ -1059,7 +1093,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 					if let Some(nest_value) = value.get("@nest") {
 						// If processing mode is json-ld-1.0, an invalid term definition has been
 						// detected and processing is aborted.
-						if options.processing_mode == ProcessingMode::JsonLd1_0 {
+						if !Keyword::Nest.is_valid_in(options.processing_mode) {
 							return Err(ErrorCode::InvalidTermDefinition.into())
 						}
 
@@ -1084,7 +1118,7 @@ pub fn define<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send +
 						// If processing mode is json-ld-1.0, or if `term` contains a colon (:) or
 						// slash (/), an invalid term definition has been detected and processing
 						// is aborted.
-						if term.contains(':') || term.contains('/') || options.processing_mode == ProcessingMode::JsonLd1_0 {
+						if term.contains(':') || term.contains('/') || !Keyword::Prefix.is_valid_in(options.processing_mode) {
 							return Err(ErrorCode::InvalidTermDefinition.into())
 						}
 
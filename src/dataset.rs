@@ -0,0 +1,533 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::borrow::Borrow;
+use std::sync::OnceLock;
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	BlankId,
+	Reference,
+	ToReference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument,
+	rdf,
+	generator::{
+		self,
+		Generator
+	},
+	util::{self, AsJson}
+};
+
+/// Dataset.
+///
+/// A dataset is a set of expanded top-level objects in which each identified node object
+/// (including named graphs) appears only once.
+/// It is built from one or more expanded documents, merging together the top-level nodes sharing
+/// the same identifier.
+///
+/// This is useful to aggregate JSON-LD data coming from multiple sources:
+/// ```ignore
+/// use std::convert::TryFrom;
+/// use json_ld::Dataset;
+///
+/// let mut dataset = Dataset::try_from(expanded_doc_a)?;
+/// dataset.merge(Dataset::try_from(expanded_doc_b)?)?;
+/// ```
+pub struct Dataset<T: Id = IriBuf> {
+	/// Top-level nodes identified by an IRI or a blank node identifier.
+	///
+	/// Named graphs are the nodes having a `@graph` field.
+	nodes: HashMap<Reference<T>, Indexed<Node<T>>>,
+
+	/// Other top-level objects, such as unidentified nodes.
+	anonymous: HashSet<Indexed<Object<T>>>,
+
+	/// Reverse-lookup index of node references, built on first use.
+	referrers: OnceLock<GraphReferrers<T>>
+}
+
+/// For each node, the nodes referencing it and through which property.
+type Referrers<T> = HashMap<Reference<T>, HashSet<(Reference<T>, Reference<T>)>>;
+
+/// Referrers of each graph, the default graph being `None`.
+type GraphReferrers<T> = HashMap<Option<Reference<T>>, Referrers<T>>;
+
+impl<T: Id> Dataset<T> {
+	/// Create a new empty dataset.
+	pub fn new() -> Dataset<T> {
+		Dataset {
+			nodes: HashMap::new(),
+			anonymous: HashSet::new(),
+			referrers: OnceLock::new()
+		}
+	}
+
+	/// Number of top-level objects in the dataset.
+	pub fn len(&self) -> usize {
+		self.nodes.len() + self.anonymous.len()
+	}
+
+	/// Checks if the dataset is empty.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty() && self.anonymous.is_empty()
+	}
+
+	/// Get the top-level node with the given identifier, if any.
+	pub fn get<R: ToReference<T>>(&self, id: R) -> Option<&Indexed<Node<T>>> {
+		self.nodes.get(id.to_ref().borrow())
+	}
+
+	/// Get the named graph with the given name, if any.
+	pub fn graph<R: ToReference<T>>(&self, name: R) -> Option<&HashSet<Indexed<Object<T>>>> {
+		match self.get(name) {
+			Some(node) => node.graph(),
+			None => None
+		}
+	}
+
+	/// Iterate through the identified top-level nodes of the dataset.
+	///
+	/// Named graphs are included.
+	pub fn nodes(&self) -> impl Iterator<Item = &Indexed<Node<T>>> {
+		self.nodes.values()
+	}
+
+	/// Get the top-level objects that are not identified nodes.
+	pub fn anonymous(&self) -> &HashSet<Indexed<Object<T>>> {
+		&self.anonymous
+	}
+
+	/// Insert a top-level object in the dataset.
+	///
+	/// If the object is a node whose identifier is already present in the dataset, both nodes
+	/// are merged property-wise.
+	/// Fails with a [`ErrorCode::ConflictingIndexes`] error if both nodes have different indexes.
+	pub fn insert(&mut self, object: Indexed<Object<T>>) -> Result<(), Error> {
+		self.referrers.take();
+
+		let (object, index) = object.into_parts();
+		match object {
+			Object::Node(node) => {
+				let id = match &node.id {
+					Some(Lenient::Ok(id)) => Some(id.clone()),
+					_ => None
+				};
+
+				match id {
+					Some(id) => {
+						let node = Indexed::new(*node, index);
+						match self.nodes.get_mut(&id) {
+							Some(current) => merge_nodes(current, node)?,
+							None => {
+								self.nodes.insert(id, node);
+							}
+						}
+					},
+					None => {
+						self.anonymous.insert(Indexed::new(Object::Node(node), index));
+					}
+				}
+			},
+			object => {
+				self.anonymous.insert(Indexed::new(object, index));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Merge another dataset into this one.
+	///
+	/// Nodes with the same identifier are merged property-wise, and named graphs are unioned by
+	/// graph name.
+	/// Blank node identifiers are local to each dataset: the blank nodes of `other` whose
+	/// identifier is already used in `self` are renamed so that they are kept distinct.
+	pub fn merge(&mut self, other: Dataset<T>) -> Result<(), Error> {
+		self.merge_with(other, generator::Blank::new_with_prefix("m".to_string()))
+	}
+
+	/// Merge another dataset into this one using the given generator to rename the blank nodes
+	/// of `other` that collide with the blank nodes of `self`.
+	///
+	/// Generated identifiers already used by any of the two datasets are skipped.
+	pub fn merge_with<G: Generator>(&mut self, other: Dataset<T>, mut generator: G) -> Result<(), Error> {
+		let mut used = self.blank_ids();
+		let other_ids = other.blank_ids();
+
+		let mut relabeling = HashMap::new();
+		for id in &other_ids {
+			if used.contains(id) {
+				let fresh = loop {
+					let fresh = generator.next();
+					if !used.contains(&fresh) && !other_ids.contains(&fresh) {
+						break fresh
+					}
+				};
+
+				used.insert(fresh.clone());
+				relabeling.insert(id.clone(), fresh);
+			}
+		}
+
+		for object in other.into_expanded() {
+			let object = if relabeling.is_empty() {
+				object
+			} else {
+				relabel_object(object, &relabeling)
+			};
+
+			self.insert(object)?
+		}
+
+		Ok(())
+	}
+
+	/// Collect all the blank node identifiers used in the dataset.
+	pub fn blank_ids(&self) -> HashSet<BlankId> {
+		let mut ids = HashSet::new();
+
+		for (id, node) in &self.nodes {
+			collect_reference(id, &mut ids);
+			collect_node(node, &mut ids)
+		}
+
+		for object in &self.anonymous {
+			collect_object(object, &mut ids)
+		}
+
+		ids
+	}
+
+	/// Turn the dataset back into an expanded document.
+	///
+	/// Anonymous objects come first, followed by the identified nodes sorted by identifier.
+	pub fn into_expanded(self) -> ExpandedDocument<T> {
+		let mut nodes: Vec<_> = self.nodes.into_iter().collect();
+		nodes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+		let mut result: ExpandedDocument<T> = self.anonymous.into();
+		result.extend(nodes.into_iter().map(|(_, node)| node.cast::<Object<T>>()));
+		result
+	}
+
+	/// Iterate through the nodes of the given graph referencing the node `id`, with the property
+	/// through which it is referenced, as `(referring node, property)` pairs.
+	///
+	/// The default graph is selected with `None`, and named graphs with their identifier, as
+	/// for [`IndexedDataset::referencing`](crate::IndexedDataset::referencing).
+	/// References are collected as they would appear in the flattened document: embedded nodes,
+	/// lists, reverse properties and included nodes are taken into account, but unidentified
+	/// nodes are not reported.
+	/// The index is built on the first call, and after each modification of the dataset,
+	/// so that subsequent lookups do not scan the whole dataset.
+	pub fn referrers<'a, R: ToReference<T>>(&'a self, graph: Option<&Reference<T>>, id: R) -> impl 'a + Iterator<Item = (&'a Reference<T>, &'a Reference<T>)> {
+		let referrers = self.referrers.get_or_init(|| {
+			let mut referrers = HashMap::new();
+			for node in self.nodes.values() {
+				collect_node_referrers(None, node, &mut referrers)
+			}
+
+			for object in &self.anonymous {
+				collect_object_referrers(None, None, None, object, &mut referrers)
+			}
+
+			referrers
+		});
+
+		let graph = graph.cloned();
+		referrers.get(&graph).and_then(|referrers| referrers.get(id.to_ref().borrow())).into_iter().flatten().map(|(subject, prop)| (subject, prop))
+	}
+
+	/// Find the RDF quads of the dataset matching the given pattern.
+	///
+	/// See [`ExpandedDocument::match_quads`].
+	pub fn match_quads(&self, subject: Option<&Reference<T>>, predicate: Option<&Reference<T>>, object: Option<&rdf::Term<T>>, graph: Option<Option<&Reference<T>>>) -> Result<std::vec::IntoIter<rdf::Quad<T>>, Error> {
+		let mut document: ExpandedDocument<T> = self.anonymous.iter().cloned().collect::<Vec<_>>().into();
+		document.extend(self.nodes.values().map(|node| node.clone().cast::<Object<T>>()));
+		document.match_quads(subject, predicate, object, graph)
+	}
+
+	/// Draw the dataset as a [Graphviz](https://graphviz.org/) graph in the DOT language.
+	///
+	/// See [`rdf::to_dot`].
+	pub fn to_dot(&self, options: &rdf::DotOptions) -> Result<String, Error> {
+		let quads: Vec<_> = self.match_quads(None, None, None, None)?.collect();
+		Ok(rdf::to_dot(&quads, options))
+	}
+}
+
+impl<T: Id> Default for Dataset<T> {
+	fn default() -> Dataset<T> {
+		Self::new()
+	}
+}
+
+impl<T: Id> TryFrom<ExpandedDocument<T>> for Dataset<T> {
+	type Error = Error;
+
+	/// Build a dataset from an expanded document, merging top-level nodes with the same
+	/// identifier.
+	fn try_from(doc: ExpandedDocument<T>) -> Result<Dataset<T>, Error> {
+		let mut dataset = Dataset::new();
+		for object in doc {
+			dataset.insert(object)?
+		}
+
+		Ok(dataset)
+	}
+}
+
+impl<T: Id> AsJson for Dataset<T> {
+	fn as_json(&self) -> JsonValue {
+		let mut nodes: Vec<_> = self.nodes.iter().collect();
+		nodes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+		let mut ary = Vec::with_capacity(self.len());
+		for (_, node) in nodes {
+			ary.push(node.as_json())
+		}
+
+		let mut anonymous: Vec<_> = self.anonymous.iter().map(|object| object.as_json()).collect();
+		util::sort_json_items(&mut anonymous);
+		ary.extend(anonymous);
+
+		JsonValue::Array(ary)
+	}
+}
+
+/// Merge `other` into `node`, both nodes having the same identifier.
+fn merge_nodes<T: Id>(node: &mut Indexed<Node<T>>, other: Indexed<Node<T>>) -> Result<(), Error> {
+	let (other, other_index) = other.into_parts();
+
+	if let Some(other_index) = other_index {
+		match node.index() {
+			Some(index) if index != other_index => {
+				return Err(ErrorCode::ConflictingIndexes.into())
+			},
+			Some(_) => (),
+			None => node.set_index(Some(other_index))
+		}
+	}
+
+	for ty in other.types {
+		if !node.types.contains(&ty) {
+			node.types.push(ty)
+		}
+	}
+
+	if let Some(other_graph) = other.graph {
+		node.graph = Some(match node.graph.take() {
+			Some(graph) => Box::new(merge_graphs(*graph, *other_graph)?),
+			None => other_graph
+		})
+	}
+
+	if let Some(other_included) = other.included {
+		match &mut node.included {
+			Some(included) => included.extend(*other_included),
+			None => node.included = Some(other_included)
+		}
+	}
+
+	for (prop, values) in other.properties {
+		let node_values = node.properties.entry(prop).or_default();
+		for value in values {
+			if !node_values.contains(&value) {
+				node_values.push(value)
+			}
+		}
+	}
+
+	for (prop, values) in other.reverse_properties {
+		let node_values = node.reverse_properties.entry(prop).or_default();
+		for value in values {
+			if !node_values.contains(&value) {
+				node_values.push(value)
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Union of two graphs, merging the nodes sharing the same identifier.
+fn merge_graphs<T: Id>(graph: HashSet<Indexed<Object<T>>>, other: HashSet<Indexed<Object<T>>>) -> Result<HashSet<Indexed<Object<T>>>, Error> {
+	let mut dataset = Dataset::try_from(ExpandedDocument::from(graph))?;
+	for object in other {
+		dataset.insert(object)?
+	}
+
+	Ok(dataset.into_expanded().into_set())
+}
+
+fn collect_reference<T: Id>(r: &Reference<T>, ids: &mut HashSet<BlankId>) {
+	if let Reference::Blank(id) = r {
+		ids.insert(id.clone());
+	}
+}
+
+fn collect_lenient<T: Id>(r: &Lenient<Reference<T>>, ids: &mut HashSet<BlankId>) {
+	if let Lenient::Ok(r) = r {
+		collect_reference(r, ids)
+	}
+}
+
+fn collect_object<T: Id>(object: &Object<T>, ids: &mut HashSet<BlankId>) {
+	match object {
+		Object::Value(_) => (),
+		Object::Node(node) => collect_node(node, ids),
+		Object::List(items) => {
+			for item in items {
+				collect_object(item, ids)
+			}
+		}
+	}
+}
+
+fn collect_node<T: Id>(node: &Node<T>, ids: &mut HashSet<BlankId>) {
+	if let Some(id) = &node.id {
+		collect_lenient(id, ids)
+	}
+
+	for ty in &node.types {
+		collect_lenient(ty, ids)
+	}
+
+	if let Some(graph) = node.graph() {
+		for object in graph {
+			collect_object(object, ids)
+		}
+	}
+
+	if let Some(included) = node.included() {
+		for node in included {
+			collect_node(node, ids)
+		}
+	}
+
+	for (prop, values) in &node.properties {
+		collect_reference(prop, ids);
+		for value in values {
+			collect_object(value, ids)
+		}
+	}
+
+	for (prop, nodes) in &node.reverse_properties {
+		collect_reference(prop, ids);
+		for node in nodes {
+			collect_node(node, ids)
+		}
+	}
+}
+
+fn collect_object_referrers<T: Id>(graph: Option<&Reference<T>>, subject: Option<&Reference<T>>, prop: Option<&Reference<T>>, object: &Object<T>, referrers: &mut GraphReferrers<T>) {
+	match object {
+		Object::Value(_) => (),
+		Object::Node(node) => {
+			if let (Some(subject), Some(prop), Some(Lenient::Ok(id))) = (subject, prop, &node.id) {
+				referrers.entry(graph.cloned()).or_default().entry(id.clone()).or_default().insert((subject.clone(), prop.clone()));
+			}
+
+			collect_node_referrers(graph, node, referrers)
+		},
+		Object::List(items) => {
+			for item in items {
+				collect_object_referrers(graph, subject, prop, item, referrers)
+			}
+		}
+	}
+}
+
+fn collect_node_referrers<T: Id>(graph: Option<&Reference<T>>, node: &Node<T>, referrers: &mut GraphReferrers<T>) {
+	let subject = match &node.id {
+		Some(Lenient::Ok(id)) => Some(id),
+		_ => None
+	};
+
+	// The graph of an unidentified named graph has no name to be selected with.
+	if let (Some(objects), Some(name)) = (node.graph(), subject) {
+		for object in objects {
+			collect_object_referrers(Some(name), None, None, object, referrers)
+		}
+	}
+
+	if let Some(included) = node.included() {
+		for node in included {
+			collect_node_referrers(graph, node, referrers)
+		}
+	}
+
+	for (prop, values) in &node.properties {
+		for value in values {
+			collect_object_referrers(graph, subject, Some(prop), value, referrers)
+		}
+	}
+
+	// The nodes of a reverse property reference the node.
+	for (prop, nodes) in &node.reverse_properties {
+		for reverse in nodes {
+			if let (Some(subject), Some(Lenient::Ok(id))) = (subject, &reverse.id) {
+				referrers.entry(graph.cloned()).or_default().entry(subject.clone()).or_default().insert((id.clone(), prop.clone()));
+			}
+
+			collect_node_referrers(graph, reverse, referrers)
+		}
+	}
+}
+
+fn relabel_reference<T: Id>(r: Reference<T>, relabeling: &HashMap<BlankId, BlankId>) -> Reference<T> {
+	match r {
+		Reference::Blank(id) => match relabeling.get(&id) {
+			Some(fresh) => Reference::Blank(fresh.clone()),
+			None => Reference::Blank(id)
+		},
+		r => r
+	}
+}
+
+fn relabel_lenient<T: Id>(r: Lenient<Reference<T>>, relabeling: &HashMap<BlankId, BlankId>) -> Lenient<Reference<T>> {
+	match r {
+		Lenient::Ok(r) => Lenient::Ok(relabel_reference(r, relabeling)),
+		unknown => unknown
+	}
+}
+
+fn relabel_object<T: Id>(object: Indexed<Object<T>>, relabeling: &HashMap<BlankId, BlankId>) -> Indexed<Object<T>> {
+	let (object, index) = object.into_parts();
+	let object = match object {
+		Object::Value(value) => Object::Value(value),
+		Object::Node(node) => Object::Node(Box::new(relabel_node(*node, relabeling))),
+		Object::List(items) => Object::List(items.into_iter().map(|item| relabel_object(item, relabeling)).collect())
+	};
+
+	Indexed::new(object, index)
+}
+
+fn relabel_indexed_node<T: Id>(node: Indexed<Node<T>>, relabeling: &HashMap<BlankId, BlankId>) -> Indexed<Node<T>> {
+	let (node, index) = node.into_parts();
+	Indexed::new(relabel_node(node, relabeling), index)
+}
+
+fn relabel_node<T: Id>(node: Node<T>, relabeling: &HashMap<BlankId, BlankId>) -> Node<T> {
+	Node {
+		id: node.id.map(|id| relabel_lenient(id, relabeling)),
+		types: node.types.into_iter().map(|ty| relabel_lenient(ty, relabeling)).collect(),
+		graph: node.graph.map(|graph| Box::new(graph.into_iter().map(|object| relabel_object(object, relabeling)).collect())),
+		included: node.included.map(|included| Box::new(included.into_iter().map(|node| relabel_indexed_node(node, relabeling)).collect())),
+		properties: node.properties.into_iter().map(|(prop, values)| {
+			(relabel_reference(prop, relabeling), values.into_iter().map(|value| relabel_object(value, relabeling)).collect())
+		}).collect(),
+		reverse_properties: node.reverse_properties.into_iter().map(|(prop, nodes)| {
+			(relabel_reference(prop, relabeling), nodes.into_iter().map(|node| relabel_indexed_node(node, relabeling)).collect())
+		}).collect(),
+		extensions: node.extensions.into_iter().map(|(keyword, values)| {
+			(keyword, values.into_iter().map(|value| relabel_object(value, relabeling)).collect())
+		}).collect()
+	}
+}
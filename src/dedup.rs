@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+use crate::{
+	Id,
+	Lenient,
+	Reference,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument
+};
+
+/// Report of the deduplication of an expanded document.
+///
+/// Returned by [`ExpandedDocument::dedup`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Deduplication {
+	/// Number of duplicate objects and types removed.
+	pub duplicates: usize,
+
+	/// Number of node objects merged into a previous node object with the same identifier.
+	pub merged_nodes: usize
+}
+
+impl Deduplication {
+	/// Total number of objects removed.
+	pub fn removed(&self) -> usize {
+		self.duplicates + self.merged_nodes
+	}
+
+	/// Checks if nothing has been removed.
+	pub fn is_empty(&self) -> bool {
+		self.removed() == 0
+	}
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Removes duplicates from the document, recursively.
+	///
+	/// Property values, types, graphs and included blocks are sets in the JSON-LD data model.
+	/// At each level (top-level objects, and the values of each property), node objects with the
+	/// same identifier and index are merged into the first one, then duplicate objects are
+	/// removed, keeping the first occurrence of each.
+	/// The order and duplicates of list items are significant, and kept.
+	pub fn dedup(&mut self) -> Deduplication {
+		let mut report = Deduplication::default();
+		let objects = dedup_objects(self.drain().collect(), &mut report);
+		*self = objects.into();
+		report
+	}
+}
+
+fn dedup_objects<T: Id>(objects: Vec<Indexed<Object<T>>>, report: &mut Deduplication) -> Vec<Indexed<Object<T>>> {
+	let mut merged: Vec<Indexed<Object<T>>> = Vec::with_capacity(objects.len());
+	let mut positions: HashMap<(Reference<T>, Option<String>), usize> = HashMap::new();
+	for object in objects {
+		let key = match (object.inner(), object.index()) {
+			(Object::Node(node), index) => match &node.id {
+				Some(Lenient::Ok(id)) => Some((id.clone(), index.map(String::from))),
+				_ => None
+			},
+			_ => None
+		};
+
+		if let Some(key) = key {
+			if let Some(i) = positions.get(&key) {
+				if let (Object::Node(target), Object::Node(node)) = (merged[*i].as_mut(), object.into_inner()) {
+					merge_node(target, *node, report);
+					report.merged_nodes += 1
+				}
+
+				continue
+			}
+
+			positions.insert(key, merged.len());
+		}
+
+		merged.push(object)
+	}
+
+	let mut seen = HashSet::new();
+	let mut result = Vec::with_capacity(merged.len());
+	for mut object in merged {
+		dedup_object(&mut object, report);
+		if seen.contains(&object) {
+			report.duplicates += 1
+		} else {
+			seen.insert(object.clone());
+			result.push(object)
+		}
+	}
+
+	result
+}
+
+fn dedup_nodes<T: Id, I: IntoIterator<Item = Indexed<Node<T>>>>(nodes: I, report: &mut Deduplication) -> impl Iterator<Item = Indexed<Node<T>>> {
+	let objects = nodes.into_iter().map(|node| node.cast::<Object<T>>()).collect();
+	dedup_objects(objects, report).into_iter().filter_map(|object| object.try_cast::<Node<T>>().ok())
+}
+
+fn dedup_object<T: Id>(object: &mut Object<T>, report: &mut Deduplication) {
+	match object {
+		Object::Value(_) => (),
+		Object::Node(node) => dedup_node(node, report),
+		Object::List(items) => {
+			for item in items {
+				dedup_object(item, report)
+			}
+		}
+	}
+}
+
+fn dedup_node<T: Id>(node: &mut Node<T>, report: &mut Deduplication) {
+	let mut types = Vec::with_capacity(node.types.len());
+	for ty in node.types.drain(..) {
+		if types.contains(&ty) {
+			report.duplicates += 1
+		} else {
+			types.push(ty)
+		}
+	}
+	node.types = types;
+
+	if let Some(graph) = node.graph.take() {
+		let objects = dedup_objects(graph.into_iter().collect(), report);
+		node.graph = Some(Box::new(objects.into_iter().collect()))
+	}
+
+	if let Some(included) = node.included.take() {
+		node.included = Some(Box::new(dedup_nodes(*included, report).collect()))
+	}
+
+	for values in node.properties.values_mut() {
+		*values = dedup_objects(values.drain(..).collect(), report).into_iter().collect()
+	}
+
+	for values in node.reverse_properties.values_mut() {
+		*values = dedup_nodes(values.drain(..), report).collect()
+	}
+}
+
+/// Append the content of `other` to `node`.
+///
+/// The duplicates are removed afterward, by [`dedup_node`].
+fn merge_node<T: Id>(node: &mut Node<T>, other: Node<T>, report: &mut Deduplication) {
+	node.types.extend(other.types);
+
+	if let Some(other_graph) = other.graph {
+		match &mut node.graph {
+			Some(graph) => {
+				for object in *other_graph {
+					if !graph.insert(object) {
+						report.duplicates += 1
+					}
+				}
+			},
+			None => node.graph = Some(other_graph)
+		}
+	}
+
+	if let Some(other_included) = other.included {
+		match &mut node.included {
+			Some(included) => {
+				for object in *other_included {
+					if !included.insert(object) {
+						report.duplicates += 1
+					}
+				}
+			},
+			None => node.included = Some(other_included)
+		}
+	}
+
+	for (prop, values) in other.properties {
+		node.properties.entry(prop).or_default().extend(values)
+	}
+
+	for (prop, values) in other.reverse_properties {
+		node.reverse_properties.entry(prop).or_default().extend(values)
+	}
+
+	for (keyword, values) in other.extensions {
+		node.extensions.entry(keyword).or_default().extend(values)
+	}
+}
@@ -1,5 +1,6 @@
 use std::convert::TryFrom;
 use std::fmt;
+use iref::Iri;
 use json::JsonValue;
 use crate::util::AsJson;
 
@@ -16,6 +17,31 @@ pub enum Direction {
 	Rtl
 }
 
+/// Namespace of the RDF datatypes encoding the language and direction of strings.
+pub(crate) const I18N: &str = "https://www.w3.org/ns/i18n#";
+
+impl Direction {
+	/// Extract the direction from a `https://www.w3.org/ns/i18n#<language>_<direction>` RDF
+	/// datatype.
+	///
+	/// Returns `None` if the IRI is not such a datatype.
+	pub fn from_i18n_datatype(iri: Iri) -> Option<Direction> {
+		parse_i18n_datatype(iri.as_str()).map(|(_, direction)| direction)
+	}
+}
+
+/// Split an i18n datatype into its (possibly empty) language tag and direction.
+pub(crate) fn parse_i18n_datatype(iri: &str) -> Option<(&str, Direction)> {
+	if !iri.starts_with(I18N) {
+		return None
+	}
+
+	let suffix = &iri[I18N.len()..];
+	let underscore = suffix.rfind('_')?;
+	let direction = Direction::try_from(&suffix[(underscore + 1)..]).ok()?;
+	Some((&suffix[..underscore], direction))
+}
+
 impl<'a> TryFrom<&'a str> for Direction {
 	type Error = &'a str;
 
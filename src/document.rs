@@ -19,13 +19,171 @@ use crate::{
 		self,
 		Loader
 	},
-	expansion
+	expansion,
+	util::AsJson
 };
 
 /// Result of the document expansion algorithm.
 ///
-/// It is just an alias for a set of (indexed) objects.
-pub type ExpandedDocument<T> = HashSet<Indexed<Object<T>>>;
+/// Sequence of (indexed) objects, in the order in which they appear in the expanded document.
+/// Duplicate objects are preserved.
+/// Note that graph objects and included blocks are sets in the JSON-LD data model:
+/// when the document is a single unnamed graph (a top-level `@graph` entry),
+/// its objects are sorted by their JSON representation.
+/// Equality is order-sensitive.
+#[derive(PartialEq, Eq, Clone)]
+pub struct ExpandedDocument<T: Id = IriBuf> {
+	objects: Vec<Indexed<Object<T>>>
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Create a new empty document.
+	pub fn new() -> ExpandedDocument<T> {
+		ExpandedDocument {
+			objects: Vec::new()
+		}
+	}
+
+	/// Number of top-level objects.
+	pub fn len(&self) -> usize {
+		self.objects.len()
+	}
+
+	/// Checks if the document has no top-level object.
+	pub fn is_empty(&self) -> bool {
+		self.objects.is_empty()
+	}
+
+	/// Top-level objects, in order.
+	pub fn objects(&self) -> &[Indexed<Object<T>>] {
+		&self.objects
+	}
+
+	/// Iterate through the top-level objects, in order.
+	pub fn iter(&self) -> std::slice::Iter<'_, Indexed<Object<T>>> {
+		self.objects.iter()
+	}
+
+	/// Iterate through the top-level objects, in order, mutably.
+	pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Indexed<Object<T>>> {
+		self.objects.iter_mut()
+	}
+
+	/// Checks if the document contains the given top-level object.
+	pub fn contains(&self, object: &Indexed<Object<T>>) -> bool {
+		self.objects.contains(object)
+	}
+
+	/// Append a top-level object.
+	pub fn push(&mut self, object: Indexed<Object<T>>) {
+		self.objects.push(object)
+	}
+
+	/// Append a top-level object, unless an equal object is already in the document.
+	///
+	/// Returns `true` if the object has been inserted.
+	pub fn insert(&mut self, object: Indexed<Object<T>>) -> bool {
+		if self.contains(&object) {
+			false
+		} else {
+			self.push(object);
+			true
+		}
+	}
+
+	/// Retain only the top-level objects satisfying the given predicate, keeping their order.
+	pub fn retain<F: FnMut(&Indexed<Object<T>>) -> bool>(&mut self, f: F) {
+		self.objects.retain(f)
+	}
+
+	/// Removes every top-level object, and returns them in order.
+	pub fn drain(&mut self) -> std::vec::Drain<'_, Indexed<Object<T>>> {
+		self.objects.drain(..)
+	}
+
+	/// Returns the top-level objects as a vector, in order.
+	pub fn into_vec(self) -> Vec<Indexed<Object<T>>> {
+		self.objects
+	}
+
+	/// Returns the set of top-level objects, removing duplicates.
+	pub fn into_set(self) -> HashSet<Indexed<Object<T>>> {
+		self.objects.into_iter().collect()
+	}
+}
+
+impl<T: Id> Default for ExpandedDocument<T> {
+	fn default() -> ExpandedDocument<T> {
+		Self::new()
+	}
+}
+
+impl<T: Id> From<Vec<Indexed<Object<T>>>> for ExpandedDocument<T> {
+	fn from(objects: Vec<Indexed<Object<T>>>) -> ExpandedDocument<T> {
+		ExpandedDocument {
+			objects
+		}
+	}
+}
+
+impl<T: Id> From<HashSet<Indexed<Object<T>>>> for ExpandedDocument<T> {
+	/// Build a document from a set of objects, sorted by their JSON representation so that the
+	/// result does not depend on the set iteration order.
+	fn from(objects: HashSet<Indexed<Object<T>>>) -> ExpandedDocument<T> {
+		let mut objects: Vec<_> = objects.into_iter().collect();
+		objects.sort_by_cached_key(|object| object.as_json().dump());
+		ExpandedDocument {
+			objects
+		}
+	}
+}
+
+impl<T: Id> std::iter::FromIterator<Indexed<Object<T>>> for ExpandedDocument<T> {
+	fn from_iter<I: IntoIterator<Item = Indexed<Object<T>>>>(iter: I) -> ExpandedDocument<T> {
+		ExpandedDocument {
+			objects: iter.into_iter().collect()
+		}
+	}
+}
+
+impl<T: Id> Extend<Indexed<Object<T>>> for ExpandedDocument<T> {
+	fn extend<I: IntoIterator<Item = Indexed<Object<T>>>>(&mut self, iter: I) {
+		self.objects.extend(iter)
+	}
+}
+
+impl<T: Id> IntoIterator for ExpandedDocument<T> {
+	type Item = Indexed<Object<T>>;
+	type IntoIter = std::vec::IntoIter<Indexed<Object<T>>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.objects.into_iter()
+	}
+}
+
+impl<'a, T: Id> IntoIterator for &'a ExpandedDocument<T> {
+	type Item = &'a Indexed<Object<T>>;
+	type IntoIter = std::slice::Iter<'a, Indexed<Object<T>>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.objects.iter()
+	}
+}
+
+impl<'a, T: Id> IntoIterator for &'a mut ExpandedDocument<T> {
+	type Item = &'a mut Indexed<Object<T>>;
+	type IntoIter = std::slice::IterMut<'a, Indexed<Object<T>>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.objects.iter_mut()
+	}
+}
+
+impl<T: Id> AsJson for ExpandedDocument<T> {
+	fn as_json(&self) -> JsonValue {
+		self.objects.as_json()
+	}
+}
 
 /// JSON-LD document.
 ///
@@ -145,6 +303,9 @@ pub struct RemoteDocument<D = JsonValue> {
 
 	/// The document contents.
 	doc: D,
+
+	/// The raw content of the document, as served, if known.
+	content: Option<String>
 }
 
 impl<D> RemoteDocument<D> {
@@ -152,10 +313,29 @@ impl<D> RemoteDocument<D> {
 	pub fn new(doc: D, base_url: Iri) -> RemoteDocument<D> {
 		RemoteDocument {
 			base_url: base_url.into(),
-			doc: doc
+			doc: doc,
+			content: None
+		}
+	}
+
+	/// Set the raw content of the document, from which it has been parsed.
+	pub fn with_content(self, content: String) -> RemoteDocument<D> {
+		RemoteDocument {
+			content: Some(content),
+			..self
 		}
 	}
 
+	/// The raw content of the document, as served, if known.
+	pub fn content(&self) -> Option<&str> {
+		self.content.as_deref()
+	}
+
+	/// The URL of the document.
+	pub fn url(&self) -> Iri<'_> {
+		self.base_url.as_iri()
+	}
+
 	/// Consume the remote document and return the inner document.
 	pub fn into_document(self) -> D {
 		self.doc
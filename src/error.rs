@@ -12,7 +12,10 @@ pub struct Error {
 	code: ErrorCode,
 
 	/// The lower-level source of this error, if any.
-	source: Option<Box<dyn std::error::Error + 'static>>
+	source: Option<Box<dyn std::error::Error + 'static>>,
+
+	/// Keys of the source document entries in which the error occurred, outermost first.
+	entry_path: Vec<String>
 }
 
 impl Error {
@@ -20,7 +23,8 @@ impl Error {
 	pub fn new<S: std::error::Error + 'static>(code: ErrorCode, source: S) -> Error {
 		Error {
 			code,
-			source: Some(Box::new(source))
+			source: Some(Box::new(source)),
+			entry_path: Vec::new()
 		}
 	}
 
@@ -28,6 +32,21 @@ impl Error {
 	pub fn code(&self) -> ErrorCode {
 		self.code
 	}
+
+	/// Keys of the source document entries in which the error occurred, outermost first.
+	///
+	/// These are the keys as written in the input document, before expansion,
+	/// so a term `foo` is reported as `foo` rather than the IRI it expands to.
+	/// The path is empty if the error is not related to a particular entry.
+	pub fn entry_path(&self) -> &[String] {
+		&self.entry_path
+	}
+
+	/// Attach the error to the entry with the given key, enclosing the current entry path.
+	pub(crate) fn in_entry(mut self, key: &str) -> Error {
+		self.entry_path.insert(0, key.to_string());
+		self
+	}
 }
 
 impl std::error::Error for Error {
@@ -41,7 +60,17 @@ impl std::error::Error for Error {
 
 impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{}", self.code.as_str())
+		write!(f, "{}", self.code.as_str())?;
+
+		if let Some((first, rest)) = self.entry_path.split_first() {
+			write!(f, " (in entry `{}`", first)?;
+			for key in rest {
+				write!(f, " > `{}`", key)?
+			}
+			write!(f, ")")?
+		}
+
+		Ok(())
 	}
 }
 
@@ -49,7 +78,8 @@ impl From<ErrorCode> for Error {
 	fn from(code: ErrorCode) -> Error {
 		Error {
 			code,
-			source: None
+			source: None,
+			entry_path: Vec::new()
 		}
 	}
 }
@@ -215,7 +245,42 @@ pub enum ErrorCode {
 	ProcessingModeConflict,
 
 	/// An attempt was made to redefine a protected term.
-	ProtectedTermRedefinition
+	ProtectedTermRedefinition,
+
+	/// Processing has been cancelled through a [`cancel::Token`](crate::cancel::Token).
+	///
+	/// This is not a JSON-LD error code.
+	Cancelled,
+
+	/// A compact IRI has been found while they are disabled with
+	/// [`expansion::Options::compact_iris`](crate::expansion::Options::compact_iris).
+	///
+	/// This is not a JSON-LD error code.
+	CompactIri,
+
+	/// A relative IRI reference could not be resolved because there is no base IRI, or a node
+	/// identifier is not an absolute IRI or blank node identifier, while they are disabled with [`expansion::Options::relative_iris`](crate::expansion::Options::relative_iris).
+	///
+	/// This is not a JSON-LD error code.
+	RelativeIri,
+
+	/// An entry would have been dropped during expansion, while dropped entries are disabled with
+	/// [`expansion::Options::dropped_entries`](crate::expansion::Options::dropped_entries).
+	///
+	/// This is not a JSON-LD error code.
+	DroppedEntry,
+
+	/// An unknown keyword has been found, while they are rejected with
+	/// [`KeywordPolicy::Error`](crate::KeywordPolicy::Error).
+	///
+	/// This is not a JSON-LD error code.
+	UnknownKeyword,
+
+	/// A top-level item index is out of bounds in an
+	/// [`expansion::Incremental`](crate::expansion::Incremental) document.
+	///
+	/// This is not a JSON-LD error code.
+	ItemIndexOutOfBounds
 }
 
 impl ErrorCode {
@@ -272,7 +337,13 @@ impl ErrorCode {
 			LoadingRemoteContextFailed => "loading remote context failed",
 			MultipleContextLinkHeaders => "multiple context link headers",
 			ProcessingModeConflict => "processing mode conflict",
-			ProtectedTermRedefinition => "protected term redefinition"
+			ProtectedTermRedefinition => "protected term redefinition",
+			Cancelled => "cancelled",
+			CompactIri => "compact IRI",
+			RelativeIri => "relative IRI",
+			DroppedEntry => "dropped entry",
+			UnknownKeyword => "unknown keyword",
+			ItemIndexOutOfBounds => "item index out of bounds"
 		}
 	}
 }
@@ -332,6 +403,12 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 			"multiple context link headers" => Ok(MultipleContextLinkHeaders),
 			"processing mode conflict" => Ok(ProcessingModeConflict),
 			"protected term redefinition" => Ok(ProtectedTermRedefinition),
+			"cancelled" => Ok(Cancelled),
+			"compact IRI" => Ok(CompactIri),
+			"relative IRI" => Ok(RelativeIri),
+			"dropped entry" => Ok(DroppedEntry),
+			"unknown keyword" => Ok(UnknownKeyword),
+			"item index out of bounds" => Ok(ItemIndexOutOfBounds),
 			_ => Err(())
 		}
 	}
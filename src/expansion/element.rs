@@ -6,7 +6,9 @@ use crate::{
 	Error,
 	ErrorCode,
 	Id,
+	Indexed,
 	Lenient,
+	ProcessingMode,
 	object::*,
 	context::{
 		ContextMut,
@@ -17,8 +19,10 @@ use crate::{
 	},
 	syntax::{
 		Keyword,
-		Term
-	}
+		Term,
+		Type
+	},
+	usage
 };
 use crate::util::as_array;
 use super::{
@@ -29,13 +33,21 @@ use super::{
 	expand_array,
 	expand_value,
 	expand_node,
-	expand_iri
+	expand_iri,
+	expand_keyword,
+	check_compact_iri,
+	check_relative_iri,
+	check_node_id
 };
 
 /// https://www.w3.org/TR/json-ld11-api/#expansion-algorithm
 /// The default specified value for `ordered` and `from_map` is `false`.
 pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, active_property: Option<&'a str>, element: &'a JsonValue, base_url: Option<Iri<'a>>, loader: &'a mut L, options: Options) -> BoxFuture<'a, Result<Expanded<T>, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
-	async move {
+	instrument_trace!(async move {
+		if loader.is_cancelled() {
+			return Err(ErrorCode::Cancelled.into())
+		}
+
 		// If `element` is null, return null.
 		if element.is_null() {
 			return Ok(Expanded::Null)
@@ -68,6 +80,8 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 			},
 
 			JsonValue::Object(element) => {
+				trace!("expanding map element with {} entries (active property {:?})", element.len(), active_property);
+
 				// We will need to consider expanded keys, and maybe ordered keys.
 				let mut entries: Vec<Entry<&'a str>> = Vec::with_capacity(element.len());
 				for (key, value) in element.iter() {
@@ -81,15 +95,19 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 				let mut value_entry: Option<&JsonValue> = None;
 				let mut id_entry = None;
 
-				for Entry(key, value) in entries.iter() {
-					match expand_iri(active_context, key, false, true) {
-						Lenient::Ok(Term::Keyword(Keyword::Value)) => {
-							value_entry = Some(value)
-						},
-						Lenient::Ok(Term::Keyword(Keyword::Id)) => {
-							id_entry = Some(value)
-						},
-						_ => ()
+				// Only needed to revert to the previous context, which never exists in
+				// context-free mode.
+				if !options.context_free {
+					for Entry(key, value) in entries.iter() {
+						match expand_keyword(active_context, key) {
+							Some(Keyword::Value) => {
+								value_entry = Some(value)
+							},
+							Some(Keyword::Id) => {
+								id_entry = Some(value)
+							},
+							_ => ()
+						}
 					}
 				}
 
@@ -122,17 +140,14 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 				// of the Context Processing algorithm, passing `active_context`, the value of the
 				// `@context` entry as `local_context` and `base_url`.
 				if let Some(local_context) = element.get("@context") {
+					trace!("processing embedded context");
 					active_context = Mown::Owned(local_context.process_with(active_context.as_ref(), ProcessingStack::new(), loader, base_url, options.into()).await?);
 				}
 
 				let mut type_entries = Vec::new();
 				for Entry(key, value) in entries.iter() {
-					let expanded_key = expand_iri(active_context.as_ref(), key, false, true);
-					match &expanded_key {
-						Lenient::Ok(Term::Keyword(Keyword::Type)) => {
-							type_entries.push(Entry(key, value));
-						},
-						_ => ()
+					if expand_keyword(active_context.as_ref(), key) == Some(Keyword::Type) {
+						type_entries.push(Entry(key, value));
 					}
 				}
 
@@ -144,9 +159,20 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 				let type_scoped_context = active_context.as_ref();
 				let mut active_context = Mown::Borrowed(active_context.as_ref());
 
+				if !options.compact_iris || !options.relative_iris {
+					for Entry(_, value) in type_entries.iter() {
+						for ty in as_array(value) {
+							if let Some(ty) = ty.as_str() {
+								check_compact_iri(type_scoped_context, ty, true, options)?;
+								check_relative_iri(type_scoped_context, ty, true, options)?
+							}
+						}
+					}
+				}
+
 				// For each `key` and `value` in `element` ordered lexicographically by key where
-				// key IRI expands to @type:
-				for Entry(_, value) in &type_entries {
+				// key IRI expands to @type (there are no type-scoped contexts in context-free mode):
+				for Entry(_, value) in type_entries.iter().filter(|_| !options.context_free) {
 					// Convert `value` into an array, if necessary.
 					let value = as_array(value);
 
@@ -195,12 +221,34 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 				};
 
 				let mut expanded_entries = Vec::with_capacity(element.len());
+				let mut expanded_keywords = Vec::new();
 				let mut list_entry = None;
 				let mut set_entry = None;
 				value_entry = None;
 				for Entry(key, value) in entries.iter() {
+					check_compact_iri(active_context.as_ref(), key, true, options)?;
+					if options.unknown_keywords.is_none() {
+						options.keyword_policy.check(key).map_err(|e| e.in_entry(key))?
+					}
+
+					usage::report(loader, active_context.as_ref(), key, false);
 					match expand_iri(active_context.as_ref(), key, false, true) {
 						Lenient::Ok(expanded_key) => {
+							// Two different keys (such as a keyword and one of its aliases)
+							// expanding to the same keyword are colliding keywords, except for
+							// `@included` and `@type` whose values are merged (unless processing
+							// mode is json-ld-1.0), and `@nest` whose entries are merged.
+							if let Term::Keyword(keyword) = &expanded_key {
+								let merged = *keyword == Keyword::Nest || (options.processing_mode != ProcessingMode::JsonLd1_0 && (*keyword == Keyword::Included || *keyword == Keyword::Type));
+								if !merged {
+									if expanded_keywords.contains(keyword) {
+										return Err(ErrorCode::CollidingKeywords.into())
+									}
+
+									expanded_keywords.push(*keyword)
+								}
+							}
+
 							match &expanded_key {
 								Term::Keyword(Keyword::Value) => {
 									value_entry = Some(value)
@@ -217,6 +265,10 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 							expanded_entries.push(Entry((*key, expanded_key), value))
 						},
 						Lenient::Unknown(_) => {
+							if !options.dropped_entries {
+								return Err(Error::from(ErrorCode::DroppedEntry).in_entry(key))
+							}
+
 							warn!("failed to expand key `{}`", key)
 						}
 					}
@@ -224,10 +276,16 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 
 				if let Some(list_entry) = list_entry {
 					// List objects.
-					for Entry((_, expanded_key), _) in expanded_entries {
+					let mut index = None;
+					for Entry((_, expanded_key), value) in expanded_entries {
 						match expanded_key {
 							Term::Keyword(Keyword::Index) => {
-								panic!("TODO list index")
+								// If value is not a string, an invalid @index value error has
+								// been detected and processing is aborted.
+								match value.as_str() {
+									Some(value) => index = Some(value.to_string()),
+									None => return Err(ErrorCode::InvalidIndexValue.into())
+								}
 							},
 							Term::Keyword(Keyword::List) => (),
 							_ => {
@@ -245,13 +303,17 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 						result.extend(expand_element(active_context.as_ref(), active_property, item, base_url, loader, options).await?)
 					}
 
-					Ok(Expanded::Object(Object::List(result).into()))
+					Ok(Expanded::Object(Indexed::new(Object::List(result), index)))
 				} else if let Some(set_entry) = set_entry {
 					// Set objects.
-					for Entry((_, expanded_key), _) in expanded_entries {
+					for Entry((_, expanded_key), value) in expanded_entries {
 						match expanded_key {
 							Term::Keyword(Keyword::Index) => {
-								panic!("TODO set index")
+								// The index of a set object is dropped along with the set,
+								// but must still be a string.
+								if !value.is_string() {
+									return Err(ErrorCode::InvalidIndexValue.into())
+								}
 							},
 							Term::Keyword(Keyword::Set) => (),
 							_ => {
@@ -313,10 +375,26 @@ pub fn expand_element<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L
 					Mown::Borrowed(active_context)
 				};
 
+				if let Some(value) = element.as_str() {
+					match active_context.get_opt(active_property).and_then(|definition| definition.typ.as_ref()) {
+						Some(Type::Id) => {
+							check_compact_iri(active_context.as_ref(), value, false, options)?;
+							check_relative_iri(active_context.as_ref(), value, false, options)?
+						},
+						Some(Type::Vocab) => {
+							check_compact_iri(active_context.as_ref(), value, true, options)?;
+							check_relative_iri(active_context.as_ref(), value, true, options)?
+						},
+						_ => ()
+					}
+				}
+
 				// Return the result of the Value Expansion algorithm, passing the `active_context`,
 				// `active_property`, and `element` as value.
-				return Ok(Expanded::Object(expand_literal(active_context.as_ref(), active_property, element)?))
+				let literal = expand_literal(active_context.as_ref(), active_property, element)?;
+				check_node_id(literal.id(), options)?;
+				return Ok(Expanded::Object(literal))
 			}
 		}
-	}.boxed()
+	}, "expand_element", active_property = active_property).boxed()
 }
@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+use std::fmt;
+use futures::Future;
+use iref::{
+	Iri,
+	IriBuf
+};
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	Indexed,
+	Object,
+	ContextMut,
+	ExpandedDocument,
+	context::{
+		Local,
+		Loader,
+		ProcessingStack
+	},
+	util::as_array
+};
+use super::{
+	Options,
+	expand_element,
+	filter_top_level_item
+};
+
+/// Shape of an incrementally expanded document.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Shape {
+	/// The document is a single object (or value).
+	Single,
+
+	/// The document is an array of top-level items.
+	Array,
+
+	/// The document is an object containing only a `@graph` entry, and an optional `@context`
+	/// entry.
+	Graph
+}
+
+/// Top-level item index that is out of bounds, source of the
+/// [`ItemIndexOutOfBounds`](ErrorCode::ItemIndexOutOfBounds) error.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IndexOutOfBounds {
+	/// Index.
+	pub index: usize,
+
+	/// Number of top-level items.
+	pub len: usize
+}
+
+impl fmt::Display for IndexOutOfBounds {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "index {} is out of bounds for {} top-level items", self.index, self.len)
+	}
+}
+
+impl std::error::Error for IndexOutOfBounds {}
+
+/// Top-level item of an incrementally expanded document.
+struct Item<T: Id> {
+	/// Item source.
+	source: JsonValue,
+
+	/// Expanded item.
+	expanded: Vec<Indexed<Object<T>>>
+}
+
+/// Split a document into its shape, root context and top-level items.
+pub(super) fn split(document: &JsonValue) -> (Shape, Option<&JsonValue>, &[JsonValue]) {
+	match document {
+		JsonValue::Array(items) => (Shape::Array, None, items),
+		JsonValue::Object(obj) if obj.get("@graph").is_some() && obj.iter().all(|(key, _)| key == "@graph" || key == "@context") => {
+			(Shape::Graph, obj.get("@context"), as_array(obj.get("@graph").unwrap()))
+		},
+		_ => (Shape::Single, None, std::slice::from_ref(document))
+	}
+}
+
+/// Expand a top-level item.
+pub(super) async fn expand_item<T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &C, shape: Shape, item: &JsonValue, base_url: Option<Iri<'_>>, loader: &mut L, options: Options) -> Result<Vec<Indexed<Object<T>>>, Error> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+	let active_property = match shape {
+		Shape::Graph => Some("@graph"),
+		_ => None
+	};
+
+	let expanded = expand_element(active_context, active_property, item, base_url, loader, options).await?;
+	Ok(expanded.into_iter().filter(filter_top_level_item).collect())
+}
+
+/// Incrementally expanded document.
+///
+/// Keeps the expansion of each top-level item of a document (the items of a top-level array,
+/// or of a top-level `@graph`) so that when the document changes, only the modified items are
+/// expanded again. The top-level context (if any) is processed once and reused as long as it
+/// does not change.
+///
+/// This is useful for editors or validation services that need to re-expand a document after each
+/// small modification.
+pub struct Incremental<T: Id, C> {
+	/// Initial context.
+	initial_context: C,
+
+	/// Active context used to expand the top-level items.
+	active_context: C,
+
+	/// Top-level context, for documents with a top-level `@graph`.
+	root_context: Option<JsonValue>,
+
+	/// Document shape.
+	shape: Shape,
+
+	/// Base URL.
+	base_url: Option<IriBuf>,
+
+	/// Expansion options.
+	options: Options,
+
+	/// Top-level items.
+	items: Vec<Item<T>>
+}
+
+impl<T: Send + Sync + Id, C: Send + Sync + ContextMut<T>> Incremental<T, C> {
+	/// Expand the given document.
+	pub fn new<'a, L: Send + Sync + Loader>(document: &'a JsonValue, base_url: Option<Iri>, context: &'a C, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output = Result<Incremental<T, C>, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		let base_url = base_url.map(IriBuf::from);
+
+		async move {
+			let mut result = Incremental {
+				initial_context: context.clone(),
+				active_context: context.clone(),
+				root_context: None,
+				shape: Shape::Single,
+				base_url,
+				options,
+				items: Vec::new()
+			};
+
+			result.update(document, loader).await?;
+			Ok(result)
+		}
+	}
+
+	/// Number of top-level items.
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	/// Checks if the document has no top-level items.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Active context used to expand the top-level items.
+	pub fn active_context(&self) -> &C {
+		&self.active_context
+	}
+
+	/// Rebuild the source JSON document.
+	pub fn source(&self) -> JsonValue {
+		let items = self.items.iter().map(|item| item.source.clone());
+		match self.shape {
+			Shape::Single if self.items.len() == 1 => self.items[0].source.clone(),
+			Shape::Single | Shape::Array => JsonValue::Array(items.collect()),
+			Shape::Graph => {
+				let mut obj = json::object::Object::new();
+				if let Some(context) = &self.root_context {
+					obj.insert("@context", context.clone())
+				}
+				obj.insert("@graph", JsonValue::Array(items.collect()));
+				JsonValue::Object(obj)
+			}
+		}
+	}
+
+	/// Iterate through the expanded objects.
+	///
+	/// Contrarily to [`document`](Incremental::document), a top-level unnamed graph is not
+	/// unwrapped.
+	pub fn objects(&self) -> impl Iterator<Item = &Indexed<Object<T>>> {
+		self.items.iter().flat_map(|item| item.expanded.iter())
+	}
+
+	/// Get the expanded document.
+	///
+	/// The result is the same as the one of the [`expand`](crate::expansion::expand) function on the
+	/// current source document.
+	pub fn document(&self) -> ExpandedDocument<T> {
+		expanded_document(self.shape, self.objects().cloned().collect())
+	}
+
+	/// Consume the incremental expansion state and return the expanded document.
+	pub fn into_document(self) -> ExpandedDocument<T> {
+		let shape = self.shape;
+		let objects: Vec<_> = self.items.into_iter().flat_map(|item| item.expanded.into_iter()).collect();
+		expanded_document(shape, objects)
+	}
+
+	/// Update the document.
+	///
+	/// Only the top-level items that are not in the previous version of the document are expanded.
+	/// If the top-level context changed, every item is expanded again.
+	///
+	/// Returns the number of top-level items that have been expanded.
+	pub fn update<'a, L: Send + Sync + Loader>(&'a mut self, document: &'a JsonValue, loader: &'a mut L) -> impl 'a + Send + Future<Output = Result<usize, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		async move {
+			let (shape, root_context, sources) = split(document);
+
+			let mut old_items: Vec<Option<Item<T>>> = std::mem::take(&mut self.items).into_iter().map(Some).collect();
+
+			if shape != self.shape || root_context != self.root_context.as_ref() {
+				// The context used to expand the items changed.
+				self.active_context = match root_context {
+					Some(local_context) => {
+						let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+						local_context.process_with(&self.initial_context, ProcessingStack::new(), loader, base_url, self.options.into()).await?
+					},
+					None => self.initial_context.clone()
+				};
+
+				if (shape == Shape::Graph) != (self.shape == Shape::Graph) || root_context != self.root_context.as_ref() {
+					old_items.clear();
+				}
+
+				self.shape = shape;
+				self.root_context = root_context.cloned();
+			}
+
+			let mut count = 0;
+			let mut items = Vec::with_capacity(sources.len());
+			for (i, source) in sources.iter().enumerate() {
+				let reused = match old_items.get(i) {
+					Some(Some(old)) if old.source == *source => Some(i),
+					_ => old_items.iter().position(|old| old.as_ref().map(|old| old.source == *source).unwrap_or(false))
+				};
+
+				let item = match reused {
+					Some(j) => old_items[j].take().unwrap(),
+					None => {
+						count += 1;
+						let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+						Item {
+							source: source.clone(),
+							expanded: expand_item(&self.active_context, self.shape, source, base_url, loader, self.options).await?
+						}
+					}
+				};
+
+				items.push(item)
+			}
+
+			self.items = items;
+			Ok(count)
+		}
+	}
+
+	/// Error for an out of bounds top-level item `index`.
+	fn out_of_bounds(&self, index: usize) -> Error {
+		Error::new(ErrorCode::ItemIndexOutOfBounds, IndexOutOfBounds { index, len: self.items.len() })
+	}
+
+	/// Replace the top-level item at position `index`, and expand it.
+	///
+	/// Fails with an [`ItemIndexOutOfBounds`](ErrorCode::ItemIndexOutOfBounds) error if `index`
+	/// is out of bounds.
+	pub fn replace<'a, L: Send + Sync + Loader>(&'a mut self, index: usize, item: JsonValue, loader: &'a mut L) -> impl 'a + Send + Future<Output = Result<(), Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		async move {
+			if index >= self.items.len() {
+				return Err(self.out_of_bounds(index))
+			}
+
+			let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+			let expanded = expand_item(&self.active_context, self.shape, &item, base_url, loader, self.options).await?;
+			self.items[index] = Item {
+				source: item,
+				expanded
+			};
+
+			Ok(())
+		}
+	}
+
+	/// Insert a new top-level item at position `index`, and expand it.
+	///
+	/// Fails with an [`ItemIndexOutOfBounds`](ErrorCode::ItemIndexOutOfBounds) error if
+	/// `index > len`.
+	pub fn insert<'a, L: Send + Sync + Loader>(&'a mut self, index: usize, item: JsonValue, loader: &'a mut L) -> impl 'a + Send + Future<Output = Result<(), Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		async move {
+			if index > self.items.len() {
+				return Err(self.out_of_bounds(index))
+			}
+
+			if self.shape == Shape::Single {
+				self.shape = Shape::Array
+			}
+
+			let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+			let expanded = expand_item(&self.active_context, self.shape, &item, base_url, loader, self.options).await?;
+			self.items.insert(index, Item {
+				source: item,
+				expanded
+			});
+
+			Ok(())
+		}
+	}
+
+	/// Remove the top-level item at position `index` and return its source.
+	///
+	/// Fails with an [`ItemIndexOutOfBounds`](ErrorCode::ItemIndexOutOfBounds) error if `index`
+	/// is out of bounds.
+	pub fn remove(&mut self, index: usize) -> Result<JsonValue, Error> {
+		if index >= self.items.len() {
+			return Err(self.out_of_bounds(index))
+		}
+
+		if self.shape == Shape::Single {
+			self.shape = Shape::Array
+		}
+
+		Ok(self.items.remove(index).source)
+	}
+}
+
+pub(super) fn expanded_document<T: Id>(shape: Shape, objects: Vec<Indexed<Object<T>>>) -> ExpandedDocument<T> {
+	// A top-level unnamed graph is unwrapped, as in the `expand` function.
+	if shape != Shape::Graph && objects.len() == 1 {
+		match objects.into_iter().next().unwrap().into_unnamed_graph() {
+			Ok(graph) => graph.into(),
+			Err(obj) => std::iter::once(obj).collect()
+		}
+	} else if shape == Shape::Graph {
+		// The top-level graph is a set, sorted as in the `expand` function.
+		objects.into_iter().collect::<HashSet<_>>().into()
+	} else {
+		objects.into_iter().collect()
+	}
+}
@@ -1,8 +1,12 @@
 use std::convert::TryFrom;
+use std::fmt;
 use iref::{Iri, IriRef};
 use crate::{
+	Error,
+	ErrorCode,
 	BlankId,
 	Id,
+	Reference,
 	Lenient,
 	Context,
 	syntax::{
@@ -12,6 +16,165 @@ use crate::{
 	}
 };
 
+/// IRI expansion options.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IriOptions {
+	/// Resolve relative IRI references against the base IRI of the active context.
+	///
+	/// Default is `false`.
+	pub document_relative: bool,
+
+	/// Use term definitions and the vocabulary mapping of the active context.
+	///
+	/// Default is `true`.
+	pub vocab: bool
+}
+
+impl Default for IriOptions {
+	fn default() -> IriOptions {
+		IriOptions {
+			document_relative: false,
+			vocab: true
+		}
+	}
+}
+
+/// Value that does not expand into a keyword, IRI or blank node identifier.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InvalidIri(pub String);
+
+impl fmt::Display for InvalidIri {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` does not expand into an IRI", self.0)
+	}
+}
+
+impl std::error::Error for InvalidIri {}
+
+/// Compact IRI found while they are disabled with [`Options::compact_iris`](super::Options::compact_iris).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForbiddenCompactIri(pub String);
+
+impl fmt::Display for ForbiddenCompactIri {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` is a compact IRI", self.0)
+	}
+}
+
+impl std::error::Error for ForbiddenCompactIri {}
+
+/// Relative IRI reference left unresolved while they are disabled with
+/// [`Options::relative_iris`](super::Options::relative_iris).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnresolvedIri(pub String);
+
+impl fmt::Display for UnresolvedIri {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` cannot be resolved without base IRI", self.0)
+	}
+}
+
+impl std::error::Error for UnresolvedIri {}
+
+/// Node identifier that is neither an absolute IRI nor a blank node identifier, while relative
+/// IRIs are disabled with [`Options::relative_iris`](super::Options::relative_iris).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NonAbsoluteId(pub String);
+
+impl fmt::Display for NonAbsoluteId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` is not an absolute IRI or blank node identifier", self.0)
+	}
+}
+
+impl std::error::Error for NonAbsoluteId {}
+
+/// Expand a single term, compact IRI or IRI against the given active context.
+///
+/// Contrarily to [`expand_iri`], an error is returned if the value does not expand into a
+/// keyword, IRI or blank node identifier.
+/// `Term::Null` is returned for keyword-like values and terms explicitly mapped to `null`.
+pub fn try_expand_iri<T: Id, C: Context<T>>(active_context: &C, value: &str, options: IriOptions) -> Result<Term<T>, InvalidIri> {
+	match expand_iri(active_context, value, options.document_relative, options.vocab) {
+		Lenient::Ok(term) => Ok(term),
+		Lenient::Unknown(value) => Err(InvalidIri(value))
+	}
+}
+
+/// Returns the keyword the given key expands to, if any.
+///
+/// This is the same as checking for a `Term::Keyword` result of [`expand_iri`] with `vocab`
+/// set to `true`, but never allocates: keys are only expanded into keywords by being one, or
+/// through a term definition mapping them to one.
+pub fn expand_keyword<T: Id, C: Context<T>>(active_context: &C, value: &str) -> Option<Keyword> {
+	if let Ok(keyword) = Keyword::try_from(value) {
+		Some(keyword)
+	} else if is_keyword_like(value) {
+		None
+	} else {
+		match active_context.get(value).and_then(|term_definition| term_definition.value.as_ref()) {
+			Some(Term::Keyword(keyword)) => Some(*keyword),
+			_ => None
+		}
+	}
+}
+
+/// Checks if [`expand_iri`] would expand the given value as a compact IRI, by concatenating the
+/// IRI mapping of a prefix with a suffix.
+pub fn is_compact_iri<T: Id, C: Context<T>>(active_context: &C, value: &str, vocab: bool) -> bool {
+	if Keyword::try_from(value).is_ok() || is_keyword_like(value) {
+		return false
+	}
+
+	if let Some(term_definition) = active_context.get(value) {
+		if vocab || term_definition.value.as_ref().map(Term::is_keyword).unwrap_or(false) {
+			return false
+		}
+	}
+
+	match value.find(':') {
+		Some(index) if index > 0 => {
+			let (prefix, suffix) = (&value[..index], &value[(index + 1)..]);
+			prefix != "_" && !suffix.starts_with("//") && match active_context.get(prefix) {
+				Some(term_definition) => term_definition.prefix && term_definition.value.is_some(),
+				None => false
+			}
+		},
+		_ => false
+	}
+}
+
+/// Fails if the given value is a compact IRI while they are disabled by the expansion options.
+pub(crate) fn check_compact_iri<T: Id, C: Context<T>>(active_context: &C, value: &str, vocab: bool, options: super::Options) -> Result<(), Error> {
+	if !options.compact_iris && is_compact_iri(active_context, value, vocab) {
+		Err(Error::new(ErrorCode::CompactIri, ForbiddenCompactIri(value.to_string())))
+	} else {
+		Ok(())
+	}
+}
+
+/// Fails if the given value is a relative IRI reference that is left as it is by document-relative
+/// IRI expansion, because the active context has no base IRI, while relative IRIs are disabled
+/// by the expansion options.
+pub(crate) fn check_relative_iri<T: Id, C: Context<T>>(active_context: &C, value: &str, vocab: bool, options: super::Options) -> Result<(), Error> {
+	if !options.relative_iris && active_context.base_iri().is_none() && active_context.get(value).is_none() && IriRef::new(value).is_ok() {
+		if let Lenient::Unknown(value) = expand_iri(active_context, value, true, vocab) {
+			return Err(Error::new(ErrorCode::RelativeIri, UnresolvedIri(value)))
+		}
+	}
+
+	Ok(())
+}
+
+/// Fails if the given node identifier is not an absolute IRI or blank node identifier while
+/// relative IRIs are disabled by the expansion options.
+pub(crate) fn check_node_id<T: Id>(id: Option<&Lenient<Reference<T>>>, options: super::Options) -> Result<(), Error> {
+	match id {
+		Some(Lenient::Unknown(id)) if !options.relative_iris => Err(Error::new(ErrorCode::RelativeIri, NonAbsoluteId(id.clone()))),
+		_ => Ok(())
+	}
+}
+
 // Default value for `document_relative` is `false` and for `vocab` is `true`.
 pub fn expand_iri<T: Id, C: Context<T>>(active_context: &C, value: &str, document_relative: bool, vocab: bool) -> Lenient<Term<T>> {
 	if let Ok(keyword) = Keyword::try_from(value) {
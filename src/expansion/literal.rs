@@ -48,7 +48,7 @@ pub fn expand_literal<T: Id, C: Context<T>>(active_context: &C, active_property:
 		Some(Type::Id) if value.is_string() => {
 			let mut node = Node::new();
 			node.id = node_id_of_term(expand_iri(active_context, value.as_str().unwrap(), true, false));
-			Ok(Object::Node(node).into())
+			Ok(Object::Node(Box::new(node)).into())
 		},
 
 		// If `active_property` has a type mapping in active context that is `@vocab`, and the
@@ -58,7 +58,7 @@ pub fn expand_literal<T: Id, C: Context<T>>(active_context: &C, active_property:
 		Some(Type::Vocab) if value.is_string() => {
 			let mut node = Node::new();
 			node.id = node_id_of_term(expand_iri(active_context, value.as_str().unwrap(), true, true));
-			Ok(Object::Node(node).into())
+			Ok(Object::Node(Box::new(node)).into())
 		},
 
 		_ => {
@@ -7,23 +7,32 @@ mod value;
 mod node;
 mod array;
 mod element;
+mod incremental;
+mod partial;
 
 use std::cmp::{Ord, Ordering};
-use std::collections::HashSet;
 use futures::Future;
 use iref::{Iri, IriBuf};
 use json::JsonValue;
 use crate::{
 	ProcessingMode,
+	KeywordPolicy,
 	Error,
 	Id,
 	Indexed,
 	Object,
+	ExpandedDocument,
+	Document,
+	Context,
 	ContextMut,
+	JsonContext,
 	context::{
 		ProcessingOptions,
+		ProcessingStack,
+		Local,
 		Loader
-	}
+	},
+	util::Timer
 };
 
 pub use expanded::*;
@@ -33,21 +42,126 @@ pub use value::*;
 pub use node::*;
 pub use array::*;
 pub use element::*;
+pub use incremental::*;
+pub use partial::*;
+
+/// Action taken on an unknown keyword entry.
+#[derive(Clone, PartialEq, Debug)]
+pub enum KeywordAction {
+	/// Drop the entry, as specified.
+	Drop,
+
+	/// Keep the entry: its value is expanded and kept as an extension entry of the node.
+	Keep,
+
+	/// Keep the entry, expanding the given value instead of the original one.
+	Transform(JsonValue)
+}
 
-#[derive(Clone, Copy, Default)]
+/// Handler of unknown keywords.
+///
+/// Called with the key and value of each node object entry whose key has the form of a keyword
+/// (`@` followed by letters, such as `@annotation`) without being a JSON-LD keyword.
+pub type KeywordHandler = fn(&str, &JsonValue) -> KeywordAction;
+
+#[derive(Clone, Copy)]
 pub struct Options {
 	/// Sets the processing mode.
 	pub processing_mode: ProcessingMode,
 
 	/// If set to true, input document entries are processed lexicographically.
 	/// If false, order is not considered in processing.
-	pub ordered: bool
+	pub ordered: bool,
+
+	/// Handler of unknown keywords, used to experiment with draft or vendor keywords.
+	///
+	/// When `None`, unknown keyword entries are dropped, as specified.
+	/// When set, it takes precedence over [`keyword_policy`](Options::keyword_policy) and
+	/// [`dropped_entries`](Options::dropped_entries) for node object keys.
+	/// Kept entries are available through [`Node::extension`](crate::Node::extension).
+	pub unknown_keywords: Option<KeywordHandler>,
+
+	/// Skip the context processing checks of each node object.
+	///
+	/// This is only correct when the active context can never change during expansion
+	/// (see [`is_context_free`]).
+	/// It is set by [`expand`] when that is the case, so that bulk expansion of records
+	/// against a preprocessed context does not look for scoped and embedded contexts at each
+	/// node.
+	pub context_free: bool,
+
+	/// Allow compact IRIs.
+	///
+	/// When `false`, expansion fails with [`ErrorCode::CompactIri`](crate::ErrorCode::CompactIri)
+	/// whenever a key or value would be expanded using the IRI mapping of a prefix, instead of
+	/// being an absolute IRI, a keyword or a defined term.
+	///
+	/// Default is `true`.
+	pub compact_iris: bool,
+
+	/// Allow relative IRI references that cannot be resolved.
+	///
+	/// Document-relative IRIs (such as `@id` values) are resolved against the base IRI of the
+	/// active context. When there is none, for instance after `"@base": null`, they are kept
+	/// relative as specified.
+	/// When `false`, expansion fails with [`ErrorCode::RelativeIri`](crate::ErrorCode::RelativeIri)
+	/// instead, and also whenever a node identifier (an `@id` entry, the value of a term whose
+	/// type is `@id` or `@vocab`, or the key of an `@id` map) does not expand to an absolute IRI
+	/// or a blank node identifier, such as an invalid IRI.
+	///
+	/// Default is `true`.
+	pub relative_iris: bool,
+
+	/// Allow dropped entries.
+	///
+	/// Entries whose key does not expand to an IRI or a keyword, such as undefined terms
+	/// without vocabulary mapping, terms defined as `null` or unknown keywords, are dropped
+	/// as specified.
+	/// When `false`, expansion fails with [`ErrorCode::DroppedEntry`](crate::ErrorCode::DroppedEntry)
+	/// instead, unless the [`unknown_keywords`](Options::unknown_keywords) handler explicitly
+	/// drops the entry.
+	///
+	/// Default is `true`.
+	pub dropped_entries: bool,
+
+	/// Policy applied to unknown keywords.
+	///
+	/// It applies to the keys of the expanded objects, `@id` values and `@id` map keys having the
+	/// form of a keyword, and to the contexts processed during expansion.
+	///
+	/// An unknown keyword key is handled by the first of these options that applies:
+	///
+	/// 1. the [`unknown_keywords`](Options::unknown_keywords) handler, if any, keeps, transforms
+	///    or drops the entry, and this policy is not applied to keys;
+	/// 2. [`KeywordPolicy::Error`] fails with [`ErrorCode::UnknownKeyword`](crate::ErrorCode::UnknownKeyword);
+	/// 3. the entry is dropped (with a warning for [`KeywordPolicy::Warn`]), which fails with
+	///    [`ErrorCode::DroppedEntry`](crate::ErrorCode::DroppedEntry) when
+	///    [`dropped_entries`](Options::dropped_entries) is `false`.
+	///
+	/// Default is [`KeywordPolicy::Ignore`].
+	pub keyword_policy: KeywordPolicy
+}
+
+impl Default for Options {
+	fn default() -> Options {
+		Options {
+			processing_mode: ProcessingMode::default(),
+			ordered: false,
+			unknown_keywords: None,
+			context_free: false,
+			compact_iris: true,
+			relative_iris: true,
+			dropped_entries: true,
+			keyword_policy: KeywordPolicy::default()
+		}
+	}
 }
 
 impl From<Options> for ProcessingOptions {
 	fn from(options: Options) -> ProcessingOptions {
 		let mut copt = ProcessingOptions::default();
 		copt.processing_mode = options.processing_mode;
+		copt.keyword_policy = options.keyword_policy;
 		copt
 	}
 }
@@ -67,33 +181,95 @@ impl<'a, T: Ord> Ord for Entry<'a, T> {
 	}
 }
 
+/// Checks if the active context can never change while expanding the given element.
+///
+/// This is the case when the element has no `@context` entry, and the active context has
+/// no scoped context and is not a non-propagated context.
+pub fn is_context_free<T: Id, C: Context<T>>(active_context: &C, element: &JsonValue) -> bool {
+	fn has_context(element: &JsonValue) -> bool {
+		match element {
+			JsonValue::Array(items) => items.iter().any(has_context),
+			JsonValue::Object(object) => object.get("@context").is_some() || object.iter().any(|(_, value)| has_context(value)),
+			_ => false
+		}
+	}
+
+	active_context.previous_context().is_none()
+	&& active_context.definitions().all(|(_, definition)| definition.context.is_none())
+	&& !has_context(element)
+}
+
 fn filter_top_level_item<T: Id>(item: &Indexed<Object<T>>) -> bool {
 	// Remove dangling values.
-	match item.inner() {
-		Object::Value(_) => false,
-		_ => true
-	}
+	!item.is_free_floating()
 }
 
-pub fn expand<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, element: &'a JsonValue, base_url: Option<Iri>, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<HashSet<Indexed<Object<T>>>, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+pub fn expand<'a, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &'a C, element: &'a JsonValue, base_url: Option<Iri>, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<ExpandedDocument<T>, Error>> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
 	let base_url = base_url.map(|url| IriBuf::from(url));
 
 	async move {
+		match &base_url {
+			Some(url) => debug!("expanding document `{}'", url),
+			None => debug!("expanding document")
+		}
+
+		let timer = Timer::start();
 		let base_url = base_url.as_ref().map(|url| url.as_iri());
-		let expanded = expand_element(active_context, None, element, base_url, loader, options).await?;
-		if expanded.len() == 1 {
+		let options = Options {
+			context_free: is_context_free(active_context, element),
+			..options
+		};
+
+		let expanded = match instrument!(expand_element(active_context, None, element, base_url, loader, options), "expand", base_url = base_url.map(|url| url.into_str())).await {
+			Ok(expanded) => expanded,
+			Err(e) => {
+				debug!("expansion failed after {}: {}", timer, e.code());
+				return Err(e)
+			}
+		};
+
+		let result = if expanded.len() == 1 {
 			match expanded.into_iter().next().unwrap().into_unnamed_graph() {
-				Ok(graph) => Ok(graph),
+				Ok(graph) => graph.into(),
 				Err(obj) => {
-					let mut set = HashSet::new();
+					let mut document = ExpandedDocument::new();
 					if filter_top_level_item(&obj) {
-						set.insert(obj);
+						document.push(obj);
 					}
-					Ok(set)
+					document
 				}
 			}
 		} else {
-			Ok(expanded.into_iter().filter(filter_top_level_item).collect())
-		}
+			expanded.into_iter().filter(filter_top_level_item).collect()
+		};
+
+		debug!("expanded document in {} ({} top-level objects)", timer, result.len());
+		Ok(result)
+	}
+}
+
+/// Expand a document against a context stored separately.
+///
+/// This is the `expandContext` option of the JSON-LD API: `context` is processed on top of an
+/// empty context with the document base URL, as if it was the first `@context` entry of the
+/// document, which is processed after it if any.
+/// It may be a context definition, a context URL, an array of those, or a map with a `@context`
+/// entry.
+///
+/// ```ignore
+/// let context = json::parse(r#"{"@vocab": "http://schema.org/"}"#)?;
+/// let expanded: ExpandedDocument<IriBuf> = expansion::expand_with_context(&record, &context, &mut loader, Options::default()).await?;
+/// ```
+pub fn expand_with_context<'a, T: Send + Sync + Id, D: Send + Sync + Document<T, LocalContext = JsonValue>, L: Send + Sync + Loader>(document: &'a D, context: &'a JsonValue, loader: &'a mut L, options: Options) -> impl 'a + Send + Future<Output=Result<ExpandedDocument<T>, Error>> where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+	async move {
+		let context = match context {
+			JsonValue::Object(object) => object.get("@context").unwrap_or(context),
+			_ => context
+		};
+
+		let base_url = document.base_url();
+		let initial_context: JsonContext<T> = JsonContext::new(base_url);
+		let active_context = context.process_with(&initial_context, ProcessingStack::new(), loader, base_url, options.into()).await?;
+		document.expand_with(base_url, &active_context, loader, options).await
 	}
 }
@@ -0,0 +1,119 @@
+use iref::Iri;
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	ContextMut,
+	ExpandedDocument,
+	context::{
+		Local,
+		Loader,
+		ProcessingStack
+	},
+	syntax::Term
+};
+use super::{
+	Options,
+	Shape,
+	split,
+	expand_item,
+	expanded_document
+};
+
+/// Partially expanded document.
+///
+/// Result of [`expand_partial`].
+pub struct Partial<T: Id> {
+	/// Successfully expanded objects.
+	pub document: ExpandedDocument<T>,
+
+	/// Expansion failures, along with the path of the failing entry in the source document.
+	///
+	/// The path starts with the position of the top-level item (preceded by `@graph` when the
+	/// items are in a top-level `@graph`), followed by the [entry path](Error::entry_path) of
+	/// the error.
+	pub failures: Vec<(Vec<String>, Error)>
+}
+
+impl<T: Id> Partial<T> {
+	/// Checks if the whole document has been expanded.
+	pub fn is_complete(&self) -> bool {
+		self.failures.is_empty()
+	}
+}
+
+/// Checks if the given entry key is a keyword, or an alias of a keyword.
+fn is_keyword_entry<T: Id, C: ContextMut<T>>(active_context: &C, key: &str) -> bool {
+	key.starts_with('@') || matches!(active_context.get(key).and_then(|definition| definition.value.as_ref()), Some(Term::Keyword(_)))
+}
+
+/// Expand a document, continuing past the failing parts.
+///
+/// The top-level items of the document (the items of a top-level array, or of a top-level
+/// `@graph`) are expanded separately. When a property entry of an item fails, the failure is
+/// recorded and the item is expanded again without this entry, keeping its other entries.
+/// Items failing because of a keyword entry (such as `@context` or `@id`) are dropped.
+///
+/// This is useful for bulk importers, to salvage the valid part of a feed.
+/// Only the failure to process the top-level context, or the cancellation of the expansion,
+/// make the whole expansion fail.
+/// ```ignore
+/// let partial: Partial<IriBuf> = expansion::expand_partial(&context, &feed, None, &mut loader, Options::default()).await?;
+/// for (path, e) in &partial.failures {
+///     warn!("skipped `{}`: {}", path.join("/"), e)
+/// }
+/// ```
+pub async fn expand_partial<T: Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(active_context: &C, document: &JsonValue, base_url: Option<Iri<'_>>, loader: &mut L, options: Options) -> Result<Partial<T>, Error> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+	let (shape, root_context, sources) = split(document);
+	let root_context: C = match root_context {
+		Some(local_context) => local_context.process_with(active_context, ProcessingStack::new(), loader, base_url, options.into()).await?,
+		None => active_context.clone()
+	};
+
+	let mut objects = Vec::new();
+	let mut failures = Vec::new();
+	for (i, source) in sources.iter().enumerate() {
+		let prefix: Vec<String> = match shape {
+			Shape::Single => Vec::new(),
+			Shape::Array => vec![i.to_string()],
+			Shape::Graph => vec!["@graph".to_string(), i.to_string()]
+		};
+
+		// Source without the failing entries, if any.
+		let mut retry: Option<JsonValue> = None;
+		loop {
+			let item = retry.as_ref().unwrap_or(source);
+			match expand_item(&root_context, shape, item, base_url, loader, options).await {
+				Ok(expanded) => {
+					objects.extend(expanded);
+					break
+				},
+				Err(e) => {
+					if e.code() == ErrorCode::Cancelled {
+						return Err(e)
+					}
+
+					let entry = e.entry_path().first().cloned();
+					let mut path = prefix.clone();
+					path.extend(e.entry_path().iter().cloned());
+					failures.push((path, e));
+
+					match entry {
+						Some(key) if item.is_object() && !is_keyword_entry(&root_context, &key) => {
+							let mut item = item.clone();
+							item.remove(&key);
+							retry = Some(item)
+						},
+						_ => break
+					}
+				}
+			}
+		}
+	}
+
+	Ok(Partial {
+		document: expanded_document(shape, objects),
+		failures
+	})
+}
@@ -0,0 +1,134 @@
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Error,
+	Id,
+	Reference,
+	Lenient,
+	Indexed,
+	Node,
+	ExpandedDocument,
+	generator::Generator,
+	syntax::Keyword,
+	util::AsJson
+};
+use super::{
+	NodeMapGraph,
+	node_map
+};
+
+/// Nodes of a flattened graph.
+type Graph<T> = Vec<Indexed<Node<T>>>;
+
+/// Flattened document.
+///
+/// Result of the [Flattening algorithm](https://www.w3.org/TR/json-ld11-api/#flattening-algorithm).
+/// Every node of the document, including embedded nodes, appears once in its graph,
+/// and every property value that is a node object is replaced by a reference to the node.
+/// The nodes of each graph are sorted by identifier, and nodes having no other entry than
+/// `@id` are removed.
+///
+/// It can be built with the [`flatten`] function.
+pub struct FlattenedDocument<T: Id = IriBuf> {
+	/// Nodes of the default graph.
+	default_graph: Graph<T>,
+
+	/// Named graphs, sorted by name.
+	graphs: Vec<(Reference<T>, Graph<T>)>
+}
+
+impl<T: Id> FlattenedDocument<T> {
+	/// Nodes of the default graph.
+	///
+	/// This includes a node for each named graph, identified by the graph name.
+	pub fn default_graph(&self) -> &[Indexed<Node<T>>] {
+		&self.default_graph
+	}
+
+	/// Nodes of the graph with the given name.
+	///
+	/// The default graph is selected using `None`.
+	pub fn graph(&self, name: Option<&Reference<T>>) -> Option<&[Indexed<Node<T>>]> {
+		match name {
+			Some(name) => self.graphs.iter().find(|(n, _)| n == name).map(|(_, nodes)| nodes.as_slice()),
+			None => Some(&self.default_graph)
+		}
+	}
+
+	/// Iterate through the named graphs, sorted by name.
+	pub fn named_graphs(&self) -> impl Iterator<Item = (&Reference<T>, &[Indexed<Node<T>>])> {
+		self.graphs.iter().map(|(name, nodes)| (name, nodes.as_slice()))
+	}
+
+	/// Iterate through every graph, starting with the default graph (named `None`).
+	pub fn graphs(&self) -> impl Iterator<Item = (Option<&Reference<T>>, &[Indexed<Node<T>>])> {
+		std::iter::once((None, self.default_graph.as_slice())).chain(self.named_graphs().map(|(name, nodes)| (Some(name), nodes)))
+	}
+}
+
+impl<T: Id> AsJson for FlattenedDocument<T> {
+	/// Returns the flattened document in its JSON-LD form: the array of the default graph
+	/// nodes, where the nodes of each named graph are listed in the `@graph` entry of the node
+	/// identified by the graph name.
+	fn as_json(&self) -> JsonValue {
+		let mut result = Vec::with_capacity(self.default_graph.len());
+		for node in &self.default_graph {
+			let mut json = node.as_json();
+			if let Some(Lenient::Ok(id)) = node.id() {
+				if let Some(nodes) = self.graph(Some(id)) {
+					json.insert(Keyword::Graph.into(), nodes.as_json()).unwrap();
+				}
+			}
+
+			result.push(json)
+		}
+
+		JsonValue::Array(result)
+	}
+}
+
+/// Checks if the only entry of the given node is `@id`.
+pub(super) fn is_only_id<T: Id>(node: &Indexed<Node<T>>) -> bool {
+	node.is_empty() && node.index().is_none()
+}
+
+/// Sort the nodes of a graph by identifier.
+fn sorted_nodes<T: Id>(graph: NodeMapGraph<T>) -> Vec<(Reference<T>, Indexed<Node<T>>)> {
+	let mut nodes: Vec<_> = graph.into_nodes().filter_map(|node| match node.id() {
+		Some(Lenient::Ok(id)) => Some((id.clone(), node)),
+		_ => None
+	}).collect();
+	nodes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+	nodes
+}
+
+/// Flatten the given expanded document.
+///
+/// Blank node identifiers are relabeled using the given generator.
+pub fn flatten<T: Id, G: Generator>(document: &ExpandedDocument<T>, generator: G) -> Result<FlattenedDocument<T>, Error> {
+	let (default_graph, named_graphs) = node_map(document, generator)?.into_parts();
+
+	let mut graphs: Vec<_> = named_graphs.into_iter().map(|(name, graph)| {
+		let nodes = sorted_nodes(graph).into_iter().map(|(_, node)| node).filter(|node| !is_only_id(node)).collect();
+		(name, nodes)
+	}).collect();
+	graphs.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+	// Each named graph is attached to the node of the default graph identified by its name,
+	// created if necessary.
+	let mut default_graph = sorted_nodes(default_graph);
+	for (name, _) in &graphs {
+		if let Err(i) = default_graph.binary_search_by(|(id, _)| id.as_str().cmp(name.as_str())) {
+			let mut node = Node::new();
+			node.id = Some(Lenient::Ok(name.clone()));
+			default_graph.insert(i, (name.clone(), node.into()))
+		}
+	}
+
+	let default_graph = default_graph.into_iter().filter(|(id, node)| !is_only_id(node) || graphs.iter().any(|(name, _)| name == id)).map(|(_, node)| node).collect();
+
+	Ok(FlattenedDocument {
+		default_graph,
+		graphs
+	})
+}
@@ -0,0 +1,11 @@
+//! Flattening algorithm and types.
+
+mod node_map;
+mod flattened;
+mod store;
+mod partition;
+
+pub use node_map::*;
+pub use flattened::*;
+pub use store::*;
+pub use partition::*;
@@ -0,0 +1,529 @@
+use std::collections::HashMap;
+use std::borrow::Borrow;
+use iref::IriBuf;
+use json::JsonValue;
+use smallvec::smallvec;
+use crate::{
+	Error,
+	ErrorCode,
+	Id,
+	BlankId,
+	Reference,
+	ToReference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument,
+	generator::Generator,
+	util::{self, AsJson}
+};
+use super::NodeStore;
+
+/// Graph of a node map.
+///
+/// Maps each node identifier to a flattened node object.
+/// In a flattened node, every property value that is a node object is replaced by a reference to
+/// the node (a node object with only an `@id` field).
+pub struct NodeMapGraph<T: Id = IriBuf> {
+	nodes: HashMap<Reference<T>, Indexed<Node<T>>>
+}
+
+impl<T: Id> NodeMapGraph<T> {
+	/// Create a new empty graph.
+	pub fn new() -> NodeMapGraph<T> {
+		NodeMapGraph {
+			nodes: HashMap::new()
+		}
+	}
+
+	/// Number of nodes in the graph.
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Checks if the graph is empty.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	/// Checks if the graph contains a node with the given identifier.
+	pub fn contains<R: ToReference<T>>(&self, id: R) -> bool {
+		self.nodes.contains_key(id.to_ref().borrow())
+	}
+
+	/// Get the flattened node with the given identifier.
+	pub fn get<R: ToReference<T>>(&self, id: R) -> Option<&Indexed<Node<T>>> {
+		self.nodes.get(id.to_ref().borrow())
+	}
+
+	/// Iterate through the identifiers and nodes of the graph.
+	pub fn iter(&self) -> impl Iterator<Item = (&Reference<T>, &Indexed<Node<T>>)> {
+		self.nodes.iter()
+	}
+
+	/// Iterate through the nodes of the graph.
+	pub fn nodes(&self) -> impl Iterator<Item = &Indexed<Node<T>>> {
+		self.nodes.values()
+	}
+
+	/// Consume the graph and return its nodes.
+	pub fn into_nodes(self) -> impl Iterator<Item = Indexed<Node<T>>> {
+		self.nodes.into_values()
+	}
+
+	/// Get the node with the given identifier, creating it if necessary.
+	fn node_mut(&mut self, id: &Reference<T>) -> &mut Indexed<Node<T>> {
+		if !self.nodes.contains_key(id) {
+			let mut node = Node::new();
+			node.id = Some(Lenient::Ok(id.clone()));
+			self.nodes.insert(id.clone(), node.into());
+		}
+
+		self.nodes.get_mut(id).unwrap()
+	}
+}
+
+impl<T: Id> Default for NodeMapGraph<T> {
+	fn default() -> NodeMapGraph<T> {
+		Self::new()
+	}
+}
+
+impl<T: Id> AsJson for NodeMapGraph<T> {
+	fn as_json(&self) -> JsonValue {
+		util::sorted_json_object(self.nodes.iter().map(|(id, node)| (id.as_str(), node.as_json())))
+	}
+}
+
+/// Node map.
+///
+/// Result of the [Node Map Generation algorithm](https://www.w3.org/TR/json-ld11-api/#node-map-generation),
+/// which collects every node of a document (including embedded nodes) into a flat map for each
+/// graph.
+/// This is the core data structure used by flattening, framing and RDF serialization.
+/// It can be built with the [`node_map`] function.
+pub struct NodeMap<T: Id = IriBuf> {
+	/// The default graph.
+	default_graph: NodeMapGraph<T>,
+
+	/// Named graphs.
+	graphs: HashMap<Reference<T>, NodeMapGraph<T>>
+}
+
+impl<T: Id> NodeMap<T> {
+	/// Create a new empty node map.
+	pub fn new() -> NodeMap<T> {
+		NodeMap {
+			default_graph: NodeMapGraph::new(),
+			graphs: HashMap::new()
+		}
+	}
+
+	/// Get the default graph.
+	pub fn default_graph(&self) -> &NodeMapGraph<T> {
+		&self.default_graph
+	}
+
+	/// Get the graph with the given name.
+	///
+	/// The default graph is selected using `None`.
+	pub fn graph(&self, name: Option<&Reference<T>>) -> Option<&NodeMapGraph<T>> {
+		match name {
+			Some(name) => self.graphs.get(name),
+			None => Some(&self.default_graph)
+		}
+	}
+
+	/// Iterate through the named graphs of the node map.
+	pub fn named_graphs(&self) -> impl Iterator<Item = (&Reference<T>, &NodeMapGraph<T>)> {
+		self.graphs.iter()
+	}
+
+	/// Iterate through every graph of the node map, starting with the default graph (named
+	/// `None`).
+	pub fn graphs(&self) -> impl Iterator<Item = (Option<&Reference<T>>, &NodeMapGraph<T>)> {
+		std::iter::once((None, &self.default_graph)).chain(self.graphs.iter().map(|(name, graph)| (Some(name), graph)))
+	}
+
+	/// Get the flattened node with the given identifier in the given graph.
+	///
+	/// The default graph is selected using `None`.
+	pub fn get<R: ToReference<T>>(&self, graph: Option<&Reference<T>>, id: R) -> Option<&Indexed<Node<T>>> {
+		match self.graph(graph) {
+			Some(graph) => graph.get(id),
+			None => None
+		}
+	}
+
+	/// Consume the node map and return its components: the default graph and the named graphs.
+	pub fn into_parts(self) -> (NodeMapGraph<T>, HashMap<Reference<T>, NodeMapGraph<T>>) {
+		(self.default_graph, self.graphs)
+	}
+
+	/// Get the graph with the given name, creating it if necessary.
+	fn graph_mut(&mut self, name: Option<&Reference<T>>) -> &mut NodeMapGraph<T> {
+		match name {
+			Some(name) => {
+				if !self.graphs.contains_key(name) {
+					self.graphs.insert(name.clone(), NodeMapGraph::new());
+				}
+
+				self.graphs.get_mut(name).unwrap()
+			},
+			None => &mut self.default_graph
+		}
+	}
+}
+
+impl<T: Id> NodeStore<T> for NodeMap<T> {
+	fn insert_graph(&mut self, name: &Reference<T>) {
+		self.graph_mut(Some(name));
+	}
+
+	fn update<R, F: FnOnce(&mut Indexed<Node<T>>) -> R>(&mut self, graph: Option<&Reference<T>>, id: &Reference<T>, f: F) -> R {
+		f(self.graph_mut(graph).node_mut(id))
+	}
+
+	fn graph_names(&self) -> Vec<Reference<T>> {
+		self.graphs.keys().cloned().collect()
+	}
+
+	fn node_ids(&self, graph: Option<&Reference<T>>) -> Vec<Reference<T>> {
+		self.graph(graph).map(|graph| graph.nodes.keys().cloned().collect()).unwrap_or_default()
+	}
+
+	fn get(&self, graph: Option<&Reference<T>>, id: &Reference<T>) -> Option<Indexed<Node<T>>> {
+		NodeMap::get(self, graph, id).cloned()
+	}
+}
+
+impl<T: Id> Default for NodeMap<T> {
+	fn default() -> NodeMap<T> {
+		Self::new()
+	}
+}
+
+impl<T: Id> AsJson for NodeMap<T> {
+	/// Returns a JSON object mapping each graph name (`@default` for the default graph) to the
+	/// graph nodes indexed by identifier.
+	fn as_json(&self) -> JsonValue {
+		let mut obj = json::object::Object::new();
+		obj.insert("@default", self.default_graph.as_json());
+
+		let mut graphs: Vec<_> = self.graphs.iter().collect();
+		graphs.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		for (name, graph) in graphs {
+			obj.insert(name.as_str(), graph.as_json())
+		}
+
+		JsonValue::Object(obj)
+	}
+}
+
+/// Blank node identifiers renaming.
+///
+/// Implements the [Generate Blank Node Identifier](https://www.w3.org/TR/json-ld11-api/#generate-blank-node-identifier)
+/// algorithm on top of a [`Generator`].
+///
+/// Nodes whose identifier is neither an IRI nor a blank node identifier are stored under a
+/// generated blank node identifier, the same for every occurrence of the original identifier.
+struct BlankIdMap<G: Generator> {
+	generator: G,
+	map: HashMap<BlankId, BlankId>,
+
+	/// Generated identifiers of the malformed identifiers.
+	unknown: HashMap<String, BlankId>,
+
+	/// Malformed identifiers of the generated identifiers.
+	names: HashMap<BlankId, String>
+}
+
+impl<G: Generator> BlankIdMap<G> {
+	fn new(generator: G) -> BlankIdMap<G> {
+		BlankIdMap {
+			generator,
+			map: HashMap::new(),
+			unknown: HashMap::new(),
+			names: HashMap::new()
+		}
+	}
+
+	/// Get the new identifier assigned to the given blank node identifier.
+	fn assign(&mut self, id: &BlankId) -> BlankId {
+		match self.map.get(id) {
+			Some(new_id) => new_id.clone(),
+			None => {
+				let new_id = self.generator.next();
+				self.map.insert(id.clone(), new_id.clone());
+				new_id
+			}
+		}
+	}
+
+	fn assign_reference<T: Id>(&mut self, r: &Reference<T>) -> Reference<T> {
+		match r {
+			Reference::Blank(id) => Reference::Blank(self.assign(id)),
+			r => r.clone()
+		}
+	}
+
+	fn assign_lenient<T: Id>(&mut self, r: &Lenient<Reference<T>>) -> Lenient<Reference<T>> {
+		match r {
+			Lenient::Ok(r) => Lenient::Ok(self.assign_reference(r)),
+			Lenient::Unknown(u) => Lenient::Unknown(u.clone())
+		}
+	}
+
+	/// Get the identifier under which the node with the given malformed identifier is stored.
+	fn assign_unknown(&mut self, id: &str) -> BlankId {
+		match self.unknown.get(id) {
+			Some(new_id) => new_id.clone(),
+			None => {
+				let new_id = self.generator.next();
+				self.unknown.insert(id.to_string(), new_id.clone());
+				self.names.insert(new_id.clone(), id.to_string());
+				new_id
+			}
+		}
+	}
+
+	/// Get the identifier of the node stored under the given identifier.
+	fn original<T: Id>(&self, id: &Reference<T>) -> Lenient<Reference<T>> {
+		match id {
+			Reference::Blank(blank) => match self.names.get(blank) {
+				Some(name) => Lenient::Unknown(name.clone()),
+				None => Lenient::Ok(id.clone())
+			},
+			id => Lenient::Ok(id.clone())
+		}
+	}
+
+	/// Create a reference to the node stored under the given identifier.
+	fn reference<T: Id>(&self, id: &Reference<T>) -> Indexed<Object<T>> {
+		let mut node = Node::new();
+		node.id = Some(self.original(id));
+		Object::Node(Box::new(node)).into()
+	}
+
+	/// Generate a fresh blank node identifier.
+	fn generate(&mut self) -> BlankId {
+		self.generator.next()
+	}
+}
+
+/// How a node is linked to the node being processed.
+enum Link<'a, T: Id> {
+	/// Top-level node, or node included in another with `@included`.
+	None,
+
+	/// The subject node refers to the node through the property.
+	Forward(&'a Reference<T>, &'a Reference<T>),
+
+	/// The node refers to the subject node through the property (reverse property).
+	Reverse(&'a Reference<T>, &'a Reference<T>)
+}
+
+/// Node Map Generation algorithm.
+///
+/// Collects all the nodes of the given expanded document into a node map.
+/// Every blank node identifier is renamed using the given generator, and nodes without
+/// identifier are assigned a newly generated blank node identifier.
+/// Nodes whose identifier is not a valid IRI or blank node identifier (such as a relative IRI
+/// without base IRI) keep it, but are stored under a generated blank node identifier.
+///
+/// Fails with a [`ErrorCode::ConflictingIndexes`] error if the same node is given different
+/// indexes.
+///
+/// See <https://www.w3.org/TR/json-ld11-api/#node-map-generation>.
+pub fn node_map<T: Id, G: Generator>(document: &ExpandedDocument<T>, generator: G) -> Result<NodeMap<T>, Error> {
+	let mut map = NodeMap::new();
+	node_map_into(document, generator, &mut map)?;
+	Ok(map)
+}
+
+/// Node Map Generation algorithm, storing the nodes in the given node store.
+///
+/// This is the same as [`node_map`], but the nodes can be stored outside of memory
+/// (see [`NodeStore`]).
+pub fn node_map_into<T: Id, G: Generator, S: NodeStore<T>>(document: &ExpandedDocument<T>, generator: G, store: &mut S) -> Result<(), Error> {
+	let mut ids = BlankIdMap::new(generator);
+
+	for object in document {
+		extend_object(store, &mut ids, object, None, None, None)?
+	}
+
+	Ok(())
+}
+
+/// Add a value to a property of the node, unless it is already there.
+fn insert_unique<T: Id>(node: &mut Node<T>, prop: &Reference<T>, value: Indexed<Object<T>>) {
+	match node.properties.get_mut(prop) {
+		Some(values) => {
+			if !values.contains(&value) {
+				values.push(value)
+			}
+		},
+		None => {
+			node.properties.insert(prop.clone(), smallvec![value]);
+		}
+	}
+}
+
+fn extend_object<T: Id, G: Generator, S: NodeStore<T>>(map: &mut S, ids: &mut BlankIdMap<G>, object: &Indexed<Object<T>>, active_graph: Option<&Reference<T>>, subject: Option<(&Reference<T>, &Reference<T>)>, list: Option<&mut Vec<Indexed<Object<T>>>>) -> Result<(), Error> {
+	let index = object.index().map(|index| index.to_string());
+
+	match object.inner() {
+		// If element has an @value entry:
+		Object::Value(value) => {
+			let value = Indexed::new(Object::Value(value.clone()), index);
+			match list {
+				// If list is null, add element to the active property entry of the subject node,
+				// unless it is already there.
+				None => {
+					if let Some((subject, prop)) = subject {
+						map.update(active_graph, subject, |node| insert_unique(node, prop, value))
+					}
+				},
+				// Otherwise, append element to the @list entry of list.
+				Some(list) => list.push(value)
+			}
+		},
+		// Otherwise, if element has an @list entry:
+		Object::List(items) => {
+			let mut result = Vec::with_capacity(items.len());
+			for item in items {
+				extend_object(map, ids, item, active_graph, subject, Some(&mut result))?
+			}
+
+			let result = Indexed::new(Object::List(result), index);
+			match list {
+				// If list is null, append result to the active property entry of the subject node.
+				None => {
+					if let Some((subject, prop)) = subject {
+						map.update(active_graph, subject, |node| node.insert(prop.clone(), result))
+					}
+				},
+				// Otherwise, append result to the @list entry of list.
+				Some(list) => list.push(result)
+			}
+		},
+		// Otherwise element is a node object.
+		Object::Node(node) => {
+			let link = match subject {
+				Some((subject, prop)) => Link::Forward(subject, prop),
+				None => Link::None
+			};
+
+			extend_node(map, ids, node, object.index(), active_graph, link, list)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn extend_node<T: Id, G: Generator, S: NodeStore<T>>(map: &mut S, ids: &mut BlankIdMap<G>, node: &Node<T>, index: Option<&str>, active_graph: Option<&Reference<T>>, link: Link<T>, list: Option<&mut Vec<Indexed<Object<T>>>>) -> Result<Reference<T>, Error> {
+	// If element has an @id entry, set id to its value and remove the entry.
+	// If id is a blank node identifier, replace it with a newly generated blank node identifier.
+	// Otherwise, set id to the result of the Generate Blank Node Identifier algorithm.
+	let id = match &node.id {
+		Some(Lenient::Ok(id)) => ids.assign_reference(id),
+		Some(Lenient::Unknown(id)) => Reference::Blank(ids.assign_unknown(id)),
+		None => Reference::Blank(ids.generate())
+	};
+
+	// If graph does not contain an entry id, create one.
+	let original = ids.original(&id);
+	map.update(active_graph, &id, |flat_node| flat_node.id = Some(original));
+
+	match link {
+		Link::None => (),
+		// If active subject is a map, add active subject as a reference to the active property
+		// entry of node, unless it is already there.
+		Link::Reverse(subject, prop) => {
+			let reference = ids.reference(subject);
+			map.update(active_graph, &id, |node| insert_unique(node, prop, reference))
+		},
+		// Otherwise, if active property is not null, create a node reference to id.
+		Link::Forward(subject, prop) => {
+			let reference = ids.reference(&id);
+			match list {
+				// If list is null, add reference to the active property entry of the subject node,
+				// unless it is already there.
+				None => map.update(active_graph, subject, |node| insert_unique(node, prop, reference)),
+				// Otherwise, append reference to the @list entry of list.
+				Some(list) => list.push(reference)
+			}
+		}
+	}
+
+	map.update(active_graph, &id, |flat_node| {
+		// If element has an @type entry, add each of its items to the @type entry of node, unless
+		// it is already there.
+		for ty in &node.types {
+			let ty = ids.assign_lenient(ty);
+			if !flat_node.types.contains(&ty) {
+				flat_node.types.push(ty)
+			}
+		}
+
+		// If element has an @index entry:
+		if let Some(index) = index {
+			match flat_node.index() {
+				// If node has an @index entry with a different value, a conflicting indexes error
+				// has been detected.
+				Some(flat_index) if flat_index != index => {
+					return Err(ErrorCode::ConflictingIndexes.into())
+				},
+				Some(_) => (),
+				None => flat_node.set_index(Some(index.to_string()))
+			}
+		}
+
+		Ok::<_, Error>(())
+	})?;
+
+	// If element has an @reverse entry, for each reverse property, for each value, recursively
+	// invoke this algorithm passing a reference to id for active subject.
+	for (prop, values) in &node.reverse_properties {
+		let prop = ids.assign_reference(prop);
+		for value in values {
+			extend_node(map, ids, value, value.index(), active_graph, Link::Reverse(&id, &prop), None)?;
+		}
+	}
+
+	// If element has an @graph entry, recursively invoke this algorithm passing id for active
+	// graph.
+	if let Some(graph) = node.graph() {
+		map.insert_graph(&id);
+		for object in graph {
+			extend_object(map, ids, object, Some(&id), None, None)?
+		}
+	}
+
+	// If element has an @included entry, recursively invoke this algorithm in the active graph.
+	if let Some(included) = node.included() {
+		for included_node in included {
+			extend_node(map, ids, included_node, included_node.index(), active_graph, Link::None, None)?;
+		}
+	}
+
+	// Finally, for each property and value in element:
+	for (prop, values) in &node.properties {
+		// If property is a blank node identifier, replace it with a newly generated blank node
+		// identifier.
+		let prop = ids.assign_reference(prop);
+
+		// If node does not have a property entry, create one and initialize its value to an
+		// empty array.
+		map.update(active_graph, &id, |node| { node.properties.entry(prop.clone()).or_default(); });
+
+		// Recursively invoke this algorithm, passing id for active subject and property for
+		// active property.
+		for value in values {
+			extend_object(map, ids, value, active_graph, Some((&id, &prop)), None)?
+		}
+	}
+
+	Ok(id)
+}
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::{
+	Id,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Node
+};
+
+/// Chunk of a flattened graph.
+///
+/// Result of [`chunks`].
+pub struct Chunk<'a, T: Id> {
+	/// Nodes of the chunk.
+	///
+	/// Each node of the graph belongs to exactly one chunk.
+	pub nodes: Vec<&'a Indexed<Node<T>>>,
+
+	/// Nodes of other chunks referenced by the nodes of the chunk, directly or through other
+	/// referenced nodes, up to the closure depth.
+	pub closure: Vec<&'a Indexed<Node<T>>>
+}
+
+impl<'a, T: Id> Chunk<'a, T> {
+	/// Iterate through the nodes of the chunk, followed by its closure.
+	pub fn iter(&self) -> impl Iterator<Item = &'a Indexed<Node<T>>> + '_ {
+		self.nodes.iter().chain(&self.closure).copied()
+	}
+}
+
+/// Collect the identifiers of the nodes referenced by the given objects.
+fn collect_references<'a, T: Id, I: IntoIterator<Item = &'a Indexed<Object<T>>>>(objects: I, references: &mut Vec<&'a Reference<T>>) {
+	for object in objects {
+		match object.inner() {
+			Object::Node(node) => if let Some(Lenient::Ok(id)) = &node.id {
+				references.push(id)
+			},
+			Object::List(items) => collect_references(items, references),
+			Object::Value(_) => ()
+		}
+	}
+}
+
+/// Positions of the nodes referenced by each node of the graph.
+///
+/// References to nodes outside of the graph are ignored.
+fn references<T: Id>(nodes: &[Indexed<Node<T>>]) -> Vec<Vec<usize>> {
+	let positions: HashMap<&Reference<T>, usize> = nodes.iter().enumerate().filter_map(|(i, node)| match &node.id {
+		Some(Lenient::Ok(id)) => Some((id, i)),
+		_ => None
+	}).collect();
+
+	nodes.iter().map(|node| {
+		let mut references = Vec::new();
+		collect_references(node.properties.values().flatten(), &mut references);
+		let mut targets: Vec<usize> = references.into_iter().filter_map(|id| positions.get(id).copied()).collect();
+		targets.sort_unstable();
+		targets.dedup();
+		targets
+	}).collect()
+}
+
+/// Connected components of the graph, as lists of node positions in breadth-first order.
+///
+/// Components are sorted by their first node, in graph order.
+fn component_positions(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+	// Edges are followed in both directions.
+	let mut neighbors = edges.to_vec();
+	for (i, targets) in edges.iter().enumerate() {
+		for &j in targets {
+			if j != i {
+				neighbors[j].push(i)
+			}
+		}
+	}
+
+	let mut visited = vec![false; edges.len()];
+	let mut components = Vec::new();
+	for start in 0..edges.len() {
+		if !visited[start] {
+			visited[start] = true;
+			let mut component = Vec::new();
+			let mut queue = VecDeque::new();
+			queue.push_back(start);
+			while let Some(i) = queue.pop_front() {
+				component.push(i);
+				for &j in &neighbors[i] {
+					if !visited[j] {
+						visited[j] = true;
+						queue.push_back(j)
+					}
+				}
+			}
+
+			components.push(component)
+		}
+	}
+
+	components
+}
+
+/// Split the nodes of a flattened graph into its connected components.
+///
+/// Two nodes are connected when one is the value of a property of the other, possibly
+/// in a list. Types are not considered as references.
+/// A component can then be processed independently of the others, since none of its nodes
+/// refer to a node of another component.
+/// Components are sorted by their first node in the graph, and the nodes of each component
+/// keep their order in the graph.
+/// ```ignore
+/// for component in flattening::components(flattened.default_graph()) {
+///     index.push(component)
+/// }
+/// ```
+pub fn components<T: Id>(nodes: &[Indexed<Node<T>>]) -> Vec<Vec<&Indexed<Node<T>>>> {
+	component_positions(&references(nodes)).into_iter().map(|mut component| {
+		component.sort_unstable();
+		component.into_iter().map(|i| &nodes[i]).collect()
+	}).collect()
+}
+
+/// Split the nodes of a flattened graph into chunks of at most `max_size` nodes, for
+/// parallel processing.
+///
+/// Connected [components](components) are packed together in the order of the graph, and
+/// only split when they are larger than `max_size`, in breadth-first order so that
+/// connected nodes tend to stay in the same chunk.
+/// The [closure](Chunk::closure) of each chunk holds the nodes of other chunks it refers to,
+/// following references up to `depth` times (none for `0`), so that a chunk can be indexed
+/// without looking up the rest of the graph.
+///
+/// ## Panics
+///
+/// Panics if `max_size` is `0`.
+pub fn chunks<T: Id>(nodes: &[Indexed<Node<T>>], max_size: usize, depth: usize) -> Vec<Chunk<'_, T>> {
+	assert!(max_size > 0, "chunks must have a positive size");
+
+	let edges = references(nodes);
+	let mut parts: Vec<Vec<usize>> = Vec::new();
+	for component in component_positions(&edges) {
+		match parts.last_mut() {
+			Some(part) if part.len() + component.len() <= max_size => part.extend(component),
+			_ => parts.extend(component.chunks(max_size).map(<[usize]>::to_vec))
+		}
+	}
+
+	parts.into_iter().map(|mut part| {
+		part.sort_unstable();
+
+		let members: HashSet<usize> = part.iter().copied().collect();
+		let mut reached = HashSet::new();
+		let mut closure = Vec::new();
+		let mut frontier = part.clone();
+		for _ in 0..depth {
+			let mut next = Vec::new();
+			for i in frontier {
+				for &j in &edges[i] {
+					if !members.contains(&j) && reached.insert(j) {
+						next.push(j)
+					}
+				}
+			}
+
+			closure.extend(next.iter().copied());
+			frontier = next
+		}
+
+		closure.sort_unstable();
+
+		Chunk {
+			nodes: part.into_iter().map(|i| &nodes[i]).collect(),
+			closure: closure.into_iter().map(|i| &nodes[i]).collect()
+		}
+	}).collect()
+}
@@ -0,0 +1,79 @@
+use crate::{
+	Id,
+	Reference,
+	Indexed,
+	Node
+};
+use super::is_only_id;
+
+/// Storage of the nodes of a node map.
+///
+/// The [Node Map Generation algorithm](super::node_map_into) only accesses the nodes it builds
+/// through this trait, one at a time, and [`flatten_store`] reads them back one at a time,
+/// so that these algorithms can run over datasets too large for memory with a store backed
+/// by an on-disk key-value store.
+/// [`NodeMap`](super::NodeMap) is the default, in-memory implementation.
+pub trait NodeStore<T: Id> {
+	/// Add a named graph, unless it is already there.
+	fn insert_graph(&mut self, name: &Reference<T>);
+
+	/// Modify the node with the given identifier in the given graph (`None` being the default
+	/// graph), creating the graph and the node if necessary.
+	///
+	/// A new node only has an identifier.
+	/// Stores serializing their nodes must write back the modified node.
+	fn update<R, F: FnOnce(&mut Indexed<Node<T>>) -> R>(&mut self, graph: Option<&Reference<T>>, id: &Reference<T>, f: F) -> R;
+
+	/// Names of the named graphs, in any order.
+	fn graph_names(&self) -> Vec<Reference<T>>;
+
+	/// Identifiers of the nodes of the given graph, in any order.
+	fn node_ids(&self, graph: Option<&Reference<T>>) -> Vec<Reference<T>>;
+
+	/// Get a copy of the node with the given identifier in the given graph.
+	fn get(&self, graph: Option<&Reference<T>>, id: &Reference<T>) -> Option<Indexed<Node<T>>>;
+}
+
+/// Sort references by identifier.
+fn sorted<T: Id>(mut ids: Vec<Reference<T>>) -> Vec<Reference<T>> {
+	ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	ids
+}
+
+/// Flattening algorithm over a node store.
+///
+/// Calls `f` on each node of the flattened document, with the name of its graph, in the
+/// order of [`FlattenedDocument::graphs`](super::FlattenedDocument::graphs): the nodes of the
+/// default graph sorted by identifier, followed by the nodes of each named graph, sorted by
+/// name then identifier. This is the same as [`flatten`](super::flatten), without collecting
+/// the nodes in memory.
+pub fn flatten_store<T: Id, S: NodeStore<T>, F: FnMut(Option<&Reference<T>>, Indexed<Node<T>>)>(store: &S, mut f: F) {
+	let names = sorted(store.graph_names());
+
+	// Each named graph is attached to the node of the default graph identified by its name,
+	// created if necessary.
+	let mut ids = store.node_ids(None);
+	for name in &names {
+		if !ids.contains(name) {
+			ids.push(name.clone())
+		}
+	}
+
+	for id in sorted(ids) {
+		let node = store.get(None, &id).unwrap_or_else(|| Node::with_id(id.clone()).into());
+
+		if !is_only_id(&node) || names.contains(&id) {
+			f(None, node)
+		}
+	}
+
+	for name in &names {
+		for id in sorted(store.node_ids(Some(name))) {
+			if let Some(node) = store.get(Some(name), &id) {
+				if !is_only_id(&node) {
+					f(Some(name), node)
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,69 @@
+//! Blank node identifier generators.
+
+use crate::BlankId;
+
+/// Blank node identifier generator.
+///
+/// Some algorithms (such as the dataset merge operation) need to create fresh blank node
+/// identifiers. This trait abstracts the way those identifiers are generated.
+pub trait Generator {
+	/// Generate a new blank node identifier.
+	fn next(&mut self) -> BlankId;
+}
+
+/// Counter-based blank node identifier generator.
+///
+/// Generates the identifiers `_:<prefix>0`, `_:<prefix>1`, `_:<prefix>2`, etc.
+/// The default prefix is `b`, as in the JSON-LD specification.
+pub struct Blank {
+	/// Prefix of every generated identifier name.
+	prefix: String,
+
+	/// Number of already generated identifiers.
+	count: usize
+}
+
+impl Blank {
+	/// Create a new generator using the `b` prefix.
+	pub fn new() -> Blank {
+		Self::new_with_prefix("b".to_string())
+	}
+
+	/// Create a new generator using the given prefix.
+	pub fn new_with_prefix(prefix: String) -> Blank {
+		Blank {
+			prefix,
+			count: 0
+		}
+	}
+
+	/// Get the prefix of the generated identifiers.
+	pub fn prefix(&self) -> &str {
+		&self.prefix
+	}
+
+	/// Number of identifiers generated so far.
+	pub fn count(&self) -> usize {
+		self.count
+	}
+}
+
+impl Default for Blank {
+	fn default() -> Blank {
+		Self::new()
+	}
+}
+
+impl Generator for Blank {
+	fn next(&mut self) -> BlankId {
+		let id = BlankId::new(&format!("{}{}", self.prefix, self.count));
+		self.count += 1;
+		id
+	}
+}
+
+impl<G: Generator> Generator for &mut G {
+	fn next(&mut self) -> BlankId {
+		G::next(*self)
+	}
+}
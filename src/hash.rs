@@ -0,0 +1,126 @@
+//! Content hashes.
+//!
+//! Hashes are written in the style of [Subresource Integrity](https://www.w3.org/TR/SRI/)
+//! metadata, as the name of the algorithm followed by the base64 encoded digest:
+//! ```ignore
+//! let hash = Hash::of(Algorithm::Sha256, content.as_bytes());
+//! assert_eq!(hash, "sha256-...".parse()?);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use json::JsonValue;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Hash algorithm.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Algorithm {
+	Sha256,
+	Sha384,
+	Sha512
+}
+
+impl Algorithm {
+	/// Algorithm name, as used in integrity metadata.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Algorithm::Sha256 => "sha256",
+			Algorithm::Sha384 => "sha384",
+			Algorithm::Sha512 => "sha512"
+		}
+	}
+
+	fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+		match self {
+			Algorithm::Sha256 => Sha256::digest(bytes).to_vec(),
+			Algorithm::Sha384 => Sha384::digest(bytes).to_vec(),
+			Algorithm::Sha512 => Sha512::digest(bytes).to_vec()
+		}
+	}
+}
+
+/// Content hash.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Hash {
+	algorithm: Algorithm,
+
+	/// Base64 encoded digest.
+	digest: String
+}
+
+impl Hash {
+	/// Hash the given bytes.
+	pub fn of(algorithm: Algorithm, bytes: &[u8]) -> Hash {
+		Hash {
+			algorithm,
+			digest: base64::encode(algorithm.digest(bytes))
+		}
+	}
+
+	/// Hash the compact JSON serialization of the given document.
+	///
+	/// This is the hash of the raw content only if the document was served in its compact
+	/// form: it is meant to pin documents whose raw content is not available.
+	pub fn of_document(algorithm: Algorithm, doc: &JsonValue) -> Hash {
+		Hash::of(algorithm, doc.dump().as_bytes())
+	}
+
+	/// Hash algorithm.
+	pub fn algorithm(&self) -> Algorithm {
+		self.algorithm
+	}
+
+	/// Base64 encoded digest.
+	pub fn digest(&self) -> &str {
+		&self.digest
+	}
+
+	/// Hexadecimal encoded digest.
+	pub fn to_hex(&self) -> String {
+		base64::decode(&self.digest).unwrap().iter().map(|b| format!("{:02x}", b)).collect()
+	}
+}
+
+impl fmt::Display for Hash {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}-{}", self.algorithm.as_str(), self.digest)
+	}
+}
+
+/// Invalid integrity metadata.
+#[derive(Debug)]
+pub struct InvalidHash(pub String);
+
+impl fmt::Display for InvalidHash {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid integrity metadata `{}`", self.0)
+	}
+}
+
+impl std::error::Error for InvalidHash {}
+
+impl FromStr for Hash {
+	type Err = InvalidHash;
+
+	fn from_str(s: &str) -> Result<Hash, InvalidHash> {
+		let invalid = || InvalidHash(s.to_string());
+		let dash = s.find('-').ok_or_else(invalid)?;
+		let (algorithm, len) = match &s[..dash] {
+			"sha256" => (Algorithm::Sha256, 32usize),
+			"sha384" => (Algorithm::Sha384, 48),
+			"sha512" => (Algorithm::Sha512, 64),
+			_ => return Err(invalid())
+		};
+
+		let digest = &s[(dash + 1)..];
+		match base64::decode(digest) {
+			Ok(bytes) if bytes.len() == len && digest.len() == len.div_ceil(3) * 4 => (),
+			_ => return Err(invalid())
+		}
+
+		Ok(Hash {
+			algorithm,
+			digest: digest.to_string()
+		})
+	}
+}
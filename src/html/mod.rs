@@ -0,0 +1,325 @@
+//! HTML script extraction.
+//!
+//! JSON-LD documents can be embedded in HTML documents using `<script>` elements of type
+//! `application/ld+json`.
+//! This module implements the extraction steps of the
+//! [JSON-LD API](https://www.w3.org/TR/json-ld11-api/#process-html), using a lightweight
+//! HTML scanner that only looks for `<script>` and `<base>` elements.
+//!
+//! The extracted document is returned as a [`RemoteDocument`] holding the document base URL,
+//! ready to be expanded:
+//! ```ignore
+//! let doc = html::extract(&html, url, html::Options::default())?;
+//! let expanded = doc.expand(&context, &mut loader).await?;
+//! ```
+//!
+//! For structured data testing, the [`report`] function expands each JSON-LD script element
+//! separately and reports the types it defines along with any warnings.
+
+use iref::{Iri, IriRef, IriBuf};
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	RemoteDocument,
+	util::parse_json
+};
+
+mod report;
+
+pub use report::*;
+
+/// JSON-LD script media type.
+pub const MEDIA_TYPE: &str = "application/ld+json";
+
+/// Extraction options.
+#[derive(Clone, Copy, Default)]
+pub struct Options {
+	/// If set to `true`, every JSON-LD script of the HTML document is extracted (and the
+	/// result is an array), otherwise only the first one is.
+	///
+	/// This is the `extractAllScripts` option of the JSON-LD API.
+	/// It is ignored if the document URL has a fragment identifier.
+	pub extract_all_scripts: bool
+}
+
+/// HTML script element.
+pub struct Script<'a> {
+	/// Value of the `id` attribute, if any.
+	pub id: Option<String>,
+
+	/// Value of the `type` attribute, if any.
+	pub media_type: Option<String>,
+
+	/// Script content.
+	pub content: &'a str
+}
+
+impl<'a> Script<'a> {
+	/// Checks if this is a JSON-LD script element.
+	///
+	/// Parameters of the `type` attribute (such as a `profile`) are ignored.
+	pub fn is_json_ld(&self) -> bool {
+		match &self.media_type {
+			Some(ty) => ty.split(';').next().unwrap().trim().eq_ignore_ascii_case(MEDIA_TYPE),
+			None => false
+		}
+	}
+
+	/// Parse the content of the script as JSON.
+	///
+	/// Returns an `InvalidScriptElement` error if the content is not valid JSON.
+	pub fn parse(&self) -> Result<JsonValue, Error> {
+		parse_json(self.content).map_err(|_| ErrorCode::InvalidScriptElement.into())
+	}
+}
+
+/// Scanned HTML document.
+struct Scan<'a> {
+	base: Option<String>,
+	scripts: Vec<Script<'a>>
+}
+
+impl<'a> Scan<'a> {
+	/// Base URL of the document, given by the first `<base>` element, if any, resolved against
+	/// the document URL.
+	fn base_url(&self, url: Iri) -> IriBuf {
+		match self.base.as_ref().and_then(|href| IriRef::new(href).ok()) {
+			Some(href) => href.resolved(url),
+			None => url.into()
+		}
+	}
+}
+
+/// Elements whose content is raw text, that must be skipped while scanning.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes"];
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+	let needle = needle.as_bytes();
+	haystack.as_bytes().windows(needle.len()).position(|w| w.eq_ignore_ascii_case(needle))
+}
+
+/// Decode the character references of an attribute value.
+fn decode_entities(value: &str) -> String {
+	let mut result = String::with_capacity(value.len());
+	let mut rest = value;
+	while let Some(i) = rest.find('&') {
+		result.push_str(&rest[..i]);
+		rest = &rest[i..];
+
+		let decoded = rest.find(';').and_then(|end| {
+			let name = &rest[1..end];
+			let c = match name {
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				_ if name.starts_with("#x") || name.starts_with("#X") => u32::from_str_radix(&name[2..], 16).ok().and_then(std::char::from_u32),
+				_ if name.starts_with('#') => name[1..].parse().ok().and_then(std::char::from_u32),
+				_ => None
+			};
+
+			c.map(|c| (c, end + 1))
+		});
+
+		match decoded {
+			Some((c, len)) => {
+				result.push(c);
+				rest = &rest[len..]
+			},
+			None => {
+				result.push('&');
+				rest = &rest[1..]
+			}
+		}
+	}
+
+	result.push_str(rest);
+	result
+}
+
+/// Parse the attributes of a start tag, until the end of the tag.
+///
+/// Returns the attributes and the length of the parsed input, including the closing `>`.
+fn parse_attributes(input: &str) -> (Vec<(String, String)>, usize) {
+	let bytes = input.as_bytes();
+	let mut attributes = Vec::new();
+	let mut i = 0;
+
+	loop {
+		while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+			i += 1
+		}
+
+		if i >= bytes.len() {
+			return (attributes, i)
+		}
+
+		if bytes[i] == b'>' {
+			return (attributes, i + 1)
+		}
+
+		let name_start = i;
+		while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'=' && bytes[i] != b'>' && bytes[i] != b'/' {
+			i += 1
+		}
+		let name = input[name_start..i].to_ascii_lowercase();
+
+		while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+			i += 1
+		}
+
+		let mut value = String::new();
+		if i < bytes.len() && bytes[i] == b'=' {
+			i += 1;
+			while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+				i += 1
+			}
+
+			if i < bytes.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+				let quote = bytes[i];
+				let value_start = i + 1;
+				i = value_start;
+				while i < bytes.len() && bytes[i] != quote {
+					i += 1
+				}
+				value = decode_entities(&input[value_start..i]);
+				i = std::cmp::min(i + 1, bytes.len())
+			} else {
+				let value_start = i;
+				while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+					i += 1
+				}
+				value = decode_entities(&input[value_start..i])
+			}
+		}
+
+		attributes.push((name, value))
+	}
+}
+
+fn attribute(attributes: &[(String, String)], name: &str) -> Option<String> {
+	attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone())
+}
+
+/// Scan the given HTML document for `<script>` and `<base>` elements.
+fn scan(html: &str) -> Scan<'_> {
+	let mut result = Scan {
+		base: None,
+		scripts: Vec::new()
+	};
+
+	let mut i = 0;
+	while let Some(offset) = html[i..].find('<') {
+		i += offset;
+		let rest = &html[i..];
+
+		if let Some(comment) = rest.strip_prefix("<!--") {
+			i += match comment.find("-->") {
+				Some(end) => 4 + end + 3,
+				None => rest.len()
+			};
+			continue
+		}
+
+		if rest.starts_with("<!") || rest.starts_with("<?") || rest.starts_with("</") {
+			i += match rest.find('>') {
+				Some(end) => end + 1,
+				None => rest.len()
+			};
+			continue
+		}
+
+		let name_len = rest[1..].find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len() - 1);
+		if name_len == 0 {
+			i += 1;
+			continue
+		}
+
+		let name = rest[1..(1 + name_len)].to_ascii_lowercase();
+		let (attributes, attributes_len) = parse_attributes(&rest[(1 + name_len)..]);
+		i += 1 + name_len + attributes_len;
+
+		if name == "base" && result.base.is_none() {
+			result.base = attribute(&attributes, "href")
+		}
+
+		if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+			let content_start = i;
+			let content_end = match find_ignore_case(&html[i..], &format!("</{}", name)) {
+				Some(end) => content_start + end,
+				None => html.len()
+			};
+
+			if name == "script" {
+				result.scripts.push(Script {
+					id: attribute(&attributes, "id"),
+					media_type: attribute(&attributes, "type"),
+					content: &html[content_start..content_end]
+				})
+			}
+
+			i = match html[content_end..].find('>') {
+				Some(end) => content_end + end + 1,
+				None => html.len()
+			}
+		}
+	}
+
+	result
+}
+
+/// List every script element of the given HTML document.
+pub fn scripts(html: &str) -> Vec<Script<'_>> {
+	scan(html).scripts
+}
+
+/// Extract the JSON-LD content of the given HTML document located at the given URL.
+///
+/// If the URL has a fragment identifier, the script element whose `id` is the fragment is
+/// extracted.
+/// Otherwise, the first JSON-LD script element is extracted, or all of them if the
+/// [`extract_all_scripts`](Options::extract_all_scripts) option is set.
+/// The base URL of the returned document is given by the first `<base>` element of the
+/// HTML document, if any, resolved against the document URL.
+///
+/// Returns a `LoadingDocumentFailed` error if the requested script element cannot be found, and
+/// an `InvalidScriptElement` error if the content of an extracted script element is not valid
+/// JSON.
+pub fn extract(html: &str, url: Iri, options: Options) -> Result<RemoteDocument, Error> {
+	let scan = scan(html);
+	let base_url = scan.base_url(url);
+
+	let document = match url.fragment() {
+		Some(fragment) => {
+			let fragment = fragment.as_str();
+			match scan.scripts.iter().find(|script| script.id.as_deref() == Some(fragment)) {
+				Some(script) if script.is_json_ld() => script.parse()?,
+				_ => return Err(ErrorCode::LoadingDocumentFailed.into())
+			}
+		},
+		None => {
+			let mut scripts = scan.scripts.iter().filter(|script| script.is_json_ld());
+
+			if options.extract_all_scripts {
+				let mut result = Vec::new();
+				for script in scripts {
+					match script.parse()? {
+						JsonValue::Array(items) => result.extend(items),
+						item => result.push(item)
+					}
+				}
+
+				JsonValue::Array(result)
+			} else {
+				match scripts.next() {
+					Some(script) => script.parse()?,
+					None => return Err(ErrorCode::LoadingDocumentFailed.into())
+				}
+			}
+		}
+	};
+
+	Ok(RemoteDocument::new(document, base_url.as_iri()))
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::fmt;
+use iref::{Iri, IriBuf};
+use crate::{
+	Id,
+	ErrorCode,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Document,
+	ContextMut,
+	ExpandedDocument,
+	context::Loader,
+	shape::{
+		Shape,
+		Violation
+	}
+};
+use super::scan;
+
+/// Structured data report of an HTML document.
+pub struct Report<T: Id = IriBuf> {
+	/// Base URL of the HTML document.
+	pub base_url: IriBuf,
+
+	/// JSON-LD script elements, in document order.
+	pub blocks: Vec<Block<T>>
+}
+
+impl<T: Id> Report<T> {
+	/// Every type defined by the document, without duplicates.
+	pub fn types(&self) -> Vec<&Reference<T>> {
+		let mut types = BTreeMap::new();
+		for block in &self.blocks {
+			for ty in &block.types {
+				types.insert(ty.as_str(), ty);
+			}
+		}
+
+		types.into_values().collect()
+	}
+
+	/// Checks if the document defines a node with the given type.
+	pub fn has_type(&self, ty: &str) -> bool {
+		self.blocks.iter().any(|block| block.types.iter().any(|t| t.as_str() == ty))
+	}
+
+	/// Iterate through the warnings of every block.
+	pub fn warnings(&self) -> impl Iterator<Item = &Warning<T>> {
+		self.blocks.iter().flat_map(|block| block.warnings.iter())
+	}
+
+	/// Checks that no warning has been reported.
+	pub fn is_valid(&self) -> bool {
+		self.warnings().next().is_none()
+	}
+}
+
+/// JSON-LD script element report.
+pub struct Block<T: Id = IriBuf> {
+	/// Value of the `id` attribute of the script element, if any.
+	pub id: Option<String>,
+
+	/// Content of the script element.
+	pub source: String,
+
+	/// Expanded content, if the expansion succeeded.
+	pub expanded: Option<ExpandedDocument<T>>,
+
+	/// Types of the nodes defined in the block (including embedded nodes), without duplicates.
+	pub types: Vec<Reference<T>>,
+
+	/// Warnings.
+	pub warnings: Vec<Warning<T>>
+}
+
+/// Structured data warning.
+pub enum Warning<T: Id = IriBuf> {
+	/// The content of the script element is not valid JSON.
+	InvalidJson,
+
+	/// The expansion failed with the given error.
+	Expansion(ErrorCode),
+
+	/// The block does not define any node, most likely because of a missing or invalid
+	/// `@context`.
+	Empty,
+
+	/// A type could not be expanded into an IRI, most likely because it is not defined by
+	/// the context.
+	UnknownType(String),
+
+	/// A top-level node has no type.
+	Untyped(Option<Reference<T>>),
+
+	/// A node does not conform to a shape.
+	Violation(Violation<T>)
+}
+
+impl<T: Id> fmt::Display for Warning<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Warning::InvalidJson => write!(f, "invalid JSON"),
+			Warning::Expansion(code) => write!(f, "expansion failed: {}", code),
+			Warning::Empty => write!(f, "no node defined"),
+			Warning::UnknownType(ty) => write!(f, "unknown type `{}`", ty),
+			Warning::Untyped(Some(id)) => write!(f, "node `{}` has no type", id),
+			Warning::Untyped(None) => write!(f, "anonymous node has no type"),
+			Warning::Violation(violation) => violation.fmt(f)
+		}
+	}
+}
+
+/// Visit every node of an object, including embedded nodes.
+fn visit_object<'a, T: Id, F: FnMut(&'a Node<T>)>(object: &'a Object<T>, f: &mut F) {
+	match object {
+		Object::Node(node) => visit_node(node, f),
+		Object::List(items) => {
+			for item in items {
+				visit_object(item, f)
+			}
+		},
+		Object::Value(_) => ()
+	}
+}
+
+fn visit_node<'a, T: Id, F: FnMut(&'a Node<T>)>(node: &'a Node<T>, f: &mut F) {
+	f(node);
+
+	if let Some(graph) = node.graph() {
+		for object in graph {
+			visit_object(object, f)
+		}
+	}
+
+	if let Some(included) = node.included() {
+		for node in included {
+			visit_node(node, f)
+		}
+	}
+
+	for values in node.properties.values() {
+		for value in values {
+			visit_object(value, f)
+		}
+	}
+
+	for nodes in node.reverse_properties.values() {
+		for node in nodes {
+			visit_node(node, f)
+		}
+	}
+}
+
+/// Analyze an expanded block.
+fn analyze<T: Id>(expanded: &ExpandedDocument<T>, shapes: &[Shape<T>], types: &mut Vec<Reference<T>>, warnings: &mut Vec<Warning<T>>) {
+	let mut found = BTreeMap::new();
+	let mut unknown = BTreeSet::new();
+	let mut empty = true;
+
+	for object in expanded {
+		if let Object::Node(node) = object.inner() {
+			empty = false;
+
+			if node.types().is_empty() && !node.is_graph() {
+				let id = match node.id() {
+					Some(Lenient::Ok(id)) => Some(id.clone()),
+					_ => None
+				};
+
+				warnings.push(Warning::Untyped(id))
+			}
+		}
+
+		visit_object(object, &mut |node| {
+			for ty in node.types() {
+				match ty {
+					Lenient::Ok(ty) => {
+						found.insert(ty.as_str().to_string(), ty.clone());
+					},
+					Lenient::Unknown(ty) => {
+						unknown.insert(ty.clone());
+					}
+				}
+			}
+		})
+	}
+
+	if empty {
+		warnings.push(Warning::Empty)
+	}
+
+	for ty in unknown {
+		warnings.push(Warning::UnknownType(ty))
+	}
+
+	types.extend(found.into_values());
+
+	for shape in shapes {
+		warnings.extend(shape.validate_document(expanded).into_iter().map(Warning::Violation))
+	}
+}
+
+/// Build the structured data report of the given HTML document located at the given URL.
+///
+/// Each JSON-LD script element is expanded separately with the given initial context and
+/// loader, and its top-level nodes are validated against the given shapes.
+pub fn report<'a, T: 'a + Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader>(html: &'a str, url: Iri, context: &'a C, loader: &'a mut L, shapes: &'a [Shape<T>]) -> impl 'a + Send + Future<Output = Report<T>> where C::LocalContext: Send + Sync + From<L::Output> + From<json::JsonValue>, L::Output: Into<json::JsonValue> {
+	let scan = scan(html);
+	let base_url = scan.base_url(url);
+	let scripts: Vec<_> = scan.scripts.into_iter().filter(|script| script.is_json_ld()).map(|script| {
+		let json = script.parse().ok();
+		(script.id, script.content.to_string(), json)
+	}).collect();
+
+	async move {
+		let mut blocks = Vec::new();
+
+		for (id, source, json) in scripts {
+			let mut block = Block {
+				id,
+				source,
+				expanded: None,
+				types: Vec::new(),
+				warnings: Vec::new()
+			};
+
+			match json {
+				Some(json) => match json.expand_with(Some(base_url.as_iri()), context, loader, Default::default()).await {
+					Ok(expanded) => {
+						analyze(&expanded, shapes, &mut block.types, &mut block.warnings);
+						block.expanded = Some(expanded)
+					},
+					Err(e) => block.warnings.push(Warning::Expansion(e.code()))
+				},
+				None => block.warnings.push(Warning::InvalidJson)
+			}
+
+			blocks.push(block)
+		}
+
+		Report {
+			base_url,
+			blocks
+		}
+	}
+}
@@ -15,7 +15,9 @@ use crate::{
 /// It is a pointer type that `Deref` into the underlying value.
 pub struct Indexed<T> {
 	/// Index.
-	index: Option<String>,
+	///
+	/// Stored as a boxed string slice, since indexes are never modified in place.
+	index: Option<Box<str>>,
 
 	/// Value.
 	value: T
@@ -25,7 +27,8 @@ impl<T> Indexed<T> {
 	/// Create a new (maybe) indexed value.
 	pub fn new(value: T, index: Option<String>) -> Indexed<T> {
 		Indexed {
-			value, index
+			value,
+			index: index.map(String::into_boxed_str)
 		}
 	}
 
@@ -41,32 +44,32 @@ impl<T> Indexed<T> {
 
 	/// Get the index, if any.
 	pub fn index(&self) -> Option<&str> {
-		match &self.index {
-			Some(index) => Some(index.as_str()),
-			None => None
-		}
+		self.index.as_deref()
 	}
 
 	/// Set the value index.
 	pub fn set_index(&mut self, index: Option<String>) {
-		self.index = index
+		self.index = index.map(String::into_boxed_str)
 	}
 
 	/// Turn this indexed value into its components: inner value and index.
 	pub fn into_parts(self) -> (T, Option<String>) {
-		(self.value, self.index)
+		(self.value, self.index.map(String::from))
 	}
 
 	/// Cast the inner value.
 	pub fn cast<U: From<T>>(self) -> Indexed<U> {
-		Indexed::new(self.value.into(), self.index)
+		Indexed {
+			value: self.value.into(),
+			index: self.index
+		}
 	}
 
 	/// Try to cast the inner value.
 	pub fn try_cast<U: TryFrom<T>>(self) -> Result<Indexed<U>, Indexed<U::Error>> {
 		match self.value.try_into() {
-			Ok(value) => Ok(Indexed::new(value, self.index)),
-			Err(e) => Err(Indexed::new(e, self.index))
+			Ok(value) => Ok(Indexed { value, index: self.index }),
+			Err(e) => Err(Indexed { value: e, index: self.index })
 		}
 	}
 }
@@ -88,7 +91,10 @@ impl<T: Eq> Eq for Indexed<T> {}
 
 impl<T: Clone> Clone for Indexed<T> {
 	fn clone(&self) -> Self {
-		Indexed::new(self.value.clone(), self.index.clone())
+		Indexed {
+			value: self.value.clone(),
+			index: self.index.clone()
+		}
 	}
 }
 
@@ -0,0 +1,186 @@
+use std::collections::{
+	HashMap,
+	HashSet
+};
+use std::borrow::Borrow;
+use iref::IriBuf;
+use crate::{
+	Error,
+	Id,
+	Reference,
+	ToReference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument,
+	generator::{
+		self,
+		Generator
+	},
+	flattening::{
+		node_map,
+		NodeMap,
+		NodeMapGraph
+	}
+};
+
+/// Set of node identifiers, by key.
+type NodeSets<T> = HashMap<Reference<T>, HashSet<Reference<T>>>;
+
+/// Set of `(node, property)` pairs, by key.
+type PropertySets<T> = HashMap<Reference<T>, HashSet<(Reference<T>, Reference<T>)>>;
+
+/// Indices of a node map graph.
+struct GraphIndex<T: Id> {
+	/// Nodes by type.
+	types: NodeSets<T>,
+
+	/// Nodes by property, then by referenced node.
+	property_values: HashMap<Reference<T>, NodeSets<T>>,
+
+	/// For each node, the nodes referencing it and through which property.
+	references: PropertySets<T>
+}
+
+impl<T: Id> GraphIndex<T> {
+	fn new(graph: &NodeMapGraph<T>) -> GraphIndex<T> {
+		let mut index = GraphIndex {
+			types: HashMap::new(),
+			property_values: HashMap::new(),
+			references: HashMap::new()
+		};
+
+		for (id, node) in graph.iter() {
+			for ty in &node.types {
+				if let Lenient::Ok(ty) = ty {
+					index.types.entry(ty.clone()).or_default().insert(id.clone());
+				}
+			}
+
+			for (prop, values) in &node.properties {
+				for value in values {
+					index.insert_references(id, prop, value)
+				}
+			}
+		}
+
+		index
+	}
+
+	fn insert_references(&mut self, subject: &Reference<T>, prop: &Reference<T>, value: &Object<T>) {
+		match value {
+			Object::Node(node) => {
+				if let Some(Lenient::Ok(target)) = &node.id {
+					self.property_values.entry(prop.clone()).or_default().entry(target.clone()).or_default().insert(subject.clone());
+					self.references.entry(target.clone()).or_default().insert((subject.clone(), prop.clone()));
+				}
+			},
+			Object::List(items) => {
+				for item in items {
+					self.insert_references(subject, prop, item)
+				}
+			},
+			Object::Value(_) => ()
+		}
+	}
+}
+
+/// Indexed dataset.
+///
+/// Flattened view of an expanded document providing constant time lookup of nodes by
+/// identifier, by type, and by property value, as well as reverse lookup of the nodes
+/// referencing a given node.
+/// This is much faster than scanning the expanded document for large documents.
+///
+/// Nodes are collected using the [Node Map Generation algorithm](crate::flattening::node_map),
+/// hence each index is relative to a graph (the default graph being selected with `None`).
+/// This algorithm renames every blank node identifier, so blank nodes cannot be looked up with
+/// the identifiers of the original document, but only with the ones found in the dataset.
+pub struct IndexedDataset<T: Id = IriBuf> {
+	/// Underlying node map.
+	map: NodeMap<T>,
+
+	/// Default graph indices.
+	default_index: GraphIndex<T>,
+
+	/// Named graphs indices.
+	indices: HashMap<Reference<T>, GraphIndex<T>>
+}
+
+impl<T: Id> IndexedDataset<T> {
+	/// Index the given expanded document.
+	///
+	/// Blank node identifiers are renamed using a [`generator::Blank`] generator.
+	pub fn new(document: &ExpandedDocument<T>) -> Result<IndexedDataset<T>, Error> {
+		Self::new_with(document, generator::Blank::new())
+	}
+
+	/// Index the given expanded document, using the given generator to rename blank node
+	/// identifiers.
+	pub fn new_with<G: Generator>(document: &ExpandedDocument<T>, generator: G) -> Result<IndexedDataset<T>, Error> {
+		Ok(node_map(document, generator)?.into())
+	}
+
+	/// Get the underlying node map.
+	pub fn node_map(&self) -> &NodeMap<T> {
+		&self.map
+	}
+
+	/// Consume the dataset and return the underlying node map.
+	pub fn into_node_map(self) -> NodeMap<T> {
+		self.map
+	}
+
+	fn index(&self, graph: Option<&Reference<T>>) -> Option<&GraphIndex<T>> {
+		match graph {
+			Some(graph) => self.indices.get(graph),
+			None => Some(&self.default_index)
+		}
+	}
+
+	/// Get the node with the given identifier in the given graph.
+	pub fn get<R: ToReference<T>>(&self, graph: Option<&Reference<T>>, id: R) -> Option<&Indexed<Node<T>>> {
+		self.map.get(graph, id)
+	}
+
+	/// Iterate through the nodes of the given graph having the given type.
+	pub fn with_type<'a, R: ToReference<T>>(&'a self, graph: Option<&Reference<T>>, ty: R) -> impl 'a + Iterator<Item = &'a Indexed<Node<T>>> {
+		let nodes = self.map.graph(graph);
+		let ids = self.index(graph).and_then(|index| index.types.get(ty.to_ref().borrow()));
+		ids.into_iter().flatten().filter_map(move |id| nodes.and_then(|nodes| nodes.get(id)))
+	}
+
+	/// Iterate through the nodes of the given graph referencing the node `value` through the
+	/// property `prop`.
+	pub fn with_property_value<'a, P: ToReference<T>, R: ToReference<T>>(&'a self, graph: Option<&Reference<T>>, prop: P, value: R) -> impl 'a + Iterator<Item = &'a Indexed<Node<T>>> {
+		let nodes = self.map.graph(graph);
+		let ids = self.index(graph)
+			.and_then(|index| index.property_values.get(prop.to_ref().borrow()))
+			.and_then(|values| values.get(value.to_ref().borrow()));
+		ids.into_iter().flatten().filter_map(move |id| nodes.and_then(|nodes| nodes.get(id)))
+	}
+
+	/// Iterate through the nodes of the given graph referencing the node `id`, with the property
+	/// through which it is referenced.
+	///
+	/// Node references nested in lists are taken into account.
+	pub fn referencing<'a, R: ToReference<T>>(&'a self, graph: Option<&Reference<T>>, id: R) -> impl 'a + Iterator<Item = (&'a Reference<T>, &'a Indexed<Node<T>>)> {
+		let nodes = self.map.graph(graph);
+		let references = self.index(graph).and_then(|index| index.references.get(id.to_ref().borrow()));
+		references.into_iter().flatten().filter_map(move |(subject, prop)| nodes.and_then(|nodes| nodes.get(subject)).map(|node| (prop, node)))
+	}
+}
+
+impl<T: Id> From<NodeMap<T>> for IndexedDataset<T> {
+	fn from(map: NodeMap<T>) -> IndexedDataset<T> {
+		let default_index = GraphIndex::new(map.default_graph());
+		let indices = map.named_graphs().map(|(name, graph)| (name.clone(), GraphIndex::new(graph))).collect();
+
+		IndexedDataset {
+			map,
+			default_index,
+			indices
+		}
+	}
+}
@@ -0,0 +1,64 @@
+//! Optional `tracing` instrumentation.
+//!
+//! With the `tracing` feature, the futures of context processing, document loading and
+//! expansion are attached to [`tracing`](https://crates.io/crates/tracing) spans, so that
+//! subscribers can tell which remote context or document section takes time.
+//! Without it, the macros of this module have no effect.
+
+/// Attach a `debug` level span to a future.
+///
+/// The span is given as the arguments of `tracing::debug_span!`. It is created before the future,
+/// so that its fields may borrow what the future moves, and entered while the future is built,
+/// so that the spans of nested loaders have the right parent.
+#[cfg(feature = "tracing")]
+macro_rules! instrument {
+	($future:expr, $($span:tt)*) => {
+		{
+			let span = tracing::debug_span!($($span)*);
+			let future = {
+				let _entered = span.enter();
+				$future
+			};
+			tracing::Instrument::instrument(future, span)
+		}
+	};
+}
+
+/// Attach a `debug` level span to a future.
+///
+/// The span is given as the arguments of `tracing::debug_span!`.
+#[cfg(not(feature = "tracing"))]
+macro_rules! instrument {
+	($future:expr, $($span:tt)*) => {
+		$future
+	};
+}
+
+/// Attach a `trace` level span to a future.
+///
+/// The span is given as the arguments of `tracing::trace_span!`. It is created before the future,
+/// so that its fields may borrow what the future moves, and entered while the future is built,
+/// so that the spans of nested loaders have the right parent.
+#[cfg(feature = "tracing")]
+macro_rules! instrument_trace {
+	($future:expr, $($span:tt)*) => {
+		{
+			let span = tracing::trace_span!($($span)*);
+			let future = {
+				let _entered = span.enter();
+				$future
+			};
+			tracing::Instrument::instrument(future, span)
+		}
+	};
+}
+
+/// Attach a `trace` level span to a future.
+///
+/// The span is given as the arguments of `tracing::trace_span!`.
+#[cfg(not(feature = "tracing"))]
+macro_rules! instrument_trace {
+	($future:expr, $($span:tt)*) => {
+		$future
+	};
+}
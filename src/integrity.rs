@@ -0,0 +1,196 @@
+//! Context integrity pinning.
+//!
+//! Substituting a remote context can change the meaning of a document without changing its
+//! content, a known attack on JSON-LD signatures.
+//! The [`IntegrityLayer`] mitigates it by pinning the expected hash of remote documents,
+//! in the style of [Subresource Integrity](https://www.w3.org/TR/SRI/) metadata
+//! (`sha256-<base64 digest>`):
+//! ```ignore
+//! let loader = reqwest::Loader::new().with(
+//!     IntegrityLayer::new().pin(iri!("https://www.w3.org/ns/did/v1"), "sha256-...".parse()?)
+//! );
+//! ```
+//!
+//! Hashes are computed on the raw content of a document, as served, when the loader keeps it
+//! (see [`RemoteDocument::content`]): this is the case of the file system, HTTP and function
+//! loaders.
+//! Otherwise, for instance for documents served from a cache or a preset, they are computed on
+//! the compact JSON serialization of the document, which [`Hash::of_document`] computes to
+//! obtain the pin.
+//!
+//! Expanded documents can also be hashed with [`ExpandedDocument::canonical_hash`], to detect
+//! changes or build content-addressed stores.
+
+use std::collections::HashMap;
+use std::fmt;
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	ErrorCode,
+	RemoteDocument,
+	ExpandedDocument,
+	Loader,
+	layer::LoaderLayer,
+	usage::TermUse
+};
+
+pub use crate::hash::{
+	Algorithm,
+	Hash,
+	InvalidHash
+};
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Hash of the canonical form of the document (see [`ExpandedDocument::canonical_form`]).
+	///
+	/// Documents expanding to the same objects have the same hash, however they are written,
+	/// which makes it suitable to detect changes or to address documents by content.
+	pub fn canonical_hash(&self, algorithm: Algorithm) -> Hash {
+		Hash::of(algorithm, self.canonical_form().as_bytes())
+	}
+}
+
+/// Integrity check failure, source of the `LoadingDocumentFailed` error raised by an
+/// [`Integrity`] loader.
+#[derive(Debug)]
+pub enum IntegrityError {
+	/// The document at the given URL does not match its pinned hash.
+	Mismatch {
+		url: IriBuf,
+		expected: Hash,
+		found: Hash
+	},
+
+	/// The document at the given URL is not pinned, in strict mode.
+	NotPinned(IriBuf)
+}
+
+impl fmt::Display for IntegrityError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			IntegrityError::Mismatch { url, expected, found } => write!(f, "integrity mismatch for `{}`: expected {}, found {}", url, expected, found),
+			IntegrityError::NotPinned(url) => write!(f, "`{}` is not pinned", url)
+		}
+	}
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Integrity pinning layer.
+#[derive(Clone, Default)]
+pub struct IntegrityLayer {
+	pins: HashMap<IriBuf, Hash>,
+	strict: bool
+}
+
+impl IntegrityLayer {
+	/// Create a new layer, without any pin.
+	pub fn new() -> IntegrityLayer {
+		IntegrityLayer::default()
+	}
+
+	/// Pin the expected hash of the document at the given URL.
+	pub fn pin(mut self, url: Iri, hash: Hash) -> IntegrityLayer {
+		self.pins.insert(url.into(), hash);
+		self
+	}
+
+	/// In strict mode, documents that are not pinned are rejected.
+	pub fn strict(self, strict: bool) -> IntegrityLayer {
+		IntegrityLayer {
+			strict,
+			..self
+		}
+	}
+
+	/// Check the given document.
+	///
+	/// The raw content of the document is hashed if available, its compact JSON serialization
+	/// otherwise.
+	pub fn check(&self, url: Iri, doc: &RemoteDocument) -> Result<(), Box<IntegrityError>> {
+		match self.pins.get(&IriBuf::from(url)) {
+			Some(expected) => {
+				let found = match doc.content() {
+					Some(content) => Hash::of(expected.algorithm(), content.as_bytes()),
+					None => Hash::of_document(expected.algorithm(), doc)
+				};
+				if found == *expected {
+					Ok(())
+				} else {
+					Err(Box::new(IntegrityError::Mismatch {
+						url: url.into(),
+						expected: expected.clone(),
+						found
+					}))
+				}
+			},
+			None if self.strict => Err(Box::new(IntegrityError::NotPinned(url.into()))),
+			None => Ok(())
+		}
+	}
+}
+
+impl From<HashMap<IriBuf, Hash>> for IntegrityLayer {
+	fn from(pins: HashMap<IriBuf, Hash>) -> IntegrityLayer {
+		IntegrityLayer {
+			pins,
+			strict: false
+		}
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> LoaderLayer<L> for IntegrityLayer {
+	type Loader = Integrity<L>;
+
+	fn layer(self, inner: L) -> Integrity<L> {
+		Integrity {
+			inner,
+			config: self
+		}
+	}
+}
+
+/// Loader produced by the [`IntegrityLayer`].
+///
+/// Raises a `LoadingDocumentFailed` error, with an [`IntegrityError`] source, if a loaded
+/// document does not match its pinned hash.
+pub struct Integrity<L> {
+	inner: L,
+	config: IntegrityLayer
+}
+
+impl<L> Integrity<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> Loader for Integrity<L> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let doc = self.inner.load(url.as_iri()).await?;
+			match self.config.check(url.as_iri(), &doc) {
+				Ok(()) => Ok(doc),
+				Err(e) => {
+					warn!("{}", e);
+					Err(Error::new(ErrorCode::LoadingDocumentFailed, e))
+				}
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
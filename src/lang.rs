@@ -1,4 +1,8 @@
-use crate::Direction;
+use iref::Iri;
+use crate::{
+	Direction,
+	direction::parse_i18n_datatype
+};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct LangString {
@@ -38,4 +42,112 @@ impl LangString {
 	pub fn set_direction(&mut self, direction: Option<Direction>) {
 		self.direction = direction
 	}
+
+	/// Render the string as an HTML fragment, keeping its language and direction.
+	///
+	/// The text is escaped and wrapped in a `<span>` element with `dir` and `lang`
+	/// attributes, which isolates it from the surrounding text.
+	/// Strings without direction are wrapped in a `<bdi>` element instead, so that their
+	/// direction is detected from their content.
+	pub fn to_html(&self) -> String {
+		let lang = match &self.language {
+			Some(lang) => format!(" lang=\"{}\"", escape_html(lang)),
+			None => String::new()
+		};
+
+		match self.direction {
+			Some(direction) => format!("<span dir=\"{}\"{}>{}</span>", direction, lang, escape_html(&self.data)),
+			None => format!("<bdi{}>{}</bdi>", lang, escape_html(&self.data))
+		}
+	}
+
+	/// Wrap the string in Unicode bidirectional isolate characters, for plain-text display.
+	///
+	/// The string is enclosed in a left-to-right (`U+2066`) or right-to-left (`U+2067`)
+	/// isolate according to its direction, or a first-strong isolate (`U+2068`) if it has no
+	/// direction, terminated by a pop directional isolate (`U+2069`).
+	pub fn to_bidi_isolated(&self) -> String {
+		let isolate = match self.direction {
+			Some(Direction::Ltr) => '\u{2066}',
+			Some(Direction::Rtl) => '\u{2067}',
+			None => '\u{2068}'
+		};
+
+		format!("{}{}\u{2069}", isolate, self.data)
+	}
+
+	/// Build a language string from an RDF literal.
+	///
+	/// The literal is a language string if it has a language tag (datatype `rdf:langString`),
+	/// or if its datatype is a `https://www.w3.org/ns/i18n#<language>_<direction>` datatype, from
+	/// which the language and direction are extracted.
+	/// Otherwise, the value is given back.
+	pub fn from_rdf_literal(value: String, datatype: Option<Iri>, language: Option<String>) -> Result<LangString, String> {
+		if language.is_some() {
+			return Ok(LangString::new(value, language, None))
+		}
+
+		match datatype.and_then(|datatype| parse_i18n_datatype(datatype.as_str()).map(|(language, direction)| (language.to_string(), direction))) {
+			Some((language, direction)) => {
+				let language = if language.is_empty() { None } else { Some(language) };
+				Ok(LangString::new(value, language, Some(direction)))
+			},
+			None => Err(value)
+		}
+	}
+}
+
+/// Escape the HTML special characters of a text or attribute value.
+fn escape_html(value: &str) -> String {
+	let mut result = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'&' => result.push_str("&amp;"),
+			'<' => result.push_str("&lt;"),
+			'>' => result.push_str("&gt;"),
+			'"' => result.push_str("&quot;"),
+			'\'' => result.push_str("&#39;"),
+			c => result.push(c)
+		}
+	}
+
+	result
+}
+
+/// Find the language string best matching a language priority list, following the
+/// [lookup](https://tools.ietf.org/html/rfc4647#section-3.4) scheme of RFC 4647.
+///
+/// Each language range, in order of preference, is compared to the language tags of the
+/// strings (ignoring case), and is progressively truncated from the end until a tag matches:
+/// `en-GB` matches `en-GB`, then `en`, but not `en-US`.
+/// The `*` range matches any string, including strings without language tag.
+/// Among the strings with the same tag, the first one is selected.
+pub fn lookup_language<'a, I: IntoIterator<Item = &'a LangString>>(strings: I, ranges: &[&str]) -> Option<&'a LangString> {
+	let strings: Vec<_> = strings.into_iter().collect();
+	for range in ranges {
+		if *range == "*" {
+			return strings.first().copied()
+		}
+
+		let mut range: &str = range;
+		loop {
+			if let Some(s) = strings.iter().find(|s| s.language().is_some_and(|lang| lang.eq_ignore_ascii_case(range))) {
+				return Some(s)
+			}
+
+			match range.rfind('-') {
+				Some(i) => {
+					range = &range[..i];
+					// A single-letter subtag (such as an extension singleton) is removed along
+					// with the subtag that follows.
+					if range.len() >= 2 && range.as_bytes()[range.len() - 2] == b'-' {
+						range = &range[..range.len() - 2]
+					}
+				},
+				None => break
+			}
+		}
+	}
+
+	None
 }
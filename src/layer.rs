@@ -0,0 +1,882 @@
+//! Loader middlewares.
+//!
+//! A [`LoaderLayer`] wraps a document loader into another loader adding some behavior,
+//! so that context fetching can be customized without writing a whole loader.
+//! Layers are stacked with [`LoaderExt::with`], the last layer being the outermost:
+//! ```ignore
+//! let loader = FsLoader::new()
+//!     .with(RetryLayer::new(3))
+//!     .with(CacheLayer::new())
+//!     .with(RewriteLayer::new().mirror(iri!("https://www.w3.org/"), iri!("https://mirror.example.org/w3c/")));
+//! ```
+//!
+//! Retrying each location before falling back to an internal mirror:
+//! ```ignore
+//! let loader = loader
+//!     .with(RetryLayer::new(3).backoff(Duration::from_millis(100), sleep))
+//!     .with(FallbackLayer::new().fallback(iri!("https://www.w3.org/ns/"), iri!("https://mirror.example.org/ns/")));
+//! ```
+//!
+//! This module provides the following layers:
+//!   - [`RetryLayer`] tries again failed loads, with an optional exponential backoff.
+//!   - [`CacheLayer`] fetches each document once.
+//!   - [`SharedCacheLayer`] fetches each document once, and can be shared between tasks.
+//!   - [`RewriteLayer`] fetches documents from mirrors.
+//!   - [`FallbackLayer`] fetches documents from mirrors when their original URL fails.
+//!   - [`RecordLayer`] records every fetch.
+//!   - [`FixturesLayer`] serves predefined documents, for instance in tests.
+//!   - [`PolicyLayer`] enforces per-origin rules (allow or deny, cache duration, maximum size).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	RemoteDocument,
+	Loader,
+	SharedLoader,
+	usage::TermUse,
+	util::Timer
+};
+
+/// Loader middleware.
+pub trait LoaderLayer<L> {
+	/// Resulting loader.
+	type Loader: Loader;
+
+	/// Wrap the given loader.
+	fn layer(self, inner: L) -> Self::Loader;
+}
+
+/// Extension methods for loaders.
+pub trait LoaderExt: Sized {
+	/// Wrap this loader with the given layer.
+	fn with<Y: LoaderLayer<Self>>(self, layer: Y) -> Y::Loader {
+		layer.layer(self)
+	}
+}
+
+impl<L: Loader> LoaderExt for L {}
+
+/// Asynchronous sleeping function, used to wait between two attempts.
+pub type Sleep = Arc<dyn Send + Sync + Fn(Duration) -> BoxFuture<'static, ()>>;
+
+/// Retry layer.
+///
+/// Tries loading a document up to a given number of times before returning the last error.
+#[derive(Clone)]
+pub struct RetryLayer {
+	attempts: usize,
+	backoff: Option<(Duration, Sleep)>
+}
+
+impl RetryLayer {
+	/// Create a layer trying each load up to `attempts` times (at least once), without delay.
+	pub fn new(attempts: usize) -> RetryLayer {
+		RetryLayer {
+			attempts: std::cmp::max(attempts, 1),
+			backoff: None
+		}
+	}
+
+	/// Wait between two attempts, starting with the `initial` delay and doubling it each time.
+	///
+	/// The crate does not depend on any runtime, hence the given `sleep` function.
+	pub fn backoff(self, initial: Duration, sleep: Sleep) -> RetryLayer {
+		RetryLayer {
+			backoff: Some((initial, sleep)),
+			..self
+		}
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for RetryLayer {
+	type Loader = Retry<L>;
+
+	fn layer(self, inner: L) -> Retry<L> {
+		Retry {
+			inner,
+			config: self
+		}
+	}
+}
+
+/// Loader produced by the [`RetryLayer`].
+pub struct Retry<L> {
+	inner: L,
+	config: RetryLayer
+}
+
+impl<L> Retry<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+}
+
+impl<L: Send + Loader> Loader for Retry<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let mut delay = self.config.backoff.as_ref().map(|(initial, _)| *initial);
+			let mut attempt = 1;
+			loop {
+				let code = match self.inner.load(url.as_iri()).await {
+					Ok(doc) => return Ok(doc),
+					Err(e) if attempt >= self.config.attempts || self.inner.is_cancelled() => return Err(e),
+					Err(e) => e.code()
+				};
+
+				debug!("attempt {} to load `{}' failed ({}), retrying", attempt, url, code);
+				if let (Some((_, sleep)), Some(d)) = (&self.config.backoff, delay) {
+					sleep(d).await;
+					delay = Some(d * 2);
+				}
+
+				attempt += 1
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Cache layer.
+///
+/// Documents are fetched once by the inner loader, then served from the cache.
+/// Failed loads are not cached.
+#[derive(Clone, Copy, Default)]
+pub struct CacheLayer;
+
+impl CacheLayer {
+	/// Create a new cache layer.
+	pub fn new() -> CacheLayer {
+		CacheLayer
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for CacheLayer where L::Document: Send + Clone {
+	type Loader = Cache<L>;
+
+	fn layer(self, inner: L) -> Cache<L> {
+		Cache {
+			inner,
+			cache: HashMap::new()
+		}
+	}
+}
+
+/// Loader produced by the [`CacheLayer`].
+pub struct Cache<L: Loader> {
+	inner: L,
+	cache: HashMap<IriBuf, RemoteDocument<L::Document>>
+}
+
+impl<L: Loader> Cache<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Clear the cache.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
+
+impl<L: Send + Loader> Loader for Cache<L> where L::Document: Send + Clone {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			match self.cache.get(&url) {
+				Some(doc) => Ok(doc.clone()),
+				None => {
+					let doc = self.inner.load(url.as_iri()).await?;
+					self.cache.insert(url, doc.clone());
+					Ok(doc)
+				}
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Shared cache layer.
+///
+/// Same as the [`CacheLayer`], but the resulting loader is a [`SharedLoader`]:
+/// it can be put behind an `Arc` and used by concurrent tasks.
+/// The inner loader is behind a lock, so that it loads one document at a time,
+/// while cached documents are served concurrently.
+#[derive(Clone, Copy, Default)]
+pub struct SharedCacheLayer;
+
+impl SharedCacheLayer {
+	/// Create a new shared cache layer.
+	pub fn new() -> SharedCacheLayer {
+		SharedCacheLayer
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for SharedCacheLayer where L::Document: Send + Sync + Clone {
+	type Loader = SharedCache<L>;
+
+	fn layer(self, inner: L) -> SharedCache<L> {
+		SharedCache {
+			inner: futures::lock::Mutex::new(inner),
+			cache: RwLock::new(HashMap::new())
+		}
+	}
+}
+
+/// Loader produced by the [`SharedCacheLayer`].
+pub struct SharedCache<L: Loader> {
+	inner: futures::lock::Mutex<L>,
+	cache: RwLock<HashMap<IriBuf, RemoteDocument<L::Document>>>
+}
+
+impl<L: Loader> SharedCache<L> {
+	/// Clear the cache.
+	pub fn clear(&self) {
+		self.cache.write().unwrap().clear()
+	}
+}
+
+impl<L: Send + Loader> SharedLoader for SharedCache<L> where L::Document: Send + Sync + Clone {
+	type Document = L::Document;
+
+	fn load_shared<'a>(&'a self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			if let Some(doc) = self.cache.read().unwrap().get(&url) {
+				return Ok(doc.clone())
+			}
+
+			let mut inner = self.inner.lock().await;
+
+			// The document may have been loaded while waiting for the lock.
+			if let Some(doc) = self.cache.read().unwrap().get(&url) {
+				return Ok(doc.clone())
+			}
+
+			let doc = inner.load(url.as_iri()).await?;
+			self.cache.write().unwrap().insert(url, doc.clone());
+			Ok(doc)
+		}.boxed()
+	}
+}
+
+impl<L: Send + Loader> Loader for SharedCache<L> where L::Document: Send + Sync + Clone {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		self.load_shared(url)
+	}
+}
+
+/// Rewrite layer.
+///
+/// Fetches the documents whose URL starts with a given prefix from a mirror, by replacing
+/// the prefix.
+/// The loaded documents keep their original URL as base URL.
+#[derive(Clone, Default)]
+pub struct RewriteLayer {
+	mirrors: Vec<(String, String)>
+}
+
+impl RewriteLayer {
+	/// Create a new layer, without any mirror.
+	pub fn new() -> RewriteLayer {
+		RewriteLayer::default()
+	}
+
+	/// Fetch the documents whose URL starts with `prefix` from `mirror`.
+	///
+	/// When multiple prefixes match an URL, the longest one is used.
+	pub fn mirror(mut self, prefix: Iri, mirror: Iri) -> RewriteLayer {
+		self.mirrors.push((prefix.as_str().to_string(), mirror.as_str().to_string()));
+		self
+	}
+
+	/// Rewrite the given URL.
+	///
+	/// Returns `None` if no prefix matches, or the rewritten URL is not a valid IRI.
+	pub fn rewrite(&self, url: Iri) -> Option<IriBuf> {
+		let (prefix, mirror) = self.mirrors.iter()
+			.filter(|(prefix, _)| url.as_str().starts_with(prefix.as_str()))
+			.max_by_key(|(prefix, _)| prefix.len())?;
+
+		IriBuf::new(&format!("{}{}", mirror, &url.as_str()[prefix.len()..])).ok()
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for RewriteLayer {
+	type Loader = Rewrite<L>;
+
+	fn layer(self, inner: L) -> Rewrite<L> {
+		Rewrite {
+			inner,
+			config: self
+		}
+	}
+}
+
+/// Loader produced by the [`RewriteLayer`].
+pub struct Rewrite<L> {
+	inner: L,
+	config: RewriteLayer
+}
+
+impl<L> Rewrite<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+}
+
+impl<L: Send + Loader> Loader for Rewrite<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			match self.config.rewrite(url.as_iri()) {
+				Some(target) => {
+					debug!("loading `{}' from mirror `{}'", url, target);
+					let (doc, _) = self.inner.load(target.as_iri()).await?.into_parts();
+					Ok(RemoteDocument::new(doc, url.as_iri()))
+				},
+				None => self.inner.load(url.as_iri()).await
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Fallback layer.
+///
+/// Fetches the documents from their original URL first, and from fallback mirrors if it fails,
+/// for instance to keep resolving public contexts when their host is unavailable.
+/// The mirrors of an URL are tried in order, the mirrors of longer prefixes first.
+/// The loaded documents keep their original URL as base URL.
+///
+/// Stack a [`RetryLayer`] under this layer to retry each location before falling back to the
+/// next one.
+#[derive(Clone, Default)]
+pub struct FallbackLayer {
+	mirrors: Vec<(String, String)>
+}
+
+impl FallbackLayer {
+	/// Create a new layer, without any mirror.
+	pub fn new() -> FallbackLayer {
+		FallbackLayer::default()
+	}
+
+	/// Fetch the documents whose URL starts with `prefix` from `mirror` when the original
+	/// URL fails.
+	pub fn fallback(mut self, prefix: Iri, mirror: Iri) -> FallbackLayer {
+		self.mirrors.push((prefix.as_str().to_string(), mirror.as_str().to_string()));
+		self
+	}
+
+	/// Fallback URLs of the given URL, in the order in which they are tried.
+	///
+	/// Rewritten URLs that are not valid IRIs are skipped.
+	pub fn fallbacks(&self, url: Iri) -> Vec<IriBuf> {
+		let mut mirrors: Vec<_> = self.mirrors.iter()
+			.filter(|(prefix, _)| url.as_str().starts_with(prefix.as_str()))
+			.collect();
+		mirrors.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+		mirrors.into_iter().filter_map(|(prefix, mirror)| {
+			IriBuf::new(&format!("{}{}", mirror, &url.as_str()[prefix.len()..])).ok()
+		}).collect()
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for FallbackLayer {
+	type Loader = Fallback<L>;
+
+	fn layer(self, inner: L) -> Fallback<L> {
+		Fallback {
+			inner,
+			config: self
+		}
+	}
+}
+
+/// Loader produced by the [`FallbackLayer`].
+pub struct Fallback<L> {
+	inner: L,
+	config: FallbackLayer
+}
+
+impl<L> Fallback<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+}
+
+impl<L: Send + Loader> Loader for Fallback<L> {
+	type Document = L::Document;
+
+	/// Fails with the error code of the original URL if every mirror also fails.
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let fallbacks = self.config.fallbacks(url.as_iri());
+			let code = match self.inner.load(url.as_iri()).await {
+				Ok(doc) => return Ok(doc),
+				Err(e) if fallbacks.is_empty() || self.inner.is_cancelled() => return Err(e),
+				Err(e) => e.code()
+			};
+
+			for target in fallbacks {
+				debug!("loading `{}' failed ({}), falling back to `{}'", url, code, target);
+				match self.inner.load(target.as_iri()).await {
+					Ok(doc) => {
+						let (doc, _) = doc.into_parts();
+						return Ok(RemoteDocument::new(doc, url.as_iri()))
+					},
+					Err(_) if self.inner.is_cancelled() => break,
+					Err(_) => ()
+				}
+			}
+
+			Err(code.into())
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Recorded fetch.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Fetch {
+	/// Requested URL.
+	pub url: IriBuf,
+
+	/// Whether the document has been successfully loaded.
+	pub success: bool
+}
+
+/// Record layer.
+///
+/// Records every fetch going through the layer.
+/// The records are shared between the layer and the loader it produces, so that they can be
+/// read after the loader has been moved into the processor.
+#[derive(Clone, Default)]
+pub struct RecordLayer {
+	fetches: Arc<Mutex<Vec<Fetch>>>
+}
+
+impl RecordLayer {
+	/// Create a new record layer.
+	pub fn new() -> RecordLayer {
+		RecordLayer::default()
+	}
+
+	/// Fetches recorded so far, in order.
+	pub fn fetches(&self) -> Vec<Fetch> {
+		self.fetches.lock().unwrap().clone()
+	}
+
+	/// Clear the records.
+	pub fn clear(&self) {
+		self.fetches.lock().unwrap().clear()
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for RecordLayer {
+	type Loader = Record<L>;
+
+	fn layer(self, inner: L) -> Record<L> {
+		Record {
+			inner,
+			records: self
+		}
+	}
+}
+
+/// Loader produced by the [`RecordLayer`].
+pub struct Record<L> {
+	inner: L,
+	records: RecordLayer
+}
+
+impl<L> Record<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Fetches recorded so far, in order.
+	pub fn fetches(&self) -> Vec<Fetch> {
+		self.records.fetches()
+	}
+}
+
+impl<L: Send + Loader> Loader for Record<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let result = self.inner.load(url.as_iri()).await;
+			self.records.fetches.lock().unwrap().push(Fetch {
+				url,
+				success: result.is_ok()
+			});
+			result
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Fixtures layer.
+///
+/// Serves predefined documents without calling the inner loader.
+/// Other documents are loaded by the inner loader.
+pub struct FixturesLayer<D> {
+	fixtures: HashMap<IriBuf, D>
+}
+
+impl<D> Default for FixturesLayer<D> {
+	fn default() -> FixturesLayer<D> {
+		FixturesLayer {
+			fixtures: HashMap::new()
+		}
+	}
+}
+
+impl<D> FixturesLayer<D> {
+	/// Create a new layer, without any fixture.
+	pub fn new() -> FixturesLayer<D> {
+		FixturesLayer::default()
+	}
+
+	/// Serve the given document at the given URL.
+	pub fn fixture(mut self, url: Iri, doc: D) -> FixturesLayer<D> {
+		self.fixtures.insert(url.into(), doc);
+		self
+	}
+}
+
+impl<L: Send + Loader> LoaderLayer<L> for FixturesLayer<L::Document> where L::Document: Send + Sync + Clone {
+	type Loader = Fixtures<L>;
+
+	fn layer(self, inner: L) -> Fixtures<L> {
+		Fixtures {
+			inner,
+			fixtures: self.fixtures
+		}
+	}
+}
+
+/// Loader produced by the [`FixturesLayer`].
+pub struct Fixtures<L: Loader> {
+	inner: L,
+	fixtures: HashMap<IriBuf, L::Document>
+}
+
+impl<L: Loader> Fixtures<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+}
+
+impl<L: Send + Loader> Loader for Fixtures<L> where L::Document: Send + Sync + Clone {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			match self.fixtures.get(&url) {
+				Some(doc) => Ok(RemoteDocument::new(doc.clone(), url.as_iri())),
+				None => self.inner.load(url.as_iri()).await
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Loading rule of an origin.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OriginRule {
+	/// Whether documents from this origin may be loaded.
+	pub allow: bool,
+
+	/// How long loaded documents are cached, or `None` to disable caching.
+	///
+	/// Documents are not cached on `wasm32` targets, where time is not measured.
+	pub cache_ttl: Option<Duration>,
+
+	/// Maximum size of a document, in bytes of its content as served.
+	///
+	/// Documents loaded without their raw content are measured by their compact JSON
+	/// serialization.
+	/// The size is checked once the inner loader has loaded the document: set a maximum size on
+	/// the inner loader too (such as `reqwest::Loader::with_max_size`) so that larger documents
+	/// are not downloaded entirely.
+	pub max_size: Option<usize>
+}
+
+impl OriginRule {
+	/// Rule allowing every document, without caching.
+	pub fn allow() -> OriginRule {
+		OriginRule {
+			allow: true,
+			cache_ttl: None,
+			max_size: None
+		}
+	}
+
+	/// Rule denying every document.
+	pub fn deny() -> OriginRule {
+		OriginRule {
+			allow: false,
+			..OriginRule::allow()
+		}
+	}
+}
+
+impl Default for OriginRule {
+	fn default() -> OriginRule {
+		OriginRule::allow()
+	}
+}
+
+/// Per-origin loading policy.
+///
+/// Origins are written `scheme://host` or `scheme://host:port`, in lowercase, without the
+/// default port of HTTP(S) URLs.
+/// Documents from origins without a specific rule follow the default rule.
+#[derive(Clone, Default, Debug)]
+pub struct OriginPolicy {
+	/// Rule of the origins without a specific rule.
+	pub default: OriginRule,
+
+	/// Specific rules.
+	pub origins: HashMap<String, OriginRule>
+}
+
+impl OriginPolicy {
+	/// Policy allowing every origin.
+	pub fn new() -> OriginPolicy {
+		OriginPolicy::default()
+	}
+
+	/// Policy only allowing the given origins.
+	pub fn allowlist<'o, O: IntoIterator<Item = &'o str>>(origins: O) -> OriginPolicy {
+		OriginPolicy {
+			default: OriginRule::deny(),
+			origins: origins.into_iter().map(|origin| (origin.to_lowercase(), OriginRule::allow())).collect()
+		}
+	}
+
+	/// Origin of the given URL.
+	pub fn origin(url: Iri) -> String {
+		let mut origin = format!("{}://", url.scheme().as_str());
+		if let Some(authority) = url.authority() {
+			origin.push_str(authority.host().as_str());
+			if let Some(port) = authority.port() {
+				let default_port = match url.scheme().as_str().to_lowercase().as_str() {
+					"http" => Some("80"),
+					"https" => Some("443"),
+					_ => None
+				};
+
+				if Some(port.as_str()) != default_port {
+					origin.push(':');
+					origin.push_str(port.as_str());
+				}
+			}
+		}
+
+		origin.to_lowercase()
+	}
+
+	/// Rule applying to the given URL.
+	pub fn rule(&self, url: Iri) -> &OriginRule {
+		self.origins.get(&OriginPolicy::origin(url)).unwrap_or(&self.default)
+	}
+}
+
+/// Policy violation, source of the `LoadingDocumentFailed` error raised by a [`Policy`]
+/// loader.
+#[derive(Debug)]
+pub enum PolicyError {
+	/// The origin of the document is denied.
+	Denied(IriBuf),
+
+	/// The document exceeds the maximum size of its origin.
+	TooLarge(IriBuf, usize)
+}
+
+impl fmt::Display for PolicyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PolicyError::Denied(url) => write!(f, "loading `{}` is denied by the origin policy", url),
+			PolicyError::TooLarge(url, size) => write!(f, "`{}` exceeds the maximum size of its origin ({} bytes)", url, size)
+		}
+	}
+}
+
+impl std::error::Error for PolicyError {}
+
+/// Origin policy layer.
+#[derive(Clone, Default)]
+pub struct PolicyLayer {
+	policy: OriginPolicy
+}
+
+impl PolicyLayer {
+	/// Create a layer enforcing the given policy.
+	pub fn new(policy: OriginPolicy) -> PolicyLayer {
+		PolicyLayer {
+			policy
+		}
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> LoaderLayer<L> for PolicyLayer {
+	type Loader = Policy<L>;
+
+	fn layer(self, inner: L) -> Policy<L> {
+		Policy {
+			inner,
+			policy: self.policy,
+			cache: HashMap::new()
+		}
+	}
+}
+
+/// Loader produced by the [`PolicyLayer`].
+///
+/// Raises a `LoadingDocumentFailed` error, with a [`PolicyError`] source, if a document
+/// violates the policy.
+pub struct Policy<L> {
+	inner: L,
+	policy: OriginPolicy,
+	cache: HashMap<IriBuf, (RemoteDocument, Timer)>
+}
+
+impl<L> Policy<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Enforced policy.
+	pub fn policy(&self) -> &OriginPolicy {
+		&self.policy
+	}
+
+	/// Clear the cache.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> Loader for Policy<L> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let rule = self.policy.rule(url.as_iri()).clone();
+			if !rule.allow {
+				warn!("loading `{}' is denied by the origin policy", url);
+				return Err(Error::new(ErrorCode::LoadingDocumentFailed, PolicyError::Denied(url)))
+			}
+
+			if let Some(ttl) = rule.cache_ttl {
+				if let Some((doc, timer)) = self.cache.get(&url) {
+					match timer.elapsed() {
+						Some(elapsed) if elapsed <= ttl => return Ok(doc.clone()),
+						_ => ()
+					}
+				}
+			}
+
+			let doc = self.inner.load(url.as_iri()).await?;
+
+			if let Some(max_size) = rule.max_size {
+				let size = match doc.content() {
+					Some(content) => content.len(),
+					None => doc.dump().len()
+				};
+
+				if size > max_size {
+					warn!("`{}' exceeds the maximum size of its origin", url);
+					return Err(Error::new(ErrorCode::LoadingDocumentFailed, PolicyError::TooLarge(url, max_size)))
+				}
+			}
+
+			if rule.cache_ttl.is_some() {
+				self.cache.insert(url, (doc.clone(), Timer::start()));
+			}
+
+			Ok(doc)
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
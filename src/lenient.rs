@@ -11,13 +11,87 @@ use crate::{
 ///
 /// This is usually used with `Term`, or `Type` to allow for terms/types that are not IRIs or
 /// blanck nodes, etc. but must be kept in the data structure.
-#[derive(PartialEq, Eq, Clone, Hash)]
+///
+/// The `Unknown` variant only appears when IRI expansion fails to produce a `T`:
+/// it then holds the value as far as it could be expanded, for instance a relative IRI
+/// reference when there is no base IRI, or the concatenation of a prefix (or vocabulary)
+/// mapping and a suffix that is not a valid IRI.
+/// Anything that parses as a `T` is always wrapped in `Ok`.
+#[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub enum Lenient<T> {
+	/// Well-formed value.
 	Ok(T),
+
+	/// Malformed value, kept as a string.
 	Unknown(String)
 }
 
 impl<T> Lenient<T> {
+	/// Checks if the value is well-formed.
+	pub fn is_ok(&self) -> bool {
+		matches!(self, Lenient::Ok(_))
+	}
+
+	/// Checks if the value is malformed.
+	pub fn is_unknown(&self) -> bool {
+		matches!(self, Lenient::Unknown(_))
+	}
+
+	/// Get the well-formed value, if any.
+	pub fn ok(self) -> Option<T> {
+		match self {
+			Lenient::Ok(t) => Some(t),
+			Lenient::Unknown(_) => None
+		}
+	}
+
+	/// Get the malformed value, if any.
+	pub fn unknown(self) -> Option<String> {
+		match self {
+			Lenient::Ok(_) => None,
+			Lenient::Unknown(u) => Some(u)
+		}
+	}
+
+	/// Borrow the value, with the malformed value as error.
+	pub fn as_ref(&self) -> Result<&T, &str> {
+		match self {
+			Lenient::Ok(t) => Ok(t),
+			Lenient::Unknown(u) => Err(u.as_str())
+		}
+	}
+
+	/// Map the well-formed value, leaving malformed values untouched.
+	pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Lenient<U> {
+		match self {
+			Lenient::Ok(t) => Lenient::Ok(f(t)),
+			Lenient::Unknown(u) => Lenient::Unknown(u)
+		}
+	}
+
+	/// Map the well-formed value with a fallible function.
+	///
+	/// Values rejected by `f` become `Unknown`, using their given string representation.
+	pub fn and_then<U, F: FnOnce(T) -> Result<U, String>>(self, f: F) -> Lenient<U> {
+		match self {
+			Lenient::Ok(t) => match f(t) {
+				Ok(u) => Lenient::Ok(u),
+				Err(u) => Lenient::Unknown(u)
+			},
+			Lenient::Unknown(u) => Lenient::Unknown(u)
+		}
+	}
+
+	/// Get the well-formed value, or the given default.
+	pub fn unwrap_or(self, default: T) -> T {
+		self.ok().unwrap_or(default)
+	}
+
+	/// Convert into a `Result`, with the malformed value as error.
+	pub fn into_result(self) -> Result<T, String> {
+		self.into()
+	}
+
 	pub fn cast<U>(self) -> Lenient<U> where U: From<T> {
 		match self {
 			Lenient::Ok(t) => Lenient::Ok(t.into()),
@@ -64,6 +138,24 @@ impl<T> From<T> for Lenient<T> {
 	}
 }
 
+impl<T> From<Result<T, String>> for Lenient<T> {
+	fn from(result: Result<T, String>) -> Lenient<T> {
+		match result {
+			Ok(t) => Lenient::Ok(t),
+			Err(u) => Lenient::Unknown(u)
+		}
+	}
+}
+
+impl<T> From<Lenient<T>> for Result<T, String> {
+	fn from(lenient: Lenient<T>) -> Result<T, String> {
+		match lenient {
+			Lenient::Ok(t) => Ok(t),
+			Lenient::Unknown(u) => Err(u)
+		}
+	}
+}
+
 impl<T: fmt::Display> fmt::Display for Lenient<T> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
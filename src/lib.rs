@@ -6,7 +6,10 @@ extern crate log;
 extern crate json;
 extern crate iref;
 
+#[macro_use]
+mod instrument;
 mod mode;
+mod policy;
 mod error;
 mod direction;
 mod lang;
@@ -15,19 +18,72 @@ mod blank;
 mod reference;
 mod lenient;
 mod indexed;
-mod vocab;
 mod document;
+mod dataset;
+mod indexed_dataset;
+mod stats;
+mod dedup;
+mod canonical;
 mod loader;
 pub mod syntax;
 pub mod object;
 pub mod context;
 pub mod expansion;
+pub mod flattening;
+pub mod compaction;
 pub mod util;
+pub mod binding;
+pub mod generator;
+pub mod vocab;
+pub mod patch;
+pub mod shape;
+pub mod schema;
+pub mod preset;
+pub mod metrics;
+pub mod path;
+pub mod pointer;
+pub mod table;
+pub mod rdf;
+pub mod stream;
+pub mod layer;
+pub mod cancel;
+pub mod processor;
+pub mod provenance;
+pub mod build;
+pub mod usage;
 
 #[cfg(feature="reqwest-loader")]
 pub mod reqwest;
 
+#[cfg(feature="vc")]
+pub mod vc;
+
+#[cfg(feature="html")]
+pub mod html;
+
+#[cfg(feature="capi")]
+pub mod capi;
+
+#[cfg(feature="python")]
+pub mod python;
+
+#[cfg(feature="cbor-ld")]
+pub mod cbor_ld;
+
+#[cfg(feature="replay")]
+pub mod replay;
+
+#[cfg(any(feature="integrity", feature="replay"))]
+pub mod hash;
+
+#[cfg(feature="integrity")]
+pub mod integrity;
+
+#[cfg(feature="structured")]
+pub mod structured;
+
 pub use mode::*;
+pub use policy::*;
 pub use error::*;
 pub use direction::*;
 pub use lang::*;
@@ -36,9 +92,17 @@ pub use blank::*;
 pub use reference::*;
 pub use lenient::*;
 pub use indexed::*;
-pub use vocab::*;
+pub use vocab::{
+	Vocab,
+	Lexicon
+};
 pub use document::*;
+pub use dataset::*;
+pub use indexed_dataset::*;
+pub use stats::*;
+pub use dedup::*;
 pub use loader::*;
+pub use processor::Processor;
 
 pub use object::{Object, Node, Value};
 pub use context::{
@@ -1,7 +1,5 @@
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use std::fs::File;
-use std::io::{Read, BufReader};
+use std::future::Future;
+use std::sync::Arc;
 use futures::future::{FutureExt, BoxFuture};
 use iref::{Iri, IriBuf};
 use json::JsonValue;
@@ -12,13 +10,97 @@ use crate::{
 	context::{
 		self,
 		RemoteContext
+	},
+	usage::TermUse,
+	util::{
+		Timer,
+		parse_json
 	}
 };
 
+/// Checks if the given content type is a JSON media type.
+///
+/// This is `application/json`, `application/ld+json` or any media type with a `+json` suffix,
+/// media type parameters being ignored.
+pub fn is_json_media_type(ty: &str) -> bool {
+	let essence = media_type_essence(ty);
+	essence == "application/json" || essence == "application/ld+json" || essence.ends_with("+json")
+}
+
+/// Media type without its parameters, in lowercase.
+pub(crate) fn media_type_essence(ty: &str) -> String {
+	ty.split(';').next().unwrap_or("").trim().to_ascii_lowercase()
+}
+
 pub trait Loader {
 	type Document;
 
 	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>>;
+
+	/// Checks if processing has been cancelled.
+	///
+	/// Processing using this loader is aborted with a `Cancelled` error when this returns
+	/// `true`. See the [`cancel`](crate::cancel) module.
+	fn is_cancelled(&self) -> bool {
+		false
+	}
+
+	/// Called by expansion for each entry key and node object type it expands.
+	///
+	/// Does nothing by default. See the [`usage`](crate::usage) module.
+	fn term_used(&mut self, _term: &str, _usage: TermUse) {}
+}
+
+impl<L: ?Sized + Loader> Loader for Box<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		(**self).load(url)
+	}
+
+	fn is_cancelled(&self) -> bool {
+		(**self).is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		(**self).term_used(term, usage)
+	}
+}
+
+/// Loader usable through a shared reference.
+///
+/// Implementations rely on interior mutability (for instance to cache documents), so that a
+/// single loader can be shared by concurrent tasks.
+/// Every clone of an `Arc<L>` (including `Arc<dyn SharedLoader<Document = JsonValue>>`)
+/// is a [`Loader`], so each task can use its own clone:
+/// ```ignore
+/// let loader: Arc<dyn SharedLoader<Document = JsonValue>> = Arc::new(FsLoader::new().with(SharedCacheLayer::new()));
+///
+/// // In each request handler.
+/// let mut loader = loader.clone();
+/// let expanded = doc.expand(&context, &mut loader).await?;
+/// ```
+pub trait SharedLoader: Send + Sync {
+	type Document;
+
+	fn load_shared<'a>(&'a self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>>;
+
+	/// Checks if processing has been cancelled.
+	fn is_cancelled(&self) -> bool {
+		false
+	}
+}
+
+impl<L: ?Sized + SharedLoader> Loader for Arc<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		(**self).load_shared(url)
+	}
+
+	fn is_cancelled(&self) -> bool {
+		SharedLoader::is_cancelled(&**self)
+	}
 }
 
 impl<L: Send + Sync + Loader<Document = JsonValue>> context::Loader for L {
@@ -27,8 +109,11 @@ impl<L: Send + Sync + Loader<Document = JsonValue>> context::Loader for L {
 	fn load_context<'a>(&'a mut self, url: Iri) -> BoxFuture<'a, Result<RemoteContext<JsonValue>, Error>> {
 		let url = IriBuf::from(url);
 		async move {
-			match self.load(url.as_iri()).await {
+			debug!("loading remote context `{}'", url);
+			let timer = Timer::start();
+			match instrument!(self.load(url.as_iri()), "load_context", url = %url).await {
 				Ok(remote_doc) => {
+					debug!("loaded remote context `{}' in {}", url, timer);
 					let (doc, url) = remote_doc.into_parts();
 					if let JsonValue::Object(obj) = doc {
 						if let Some(context) = obj.get("@context") {
@@ -40,12 +125,22 @@ impl<L: Send + Sync + Loader<Document = JsonValue>> context::Loader for L {
 						Err(ErrorCode::InvalidRemoteContext.into())
 					}
 				},
-				Err(_) => {
+				Err(e) if e.code() == ErrorCode::Cancelled => Err(e),
+				Err(e) => {
+					warn!("unable to load remote context `{}' ({}, after {})", url, e.code(), timer);
 					Err(ErrorCode::LoadingRemoteContextFailed.into())
 				}
 			}
 		}.boxed()
 	}
+
+	fn is_cancelled(&self) -> bool {
+		Loader::is_cancelled(self)
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		Loader::term_used(self, term, usage)
+	}
 }
 
 /// Dummy loader.
@@ -66,15 +161,66 @@ impl Loader for NoLoader {
 	}
 }
 
+#[cfg(feature = "fs-loader")]
+use std::collections::HashMap;
+#[cfg(feature = "fs-loader")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "fs-loader")]
+use std::fs::File;
+#[cfg(feature = "fs-loader")]
+use std::io::{Read, BufReader};
+
+/// Callback loader.
+///
+/// Loads documents by calling a user-provided function returning the content of the document at
+/// the given URL.
+/// This can be used to bridge an external fetching mechanism, such as the JavaScript `fetch`
+/// function when targeting WebAssembly, where blocking I/O is not available.
+///
+/// Raises a `LoadingDocumentFailed` error if the fetched content is not valid JSON.
+pub struct FnLoader<F> {
+	fetch: F
+}
+
+impl<F> FnLoader<F> {
+	/// Create a new loader using the given fetching function.
+	pub fn new(fetch: F) -> FnLoader<F> {
+		FnLoader {
+			fetch
+		}
+	}
+}
+
+impl<F: Send + FnMut(Iri) -> R, R: 'static + Send + Future<Output = Result<String, ErrorCode>>> Loader for FnLoader<F> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		debug!("loading document `{}'", url);
+		let content = (self.fetch)(url);
+		let url: IriBuf = url.into();
+		instrument!(async move {
+			match content.await {
+				Ok(content) => match parse_json(&content) {
+					Ok(doc) => Ok(RemoteDocument::new(doc, url.as_iri()).with_content(content)),
+					Err(_) => Err(ErrorCode::LoadingDocumentFailed.into())
+				},
+				Err(code) => Err(code.into())
+			}
+		}, "load_document", url = %url).boxed()
+	}
+}
+
 /// File-system loader.
 ///
 /// This is a special JSON-LD document loader that can load document from the file system by
 /// attaching a directory to specific URLs.
+#[cfg(feature = "fs-loader")]
 pub struct FsLoader {
 	cache: HashMap<IriBuf, RemoteDocument>,
 	mount_points: HashMap<PathBuf, IriBuf>
 }
 
+#[cfg(feature = "fs-loader")]
 impl FsLoader {
 	pub fn new() -> FsLoader {
 		FsLoader {
@@ -88,12 +234,13 @@ impl FsLoader {
 	}
 }
 
+#[cfg(feature = "fs-loader")]
 impl Loader for FsLoader {
 	type Document = JsonValue;
 
 	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
 		let url: IriBuf = url.into();
-		async move {
+		instrument!(async move {
 			match self.cache.get(&url) {
 				Some(doc) => Ok(doc.clone()),
 				None => {
@@ -106,12 +253,13 @@ impl Loader for FsLoader {
 									filepath.push(seg.as_str())
 								}
 
+								debug!("loading document `{}' from `{}'", url, filepath.display());
 								if let Ok(file) = File::open(filepath) {
 								    let mut buf_reader = BufReader::new(file);
 								    let mut contents = String::new();
 								    if buf_reader.read_to_string(&mut contents).is_ok() {
-										if let Ok(doc) = json::parse(contents.as_str()) {
-											let remote_doc = RemoteDocument::new(doc, url.as_iri());
+										if let Ok(doc) = parse_json(contents.as_str()) {
+											let remote_doc = RemoteDocument::new(doc, url.as_iri()).with_content(contents);
 											self.cache.insert(url.clone(), remote_doc.clone());
 											return Ok(remote_doc)
 										} else {
@@ -131,6 +279,6 @@ impl Loader for FsLoader {
 					Err(ErrorCode::LoadingDocumentFailed.into())
 				}
 			}
-		}.boxed()
+		}, "load_document", url = %url).boxed()
 	}
 }
@@ -0,0 +1,217 @@
+//! Processing metrics.
+//!
+//! The [`Metrics`] trait receives reports from the processor: documents fetched by a
+//! [`MeteredLoader`], cache hits, and statistics about expanded documents.
+//! It can be implemented on top of any metrics system (such as Prometheus counters),
+//! or the provided [`Counters`] can be used.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	ErrorCode,
+	Document,
+	ContextMut,
+	RemoteDocument,
+	ExpandedDocument,
+	Loader,
+	usage::TermUse,
+	util::parse_json
+};
+
+/// Metrics reporter.
+///
+/// Every method does nothing by default.
+/// Reports are received through a shared reference, since the same reporter may be used by
+/// concurrent processing tasks.
+pub trait Metrics: Send + Sync {
+	/// A document has been fetched by the underlying loader.
+	fn document_fetched(&self, _url: Iri) {}
+
+	/// A document has been served from the cache of a [`MeteredLoader`].
+	fn cache_hit(&self, _url: Iri) {}
+
+	/// A document has been expanded.
+	///
+	/// `bytes` is the length of the JSON source, `nodes` the number of nodes in the expanded
+	/// document (including embedded nodes), and `depth` its maximum nesting depth.
+	fn document_expanded(&self, _bytes: usize, _nodes: usize, _depth: usize) {}
+}
+
+impl<M: Metrics> Metrics for &M {
+	fn document_fetched(&self, url: Iri) {
+		(*self).document_fetched(url)
+	}
+
+	fn cache_hit(&self, url: Iri) {
+		(*self).cache_hit(url)
+	}
+
+	fn document_expanded(&self, bytes: usize, nodes: usize, depth: usize) {
+		(*self).document_expanded(bytes, nodes, depth)
+	}
+}
+
+/// Atomic counters, to be read by an exporter.
+#[derive(Default, Debug)]
+pub struct Counters {
+	documents_fetched: AtomicUsize,
+	cache_hits: AtomicUsize,
+	documents_expanded: AtomicUsize,
+	nodes_expanded: AtomicUsize,
+	max_depth: AtomicUsize,
+	bytes_processed: AtomicUsize
+}
+
+impl Counters {
+	/// Create new counters, all set to zero.
+	pub fn new() -> Counters {
+		Counters::default()
+	}
+
+	/// Number of documents (including contexts) fetched by the underlying loaders.
+	pub fn documents_fetched(&self) -> usize {
+		self.documents_fetched.load(Ordering::Relaxed)
+	}
+
+	/// Number of documents (including contexts) served from a cache.
+	pub fn cache_hits(&self) -> usize {
+		self.cache_hits.load(Ordering::Relaxed)
+	}
+
+	/// Number of expanded documents.
+	pub fn documents_expanded(&self) -> usize {
+		self.documents_expanded.load(Ordering::Relaxed)
+	}
+
+	/// Total number of expanded nodes.
+	pub fn nodes_expanded(&self) -> usize {
+		self.nodes_expanded.load(Ordering::Relaxed)
+	}
+
+	/// Maximum nesting depth reached by an expanded document.
+	pub fn max_depth(&self) -> usize {
+		self.max_depth.load(Ordering::Relaxed)
+	}
+
+	/// Total length of the processed JSON sources, in bytes.
+	pub fn bytes_processed(&self) -> usize {
+		self.bytes_processed.load(Ordering::Relaxed)
+	}
+}
+
+impl Metrics for Counters {
+	fn document_fetched(&self, _url: Iri) {
+		self.documents_fetched.fetch_add(1, Ordering::Relaxed);
+	}
+
+	fn cache_hit(&self, _url: Iri) {
+		self.cache_hits.fetch_add(1, Ordering::Relaxed);
+	}
+
+	fn document_expanded(&self, bytes: usize, nodes: usize, depth: usize) {
+		self.documents_expanded.fetch_add(1, Ordering::Relaxed);
+		self.nodes_expanded.fetch_add(nodes, Ordering::Relaxed);
+		self.max_depth.fetch_max(depth, Ordering::Relaxed);
+		self.bytes_processed.fetch_add(bytes, Ordering::Relaxed);
+	}
+}
+
+/// Caching loader reporting into a [`Metrics`] reporter.
+///
+/// Documents are fetched once by the inner loader, then served from the cache.
+pub struct MeteredLoader<L, M> {
+	inner: L,
+	metrics: M,
+	cache: HashMap<IriBuf, RemoteDocument>
+}
+
+impl<L, M> MeteredLoader<L, M> {
+	/// Create a new loader on top of the given loader.
+	pub fn new(inner: L, metrics: M) -> MeteredLoader<L, M> {
+		MeteredLoader {
+			inner,
+			metrics,
+			cache: HashMap::new()
+		}
+	}
+
+	/// Returns a reference to the metrics reporter.
+	pub fn metrics(&self) -> &M {
+		&self.metrics
+	}
+
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Clear the cache.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+
+	/// Consumes the loader and returns the inner loader and metrics reporter.
+	pub fn into_parts(self) -> (L, M) {
+		(self.inner, self.metrics)
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>, M: Metrics> Loader for MeteredLoader<L, M> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			match self.cache.get(&url) {
+				Some(doc) => {
+					self.metrics.cache_hit(url.as_iri());
+					Ok(doc.clone())
+				},
+				None => {
+					let doc = self.inner.load(url.as_iri()).await?;
+					self.metrics.document_fetched(url.as_iri());
+					self.cache.insert(url, doc.clone());
+					Ok(doc)
+				}
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Returns the number of nodes (including embedded nodes) of an expanded document, and its
+/// maximum nesting depth.
+pub fn stats<T: Id>(doc: &ExpandedDocument<T>) -> (usize, usize) {
+	let stats = doc.stats();
+	(stats.nodes, stats.max_depth)
+}
+
+/// Parse and expand the given JSON-LD source, reporting into the metrics of the loader.
+///
+/// A `loading document failed` error is returned if the source is not valid JSON.
+pub fn expand<'a, T: 'a + Send + Sync + Id, C: Send + Sync + ContextMut<T>, L: Send + Sync + Loader<Document = JsonValue>, M: Metrics>(source: &'a str, base_url: Option<Iri<'a>>, context: &'a C, loader: &'a mut MeteredLoader<L, M>) -> impl 'a + Send + Future<Output = Result<ExpandedDocument<T>, Error>> where C::LocalContext: Send + Sync + From<JsonValue> {
+	async move {
+		let doc = match parse_json(source) {
+			Ok(doc) => doc,
+			Err(_) => return Err(ErrorCode::LoadingDocumentFailed.into())
+		};
+
+		let expanded = doc.expand_with(base_url, context, loader, Default::default()).await?;
+		let (nodes, depth) = stats(&expanded);
+		loader.metrics.document_expanded(source.len(), nodes, depth);
+		Ok(expanded)
+	}
+}
@@ -0,0 +1,178 @@
+//! Custom literal datatypes.
+//!
+//! Applications can register the datatypes they use, such as `geo:wktLiteral` or units of
+//! measure, in a [`Datatypes`] registry, with a [`Datatype`] implementation parsing,
+//! validating and canonicalizing their lexical forms.
+//! The registry is consulted by [`rdf::to_rdf_with_datatypes`](crate::rdf::to_rdf_with_datatypes),
+//! which writes the canonical lexical form of valid literals, and by the [`Value`] methods
+//! defined here.
+//!
+//! ```ignore
+//! let datatypes = Datatypes::new().with(Iri::new("http://example.org/meters")?, Meters);
+//! let length: Option<f64> = value.parse_with(&datatypes);
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use iref::Iri;
+use crate::{
+	Id,
+	Value,
+	object::Literal
+};
+
+/// Literal datatype.
+pub trait Datatype: Send + Sync {
+	/// Parsed value.
+	type Value: 'static;
+
+	/// Parse the given lexical form, returning `None` if it is not valid.
+	fn parse(&self, lexical: &str) -> Option<Self::Value>;
+
+	/// Checks if the given lexical form is valid.
+	///
+	/// By default, a lexical form is valid if it can be parsed.
+	fn validate(&self, lexical: &str) -> bool {
+		self.parse(lexical).is_some()
+	}
+
+	/// Canonical lexical form of the given valid lexical form.
+	///
+	/// By default, the lexical form is kept as is.
+	fn canonicalize(&self, lexical: &str) -> String {
+		lexical.to_string()
+	}
+}
+
+/// Datatype with its values type erased, for storage in the registry.
+trait AnyDatatype: Send + Sync {
+	fn parse_any(&self, lexical: &str) -> Option<Box<dyn Any>>;
+
+	fn validate(&self, lexical: &str) -> bool;
+
+	fn canonicalize(&self, lexical: &str) -> String;
+}
+
+impl<D: Datatype> AnyDatatype for D {
+	fn parse_any(&self, lexical: &str) -> Option<Box<dyn Any>> {
+		self.parse(lexical).map(|value| Box::new(value) as Box<dyn Any>)
+	}
+
+	fn validate(&self, lexical: &str) -> bool {
+		Datatype::validate(self, lexical)
+	}
+
+	fn canonicalize(&self, lexical: &str) -> String {
+		Datatype::canonicalize(self, lexical)
+	}
+}
+
+/// Registry of custom datatypes, by IRI.
+#[derive(Default)]
+pub struct Datatypes {
+	datatypes: HashMap<String, Box<dyn AnyDatatype>>
+}
+
+impl Datatypes {
+	/// Create an empty registry.
+	pub fn new() -> Datatypes {
+		Datatypes::default()
+	}
+
+	/// Register a datatype, replacing any datatype previously registered with the same IRI.
+	pub fn insert<D: 'static + Datatype>(&mut self, iri: Iri, datatype: D) {
+		self.datatypes.insert(iri.as_str().to_string(), Box::new(datatype));
+	}
+
+	/// Register a datatype.
+	pub fn with<D: 'static + Datatype>(mut self, iri: Iri, datatype: D) -> Datatypes {
+		self.insert(iri, datatype);
+		self
+	}
+
+	/// Number of registered datatypes.
+	pub fn len(&self) -> usize {
+		self.datatypes.len()
+	}
+
+	/// Checks if no datatype is registered.
+	pub fn is_empty(&self) -> bool {
+		self.datatypes.is_empty()
+	}
+
+	/// Checks if a datatype is registered with the given IRI.
+	pub fn contains(&self, iri: Iri) -> bool {
+		self.datatypes.contains_key(iri.as_str())
+	}
+
+	/// Checks if the given lexical form is valid for the given datatype.
+	///
+	/// Returns `None` if the datatype is not registered.
+	pub fn validate(&self, iri: Iri, lexical: &str) -> Option<bool> {
+		self.datatypes.get(iri.as_str()).map(|datatype| datatype.validate(lexical))
+	}
+
+	/// Canonical lexical form of the given lexical form of the given datatype.
+	///
+	/// Returns `None` if the datatype is not registered, or the lexical form is not valid.
+	pub fn canonicalize(&self, iri: Iri, lexical: &str) -> Option<String> {
+		let datatype = self.datatypes.get(iri.as_str())?;
+		if datatype.validate(lexical) {
+			Some(datatype.canonicalize(lexical))
+		} else {
+			None
+		}
+	}
+
+	/// Parse the given lexical form of the given datatype.
+	///
+	/// Returns `None` if the datatype is not registered, the lexical form is not valid, or
+	/// the values of the datatype are not of type `V`.
+	pub fn parse<V: 'static>(&self, iri: Iri, lexical: &str) -> Option<V> {
+		self.datatypes.get(iri.as_str())?.parse_any(lexical)?.downcast().ok().map(|value| *value)
+	}
+}
+
+impl fmt::Debug for Datatypes {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_set().entries(self.datatypes.keys()).finish()
+	}
+}
+
+impl<T: Id> Value<T> {
+	/// Lexical form and registered datatype of the value, if it is a string typed with a
+	/// datatype of the registry.
+	fn registered<'a>(&'a self, datatypes: &Datatypes) -> Option<(&'a str, Iri<'a>)> {
+		match self {
+			Value::Literal(Literal::String(s), types) => types.iter().map(Id::as_iri).find(|ty| datatypes.contains(*ty)).map(|ty| (s.as_str(), ty)),
+			_ => None
+		}
+	}
+
+	/// Parse the value with its registered datatype.
+	///
+	/// Returns `None` if the value is not a string typed with a datatype of the registry,
+	/// if its lexical form is not valid, or if the values of the datatype are not of type `V`.
+	pub fn parse_with<V: 'static>(&self, datatypes: &Datatypes) -> Option<V> {
+		let (lexical, ty) = self.registered(datatypes)?;
+		datatypes.parse(ty, lexical)
+	}
+
+	/// Checks if the lexical form of the value is valid for its registered datatype.
+	///
+	/// Returns `None` if the value is not a string typed with a datatype of the registry.
+	pub fn validate_with(&self, datatypes: &Datatypes) -> Option<bool> {
+		let (lexical, ty) = self.registered(datatypes)?;
+		datatypes.validate(ty, lexical)
+	}
+
+	/// Canonical lexical form of the value, according to its registered datatype.
+	///
+	/// Returns `None` if the value is not a string typed with a datatype of the registry,
+	/// or if its lexical form is not valid.
+	pub fn canonical_with(&self, datatypes: &Datatypes) -> Option<String> {
+		let (lexical, ty) = self.registered(datatypes)?;
+		datatypes.canonicalize(ty, lexical)
+	}
+}
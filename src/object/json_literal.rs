@@ -0,0 +1,255 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use json::{
+	JsonValue,
+	number::Number
+};
+use crate::util::AsJson;
+
+/// JSON literal, the value of a value object typed with `@json`.
+///
+/// Along with the JSON value, the literal keeps its compact serialization, which reproduces
+/// the member order and the number lexical forms of its source, contrarily to
+/// [`JsonValue::dump`], which normalizes numbers (`1e2` is dumped as `100`).
+/// The serialization is only used to write the literal back: literals are compared and hashed
+/// by their [canonical form](JsonLiteral::canonical_form), so that `{"a":1,"b":2}` and
+/// `{"b":2,"a":1}`, or `1e2` and `100`, are equal.
+/// JSON literals are rare, and boxed to keep values small.
+/// ```ignore
+/// let literal = JsonLiteral::parse(r#"{"b": 1.50, "a": 1E+2}"#)?;
+/// assert_eq!(literal.as_str(), r#"{"b":1.50,"a":1E+2}"#);
+/// ```
+#[derive(Clone, Debug)]
+pub struct JsonLiteral(Box<Data>);
+
+#[derive(Clone, Debug)]
+struct Data {
+	/// Compact serialization.
+	text: String,
+
+	/// Parsed value.
+	value: JsonValue
+}
+
+impl JsonLiteral {
+	/// Parse a JSON literal, keeping the lexical form of its numbers and strings.
+	///
+	/// Only insignificant whitespace is removed.
+	pub fn parse(source: &str) -> Result<JsonLiteral, json::Error> {
+		let value = json::parse(source)?;
+
+		let mut text = String::with_capacity(source.len());
+		let mut in_string = false;
+		let mut escaped = false;
+		for c in source.chars() {
+			if in_string {
+				text.push(c);
+				match c {
+					_ if escaped => escaped = false,
+					'\\' => escaped = true,
+					'"' => in_string = false,
+					_ => ()
+				}
+			} else if !c.is_whitespace() {
+				text.push(c);
+				in_string = c == '"'
+			}
+		}
+
+		Ok(JsonLiteral(Box::new(Data {
+			text,
+			value
+		})))
+	}
+
+	/// Compact serialization of the literal.
+	pub fn as_str(&self) -> &str {
+		&self.0.text
+	}
+
+	/// JSON value of the literal.
+	pub fn value(&self) -> &JsonValue {
+		&self.0.value
+	}
+
+	/// Returns the JSON value of the literal.
+	pub fn into_value(self) -> JsonValue {
+		self.0.value
+	}
+
+	/// Canonical serialization of the literal, following the
+	/// [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785) (JCS).
+	///
+	/// Object members are sorted by key and numbers are normalized.
+	/// This is the lexical form of the literal in RDF.
+	pub fn canonical_form(&self) -> String {
+		canonical_json(&self.0.value)
+	}
+}
+
+/// Canonical serialization of a JSON value, with object members sorted by the UTF-16 code units
+/// of their key.
+fn canonical_json(value: &JsonValue) -> String {
+	match value {
+		JsonValue::Array(items) => {
+			let items: Vec<_> = items.iter().map(canonical_json).collect();
+			format!("[{}]", items.join(","))
+		},
+		JsonValue::Object(obj) => {
+			let mut entries: Vec<_> = obj.iter().collect();
+			entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+			let entries: Vec<_> = entries.into_iter().map(|(key, value)| format!("{}:{}", JsonValue::from(key).dump(), canonical_json(value))).collect();
+			format!("{{{}}}", entries.join(","))
+		},
+		JsonValue::Number(n) => canonical_number((*n).into()),
+		value => value.dump()
+	}
+}
+
+/// Canonical serialization of a number, as the ECMAScript `Number.prototype.toString` method:
+/// the shortest decimal form parsing back to the same double, with an exponent for very large
+/// and very small numbers.
+fn canonical_number(n: f64) -> String {
+	if n == 0.0 || !n.is_finite() {
+		return "0".to_string()
+	}
+
+	let sign = if n < 0.0 { "-" } else { "" };
+	let scientific = format!("{:e}", n.abs());
+	let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+	let digits = mantissa.replace('.', "");
+	let k = digits.len() as i32;
+	let n = exponent[1..].parse::<i32>().unwrap() + 1;
+
+	let result = if k <= n && n <= 21 {
+		format!("{}{}", digits, "0".repeat((n - k) as usize))
+	} else if 0 < n && n <= 21 {
+		format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+	} else if -6 < n && n <= 0 {
+		format!("0.{}{}", "0".repeat(-n as usize), digits)
+	} else {
+		let exponent = if n > 0 { format!("+{}", n - 1) } else { format!("-{}", 1 - n) };
+		match digits.split_at(1) {
+			(first, "") => format!("{}e{}", first, exponent),
+			(first, rest) => format!("{}.{}e{}", first, rest, exponent)
+		}
+	};
+
+	format!("{}{}", sign, result)
+}
+
+impl From<JsonValue> for JsonLiteral {
+	/// Serializes the value, writing numbers so that they are parsed back with the same
+	/// mantissa and exponent.
+	fn from(value: JsonValue) -> JsonLiteral {
+		let mut text = String::new();
+		write_value(&value, &mut text);
+		JsonLiteral(Box::new(Data {
+			text,
+			value
+		}))
+	}
+}
+
+impl<'a> From<&'a JsonValue> for JsonLiteral {
+	fn from(value: &'a JsonValue) -> JsonLiteral {
+		value.clone().into()
+	}
+}
+
+impl From<JsonLiteral> for JsonValue {
+	fn from(literal: JsonLiteral) -> JsonValue {
+		literal.0.value
+	}
+}
+
+impl PartialEq for JsonLiteral {
+	fn eq(&self, other: &JsonLiteral) -> bool {
+		self.0.text == other.0.text || self.canonical_form() == other.canonical_form()
+	}
+}
+
+impl Eq for JsonLiteral {}
+
+impl Hash for JsonLiteral {
+	fn hash<H: Hasher>(&self, h: &mut H) {
+		self.canonical_form().hash(h)
+	}
+}
+
+impl fmt::Display for JsonLiteral {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0.text)
+	}
+}
+
+impl AsJson for JsonLiteral {
+	fn as_json(&self) -> JsonValue {
+		self.0.value.clone()
+	}
+}
+
+fn write_value(value: &JsonValue, text: &mut String) {
+	match value {
+		JsonValue::Number(n) => write_number(n, text),
+		JsonValue::Array(items) => {
+			text.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					text.push(',')
+				}
+
+				write_value(item, text)
+			}
+			text.push(']')
+		},
+		JsonValue::Object(object) => {
+			text.push('{');
+			for (i, (key, value)) in object.iter().enumerate() {
+				if i > 0 {
+					text.push(',')
+				}
+
+				text.push_str(&JsonValue::from(key).dump());
+				text.push(':');
+				write_value(value, text)
+			}
+			text.push('}')
+		},
+		value => text.push_str(&value.dump())
+	}
+}
+
+/// Write a number from its mantissa and exponent: `1e2` and `100`, or `1.50` and `1.5`, are
+/// kept distinct.
+fn write_number(n: &Number, text: &mut String) {
+	if n.is_nan() {
+		text.push_str("null");
+		return
+	}
+
+	let (positive, mantissa, exponent) = n.as_parts();
+	if !positive {
+		text.push('-')
+	}
+
+	let digits = mantissa.to_string();
+	let decimals = -(exponent as i32);
+	if exponent > 0 || decimals > digits.len() as i32 + 6 {
+		text.push_str(&format!("{}e{}", digits, exponent))
+	} else if decimals > 0 {
+		let decimals = decimals as usize;
+		if decimals >= digits.len() {
+			text.push_str("0.");
+			text.push_str(&"0".repeat(decimals - digits.len()));
+			text.push_str(&digits)
+		} else {
+			let (int, frac) = digits.split_at(digits.len() - decimals);
+			text.push_str(int);
+			text.push('.');
+			text.push_str(frac)
+		}
+	} else {
+		text.push_str(&digits)
+	}
+}
@@ -2,6 +2,9 @@
 
 pub mod value;
 pub mod node;
+pub mod xsd;
+pub mod datatype;
+pub mod json_literal;
 
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -22,18 +25,22 @@ pub use value::{
 	Value
 };
 pub use node::Node;
+pub use json_literal::JsonLiteral;
 
 /// Object.
 ///
 /// JSON-LD connects together multiple kinds of data objects.
 /// Objects may be nodes, values or lists of objects.
-#[derive(PartialEq, Eq, Hash)]
+///
+/// Nodes are boxed, since they are much larger than values: this way, the many value objects
+/// of a document do not take the size of a node each.
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub enum Object<T: Id = IriBuf> {
 	/// Value object.
 	Value(Value<T>),
 
 	/// Node object.
-	Node(Node<T>),
+	Node(Box<Node<T>>),
 
 	/// List object.
 	List(Vec<Indexed<Object<T>>>),
@@ -91,6 +98,78 @@ impl<T: Id> Object<T> {
 		}
 	}
 
+	/// Returns the node, if the object is a node.
+	pub fn as_node(&self) -> Option<&Node<T>> {
+		match self {
+			Object::Node(node) => Some(node),
+			_ => None
+		}
+	}
+
+	/// Returns a mutable reference to the node, if the object is a node.
+	pub fn as_node_mut(&mut self) -> Option<&mut Node<T>> {
+		match self {
+			Object::Node(node) => Some(node),
+			_ => None
+		}
+	}
+
+	/// Converts the object into a node, if it is one.
+	pub fn into_node(self) -> Option<Node<T>> {
+		match self {
+			Object::Node(node) => Some(*node),
+			_ => None
+		}
+	}
+
+	/// Returns the value, if the object is a value.
+	pub fn as_value(&self) -> Option<&Value<T>> {
+		match self {
+			Object::Value(value) => Some(value),
+			_ => None
+		}
+	}
+
+	/// Returns a mutable reference to the value, if the object is a value.
+	pub fn as_value_mut(&mut self) -> Option<&mut Value<T>> {
+		match self {
+			Object::Value(value) => Some(value),
+			_ => None
+		}
+	}
+
+	/// Converts the object into a value, if it is one.
+	pub fn into_value(self) -> Option<Value<T>> {
+		match self {
+			Object::Value(value) => Some(value),
+			_ => None
+		}
+	}
+
+	/// Returns the list items, if the object is a list.
+	pub fn as_list(&self) -> Option<&[Indexed<Object<T>>]> {
+		match self {
+			Object::List(items) => Some(items),
+			_ => None
+		}
+	}
+
+	/// Returns a mutable reference to the list items, if the object is a list.
+	pub fn as_list_mut(&mut self) -> Option<&mut Vec<Indexed<Object<T>>>> {
+		match self {
+			Object::List(items) => Some(items),
+			_ => None
+		}
+	}
+
+	/// Converts the object into its list items, if it is a list.
+	pub fn into_list(self) -> Option<Vec<Indexed<Object<T>>>> {
+		match self {
+			Object::List(items) => Some(items),
+			_ => None
+		}
+	}
+
 	/// Get the object as a string.
 	///
 	/// If the object is a value that is a string, returns this string.
@@ -111,7 +190,7 @@ impl<T: Id> Object<T> {
 			Object::Node(n) => {
 				match n.into_unnamed_graph() {
 					Ok(g) => Ok(g),
-					Err(n) => Err(Indexed::new(Object::Node(n), index))
+					Err(n) => Err(Indexed::new(Object::Node(Box::new(n)), index))
 				}
 			},
 			obj => Err(Indexed::new(obj, index))
@@ -119,6 +198,35 @@ impl<T: Id> Object<T> {
 	}
 }
 
+impl<T: Id> Indexed<Object<T>> {
+	/// Converts the object into an indexed node, if it is one.
+	///
+	/// The index is preserved.
+	pub fn into_node(self) -> Option<Indexed<Node<T>>> {
+		self.try_cast().ok()
+	}
+
+	/// Converts the object into an indexed value, if it is one.
+	///
+	/// The index is preserved.
+	pub fn into_value(self) -> Option<Indexed<Value<T>>> {
+		self.try_cast().ok()
+	}
+
+	/// Tests if the object is free-floating.
+	///
+	/// Free-floating objects are value objects, list objects, and free-floating nodes
+	/// (see [`Indexed::<Node>::is_free_floating`](Indexed::<Node<T>>::is_free_floating)).
+	/// They are dropped by the expansion algorithm when they appear at the top level of a
+	/// document or directly in a graph.
+	pub fn is_free_floating(&self) -> bool {
+		match self.inner() {
+			Object::Value(_) | Object::List(_) => true,
+			Object::Node(node) => self.index().is_none() && node.is_empty()
+		}
+	}
+}
+
 impl<T: Id> fmt::Debug for Object<T> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "{}", self.as_json().pretty(2))
@@ -133,7 +241,7 @@ impl<T: Id> From<Value<T>> for Object<T> {
 
 impl<T: Id> From<Node<T>> for Object<T> {
 	fn from(node: Node<T>) -> Object<T> {
-		Object::Node(node)
+		Object::Node(Box::new(node))
 	}
 }
 
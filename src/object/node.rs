@@ -4,13 +4,17 @@ use std::convert::TryFrom;
 use std::borrow::Borrow;
 use iref::{Iri, IriBuf};
 use json::JsonValue;
+use smallvec::SmallVec;
 use crate::{
 	Id,
 	Reference,
 	ToReference,
 	Lenient,
 	Object,
+	Value,
 	Indexed,
+	LangString,
+	lookup_language,
 	syntax::{
 		Keyword,
 		Term,
@@ -18,12 +22,18 @@ use crate::{
 	util
 };
 
+/// Values of a node property.
+///
+/// Most properties have a single value, stored inline without allocation.
+pub(crate) type PropertyValues<T> = SmallVec<[Indexed<Object<T>>; 1]>;
+
 /// A node object.
 ///
 /// A node is defined by its identifier (`@id` field), types, properties and reverse properties.
 /// In addition, a node may represent a graph (`@graph field`) and includes nodes
 /// (`@included` field).
-#[derive(PartialEq, Eq)]
+/// The graph and included nodes are rarely used, and boxed to keep nodes small.
+#[derive(PartialEq, Eq, Clone)]
 pub struct Node<T: Id = IriBuf> {
 	/// Identifier.
 	///
@@ -38,27 +48,52 @@ pub struct Node<T: Id = IriBuf> {
 	/// Associated graph.
 	///
 	/// This is the `@graph` field.
-	pub(crate) graph: Option<HashSet<Indexed<Object<T>>>>,
+	#[allow(clippy::box_collection)]
+	pub(crate) graph: Option<Box<HashSet<Indexed<Object<T>>>>>,
 
 	/// Included nodes.
 	///
 	/// This is the `@included` field.
-	pub(crate) included: Option<HashSet<Indexed<Node<T>>>>,
+	#[allow(clippy::box_collection)]
+	pub(crate) included: Option<Box<HashSet<Indexed<Node<T>>>>>,
 
 	/// Properties.
 	///
 	/// Any non-keyword field.
-	pub(crate) properties: HashMap<Reference<T>, Vec<Indexed<Object<T>>>>,
+	pub(crate) properties: HashMap<Reference<T>, PropertyValues<T>>,
 
 	/// Reverse properties.
 	///
 	/// This is the `@reverse` field.
-	pub(crate) reverse_properties: HashMap<Reference<T>, Vec<Indexed<Node<T>>>>
+	pub(crate) reverse_properties: HashMap<Reference<T>, Vec<Indexed<Node<T>>>>,
+
+	/// Extension entries.
+	///
+	/// Entries whose key has the form of a keyword without being a JSON-LD keyword,
+	/// kept by the [unknown keywords handler](crate::expansion::Options::unknown_keywords).
+	pub(crate) extensions: HashMap<String, Vec<Indexed<Object<T>>>>
 }
 
 /// Iterator through indexed objects.
 pub struct Objects<'a, T: Id>(Option<std::slice::Iter<'a, Indexed<Object<T>>>>);
 
+impl<'a, T: Id> Objects<'a, T> {
+	/// Only iterate through the node objects.
+	pub fn nodes(self) -> Nodes<'a, T> {
+		Nodes(self)
+	}
+
+	/// Only iterate through the value objects.
+	pub fn values(self) -> Values<'a, T> {
+		Values(self)
+	}
+
+	/// Only iterate through the list objects.
+	pub fn lists(self) -> Lists<'a, T> {
+		Lists(self)
+	}
+}
+
 impl<'a, T: Id> Iterator for Objects<'a, T> {
 	type Item = &'a Indexed<Object<T>>;
 
@@ -70,6 +105,39 @@ impl<'a, T: Id> Iterator for Objects<'a, T> {
 	}
 }
 
+/// Iterator through the node objects of an [`Objects`] iterator.
+pub struct Nodes<'a, T: Id>(Objects<'a, T>);
+
+impl<'a, T: Id> Iterator for Nodes<'a, T> {
+	type Item = &'a Node<T>;
+
+	fn next(&mut self) -> Option<&'a Node<T>> {
+		self.0.by_ref().find_map(|object| object.as_node())
+	}
+}
+
+/// Iterator through the value objects of an [`Objects`] iterator.
+pub struct Values<'a, T: Id>(Objects<'a, T>);
+
+impl<'a, T: Id> Iterator for Values<'a, T> {
+	type Item = &'a Value<T>;
+
+	fn next(&mut self) -> Option<&'a Value<T>> {
+		self.0.by_ref().find_map(|object| object.as_value())
+	}
+}
+
+/// Iterator through the items of the list objects of an [`Objects`] iterator.
+pub struct Lists<'a, T: Id>(Objects<'a, T>);
+
+impl<'a, T: Id> Iterator for Lists<'a, T> {
+	type Item = &'a [Indexed<Object<T>>];
+
+	fn next(&mut self) -> Option<&'a [Indexed<Object<T>>]> {
+		self.0.by_ref().find_map(|object| object.as_list())
+	}
+}
+
 impl<T: Id> Node<T> {
 	/// Create a new empty node.
 	pub fn new() -> Node<T> {
@@ -79,10 +147,18 @@ impl<T: Id> Node<T> {
 			graph: None,
 			included: None,
 			properties: HashMap::new(),
-			reverse_properties: HashMap::new()
+			reverse_properties: HashMap::new(),
+			extensions: HashMap::new()
 		}
 	}
 
+	/// Create a new node with the given identifier, and no other entry.
+	pub fn with_id(id: Reference<T>) -> Node<T> {
+		let mut node = Node::new();
+		node.id = Some(Lenient::Ok(id));
+		node
+	}
+
 	/// Checks if the node object has the given term as key.
 	///
 	/// # Example
@@ -132,7 +208,7 @@ impl<T: Id> Node<T> {
 		}
 	}
 
-	/// Get the list of the node's types.
+	/// Get the list of the node's types, in the order in which they are declared.
 	///
 	/// This returns a list of `Lenient` types, including malformed types that are not
 	/// IRIs of blank node identifiers.
@@ -140,6 +216,11 @@ impl<T: Id> Node<T> {
 		self.types.as_ref()
 	}
 
+	/// Get the set of the node's types, for order-insensitive comparisons.
+	pub fn type_set(&self) -> HashSet<&Lenient<Reference<T>>> {
+		self.types.iter().collect()
+	}
+
 	/// Checks if the node has the given type.
 	pub fn has_type<U>(&self, ty: &U) -> bool where Lenient<Reference<T>>: PartialEq<U> {
 		for self_ty in &self.types {
@@ -151,6 +232,44 @@ impl<T: Id> Node<T> {
 		false
 	}
 
+	/// Checks if the node has all the given types, in any order.
+	pub fn has_types<U>(&self, types: &[U]) -> bool where Lenient<Reference<T>>: PartialEq<U> {
+		types.iter().all(|ty| self.has_type(ty))
+	}
+
+	/// Add a type after the existing ones, unless the node already has it.
+	///
+	/// Returns `true` if the type has been added.
+	pub fn insert_type(&mut self, ty: Lenient<Reference<T>>) -> bool {
+		if self.types.contains(&ty) {
+			false
+		} else {
+			self.types.push(ty);
+			true
+		}
+	}
+
+	/// Remove a type, keeping the order of the others.
+	///
+	/// Returns `true` if the node had the type.
+	pub fn remove_type(&mut self, ty: &Lenient<Reference<T>>) -> bool {
+		match self.types.iter().position(|t| t == ty) {
+			Some(i) => {
+				self.types.remove(i);
+				true
+			},
+			None => false
+		}
+	}
+
+	/// Replace the types of the node, keeping only the first occurrence of duplicate types.
+	pub fn set_types<I: IntoIterator<Item = Lenient<Reference<T>>>>(&mut self, types: I) {
+		self.types.clear();
+		for ty in types {
+			self.insert_type(ty);
+		}
+	}
+
 	/// Tests if the node is empty.
 	///
 	/// It is empty is every field except for `@id` is empty.
@@ -160,6 +279,15 @@ impl<T: Id> Node<T> {
 		&& self.included.is_none()
 		&& self.properties.is_empty()
 		&& self.reverse_properties.is_empty()
+		&& self.extensions.is_empty()
+	}
+
+	/// Tests if the node has an `@id` field, and no other field.
+	///
+	/// The index of an indexed node is not considered, see
+	/// [`Indexed::<Node>::has_only_id`](Indexed::<Node<T>>::has_only_id).
+	pub fn has_only_id(&self) -> bool {
+		self.id.is_some() && self.is_empty()
 	}
 
 	/// Tests if the node is a graph object (has a `@graph` field).
@@ -169,36 +297,36 @@ impl<T: Id> Node<T> {
 
 	/// If the node is a graph object, get the graph.
 	pub fn graph(&self) -> Option<&HashSet<Indexed<Object<T>>>> {
-		self.graph.as_ref()
+		self.graph.as_deref()
 	}
 
 	/// If the node is a graph object, get the mutable graph.
 	pub fn graph_mut(&mut self) -> Option<&mut HashSet<Indexed<Object<T>>>> {
-		self.graph.as_mut()
+		self.graph.as_deref_mut()
 	}
 
 	/// Set the graph.
 	pub fn set_graph(&mut self, graph: Option<HashSet<Indexed<Object<T>>>>) {
-		self.graph = graph
+		self.graph = graph.map(Box::new)
 	}
 
 	/// Get the set of nodes included by this node.
 	///
 	/// This correspond to the `@included` field in the JSON representation.
 	pub fn included(&self) -> Option<&HashSet<Indexed<Node<T>>>> {
-		self.included.as_ref()
+		self.included.as_deref()
 	}
 
 	/// Get the mutable set of nodes included by this node.
 	///
 	/// This correspond to the `@included` field in the JSON representation.
 	pub fn included_mut(&mut self) -> Option<&mut HashSet<Indexed<Node<T>>>> {
-		self.included.as_mut()
+		self.included.as_deref_mut()
 	}
 
 	/// Set the set of nodes included by the node.
 	pub fn set_included(&mut self, included: Option<HashSet<Indexed<Node<T>>>>) {
-		self.included = included
+		self.included = included.map(Box::new)
 	}
 
 	/// Get all the objects associated to the node with the given property.
@@ -220,12 +348,23 @@ impl<T: Id> Node<T> {
 		}
 	}
 
+	/// Get the language string value of the given property best matching the given language
+	/// priority list, such as `&["en-GB", "en", "*"]`.
+	///
+	/// See [`lookup_language`] for the matching rules.
+	pub fn get_lang_preferred<'a, Q: ToReference<T>>(&self, prop: Q, ranges: &[&str]) -> Option<&LangString> where T: 'a {
+		lookup_language(self.get(prop).values().filter_map(|value| match value {
+			Value::LangString(s) => Some(s),
+			_ => None
+		}), ranges)
+	}
+
 	/// Associate the given object to the node through the given property.
 	pub fn insert(&mut self, prop: Reference<T>, value: Indexed<Object<T>>) {
 		if let Some(node_values) = self.properties.get_mut(&prop) {
 			node_values.push(value);
 		} else {
-			let mut node_values = Vec::new();
+			let mut node_values = PropertyValues::new();
 			node_values.push(value);
 			self.properties.insert(prop, node_values);
 		}
@@ -258,6 +397,25 @@ impl<T: Id> Node<T> {
 		}
 	}
 
+	/// Get the values of the given extension entry.
+	///
+	/// Extension entries are entries whose key has the form of a keyword (such as
+	/// `@annotation`) without being a JSON-LD keyword, kept during expansion by the
+	/// [unknown keywords handler](crate::expansion::Options::unknown_keywords).
+	pub fn extension(&self, keyword: &str) -> Option<&[Indexed<Object<T>>]> {
+		self.extensions.get(keyword).map(Vec::as_slice)
+	}
+
+	/// Iterate through the extension entries of the node.
+	pub fn extensions(&self) -> impl Iterator<Item = (&str, &[Indexed<Object<T>>])> {
+		self.extensions.iter().map(|(keyword, values)| (keyword.as_str(), values.as_slice()))
+	}
+
+	/// Insert a value in the given extension entry.
+	pub fn insert_extension(&mut self, keyword: String, value: Indexed<Object<T>>) {
+		self.extensions.entry(keyword).or_default().push(value)
+	}
+
 	/// Tests if the node is an unnamed graph object.
 	///
 	/// Returns `true` is the only field of the object is a `@graph` field.
@@ -269,6 +427,7 @@ impl<T: Id> Node<T> {
 		&& self.included.is_none()
 		&& self.properties.is_empty()
 		&& self.reverse_properties.is_empty()
+		&& self.extensions.is_empty()
 	}
 
 	/// Returns the node as an unnamed graph, if it is one.
@@ -277,19 +436,35 @@ impl<T: Id> Node<T> {
 	/// Fails and returns itself if the node is *not* an unnamed graph.
 	pub fn into_unnamed_graph(self) -> Result<HashSet<Indexed<Object<T>>>, Node<T>> {
 		if self.is_unnamed_graph() {
-			Ok(self.graph.unwrap())
+			Ok(*self.graph.unwrap())
 		} else {
 			Err(self)
 		}
 	}
 }
 
+impl<T: Id> Indexed<Node<T>> {
+	/// Tests if the node has an `@id` field, and no other field (including `@index`).
+	pub fn has_only_id(&self) -> bool {
+		self.index().is_none() && self.inner().has_only_id()
+	}
+
+	/// Tests if the node is free-floating: it is either empty, or has no other field than
+	/// `@id`.
+	///
+	/// Such nodes do not carry any information when they appear at the top level of a
+	/// document or directly in a graph, and are dropped by the expansion algorithm.
+	pub fn is_free_floating(&self) -> bool {
+		self.index().is_none() && self.inner().is_empty()
+	}
+}
+
 impl<T: Id> TryFrom<Object<T>> for Node<T> {
 	type Error = Object<T>;
 
 	fn try_from(obj: Object<T>) -> Result<Node<T>, Object<T>> {
 		match obj {
-			Object::Node(node) => Ok(node),
+			Object::Node(node) => Ok(*node),
 			obj => Err(obj)
 		}
 	}
@@ -299,10 +474,15 @@ impl<T: Id> Hash for Node<T> {
 	fn hash<H: Hasher>(&self, h: &mut H) {
 		self.id.hash(h);
 		self.types.hash(h);
-		util::hash_set_opt(&self.graph, h);
-		util::hash_set_opt(&self.included, h);
+		if let Some(graph) = self.graph() {
+			util::hash_set(graph, h)
+		}
+		if let Some(included) = self.included() {
+			util::hash_set(included, h)
+		}
 		util::hash_map(&self.properties, h);
 		util::hash_map(&self.reverse_properties, h);
+		util::hash_map(&self.extensions, h);
 	}
 }
 
@@ -327,18 +507,22 @@ impl<T: Id> util::AsJson for Node<T> {
 		}
 
 		if !self.reverse_properties.is_empty() {
-			let mut reverse = json::object::Object::new();
-			for (key, value) in &self.reverse_properties {
-				reverse.insert(key.as_str(), value.as_json())
-			}
-
-			obj.insert(Keyword::Reverse.into(), JsonValue::Object(reverse))
+			let reverse = util::sorted_json_object(self.reverse_properties.iter().map(|(key, value)| (key.as_str(), value.as_json())));
+			obj.insert(Keyword::Reverse.into(), reverse)
 		}
 
-		for (key, value) in &self.properties {
+		let mut properties: Vec<_> = self.properties.iter().collect();
+		properties.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		for (key, value) in properties {
 			obj.insert(key.as_str(), value.as_json())
 		}
 
+		let mut extensions: Vec<_> = self.extensions.iter().collect();
+		extensions.sort_by_key(|(key, _)| key.as_str());
+		for (key, value) in extensions {
+			obj.insert(key, value.as_json())
+		}
+
 		JsonValue::Object(obj)
 	}
 }
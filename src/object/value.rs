@@ -1,10 +1,13 @@
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
+use std::convert::TryFrom;
 use iref::IriBuf;
 use json::JsonValue;
 use crate::{
 	Id,
 	LangString,
+	Object,
+	object::JsonLiteral,
 	syntax::Keyword,
 	util
 };
@@ -25,7 +28,7 @@ pub enum Literal {
 	String(String),
 
 	/// A JSON literal value.
-	Json(JsonValue)
+	Json(JsonLiteral)
 }
 
 impl PartialEq for Literal {
@@ -38,6 +41,7 @@ impl PartialEq for Literal {
 				a.as_parts() == b.as_parts()
 			},
 			(String(a), String(b)) => a == b,
+			(Json(a), Json(b)) => a == b,
 			_ => false
 		}
 	}
@@ -52,7 +56,7 @@ impl Hash for Literal {
 			Literal::Boolean(b) => b.hash(h),
 			Literal::Number(n) => util::hash_json_number(n, h),
 			Literal::String(s) => s.hash(h),
-			Literal::Json(value) => util::hash_json(value, h)
+			Literal::Json(value) => value.hash(h)
 		}
 	}
 }
@@ -99,6 +103,17 @@ impl<T: Id> Value<T> {
 	}
 }
 
+impl<T: Id> TryFrom<Object<T>> for Value<T> {
+	type Error = Object<T>;
+
+	fn try_from(obj: Object<T>) -> Result<Value<T>, Object<T>> {
+		match obj {
+			Object::Value(value) => Ok(value),
+			obj => Err(obj)
+		}
+	}
+}
+
 impl<T: Id> util::AsJson for Value<T> {
 	fn as_json(&self) -> JsonValue {
 		let mut obj = json::object::Object::new();
@@ -129,7 +144,7 @@ impl<T: Id> util::AsJson for Value<T> {
 						obj.insert(Keyword::Value.into(), s.as_json())
 					},
 					Literal::Json(json) => {
-						obj.insert(Keyword::Value.into(), json.clone());
+						obj.insert(Keyword::Value.into(), json.as_json());
 
 						tys = if let Some(tys) = tys {
 							let mut ary = match tys {
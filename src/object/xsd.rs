@@ -0,0 +1,507 @@
+//! XML Schema datatypes.
+//!
+//! Value objects typed with an `xsd` datatype store the lexical form of the value.
+//! The [`Value`] methods defined here check the datatype and parse the lexical form,
+//! also accepting native JSON numbers where numbers are expected.
+//! Dates, times and durations are parsed into [`chrono`](https://crates.io/crates/chrono) types
+//! with the `chrono` feature.
+//! [`Value::semantically_equals`] compares values by what they denote rather than by their
+//! lexical form.
+//!
+//! ```ignore
+//! let issued: Option<DateTime<FixedOffset>> = value.as_datetime();
+//! let count: Option<i64> = value.as_integer();
+//! assert!(value.semantically_equals(&other));
+//! ```
+
+use std::convert::TryFrom;
+use json::number::Number;
+use crate::{
+	Id,
+	Value,
+	object::Literal
+};
+
+/// XML Schema namespace.
+pub const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// `xsd:string` datatype.
+pub const STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// `xsd:boolean` datatype.
+pub const BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+
+/// `xsd:integer` datatype.
+pub const INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+
+/// `xsd:decimal` datatype.
+pub const DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
+
+/// `xsd:double` datatype.
+pub const DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+/// Integer datatypes, derived from `xsd:integer`.
+const INTEGERS: &[&str] = &[
+	"integer",
+	"long",
+	"int",
+	"short",
+	"byte",
+	"nonNegativeInteger",
+	"positiveInteger",
+	"nonPositiveInteger",
+	"negativeInteger",
+	"unsignedLong",
+	"unsignedInt",
+	"unsignedShort",
+	"unsignedByte"
+];
+
+/// Decimal datatypes: `xsd:decimal` and the integer datatypes.
+const DECIMALS: &[&str] = &[
+	"decimal",
+	"integer",
+	"long",
+	"int",
+	"short",
+	"byte",
+	"nonNegativeInteger",
+	"positiveInteger",
+	"nonPositiveInteger",
+	"negativeInteger",
+	"unsignedLong",
+	"unsignedInt",
+	"unsignedShort",
+	"unsignedByte"
+];
+
+/// Floating point datatypes.
+const FLOATS: &[&str] = &[
+	"double",
+	"float"
+];
+
+/// Numeric value, for comparisons across numeric datatypes.
+enum Numeric {
+	Decimal(Number),
+	Double(f64)
+}
+
+impl PartialEq for Numeric {
+	fn eq(&self, other: &Numeric) -> bool {
+		match (self, other) {
+			(Numeric::Decimal(a), Numeric::Decimal(b)) => normalize(a) == normalize(b),
+			(Numeric::Decimal(a), Numeric::Double(b)) | (Numeric::Double(b), Numeric::Decimal(a)) => f64::from(*a) == *b,
+			(Numeric::Double(a), Numeric::Double(b)) => a == b
+		}
+	}
+}
+
+impl<T: Id> Value<T> {
+	/// Checks that the value is typed, with one of the given `xsd` datatypes.
+	fn has_datatype(&self, datatypes: &[&str]) -> bool {
+		match self {
+			Value::Literal(_, types) => {
+				!types.is_empty() && types.iter().all(|ty| match ty.as_iri().as_str().strip_prefix(XSD) {
+					Some(name) => datatypes.contains(&name),
+					None => false
+				})
+			},
+			Value::LangString(_) => false
+		}
+	}
+
+	/// Lexical form of the value, if it is a string typed with one of the given datatypes.
+	fn lexical(&self, datatypes: &[&str]) -> Option<&str> {
+		match self {
+			Value::Literal(Literal::String(s), _) if self.has_datatype(datatypes) => Some(s.as_str()),
+			_ => None
+		}
+	}
+
+	/// Native number, untyped or typed with one of the given datatypes.
+	fn number(&self, datatypes: &[&str]) -> Option<&Number> {
+		match self {
+			Value::Literal(Literal::Number(n), types) if types.is_empty() || self.has_datatype(datatypes) => Some(n),
+			_ => None
+		}
+	}
+
+	/// Returns the value as an integer.
+	///
+	/// This is either a native integer number, or a string typed with `xsd:integer` or one of
+	/// its derived datatypes (such as `xsd:int` or `xsd:nonNegativeInteger`).
+	/// Returns `None` for other values, and for integers that do not fit in an `i64`.
+	pub fn as_integer(&self) -> Option<i64> {
+		match self.number(INTEGERS) {
+			Some(n) => integer_of_number(n),
+			None => parse_integer(self.lexical(INTEGERS)?)
+		}
+	}
+
+	/// Returns the value as an unsigned integer.
+	///
+	/// This is like [`as_integer`](Value::as_integer), but for non-negative integers that fit
+	/// in an `u64`.
+	pub fn as_unsigned_integer(&self) -> Option<u64> {
+		match self.number(INTEGERS) {
+			Some(n) => match magnitude_of_number(n)? {
+				(true, u) | (false, u @ 0) => Some(u),
+				_ => None
+			},
+			None => parse_unsigned_integer(self.lexical(INTEGERS)?)
+		}
+	}
+
+	/// Returns the value as a boolean.
+	///
+	/// This is either a native boolean, or a string typed with `xsd:boolean`, whose lexical
+	/// forms are `true`, `false`, `1` and `0`.
+	pub fn as_boolean(&self) -> Option<bool> {
+		match self {
+			Value::Literal(Literal::Boolean(b), types) if types.is_empty() || self.has_datatype(&["boolean"]) => Some(*b),
+			_ => match self.lexical(&["boolean"])? {
+				"true" | "1" => Some(true),
+				"false" | "0" => Some(false),
+				_ => None
+			}
+		}
+	}
+
+	/// Returns the value as an exact decimal number.
+	///
+	/// This is either a native number, or a string typed with `xsd:decimal` or one of the
+	/// integer datatypes.
+	/// Returns `None` for other values, and for decimals with more significant digits than
+	/// a [`Number`] can hold.
+	pub fn as_decimal(&self) -> Option<Number> {
+		match self.number(DECIMALS) {
+			Some(n) if !n.is_nan() => Some(*n),
+			Some(_) => None,
+			None => parse_decimal(self.lexical(DECIMALS)?)
+		}
+	}
+
+	/// Returns the value as a floating point number.
+	///
+	/// This is either a native number, or a string typed with `xsd:double`, `xsd:float`,
+	/// `xsd:decimal` or one of the integer datatypes.
+	pub fn as_double(&self) -> Option<f64> {
+		match self.as_numeric()? {
+			Numeric::Decimal(n) => Some(n.into()),
+			Numeric::Double(d) => Some(d)
+		}
+	}
+
+	/// Returns the value of a string typed with `xsd:dateTime` or `xsd:dateTimeStamp`.
+	///
+	/// Date-times without timezone do not denote an instant, and are not returned.
+	#[cfg(feature = "chrono")]
+	pub fn as_datetime(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+		chrono::DateTime::parse_from_rfc3339(self.lexical(&["dateTime", "dateTimeStamp"])?).ok()
+	}
+
+	/// Returns the value of a string typed with `xsd:date`.
+	///
+	/// The timezone of the date, if any, is ignored.
+	#[cfg(feature = "chrono")]
+	pub fn as_date(&self) -> Option<chrono::NaiveDate> {
+		let date = self.lexical(&["date"])?;
+		let date = match date.find(['Z', '+']).or_else(|| date.rfind('-').filter(|i| date.len() - i == 6)) {
+			Some(i) => &date[..i],
+			None => date
+		};
+
+		chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+	}
+
+	/// Returns the value of a string typed with `xsd:duration`, `xsd:dayTimeDuration` or
+	/// `xsd:yearMonthDuration`.
+	#[cfg(feature = "chrono")]
+	pub fn as_duration(&self) -> Option<Duration> {
+		parse_duration(self.lexical(&["duration", "dayTimeDuration", "yearMonthDuration"])?)
+	}
+
+	/// Numeric value, either exact or floating point.
+	fn as_numeric(&self) -> Option<Numeric> {
+		if let Some(n) = self.as_decimal() {
+			return Some(Numeric::Decimal(n))
+		}
+
+		match self.number(FLOATS) {
+			Some(n) => Some(Numeric::Double(f64::from(*n))),
+			None => parse_double(self.lexical(FLOATS)?).map(Numeric::Double)
+		}
+	}
+
+	/// Value of a string without language or direction, untyped or typed with `xsd:string`.
+	fn as_plain_string(&self) -> Option<&str> {
+		match self {
+			Value::Literal(Literal::String(s), types) if types.is_empty() || self.has_datatype(&["string"]) => Some(s.as_str()),
+			Value::LangString(s) if s.language().is_none() && s.direction().is_none() => Some(s.as_str()),
+			_ => None
+		}
+	}
+
+	/// Checks if the two values denote the same value, according to their datatypes.
+	///
+	/// Unlike `==`, which compares lexical forms, this compares numbers by value across the
+	/// numeric datatypes (`1`, `"1.0"^^xsd:decimal` and `"1E0"^^xsd:double` are equal),
+	/// booleans across their lexical forms, untyped strings with `xsd:string` strings, and
+	/// language tags case-insensitively.
+	/// With the `chrono` feature, date-times denoting the same instant, dates, and durations
+	/// are compared by value as well.
+	/// Other values are equal only if they are identical.
+	pub fn semantically_equals(&self, other: &Value<T>) -> bool {
+		if self == other {
+			return true
+		}
+
+		if let (Value::LangString(a), Value::LangString(b)) = (self, other) {
+			return a.as_str() == b.as_str()
+				&& a.direction() == b.direction()
+				&& a.language().map(str::to_lowercase) == b.language().map(str::to_lowercase)
+		}
+
+		if let (Some(a), Some(b)) = (self.as_numeric(), other.as_numeric()) {
+			return a == b
+		}
+
+		if let (Some(a), Some(b)) = (self.as_boolean(), other.as_boolean()) {
+			return a == b
+		}
+
+		if let (Some(a), Some(b)) = (self.as_plain_string(), other.as_plain_string()) {
+			return a == b
+		}
+
+		#[cfg(feature = "chrono")]
+		{
+			if let (Some(a), Some(b)) = (self.as_datetime(), other.as_datetime()) {
+				return a == b
+			}
+
+			if let (Some(a), Some(b)) = (self.as_date(), other.as_date()) {
+				return a == b
+			}
+
+			if let (Some(a), Some(b)) = (self.as_duration(), other.as_duration()) {
+				return a == b
+			}
+		}
+
+		false
+	}
+}
+
+/// Value of an `xsd:duration`.
+///
+/// The number of days in a month depends on the month, so durations have a number of months
+/// (from their years and months components) and an exact duration (from the others).
+/// Both have the sign of the duration.
+#[cfg(feature = "chrono")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Duration {
+	/// Number of months.
+	pub months: i64,
+
+	/// Days, hours, minutes and seconds.
+	pub time: chrono::Duration
+}
+
+/// Sign and absolute value of an integer number.
+fn magnitude_of_number(n: &Number) -> Option<(bool, u64)> {
+	let (positive, mut mantissa, exponent) = n.as_parts();
+	if n.is_nan() {
+		return None
+	}
+
+	if exponent >= 0 {
+		mantissa = mantissa.checked_mul(10u64.checked_pow(exponent as u32)?)?
+	} else {
+		let divisor = 10u64.checked_pow(-exponent as u32)?;
+		if mantissa % divisor != 0 {
+			return None
+		}
+
+		mantissa /= divisor
+	}
+
+	Some((positive, mantissa))
+}
+
+fn integer_of_number(n: &Number) -> Option<i64> {
+	let (positive, mantissa) = magnitude_of_number(n)?;
+	if positive {
+		i64::try_from(mantissa).ok()
+	} else if mantissa == 1 << 63 {
+		Some(i64::MIN)
+	} else {
+		i64::try_from(mantissa).ok().map(|n| -n)
+	}
+}
+
+/// Splits the sign of a lexical form.
+fn sign(lexical: &str) -> (bool, &str) {
+	match lexical.strip_prefix('-') {
+		Some(rest) => (false, rest),
+		None => (true, lexical.strip_prefix('+').unwrap_or(lexical))
+	}
+}
+
+fn is_digits(s: &str) -> bool {
+	!s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Mantissa and exponent without trailing zeros, so that equal decimals have equal parts.
+fn normalize(n: &Number) -> (bool, u64, i16) {
+	let (positive, mut mantissa, mut exponent) = n.as_parts();
+	if mantissa == 0 {
+		return (true, 0, 0)
+	}
+
+	while mantissa % 10 == 0 {
+		mantissa /= 10;
+		exponent += 1
+	}
+
+	(positive, mantissa, exponent)
+}
+
+fn parse_double(lexical: &str) -> Option<f64> {
+	match lexical {
+		"INF" | "+INF" => Some(f64::INFINITY),
+		"-INF" => Some(f64::NEG_INFINITY),
+		"NaN" => Some(f64::NAN),
+		_ if lexical.bytes().all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b)) => lexical.parse().ok(),
+		_ => None
+	}
+}
+
+fn parse_integer(lexical: &str) -> Option<i64> {
+	let (_, digits) = sign(lexical);
+	if is_digits(digits) {
+		lexical.parse().ok()
+	} else {
+		None
+	}
+}
+
+fn parse_unsigned_integer(lexical: &str) -> Option<u64> {
+	let (positive, digits) = sign(lexical);
+	if is_digits(digits) {
+		match digits.parse().ok()? {
+			0 => Some(0),
+			u if positive => Some(u),
+			_ => None
+		}
+	} else {
+		None
+	}
+}
+
+fn parse_decimal(lexical: &str) -> Option<Number> {
+	let (positive, digits) = sign(lexical);
+	let (integer, fraction) = match digits.find('.') {
+		Some(i) => (&digits[..i], &digits[(i + 1)..]),
+		None => (digits, "")
+	};
+
+	if (integer.is_empty() && fraction.is_empty()) || !(integer.is_empty() || is_digits(integer)) || !(fraction.is_empty() || is_digits(fraction)) {
+		return None
+	}
+
+	let fraction = fraction.trim_end_matches('0');
+	let mut mantissa: u64 = 0;
+	for b in integer.bytes().chain(fraction.bytes()) {
+		mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as u64)?
+	}
+
+	Some(Number::from_parts(positive, mantissa, -(fraction.len() as i16)))
+}
+
+#[cfg(feature = "chrono")]
+fn parse_duration(lexical: &str) -> Option<Duration> {
+	let (positive, rest) = match lexical.strip_prefix('-') {
+		Some(rest) => (false, rest),
+		None => (true, lexical)
+	};
+
+	let rest = rest.strip_prefix('P')?;
+	let (date, time) = match rest.find('T') {
+		Some(i) => (&rest[..i], Some(&rest[(i + 1)..])),
+		None => (rest, None)
+	};
+
+	if (date.is_empty() && time.is_none()) || time == Some("") {
+		return None
+	}
+
+	let mut months: i64 = 0;
+	let mut seconds: i64 = 0;
+	let mut nanos: i64 = 0;
+
+	for (value, designator) in components(date, "YMD")? {
+		if !is_digits(value) {
+			return None
+		}
+
+		let value: i64 = value.parse().ok()?;
+		match designator {
+			'Y' => months = months.checked_add(value.checked_mul(12)?)?,
+			'M' => months = months.checked_add(value)?,
+			_ => seconds = seconds.checked_add(value.checked_mul(86400)?)?
+		}
+	}
+
+	for (value, designator) in components(time.unwrap_or(""), "HMS")? {
+		let (integer, fraction) = match value.find('.') {
+			Some(i) if designator == 'S' => (&value[..i], &value[(i + 1)..]),
+			_ => (value, "0")
+		};
+
+		if !is_digits(integer) || !is_digits(fraction) {
+			return None
+		}
+
+		let value: i64 = integer.parse().ok()?;
+		match designator {
+			'H' => seconds = seconds.checked_add(value.checked_mul(3600)?)?,
+			'M' => seconds = seconds.checked_add(value.checked_mul(60)?)?,
+			_ => {
+				seconds = seconds.checked_add(value)?;
+				let digits = &fraction[..fraction.len().min(9)];
+				nanos = digits.parse::<i64>().ok()? * 10i64.pow(9 - digits.len() as u32)
+			}
+		}
+	}
+
+	let time = chrono::Duration::new(seconds, nanos as u32)?;
+	Some(if positive {
+		Duration { months, time }
+	} else {
+		Duration { months: -months, time: -time }
+	})
+}
+
+/// Components of a duration part, such as `1Y2M` for the `YMD` designators.
+///
+/// Designators must appear in order, at most once.
+#[cfg(feature = "chrono")]
+fn components<'a>(mut part: &'a str, designators: &str) -> Option<Vec<(&'a str, char)>> {
+	let mut result = Vec::new();
+	let mut designators = designators.chars();
+	while !part.is_empty() {
+		let end = part.find(|c: char| c.is_ascii_alphabetic())?;
+		let designator = part[end..].chars().next()?;
+		designators.find(|d| *d == designator)?;
+		if end == 0 {
+			return None
+		}
+
+		result.push((&part[..end], designator));
+		part = &part[(end + 1)..]
+	}
+
+	Some(result)
+}
@@ -0,0 +1,433 @@
+//! Patching of expanded documents.
+//!
+//! Expanded documents can be modified either through [`Operation`]s addressing nodes and
+//! properties by IRI (in the spirit of [LD Patch](https://www.w3.org/TR/ldpatch/)), or through
+//! [JSON Patch](https://tools.ietf.org/html/rfc6902) documents.
+//! In both cases, only top-level node objects with an identifier can be modified.
+
+use std::collections::HashMap;
+use std::fmt;
+use futures::Future;
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Id,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion,
+	util::AsJson
+};
+
+/// Patching error.
+#[derive(Debug)]
+pub enum Error {
+	/// The patch document is not an array of valid JSON Patch operations.
+	InvalidPatch,
+
+	/// The given JSON Pointer does not point to any value.
+	PathNotFound(String),
+
+	/// A JSON Patch `test` operation failed on the given path.
+	TestFailed(String),
+
+	/// There is no top-level node with the given identifier.
+	NodeNotFound(String),
+
+	/// The value to remove has not been found.
+	ValueNotFound,
+
+	/// A patched node is not a valid expanded node object.
+	InvalidNode(crate::Error)
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::InvalidNode(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::InvalidPatch => write!(f, "invalid patch"),
+			Error::PathNotFound(path) => write!(f, "path `{}` not found", path),
+			Error::TestFailed(path) => write!(f, "test failed on path `{}`", path),
+			Error::NodeNotFound(id) => write!(f, "node `{}` not found", id),
+			Error::ValueNotFound => write!(f, "value not found"),
+			Error::InvalidNode(e) => write!(f, "invalid node: {}", e)
+		}
+	}
+}
+
+/// Patch operation on an expanded document.
+///
+/// Nodes, properties and types are designated by their identifier.
+pub enum Operation<T: Id = IriBuf> {
+	/// Add a value to a property of a node.
+	///
+	/// The node is created if it does not exist.
+	/// Nothing happens if the value is already present.
+	Add(Reference<T>, Reference<T>, Indexed<Object<T>>),
+
+	/// Remove a value from a property of a node.
+	Remove(Reference<T>, Reference<T>, Indexed<Object<T>>),
+
+	/// Remove every value of a property of a node.
+	Clear(Reference<T>, Reference<T>),
+
+	/// Add a type to a node.
+	///
+	/// The node is created if it does not exist.
+	AddType(Reference<T>, Reference<T>),
+
+	/// Remove a type of a node.
+	RemoveType(Reference<T>, Reference<T>),
+
+	/// Remove a node.
+	RemoveNode(Reference<T>)
+}
+
+impl<T: Id> Operation<T> {
+	/// Identifier of the node targeted by the operation.
+	pub fn node(&self) -> &Reference<T> {
+		match self {
+			Operation::Add(id, _, _) => id,
+			Operation::Remove(id, _, _) => id,
+			Operation::Clear(id, _) => id,
+			Operation::AddType(id, _) => id,
+			Operation::RemoveType(id, _) => id,
+			Operation::RemoveNode(id) => id
+		}
+	}
+}
+
+/// Top-level objects of a document being patched, with an index on node identifiers.
+struct Objects<T: Id> {
+	objects: Vec<Option<Indexed<Object<T>>>>,
+	nodes: HashMap<Reference<T>, usize>
+}
+
+impl<T: Id> Objects<T> {
+	fn new(document: &ExpandedDocument<T>) -> Objects<T> {
+		let mut objects = Vec::with_capacity(document.len());
+		let mut nodes = HashMap::new();
+		for object in document {
+			if let Some(id) = top_level_id(object) {
+				nodes.insert(id.clone(), objects.len());
+			}
+
+			objects.push(Some(object.clone()))
+		}
+
+		Objects {
+			objects,
+			nodes
+		}
+	}
+
+	fn get_mut(&mut self, id: &Reference<T>) -> Result<&mut Node<T>, Error> {
+		match self.nodes.get(id) {
+			Some(i) => match self.objects[*i].as_deref_mut() {
+				Some(Object::Node(node)) => Ok(node),
+				_ => unreachable!()
+			},
+			None => Err(Error::NodeNotFound(id.as_str().to_string()))
+		}
+	}
+
+	fn get_or_insert(&mut self, id: &Reference<T>) -> &mut Node<T> {
+		if !self.nodes.contains_key(id) {
+			let mut node = Node::new();
+			node.id = Some(Lenient::Ok(id.clone()));
+			self.insert(Object::Node(Box::new(node)).into())
+		}
+
+		self.get_mut(id).unwrap()
+	}
+
+	/// Insert a top-level object.
+	///
+	/// A node replaces the node with the same identifier, at its position.
+	/// Other objects are appended.
+	fn insert(&mut self, object: Indexed<Object<T>>) {
+		if let Some(id) = top_level_id(&object) {
+			if let Some(i) = self.nodes.get(id) {
+				self.objects[*i] = Some(object);
+				return
+			}
+
+			self.nodes.insert(id.clone(), self.objects.len());
+		}
+
+		self.objects.push(Some(object))
+	}
+
+	fn remove(&mut self, id: &Reference<T>) -> Result<Indexed<Object<T>>, Error> {
+		match self.nodes.remove(id) {
+			Some(i) => Ok(self.objects[i].take().unwrap()),
+			None => Err(Error::NodeNotFound(id.as_str().to_string()))
+		}
+	}
+
+	fn into_document(self) -> ExpandedDocument<T> {
+		self.objects.into_iter().flatten().collect()
+	}
+}
+
+/// Identifier of a top-level object, if it is a node object with a valid identifier.
+fn top_level_id<T: Id>(object: &Indexed<Object<T>>) -> Option<&Reference<T>> {
+	match object.inner() {
+		Object::Node(node) => match &node.id {
+			Some(Lenient::Ok(id)) => Some(id),
+			_ => None
+		},
+		_ => None
+	}
+}
+
+/// Apply the given operations, in order, to an expanded document.
+///
+/// If an operation fails, the document is left unchanged.
+pub fn apply<T: Id, O: IntoIterator<Item = Operation<T>>>(document: &mut ExpandedDocument<T>, operations: O) -> Result<(), Error> {
+	let mut objects = Objects::new(document);
+
+	for op in operations {
+		match op {
+			Operation::Add(id, prop, value) => {
+				let node = objects.get_or_insert(&id);
+				let values = node.properties.entry(prop).or_default();
+				if !values.contains(&value) {
+					values.push(value)
+				}
+			},
+			Operation::Remove(id, prop, value) => {
+				let node = objects.get_mut(&id)?;
+				let values = node.properties.get_mut(&prop).ok_or(Error::ValueNotFound)?;
+				let i = values.iter().position(|v| *v == value).ok_or(Error::ValueNotFound)?;
+				values.remove(i);
+				if values.is_empty() {
+					node.properties.remove(&prop);
+				}
+			},
+			Operation::Clear(id, prop) => {
+				objects.get_mut(&id)?.properties.remove(&prop);
+			},
+			Operation::AddType(id, ty) => {
+				let node = objects.get_or_insert(&id);
+				node.insert_type(Lenient::Ok(ty));
+			},
+			Operation::RemoveType(id, ty) => {
+				let node = objects.get_mut(&id)?;
+				if !node.remove_type(&Lenient::Ok(ty)) {
+					return Err(Error::ValueNotFound)
+				}
+			},
+			Operation::RemoveNode(id) => {
+				objects.remove(&id)?;
+			}
+		}
+	}
+
+	*document = objects.into_document();
+	Ok(())
+}
+
+/// Apply a [JSON Patch](https://tools.ietf.org/html/rfc6902) to an expanded document.
+///
+/// The patch operates on a JSON view of the document where the top-level node objects are
+/// stored in a JSON object, indexed by their identifier, in expanded form.
+/// For instance, the path `/http:~1~1example.org~1alice/http:~1~1xmlns.com~1foaf~10.1~1name/0`
+/// points to the first name of the node `http://example.org/alice`.
+/// Other top-level objects (anonymous nodes, lists) are not affected.
+///
+/// Modified nodes keep their position in the document, and new nodes are added at the end.
+/// Each modified node must remain a valid expanded node object.
+/// If an operation fails, the document is left unchanged.
+pub fn apply_json_patch<'a, T: Send + Sync + Id>(document: &'a mut ExpandedDocument<T>, patch: &'a JsonValue) -> impl 'a + Send + Future<Output = Result<(), Error>> {
+	async move {
+		if !patch.is_array() {
+			return Err(Error::InvalidPatch)
+		}
+
+		let mut objects = Objects::new(document);
+
+		// Build the JSON view, in document order.
+		let mut ids = HashMap::new();
+		let mut view = json::object::Object::new();
+		for (i, object) in objects.objects.iter().enumerate() {
+			if let Some(id) = top_level_id(object.as_ref().unwrap()) {
+				if objects.nodes[id] == i {
+					ids.insert(id.as_str().to_string(), id.clone());
+					view.insert(id.as_str(), object.as_ref().unwrap().as_json())
+				}
+			}
+		}
+		let original = view.clone();
+		let mut view = JsonValue::Object(view);
+
+		for op in patch.members() {
+			apply_json_operation(&mut view, op)?
+		}
+
+		let view = match view {
+			JsonValue::Object(view) => view,
+			_ => return Err(Error::InvalidPatch)
+		};
+
+		// Parse the new and modified nodes.
+		let context: JsonContext<T> = JsonContext::new(None);
+		let mut modified = Vec::new();
+		for (key, value) in view.iter() {
+			if original.get(key) != Some(value) {
+				let expanded = expansion::expand(&context, value, None, &mut NoLoader, expansion::Options::default()).await.map_err(Error::InvalidNode)?;
+				modified.push((key.to_string(), expanded))
+			}
+		}
+
+		// Remove the nodes that are gone, or whose identifier changed.
+		for key in original.iter().map(|(key, _)| key) {
+			let kept = match view.get(key) {
+				Some(_) => match modified.iter().find(|(k, _)| k == key) {
+					Some((_, expanded)) => expanded.iter().any(|object| top_level_id(object) == Some(&ids[key])),
+					None => true
+				},
+				None => false
+			};
+
+			if !kept {
+				objects.remove(&ids[key])?;
+			}
+		}
+
+		for (_, expanded) in modified {
+			for object in expanded {
+				objects.insert(object)
+			}
+		}
+
+		*document = objects.into_document();
+		Ok(())
+	}
+}
+
+/// Parse a JSON Pointer.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, Error> {
+	if pointer.is_empty() {
+		Ok(Vec::new())
+	} else if let Some(pointer) = pointer.strip_prefix('/') {
+		Ok(pointer.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+	} else {
+		Err(Error::InvalidPatch)
+	}
+}
+
+fn pointer_get<'a>(value: &'a JsonValue, path: &[String]) -> Option<&'a JsonValue> {
+	match path.split_first() {
+		None => Some(value),
+		Some((token, rest)) => match value {
+			JsonValue::Object(obj) => obj.get(token).and_then(|v| pointer_get(v, rest)),
+			JsonValue::Array(ary) => token.parse::<usize>().ok().and_then(|i| ary.get(i)).and_then(|v| pointer_get(v, rest)),
+			_ => None
+		}
+	}
+}
+
+fn pointer_get_mut<'a>(value: &'a mut JsonValue, path: &[String]) -> Option<&'a mut JsonValue> {
+	match path.split_first() {
+		None => Some(value),
+		Some((token, rest)) => match value {
+			JsonValue::Object(obj) => obj.get_mut(token).and_then(|v| pointer_get_mut(v, rest)),
+			JsonValue::Array(ary) => token.parse::<usize>().ok().and_then(move |i| ary.get_mut(i)).and_then(|v| pointer_get_mut(v, rest)),
+			_ => None
+		}
+	}
+}
+
+fn pointer_add(root: &mut JsonValue, pointer: &str, path: &[String], value: JsonValue) -> Result<(), Error> {
+	match path.split_last() {
+		None => *root = value,
+		Some((token, parent)) => match pointer_get_mut(root, parent) {
+			Some(JsonValue::Object(obj)) => obj.insert(token, value),
+			Some(JsonValue::Array(ary)) => {
+				if token == "-" {
+					ary.push(value)
+				} else {
+					match token.parse::<usize>() {
+						Ok(i) if i <= ary.len() => ary.insert(i, value),
+						_ => return Err(Error::PathNotFound(pointer.to_string()))
+					}
+				}
+			},
+			_ => return Err(Error::PathNotFound(pointer.to_string()))
+		}
+	}
+
+	Ok(())
+}
+
+fn pointer_remove(root: &mut JsonValue, pointer: &str, path: &[String]) -> Result<JsonValue, Error> {
+	let not_found = || Error::PathNotFound(pointer.to_string());
+	match path.split_last() {
+		None => Ok(std::mem::replace(root, JsonValue::Null)),
+		Some((token, parent)) => match pointer_get_mut(root, parent) {
+			Some(JsonValue::Object(obj)) => obj.remove(token).ok_or_else(not_found),
+			Some(JsonValue::Array(ary)) => match token.parse::<usize>() {
+				Ok(i) if i < ary.len() => Ok(ary.remove(i)),
+				_ => Err(not_found())
+			},
+			_ => Err(not_found())
+		}
+	}
+}
+
+/// Apply a single JSON Patch operation.
+fn apply_json_operation(root: &mut JsonValue, op: &JsonValue) -> Result<(), Error> {
+	let pointer = op["path"].as_str().ok_or(Error::InvalidPatch)?;
+	let path = parse_pointer(pointer)?;
+	let value = || if op.has_key("value") { Ok(op["value"].clone()) } else { Err(Error::InvalidPatch) };
+	let from = || -> Result<(&str, Vec<String>), Error> {
+		let from = op["from"].as_str().ok_or(Error::InvalidPatch)?;
+		Ok((from, parse_pointer(from)?))
+	};
+
+	match op["op"].as_str() {
+		Some("add") => pointer_add(root, pointer, &path, value()?),
+		Some("remove") => pointer_remove(root, pointer, &path).map(|_| ()),
+		Some("replace") => {
+			let target = pointer_get_mut(root, &path).ok_or_else(|| Error::PathNotFound(pointer.to_string()))?;
+			*target = value()?;
+			Ok(())
+		},
+		Some("move") => {
+			let (from_pointer, from_path) = from()?;
+			if path.len() > from_path.len() && path.starts_with(&from_path) {
+				return Err(Error::InvalidPatch)
+			}
+
+			let value = pointer_remove(root, from_pointer, &from_path)?;
+			pointer_add(root, pointer, &path, value)
+		},
+		Some("copy") => {
+			let (from_pointer, from_path) = from()?;
+			let value = pointer_get(root, &from_path).ok_or_else(|| Error::PathNotFound(from_pointer.to_string()))?.clone();
+			pointer_add(root, pointer, &path, value)
+		},
+		Some("test") => {
+			if pointer_get(root, &path) == Some(&value()?) {
+				Ok(())
+			} else {
+				Err(Error::TestFailed(pointer.to_string()))
+			}
+		},
+		_ => Err(Error::InvalidPatch)
+	}
+}
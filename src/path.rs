@@ -0,0 +1,430 @@
+//! Property path queries.
+//!
+//! A [`Path`] is a sequence of steps following a property from a node to its values
+//! (forward steps), or from a node to the nodes having it as value (reverse steps).
+//! It can be built step by step:
+//! ```ignore
+//! let path = Path::new().prop(foaf_knows).prop(foaf_name);
+//! ```
+//! or parsed from a string where steps are separated by `/`, and reverse steps are prefixed
+//! with `^`. Each step is a term, compact IRI or absolute IRI expanded with an active
+//! context, or an absolute IRI between angle brackets:
+//! ```ignore
+//! let path = Path::parse("foaf:knows/^foaf:member/<http://xmlns.com/foaf/0.1/name>", &context)?;
+//! let names = document.select(&path);
+//! ```
+//!
+//! Node references are resolved across the document: a step from a node identified by `_:b0`
+//! considers every description of `_:b0` in the document, whether it is a top-level node or
+//! an embedded node.
+//! The contents of named graphs are not considered.
+
+use std::collections::HashMap;
+use std::fmt;
+use iref::{Iri, IriBuf};
+use crate::{
+	Id,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	Value,
+	Context,
+	ExpandedDocument,
+	expansion,
+	syntax::Term
+};
+
+/// Path parsing error.
+#[derive(Debug)]
+pub enum Error {
+	/// The path has no step.
+	Empty,
+
+	/// A step does not expand into an IRI or blank node identifier.
+	InvalidStep(String)
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Empty => write!(f, "empty path"),
+			Error::InvalidStep(step) => write!(f, "invalid path step `{}`", step)
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Path step.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Step<T: Id = IriBuf> {
+	/// Follow the property from a node to its values.
+	Forward(Reference<T>),
+
+	/// Follow the property from a node to the nodes having it as value.
+	Reverse(Reference<T>)
+}
+
+impl<T: Id> fmt::Display for Step<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Step::Forward(prop) => write!(f, "<{}>", prop),
+			Step::Reverse(prop) => write!(f, "^<{}>", prop)
+		}
+	}
+}
+
+/// Property path.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Path<T: Id = IriBuf> {
+	steps: Vec<Step<T>>
+}
+
+impl<T: Id> Path<T> {
+	/// Create a new empty path.
+	///
+	/// Selecting an empty path returns the starting nodes.
+	pub fn new() -> Path<T> {
+		Path {
+			steps: Vec::new()
+		}
+	}
+
+	/// Parse a path, expanding each step with the given active context.
+	pub fn parse<C: Context<T>>(path: &str, context: &C) -> Result<Path<T>, Error> {
+		if path.trim().is_empty() {
+			return Err(Error::Empty)
+		}
+
+		let mut steps = Vec::new();
+
+		for step in split_steps(path) {
+			let step = step.trim();
+			let (reverse, name) = match step.strip_prefix('^') {
+				Some(name) => (true, name.trim()),
+				None => (false, step)
+			};
+
+			let prop = if name.starts_with('<') && name.ends_with('>') && name.len() > 1 {
+				match Iri::new(&name[1..(name.len() - 1)]) {
+					Ok(iri) => Reference::Id(T::from_iri(iri)),
+					Err(_) => return Err(Error::InvalidStep(step.to_string()))
+				}
+			} else {
+				match expansion::expand_iri(context, name, false, true) {
+					Lenient::Ok(Term::Ref(prop)) => prop,
+					_ => return Err(Error::InvalidStep(step.to_string()))
+				}
+			};
+
+			steps.push(if reverse { Step::Reverse(prop) } else { Step::Forward(prop) })
+		}
+
+		Ok(Path {
+			steps
+		})
+	}
+
+	/// Path steps.
+	pub fn steps(&self) -> &[Step<T>] {
+		&self.steps
+	}
+
+	/// Append a forward step.
+	pub fn prop<P: Into<Reference<T>>>(mut self, prop: P) -> Self {
+		self.steps.push(Step::Forward(prop.into()));
+		self
+	}
+
+	/// Append a reverse step.
+	pub fn reverse<P: Into<Reference<T>>>(mut self, prop: P) -> Self {
+		self.steps.push(Step::Reverse(prop.into()));
+		self
+	}
+
+	/// Select the objects reached by following this path from every top-level node of the
+	/// document.
+	pub fn select<'a>(&self, document: &'a ExpandedDocument<T>) -> Vec<Selection<'a, T>> {
+		let index = DocumentIndex::new(document);
+		let start = document.iter().filter_map(|object| object.as_node()).collect();
+		index.select(&self.steps, start)
+	}
+
+	/// Select the objects reached by following this path from the given node of the document.
+	pub fn select_from<'a>(&self, document: &'a ExpandedDocument<T>, node: &'a Node<T>) -> Vec<Selection<'a, T>> {
+		let index = DocumentIndex::new(document);
+		index.select(&self.steps, vec![node])
+	}
+}
+
+impl<T: Id> Default for Path<T> {
+	fn default() -> Path<T> {
+		Self::new()
+	}
+}
+
+impl<T: Id> fmt::Display for Path<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, step) in self.steps.iter().enumerate() {
+			if i > 0 {
+				write!(f, "/")?
+			}
+
+			step.fmt(f)?
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Select the objects reached by following the given path from every top-level node.
+	///
+	/// See [`Path::select`].
+	pub fn select(&self, path: &Path<T>) -> Vec<Selection<'_, T>> {
+		path.select(self)
+	}
+}
+
+/// Object reached by a path.
+pub enum Selection<'a, T: Id> {
+	/// Node object.
+	///
+	/// When the node is only a reference, this is the first description of the node found in
+	/// the document, if any.
+	Node(&'a Node<T>),
+
+	/// Value object.
+	Value(&'a Value<T>),
+
+	/// List object.
+	List(&'a [Indexed<Object<T>>])
+}
+
+impl<'a, T: Id> Selection<'a, T> {
+	/// Returns the node, if the selection is a node.
+	pub fn as_node(&self) -> Option<&'a Node<T>> {
+		match self {
+			Selection::Node(node) => Some(node),
+			_ => None
+		}
+	}
+
+	/// Returns the value, if the selection is a value.
+	pub fn as_value(&self) -> Option<&'a Value<T>> {
+		match self {
+			Selection::Value(value) => Some(value),
+			_ => None
+		}
+	}
+
+	/// Get the selection as a string.
+	///
+	/// Returns the string of a string value, or the identifier of an identified node.
+	pub fn as_str(&self) -> Option<&'a str> {
+		match self {
+			Selection::Node(node) => node.as_str(),
+			Selection::Value(value) => value.as_str(),
+			Selection::List(_) => None
+		}
+	}
+}
+
+impl<'a, T: Id> Clone for Selection<'a, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: Id> Copy for Selection<'a, T> {}
+
+/// Split a path into steps, on the `/` characters that are not between angle brackets.
+fn split_steps(path: &str) -> Vec<&str> {
+	let mut steps = Vec::new();
+	let mut start = 0;
+	let mut in_iri = false;
+
+	for (i, c) in path.char_indices() {
+		match c {
+			'<' => in_iri = true,
+			'>' => in_iri = false,
+			'/' if !in_iri => {
+				steps.push(&path[start..i]);
+				start = i + 1
+			},
+			_ => ()
+		}
+	}
+
+	steps.push(&path[start..]);
+	steps
+}
+
+fn node_id<T: Id>(node: &Node<T>) -> Option<&Reference<T>> {
+	match node.id() {
+		Some(Lenient::Ok(id)) => Some(id),
+		_ => None
+	}
+}
+
+/// Checks if the given nodes are the same node (same identifier, or same anonymous node).
+fn same_node<T: Id>(a: &Node<T>, b: &Node<T>) -> bool {
+	match (node_id(a), node_id(b)) {
+		(Some(a), Some(b)) => a == b,
+		_ => std::ptr::eq(a, b)
+	}
+}
+
+/// Subjects of each node (by address) through each property.
+type Subjects<'a, T> = HashMap<(*const Node<T>, &'a Reference<T>), Vec<&'a Node<T>>>;
+
+/// Index of the nodes of a document.
+pub(crate) struct DocumentIndex<'a, T: Id> {
+	/// Descriptions of each identified node.
+	nodes: HashMap<&'a Reference<T>, Vec<&'a Node<T>>>,
+
+	/// Subjects of each node.
+	subjects: Subjects<'a, T>
+}
+
+impl<'a, T: Id> DocumentIndex<'a, T> {
+	pub(crate) fn new(document: &'a ExpandedDocument<T>) -> DocumentIndex<'a, T> {
+		let mut index = DocumentIndex {
+			nodes: HashMap::new(),
+			subjects: HashMap::new()
+		};
+
+		for object in document {
+			index.add_object(object)
+		}
+
+		index
+	}
+
+	fn add_object(&mut self, object: &'a Object<T>) {
+		match object {
+			Object::Node(node) => self.add_node(node),
+			Object::List(items) => {
+				for item in items {
+					self.add_object(item)
+				}
+			},
+			Object::Value(_) => ()
+		}
+	}
+
+	fn add_node(&mut self, node: &'a Node<T>) {
+		if let Some(id) = node_id(node) {
+			self.nodes.entry(id).or_default().push(node)
+		}
+
+		if let Some(included) = node.included() {
+			for included in included {
+				self.add_node(included)
+			}
+		}
+
+		for (prop, values) in &node.properties {
+			for value in values {
+				self.add_subject(value, prop, node);
+				self.add_object(value)
+			}
+		}
+
+		for (prop, subjects) in &node.reverse_properties {
+			for subject in subjects {
+				self.subjects.entry((node as *const Node<T>, prop)).or_default().push(subject);
+				self.add_node(subject)
+			}
+		}
+	}
+
+	/// Register `subject` as a subject of the nodes of `object` through `prop`.
+	fn add_subject(&mut self, object: &'a Object<T>, prop: &'a Reference<T>, subject: &'a Node<T>) {
+		match object {
+			Object::Node(node) => self.subjects.entry((&**node as *const Node<T>, prop)).or_default().push(subject),
+			Object::List(items) => {
+				for item in items {
+					self.add_subject(item, prop, subject)
+				}
+			},
+			Object::Value(_) => ()
+		}
+	}
+
+	/// Every description of the given node.
+	fn descriptions(&self, node: &'a Node<T>) -> Vec<&'a Node<T>> {
+		match node_id(node).and_then(|id| self.nodes.get(id)) {
+			Some(descriptions) => descriptions.clone(),
+			None => vec![node]
+		}
+	}
+
+	/// Most complete description of the given node.
+	pub(crate) fn description(&self, node: &'a Node<T>) -> &'a Node<T> {
+		self.descriptions(node).into_iter().find(|d| !is_reference(d)).unwrap_or(node)
+	}
+
+	pub(crate) fn select(&self, steps: &[Step<T>], start: Vec<&'a Node<T>>) -> Vec<Selection<'a, T>> {
+		let mut current: Vec<&'a Node<T>> = Vec::new();
+		for node in start {
+			insert_node(&mut current, node)
+		}
+
+		for (i, step) in steps.iter().enumerate() {
+			let last = i + 1 == steps.len();
+			let mut next = Vec::new();
+			let mut result = Vec::new();
+
+			for node in &current {
+				for d in self.descriptions(node) {
+					match step {
+						Step::Forward(prop) => {
+							for value in d.get(prop) {
+								match value.inner() {
+									Object::Node(node) => insert_node(&mut next, node),
+									Object::Value(value) if last => {
+										if !result.iter().any(|s| matches!(s, Selection::Value(v) if *v == value)) {
+											result.push(Selection::Value(value))
+										}
+									},
+									Object::List(items) if last => result.push(Selection::List(items)),
+									_ => ()
+								}
+							}
+						},
+						Step::Reverse(prop) => {
+							if let Some(subjects) = self.subjects.get(&(d as *const Node<T>, prop)) {
+								for subject in subjects {
+									insert_node(&mut next, subject)
+								}
+							}
+						}
+					}
+				}
+			}
+
+			if last {
+				result.extend(next.into_iter().map(|node| Selection::Node(self.description(node))));
+				return result
+			}
+
+			current = next
+		}
+
+		current.into_iter().map(|node| Selection::Node(self.description(node))).collect()
+	}
+}
+
+/// Checks if the node is only a reference to another node.
+fn is_reference<T: Id>(node: &Node<T>) -> bool {
+	node.id().is_some() && node.types().is_empty() && node.properties.is_empty() && node.reverse_properties.is_empty() && node.graph().is_none() && node.included().is_none()
+}
+
+fn insert_node<'a, T: Id>(nodes: &mut Vec<&'a Node<T>>, node: &'a Node<T>) {
+	if !nodes.iter().any(|n| same_node(n, node)) {
+		nodes.push(node)
+	}
+}
@@ -0,0 +1,192 @@
+//! Object addressing.
+//!
+//! A [`Pointer`] is a [JSON Pointer](https://tools.ietf.org/html/rfc6901) addressing an object
+//! of an expanded document, for external annotations, error reports or patch targets.
+//! Its first token is the identifier of a top-level node (or the position of a top-level
+//! object without identifier), followed by the steps leading to the object:
+//! a property IRI and the position of the value, `@list` and the position of the item,
+//! or `@graph` and the identifier of a node of the named graph.
+//! ```ignore
+//! let pointer: Pointer = "/http:~1~1example.org~1alice/http:~1~1schema.org~1knows/0".parse()?;
+//! let bob = document.resolve(&pointer);
+//! ```
+//!
+//! Identifiers make pointers stable when the document is reordered, and match the JSON view
+//! used by [`apply_json_patch`](crate::patch::apply_json_patch) for property values.
+//! Since named graphs are unordered, their anonymous members cannot be addressed.
+
+use std::fmt;
+use std::str::FromStr;
+use crate::{
+	Id,
+	Indexed,
+	Object,
+	ExpandedDocument
+};
+
+/// Invalid JSON Pointer.
+#[derive(Debug)]
+pub struct InvalidPointer(pub String);
+
+impl fmt::Display for InvalidPointer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid JSON pointer `{}`", self.0)
+	}
+}
+
+impl std::error::Error for InvalidPointer {}
+
+/// Pointer to an object of an expanded document.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Pointer {
+	/// Unescaped tokens.
+	tokens: Vec<String>
+}
+
+impl Pointer {
+	/// Create a new pointer to the whole document.
+	pub fn new() -> Pointer {
+		Pointer::default()
+	}
+
+	/// Unescaped tokens of the pointer.
+	pub fn tokens(&self) -> &[String] {
+		&self.tokens
+	}
+
+	/// Checks if the pointer has no token.
+	pub fn is_empty(&self) -> bool {
+		self.tokens.is_empty()
+	}
+
+	/// Append a token, such as an identifier or a property IRI.
+	pub fn with(mut self, token: &str) -> Pointer {
+		self.tokens.push(token.to_string());
+		self
+	}
+
+	/// Append a position.
+	pub fn with_index(self, index: usize) -> Pointer {
+		self.with(&index.to_string())
+	}
+}
+
+impl fmt::Display for Pointer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for token in &self.tokens {
+			write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for Pointer {
+	type Err = InvalidPointer;
+
+	fn from_str(s: &str) -> Result<Pointer, InvalidPointer> {
+		if s.is_empty() {
+			Ok(Pointer::new())
+		} else if let Some(rest) = s.strip_prefix('/') {
+			Ok(Pointer {
+				tokens: rest.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect()
+			})
+		} else {
+			Err(InvalidPointer(s.to_string()))
+		}
+	}
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Find the object addressed by the given pointer.
+	///
+	/// A pointer to a node identifier designates the first node with this identifier.
+	/// Returns `None` for the empty pointer, designating the whole document.
+	pub fn resolve(&self, pointer: &Pointer) -> Option<&Indexed<Object<T>>> {
+		let (token, rest) = pointer.tokens.split_first()?;
+		let object = self.objects().iter().find(|object| id_of(object) == Some(token.as_str())).or_else(|| {
+			token.parse::<usize>().ok().and_then(|i| self.objects().get(i)).filter(|object| id_of(object).is_none())
+		})?;
+
+		resolve_in(object, rest)
+	}
+
+	/// Every addressable object of the document, with its pointer, in depth-first order.
+	///
+	/// Properties and named graph members are visited in lexicographic order.
+	pub fn pointers(&self) -> Vec<(Pointer, &Indexed<Object<T>>)> {
+		let mut result = Vec::new();
+		for (i, object) in self.objects().iter().enumerate() {
+			let pointer = match id_of(object) {
+				Some(id) => Pointer::new().with(id),
+				None => Pointer::new().with_index(i)
+			};
+
+			collect(object, pointer, &mut result)
+		}
+
+		result
+	}
+
+	/// Find the pointer of the given object, which must be borrowed from this document.
+	pub fn pointer_of(&self, object: &Indexed<Object<T>>) -> Option<Pointer> {
+		self.pointers().into_iter().find(|(_, o)| std::ptr::eq(*o, object)).map(|(pointer, _)| pointer)
+	}
+}
+
+/// Identifier of a node object.
+fn id_of<T: Id>(object: &Indexed<Object<T>>) -> Option<&str> {
+	match object.inner() {
+		Object::Node(node) => node.id.as_ref().map(|id| id.as_str()),
+		_ => None
+	}
+}
+
+fn resolve_in<'a, T: Id>(object: &'a Indexed<Object<T>>, tokens: &[String]) -> Option<&'a Indexed<Object<T>>> {
+	let (token, rest) = match tokens.split_first() {
+		Some(split) => split,
+		None => return Some(object)
+	};
+
+	let (next, rest) = rest.split_first()?;
+	let object = match (object.inner(), token.as_str()) {
+		(Object::List(items), "@list") => items.get(next.parse::<usize>().ok()?)?,
+		(Object::Node(node), "@graph") => node.graph()?.iter().find(|object| id_of(object) == Some(next.as_str()))?,
+		(Object::Node(node), prop) => {
+			let (_, values) = node.properties.iter().find(|(p, _)| p.as_str() == prop)?;
+			values.get(next.parse::<usize>().ok()?)?
+		},
+		_ => return None
+	};
+
+	resolve_in(object, rest)
+}
+
+fn collect<'a, T: Id>(object: &'a Indexed<Object<T>>, pointer: Pointer, result: &mut Vec<(Pointer, &'a Indexed<Object<T>>)>) {
+	result.push((pointer.clone(), object));
+	match object.inner() {
+		Object::Value(_) => (),
+		Object::List(items) => {
+			for (i, item) in items.iter().enumerate() {
+				collect(item, pointer.clone().with("@list").with_index(i), result)
+			}
+		},
+		Object::Node(node) => {
+			if let Some(graph) = node.graph() {
+				let mut members: Vec<_> = graph.iter().filter_map(|object| id_of(object).map(|id| (id, object))).collect();
+				members.sort_by_key(|(id, _)| *id);
+				for (id, member) in members {
+					collect(member, pointer.clone().with("@graph").with(id), result)
+				}
+			}
+
+			let mut properties: Vec<_> = node.properties.iter().collect();
+			properties.sort_by_key(|(prop, _)| prop.as_str());
+			for (prop, values) in properties {
+				for (i, value) in values.iter().enumerate() {
+					collect(value, pointer.clone().with(prop.as_str()).with_index(i), result)
+				}
+			}
+		}
+	}
+}
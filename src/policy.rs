@@ -0,0 +1,54 @@
+use std::fmt;
+use crate::{
+	Error,
+	ErrorCode,
+	syntax::{
+		is_keyword,
+		is_keyword_like
+	}
+};
+
+/// Policy applied to unknown keywords.
+///
+/// Terms, keys and IRIs having the form of a keyword (`@` followed by letters, such as
+/// `@annotation`) without being a JSON-LD keyword are ignored by context processing and
+/// expansion, and processors should generate a warning.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum KeywordPolicy {
+	/// Silently ignore unknown keywords.
+	#[default]
+	Ignore,
+
+	/// Ignore unknown keywords, logging a warning.
+	Warn,
+
+	/// Fail with [`ErrorCode::UnknownKeyword`].
+	Error
+}
+
+impl KeywordPolicy {
+	/// Apply the policy to the given value, if it is an unknown keyword.
+	pub(crate) fn check(&self, value: &str) -> Result<(), Error> {
+		if is_keyword_like(value) && !is_keyword(value) {
+			match self {
+				KeywordPolicy::Ignore => (),
+				KeywordPolicy::Warn => warn!("ignoring unknown keyword `{}`", value),
+				KeywordPolicy::Error => return Err(Error::new(ErrorCode::UnknownKeyword, UnknownKeyword(value.to_string())))
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Unknown keyword, while they are rejected with [`KeywordPolicy::Error`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnknownKeyword(pub String);
+
+impl fmt::Display for UnknownKeyword {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` is not a keyword", self.0)
+	}
+}
+
+impl std::error::Error for UnknownKeyword {}
@@ -0,0 +1,382 @@
+//! ActivityStreams documents.
+//!
+//! Preset for [ActivityStreams 2.0](https://www.w3.org/TR/activitystreams-core/) documents,
+//! as exchanged by [ActivityPub](https://www.w3.org/TR/activitypub/) servers.
+//! It bundles a pinned copy of the ActivityStreams context
+//! (`https://www.w3.org/ns/activitystreams`).
+//! Extension contexts (such as the ones used by fediverse servers to define their own terms)
+//! are processed on top of it as usual, and loaded by the inner loader.
+//!
+//! ActivityStreams documents are often served as plain `application/activity+json` without
+//! `@context`. As required by the ActivityStreams specification, the ActivityStreams context is
+//! then implied: [`expand`] uses it as initial context when given such a content type.
+//!
+//! ```ignore
+//! let mut loader = activity_streams::loader(NoLoader);
+//! let expanded: ExpandedDocument<IriBuf> = activity_streams::expand(&json, Some("application/activity+json"), &mut loader).await?;
+//! let activity = Activity::from_document(&expanded).unwrap();
+//! for actor in activity.actor() {
+//!     println!("{}", actor.id().unwrap())
+//! }
+//! ```
+
+use std::future::Future;
+use iref::Iri;
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Value,
+	Loader,
+	Document,
+	JsonContext,
+	ExpandedDocument,
+	ProcessingMode,
+	expansion,
+	context::Local
+};
+use super::{
+	PresetLoader,
+	bundled,
+	property,
+	node_id,
+	nodes
+};
+
+/// URL of the ActivityStreams context.
+pub const CONTEXT_URL: &str = "https://www.w3.org/ns/activitystreams";
+
+/// Pinned ActivityStreams context.
+pub const CONTEXT: &str = include_str!("contexts/activitystreams.jsonld");
+
+/// ActivityStreams media type.
+pub const MEDIA_TYPE: &str = "application/activity+json";
+
+/// ActivityStreams namespace.
+pub const NAMESPACE: &str = "https://www.w3.org/ns/activitystreams#";
+
+/// Special collection including all users.
+pub const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+const ACTOR: &str = "https://www.w3.org/ns/activitystreams#actor";
+const OBJECT: &str = "https://www.w3.org/ns/activitystreams#object";
+const TARGET: &str = "https://www.w3.org/ns/activitystreams#target";
+const ORIGIN: &str = "https://www.w3.org/ns/activitystreams#origin";
+const RESULT: &str = "https://www.w3.org/ns/activitystreams#result";
+const INSTRUMENT: &str = "https://www.w3.org/ns/activitystreams#instrument";
+const ATTRIBUTED_TO: &str = "https://www.w3.org/ns/activitystreams#attributedTo";
+const IN_REPLY_TO: &str = "https://www.w3.org/ns/activitystreams#inReplyTo";
+const TAG: &str = "https://www.w3.org/ns/activitystreams#tag";
+const ATTACHMENT: &str = "https://www.w3.org/ns/activitystreams#attachment";
+const TO: &str = "https://www.w3.org/ns/activitystreams#to";
+const CC: &str = "https://www.w3.org/ns/activitystreams#cc";
+const BTO: &str = "https://www.w3.org/ns/activitystreams#bto";
+const BCC: &str = "https://www.w3.org/ns/activitystreams#bcc";
+const AUDIENCE: &str = "https://www.w3.org/ns/activitystreams#audience";
+const URL: &str = "https://www.w3.org/ns/activitystreams#url";
+const NAME: &str = "https://www.w3.org/ns/activitystreams#name";
+const SUMMARY: &str = "https://www.w3.org/ns/activitystreams#summary";
+const CONTENT: &str = "https://www.w3.org/ns/activitystreams#content";
+const PUBLISHED: &str = "https://www.w3.org/ns/activitystreams#published";
+const UPDATED: &str = "https://www.w3.org/ns/activitystreams#updated";
+const PREFERRED_USERNAME: &str = "https://www.w3.org/ns/activitystreams#preferredUsername";
+const INBOX: &str = "http://www.w3.org/ns/ldp#inbox";
+const OUTBOX: &str = "https://www.w3.org/ns/activitystreams#outbox";
+const FOLLOWERS: &str = "https://www.w3.org/ns/activitystreams#followers";
+const FOLLOWING: &str = "https://www.w3.org/ns/activitystreams#following";
+
+/// Create a loader serving the pinned ActivityStreams context, on top of the given loader.
+pub fn loader<L>(inner: L) -> PresetLoader<L> {
+	PresetLoader::new(inner).with(Iri::new(CONTEXT_URL).unwrap(), bundled(CONTEXT))
+}
+
+/// Expansion options used to process ActivityStreams documents.
+pub fn options() -> expansion::Options {
+	expansion::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		..expansion::Options::default()
+	}
+}
+
+/// Checks if the given content type implies the ActivityStreams context.
+///
+/// This is the case of `application/activity+json`, and of `application/ld+json` with the
+/// ActivityStreams profile.
+pub fn implies_context(content_type: &str) -> bool {
+	let mut parts = content_type.split(';').map(str::trim);
+	let media_type = parts.next().unwrap_or("");
+
+	if media_type.eq_ignore_ascii_case(MEDIA_TYPE) {
+		return true
+	}
+
+	media_type.eq_ignore_ascii_case("application/ld+json") && parts.any(|param| {
+		let mut param = param.splitn(2, '=').map(str::trim);
+		match (param.next(), param.next()) {
+			(Some(name), Some(value)) => name.eq_ignore_ascii_case("profile") && value.trim_matches('"').split(' ').any(|profile| profile == CONTEXT_URL),
+			_ => false
+		}
+	})
+}
+
+/// Expand an ActivityStreams document.
+///
+/// If the given content type [implies the ActivityStreams context](implies_context), the
+/// document is expanded with the ActivityStreams context as initial context.
+pub fn expand<'a, T: 'a + Send + Sync + Id, L: Send + Sync + Loader<Document = JsonValue>>(document: &'a JsonValue, content_type: Option<&str>, loader: &'a mut PresetLoader<L>) -> impl 'a + Send + Future<Output = Result<ExpandedDocument<T>, Error>> {
+	let implied = content_type.map(implies_context).unwrap_or(false);
+
+	async move {
+		let mut context: JsonContext<T> = JsonContext::new(None);
+
+		if implied {
+			let implied_context = JsonValue::from(CONTEXT_URL);
+			context = implied_context.process(&context, loader, None).await?;
+		}
+
+		document.expand_with(None, &context, loader, options()).await
+	}
+}
+
+/// Select a localized string value of a property.
+///
+/// Without preferred language, or if no value has the preferred language, the first value
+/// without language is selected, or else the first value.
+fn localized<'a, T: Id>(node: &'a Node<T>, prop: &'static str, language: Option<&str>) -> Option<&'a str> {
+	let values: Vec<_> = node.get(&property(prop)).filter_map(|object| match object.inner() {
+		Object::Value(Value::LangString(str)) => Some((str.as_str(), str.language())),
+		Object::Value(value) => value.as_str().map(|str| (str, None)),
+		_ => None
+	}).collect();
+
+	if let Some(preferred) = language {
+		if let Some((str, _)) = values.iter().find(|(_, lang)| lang.map(|lang| lang.eq_ignore_ascii_case(preferred)).unwrap_or(false)) {
+			return Some(str)
+		}
+	}
+
+	values.iter().find(|(_, lang)| lang.is_none()).or_else(|| values.first()).map(|(str, _)| *str)
+}
+
+/// First string value of a property.
+fn string<'a, T: Id>(node: &'a Node<T>, prop: &'static str) -> Option<&'a str> {
+	node.get(&property(prop)).find_map(|object| match object.inner() {
+		Object::Value(value) => value.as_str(),
+		_ => None
+	})
+}
+
+/// ActivityStreams object view.
+///
+/// Gives typed access to the common properties of an expanded ActivityStreams object.
+/// This works with any object (activities, actors, notes, collections, etc.): accessors to
+/// properties that do not apply to the object simply return nothing.
+pub struct Activity<'a, T: Id> {
+	node: &'a Node<T>
+}
+
+impl<'a, T: Id> Clone for Activity<'a, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: Id> Copy for Activity<'a, T> {}
+
+impl<'a, T: Id> Activity<'a, T> {
+	/// Create a view over the given node.
+	pub fn new(node: &'a Node<T>) -> Activity<'a, T> {
+		Activity {
+			node
+		}
+	}
+
+	/// Create a view over the single top-level node of the given document.
+	///
+	/// Returns `None` if the document does not contain exactly one top-level node.
+	pub fn from_document(document: &'a ExpandedDocument<T>) -> Option<Activity<'a, T>> {
+		let mut nodes = document.iter().filter_map(|object| match object.inner() {
+			Object::Node(node) => Some(node),
+			_ => None
+		});
+
+		match (nodes.next(), nodes.next()) {
+			(Some(node), None) => Some(Activity::new(node)),
+			_ => None
+		}
+	}
+
+	/// The underlying node.
+	pub fn node(&self) -> &'a Node<T> {
+		self.node
+	}
+
+	/// Identifier of the object.
+	pub fn id(&self) -> Option<&'a Reference<T>> {
+		node_id(self.node)
+	}
+
+	/// Types of the object.
+	pub fn types(&self) -> &'a [Lenient<Reference<T>>] {
+		self.node.types()
+	}
+
+	/// Checks if the object has the given ActivityStreams type.
+	///
+	/// The type is given by name, without namespace (such as `Create` or `Note`).
+	pub fn is(&self, ty: &str) -> bool {
+		self.node.types().iter().any(|t| match t {
+			Lenient::Ok(Reference::Id(id)) => id.as_iri().as_str().strip_prefix(NAMESPACE) == Some(ty),
+			_ => false
+		})
+	}
+
+	fn objects(&self, prop: &'static str) -> Vec<Activity<'a, T>> {
+		nodes(self.node, prop).map(Activity::new).collect()
+	}
+
+	fn references(&self, prop: &'static str) -> Vec<&'a Reference<T>> {
+		nodes(self.node, prop).filter_map(node_id).collect()
+	}
+
+	/// `actor` of the activity.
+	pub fn actor(&self) -> Vec<Activity<'a, T>> {
+		self.objects(ACTOR)
+	}
+
+	/// `object` of the activity.
+	pub fn object(&self) -> Vec<Activity<'a, T>> {
+		self.objects(OBJECT)
+	}
+
+	/// `target` of the activity.
+	pub fn target(&self) -> Vec<Activity<'a, T>> {
+		self.objects(TARGET)
+	}
+
+	/// `origin` of the activity.
+	pub fn origin(&self) -> Vec<Activity<'a, T>> {
+		self.objects(ORIGIN)
+	}
+
+	/// `result` of the activity.
+	pub fn result(&self) -> Vec<Activity<'a, T>> {
+		self.objects(RESULT)
+	}
+
+	/// `instrument` of the activity.
+	pub fn instrument(&self) -> Vec<Activity<'a, T>> {
+		self.objects(INSTRUMENT)
+	}
+
+	/// `attributedTo` property.
+	pub fn attributed_to(&self) -> Vec<Activity<'a, T>> {
+		self.objects(ATTRIBUTED_TO)
+	}
+
+	/// `inReplyTo` property.
+	pub fn in_reply_to(&self) -> Vec<Activity<'a, T>> {
+		self.objects(IN_REPLY_TO)
+	}
+
+	/// `tag` property.
+	pub fn tag(&self) -> Vec<Activity<'a, T>> {
+		self.objects(TAG)
+	}
+
+	/// `attachment` property.
+	pub fn attachment(&self) -> Vec<Activity<'a, T>> {
+		self.objects(ATTACHMENT)
+	}
+
+	/// `to` audience.
+	pub fn to(&self) -> Vec<&'a Reference<T>> {
+		self.references(TO)
+	}
+
+	/// `cc` audience.
+	pub fn cc(&self) -> Vec<&'a Reference<T>> {
+		self.references(CC)
+	}
+
+	/// `bto` audience.
+	pub fn bto(&self) -> Vec<&'a Reference<T>> {
+		self.references(BTO)
+	}
+
+	/// `bcc` audience.
+	pub fn bcc(&self) -> Vec<&'a Reference<T>> {
+		self.references(BCC)
+	}
+
+	/// `audience` property.
+	pub fn audience(&self) -> Vec<&'a Reference<T>> {
+		self.references(AUDIENCE)
+	}
+
+	/// Checks if the object is addressed to the [`PUBLIC`] collection.
+	pub fn is_public(&self) -> bool {
+		[TO, CC, BTO, BCC, AUDIENCE].iter().any(|prop| {
+			self.references(prop).into_iter().any(|r| r.as_str() == PUBLIC)
+		})
+	}
+
+	/// `url` property.
+	pub fn url(&self) -> Vec<&'a Reference<T>> {
+		self.references(URL)
+	}
+
+	/// `name` of the object, in the preferred language if any.
+	pub fn name(&self, language: Option<&str>) -> Option<&'a str> {
+		localized(self.node, NAME, language)
+	}
+
+	/// `summary` of the object, in the preferred language if any.
+	pub fn summary(&self, language: Option<&str>) -> Option<&'a str> {
+		localized(self.node, SUMMARY, language)
+	}
+
+	/// `content` of the object, in the preferred language if any.
+	pub fn content(&self, language: Option<&str>) -> Option<&'a str> {
+		localized(self.node, CONTENT, language)
+	}
+
+	/// `published` date.
+	pub fn published(&self) -> Option<&'a str> {
+		string(self.node, PUBLISHED)
+	}
+
+	/// `updated` date.
+	pub fn updated(&self) -> Option<&'a str> {
+		string(self.node, UPDATED)
+	}
+
+	/// `preferredUsername` of the actor.
+	pub fn preferred_username(&self) -> Option<&'a str> {
+		string(self.node, PREFERRED_USERNAME)
+	}
+
+	/// `inbox` of the actor.
+	pub fn inbox(&self) -> Option<&'a Reference<T>> {
+		self.references(INBOX).into_iter().next()
+	}
+
+	/// `outbox` of the actor.
+	pub fn outbox(&self) -> Option<&'a Reference<T>> {
+		self.references(OUTBOX).into_iter().next()
+	}
+
+	/// `followers` collection of the actor.
+	pub fn followers(&self) -> Option<&'a Reference<T>> {
+		self.references(FOLLOWERS).into_iter().next()
+	}
+
+	/// `following` collection of the actor.
+	pub fn following(&self) -> Option<&'a Reference<T>> {
+		self.references(FOLLOWING).into_iter().next()
+	}
+}
@@ -0,0 +1,378 @@
+//! DID documents.
+//!
+//! Preset for [Decentralized Identifiers (DIDs)](https://www.w3.org/TR/did-core/) documents,
+//! as found in the `did:web` and `did:key` ecosystems.
+//! It bundles a pinned copy of the DID core context (`https://www.w3.org/ns/did/v1`), identical
+//! to the published file.
+//! The DID core context protects all its terms (`@protected`): the preset processes documents
+//! in JSON-LD 1.1 mode, where any attempt to redefine them is an error.
+//!
+//! ```ignore
+//! let mut loader = did::loader(NoLoader);
+//! let expanded: ExpandedDocument<IriBuf> = did::expand(&json, &mut loader).await?;
+//! let doc = DidDocument::from_document(&expanded).unwrap();
+//! for method in doc.authentication() {
+//!     println!("{}", method.public_key_multibase().unwrap())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use iref::Iri;
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Value,
+	Loader,
+	Document,
+	JsonContext,
+	ExpandedDocument,
+	ProcessingMode,
+	expansion,
+	object::value::Literal
+};
+use super::{
+	PresetLoader,
+	bundled,
+	property,
+	node_id,
+	is_reference,
+	nodes
+};
+
+/// URL of the DID core context.
+pub const CONTEXT_URL: &str = "https://www.w3.org/ns/did/v1";
+
+/// Pinned DID core context.
+pub const CONTEXT: &str = include_str!("contexts/did-v1.jsonld");
+
+/// `verificationMethod` property.
+pub const VERIFICATION_METHOD: &str = "https://w3id.org/security#verificationMethod";
+
+/// `authentication` property.
+pub const AUTHENTICATION: &str = "https://w3id.org/security#authenticationMethod";
+
+/// `assertionMethod` property.
+pub const ASSERTION_METHOD: &str = "https://w3id.org/security#assertionMethod";
+
+/// `keyAgreement` property.
+pub const KEY_AGREEMENT: &str = "https://w3id.org/security#keyAgreementMethod";
+
+/// `capabilityInvocation` property.
+pub const CAPABILITY_INVOCATION: &str = "https://w3id.org/security#capabilityInvocationMethod";
+
+/// `capabilityDelegation` property.
+pub const CAPABILITY_DELEGATION: &str = "https://w3id.org/security#capabilityDelegationMethod";
+
+/// `controller` property.
+pub const CONTROLLER: &str = "https://w3id.org/security#controller";
+
+/// `alsoKnownAs` property.
+pub const ALSO_KNOWN_AS: &str = "https://www.w3.org/ns/activitystreams#alsoKnownAs";
+
+/// `service` property.
+pub const SERVICE: &str = "https://www.w3.org/ns/did#service";
+
+/// `serviceEndpoint` property.
+pub const SERVICE_ENDPOINT: &str = "https://www.w3.org/ns/did#serviceEndpoint";
+
+/// `publicKeyMultibase` property.
+pub const PUBLIC_KEY_MULTIBASE: &str = "https://w3id.org/security#publicKeyMultibase";
+
+/// `publicKeyJwk` property.
+pub const PUBLIC_KEY_JWK: &str = "https://w3id.org/security#publicKeyJwk";
+
+/// Create a loader serving the pinned DID core context, on top of the given loader.
+pub fn loader<L>(inner: L) -> PresetLoader<L> {
+	PresetLoader::new(inner).with(Iri::new(CONTEXT_URL).unwrap(), bundled(CONTEXT))
+}
+
+/// Expansion options used to process DID documents.
+pub fn options() -> expansion::Options {
+	expansion::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		..expansion::Options::default()
+	}
+}
+
+/// Expand a DID document.
+pub fn expand<'a, T: 'a + Send + Sync + Id, L: Send + Sync + Loader<Document = JsonValue>>(document: &'a JsonValue, loader: &'a mut PresetLoader<L>) -> impl 'a + Send + Future<Output = Result<ExpandedDocument<T>, Error>> {
+	async move {
+		let context: JsonContext<T> = JsonContext::new(None);
+		document.expand_with(None, &context, loader, options()).await
+	}
+}
+
+/// Verification relationship.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Relationship {
+	/// `authentication`.
+	Authentication,
+
+	/// `assertionMethod`.
+	AssertionMethod,
+
+	/// `keyAgreement`.
+	KeyAgreement,
+
+	/// `capabilityInvocation`.
+	CapabilityInvocation,
+
+	/// `capabilityDelegation`.
+	CapabilityDelegation
+}
+
+impl Relationship {
+	/// Property IRI of the relationship.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Relationship::Authentication => AUTHENTICATION,
+			Relationship::AssertionMethod => ASSERTION_METHOD,
+			Relationship::KeyAgreement => KEY_AGREEMENT,
+			Relationship::CapabilityInvocation => CAPABILITY_INVOCATION,
+			Relationship::CapabilityDelegation => CAPABILITY_DELEGATION
+		}
+	}
+}
+
+/// DID document view.
+///
+/// Gives typed access to the properties of an expanded DID document node.
+pub struct DidDocument<'a, T: Id> {
+	node: &'a Node<T>,
+
+	/// Verification methods defined in the document, by identifier.
+	methods: HashMap<&'a Reference<T>, &'a Node<T>>
+}
+
+impl<'a, T: Id> DidDocument<'a, T> {
+	/// Create a view over the given node.
+	pub fn new(node: &'a Node<T>) -> DidDocument<'a, T> {
+		let mut methods = HashMap::new();
+
+		for method in nodes(node, VERIFICATION_METHOD) {
+			if let Some(id) = node_id(method) {
+				methods.insert(id, method);
+			}
+		}
+
+		for rel in &[Relationship::Authentication, Relationship::AssertionMethod, Relationship::KeyAgreement, Relationship::CapabilityInvocation, Relationship::CapabilityDelegation] {
+			for method in nodes(node, rel.as_str()) {
+				if !is_reference(method) {
+					if let Some(id) = node_id(method) {
+						methods.entry(id).or_insert(method);
+					}
+				}
+			}
+		}
+
+		DidDocument {
+			node,
+			methods
+		}
+	}
+
+	/// Create a view over the single top-level node of the given document.
+	///
+	/// Returns `None` if the document does not contain exactly one top-level node.
+	pub fn from_document(document: &'a ExpandedDocument<T>) -> Option<DidDocument<'a, T>> {
+		let mut nodes = document.iter().filter_map(|object| match object.inner() {
+			Object::Node(node) => Some(node),
+			_ => None
+		});
+
+		match (nodes.next(), nodes.next()) {
+			(Some(node), None) => Some(DidDocument::new(node)),
+			_ => None
+		}
+	}
+
+	/// The underlying node.
+	pub fn node(&self) -> &'a Node<T> {
+		self.node
+	}
+
+	/// The DID subject.
+	pub fn id(&self) -> Option<&'a Reference<T>> {
+		node_id(self.node)
+	}
+
+	/// Controllers of the DID subject.
+	pub fn controllers(&self) -> Vec<&'a Reference<T>> {
+		nodes(self.node, CONTROLLER).filter_map(node_id).collect()
+	}
+
+	/// Other identifiers of the DID subject.
+	pub fn also_known_as(&self) -> Vec<&'a Reference<T>> {
+		nodes(self.node, ALSO_KNOWN_AS).filter_map(node_id).collect()
+	}
+
+	/// Verification methods listed in the `verificationMethod` property.
+	pub fn verification_methods(&self) -> Vec<VerificationMethod<'a, T>> {
+		nodes(self.node, VERIFICATION_METHOD).map(VerificationMethod::new).collect()
+	}
+
+	/// Find a verification method defined in the document by identifier.
+	///
+	/// This includes verification methods embedded in verification relationships.
+	pub fn verification_method(&self, id: &Reference<T>) -> Option<VerificationMethod<'a, T>> {
+		self.methods.get(id).map(|node| VerificationMethod::new(node))
+	}
+
+	/// Verification methods of the given relationship.
+	///
+	/// Methods referenced by identifier are resolved against the methods defined in the
+	/// document. References to methods defined elsewhere are returned as is.
+	pub fn relationship(&self, rel: Relationship) -> Vec<VerificationMethod<'a, T>> {
+		nodes(self.node, rel.as_str()).map(|method| {
+			if is_reference(method) {
+				if let Some(resolved) = node_id(method).and_then(|id| self.methods.get(id)) {
+					return VerificationMethod::new(resolved)
+				}
+			}
+
+			VerificationMethod::new(method)
+		}).collect()
+	}
+
+	/// `authentication` verification methods.
+	pub fn authentication(&self) -> Vec<VerificationMethod<'a, T>> {
+		self.relationship(Relationship::Authentication)
+	}
+
+	/// `assertionMethod` verification methods.
+	pub fn assertion_method(&self) -> Vec<VerificationMethod<'a, T>> {
+		self.relationship(Relationship::AssertionMethod)
+	}
+
+	/// `keyAgreement` verification methods.
+	pub fn key_agreement(&self) -> Vec<VerificationMethod<'a, T>> {
+		self.relationship(Relationship::KeyAgreement)
+	}
+
+	/// `capabilityInvocation` verification methods.
+	pub fn capability_invocation(&self) -> Vec<VerificationMethod<'a, T>> {
+		self.relationship(Relationship::CapabilityInvocation)
+	}
+
+	/// `capabilityDelegation` verification methods.
+	pub fn capability_delegation(&self) -> Vec<VerificationMethod<'a, T>> {
+		self.relationship(Relationship::CapabilityDelegation)
+	}
+
+	/// Services of the DID subject.
+	pub fn services(&self) -> Vec<Service<'a, T>> {
+		nodes(self.node, SERVICE).map(|node| Service { node }).collect()
+	}
+}
+
+/// Verification method view.
+pub struct VerificationMethod<'a, T: Id> {
+	node: &'a Node<T>
+}
+
+impl<'a, T: Id> Clone for VerificationMethod<'a, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: Id> Copy for VerificationMethod<'a, T> {}
+
+impl<'a, T: Id> VerificationMethod<'a, T> {
+	fn new(node: &'a Node<T>) -> VerificationMethod<'a, T> {
+		VerificationMethod {
+			node
+		}
+	}
+
+	/// The underlying node.
+	pub fn node(&self) -> &'a Node<T> {
+		self.node
+	}
+
+	/// Identifier of the verification method.
+	pub fn id(&self) -> Option<&'a Reference<T>> {
+		node_id(self.node)
+	}
+
+	/// Checks if the method is only a reference to a method defined outside of the document.
+	pub fn is_reference(&self) -> bool {
+		is_reference(self.node)
+	}
+
+	/// Types of the verification method.
+	pub fn types(&self) -> &'a [Lenient<Reference<T>>] {
+		self.node.types()
+	}
+
+	/// Controller of the verification method.
+	pub fn controller(&self) -> Option<&'a Reference<T>> {
+		nodes(self.node, CONTROLLER).find_map(node_id)
+	}
+
+	/// Multibase encoded public key.
+	pub fn public_key_multibase(&self) -> Option<&'a str> {
+		self.node.get(&property(PUBLIC_KEY_MULTIBASE)).find_map(|object| match object.inner() {
+			Object::Value(value) => value.as_str(),
+			_ => None
+		})
+	}
+
+	/// JSON Web Key public key.
+	///
+	/// The key is only available if the `publicKeyJwk` term is defined as a JSON literal
+	/// (`"@type": "@json"`) by the document context.
+	pub fn public_key_jwk(&self) -> Option<&'a JsonValue> {
+		self.node.get(&property(PUBLIC_KEY_JWK)).find_map(|object| match object.inner() {
+			Object::Value(Value::Literal(Literal::Json(jwk), _)) => Some(jwk.value()),
+			_ => None
+		})
+	}
+}
+
+/// Service view.
+pub struct Service<'a, T: Id> {
+	node: &'a Node<T>
+}
+
+impl<'a, T: Id> Clone for Service<'a, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: Id> Copy for Service<'a, T> {}
+
+impl<'a, T: Id> Service<'a, T> {
+	/// The underlying node.
+	pub fn node(&self) -> &'a Node<T> {
+		self.node
+	}
+
+	/// Identifier of the service.
+	pub fn id(&self) -> Option<&'a Reference<T>> {
+		node_id(self.node)
+	}
+
+	/// Types of the service.
+	pub fn types(&self) -> &'a [Lenient<Reference<T>>] {
+		self.node.types()
+	}
+
+	/// Endpoints of the service.
+	///
+	/// Endpoints given as maps are not included.
+	pub fn endpoints(&self) -> Vec<&'a str> {
+		self.node.get(&property(SERVICE_ENDPOINT)).filter_map(|object| match object.inner() {
+			Object::Node(node) if is_reference(node) => node_id(node).map(Reference::as_str),
+			Object::Value(value) => value.as_str(),
+			_ => None
+		}).collect()
+	}
+}
@@ -0,0 +1,120 @@
+//! Processing presets.
+//!
+//! A preset bundles what is needed to process documents of a given ecosystem: pinned copies
+//! of its contexts (served by a [`PresetLoader`] without network access), expansion options,
+//! and view types giving typed access to the expanded nodes.
+
+use std::collections::HashMap;
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	Reference,
+	Lenient,
+	Node,
+	RemoteDocument,
+	Loader
+};
+
+pub mod did;
+pub mod activity_streams;
+pub mod safe;
+
+#[cfg(feature="well-known")]
+pub mod well_known;
+
+/// Loader serving pinned documents.
+///
+/// Documents registered in this loader are returned directly, without calling the inner loader.
+/// Any other document is loaded by the inner loader.
+pub struct PresetLoader<L> {
+	documents: HashMap<IriBuf, RemoteDocument>,
+	inner: L
+}
+
+impl<L> PresetLoader<L> {
+	/// Create a new loader with no pinned document, on top of the given loader.
+	pub fn new(inner: L) -> PresetLoader<L> {
+		PresetLoader {
+			documents: HashMap::new(),
+			inner
+		}
+	}
+
+	/// Pin a document to the given URL.
+	pub fn insert(&mut self, url: Iri, document: JsonValue) {
+		self.documents.insert(url.into(), RemoteDocument::new(document, url));
+	}
+
+	/// Pin a document to the given URL.
+	pub fn with(mut self, url: Iri, document: JsonValue) -> Self {
+		self.insert(url, document);
+		self
+	}
+
+	/// Checks if a document is pinned to the given URL.
+	pub fn contains(&self, url: Iri) -> bool {
+		self.documents.contains_key(&IriBuf::from(url))
+	}
+
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the inner loader.
+	pub fn inner_mut(&mut self) -> &mut L {
+		&mut self.inner
+	}
+
+	/// Consumes the loader and returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.inner
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> Loader for PresetLoader<L> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			match self.documents.get(&url) {
+				Some(doc) => Ok(doc.clone()),
+				None => self.inner.load(url.as_iri()).await
+			}
+		}.boxed()
+	}
+}
+
+/// Parse a bundled context.
+///
+/// ## Panics
+///
+/// Panics if the bundled context is not valid JSON.
+fn bundled(source: &str) -> JsonValue {
+	json::parse(source).expect("invalid bundled context")
+}
+
+fn property<T: Id>(iri: &'static str) -> Reference<T> {
+	Reference::Id(T::from_iri(Iri::new(iri).unwrap()))
+}
+
+fn node_id<T: Id>(node: &Node<T>) -> Option<&Reference<T>> {
+	match &node.id {
+		Some(Lenient::Ok(id)) => Some(id),
+		_ => None
+	}
+}
+
+/// Checks if the node is only a reference to another node.
+fn is_reference<T: Id>(node: &Node<T>) -> bool {
+	node.id.is_some() && node.types.is_empty() && node.properties.is_empty() && node.graph.is_none() && node.included.is_none()
+}
+
+/// Nodes values of the given property.
+fn nodes<'a, T: Id>(node: &'a Node<T>, prop: &'static str) -> impl Iterator<Item = &'a Node<T>> {
+	node.get(&property(prop)).nodes()
+}
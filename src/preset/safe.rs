@@ -0,0 +1,90 @@
+//! Safe mode.
+//!
+//! Preset for documents whose meaning must not be silently altered, such as verifiable
+//! credentials, where a verifier processing a crafted context could be fooled by a
+//! semantic-substitution attack. Like the "safe mode" of other JSON-LD processors, it makes
+//! processing fail instead of quietly changing or losing information:
+//!
+//! - documents must be served with a JSON media type (see [`is_json_media_type`]);
+//! - entries that expansion would drop, because their key does not expand to an IRI
+//!   or a keyword, are errors, as are relative IRI references that cannot be resolved and
+//!   node identifiers that are not absolute IRIs or blank node identifiers;
+//! - unknown keywords (see [`KeywordPolicy`]) are errors, in documents and contexts;
+//! - contexts are only served from the copies pinned in the [`loader`], and never fetched;
+//! - documents are processed in JSON-LD 1.1 mode, where protected terms cannot be redefined.
+//!
+//! Application-enforced mappings can be given as initial context, processed with
+//! [`Local::enforce`](crate::context::Local::enforce).
+//!
+//! ```ignore
+//! let mut loader = safe::loader().with(credentials_url, credentials_context);
+//! let expanded: ExpandedDocument<IriBuf> = safe::expand(&json, Some("application/ld+json"), &JsonContext::new(None), &mut loader).await?;
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use json::JsonValue;
+use crate::{
+	Id,
+	Error,
+	ErrorCode,
+	NoLoader,
+	Document,
+	JsonContext,
+	ExpandedDocument,
+	ProcessingMode,
+	KeywordPolicy,
+	expansion,
+	is_json_media_type
+};
+use super::PresetLoader;
+
+/// Unexpected content type, source of the `LoadingDocumentFailed` error raised when a
+/// document is not served with a JSON media type.
+#[derive(Debug)]
+pub struct ContentTypeError(pub Option<String>);
+
+impl fmt::Display for ContentTypeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.0 {
+			Some(ty) => write!(f, "unexpected content type `{}`", ty),
+			None => write!(f, "missing content type")
+		}
+	}
+}
+
+impl std::error::Error for ContentTypeError {}
+
+/// Create a loader serving pinned contexts only.
+///
+/// Contexts must be pinned with [`PresetLoader::with`]: loading any other document fails.
+pub fn loader() -> PresetLoader<NoLoader> {
+	PresetLoader::new(NoLoader)
+}
+
+/// Expansion options used in safe mode.
+pub fn options() -> expansion::Options {
+	expansion::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		relative_iris: false,
+		dropped_entries: false,
+		keyword_policy: KeywordPolicy::Error,
+		..expansion::Options::default()
+	}
+}
+
+/// Checks that a document is served with a JSON media type.
+pub fn check_content_type(content_type: Option<&str>) -> Result<(), Error> {
+	match content_type {
+		Some(ty) if is_json_media_type(ty) => Ok(()),
+		ty => Err(Error::new(ErrorCode::LoadingDocumentFailed, ContentTypeError(ty.map(String::from))))
+	}
+}
+
+/// Expand a document in safe mode, given the content type it was served with.
+pub fn expand<'a, T: 'a + Send + Sync + Id>(document: &'a JsonValue, content_type: Option<&'a str>, context: &'a JsonContext<T>, loader: &'a mut PresetLoader<NoLoader>) -> impl 'a + Send + Future<Output = Result<ExpandedDocument<T>, Error>> {
+	async move {
+		check_content_type(content_type)?;
+		document.expand_with(None, context, loader, options()).await
+	}
+}
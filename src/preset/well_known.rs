@@ -0,0 +1,116 @@
+//! Well-known contexts.
+//!
+//! The [`WellKnownLoader`] serves pinned copies of the most commonly used contexts without
+//! network access, and falls back to a wrapped loader for anything else.
+//! Documents referring to these contexts are then processed the same way whatever the network
+//! returns, and a compromised or unavailable context server cannot change their meaning.
+//!
+//! ```ignore
+//! let mut loader = WellKnownLoader::new(NoLoader);
+//! let expanded: ExpandedDocument<IriBuf> = credential.expand(&JsonContext::new(None), &mut loader).await?;
+//! ```
+//!
+//! The bundled contexts are published by the W3C under the
+//! [W3C Software and Document License](https://www.w3.org/Consortium/Legal/2015/copyright-software-and-document).
+//! See `src/preset/contexts/NOTICE.md` for their origin.
+
+use futures::future::BoxFuture;
+use iref::Iri;
+use json::JsonValue;
+use crate::{
+	Error,
+	RemoteDocument,
+	Loader
+};
+use super::{
+	PresetLoader,
+	bundled,
+	did,
+	activity_streams
+};
+
+/// URL of the Verifiable Credentials context.
+pub const CREDENTIALS_V1_URL: &str = "https://www.w3.org/2018/credentials/v1";
+
+/// Verifiable Credentials context.
+pub const CREDENTIALS_V1: &str = include_str!("contexts/credentials-v1.jsonld");
+
+/// URL of the first version of the security context.
+pub const SECURITY_V1_URL: &str = "https://w3id.org/security/v1";
+
+/// First version of the security context.
+pub const SECURITY_V1: &str = include_str!("contexts/security-v1.jsonld");
+
+/// URL of the second version of the security context.
+pub const SECURITY_V2_URL: &str = "https://w3id.org/security/v2";
+
+/// Second version of the security context, importing the first one.
+pub const SECURITY_V2: &str = include_str!("contexts/security-v2.jsonld");
+
+/// Pinned contexts, with their URL.
+pub const CONTEXTS: &[(&str, &str)] = &[
+	(CREDENTIALS_V1_URL, CREDENTIALS_V1),
+	(did::CONTEXT_URL, did::CONTEXT),
+	(activity_streams::CONTEXT_URL, activity_streams::CONTEXT),
+	(SECURITY_V1_URL, SECURITY_V1),
+	(SECURITY_V2_URL, SECURITY_V2)
+];
+
+/// Loader serving the well-known [`CONTEXTS`], on top of the given loader.
+pub struct WellKnownLoader<L> {
+	inner: PresetLoader<L>
+}
+
+impl<L> WellKnownLoader<L> {
+	/// Create a new loader serving the well-known contexts.
+	///
+	/// ## Panics
+	///
+	/// Panics if a bundled context is not valid JSON.
+	pub fn new(inner: L) -> WellKnownLoader<L> {
+		let mut preset = PresetLoader::new(inner);
+		for (url, context) in CONTEXTS {
+			preset.insert(Iri::new(url).unwrap(), bundled(context))
+		}
+
+		WellKnownLoader {
+			inner: preset
+		}
+	}
+
+	/// Pin another document to the given URL.
+	///
+	/// This can be used to replace one of the well-known contexts.
+	pub fn with(mut self, url: Iri, document: JsonValue) -> Self {
+		self.inner.insert(url, document);
+		self
+	}
+
+	/// Checks if the given URL is served without calling the inner loader.
+	pub fn contains(&self, url: Iri) -> bool {
+		self.inner.contains(url)
+	}
+
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		self.inner.inner()
+	}
+
+	/// Returns a mutable reference to the inner loader.
+	pub fn inner_mut(&mut self) -> &mut L {
+		self.inner.inner_mut()
+	}
+
+	/// Consumes the loader and returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.inner.into_inner()
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> Loader for WellKnownLoader<L> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		self.inner.load(url)
+	}
+}
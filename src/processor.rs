@@ -0,0 +1,160 @@
+//! Batch processing of documents.
+//!
+//! Harvesting jobs typically expand many documents sharing the same contexts.
+//! A [`Processor`] owns a single loader, used for every document, and caches the result of
+//! processing each top-level `@context` entry, so that a context common to many documents is
+//! loaded and processed only once:
+//! ```ignore
+//! let mut processor = Processor::new(loader, expansion::Options::default());
+//! let mut results = processor.expand_all(documents);
+//! while let Some(expanded) = results.next().await {
+//!     // ...
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use futures::stream::{self, Stream};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Error,
+	Id,
+	ContextMut,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	RemoteDocument,
+	context::{
+		Local,
+		Loader,
+		ProcessingStack
+	},
+	expansion
+};
+
+/// Batch document processor.
+///
+/// Processed contexts are cached by base URL, base IRI of the initial context and value of the
+/// `@context` entry.
+/// Contexts whose definitions are not propagated (`"@propagate": false`) are not cached.
+pub struct Processor<L, T: Id = IriBuf, C = JsonContext<T>> {
+	loader: L,
+	context: C,
+	options: expansion::Options,
+	cache: HashMap<(Option<IriBuf>, Option<IriBuf>, String), C>,
+	id: PhantomData<T>
+}
+
+impl<L> Processor<L> {
+	/// Create a new processor using the given loader and options, with an empty initial context.
+	pub fn new(loader: L, options: expansion::Options) -> Processor<L> {
+		Processor::with_context(loader, JsonContext::new(None), options)
+	}
+}
+
+impl<L, T: Id, C: ContextMut<T>> Processor<L, T, C> {
+	/// Create a new processor using the given loader, initial context and options.
+	pub fn with_context(loader: L, context: C, options: expansion::Options) -> Processor<L, T, C> {
+		Processor {
+			loader,
+			context,
+			options,
+			cache: HashMap::new(),
+			id: PhantomData
+		}
+	}
+
+	/// Document loader.
+	pub fn loader(&self) -> &L {
+		&self.loader
+	}
+
+	/// Document loader.
+	pub fn loader_mut(&mut self) -> &mut L {
+		&mut self.loader
+	}
+
+	/// Initial context.
+	pub fn context(&self) -> &C {
+		&self.context
+	}
+
+	/// Expansion options.
+	pub fn options(&self) -> expansion::Options {
+		self.options
+	}
+
+	/// Number of processed contexts in the cache.
+	pub fn cached_contexts(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Clear the processed contexts cache.
+	pub fn clear_cache(&mut self) {
+		self.cache.clear()
+	}
+
+	/// Returns the loader.
+	pub fn into_loader(self) -> L {
+		self.loader
+	}
+}
+
+impl<L: Send + Sync + Loader, T: Send + Sync + Id, C: Send + Sync + ContextMut<T>> Processor<L, T, C> where C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+	/// Expand a document.
+	pub async fn expand(&mut self, mut document: JsonValue, base_url: Option<Iri<'_>>) -> Result<ExpandedDocument<T>, Error> {
+		let local_context = match &document {
+			JsonValue::Object(object) if self.context.previous_context().is_none() => object.get("@context").cloned(),
+			_ => None
+		};
+
+		if let Some(local_context) = local_context {
+			let key = (base_url.map(IriBuf::from), self.context.base_iri().map(IriBuf::from), local_context.dump());
+			if !self.cache.contains_key(&key) {
+				let processed = C::LocalContext::from(local_context).process_with(&self.context, ProcessingStack::new(), &mut self.loader, base_url, self.options.into()).await?;
+				if processed.previous_context().is_some() {
+					return document.expand_with(base_url, &self.context, &mut self.loader, self.options).await
+				}
+
+				self.cache.insert(key.clone(), processed);
+			}
+
+			document.remove("@context");
+			return document.expand_with(base_url, &self.cache[&key], &mut self.loader, self.options).await
+		}
+
+		document.expand_with(base_url, &self.context, &mut self.loader, self.options).await
+	}
+
+	/// Expand every document of the given iterator, yielding the results in order.
+	///
+	/// Documents are expanded lazily, one at a time, as the stream is polled.
+	/// A failure does not prevent the following documents from being expanded.
+	pub fn expand_all<'a, I: 'a + IntoIterator<Item = RemoteDocument<JsonValue>>>(&'a mut self, documents: I) -> impl 'a + Stream<Item = Result<ExpandedDocument<T>, Error>> where I::IntoIter: Send {
+		stream::unfold((documents.into_iter(), self), |(mut documents, processor)| async move {
+			let (document, base_url) = documents.next()?.into_parts();
+			let result = processor.expand(document, Some(base_url.as_iri())).await;
+			Some((result, (documents, processor)))
+		})
+	}
+
+	/// Expand every document of the given iterator into a single document.
+	///
+	/// Each document is expanded with its own URL as base IRI, so that document-relative IRIs
+	/// resolve against the origin of their source rather than the base of the initial context.
+	/// The top-level objects of all the documents are concatenated, in order.
+	pub async fn expand_merged<I: IntoIterator<Item = RemoteDocument<JsonValue>>>(&mut self, documents: I) -> Result<ExpandedDocument<T>, Error> where I::IntoIter: Send {
+		let base_iri = self.context.base_iri().map(IriBuf::from);
+		let mut result = ExpandedDocument::new();
+		for document in documents {
+			let (document, base_url) = document.into_parts();
+			self.context.set_base_iri(Some(base_url.as_iri()));
+			let expanded = self.expand(document, Some(base_url.as_iri())).await;
+			self.context.set_base_iri(base_iri.as_ref().map(|iri| iri.as_iri()));
+			result.extend(expanded?)
+		}
+
+		Ok(result)
+	}
+}
@@ -0,0 +1,183 @@
+//! Document provenance.
+//!
+//! When aggregating data harvested from many sources in a [`Dataset`](crate::Dataset), the
+//! source of each statement is lost.
+//! A [`Provenance`] index keeps it: it records, for every property value of every identified
+//! node of an expanded document, the IRI of the document that asserted it.
+//! Indexes of different documents are merged along with their datasets.
+//!
+//! ```ignore
+//! let doc: RemoteDocument = loader.load(url).await?;
+//! let expanded = doc.expand(&context, &mut loader).await?;
+//! provenance.record_remote(&doc, &expanded);
+//! dataset.merge(Dataset::try_from(expanded)?)?;
+//!
+//! let sources = provenance.sources(&subject, &property);
+//! ```
+//!
+//! Nodes without identifier cannot be referred to, and are not tracked (their nested nodes are).
+//! Blank node identifiers are local to their document, and only remain meaningful if the
+//! datasets are merged without relabeling.
+
+use std::collections::{
+	HashMap,
+	HashSet
+};
+use std::borrow::Borrow;
+use iref::{
+	Iri,
+	IriBuf
+};
+use crate::{
+	Id,
+	Reference,
+	ToReference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	ExpandedDocument,
+	RemoteDocument
+};
+
+/// Values of a property, with their sources.
+type Assertions<T> = HashMap<Indexed<Object<T>>, HashSet<IriBuf>>;
+
+/// Source documents of the property values of nodes.
+#[derive(Clone)]
+pub struct Provenance<T: Id = IriBuf> {
+	subjects: HashMap<Reference<T>, HashMap<Reference<T>, Assertions<T>>>
+}
+
+impl<T: Id> Provenance<T> {
+	/// Create a new empty index.
+	pub fn new() -> Provenance<T> {
+		Provenance {
+			subjects: HashMap::new()
+		}
+	}
+
+	/// Checks if the index is empty.
+	pub fn is_empty(&self) -> bool {
+		self.subjects.is_empty()
+	}
+
+	/// Record the given source for every property value of the given expanded document.
+	///
+	/// Nodes embedded in properties, lists, graphs and included blocks are recorded.
+	pub fn record(&mut self, source: Iri, doc: &ExpandedDocument<T>) {
+		for object in doc {
+			self.record_object(source, object)
+		}
+	}
+
+	/// Record the URL of the given remote document as the source of its expanded form.
+	pub fn record_remote<D>(&mut self, remote: &RemoteDocument<D>, doc: &ExpandedDocument<T>) {
+		self.record(remote.url(), doc)
+	}
+
+	/// Add a source for a value of the property of the given subject.
+	pub fn insert(&mut self, subject: Reference<T>, property: Reference<T>, value: Indexed<Object<T>>, source: IriBuf) {
+		self.subjects.entry(subject).or_default().entry(property).or_default().entry(value).or_default().insert(source);
+	}
+
+	/// Merge the sources of another index in this one.
+	pub fn merge(&mut self, other: Provenance<T>) {
+		for (subject, properties) in other.subjects {
+			let self_properties = self.subjects.entry(subject).or_default();
+			for (property, values) in properties {
+				let self_values = self_properties.entry(property).or_default();
+				for (value, sources) in values {
+					self_values.entry(value).or_default().extend(sources)
+				}
+			}
+		}
+	}
+
+	/// Sources asserting any value of the given property of the given subject.
+	pub fn sources<S: ToReference<T>, P: ToReference<T>>(&self, subject: S, property: P) -> HashSet<Iri<'_>> {
+		let mut result = HashSet::new();
+		if let Some(values) = self.values(subject, property) {
+			for sources in values.values() {
+				result.extend(sources.iter().map(IriBuf::as_iri))
+			}
+		}
+
+		result
+	}
+
+	/// Sources asserting the given value of the property of the given subject.
+	pub fn value_sources<S: ToReference<T>, P: ToReference<T>>(&self, subject: S, property: P, value: &Indexed<Object<T>>) -> Option<&HashSet<IriBuf>> {
+		self.values(subject, property)?.get(value)
+	}
+
+	/// Sources asserting at least one property value of the given subject.
+	pub fn subject_sources<S: ToReference<T>>(&self, subject: S) -> HashSet<Iri<'_>> {
+		let mut result = HashSet::new();
+		if let Some(properties) = self.subjects.get(subject.to_ref().borrow()) {
+			for values in properties.values() {
+				for sources in values.values() {
+					result.extend(sources.iter().map(IriBuf::as_iri))
+				}
+			}
+		}
+
+		result
+	}
+
+	fn values<S: ToReference<T>, P: ToReference<T>>(&self, subject: S, property: P) -> Option<&Assertions<T>> {
+		self.subjects.get(subject.to_ref().borrow())?.get(property.to_ref().borrow())
+	}
+
+	fn record_object(&mut self, source: Iri, object: &Object<T>) {
+		match object {
+			Object::Value(_) => (),
+			Object::Node(node) => self.record_node(source, node),
+			Object::List(items) => {
+				for item in items {
+					self.record_object(source, item)
+				}
+			}
+		}
+	}
+
+	fn record_node(&mut self, source: Iri, node: &Node<T>) {
+		if let Some(Lenient::Ok(id)) = node.id() {
+			for (property, values) in &node.properties {
+				for value in values {
+					self.insert(id.clone(), property.clone(), value.clone(), source.into())
+				}
+			}
+		}
+
+		if let Some(graph) = node.graph() {
+			for object in graph {
+				self.record_object(source, object)
+			}
+		}
+
+		if let Some(included) = node.included() {
+			for node in included {
+				self.record_node(source, node)
+			}
+		}
+
+		for values in node.properties.values() {
+			for value in values {
+				self.record_object(source, value)
+			}
+		}
+
+		for nodes in node.reverse_properties.values() {
+			for node in nodes {
+				self.record_node(source, node)
+			}
+		}
+	}
+}
+
+impl<T: Id> Default for Provenance<T> {
+	fn default() -> Provenance<T> {
+		Self::new()
+	}
+}
@@ -0,0 +1,421 @@
+//! Python bindings.
+//!
+//! This module exposes the processor to Python, enabled by the `python` feature, with the
+//! interface of [PyLD](https://github.com/digitalbazaar/pyld): documents are given and
+//! returned as Python values (`dict`, `list`, `str`, etc.), and a `str` input is the URL of a
+//! document to load.
+//! A Python extension module can be built with:
+//! ```text
+//! cargo rustc --release --features python,pyo3/extension-module --crate-type cdylib
+//! ```
+//! The resulting `libjson_ld.so` library must be renamed `json_ld.so` (`json_ld.pyd` on
+//! Windows) to be imported:
+//! ```text
+//! import json_ld
+//!
+//! expanded = json_ld.expand(doc, {"base": "https://example.org/"})
+//! compacted = json_ld.compact(doc, {"name": "http://schema.org/name"})
+//! ```
+//!
+//! Every function takes an optional dictionary of options:
+//! - `base`: the base IRI of the document.
+//! - `documentLoader`: a function loading remote documents, called with their URL and a
+//!   dictionary of options, returning a dictionary with the loaded `document` (a JSON value, or
+//!   its JSON serialization) and, optionally, its `documentUrl`, as PyLD document loaders.
+//!   Without loader, processing fails if a remote document is referenced.
+//! - `compactArrays`: for `compact` and `flatten`, whether to replace arrays of a single item by
+//!   this item (default `True`).
+//! - `format`: for `to_rdf`, `application/n-quads` to return an N-Quads string rather than
+//!   a dataset dictionary.
+//!
+//! Processing errors raise a `JsonLdError` exception, whose message is the JSON-LD error code.
+//! The GIL is released during processing, and only acquired to call the document loader.
+//!
+//! Framing is not available, since this crate does not implement the framing algorithm.
+
+use futures::executor::block_on;
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use pyo3::prelude::*;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTypeError, PyValueError};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use crate::{
+	Document,
+	Error,
+	ErrorCode,
+	JsonContext,
+	Loader,
+	NoLoader,
+	Reference,
+	RemoteDocument,
+	ExpandedDocument,
+	compaction,
+	expansion,
+	flattening,
+	generator,
+	rdf::{
+		self,
+		Literal,
+		Term
+	},
+	util::{
+		AsJson,
+		json_float,
+		parse_json
+	}
+};
+
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+create_exception!(json_ld, JsonLdError, PyException, "JSON-LD processing error.");
+
+fn error(code: ErrorCode) -> PyErr {
+	JsonLdError::new_err(code.as_str().to_string())
+}
+
+/// Convert a Python value into JSON.
+fn to_json(value: &Bound<PyAny>) -> PyResult<JsonValue> {
+	if value.is_none() {
+		Ok(JsonValue::Null)
+	} else if let Ok(b) = value.downcast::<PyBool>() {
+		Ok(b.is_true().into())
+	} else if let Ok(n) = value.downcast::<PyInt>() {
+		match n.extract::<i64>() {
+			Ok(n) => Ok(json::number::Number::from_parts(n >= 0, n.unsigned_abs(), 0).into()),
+			Err(_) => match n.extract::<u64>() {
+				Ok(n) => Ok(n.into()),
+				Err(_) => to_json(n.call_method0("__float__")?.as_any())
+			}
+		}
+	} else if let Ok(n) = value.downcast::<PyFloat>() {
+		let n = n.value();
+		if n.is_finite() {
+			Ok(json_float(n))
+		} else {
+			Err(PyValueError::new_err("out of range float values are not JSON compliant"))
+		}
+	} else if let Ok(s) = value.downcast::<PyString>() {
+		Ok(s.to_str()?.into())
+	} else if let Ok(list) = value.downcast::<PyList>() {
+		list.iter().map(|item| to_json(&item)).collect::<PyResult<Vec<_>>>().map(JsonValue::Array)
+	} else if let Ok(tuple) = value.downcast::<PyTuple>() {
+		tuple.iter().map(|item| to_json(&item)).collect::<PyResult<Vec<_>>>().map(JsonValue::Array)
+	} else if let Ok(dict) = value.downcast::<PyDict>() {
+		let mut object = json::object::Object::with_capacity(dict.len());
+		for (key, value) in dict.iter() {
+			match key.downcast::<PyString>() {
+				Ok(key) => object.insert(key.to_str()?, to_json(&value)?),
+				Err(_) => return Err(PyTypeError::new_err(format!("keys must be str, not {}", key.get_type().name()?)))
+			}
+		}
+
+		Ok(JsonValue::Object(object))
+	} else {
+		Err(PyTypeError::new_err(format!("object of type {} is not JSON serializable", value.get_type().name()?)))
+	}
+}
+
+/// Convert JSON into a Python value.
+///
+/// Numbers with a fraction or exponent are converted into `float`, as with the `json` module.
+fn from_json<'py>(py: Python<'py>, value: &JsonValue) -> PyResult<Bound<'py, PyAny>> {
+	match value {
+		JsonValue::Null => Ok(py.None().into_bound(py)),
+		JsonValue::Boolean(b) => Ok(PyBool::new(py, *b).to_owned().into_any()),
+		JsonValue::Number(n) => {
+			let (positive, mantissa, exponent) = n.as_parts();
+			if exponent == 0 {
+				let n = mantissa as i128;
+				Ok((if positive { n } else { -n }).into_pyobject(py)?.into_any())
+			} else {
+				Ok(PyFloat::new(py, f64::from(*n)).into_any())
+			}
+		},
+		JsonValue::Short(s) => Ok(PyString::new(py, s.as_str()).into_any()),
+		JsonValue::String(s) => Ok(PyString::new(py, s).into_any()),
+		JsonValue::Array(items) => {
+			let list = PyList::empty(py);
+			for item in items {
+				list.append(from_json(py, item)?)?
+			}
+
+			Ok(list.into_any())
+		},
+		JsonValue::Object(object) => {
+			let dict = PyDict::new(py);
+			for (key, value) in object.iter() {
+				dict.set_item(key, from_json(py, value)?)?
+			}
+
+			Ok(dict.into_any())
+		}
+	}
+}
+
+/// Loader calling the Python `documentLoader` function, if any.
+struct PyLoader(Option<PyObject>);
+
+impl PyLoader {
+	fn call(&self, py: Python, url: Iri) -> PyResult<RemoteDocument> {
+		let loader = match &self.0 {
+			Some(loader) => loader,
+			None => return Err(JsonLdError::new_err(ErrorCode::LoadingDocumentFailed.as_str().to_string()))
+		};
+
+		let remote = loader.call1(py, (url.as_str(), PyDict::new(py)))?;
+		let remote = remote.bind(py).downcast::<PyDict>()?;
+		let document = match remote.get_item("document")? {
+			Some(document) => match document.downcast::<PyString>() {
+				Ok(source) => parse_json(source.to_str()?).map_err(|e| PyValueError::new_err(e.to_string()))?,
+				Err(_) => to_json(&document)?
+			},
+			None => return Err(PyValueError::new_err("the loaded document has no `document` entry"))
+		};
+
+		let document_url = match remote.get_item("documentUrl")? {
+			Some(document_url) if !document_url.is_none() => {
+				let document_url: String = document_url.extract()?;
+				IriBuf::new(&document_url).map_err(|_| PyValueError::new_err(format!("invalid document URL `{}`", document_url)))?
+			},
+			_ => url.into()
+		};
+
+		Ok(RemoteDocument::new(document, document_url.as_iri()))
+	}
+}
+
+impl Loader for PyLoader {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let result = Python::with_gil(|py| self.call(py, url)).map_err(|e| {
+			warn!("unable to load document `{}': {}", url, e);
+			ErrorCode::LoadingDocumentFailed
+		});
+		async move {
+			result.map_err(Error::from)
+		}.boxed()
+	}
+}
+
+/// Processing options.
+struct Options {
+	base: Option<IriBuf>,
+	loader: PyLoader,
+	compact_arrays: bool,
+	format: Option<String>
+}
+
+impl Options {
+	fn new(options: Option<&Bound<PyDict>>) -> PyResult<Options> {
+		let mut result = Options {
+			base: None,
+			loader: PyLoader(None),
+			compact_arrays: true,
+			format: None
+		};
+
+		if let Some(options) = options {
+			if let Some(base) = options.get_item("base")? {
+				if !base.is_none() {
+					let base: String = base.extract()?;
+					result.base = Some(IriBuf::new(&base).map_err(|_| PyValueError::new_err(format!("invalid base IRI `{}`", base)))?)
+				}
+			}
+
+			if let Some(loader) = options.get_item("documentLoader")? {
+				if !loader.is_none() {
+					result.loader = PyLoader(Some(loader.unbind()))
+				}
+			}
+
+			if let Some(compact_arrays) = options.get_item("compactArrays")? {
+				result.compact_arrays = compact_arrays.extract()?
+			}
+
+			if let Some(format) = options.get_item("format")? {
+				result.format = format.extract()?
+			}
+		}
+
+		Ok(result)
+	}
+
+	/// Input document and its base IRI.
+	///
+	/// A string input is the URL of a document, loaded with the document loader.
+	fn input(&self, py: Python, input: &Bound<PyAny>) -> PyResult<(JsonValue, Option<IriBuf>)> {
+		match input.downcast::<PyString>() {
+			Ok(url) => {
+				let url = url.to_str()?;
+				let url = Iri::new(url).map_err(|_| PyValueError::new_err(format!("invalid document URL `{}`", url)))?;
+				let (document, document_url) = self.loader.call(py, url)?.into_parts();
+				Ok((document, Some(self.base.clone().unwrap_or(document_url))))
+			},
+			Err(_) => Ok((to_json(input)?, self.base.clone()))
+		}
+	}
+
+	fn compaction(&self) -> compaction::Options {
+		compaction::Options {
+			compact_arrays: self.compact_arrays,
+			..compaction::Options::default()
+		}
+	}
+}
+
+fn expand_json<L: Send + Sync + Loader<Document = JsonValue>>(document: &JsonValue, base: Option<Iri>, loader: &mut L) -> Result<ExpandedDocument<IriBuf>, Error> {
+	block_on(document.expand_with(base, &JsonContext::new(base), loader, expansion::Options::default()))
+}
+
+fn compact_json(document: &JsonValue, context: &JsonValue, base: Option<Iri>, loader: &mut PyLoader, options: compaction::Options) -> Result<JsonValue, Error> {
+	let expanded = expand_json(document, base, loader)?;
+	block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, context, base, loader, options))
+}
+
+fn flatten_json(document: &JsonValue, context: Option<&JsonValue>, base: Option<Iri>, loader: &mut PyLoader, options: compaction::Options) -> Result<JsonValue, Error> {
+	let expanded = expand_json(document, base, loader)?;
+	let flattened = flattening::flatten(&expanded, generator::Blank::new())?.as_json();
+	match context {
+		Some(context) => {
+			// The flattened document is in expanded form.
+			let expanded = expand_json(&flattened, base, &mut NoLoader)?;
+			block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, context, base, loader, options))
+		},
+		None => Ok(flattened)
+	}
+}
+
+fn to_rdf_json(document: &JsonValue, base: Option<Iri>, loader: &mut PyLoader) -> Result<Vec<rdf::Quad>, Error> {
+	let expanded = expand_json(document, base, loader)?;
+	rdf::to_rdf(&expanded, generator::Blank::new(), rdf::Options::default())
+}
+
+/// Expand a JSON-LD document.
+#[pyfunction]
+#[pyo3(signature = (input, options = None))]
+fn expand<'py>(py: Python<'py>, input: &Bound<'py, PyAny>, options: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+	let mut options = Options::new(options)?;
+	let (document, base) = options.input(py, input)?;
+	let expanded = py.allow_threads(|| {
+		expand_json(&document, base.as_ref().map(|base| base.as_iri()), &mut options.loader).map(|expanded| expanded.as_json()).map_err(|e| e.code())
+	}).map_err(error)?;
+	from_json(py, &expanded)
+}
+
+/// Compact a JSON-LD document with the given context.
+#[pyfunction]
+#[pyo3(signature = (input, ctx, options = None))]
+fn compact<'py>(py: Python<'py>, input: &Bound<'py, PyAny>, ctx: &Bound<'py, PyAny>, options: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+	let mut options = Options::new(options)?;
+	let (document, base) = options.input(py, input)?;
+	let context = to_json(ctx)?;
+	let compaction_options = options.compaction();
+	let compacted = py.allow_threads(|| {
+		compact_json(&document, &context, base.as_ref().map(|base| base.as_iri()), &mut options.loader, compaction_options).map_err(|e| e.code())
+	}).map_err(error)?;
+	from_json(py, &compacted)
+}
+
+/// Flatten a JSON-LD document, and compact it if a context is given.
+#[pyfunction]
+#[pyo3(signature = (input, ctx = None, options = None))]
+fn flatten<'py>(py: Python<'py>, input: &Bound<'py, PyAny>, ctx: Option<&Bound<'py, PyAny>>, options: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+	let mut options = Options::new(options)?;
+	let (document, base) = options.input(py, input)?;
+	let context = ctx.filter(|ctx| !ctx.is_none()).map(to_json).transpose()?;
+	let compaction_options = compaction::Options {
+		omit_graph: false,
+		..options.compaction()
+	};
+	let flattened = py.allow_threads(|| {
+		flatten_json(&document, context.as_ref(), base.as_ref().map(|base| base.as_iri()), &mut options.loader, compaction_options).map_err(|e| e.code())
+	}).map_err(error)?;
+	from_json(py, &flattened)
+}
+
+/// Dictionary representation of an RDF term, as in PyLD datasets.
+fn rdf_term<'py>(py: Python<'py>, term: &Term) -> PyResult<Bound<'py, PyDict>> {
+	let dict = PyDict::new(py);
+	match term {
+		Term::Ref(Reference::Id(iri)) => {
+			dict.set_item("type", "IRI")?;
+			dict.set_item("value", iri.as_str())?
+		},
+		Term::Ref(Reference::Blank(id)) => {
+			dict.set_item("type", "blank node")?;
+			dict.set_item("value", id.as_str())?
+		},
+		Term::Literal(Literal::Typed(value, datatype)) => {
+			dict.set_item("type", "literal")?;
+			dict.set_item("value", value)?;
+			dict.set_item("datatype", datatype.as_str())?
+		},
+		Term::Literal(Literal::LangString(value, language)) => {
+			dict.set_item("type", "literal")?;
+			dict.set_item("value", value)?;
+			dict.set_item("datatype", RDF_LANG_STRING)?;
+			dict.set_item("language", language)?
+		}
+	}
+
+	Ok(dict)
+}
+
+/// Serialize a JSON-LD document to RDF.
+///
+/// Returns an N-Quads string with the `application/n-quads` format, a dataset dictionary
+/// mapping each graph name (`@default` for the default graph) to its triples otherwise.
+#[pyfunction]
+#[pyo3(signature = (input, options = None))]
+fn to_rdf<'py>(py: Python<'py>, input: &Bound<'py, PyAny>, options: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+	let mut options = Options::new(options)?;
+	match options.format.as_deref() {
+		None | Some("application/n-quads") => (),
+		Some(format) => return Err(JsonLdError::new_err(format!("unknown output format `{}`", format)))
+	}
+
+	let (document, base) = options.input(py, input)?;
+	let quads = py.allow_threads(|| {
+		to_rdf_json(&document, base.as_ref().map(|base| base.as_iri()), &mut options.loader).map_err(|e| e.code())
+	}).map_err(error)?;
+
+	if options.format.is_some() {
+		let nquads: String = quads.iter().map(|quad| format!("{}\n", quad)).collect();
+		return Ok(PyString::new(py, &nquads).into_any())
+	}
+
+	let dataset = PyDict::new(py);
+	for quad in &quads {
+		let graph = quad.graph.as_ref().map(|graph| graph.as_str()).unwrap_or("@default");
+		let triples = match dataset.get_item(graph)? {
+			Some(triples) => triples.downcast_into::<PyList>()?,
+			None => {
+				let triples = PyList::empty(py);
+				dataset.set_item(graph, &triples)?;
+				triples
+			}
+		};
+
+		let triple = PyDict::new(py);
+		triple.set_item("subject", rdf_term(py, &Term::Ref(quad.subject.clone()))?)?;
+		triple.set_item("predicate", rdf_term(py, &Term::Ref(quad.predicate.clone()))?)?;
+		triple.set_item("object", rdf_term(py, &quad.object)?)?;
+		triples.append(triple)?
+	}
+
+	Ok(dataset.into_any())
+}
+
+/// The `json_ld` Python module.
+#[pymodule]
+pub fn json_ld(m: &Bound<PyModule>) -> PyResult<()> {
+	m.add_function(wrap_pyfunction!(expand, m)?)?;
+	m.add_function(wrap_pyfunction!(compact, m)?)?;
+	m.add_function(wrap_pyfunction!(flatten, m)?)?;
+	m.add_function(wrap_pyfunction!(to_rdf, m)?)?;
+	m.add("JsonLdError", m.py().get_type::<JsonLdError>())?;
+	Ok(())
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap};
+use sha2::{Digest, Sha256};
+use crate::{
+	Id,
+	BlankId,
+	Reference
+};
+use super::{
+	Quad,
+	Term
+};
+
+/// Blank node identifier issuer.
+#[derive(Clone)]
+struct Issuer {
+	prefix: &'static str,
+
+	/// Issued identifiers, in issuance order.
+	issued: Vec<BlankId>,
+
+	map: HashMap<BlankId, BlankId>
+}
+
+impl Issuer {
+	fn new(prefix: &'static str) -> Issuer {
+		Issuer {
+			prefix,
+			issued: Vec::new(),
+			map: HashMap::new()
+		}
+	}
+
+	fn get(&self, id: &BlankId) -> Option<&BlankId> {
+		self.map.get(id)
+	}
+
+	fn issue(&mut self, id: &BlankId) -> BlankId {
+		match self.map.get(id) {
+			Some(new_id) => new_id.clone(),
+			None => {
+				let new_id = BlankId::new(&format!("{}{}", self.prefix, self.map.len()));
+				self.issued.push(id.clone());
+				self.map.insert(id.clone(), new_id.clone());
+				new_id
+			}
+		}
+	}
+}
+
+fn sha256(data: &str) -> String {
+	format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
+/// Blank nodes of a quad, with their position.
+fn blank_nodes<T: Id>(quad: &Quad<T>) -> impl Iterator<Item = (&BlankId, &'static str)> {
+	let subject = match &quad.subject {
+		Reference::Blank(id) => Some((id, "s")),
+		_ => None
+	};
+
+	let predicate = match &quad.predicate {
+		Reference::Blank(id) => Some((id, "p")),
+		_ => None
+	};
+
+	let object = match &quad.object {
+		Term::Ref(Reference::Blank(id)) => Some((id, "o")),
+		_ => None
+	};
+
+	let graph = match &quad.graph {
+		Some(Reference::Blank(id)) => Some((id, "g")),
+		_ => None
+	};
+
+	subject.into_iter().chain(predicate).chain(object).chain(graph)
+}
+
+/// Rename the blank nodes of a quad.
+fn relabel<T: Id, F: Fn(&BlankId) -> BlankId>(quad: &Quad<T>, f: F) -> Quad<T> {
+	let reference = |r: &Reference<T>| match r {
+		Reference::Blank(id) => Reference::Blank(f(id)),
+		r => r.clone()
+	};
+
+	Quad {
+		subject: reference(&quad.subject),
+		predicate: reference(&quad.predicate),
+		object: match &quad.object {
+			Term::Ref(r) => Term::Ref(reference(r)),
+			object => object.clone()
+		},
+		graph: quad.graph.as_ref().map(reference)
+	}
+}
+
+/// All the permutations of the given items.
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+	if items.len() <= 1 {
+		return vec![items.to_vec()]
+	}
+
+	let mut result = Vec::new();
+	for i in 0..items.len() {
+		let mut rest = items.to_vec();
+		let item = rest.remove(i);
+		for mut permutation in permutations(&rest) {
+			permutation.insert(0, item.clone());
+			result.push(permutation)
+		}
+	}
+
+	result
+}
+
+/// Canonicalization state.
+struct State<'a, T: Id> {
+	blank_to_quads: HashMap<&'a BlankId, Vec<&'a Quad<T>>>,
+	canonical: Issuer
+}
+
+impl<'a, T: Id> State<'a, T> {
+	/// Hash First Degree Quads algorithm.
+	fn hash_first_degree(&self, id: &BlankId) -> String {
+		let a = BlankId::new("a");
+		let z = BlankId::new("z");
+		let mut nquads: Vec<String> = self.blank_to_quads[id].iter().map(|quad| {
+			format!("{}\n", relabel(quad, |other| if other == id { a.clone() } else { z.clone() }))
+		}).collect();
+		nquads.sort();
+		sha256(&nquads.concat())
+	}
+
+	/// Hash Related Blank Node algorithm.
+	fn hash_related(&self, related: &BlankId, quad: &Quad<T>, issuer: &Issuer, position: &str) -> String {
+		let mut input = position.to_string();
+		if position != "g" {
+			input.push_str(&format!("<{}>", quad.predicate.as_str()))
+		}
+
+		match self.canonical.get(related).or_else(|| issuer.get(related)) {
+			Some(id) => input.push_str(id.as_str()),
+			None => input.push_str(&self.hash_first_degree(related))
+		}
+
+		sha256(&input)
+	}
+
+	/// Hash N-Degree Quads algorithm.
+	fn hash_n_degree(&self, id: &BlankId, mut issuer: Issuer) -> (String, Issuer) {
+		let mut hashes: BTreeMap<String, Vec<BlankId>> = BTreeMap::new();
+		for quad in &self.blank_to_quads[id] {
+			for (related, position) in blank_nodes(quad) {
+				if related != id {
+					let hash = self.hash_related(related, quad, &issuer, position);
+					hashes.entry(hash).or_default().push(related.clone())
+				}
+			}
+		}
+
+		let mut data = String::new();
+		for (hash, related) in hashes {
+			data.push_str(&hash);
+
+			let mut chosen: Option<(String, Issuer)> = None;
+			'permutations: for permutation in permutations(&related) {
+				let mut issuer = issuer.clone();
+				let mut path = String::new();
+				let mut recursion = Vec::new();
+
+				let longer = |path: &str, chosen: &Option<(String, Issuer)>| match chosen {
+					Some((chosen, _)) => path.len() >= chosen.len() && path > chosen.as_str(),
+					None => false
+				};
+
+				for related in &permutation {
+					match self.canonical.get(related) {
+						Some(id) => path.push_str(id.as_str()),
+						None => {
+							if issuer.get(related).is_none() {
+								recursion.push(related.clone())
+							}
+
+							path.push_str(issuer.issue(related).as_str())
+						}
+					}
+
+					if longer(&path, &chosen) {
+						continue 'permutations
+					}
+				}
+
+				for related in &recursion {
+					let (hash, result) = self.hash_n_degree(related, issuer.clone());
+					path.push_str(issuer.issue(related).as_str());
+					path.push_str(&format!("<{}>", hash));
+					issuer = result;
+
+					if longer(&path, &chosen) {
+						continue 'permutations
+					}
+				}
+
+				if chosen.as_ref().map(|(chosen, _)| path < *chosen).unwrap_or(true) {
+					chosen = Some((path, issuer))
+				}
+			}
+
+			let (path, chosen_issuer) = chosen.unwrap();
+			data.push_str(&path);
+			issuer = chosen_issuer;
+		}
+
+		(sha256(&data), issuer)
+	}
+}
+
+/// Canonical labels of the blank nodes of the given dataset.
+///
+/// Implements the [RDF Dataset Canonicalization algorithm](https://www.w3.org/TR/rdf-canon/)
+/// (RDFC-1.0, formerly URDNA2015), with SHA-256, which assigns the same labels (`_:c14n0`,
+/// `_:c14n1`, etc.) to the blank nodes of isomorphic datasets.
+/// Returns the canonical label of each blank node of the dataset.
+pub fn canonical_labels<T: Id>(quads: &[Quad<T>]) -> HashMap<BlankId, BlankId> {
+	let mut blank_to_quads: HashMap<&BlankId, Vec<&Quad<T>>> = HashMap::new();
+	for quad in quads {
+		for (id, _) in blank_nodes(quad) {
+			let quads = blank_to_quads.entry(id).or_default();
+			if !quads.iter().any(|q| std::ptr::eq(*q, quad)) {
+				quads.push(quad)
+			}
+		}
+	}
+
+	let mut state = State {
+		blank_to_quads,
+		canonical: Issuer::new("c14n")
+	};
+
+	let mut hash_to_blank: BTreeMap<String, Vec<&BlankId>> = BTreeMap::new();
+	for id in state.blank_to_quads.keys() {
+		hash_to_blank.entry(state.hash_first_degree(id)).or_default().push(id)
+	}
+
+	// Blank nodes with a unique first degree hash are labeled first.
+	let mut shared = Vec::new();
+	for (_, ids) in hash_to_blank {
+		match ids.as_slice() {
+			[id] => {
+				state.canonical.issue(id);
+			},
+			_ => shared.push(ids)
+		}
+	}
+
+	for ids in shared {
+		let mut results = Vec::new();
+		for id in ids {
+			if state.canonical.get(id).is_some() {
+				continue
+			}
+
+			let mut issuer = Issuer::new("b");
+			issuer.issue(id);
+			results.push(state.hash_n_degree(id, issuer))
+		}
+
+		results.sort_by(|(a, _), (b, _)| a.cmp(b));
+		for (_, issuer) in results {
+			for id in &issuer.issued {
+				state.canonical.issue(id);
+			}
+		}
+	}
+
+	state.canonical.map
+}
+
+/// Canonicalize the given dataset.
+///
+/// Returns the quads of the dataset with their blank nodes labeled by [`canonical_labels`],
+/// without duplicates, sorted in the code point order of their N-Quads serialization.
+pub fn canonicalize<T: Id>(quads: &[Quad<T>]) -> Vec<Quad<T>> {
+	let labels = canonical_labels(quads);
+	let mut quads: Vec<(String, Quad<T>)> = quads.iter().map(|quad| {
+		let quad = relabel(quad, |id| labels[id].clone());
+		(quad.to_string(), quad)
+	}).collect();
+
+	quads.sort_by(|(a, _), (b, _)| a.cmp(b));
+	quads.dedup_by(|(a, _), (b, _)| a == b);
+	quads.into_iter().map(|(_, quad)| quad).collect()
+}
+
+/// Canonical N-Quads serialization of the given dataset.
+///
+/// Each quad of the [canonicalized](canonicalize) dataset is written on its own line.
+pub fn canonical_nquads<T: Id>(quads: &[Quad<T>]) -> String {
+	canonicalize(quads).into_iter().map(|quad| format!("{}\n", quad)).collect()
+}
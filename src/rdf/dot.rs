@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use iref::Iri;
+use crate::{
+	Id,
+	Reference,
+	Context,
+	syntax
+};
+use super::{
+	Quad,
+	Term,
+	Literal,
+	Prefixes,
+	XSD_STRING
+};
+
+/// Graphviz export options.
+#[derive(Clone, Default, Debug)]
+pub struct DotOptions {
+	/// Terms labeling the IRI they are mapped to.
+	terms: HashMap<String, String>,
+
+	/// Prefixes abbreviating the other IRIs.
+	prefixes: Prefixes
+}
+
+impl DotOptions {
+	/// Create new options, labeling nodes and edges with full IRIs.
+	pub fn new() -> DotOptions {
+		DotOptions::default()
+	}
+
+	/// Options compacting labels with the given active context.
+	///
+	/// IRIs are labeled with the term mapped to them if any (the shortest, then first in
+	/// lexicographic order), and are otherwise abbreviated with the
+	/// [prefixes](Prefixes::from_context) of the context.
+	pub fn from_context<T: Id, C: Context<T>>(context: &C) -> DotOptions {
+		let mut terms: HashMap<String, String> = HashMap::new();
+		for (term, definition) in context.definitions() {
+			if let Some(syntax::Term::Ref(Reference::Id(iri))) = &definition.value {
+				let label = terms.entry(iri.as_iri().as_str().to_string()).or_insert_with(|| term.clone());
+				if (term.len(), term) < (label.len(), label) {
+					*label = term.clone()
+				}
+			}
+		}
+
+		DotOptions {
+			terms,
+			prefixes: Prefixes::from_context(context)
+		}
+	}
+
+	/// Use the given prefixes to abbreviate IRIs.
+	pub fn with_prefixes(mut self, prefixes: Prefixes) -> DotOptions {
+		self.prefixes = prefixes;
+		self
+	}
+
+	/// Label of the given IRI.
+	pub fn label(&self, iri: Iri) -> String {
+		match self.terms.get(iri.as_str()) {
+			Some(term) => term.clone(),
+			None => self.prefixes.compact(iri).unwrap_or_else(|| iri.as_str().to_string())
+		}
+	}
+
+	fn reference_label<T: Id>(&self, r: &Reference<T>) -> String {
+		match r {
+			Reference::Id(id) => self.label(id.as_iri()),
+			Reference::Blank(id) => id.to_string()
+		}
+	}
+
+	fn literal_label<T: Id>(&self, literal: &Literal<T>) -> String {
+		match literal {
+			Literal::Typed(value, ty) if ty.as_iri().as_str() == XSD_STRING => format!("\"{}\"", value),
+			Literal::Typed(value, ty) => format!("\"{}\"^^{}", value, self.label(ty.as_iri())),
+			Literal::LangString(value, lang) => format!("\"{}\"@{}", value, lang)
+		}
+	}
+}
+
+/// Serialize quads into a [Graphviz](https://graphviz.org/) graph in the DOT language,
+/// for debugging and documenting small datasets.
+///
+/// Each IRI or blank node is a node of the graph, and each literal a separate box.
+/// Edges are labeled with their predicate.
+/// Named graphs are drawn as clusters, after the default graph, in which the same IRI is a
+/// different node.
+pub fn to_dot<T: Id>(quads: &[Quad<T>], options: &DotOptions) -> String {
+	let mut graphs: Vec<Option<&Reference<T>>> = Vec::new();
+	for quad in quads {
+		if !graphs.contains(&quad.graph.as_ref()) {
+			graphs.push(quad.graph.as_ref())
+		}
+	}
+
+	graphs.sort_by_key(|graph| graph.is_some());
+
+	let mut result = String::new();
+	let mut literals = 0;
+	result.push_str("digraph {\n");
+	for (i, graph) in graphs.into_iter().enumerate() {
+		// Nodes of named graphs are distinguished by the position of their graph.
+		let (prefix, indent) = match graph {
+			Some(graph) => {
+				writeln!(result, "\tsubgraph \"cluster_{}\" {{", i).unwrap();
+				writeln!(result, "\t\tlabel={};", quoted(&options.reference_label(graph))).unwrap();
+				(format!("{}:", i), "\t\t")
+			},
+			None => (String::new(), "\t")
+		};
+
+		let mut nodes: Vec<&Reference<T>> = Vec::new();
+		let mut edges = String::new();
+		for quad in quads.iter().filter(|quad| quad.graph.as_ref() == graph) {
+			if !nodes.contains(&&quad.subject) {
+				nodes.push(&quad.subject)
+			}
+
+			let object = match &quad.object {
+				Term::Ref(object) => {
+					if !nodes.contains(&object) {
+						nodes.push(object)
+					}
+
+					format!("{}{}", prefix, object.as_str())
+				},
+				Term::Literal(literal) => {
+					let id = format!("{}literal {}", prefix, literals);
+					literals += 1;
+					writeln!(edges, "{}{} [label={}, shape=box];", indent, quoted(&id), quoted(&options.literal_label(literal))).unwrap();
+					id
+				}
+			};
+
+			writeln!(edges, "{}{} -> {} [label={}];", indent, quoted(&format!("{}{}", prefix, quad.subject.as_str())), quoted(&object), quoted(&options.reference_label(&quad.predicate))).unwrap();
+		}
+
+		for node in nodes {
+			writeln!(result, "{}{} [label={}];", indent, quoted(&format!("{}{}", prefix, node.as_str())), quoted(&options.reference_label(node))).unwrap()
+		}
+
+		result.push_str(&edges);
+
+		if graph.is_some() {
+			result.push_str("\t}\n")
+		}
+	}
+
+	result.push_str("}\n");
+	result
+}
+
+/// DOT quoted string.
+fn quoted(value: &str) -> String {
+	let mut result = String::with_capacity(value.len() + 2);
+	result.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\r' => (),
+			c => result.push(c)
+		}
+	}
+	result.push('"');
+	result
+}
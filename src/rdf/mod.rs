@@ -0,0 +1,529 @@
+//! RDF serialization.
+//!
+//! This module implements the
+//! [Deserialize JSON-LD to RDF algorithm](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm),
+//! turning an expanded document into a list of RDF [`Quad`]s.
+//! Quads can be written in the [N-Quads](https://www.w3.org/TR/n-quads/) format with their
+//! `Display` implementation, in the [Turtle](https://www.w3.org/TR/turtle/) format with
+//! [`to_turtle`], or in the [TriG](https://www.w3.org/TR/trig/) format, which keeps named graphs,
+//! with [`to_trig`].
+//! With the `trig` feature, TriG (and Turtle) documents can be parsed back into quads with
+//! `parse_trig`.
+//! Quads can also be drawn as a [Graphviz](https://graphviz.org/) graph with [`to_dot`].
+//! Literals of [custom datatypes](crate::object::datatype) are canonicalized by
+//! [`to_rdf_with_datatypes`].
+//! With the `canon` feature, datasets can be canonicalized with the
+//! [RDF Dataset Canonicalization algorithm](https://www.w3.org/TR/rdf-canon/) (`canonicalize`).
+
+mod turtle;
+mod dot;
+#[cfg(feature="trig")]
+mod trig;
+#[cfg(feature="canon")]
+mod canon;
+
+use std::fmt;
+use iref::{Iri, IriBuf};
+use crate::{
+	Id,
+	Error,
+	BlankId,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Value,
+	Direction,
+	direction::I18N,
+	ExpandedDocument,
+	object::{
+		value::Literal as ValueLiteral,
+		datatype::Datatypes,
+		xsd::{
+			BOOLEAN as XSD_BOOLEAN,
+			INTEGER as XSD_INTEGER,
+			DECIMAL as XSD_DECIMAL,
+			DOUBLE as XSD_DOUBLE,
+			STRING as XSD_STRING
+		}
+	},
+	flattening::{
+		node_map,
+		NodeMap
+	},
+	generator::{
+		self,
+		Generator
+	}
+};
+
+pub use turtle::*;
+pub use dot::*;
+#[cfg(feature="trig")]
+pub use trig::*;
+#[cfg(feature="canon")]
+pub use canon::*;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const RDF_VALUE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#value";
+const RDF_LANGUAGE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#language";
+const RDF_DIRECTION: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#direction";
+const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+
+/// How the base direction of language strings is represented in RDF.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RdfDirection {
+	/// Encode the language and direction in the datatype IRI
+	/// (`https://www.w3.org/ns/i18n#<language>_<direction>`).
+	I18nDatatype,
+
+	/// Use a blank node with `rdf:value`, `rdf:language` and `rdf:direction` properties.
+	CompoundLiteral
+}
+
+/// RDF serialization options.
+#[derive(Clone, Copy, Default)]
+pub struct Options {
+	/// Produce generalized RDF, where predicates may be blank nodes.
+	///
+	/// When `false`, triples whose predicate is a blank node are dropped.
+	pub produce_generalized_rdf: bool,
+
+	/// Representation of the base direction of language strings.
+	///
+	/// When `None`, the base direction is dropped.
+	pub rdf_direction: Option<RdfDirection>,
+
+	/// Convert numbers without going through floating point.
+	///
+	/// By default, as specified, non-integer numbers are converted into `xsd:double` literals,
+	/// and may lose precision (`0.1` becomes `1.0E-1`).
+	/// When `true`, numbers keep their exact decimal value: integers are converted into
+	/// `xsd:integer` literals, whatever their magnitude, and other numbers into `xsd:decimal`
+	/// literals, unless the value is explicitly typed as `xsd:double`.
+	pub exact_numbers: bool
+}
+
+/// RDF literal.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Literal<T: Id = IriBuf> {
+	/// Literal with a datatype.
+	Typed(String, T),
+
+	/// Language-tagged string (of datatype `rdf:langString`).
+	LangString(String, String)
+}
+
+impl<T: Id> Literal<T> {
+	/// Lexical form of the literal.
+	pub fn as_str(&self) -> &str {
+		match self {
+			Literal::Typed(value, _) => value,
+			Literal::LangString(value, _) => value
+		}
+	}
+}
+
+impl<T: Id> fmt::Display for Literal<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_string(self.as_str(), f)?;
+		match self {
+			Literal::Typed(_, ty) if ty.as_iri().as_str() == XSD_STRING => Ok(()),
+			Literal::Typed(_, ty) => write!(f, "^^<{}>", ty.as_iri()),
+			Literal::LangString(_, lang) => write!(f, "@{}", lang)
+		}
+	}
+}
+
+/// RDF term in object position.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Term<T: Id = IriBuf> {
+	/// IRI or blank node.
+	Ref(Reference<T>),
+
+	/// Literal.
+	Literal(Literal<T>)
+}
+
+impl<T: Id> fmt::Display for Term<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Term::Ref(r) => fmt_reference(r, f),
+			Term::Literal(lit) => lit.fmt(f)
+		}
+	}
+}
+
+/// RDF quad.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Quad<T: Id = IriBuf> {
+	/// Subject.
+	pub subject: Reference<T>,
+
+	/// Predicate.
+	///
+	/// This is a blank node only when producing generalized RDF.
+	pub predicate: Reference<T>,
+
+	/// Object.
+	pub object: Term<T>,
+
+	/// Graph name, or `None` for the default graph.
+	pub graph: Option<Reference<T>>
+}
+
+impl<T: Id> Quad<T> {
+	/// Checks if the quad matches the given pattern.
+	///
+	/// A `None` component matches any term.
+	/// The `graph` component is `Some(None)` to only match the quads of the default graph.
+	pub fn matches(&self, subject: Option<&Reference<T>>, predicate: Option<&Reference<T>>, object: Option<&Term<T>>, graph: Option<Option<&Reference<T>>>) -> bool {
+		subject.is_none_or(|s| *s == self.subject)
+			&& predicate.is_none_or(|p| *p == self.predicate)
+			&& object.is_none_or(|o| *o == self.object)
+			&& graph.is_none_or(|g| g == self.graph.as_ref())
+	}
+}
+
+impl<T: Id> fmt::Display for Quad<T> {
+	/// Format the quad as a N-Quads statement.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_reference(&self.subject, f)?;
+		write!(f, " ")?;
+		fmt_reference(&self.predicate, f)?;
+		write!(f, " {}", self.object)?;
+
+		if let Some(graph) = &self.graph {
+			write!(f, " ")?;
+			fmt_reference(graph, f)?;
+		}
+
+		write!(f, " .")
+	}
+}
+
+/// Format a quoted string, escaping the characters that must be.
+///
+/// Escapes are those of the canonical N-Triples form.
+fn fmt_string(value: &str, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f, "\"")?;
+	for c in value.chars() {
+		match c {
+			'"' => write!(f, "\\\"")?,
+			'\\' => write!(f, "\\\\")?,
+			'\n' => write!(f, "\\n")?,
+			'\r' => write!(f, "\\r")?,
+			'\t' => write!(f, "\\t")?,
+			'\u{8}' => write!(f, "\\b")?,
+			'\u{c}' => write!(f, "\\f")?,
+			'\u{0}'..='\u{1f}' | '\u{7f}' => write!(f, "\\u{:04X}", c as u32)?,
+			c => write!(f, "{}", c)?
+		}
+	}
+	write!(f, "\"")
+}
+
+fn fmt_reference<T: Id>(r: &Reference<T>, f: &mut fmt::Formatter) -> fmt::Result {
+	match r {
+		Reference::Id(id) => write!(f, "<{}>", id.as_iri()),
+		Reference::Blank(id) => write!(f, "{}", id)
+	}
+}
+
+fn iri<T: Id>(iri: &'static str) -> T {
+	T::from_iri(Iri::new(iri).unwrap())
+}
+
+fn reference<T: Id>(value: &'static str) -> Reference<T> {
+	Reference::Id(iri(value))
+}
+
+/// Serialize an expanded document into RDF quads.
+///
+/// Blank node identifiers are generated with the given generator, both to label the nodes of
+/// the document and the list nodes.
+/// As specified, subjects, predicates, objects and graph names that are not well-formed (such
+/// as relative IRIs, when the document has no base IRI) are dropped with their triples, as
+/// are literals with a malformed language tag.
+/// Quads are returned graph by graph (starting with the default graph), subject by subject,
+/// in the order of their identifiers.
+pub fn to_rdf<T: Id, G: Generator>(document: &ExpandedDocument<T>, generator: G, options: Options) -> Result<Vec<Quad<T>>, Error> {
+	to_rdf_with_datatypes(document, generator, options, &Datatypes::new())
+}
+
+/// Serialize an expanded document into RDF quads, canonicalizing the literals of the given
+/// custom datatypes.
+///
+/// Strings typed with a registered datatype are written with their
+/// [canonical](crate::object::datatype::Datatype::canonicalize) lexical form when they are
+/// valid, and kept as is otherwise.
+pub fn to_rdf_with_datatypes<T: Id, G: Generator>(document: &ExpandedDocument<T>, mut generator: G, options: Options, datatypes: &Datatypes) -> Result<Vec<Quad<T>>, Error> {
+	let node_map = node_map(document, &mut generator)?;
+	let mut quads = Vec::new();
+
+	let mut graphs: Vec<_> = node_map.graphs().collect();
+	graphs.sort_by(|(a, _), (b, _)| a.map(Reference::as_str).cmp(&b.map(Reference::as_str)));
+
+	for (graph_name, graph) in graphs {
+		// Graphs whose name is not well-formed are dropped.
+		if let Some(name) = graph_name {
+			if !is_well_formed(&node_map, name) {
+				continue
+			}
+		}
+
+		let mut nodes: Vec<_> = graph.iter().collect();
+		nodes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+		for (_, node) in nodes {
+			// Nodes whose identifier is not well-formed are dropped.
+			let subject = match node.id() {
+				Some(Lenient::Ok(id)) => id,
+				_ => continue
+			};
+
+			for ty in node.types() {
+				if let Lenient::Ok(ty) = ty {
+					quads.push(Quad {
+						subject: subject.clone(),
+						predicate: reference(RDF_TYPE),
+						object: Term::Ref(ty.clone()),
+						graph: graph_name.cloned()
+					})
+				}
+			}
+
+			let mut properties: Vec<_> = node.properties.iter().collect();
+			properties.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+			for (property, values) in properties {
+				// Blank node predicates are only allowed in generalized RDF.
+				if let Reference::Blank(_) = property {
+					if !options.produce_generalized_rdf {
+						continue
+					}
+				}
+
+				for value in values {
+					let mut triples = Vec::new();
+					if let Some(object) = object_to_rdf(value, &mut generator, options, datatypes, &mut triples) {
+						triples.push((subject.clone(), property.clone(), object))
+					}
+
+					for (subject, predicate, object) in triples {
+						quads.push(Quad {
+							subject,
+							predicate,
+							object,
+							graph: graph_name.cloned()
+						})
+					}
+				}
+			}
+		}
+	}
+
+	Ok(quads)
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Find the RDF quads of the document matching the given pattern (see [`Quad::matches`]).
+	///
+	/// The document is serialized with the default options.
+	/// Blank nodes are relabeled in the process (`_:b0`, `_:b1`, etc.), so blank node
+	/// identifiers of the document cannot be used to match quads: use the identifiers
+	/// appearing in previously matched quads instead.
+	pub fn match_quads(&self, subject: Option<&Reference<T>>, predicate: Option<&Reference<T>>, object: Option<&Term<T>>, graph: Option<Option<&Reference<T>>>) -> Result<std::vec::IntoIter<Quad<T>>, Error> {
+		let mut quads = to_rdf(self, generator::Blank::new(), Options::default())?;
+		quads.retain(|quad| quad.matches(subject, predicate, object, graph));
+		Ok(quads.into_iter())
+	}
+}
+
+type Triple<T> = (Reference<T>, Reference<T>, Term<T>);
+
+/// Object to RDF Conversion algorithm.
+///
+/// Returns the term representing the object, adding the triples needed to describe it
+/// (list nodes or compound literals) to `triples`.
+fn object_to_rdf<T: Id, G: Generator>(object: &Indexed<Object<T>>, generator: &mut G, options: Options, datatypes: &Datatypes, triples: &mut Vec<Triple<T>>) -> Option<Term<T>> {
+	match object.inner() {
+		// Node references with a non well-formed identifier are dropped.
+		Object::Node(node) => match node.id() {
+			Some(Lenient::Ok(id)) => Some(Term::Ref(id.clone())),
+			_ => None
+		},
+		Object::List(items) => Some(Term::Ref(list_to_rdf(items, generator, options, datatypes, triples))),
+		Object::Value(value) => value_to_rdf(value, generator, options, datatypes, triples)
+	}
+}
+
+fn value_to_rdf<T: Id, G: Generator>(value: &Value<T>, generator: &mut G, options: Options, datatypes: &Datatypes, triples: &mut Vec<Triple<T>>) -> Option<Term<T>> {
+	match value {
+		Value::Literal(lit, types) => {
+			let datatype = types.iter().next().cloned();
+			let (value, default_datatype) = match lit {
+				ValueLiteral::Null => return None,
+				ValueLiteral::Boolean(b) => (b.to_string(), XSD_BOOLEAN),
+				ValueLiteral::Json(json) => (json.canonical_form(), RDF_JSON),
+				ValueLiteral::Number(n) => {
+					let is_double = datatype.as_ref().map(|ty| ty.as_iri().as_str() == XSD_DOUBLE).unwrap_or(false);
+					let is_decimal = datatype.as_ref().map(|ty| ty.as_iri().as_str() == XSD_DECIMAL).unwrap_or(false);
+					if !is_double && (options.exact_numbers || is_decimal) {
+						match exact_decimal(n) {
+							(value, true) if !is_decimal => (value, XSD_INTEGER),
+							(value, true) => (format!("{}.0", value), XSD_DECIMAL),
+							(value, false) => (value, XSD_DECIMAL)
+						}
+					} else {
+						let n: f64 = (*n).into();
+						if is_double || n.fract() != 0.0 || n.abs() >= 1e21 {
+							(canonical_double(n), XSD_DOUBLE)
+						} else {
+							(format!("{:.0}", n), XSD_INTEGER)
+						}
+					}
+				},
+				ValueLiteral::String(s) => {
+					let canonical = datatype.as_ref().and_then(|ty| datatypes.canonicalize(ty.as_iri(), s));
+					(canonical.unwrap_or_else(|| s.clone()), XSD_STRING)
+				}
+			};
+
+			let datatype = match lit {
+				ValueLiteral::Json(_) => iri(RDF_JSON),
+				_ => datatype.unwrap_or_else(|| iri(default_datatype))
+			};
+
+			Some(Term::Literal(Literal::Typed(value, datatype)))
+		},
+		Value::LangString(str) => {
+			if !str.language().map(is_well_formed_language).unwrap_or(true) {
+				return None
+			}
+
+			match (str.direction(), options.rdf_direction) {
+				(Some(direction), Some(RdfDirection::I18nDatatype)) => {
+					let datatype = format!("{}{}_{}", I18N, str.language().unwrap_or("").to_lowercase(), direction);
+					let datatype = T::from_iri(Iri::new(&datatype).ok()?);
+					Some(Term::Literal(Literal::Typed(str.as_str().to_string(), datatype)))
+				},
+				(Some(direction), Some(RdfDirection::CompoundLiteral)) => {
+					let node = Reference::Blank(generator.next());
+					triples.push((node.clone(), reference(RDF_VALUE), Term::Literal(Literal::Typed(str.as_str().to_string(), iri(XSD_STRING)))));
+
+					if let Some(language) = str.language() {
+						triples.push((node.clone(), reference(RDF_LANGUAGE), Term::Literal(Literal::Typed(language.to_lowercase(), iri(XSD_STRING)))));
+					}
+
+					triples.push((node.clone(), reference(RDF_DIRECTION), Term::Literal(Literal::Typed(direction_str(direction).to_string(), iri(XSD_STRING)))));
+					Some(Term::Ref(node))
+				},
+				_ => match str.language() {
+					Some(language) => Some(Term::Literal(Literal::LangString(str.as_str().to_string(), language.to_string()))),
+					None => Some(Term::Literal(Literal::Typed(str.as_str().to_string(), iri(XSD_STRING))))
+				}
+			}
+		}
+	}
+}
+
+/// Checks if the node stored under the given identifier in the node map has a well-formed
+/// identifier.
+///
+/// Nodes with a malformed identifier are stored under a generated blank node identifier.
+fn is_well_formed<T: Id>(node_map: &NodeMap<T>, id: &Reference<T>) -> bool {
+	!node_map.graphs().any(|(_, graph)| matches!(graph.get(id).and_then(|node| node.id()), Some(Lenient::Unknown(_))))
+}
+
+/// Checks if the given language tag is well-formed, according to the
+/// [BCP47](https://tools.ietf.org/html/bcp47) syntax of a primary subtag followed by subtags.
+fn is_well_formed_language(tag: &str) -> bool {
+	let mut subtags = tag.split('-');
+	let primary = subtags.next().unwrap_or("");
+	let is_subtag = |subtag: &str, alphabetic: bool| {
+		!subtag.is_empty() && subtag.len() <= 8 && subtag.chars().all(|c| if alphabetic { c.is_ascii_alphabetic() } else { c.is_ascii_alphanumeric() })
+	};
+
+	is_subtag(primary, true) && subtags.all(|subtag| is_subtag(subtag, false))
+}
+
+fn direction_str(direction: Direction) -> &'static str {
+	match direction {
+		Direction::Ltr => "ltr",
+		Direction::Rtl => "rtl"
+	}
+}
+
+/// List to RDF Conversion algorithm.
+fn list_to_rdf<T: Id, G: Generator>(items: &[Indexed<Object<T>>], generator: &mut G, options: Options, datatypes: &Datatypes, triples: &mut Vec<Triple<T>>) -> Reference<T> {
+	if items.is_empty() {
+		return reference(RDF_NIL)
+	}
+
+	let nodes: Vec<BlankId> = items.iter().map(|_| generator.next()).collect();
+
+	for (i, item) in items.iter().enumerate() {
+		let node = Reference::Blank(nodes[i].clone());
+
+		let mut embedded = Vec::new();
+		if let Some(object) = object_to_rdf(item, generator, options, datatypes, &mut embedded) {
+			triples.push((node.clone(), reference(RDF_FIRST), object));
+		}
+		triples.extend(embedded);
+
+		let rest = match nodes.get(i + 1) {
+			Some(next) => Reference::Blank(next.clone()),
+			None => reference(RDF_NIL)
+		};
+		triples.push((node, reference(RDF_REST), Term::Ref(rest)));
+	}
+
+	Reference::Blank(nodes[0].clone())
+}
+
+/// Canonical lexical form of a `xsd:double`, such as `1.1E1`.
+fn canonical_double(n: f64) -> String {
+	let s = format!("{:E}", n);
+	match s.find('E') {
+		Some(e) if !s[..e].contains('.') => format!("{}.0{}", &s[..e], &s[e..]),
+		_ => s
+	}
+}
+
+/// Exact canonical decimal representation of a number, without exponent nor trailing zeros,
+/// and whether or not it is an integer.
+fn exact_decimal(n: &json::number::Number) -> (String, bool) {
+	let (positive, mantissa, exponent) = n.as_parts();
+	let mut digits = mantissa.to_string();
+	let (integer, mut fraction) = if exponent >= 0 {
+		if mantissa != 0 {
+			digits.push_str(&"0".repeat(exponent as usize));
+		}
+
+		(digits, String::new())
+	} else {
+		let scale = (-(exponent as i32)) as usize;
+		if digits.len() <= scale {
+			let fraction = "0".repeat(scale - digits.len()) + &digits;
+			("0".to_string(), fraction)
+		} else {
+			let fraction = digits.split_off(digits.len() - scale);
+			(digits, fraction)
+		}
+	};
+
+	while fraction.ends_with('0') {
+		fraction.pop();
+	}
+
+	let sign = if positive || (mantissa == 0) { "" } else { "-" };
+	if fraction.is_empty() {
+		(format!("{}{}", sign, integer), true)
+	} else {
+		(format!("{}{}.{}", sign, integer, fraction), false)
+	}
+}
@@ -0,0 +1,639 @@
+use std::collections::HashMap;
+use std::fmt;
+use iref::{
+	Iri,
+	IriBuf,
+	IriRef
+};
+use crate::{
+	Id,
+	BlankId,
+	Reference,
+	generator::Generator
+};
+use super::{
+	Quad,
+	Term,
+	Literal,
+	RDF_TYPE,
+	RDF_FIRST,
+	RDF_REST,
+	RDF_NIL,
+	XSD_BOOLEAN,
+	XSD_INTEGER,
+	XSD_DECIMAL,
+	XSD_DOUBLE,
+	XSD_STRING
+};
+
+/// TriG syntax error.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SyntaxError {
+	/// Line of the error, starting at 1.
+	pub line: usize,
+
+	/// Error message.
+	pub message: String
+}
+
+impl fmt::Display for SyntaxError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "line {}: {}", self.line, self.message)
+	}
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// Parse a [TriG](https://www.w3.org/TR/trig/) document into RDF quads.
+///
+/// Turtle being a subset of TriG, Turtle documents are also accepted.
+/// Relative IRIs are resolved against the given base IRI, or the last `@base` directive.
+/// Blank node labels of the document are kept, and the given generator labels the anonymous
+/// blank nodes (`[]` and collection nodes): it must not generate labels used in the document.
+pub fn parse_trig<T: Id, G: Generator>(input: &str, base_iri: Option<Iri>, generator: G) -> Result<Vec<Quad<T>>, SyntaxError> {
+	let mut parser = Parser {
+		input,
+		pos: 0,
+		line: 1,
+		base_iri: base_iri.map(IriBuf::from),
+		prefixes: HashMap::new(),
+		generator,
+		quads: Vec::new()
+	};
+
+	parser.parse_document()?;
+	Ok(parser.quads)
+}
+
+struct Parser<'a, T: Id, G> {
+	input: &'a str,
+	pos: usize,
+	line: usize,
+	base_iri: Option<IriBuf>,
+	prefixes: HashMap<String, String>,
+	generator: G,
+	quads: Vec<Quad<T>>
+}
+
+impl<'a, T: Id, G: Generator> Parser<'a, T, G> {
+	fn error<R>(&self, message: &str) -> Result<R, SyntaxError> {
+		Err(SyntaxError {
+			line: self.line,
+			message: message.to_string()
+		})
+	}
+
+	fn rest(&self) -> &'a str {
+		&self.input[self.pos..]
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.rest().chars().next()
+	}
+
+	fn next_char(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.pos += c.len_utf8();
+		if c == '\n' {
+			self.line += 1
+		}
+
+		Some(c)
+	}
+
+	fn skip_ws(&mut self) {
+		while let Some(c) = self.peek() {
+			if c == '#' {
+				while let Some(c) = self.next_char() {
+					if c == '\n' {
+						break
+					}
+				}
+			} else if c.is_whitespace() {
+				self.next_char();
+			} else {
+				break
+			}
+		}
+	}
+
+	fn expect(&mut self, expected: char) -> Result<(), SyntaxError> {
+		self.skip_ws();
+		if self.peek() == Some(expected) {
+			self.next_char();
+			Ok(())
+		} else {
+			self.error(&format!("expected `{}`", expected))
+		}
+	}
+
+	/// Consume the given keyword (case-insensitively), if it is not followed by a name
+	/// character.
+	fn keyword(&mut self, keyword: &str) -> bool {
+		let rest = self.rest();
+		if rest.len() >= keyword.len() && rest.is_char_boundary(keyword.len()) && rest[..keyword.len()].eq_ignore_ascii_case(keyword) && !rest[keyword.len()..].starts_with(is_name_char) && !rest[keyword.len()..].starts_with(':') {
+			self.pos += keyword.len();
+			true
+		} else {
+			false
+		}
+	}
+
+	fn fresh(&mut self) -> Reference<T> {
+		Reference::Blank(self.generator.next())
+	}
+
+	fn emit(&mut self, subject: Reference<T>, predicate: Reference<T>, object: Term<T>, graph: &Option<Reference<T>>) {
+		self.quads.push(Quad {
+			subject,
+			predicate,
+			object,
+			graph: graph.clone()
+		})
+	}
+
+	fn parse_document(&mut self) -> Result<(), SyntaxError> {
+		loop {
+			self.skip_ws();
+			if self.peek().is_none() {
+				return Ok(())
+			}
+
+			if self.keyword("@prefix") {
+				self.parse_prefix()?;
+				self.expect('.')?
+			} else if self.keyword("@base") {
+				self.parse_base()?;
+				self.expect('.')?
+			} else if self.keyword("prefix") {
+				self.parse_prefix()?
+			} else if self.keyword("base") {
+				self.parse_base()?
+			} else if self.keyword("graph") {
+				self.skip_ws();
+				let label = self.parse_label()?;
+				self.parse_graph(Some(label))?
+			} else if self.peek() == Some('{') {
+				self.parse_graph(None)?
+			} else {
+				self.parse_block()?
+			}
+		}
+	}
+
+	fn parse_prefix(&mut self) -> Result<(), SyntaxError> {
+		self.skip_ws();
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c != ':' && !c.is_whitespace()) {
+			self.next_char();
+		}
+
+		let name = self.input[start..self.pos].to_string();
+		self.expect(':')?;
+		self.skip_ws();
+		let iri = self.parse_iri_ref()?;
+		self.prefixes.insert(name, iri.as_str().to_string());
+		Ok(())
+	}
+
+	fn parse_base(&mut self) -> Result<(), SyntaxError> {
+		self.skip_ws();
+		self.base_iri = Some(self.parse_iri_ref()?);
+		Ok(())
+	}
+
+	/// Triples, or named graph whose label is not introduced by `GRAPH`, in the default graph.
+	fn parse_block(&mut self) -> Result<(), SyntaxError> {
+		let graph = None;
+		let subject = match self.peek() {
+			Some('[') => {
+				self.next_char();
+				self.skip_ws();
+				if self.peek() == Some(']') {
+					self.next_char();
+					self.fresh()
+				} else {
+					let subject = self.fresh();
+					self.parse_predicate_object_list(&subject, &graph)?;
+					self.expect(']')?;
+					self.skip_ws();
+					if self.peek() == Some('.') {
+						self.next_char();
+						return Ok(())
+					}
+
+					subject
+				}
+			},
+			Some('(') => self.parse_collection(&graph)?,
+			_ => self.parse_label()?
+		};
+
+		self.skip_ws();
+		if self.peek() == Some('{') {
+			return self.parse_graph(Some(subject))
+		}
+
+		self.parse_predicate_object_list(&subject, &graph)?;
+		self.expect('.')
+	}
+
+	fn parse_graph(&mut self, graph: Option<Reference<T>>) -> Result<(), SyntaxError> {
+		self.expect('{')?;
+		loop {
+			self.skip_ws();
+			if self.peek() == Some('}') {
+				self.next_char();
+				return Ok(())
+			}
+
+			match self.peek() {
+				Some('[') => {
+					self.next_char();
+					let subject = self.fresh();
+					self.skip_ws();
+					if self.peek() == Some(']') {
+						self.next_char();
+						self.parse_predicate_object_list(&subject, &graph)?
+					} else {
+						self.parse_predicate_object_list(&subject, &graph)?;
+						self.expect(']')?;
+						self.skip_ws();
+						if !matches!(self.peek(), Some('.') | Some('}')) {
+							self.parse_predicate_object_list(&subject, &graph)?
+						}
+					}
+				},
+				Some('(') => {
+					let subject = self.parse_collection(&graph)?;
+					self.parse_predicate_object_list(&subject, &graph)?
+				},
+				_ => {
+					let subject = self.parse_label()?;
+					self.parse_predicate_object_list(&subject, &graph)?
+				}
+			}
+
+			self.skip_ws();
+			match self.peek() {
+				Some('.') => {
+					self.next_char();
+				},
+				Some('}') => (),
+				_ => return self.error("expected `.` or `}`")
+			}
+		}
+	}
+
+	/// IRI or blank node label.
+	fn parse_label(&mut self) -> Result<Reference<T>, SyntaxError> {
+		if self.rest().starts_with("_:") {
+			self.parse_blank_label()
+		} else {
+			Ok(Reference::Id(T::from_iri(self.parse_iri()?.as_iri())))
+		}
+	}
+
+	fn parse_blank_label(&mut self) -> Result<Reference<T>, SyntaxError> {
+		self.pos += 2;
+		let name = self.parse_name(true);
+		if name.is_empty() {
+			self.error("invalid blank node label")
+		} else {
+			Ok(Reference::Blank(BlankId::new(&name)))
+		}
+	}
+
+	/// Name characters, not ending with a dot.
+	fn parse_name(&mut self, dots: bool) -> String {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| is_name_char(c) || (dots && c == '.')) {
+			self.next_char();
+		}
+
+		while self.pos > start && self.input[start..self.pos].ends_with('.') {
+			self.pos -= 1
+		}
+
+		self.input[start..self.pos].to_string()
+	}
+
+	/// IRI reference (between angle brackets) or prefixed name.
+	fn parse_iri(&mut self) -> Result<IriBuf, SyntaxError> {
+		if self.peek() == Some('<') {
+			self.parse_iri_ref()
+		} else {
+			let prefix = self.parse_name(true);
+			if self.peek() != Some(':') {
+				return self.error("expected an IRI")
+			}
+
+			self.next_char();
+			let namespace = match self.prefixes.get(&prefix) {
+				Some(namespace) => namespace.clone(),
+				None => return self.error(&format!("undefined prefix `{}`", prefix))
+			};
+
+			let local = self.parse_local_name()?;
+			match IriBuf::new(&format!("{}{}", namespace, local)) {
+				Ok(iri) => Ok(iri),
+				Err(_) => self.error("invalid IRI")
+			}
+		}
+	}
+
+	fn parse_local_name(&mut self) -> Result<String, SyntaxError> {
+		let mut local = String::new();
+		loop {
+			match self.peek() {
+				Some('\\') => {
+					self.next_char();
+					match self.next_char() {
+						Some(c) if "_~.-!$&'()*+,;=/?#@%".contains(c) => local.push(c),
+						_ => return self.error("invalid escape sequence in local name")
+					}
+				},
+				Some('%') => {
+					let hex = self.rest().get(1..3).unwrap_or("");
+					if hex.len() == 2 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+						local.push('%');
+						local.push_str(hex);
+						self.pos += 3
+					} else {
+						return self.error("invalid percent encoding in local name")
+					}
+				},
+				Some(c) if is_name_char(c) || c == ':' => {
+					self.next_char();
+					local.push(c)
+				},
+				Some('.') if self.rest()[1..].starts_with(|c: char| is_name_char(c) || c == ':' || c == '%' || c == '\\') => {
+					self.next_char();
+					local.push('.')
+				},
+				_ => return Ok(local)
+			}
+		}
+	}
+
+	fn parse_iri_ref(&mut self) -> Result<IriBuf, SyntaxError> {
+		if self.peek() != Some('<') {
+			return self.error("expected `<`")
+		}
+
+		self.next_char();
+		let mut iri = String::new();
+		loop {
+			match self.next_char() {
+				Some('>') => break,
+				Some('\\') => iri.push(self.parse_unicode_escape()?),
+				Some(c) if c.is_whitespace() || c == '<' || c == '"' => return self.error("invalid character in IRI"),
+				Some(c) => iri.push(c),
+				None => return self.error("unterminated IRI")
+			}
+		}
+
+		match IriRef::new(&iri) {
+			Ok(iri_ref) => match IriBuf::new(&iri) {
+				Ok(iri) => Ok(iri),
+				Err(_) => match &self.base_iri {
+					Some(base_iri) => Ok(iri_ref.resolved(base_iri.as_iri())),
+					None => self.error(&format!("relative IRI `{}` without base IRI", iri))
+				}
+			},
+			Err(_) => self.error(&format!("invalid IRI `{}`", iri))
+		}
+	}
+
+	/// Unicode escape sequence, after the backslash.
+	fn parse_unicode_escape(&mut self) -> Result<char, SyntaxError> {
+		let len = match self.next_char() {
+			Some('u') => 4,
+			Some('U') => 8,
+			_ => return self.error("invalid escape sequence")
+		};
+
+		let hex = self.rest().get(..len).unwrap_or("");
+		match u32::from_str_radix(hex, 16).ok().and_then(std::char::from_u32) {
+			Some(c) if hex.len() == len => {
+				self.pos += len;
+				Ok(c)
+			},
+			_ => self.error("invalid unicode escape sequence")
+		}
+	}
+
+	fn parse_predicate_object_list(&mut self, subject: &Reference<T>, graph: &Option<Reference<T>>) -> Result<(), SyntaxError> {
+		loop {
+			self.skip_ws();
+			let predicate = if self.peek() == Some('a') && !self.rest()[1..].starts_with(|c: char| is_name_char(c) || c == ':' || c == '.') {
+				self.next_char();
+				Reference::Id(T::from_iri(Iri::new(RDF_TYPE).unwrap()))
+			} else {
+				Reference::Id(T::from_iri(self.parse_iri()?.as_iri()))
+			};
+
+			loop {
+				self.skip_ws();
+				let object = self.parse_object(graph)?;
+				self.emit(subject.clone(), predicate.clone(), object, graph);
+				self.skip_ws();
+				if self.peek() == Some(',') {
+					self.next_char();
+				} else {
+					break
+				}
+			}
+
+			if self.peek() != Some(';') {
+				return Ok(())
+			}
+
+			while self.peek() == Some(';') {
+				self.next_char();
+				self.skip_ws()
+			}
+
+			if matches!(self.peek(), Some('.') | Some(']') | Some('}') | None) {
+				return Ok(())
+			}
+		}
+	}
+
+	fn parse_object(&mut self, graph: &Option<Reference<T>>) -> Result<Term<T>, SyntaxError> {
+		match self.peek() {
+			Some('[') => {
+				self.next_char();
+				let node = self.fresh();
+				self.skip_ws();
+				if self.peek() != Some(']') {
+					self.parse_predicate_object_list(&node, graph)?
+				}
+
+				self.expect(']')?;
+				Ok(Term::Ref(node))
+			},
+			Some('(') => Ok(Term::Ref(self.parse_collection(graph)?)),
+			Some('"') | Some('\'') => self.parse_literal(),
+			Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => self.parse_number(),
+			_ if self.keyword("true") => Ok(typed("true".to_string(), XSD_BOOLEAN)),
+			_ if self.keyword("false") => Ok(typed("false".to_string(), XSD_BOOLEAN)),
+			_ => Ok(Term::Ref(self.parse_label()?))
+		}
+	}
+
+	fn parse_collection(&mut self, graph: &Option<Reference<T>>) -> Result<Reference<T>, SyntaxError> {
+		self.expect('(')?;
+		let mut items = Vec::new();
+		loop {
+			self.skip_ws();
+			if self.peek() == Some(')') {
+				self.next_char();
+				break
+			}
+
+			if self.peek().is_none() {
+				return self.error("unterminated collection")
+			}
+
+			items.push(self.parse_object(graph)?)
+		}
+
+		let first = Reference::Id(T::from_iri(Iri::new(RDF_FIRST).unwrap()));
+		let rest = Reference::Id(T::from_iri(Iri::new(RDF_REST).unwrap()));
+		let nil = Reference::Id(T::from_iri(Iri::new(RDF_NIL).unwrap()));
+		let nodes: Vec<_> = items.iter().map(|_| self.fresh()).collect();
+		for (i, item) in items.into_iter().enumerate() {
+			let next = nodes.get(i + 1).unwrap_or(&nil).clone();
+			self.emit(nodes[i].clone(), first.clone(), item, graph);
+			self.emit(nodes[i].clone(), rest.clone(), Term::Ref(next), graph)
+		}
+
+		Ok(nodes.into_iter().next().unwrap_or(nil))
+	}
+
+	fn parse_literal(&mut self) -> Result<Term<T>, SyntaxError> {
+		let quote = self.next_char().unwrap();
+		let long = self.rest().starts_with(&format!("{}{}", quote, quote));
+		if long {
+			self.pos += 2
+		}
+
+		let mut value = String::new();
+		loop {
+			match self.next_char() {
+				Some(c) if c == quote => {
+					if !long {
+						break
+					}
+
+					if self.rest().starts_with(&format!("{}{}", quote, quote)) && !self.rest().starts_with(&format!("{}{}{}", quote, quote, quote)) {
+						self.pos += 2;
+						break
+					}
+
+					value.push(c)
+				},
+				Some('\\') => match self.peek() {
+					Some('u') | Some('U') => value.push(self.parse_unicode_escape()?),
+					Some(c) => {
+						self.next_char();
+						value.push(match c {
+							't' => '\t',
+							'b' => '\u{8}',
+							'n' => '\n',
+							'r' => '\r',
+							'f' => '\u{c}',
+							'"' | '\'' | '\\' => c,
+							_ => return self.error("invalid escape sequence")
+						})
+					},
+					None => return self.error("unterminated string")
+				},
+				Some('\n') | Some('\r') if !long => return self.error("line break in string"),
+				Some(c) => value.push(c),
+				None => return self.error("unterminated string")
+			}
+		}
+
+		if self.peek() == Some('@') {
+			self.next_char();
+			let start = self.pos;
+			while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == '-') {
+				self.next_char();
+			}
+
+			let lang = &self.input[start..self.pos];
+			if lang.is_empty() || !lang.starts_with(|c: char| c.is_ascii_alphabetic()) {
+				return self.error("invalid language tag")
+			}
+
+			Ok(Term::Literal(Literal::LangString(value, lang.to_string())))
+		} else if self.rest().starts_with("^^") {
+			self.pos += 2;
+			let ty = self.parse_iri()?;
+			Ok(Term::Literal(Literal::Typed(value, T::from_iri(ty.as_iri()))))
+		} else {
+			Ok(typed(value, XSD_STRING))
+		}
+	}
+
+	fn parse_number(&mut self) -> Result<Term<T>, SyntaxError> {
+		let start = self.pos;
+		if matches!(self.peek(), Some('+') | Some('-')) {
+			self.next_char();
+		}
+
+		let integer = self.skip_digits();
+		let mut fraction = 0;
+		if self.peek() == Some('.') && self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) {
+			self.next_char();
+			fraction = self.skip_digits()
+		}
+
+		if integer == 0 && fraction == 0 {
+			return self.error("invalid number")
+		}
+
+		let mut exponent = false;
+		if matches!(self.peek(), Some('e') | Some('E')) {
+			self.next_char();
+			if matches!(self.peek(), Some('+') | Some('-')) {
+				self.next_char();
+			}
+
+			if self.skip_digits() == 0 {
+				return self.error("invalid number exponent")
+			}
+
+			exponent = true
+		}
+
+		let lexical = self.input[start..self.pos].to_string();
+		Ok(if exponent {
+			typed(lexical, XSD_DOUBLE)
+		} else if fraction > 0 {
+			typed(lexical, XSD_DECIMAL)
+		} else {
+			typed(lexical, XSD_INTEGER)
+		})
+	}
+
+	fn skip_digits(&mut self) -> usize {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+			self.next_char();
+		}
+
+		self.pos - start
+	}
+}
+
+fn typed<T: Id>(value: String, datatype: &str) -> Term<T> {
+	Term::Literal(Literal::Typed(value, T::from_iri(Iri::new(datatype).unwrap())))
+}
+
+/// Characters of prefix names, local names and blank node labels, except dots.
+fn is_name_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_' || c == '-'
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Write};
+use iref::Iri;
+use crate::{
+	Id,
+	Reference,
+	Context,
+	syntax
+};
+use super::{
+	Quad,
+	Term,
+	Literal,
+	RDF_TYPE,
+	XSD_BOOLEAN,
+	XSD_INTEGER,
+	XSD_STRING,
+	fmt_string
+};
+
+/// Turtle prefixes, used to abbreviate IRIs.
+#[derive(Clone, Default, Debug)]
+pub struct Prefixes {
+	/// Prefix names and namespaces.
+	prefixes: Vec<(String, String)>
+}
+
+impl Prefixes {
+	/// Create a new empty set of prefixes.
+	pub fn new() -> Prefixes {
+		Prefixes::default()
+	}
+
+	/// Prefixes of the given active context.
+	///
+	/// These are its terms usable as prefixes (defined by an IRI ending with a `/`, `#`, or
+	/// other generic delimiter), and the vocabulary mapping, if any, as the empty prefix.
+	/// Terms that are not valid Turtle prefix names are ignored.
+	pub fn from_context<T: Id, C: Context<T>>(context: &C) -> Prefixes {
+		let mut prefixes = Prefixes::new();
+		if let Some(syntax::Term::Ref(Reference::Id(vocab))) = context.vocabulary() {
+			prefixes.insert("", vocab.as_iri())
+		}
+
+		let mut definitions: Vec<_> = context.definitions().collect();
+		definitions.sort_by_key(|(term, _)| *term);
+		for (term, definition) in definitions {
+			if let (true, Some(syntax::Term::Ref(Reference::Id(iri)))) = (definition.prefix, &definition.value) {
+				if is_prefix_name(term) {
+					prefixes.insert(term, iri.as_iri())
+				}
+			}
+		}
+
+		prefixes
+	}
+
+	/// Add a prefix, replacing any previous prefix with the same name.
+	///
+	/// The prefix name must be a valid Turtle prefix name, such as `schema` or the empty name.
+	pub fn insert(&mut self, name: &str, namespace: Iri) {
+		self.prefixes.retain(|(n, _)| n != name);
+		self.prefixes.push((name.to_string(), namespace.as_str().to_string()))
+	}
+
+	/// Add a prefix.
+	pub fn with(mut self, name: &str, namespace: Iri) -> Prefixes {
+		self.insert(name, namespace);
+		self
+	}
+
+	/// Number of prefixes.
+	pub fn len(&self) -> usize {
+		self.prefixes.len()
+	}
+
+	/// Checks if there are no prefixes.
+	pub fn is_empty(&self) -> bool {
+		self.prefixes.is_empty()
+	}
+
+	/// Abbreviate the given IRI into a prefixed name, using the longest matching namespace.
+	pub fn compact(&self, iri: Iri) -> Option<String> {
+		self.compact_with(iri).map(|(_, name)| name)
+	}
+
+	/// Abbreviate the given IRI, also returning the index of the prefix used.
+	fn compact_with(&self, iri: Iri) -> Option<(usize, String)> {
+		self.prefixes.iter().enumerate()
+			.filter(|(_, (_, namespace))| iri.as_str().starts_with(namespace.as_str()) && is_local_name(&iri.as_str()[namespace.len()..]))
+			.max_by(|(_, (a_name, a)), (_, (b_name, b))| a.len().cmp(&b.len()).then(b_name.cmp(a_name)))
+			.map(|(i, (name, namespace))| (i, format!("{}:{}", name, &iri.as_str()[namespace.len()..])))
+	}
+}
+
+/// Turtle prefix names, restricted to ASCII.
+fn is_prefix_name(name: &str) -> bool {
+	let mut chars = name.chars();
+	match chars.next() {
+		None => true,
+		Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+		_ => false
+	}
+}
+
+/// Turtle local names, restricted to ASCII characters that need no escaping.
+fn is_local_name(name: &str) -> bool {
+	!name.starts_with('-') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Serialize the triples of the default graph in the [Turtle](https://www.w3.org/TR/turtle/)
+/// format, for human inspection.
+///
+/// IRIs are abbreviated with the given prefixes, and `@prefix` directives are written for the
+/// prefixes used.
+/// Triples are grouped by subject, then by predicate, in the order in which they first appear.
+/// Turtle cannot represent named graphs: their quads are ignored (see [`to_trig`]).
+pub fn to_turtle<T: Id>(quads: &[Quad<T>], prefixes: &Prefixes) -> String {
+	write(quads, prefixes, false)
+}
+
+/// Serialize quads in the [TriG](https://www.w3.org/TR/trig/) format.
+///
+/// This is the same as [`to_turtle`], with the named graphs written as graph blocks after the
+/// triples of the default graph.
+pub fn to_trig<T: Id>(quads: &[Quad<T>], prefixes: &Prefixes) -> String {
+	write(quads, prefixes, true)
+}
+
+fn write<T: Id>(quads: &[Quad<T>], prefixes: &Prefixes, named_graphs: bool) -> String {
+	let mut writer = Writer {
+		prefixes,
+		used: BTreeSet::new(),
+		body: String::new()
+	};
+
+	let mut graphs: Vec<Option<&Reference<T>>> = Vec::new();
+	for quad in quads {
+		if (named_graphs || quad.graph.is_none()) && !graphs.contains(&quad.graph.as_ref()) {
+			graphs.push(quad.graph.as_ref())
+		}
+	}
+
+	graphs.sort_by_key(|graph| graph.is_some());
+	for graph in graphs {
+		let quads: Vec<_> = quads.iter().filter(|quad| quad.graph.as_ref() == graph).collect();
+		writer.write_graph(graph, &quads).unwrap()
+	}
+
+	let mut result = String::new();
+	let mut prefixes: Vec<_> = writer.used.iter().map(|i| &writer.prefixes.prefixes[*i]).collect();
+	prefixes.sort();
+	for (name, namespace) in &prefixes {
+		writeln!(result, "@prefix {}: <{}> .", name, namespace).unwrap()
+	}
+
+	if !prefixes.is_empty() && !writer.body.is_empty() {
+		result.push('\n')
+	}
+
+	result.push_str(&writer.body);
+	result
+}
+
+struct Writer<'a> {
+	prefixes: &'a Prefixes,
+	used: BTreeSet<usize>,
+	body: String
+}
+
+impl<'a> Writer<'a> {
+	fn write_graph<T: Id>(&mut self, graph: Option<&Reference<T>>, quads: &[&Quad<T>]) -> fmt::Result {
+		let indent = match graph {
+			Some(graph) => {
+				if !self.body.is_empty() {
+					self.body.push('\n')
+				}
+
+				let graph = self.reference(graph);
+				writeln!(self.body, "{} {{", graph)?;
+				"\t"
+			},
+			None => ""
+		};
+
+		let mut subjects: Vec<&Reference<T>> = Vec::new();
+		for quad in quads {
+			if !subjects.contains(&&quad.subject) {
+				subjects.push(&quad.subject)
+			}
+		}
+
+		for (i, subject) in subjects.into_iter().enumerate() {
+			if i > 0 {
+				self.body.push('\n')
+			}
+
+			let mut predicates: Vec<&Reference<T>> = Vec::new();
+			for quad in quads.iter().filter(|quad| quad.subject == *subject) {
+				if !predicates.contains(&&quad.predicate) {
+					predicates.push(&quad.predicate)
+				}
+			}
+
+			let subject_str = self.reference(subject);
+			write!(self.body, "{}{}", indent, subject_str)?;
+			for (j, predicate) in predicates.iter().enumerate() {
+				if j > 0 {
+					write!(self.body, " ;\n{}\t", indent)?
+				} else {
+					self.body.push(' ')
+				}
+
+				let predicate_str = match predicate {
+					Reference::Id(id) if id.as_iri().as_str() == RDF_TYPE => "a".to_string(),
+					_ => self.reference(predicate)
+				};
+				write!(self.body, "{} ", predicate_str)?;
+
+				let objects: Vec<_> = quads.iter().filter(|quad| quad.subject == *subject && quad.predicate == **predicate).map(|quad| &quad.object).collect();
+				for (k, object) in objects.into_iter().enumerate() {
+					if k > 0 {
+						self.body.push_str(", ")
+					}
+
+					let object = self.term(object);
+					self.body.push_str(&object)
+				}
+			}
+
+			writeln!(self.body, " .")?
+		}
+
+		if graph.is_some() {
+			writeln!(self.body, "}}")?
+		}
+
+		Ok(())
+	}
+
+	fn iri(&mut self, iri: Iri) -> String {
+		match self.prefixes.compact_with(iri) {
+			Some((i, name)) => {
+				self.used.insert(i);
+				name
+			},
+			None => format!("<{}>", iri)
+		}
+	}
+
+	fn reference<T: Id>(&mut self, r: &Reference<T>) -> String {
+		match r {
+			Reference::Id(id) => self.iri(id.as_iri()),
+			Reference::Blank(id) => id.to_string()
+		}
+	}
+
+	fn term<T: Id>(&mut self, term: &Term<T>) -> String {
+		match term {
+			Term::Ref(r) => self.reference(r),
+			Term::Literal(Literal::Typed(value, ty)) => match ty.as_iri().as_str() {
+				XSD_STRING => QuotedString(value).to_string(),
+				XSD_BOOLEAN if value == "true" || value == "false" => value.clone(),
+				XSD_INTEGER if is_integer(value) => value.clone(),
+				_ => format!("{}^^{}", QuotedString(value), self.iri(ty.as_iri()))
+			},
+			Term::Literal(Literal::LangString(value, lang)) => format!("{}@{}", QuotedString(value), lang)
+		}
+	}
+}
+
+fn is_integer(value: &str) -> bool {
+	let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+	!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+struct QuotedString<'a>(&'a str);
+
+impl<'a> fmt::Display for QuotedString<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_string(self.0, f)
+	}
+}
@@ -0,0 +1,296 @@
+//! Record-and-replay loading.
+//!
+//! A [`Recorder`] stores every document fetched by an inner loader in a directory,
+//! along with a `manifest.json` file mapping each URL to its file and the hash of its
+//! content, as integrity metadata (see [`Hash`]).
+//! Documents are stored as served when their raw content is known, pretty-printed otherwise.
+//! A [`Replayer`] then serves the documents of a recording without any network access,
+//! failing on any document not present in the recording or whose content does not match
+//! its recorded hash.
+//! This allows reproducible processing, for instance in hermetic CI builds:
+//! ```ignore
+//! // Once, with network access.
+//! let mut loader = reqwest::Loader::new().with(RecorderLayer::new("tests/contexts"));
+//!
+//! // Then, in tests.
+//! let mut loader = Replayer::open("tests/contexts")?;
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use futures::future::{FutureExt, BoxFuture};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Error,
+	ErrorCode,
+	RemoteDocument,
+	Loader,
+	hash::{
+		Algorithm,
+		Hash
+	},
+	layer::LoaderLayer,
+	usage::TermUse
+};
+
+/// Name of the manifest file of a recording.
+pub const MANIFEST: &str = "manifest.json";
+
+/// Recorded document.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Entry {
+	/// Name of the file storing the document, relative to the recording directory.
+	pub file: String,
+
+	/// Hash of the file content.
+	pub hash: Hash,
+
+	/// Base URL of the document, which may differ from the requested URL.
+	pub base_url: IriBuf
+}
+
+/// Recording manifest.
+#[derive(Clone, Default, Debug)]
+pub struct Manifest {
+	entries: HashMap<IriBuf, Entry>
+}
+
+impl Manifest {
+	/// Create an empty manifest.
+	pub fn new() -> Manifest {
+		Manifest::default()
+	}
+
+	/// Read the manifest of the given recording directory.
+	///
+	/// Returns an empty manifest if the directory has no manifest.
+	pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Manifest> {
+		let path = dir.as_ref().join(MANIFEST);
+		if !path.exists() {
+			return Ok(Manifest::new())
+		}
+
+		let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid recording manifest");
+		let content = fs::read_to_string(path)?;
+		let json = json::parse(&content).map_err(|_| invalid())?;
+
+		let mut entries = HashMap::new();
+		for (url, entry) in json.entries() {
+			let url = IriBuf::new(url).map_err(|_| invalid())?;
+			let file = entry["file"].as_str().ok_or_else(invalid)?;
+			let hash = entry["integrity"].as_str().ok_or_else(invalid)?.parse().map_err(|_| invalid())?;
+			let base_url = match entry["base_url"].as_str() {
+				Some(base_url) => IriBuf::new(base_url).map_err(|_| invalid())?,
+				None => url.clone()
+			};
+
+			entries.insert(url, Entry {
+				file: file.to_string(),
+				hash,
+				base_url
+			});
+		}
+
+		Ok(Manifest {
+			entries
+		})
+	}
+
+	/// Write the manifest in the given recording directory.
+	pub fn save<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+		let mut urls: Vec<_> = self.entries.keys().collect();
+		urls.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+		let mut json = json::object::Object::new();
+		for url in urls {
+			let entry = &self.entries[url];
+			let mut obj = json::object::Object::new();
+			obj.insert("file", entry.file.as_str().into());
+			obj.insert("integrity", entry.hash.to_string().into());
+			obj.insert("base_url", entry.base_url.as_str().into());
+			json.insert(url.as_str(), JsonValue::Object(obj));
+		}
+
+		fs::write(dir.as_ref().join(MANIFEST), JsonValue::Object(json).pretty(2))
+	}
+
+	/// Get the entry of the given URL.
+	pub fn get(&self, url: Iri) -> Option<&Entry> {
+		self.entries.get(&IriBuf::from(url))
+	}
+
+	/// Iterate through the recorded URLs and their entries.
+	pub fn iter(&self) -> impl Iterator<Item = (&IriBuf, &Entry)> {
+		self.entries.iter()
+	}
+
+	/// Number of recorded documents.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Checks if the recording is empty.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+/// Layer recording the fetched documents into a directory.
+#[derive(Clone)]
+pub struct RecorderLayer {
+	dir: PathBuf
+}
+
+impl RecorderLayer {
+	/// Record the documents into the given directory.
+	///
+	/// The directory is created if needed, and existing recordings are extended.
+	pub fn new<P: AsRef<Path>>(dir: P) -> RecorderLayer {
+		RecorderLayer {
+			dir: dir.as_ref().into()
+		}
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> LoaderLayer<L> for RecorderLayer {
+	type Loader = Recorder<L>;
+
+	fn layer(self, inner: L) -> Recorder<L> {
+		Recorder {
+			inner,
+			dir: self.dir,
+			manifest: None
+		}
+	}
+}
+
+/// Loader recording the documents fetched by its inner loader.
+///
+/// Raises a `LoadingDocumentFailed` error if a document cannot be recorded.
+pub struct Recorder<L> {
+	inner: L,
+	dir: PathBuf,
+	manifest: Option<Manifest>
+}
+
+impl<L> Recorder<L> {
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Recording directory.
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	fn record(&mut self, url: Iri, doc: &RemoteDocument) -> io::Result<()> {
+		let manifest = match &mut self.manifest {
+			Some(manifest) => manifest,
+			None => {
+				fs::create_dir_all(&self.dir)?;
+				self.manifest.get_or_insert(Manifest::open(&self.dir)?)
+			}
+		};
+
+		let content = match doc.content() {
+			Some(content) => content.to_string(),
+			None => doc.pretty(2)
+		};
+		let file = format!("{}.jsonld", Hash::of(Algorithm::Sha256, url.as_str().as_bytes()).to_hex());
+		fs::write(self.dir.join(&file), &content)?;
+
+		manifest.entries.insert(url.into(), Entry {
+			file,
+			hash: Hash::of(Algorithm::Sha256, content.as_bytes()),
+			base_url: doc.url().into()
+		});
+		manifest.save(&self.dir)
+	}
+}
+
+impl<L: Send + Loader<Document = JsonValue>> Loader for Recorder<L> {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url: IriBuf = url.into();
+		async move {
+			let doc = self.inner.load(url.as_iri()).await?;
+			match self.record(url.as_iri(), &doc) {
+				Ok(()) => {
+					debug!("recorded document `{}' in `{}'", url, self.dir.display());
+					Ok(doc)
+				},
+				Err(e) => {
+					warn!("unable to record document `{}': {}", url, e);
+					Err(ErrorCode::LoadingDocumentFailed.into())
+				}
+			}
+		}.boxed()
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.inner.term_used(term, usage)
+	}
+}
+
+/// Loader serving the documents of a recording.
+///
+/// Raises a `LoadingDocumentFailed` error for any document that is not in the recording,
+/// or whose content does not match the recorded hash.
+pub struct Replayer {
+	dir: PathBuf,
+	manifest: Manifest
+}
+
+impl Replayer {
+	/// Open the recording of the given directory.
+	pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Replayer> {
+		Ok(Replayer {
+			dir: dir.as_ref().into(),
+			manifest: Manifest::open(dir)?
+		})
+	}
+
+	/// Recording manifest.
+	pub fn manifest(&self) -> &Manifest {
+		&self.manifest
+	}
+
+	fn replay(&self, url: Iri) -> Result<RemoteDocument, String> {
+		let entry = self.manifest.get(url).ok_or_else(|| "not in the recording".to_string())?;
+		let content = fs::read_to_string(self.dir.join(&entry.file)).map_err(|e| e.to_string())?;
+
+		if Hash::of(entry.hash.algorithm(), content.as_bytes()) != entry.hash {
+			return Err("content does not match the recorded hash".to_string())
+		}
+
+		let doc = json::parse(&content).map_err(|e| e.to_string())?;
+		Ok(RemoteDocument::new(doc, entry.base_url.as_iri()).with_content(content))
+	}
+}
+
+impl Loader for Replayer {
+	type Document = JsonValue;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let doc = match self.replay(url) {
+			Ok(doc) => Some(doc),
+			Err(e) => {
+				warn!("unable to replay document `{}': {}", url, e);
+				None
+			}
+		};
+
+		async move {
+			doc.ok_or_else(|| ErrorCode::LoadingDocumentFailed.into())
+		}.boxed()
+	}
+}
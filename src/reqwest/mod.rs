@@ -1,6 +1,23 @@
 //! Simple document and context loader based on [`reqwest`](https://crates.io/crates/reqwest)
+//!
+//! The [`Loader`] reuses the same HTTP client (and its connection pool) for every request,
+//! and is a [`SharedLoader`](crate::SharedLoader): it can be shared by concurrent tasks behind
+//! an `Arc`, sharing its cache.
+//!
+//! Documents behind authenticated endpoints can be loaded by setting a
+//! [request hook](Loader::with_request_hook), called on every outgoing request before it is
+//! sent, to add bearer tokens, HTTP signatures or custom headers:
+//!
+//! ```ignore
+//! let loader = Loader::new().with_request_hook(|request: &mut reqwest::Request| {
+//!     request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+//!     Ok(())
+//! });
+//! ```
 
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
 use futures::future::{FutureExt, BoxFuture};
 use iref::{Iri, IriBuf};
 use json::JsonValue;
@@ -8,65 +25,236 @@ use crate::{
 	Error,
 	ErrorCode,
 	RemoteDocument,
+	SharedLoader,
+	media_type_essence,
 	context::{
 		self,
 		RemoteContext
-	}
+	},
+	util::parse_json
 };
 
-pub fn is_json_media_type(ty: &str) -> bool {
-	ty == "application/json" || ty == "application/ld+json"
+pub use crate::is_json_media_type;
+
+/// Content types accepted by the loader.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ContentTypes {
+	/// Only accept JSON media types (see [`is_json_media_type`]), as required by the
+	/// JSON-LD specification.
+	///
+	/// This is the default.
+	#[default]
+	Strict,
+
+	/// Also accept documents served as `text/plain`, `application/octet-stream` or without
+	/// content type, if they parse as JSON.
+	///
+	/// Many servers mislabel JSON-LD documents.
+	/// A warning is logged for every such document.
+	Lenient
+}
+
+impl ContentTypes {
+	/// Checks if a document served with the given content type (if any) is accepted.
+	///
+	/// Returns `Some(true)` for JSON media types, `Some(false)` for mislabeled documents
+	/// accepted by the lenient mode, and `None` for rejected documents.
+	pub fn accept(&self, ty: Option<&str>) -> Option<bool> {
+		match ty {
+			Some(ty) if is_json_media_type(ty) => Some(true),
+			_ if *self == ContentTypes::Strict => None,
+			None => Some(false),
+			Some(ty) => match media_type_essence(ty).as_str() {
+				"text/plain" | "application/octet-stream" => Some(false),
+				_ => None
+			}
+		}
+	}
+}
+
+/// Unexpected content type, source of the `LoadingDocumentFailed` error raised when a
+/// document is rejected by the [`ContentTypes`] accepted by the loader.
+#[derive(Debug)]
+pub struct ContentTypeError(pub IriBuf, pub Option<String>);
+
+impl fmt::Display for ContentTypeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.1 {
+			Some(ty) => write!(f, "`{}` is served with unexpected content type `{}`", self.0, ty),
+			None => write!(f, "`{}` is served without content type", self.0)
+		}
+	}
 }
 
+impl std::error::Error for ContentTypeError {}
+
+/// Document exceeding the maximum size set with [`Loader::with_max_size`], source of the
+/// `LoadingDocumentFailed` error raised when it is loaded.
+#[derive(Debug)]
+pub struct TooLargeError(pub IriBuf, pub usize);
+
+impl fmt::Display for TooLargeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}` exceeds the maximum size ({} bytes)", self.0, self.1)
+	}
+}
+
+impl std::error::Error for TooLargeError {}
+
 pub async fn load_remote_json_ld_document(url: Iri<'_>) -> Result<RemoteDocument, Error> {
+	load_remote_json_ld_document_with(&reqwest::Client::new(), url).await
+}
+
+/// Load a remote document using the given client.
+pub async fn load_remote_json_ld_document_with(client: &reqwest::Client, url: Iri<'_>) -> Result<RemoteDocument, Error> {
+	instrument!(load(client, url, None, ContentTypes::Strict, None), "load_document", url = %url).await
+}
+
+/// Hook called on every outgoing request before it is sent.
+///
+/// It may add or change headers, for instance to authenticate the request, or fail with an
+/// error to cancel it.
+pub type RequestHook = dyn Fn(&mut reqwest::Request) -> Result<(), Error> + Send + Sync;
+
+/// Read the body of a response, failing as soon as it exceeds the given maximum size.
+async fn read_body(mut response: reqwest::Response, url: Iri<'_>, max_size: Option<usize>) -> Result<String, Error> {
+	let max_size = match max_size {
+		Some(max_size) => max_size,
+		None => return Ok(response.text().await?)
+	};
+
+	let too_large = || Error::new(ErrorCode::LoadingDocumentFailed, TooLargeError(url.into(), max_size));
+	if response.content_length().map(|len| len > max_size as u64).unwrap_or(false) {
+		return Err(too_large())
+	}
+
+	let mut body = Vec::new();
+	while let Some(chunk) = response.chunk().await? {
+		if body.len() + chunk.len() > max_size {
+			return Err(too_large())
+		}
+
+		body.extend_from_slice(&chunk)
+	}
+
+	String::from_utf8(body).map_err(|e| Error::new(ErrorCode::LoadingDocumentFailed, e))
+}
+
+async fn load(client: &reqwest::Client, url: Iri<'_>, hook: Option<&RequestHook>, content_types: ContentTypes, max_size: Option<usize>) -> Result<RemoteDocument, Error> {
 	info!("loading remote document `{}'", url);
 	use reqwest::header::*;
 
-	let client = reqwest::Client::new();
-	let request = client.get(url.as_str()).header(ACCEPT, "application/ld+json, application/json");
-	let response = request.send().await?;
+	let mut request = client.get(url.as_str()).header(ACCEPT, "application/ld+json, application/json").build()?;
+	if let Some(hook) = hook {
+		hook(&mut request)?
+	}
 
-	if response.headers().get_all(CONTENT_TYPE).iter().find(|&value| {
-		if let Ok(value) = value.to_str() {
-			is_json_media_type(value)
-		} else {
-			false
-		}
-	}).is_some() {
-		let body = response.text().await?;
+	let response = client.execute(request).await?;
+	let content_type = match response.headers().get(CONTENT_TYPE) {
+		Some(value) => match value.to_str() {
+			Ok(value) => Some(value.to_string()),
+			Err(_) => return Err(ErrorCode::LoadingDocumentFailed.into())
+		},
+		None => None
+	};
 
-		match json::parse(body.as_str()) {
-			Ok(doc) => Ok(RemoteDocument::new(doc, url.into())),
-			Err(e) => panic!("invalid json: {:?}: {}", e, body.as_str())
-		}
-	} else {
-		panic!("not a json document")
+	match content_types.accept(content_type.as_deref()) {
+		Some(labeled) => {
+			let body = read_body(response, url, max_size).await?;
+			match parse_json(body.as_str()) {
+				Ok(doc) => {
+					if !labeled {
+						warn!("document `{}' is served as `{}' instead of JSON", url, content_type.as_deref().unwrap_or("(no content type)"))
+					}
+
+					Ok(RemoteDocument::new(doc, url).with_content(body))
+				},
+				Err(e) => Err(Error::new(ErrorCode::LoadingDocumentFailed, e))
+			}
+		},
+		None => Err(Error::new(ErrorCode::LoadingDocumentFailed, ContentTypeError(url.into(), content_type)))
 	}
 }
 
 pub struct Loader {
-	cache: HashMap<IriBuf, RemoteDocument>
+	client: reqwest::Client,
+	hook: Option<Box<RequestHook>>,
+	content_types: ContentTypes,
+	max_size: Option<usize>,
+	cache: RwLock<HashMap<IriBuf, RemoteDocument>>
 }
 
 impl Loader {
 	pub fn new() -> Loader {
+		Self::with_client(reqwest::Client::new())
+	}
+
+	/// Create a loader using the given client.
+	///
+	/// Clients share their connection pool when cloned.
+	pub fn with_client(client: reqwest::Client) -> Loader {
 		Loader {
-			cache: HashMap::new()
+			client,
+			hook: None,
+			content_types: ContentTypes::default(),
+			max_size: None,
+			cache: RwLock::new(HashMap::new())
 		}
 	}
 
-	pub async fn load(&mut self, url: Iri<'_>) -> Result<RemoteDocument, Error> {
+	/// Client used to fetch documents.
+	pub fn client(&self) -> &reqwest::Client {
+		&self.client
+	}
+
+	/// Set the hook called on every outgoing request before it is sent.
+	///
+	/// The request is discarded, and loading fails, if the hook returns an error.
+	pub fn with_request_hook<F: 'static + Fn(&mut reqwest::Request) -> Result<(), Error> + Send + Sync>(mut self, hook: F) -> Loader {
+		self.hook = Some(Box::new(hook));
+		self
+	}
+
+	/// Set the accepted content types.
+	pub fn with_content_types(mut self, content_types: ContentTypes) -> Loader {
+		self.content_types = content_types;
+		self
+	}
+
+	/// Set the maximum size of a document, in bytes.
+	///
+	/// Larger documents are rejected without being downloaded entirely.
+	pub fn with_max_size(mut self, max_size: usize) -> Loader {
+		self.max_size = Some(max_size);
+		self
+	}
+
+	pub async fn load(&self, url: Iri<'_>) -> Result<RemoteDocument, Error> {
 		let url = IriBuf::from(url);
-		match self.cache.get(&url) {
-			Some(doc) => {
-				Ok(doc.clone())
-			},
-			None => {
-				let doc = load_remote_json_ld_document(url.as_iri()).await?;
-				self.cache.insert(url, doc.clone());
-				Ok(doc)
-			}
+		if let Some(doc) = self.cache.read().unwrap().get(&url) {
+			return Ok(doc.clone())
 		}
+
+		let doc = instrument!(load(&self.client, url.as_iri(), self.hook.as_deref(), self.content_types, self.max_size), "load_document", url = %url).await?;
+		self.cache.write().unwrap().insert(url, doc.clone());
+		Ok(doc)
+	}
+}
+
+impl Default for Loader {
+	fn default() -> Loader {
+		Self::new()
+	}
+}
+
+impl SharedLoader for Loader {
+	type Document = JsonValue;
+
+	fn load_shared<'a>(&'a self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument, Error>> {
+		let url = IriBuf::from(url);
+		async move {
+			self.load(url.as_iri()).await
+		}.boxed()
 	}
 }
 
@@ -76,7 +264,7 @@ impl context::Loader for Loader {
 	fn load_context<'a>(&'a mut self, url: Iri) -> BoxFuture<'a, Result<RemoteContext<JsonValue>, Error>> {
 		let url = IriBuf::from(url);
 		async move {
-			match self.load(url.as_iri()).await {
+			match instrument!(self.load(url.as_iri()), "load_context", url = %url).await {
 				Ok(remote_doc) => {
 					let (doc, url) = remote_doc.into_parts();
 					if let JsonValue::Object(obj) = doc {
@@ -0,0 +1,343 @@
+//! JSON Schema generation.
+//!
+//! APIs publishing JSON-LD documents often need to publish a [JSON Schema](https://json-schema.org/)
+//! as well, for clients that only validate plain JSON.
+//! [`json_schema`] derives it from the context used to compact the documents: each term is a
+//! property whose values are constrained by the type mapping (type coercion) and the container
+//! mapping of its definition.
+//! A [`Shape`] can be given as hint to add the constraints that the context alone cannot
+//! express: required properties, single-valued properties, datatypes and node kinds of the
+//! properties without type coercion, and required types.
+//!
+//! ```ignore
+//! let context: JsonContext = json::parse(CONTEXT)?.process(&JsonContext::new(None), &mut loader, None).await?;
+//! let schema = schema::json_schema(&context, Some(&person_shape));
+//! println!("{}", schema.pretty(2));
+//! ```
+
+use std::collections::HashMap;
+use json::{
+	JsonValue,
+	object::Object as JsonObject
+};
+use crate::{
+	Id,
+	Reference,
+	Context,
+	context::TermDefinition,
+	syntax::{
+		Keyword,
+		Term,
+		Type,
+		ContainerType
+	},
+	shape::{
+		Shape,
+		PropertyShape,
+		NodeKind
+	},
+	object::xsd::XSD
+};
+
+/// JSON Schema dialect of the generated schemas.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Schema with a single entry.
+fn schema<V: Into<JsonValue>>(key: &str, value: V) -> JsonObject {
+	let mut schema = JsonObject::new();
+	schema.insert(key, value.into());
+	schema
+}
+
+/// Schema of the values of the given JSON types.
+fn types(types: &[&str]) -> JsonValue {
+	match types {
+		[ty] => schema("type", *ty).into(),
+		types => schema("type", types.to_vec()).into()
+	}
+}
+
+/// Schema of strings of the given format.
+fn format(format: &str) -> JsonValue {
+	let mut schema = schema("type", "string");
+	schema.insert("format", format.into());
+	schema.into()
+}
+
+/// Schema validating any of the given schemas.
+fn any_of(schemas: Vec<JsonValue>) -> JsonValue {
+	schema("anyOf", schemas).into()
+}
+
+/// Schema of arrays of items.
+fn array(item: JsonValue) -> JsonValue {
+	let mut schema = schema("type", "array");
+	schema.insert("items", item);
+	schema.into()
+}
+
+/// Schema of maps of values.
+fn map(value: JsonValue) -> JsonValue {
+	let mut schema = schema("type", "object");
+	schema.insert("additionalProperties", value);
+	schema.into()
+}
+
+/// Schema of a value, or an array of such values.
+fn one_or_many(item: JsonValue) -> JsonValue {
+	if is_any(&item) {
+		item
+	} else {
+		any_of(vec![item.clone(), array(item)])
+	}
+}
+
+/// Checks if the given schema validates anything.
+fn is_any(schema: &JsonValue) -> bool {
+	schema.is_empty() && schema.is_object()
+}
+
+/// Schema validating anything.
+fn any() -> JsonValue {
+	JsonObject::new().into()
+}
+
+/// Schema of node objects.
+fn node() -> JsonValue {
+	types(&["object"])
+}
+
+/// Schema of node references, compacted into IRIs, or node objects.
+fn reference() -> JsonValue {
+	any_of(vec![types(&["string"]), node()])
+}
+
+/// Schema of the values of the given datatype, compacted with a type coercion to this datatype.
+///
+/// Such values are either native JSON values, or lexical forms.
+fn coerced(datatype: &str) -> JsonValue {
+	match datatype.strip_prefix(XSD) {
+		Some("boolean") => types(&["boolean", "string"]),
+		Some("integer") | Some("int") | Some("long") | Some("short") | Some("byte")
+		| Some("nonNegativeInteger") | Some("positiveInteger") | Some("nonPositiveInteger")
+		| Some("negativeInteger") | Some("unsignedInt") | Some("unsignedLong") => types(&["integer", "string"]),
+		Some("decimal") | Some("double") | Some("float") => types(&["number", "string"]),
+		Some("dateTime") => format("date-time"),
+		Some("date") => format("date"),
+		Some("time") => format("time"),
+		Some("duration") => format("duration"),
+		Some("anyURI") => format("uri"),
+		_ => types(&["string"])
+	}
+}
+
+/// Schema of the values of the given datatype, compacted without type coercion.
+///
+/// Strings, booleans, integers and doubles are native JSON values, other literals are value
+/// objects.
+fn literal(datatype: &str) -> JsonValue {
+	match datatype.strip_prefix(XSD) {
+		Some("string") => types(&["string"]),
+		Some("boolean") => types(&["boolean"]),
+		Some("integer") => types(&["integer"]),
+		Some("double") => types(&["number"]),
+		_ => node()
+	}
+}
+
+/// Schema of a single value of a term.
+fn item<T: Id, C: Context<T>>(definition: &TermDefinition<T, C>, hint: Option<&PropertyShape<T>>) -> JsonValue {
+	if definition.reverse_property {
+		return match &definition.typ {
+			Some(Type::Id) | Some(Type::Vocab) => reference(),
+			_ => node()
+		}
+	}
+
+	match &definition.typ {
+		Some(Type::Id) | Some(Type::Vocab) => reference(),
+		Some(Type::Ref(datatype)) => coerced(datatype.as_iri().as_str()),
+		Some(Type::Json) => any(),
+		Some(Type::None) | None => {
+			let datatype = hint.and_then(|hint| hint.datatype.as_ref());
+			match (datatype, hint.and_then(|hint| hint.kind)) {
+				(Some(datatype), _) if definition.language.is_none() && definition.direction.is_none() => literal(datatype.as_str()),
+				(_, Some(NodeKind::Iri)) | (_, Some(NodeKind::BlankNode)) => node(),
+				_ => any()
+			}
+		}
+	}
+}
+
+/// Schema of the value of a term, according to its container mapping.
+fn value<T: Id, C: Context<T>>(definition: &TermDefinition<T, C>, hint: Option<&PropertyShape<T>>) -> JsonValue {
+	let container = &definition.container;
+	let values = |item: JsonValue| {
+		if container.contains(ContainerType::Set) || container.contains(ContainerType::List) {
+			array(item)
+		} else if hint.and_then(|hint| hint.max_count) == Some(1) {
+			item
+		} else {
+			one_or_many(item)
+		}
+	};
+
+	if container.contains(ContainerType::Language) {
+		// Language maps values are strings.
+		map(values(types(&["string"])))
+	} else if container.contains(ContainerType::Id) || container.contains(ContainerType::Type) {
+		let item = match &definition.typ {
+			Some(Type::Id) | Some(Type::Vocab) if container.contains(ContainerType::Type) => reference(),
+			_ => node()
+		};
+
+		map(values(item))
+	} else if container.contains(ContainerType::Index) {
+		map(values(item(definition, hint)))
+	} else if container.contains(ContainerType::Graph) {
+		values(node())
+	} else {
+		values(item(definition, hint))
+	}
+}
+
+/// Schema of the value of a keyword alias.
+fn keyword(keyword: Keyword) -> JsonValue {
+	match keyword {
+		Keyword::Id => types(&["string"]),
+		Keyword::Type => one_or_many(types(&["string"])),
+		_ => any()
+	}
+}
+
+/// Schema of a node type, whose compacted form is `ty`, in the value of the `@type` entry.
+fn has_type(type_key: &str, ty: &str) -> JsonValue {
+	let ty = schema("const", ty);
+	let mut types = schema("type", "array");
+	types.insert("contains", ty.clone().into());
+
+	let mut properties = JsonObject::new();
+	properties.insert(type_key, any_of(vec![ty.into(), types.into()]));
+	schema("properties", properties).into()
+}
+
+/// Generate the JSON Schema of the documents compacted with the given context.
+///
+/// The generated schema describes a node object, whose properties are the terms of the context
+/// (nested under their `@nest` term, if any), and the JSON-LD keywords.
+/// Compact IRIs and absolute IRIs, which are not terms, are allowed as property
+/// names, and so are any other keys when the context has a vocabulary mapping.
+///
+/// The optional `shape` gives hints on the properties:
+/// - properties with a minimum count are required, and properties with a maximum count of one
+///   are single-valued (unless their container mapping is `@set` or `@list`),
+/// - properties without type coercion take their values in the datatype or node kind of the
+///   shape,
+/// - the target class and required types of the shape are required in the `@type` entry.
+///
+/// Properties are matched to terms by IRI.
+pub fn json_schema<T: Id, C: Context<T>>(context: &C, shape: Option<&Shape<T>>) -> JsonValue {
+	let hints: HashMap<&Reference<T>, &PropertyShape<T>> = shape.map(|shape| {
+		shape.properties().iter().map(|hint| (hint.property(), hint)).collect()
+	}).unwrap_or_default();
+
+	let mut definitions: Vec<_> = context.definitions().collect();
+	definitions.sort_by_key(|(term, _)| *term);
+
+	let mut properties = JsonObject::new();
+	properties.insert("@context", any());
+	properties.insert("@id", keyword(Keyword::Id));
+	properties.insert("@type", keyword(Keyword::Type));
+
+	let mut required = Vec::new();
+	let mut nested: HashMap<&str, (JsonObject, Vec<&str>)> = HashMap::new();
+	let mut type_key = "@type";
+	for (term, definition) in &definitions {
+		let value = match &definition.value {
+			Some(Term::Keyword(Keyword::Nest)) => continue,
+			Some(Term::Keyword(k)) => {
+				if *k == Keyword::Type && type_key == "@type" {
+					type_key = term.as_str()
+				}
+
+				keyword(*k)
+			},
+			Some(Term::Ref(prop)) => {
+				let hint = hints.get(prop).copied();
+				let mut value = value(definition, hint);
+				if let JsonValue::Object(value) = &mut value {
+					value.insert("description", prop.as_str().into());
+				}
+
+				let (properties, required) = match &definition.nest {
+					Some(nest) => {
+						let (properties, required) = nested.entry(nest.as_str()).or_insert_with(|| (JsonObject::new(), Vec::new()));
+						(properties, required)
+					},
+					None => (&mut properties, &mut required)
+				};
+
+				if hint.map(|hint| hint.min_count > 0).unwrap_or(false) && !required.contains(&term.as_str()) {
+					required.push(term.as_str())
+				}
+
+				properties.insert(term.as_str(), value);
+				continue
+			},
+			Some(Term::Null) | None => continue
+		};
+
+		properties.insert(term.as_str(), value)
+	}
+
+	// `@nest` terms.
+	let mut nests: Vec<_> = nested.into_iter().collect();
+	nests.sort_by_key(|(nest, _)| *nest);
+	for (nest, (nest_properties, nest_required)) in nests {
+		let mut nest_schema = schema("type", "object");
+		nest_schema.insert("properties", nest_properties.into());
+		if !nest_required.is_empty() {
+			nest_schema.insert("required", nest_required.into());
+		}
+
+		properties.insert(nest, nest_schema.into());
+	}
+
+	let mut result = schema("$schema", DIALECT);
+	result.insert("type", "object".into());
+	result.insert("properties", properties.into());
+
+	// Keywords, compact IRIs and IRIs.
+	let mut pattern_properties = JsonObject::new();
+	pattern_properties.insert("^@", any());
+	pattern_properties.insert(":", any());
+	result.insert("patternProperties", pattern_properties.into());
+	if context.vocabulary().is_none() {
+		result.insert("additionalProperties", false.into());
+	}
+
+	if let Some(shape) = shape {
+		let mut required_types: Vec<&Reference<T>> = shape.target().into_iter().collect();
+		required_types.extend(shape.types.iter().filter(|ty| Some(*ty) != shape.target()));
+		if !required_types.is_empty() {
+			let all_of: Vec<_> = required_types.into_iter().map(|ty| {
+				// Types are compacted using the vocabulary mapping.
+				let compacted = definitions.iter().find(|(_, definition)| definition.value.as_ref() == Some(&Term::Ref(ty.clone())))
+					.map(|(term, _)| term.as_str())
+					.unwrap_or_else(|| ty.as_str());
+				has_type(type_key, compacted)
+			}).collect();
+
+			result.insert("allOf", all_of.into());
+			if !required.contains(&type_key) {
+				required.push(type_key)
+			}
+		}
+	}
+
+	if !required.is_empty() {
+		result.insert("required", required.into());
+	}
+
+	result.into()
+}
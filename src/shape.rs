@@ -0,0 +1,561 @@
+//! Shape validation.
+//!
+//! This module provides a minimal shape validation layer, inspired by
+//! [SHACL](https://www.w3.org/TR/shacl/), to check that nodes have some required types and
+//! properties, with cardinality, datatype, class and node kind constraints.
+//!
+//! Shapes can be built in Rust, or loaded from a simple JSON format:
+//! ```json
+//! {
+//!     "targetClass": "http://xmlns.com/foaf/0.1/Person",
+//!     "type": [ "http://xmlns.com/foaf/0.1/Agent" ],
+//!     "properties": {
+//!         "http://xmlns.com/foaf/0.1/name": {
+//!             "minCount": 1,
+//!             "maxCount": 1,
+//!             "datatype": "http://www.w3.org/2001/XMLSchema#string"
+//!         },
+//!         "http://xmlns.com/foaf/0.1/knows": {
+//!             "nodeKind": "IRI",
+//!             "class": "http://xmlns.com/foaf/0.1/Person"
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use iref::{
+	Iri,
+	IriBuf
+};
+use json::JsonValue;
+use crate::{
+	Id,
+	BlankId,
+	Reference,
+	Lenient,
+	Object,
+	Node,
+	Value,
+	ExpandedDocument,
+	object::{
+		value::Literal,
+		xsd::{
+			BOOLEAN as XSD_BOOLEAN,
+			INTEGER as XSD_INTEGER,
+			DOUBLE as XSD_DOUBLE,
+			STRING as XSD_STRING
+		}
+	}
+};
+
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+
+/// Shape loading error.
+#[derive(Debug)]
+pub enum Error {
+	/// The shape definition is not a valid JSON shape.
+	InvalidShape,
+
+	/// An invalid IRI or blank node identifier has been found in the shape definition.
+	InvalidIri(String),
+
+	/// An unknown node kind has been found in the shape definition.
+	InvalidNodeKind(String)
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::InvalidShape => write!(f, "invalid shape"),
+			Error::InvalidIri(iri) => write!(f, "invalid IRI `{}`", iri),
+			Error::InvalidNodeKind(kind) => write!(f, "invalid node kind `{}`", kind)
+		}
+	}
+}
+
+/// Kind of value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NodeKind {
+	/// Node identified by an IRI.
+	Iri,
+
+	/// Blank node (or list).
+	BlankNode,
+
+	/// Value object.
+	Literal
+}
+
+impl NodeKind {
+	/// Get the kind of the given object.
+	pub fn of<T: Id>(object: &Object<T>) -> NodeKind {
+		match object {
+			Object::Value(_) => NodeKind::Literal,
+			Object::Node(node) => match &node.id {
+				Some(Lenient::Ok(Reference::Id(_))) => NodeKind::Iri,
+				_ => NodeKind::BlankNode
+			},
+			Object::List(_) => NodeKind::BlankNode
+		}
+	}
+
+	/// Name of the kind, as used in the JSON shape format.
+	pub fn as_str(&self) -> &str {
+		match self {
+			NodeKind::Iri => "IRI",
+			NodeKind::BlankNode => "BlankNode",
+			NodeKind::Literal => "Literal"
+		}
+	}
+}
+
+impl<'a> TryFrom<&'a str> for NodeKind {
+	type Error = ();
+
+	fn try_from(name: &'a str) -> Result<NodeKind, ()> {
+		match name {
+			"IRI" => Ok(NodeKind::Iri),
+			"BlankNode" => Ok(NodeKind::BlankNode),
+			"Literal" => Ok(NodeKind::Literal),
+			_ => Err(())
+		}
+	}
+}
+
+impl fmt::Display for NodeKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Constraints on the values of a property.
+pub struct PropertyShape<T: Id = IriBuf> {
+	/// Constrained property.
+	property: Reference<T>,
+
+	/// Minimum number of values.
+	pub(crate) min_count: usize,
+
+	/// Maximum number of values.
+	pub(crate) max_count: Option<usize>,
+
+	/// Datatype of the values.
+	pub(crate) datatype: Option<IriBuf>,
+
+	/// Type of the values.
+	class: Option<Reference<T>>,
+
+	/// Kind of the values.
+	pub(crate) kind: Option<NodeKind>
+}
+
+impl<T: Id> PropertyShape<T> {
+	/// Create a new unconstrained property shape.
+	pub fn new(property: Reference<T>) -> PropertyShape<T> {
+		PropertyShape {
+			property,
+			min_count: 0,
+			max_count: None,
+			datatype: None,
+			class: None,
+			kind: None
+		}
+	}
+
+	/// Constrained property.
+	pub fn property(&self) -> &Reference<T> {
+		&self.property
+	}
+
+	/// Require at least `min` values.
+	pub fn min_count(mut self, min: usize) -> Self {
+		self.min_count = min;
+		self
+	}
+
+	/// Require at most `max` values.
+	pub fn max_count(mut self, max: usize) -> Self {
+		self.max_count = Some(max);
+		self
+	}
+
+	/// Require at least one value.
+	pub fn required(self) -> Self {
+		self.min_count(1)
+	}
+
+	/// Require every value to be a literal of the given datatype.
+	///
+	/// Native JSON values are typed as in the RDF serialization: strings are `xsd:string`,
+	/// booleans are `xsd:boolean`, numbers are `xsd:integer` or `xsd:double`, and language
+	/// strings are `rdf:langString`.
+	pub fn datatype(mut self, datatype: Iri) -> Self {
+		self.datatype = Some(datatype.into());
+		self
+	}
+
+	/// Require every value to be a node of the given type.
+	pub fn class(mut self, class: Reference<T>) -> Self {
+		self.class = Some(class);
+		self
+	}
+
+	/// Require every value to be of the given kind.
+	pub fn kind(mut self, kind: NodeKind) -> Self {
+		self.kind = Some(kind);
+		self
+	}
+
+	fn validate<'a, R: Fn(&Reference<T>) -> Option<&'a Node<T>>>(&self, node: &Node<T>, resolve: &R, violations: &mut Vec<Violation<T>>) where T: 'a {
+		let values = node.properties.get(&self.property).map(|values| values.as_slice()).unwrap_or(&[]);
+		let node_id = node_reference(node);
+		let mut violation = |kind| {
+			violations.push(Violation {
+				node: node_id.clone(),
+				kind
+			})
+		};
+
+		if values.len() < self.min_count {
+			violation(ViolationKind::MinCount(self.property.clone(), values.len()))
+		}
+
+		if let Some(max) = self.max_count {
+			if values.len() > max {
+				violation(ViolationKind::MaxCount(self.property.clone(), values.len()))
+			}
+		}
+
+		for value in values {
+			if let Some(kind) = self.kind {
+				if NodeKind::of(value) != kind {
+					violation(ViolationKind::NodeKind(self.property.clone()))
+				}
+			}
+
+			if let Some(datatype) = &self.datatype {
+				let valid = match value.inner() {
+					Object::Value(value) => has_datatype(value, datatype.as_iri()),
+					_ => false
+				};
+
+				if !valid {
+					violation(ViolationKind::Datatype(self.property.clone()))
+				}
+			}
+
+			if let Some(class) = &self.class {
+				let valid = match value.inner() {
+					Object::Node(value_node) => {
+						has_type(value_node, class) || match &value_node.id {
+							Some(Lenient::Ok(id)) => resolve(id).map(|n| has_type(n, class)).unwrap_or(false),
+							_ => false
+						}
+					},
+					_ => false
+				};
+
+				if !valid {
+					violation(ViolationKind::Class(self.property.clone()))
+				}
+			}
+		}
+	}
+}
+
+/// Node shape.
+///
+/// Set of constraints on a node.
+pub struct Shape<T: Id = IriBuf> {
+	/// Type of the nodes to validate in a document.
+	target: Option<Reference<T>>,
+
+	/// Required types.
+	pub(crate) types: Vec<Reference<T>>,
+
+	/// Property constraints.
+	properties: Vec<PropertyShape<T>>
+}
+
+impl<T: Id> Shape<T> {
+	/// Create a new shape, without any constraint.
+	pub fn new() -> Shape<T> {
+		Shape {
+			target: None,
+			types: Vec::new(),
+			properties: Vec::new()
+		}
+	}
+
+	/// Load a shape from its JSON definition.
+	///
+	/// See the [module documentation](crate::shape) for the format.
+	pub fn from_json(json: &JsonValue) -> Result<Shape<T>, Error> {
+		if !json.is_object() {
+			return Err(Error::InvalidShape)
+		}
+
+		let mut shape = Shape::new();
+
+		if !json["targetClass"].is_null() {
+			shape.target = Some(reference_from_json(&json["targetClass"])?)
+		}
+
+		for ty in json["type"].members().chain(std::iter::once(&json["type"]).filter(|ty| ty.is_string())) {
+			shape.types.push(reference_from_json(ty)?)
+		}
+
+		match &json["properties"] {
+			JsonValue::Null => (),
+			JsonValue::Object(properties) => {
+				for (prop, constraints) in properties.iter() {
+					let mut property_shape = PropertyShape::new(reference_from_str(prop)?);
+
+					if !constraints.is_object() {
+						return Err(Error::InvalidShape)
+					}
+
+					if !constraints["minCount"].is_null() {
+						property_shape = property_shape.min_count(constraints["minCount"].as_usize().ok_or(Error::InvalidShape)?)
+					}
+
+					if !constraints["maxCount"].is_null() {
+						property_shape = property_shape.max_count(constraints["maxCount"].as_usize().ok_or(Error::InvalidShape)?)
+					}
+
+					if !constraints["datatype"].is_null() {
+						let datatype = constraints["datatype"].as_str().ok_or(Error::InvalidShape)?;
+						let iri = Iri::new(datatype).map_err(|_| Error::InvalidIri(datatype.to_string()))?;
+						property_shape = property_shape.datatype(iri)
+					}
+
+					if !constraints["class"].is_null() {
+						property_shape = property_shape.class(reference_from_json(&constraints["class"])?)
+					}
+
+					if !constraints["nodeKind"].is_null() {
+						let kind = constraints["nodeKind"].as_str().ok_or(Error::InvalidShape)?;
+						property_shape = property_shape.kind(NodeKind::try_from(kind).map_err(|_| Error::InvalidNodeKind(kind.to_string()))?)
+					}
+
+					shape.properties.push(property_shape)
+				}
+			},
+			_ => return Err(Error::InvalidShape)
+		}
+
+		Ok(shape)
+	}
+
+	/// Type of the nodes targeted by this shape when validating a document.
+	pub fn target(&self) -> Option<&Reference<T>> {
+		self.target.as_ref()
+	}
+
+	/// Only validate the nodes of the given type when validating a document.
+	pub fn target_class(mut self, ty: Reference<T>) -> Self {
+		self.target = Some(ty);
+		self
+	}
+
+	/// Require the nodes to have the given type.
+	pub fn with_type(mut self, ty: Reference<T>) -> Self {
+		self.types.push(ty);
+		self
+	}
+
+	/// Add a property constraint.
+	pub fn with_property(mut self, property: PropertyShape<T>) -> Self {
+		self.properties.push(property);
+		self
+	}
+
+	/// Property constraints.
+	pub fn properties(&self) -> &[PropertyShape<T>] {
+		&self.properties
+	}
+
+	/// Validate a node, and return the list of violations.
+	///
+	/// Class constraints are checked against the types of embedded nodes only.
+	pub fn validate(&self, node: &Node<T>) -> Vec<Violation<T>> {
+		let mut violations = Vec::new();
+		self.validate_with(node, &|_| None, &mut violations);
+		violations
+	}
+
+	/// Checks that the given node conforms to the shape.
+	pub fn conforms(&self, node: &Node<T>) -> bool {
+		self.validate(node).is_empty()
+	}
+
+	/// Validate the top-level nodes of the document having the target type of the shape (or every
+	/// top-level node if there is no target), and return the list of violations.
+	///
+	/// Class constraints are checked against the types of embedded nodes, or of the top-level node
+	/// with the same identifier.
+	pub fn validate_document(&self, document: &ExpandedDocument<T>) -> Vec<Violation<T>> {
+		let mut nodes = HashMap::new();
+		for object in document {
+			if let Object::Node(node) = object.inner() {
+				if let Some(Lenient::Ok(id)) = &node.id {
+					nodes.insert(id, &**node);
+				}
+			}
+		}
+
+		let resolve = |id: &Reference<T>| nodes.get(id).copied();
+
+		let mut violations = Vec::new();
+		for object in document {
+			if let Object::Node(node) = object.inner() {
+				let targeted = match &self.target {
+					Some(target) => has_type(node, target),
+					None => true
+				};
+
+				if targeted {
+					self.validate_with(node, &resolve, &mut violations)
+				}
+			}
+		}
+
+		violations
+	}
+
+	fn validate_with<'a, R: Fn(&Reference<T>) -> Option<&'a Node<T>>>(&self, node: &Node<T>, resolve: &R, violations: &mut Vec<Violation<T>>) where T: 'a {
+		for ty in &self.types {
+			if !has_type(node, ty) {
+				violations.push(Violation {
+					node: node_reference(node),
+					kind: ViolationKind::MissingType(ty.clone())
+				})
+			}
+		}
+
+		for property in &self.properties {
+			property.validate(node, resolve, violations)
+		}
+	}
+}
+
+impl<T: Id> Default for Shape<T> {
+	fn default() -> Shape<T> {
+		Self::new()
+	}
+}
+
+/// Shape constraint violation.
+pub struct Violation<T: Id = IriBuf> {
+	/// Identifier of the invalid node, if any.
+	pub node: Option<Reference<T>>,
+
+	/// Violated constraint.
+	pub kind: ViolationKind<T>
+}
+
+/// Kind of shape constraint violation.
+pub enum ViolationKind<T: Id = IriBuf> {
+	/// The node does not have the given required type.
+	MissingType(Reference<T>),
+
+	/// The property does not have enough values (the number of values is given).
+	MinCount(Reference<T>, usize),
+
+	/// The property has too many values (the number of values is given).
+	MaxCount(Reference<T>, usize),
+
+	/// A value of the property does not have the required datatype.
+	Datatype(Reference<T>),
+
+	/// A value of the property does not have the required type.
+	Class(Reference<T>),
+
+	/// A value of the property is not of the required kind.
+	NodeKind(Reference<T>)
+}
+
+impl<T: Id> fmt::Display for Violation<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.node {
+			Some(id) => write!(f, "node `{}`: ", id)?,
+			None => write!(f, "anonymous node: ")?
+		}
+
+		match &self.kind {
+			ViolationKind::MissingType(ty) => write!(f, "missing type `{}`", ty),
+			ViolationKind::MinCount(prop, count) => write!(f, "not enough values for `{}` ({})", prop, count),
+			ViolationKind::MaxCount(prop, count) => write!(f, "too many values for `{}` ({})", prop, count),
+			ViolationKind::Datatype(prop) => write!(f, "invalid datatype for a value of `{}`", prop),
+			ViolationKind::Class(prop) => write!(f, "invalid type for a value of `{}`", prop),
+			ViolationKind::NodeKind(prop) => write!(f, "invalid kind for a value of `{}`", prop)
+		}
+	}
+}
+
+fn node_reference<T: Id>(node: &Node<T>) -> Option<Reference<T>> {
+	match &node.id {
+		Some(Lenient::Ok(id)) => Some(id.clone()),
+		_ => None
+	}
+}
+
+fn has_type<T: Id>(node: &Node<T>, ty: &Reference<T>) -> bool {
+	node.types.iter().any(|t| match t {
+		Lenient::Ok(t) => t == ty,
+		_ => false
+	})
+}
+
+fn has_datatype<T: Id>(value: &Value<T>, datatype: Iri) -> bool {
+	match value {
+		Value::Literal(lit, types) => {
+			if types.is_empty() {
+				let implicit = match lit {
+					Literal::Null => return false,
+					Literal::Boolean(_) => XSD_BOOLEAN,
+					Literal::Number(n) => {
+						let (_, _, exponent) = n.as_parts();
+						if exponent >= 0 { XSD_INTEGER } else { XSD_DOUBLE }
+					},
+					Literal::String(_) => XSD_STRING,
+					Literal::Json(_) => RDF_JSON
+				};
+
+				datatype.as_str() == implicit
+			} else {
+				types.iter().any(|ty| ty.as_iri().as_str() == datatype.as_str())
+			}
+		},
+		Value::LangString(str) => {
+			if str.language().is_none() && str.direction().is_none() {
+				datatype.as_str() == XSD_STRING
+			} else {
+				datatype.as_str() == RDF_LANG_STRING
+			}
+		}
+	}
+}
+
+fn reference_from_str<T: Id>(id: &str) -> Result<Reference<T>, Error> {
+	match BlankId::try_from(id) {
+		Ok(blank) => Ok(Reference::Blank(blank)),
+		Err(_) => match Iri::new(id) {
+			Ok(iri) => Ok(Reference::Id(T::from_iri(iri))),
+			Err(_) => Err(Error::InvalidIri(id.to_string()))
+		}
+	}
+}
+
+fn reference_from_json<T: Id>(json: &JsonValue) -> Result<Reference<T>, Error> {
+	match json.as_str() {
+		Some(id) => reference_from_str(id),
+		None => Err(Error::InvalidShape)
+	}
+}
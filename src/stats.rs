@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use crate::{
+	Id,
+	Lenient,
+	Reference,
+	Object,
+	Value,
+	Node,
+	ExpandedDocument
+};
+
+/// Statistics about an expanded document.
+///
+/// Computed in a single traversal of the document by [`ExpandedDocument::stats`].
+/// Nodes embedded in properties, graphs, included blocks and reverse properties are counted.
+#[derive(Clone, Default, Debug)]
+pub struct Stats {
+	/// Number of nodes.
+	pub nodes: usize,
+
+	/// Number of value objects.
+	pub values: usize,
+
+	/// Number of list objects.
+	pub lists: usize,
+
+	/// Number of blank nodes: nodes with no identifier or a blank node identifier.
+	pub blank_nodes: usize,
+
+	/// Number of nodes having each type.
+	pub types: HashMap<String, usize>,
+
+	/// Number of values of each (non reverse) property.
+	pub properties: HashMap<String, usize>,
+
+	/// Number of language-tagged strings for each language tag.
+	pub languages: HashMap<String, usize>,
+
+	/// Maximum nesting depth, top-level objects having a depth of 1.
+	pub max_depth: usize
+}
+
+impl Stats {
+	/// Number of distinct types.
+	pub fn distinct_types(&self) -> usize {
+		self.types.len()
+	}
+
+	/// Number of distinct properties.
+	pub fn distinct_properties(&self) -> usize {
+		self.properties.len()
+	}
+
+	fn add_object<T: Id>(&mut self, object: &Object<T>, depth: usize) {
+		self.max_depth = std::cmp::max(self.max_depth, depth);
+		match object {
+			Object::Value(value) => {
+				self.values += 1;
+				if let Value::LangString(str) = value {
+					if let Some(language) = str.language() {
+						*self.languages.entry(language.to_string()).or_default() += 1
+					}
+				}
+			},
+			Object::Node(node) => self.add_node(node, depth),
+			Object::List(items) => {
+				self.lists += 1;
+				for item in items {
+					self.add_object(item, depth + 1)
+				}
+			}
+		}
+	}
+
+	fn add_node<T: Id>(&mut self, node: &Node<T>, depth: usize) {
+		self.nodes += 1;
+		self.max_depth = std::cmp::max(self.max_depth, depth);
+
+		match node.id() {
+			None | Some(Lenient::Ok(Reference::Blank(_))) => self.blank_nodes += 1,
+			_ => ()
+		}
+
+		for ty in node.types() {
+			*self.types.entry(ty.as_str().to_string()).or_default() += 1
+		}
+
+		if let Some(graph) = node.graph() {
+			for object in graph {
+				self.add_object(object, depth + 1)
+			}
+		}
+
+		if let Some(included) = node.included() {
+			for node in included {
+				self.add_node(node, depth + 1)
+			}
+		}
+
+		for (property, values) in &node.properties {
+			*self.properties.entry(property.as_str().to_string()).or_default() += values.len();
+			for value in values {
+				self.add_object(value, depth + 1)
+			}
+		}
+
+		for nodes in node.reverse_properties.values() {
+			for node in nodes {
+				self.add_node(node, depth + 1)
+			}
+		}
+	}
+}
+
+impl<T: Id> ExpandedDocument<T> {
+	/// Compute statistics about this document.
+	pub fn stats(&self) -> Stats {
+		let mut stats = Stats::default();
+		for object in self {
+			stats.add_object(object, 1)
+		}
+
+		stats
+	}
+}
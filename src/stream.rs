@@ -0,0 +1,271 @@
+//! Streaming processing of JSON-LD records.
+//!
+//! Large dumps are often distributed as a sequence of independent JSON-LD documents,
+//! either one document per line ([NDJSON](http://ndjson.org/)), or as
+//! [RFC 7464](https://tools.ietf.org/html/rfc7464) JSON text sequences where each record is
+//! prefixed by the `RS` (`0x1E`) character.
+//! [`Records`] reads such a stream one record at a time, an [`Expander`] expands each record
+//! against a context processed only once, and a [`Writer`] writes the expanded records back
+//! in the same format.
+//!
+//! ```ignore
+//! let expander = Expander::new(&context, Some(&shared_context), base_url, &mut loader, Default::default()).await?;
+//! let mut writer = Writer::new(std::io::stdout(), Format::Lines);
+//! let mut results = expander.expand_records(Records::new(std::io::stdin().lock(), Format::Lines), &mut loader);
+//! while let Some(doc) = results.next().await {
+//!     writer.write(&doc?)?;
+//! }
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use futures::stream::{self, Stream};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use crate::{
+	Id,
+	ContextMut,
+	Document,
+	ExpandedDocument,
+	context::{
+		Local,
+		Loader
+	},
+	expansion,
+	util::{
+		AsJson,
+		parse_json
+	}
+};
+
+/// Record separator of JSON text sequences.
+const RS: u8 = 0x1e;
+
+/// Stream format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+	/// Newline-delimited JSON: one record per line.
+	/// Blank lines are ignored.
+	Lines,
+
+	/// RFC 7464 JSON text sequence: each record is prefixed by `RS` and terminated by a
+	/// line feed.
+	Sequence
+}
+
+/// Streaming error.
+#[derive(Debug)]
+pub enum Error {
+	/// Unable to read from or write to the stream.
+	Io(io::Error),
+
+	/// The record at the given position (starting from 1) is not valid JSON.
+	///
+	/// With the [`Lines`](Format::Lines) format, this is the line number.
+	InvalidJson(usize),
+
+	/// Context processing or expansion error on the record at the given position
+	/// (starting from 1), or `0` for the shared context.
+	Expansion(usize, crate::Error)
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Io(e) => write!(f, "I/O error: {}", e),
+			Error::InvalidJson(n) => write!(f, "record {} is not valid JSON", n),
+			Error::Expansion(0, e) => write!(f, "invalid shared context: {}", e.code().as_str()),
+			Error::Expansion(n, e) => write!(f, "record {} expansion failed: {}", n, e.code().as_str())
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Error {
+		Error::Io(e)
+	}
+}
+
+/// Iterator over the records of a stream.
+///
+/// Each record is parsed independently: after an [`InvalidJson`](Error::InvalidJson) error,
+/// the following records can still be read.
+pub struct Records<R> {
+	reader: R,
+	format: Format,
+	position: usize,
+	started: bool
+}
+
+impl<R: BufRead> Records<R> {
+	/// Read records from the given reader.
+	pub fn new(reader: R, format: Format) -> Records<R> {
+		Records {
+			reader,
+			format,
+			position: 0,
+			started: false
+		}
+	}
+
+	/// Position of the last record read (starting from 1).
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// Read the raw bytes of the next non-blank record.
+	fn next_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+		loop {
+			let mut buffer = Vec::new();
+			match self.format {
+				Format::Lines => {
+					if self.reader.read_until(b'\n', &mut buffer)? == 0 {
+						return Ok(None)
+					}
+
+					self.position += 1;
+				},
+				Format::Sequence => {
+					if !self.started {
+						// Skip anything before the first record separator.
+						self.started = true;
+						self.reader.read_until(RS, &mut buffer)?;
+						buffer.clear();
+					}
+
+					if self.reader.read_until(RS, &mut buffer)? == 0 {
+						return Ok(None)
+					}
+
+					if buffer.last() == Some(&RS) {
+						buffer.pop();
+					}
+
+					self.position += 1;
+				}
+			}
+
+			if !buffer.iter().all(u8::is_ascii_whitespace) {
+				return Ok(Some(buffer))
+			}
+		}
+	}
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+	type Item = Result<JsonValue, Error>;
+
+	fn next(&mut self) -> Option<Result<JsonValue, Error>> {
+		match self.next_record() {
+			Ok(Some(buffer)) => {
+				let record = std::str::from_utf8(&buffer).ok().and_then(|source| parse_json(source).ok());
+				Some(record.ok_or(Error::InvalidJson(self.position)))
+			},
+			Ok(None) => None,
+			Err(e) => Some(Err(e.into()))
+		}
+	}
+}
+
+/// Records expander.
+///
+/// Holds an active context, processed once, used to expand every record.
+/// Records may still define their own `@context`, processed on top of it.
+pub struct Expander<C> {
+	context: C,
+	base_url: Option<IriBuf>,
+	options: expansion::Options
+}
+
+impl<C> Expander<C> {
+	/// Active context used to expand the records.
+	pub fn context(&self) -> &C {
+		&self.context
+	}
+
+	/// Base URL of the records.
+	pub fn base_url(&self) -> Option<Iri<'_>> {
+		self.base_url.as_ref().map(|url| url.as_iri())
+	}
+}
+
+impl<C: Send + Sync> Expander<C> {
+	/// Create a new expander.
+	///
+	/// The optional `shared_context` (for instance the value of a `@context` entry common to
+	/// all the records) is processed once on top of the initial `context`.
+	pub async fn new<T: Send + Sync + Id, L: Send + Sync + Loader>(context: &C, shared_context: Option<&JsonValue>, base_url: Option<Iri<'_>>, loader: &mut L, options: expansion::Options) -> Result<Expander<C>, Error> where C: ContextMut<T>, C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		let context = match shared_context {
+			Some(local_context) => {
+				let local_context: C::LocalContext = local_context.clone().into();
+				local_context.process_with(context, crate::context::ProcessingStack::new(), loader, base_url, options.into()).await.map_err(|e| Error::Expansion(0, e))?
+			},
+			None => context.clone()
+		};
+
+		Ok(Expander {
+			context,
+			base_url: base_url.map(IriBuf::from),
+			options
+		})
+	}
+
+	/// Expand a single record.
+	pub async fn expand<T: Send + Sync + Id, L: Send + Sync + Loader>(&self, record: &JsonValue, loader: &mut L) -> Result<ExpandedDocument<T>, crate::Error> where C: ContextMut<T>, C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		record.expand_with(self.base_url(), &self.context, loader, self.options).await
+	}
+
+	/// Expand every record of the given iterator, yielding the results in order.
+	///
+	/// Records are expanded lazily, one at a time, as the stream is polled.
+	/// Expansion errors report the position of the record in the iterator.
+	pub fn expand_records<'a, T: 'a + Send + Sync + Id, I: 'a + Iterator<Item = Result<JsonValue, Error>>, L: Send + Sync + Loader>(&'a self, records: I, loader: &'a mut L) -> impl 'a + Stream<Item = Result<ExpandedDocument<T>, Error>> where C: ContextMut<T>, C::LocalContext: Send + Sync + From<L::Output> + From<JsonValue>, L::Output: Into<JsonValue> {
+		stream::unfold((records, loader, 0), move |(mut records, loader, position)| async move {
+			let position = position + 1;
+			let result = match records.next()? {
+				Ok(record) => self.expand(&record, loader).await.map_err(|e| Error::Expansion(position, e)),
+				Err(e) => Err(e)
+			};
+
+			Some((result, (records, loader, position)))
+		})
+	}
+}
+
+/// Writer of expanded records.
+pub struct Writer<W> {
+	writer: W,
+	format: Format
+}
+
+impl<W: Write> Writer<W> {
+	/// Write records into the given writer.
+	pub fn new(writer: W, format: Format) -> Writer<W> {
+		Writer {
+			writer,
+			format
+		}
+	}
+
+	/// Write an expanded record.
+	pub fn write<T: Id>(&mut self, document: &ExpandedDocument<T>) -> io::Result<()> {
+		if self.format == Format::Sequence {
+			self.writer.write_all(&[RS])?;
+		}
+
+		self.writer.write_all(document.as_json().dump().as_bytes())?;
+		self.writer.write_all(b"\n")
+	}
+
+	/// Flush the underlying writer.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.writer.flush()
+	}
+
+	/// Consume the writer and return the underlying writer.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
@@ -0,0 +1,185 @@
+//! Well-known structured values.
+//!
+//! Recognizers for common structured values of [schema.org](https://schema.org/) and
+//! [GeoJSON-LD](https://geojson.org/geojson-ld/) data, surfaced as Rust types through accessor
+//! methods on [`Node`]:
+//!
+//! | Method                                                 | Node type                                  |
+//! |--------------------------------------------------------|--------------------------------------------|
+//! | [`as_geo_coordinates`](Node::as_geo_coordinates)       | `schema:GeoCoordinates` or `geojson:Point` |
+//! | [`as_postal_address`](Node::as_postal_address)         | `schema:PostalAddress`                     |
+//! | [`as_quantitative_value`](Node::as_quantitative_value) | `schema:QuantitativeValue`                 |
+//!
+//! Numbers may be native JSON numbers or strings, as commonly found in schema.org data.
+//!
+//! ```ignore
+//! let geo: Option<GeoCoordinates> = place.geo();
+//! let address: Option<PostalAddress> = place.get_any(schema::ADDRESS).and_then(|a| a.as_node()?.as_postal_address());
+//! ```
+
+use crate::{
+	Id,
+	Node,
+	Object,
+	Value,
+	object::Literal,
+	vocab::{
+		Constant,
+		schema,
+		geojson
+	}
+};
+
+/// Geographic coordinates, in degrees.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GeoCoordinates {
+	/// Latitude.
+	pub latitude: f64,
+
+	/// Longitude.
+	pub longitude: f64,
+
+	/// Elevation (or altitude), if any.
+	pub elevation: Option<f64>
+}
+
+/// Postal address.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct PostalAddress {
+	/// Street address.
+	pub street_address: Option<String>,
+
+	/// Post office box number.
+	pub post_office_box_number: Option<String>,
+
+	/// Locality, such as a city.
+	pub address_locality: Option<String>,
+
+	/// Region, such as a state.
+	pub address_region: Option<String>,
+
+	/// Postal code.
+	pub postal_code: Option<String>,
+
+	/// Country, given by its name or code.
+	pub address_country: Option<String>
+}
+
+/// Quantitative value, such as a measure with its unit.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct QuantitativeValue {
+	/// Value.
+	pub value: Option<f64>,
+
+	/// Lower bound.
+	pub min_value: Option<f64>,
+
+	/// Upper bound.
+	pub max_value: Option<f64>,
+
+	/// Unit of measurement, as a UN/CEFACT Common Code or URL.
+	pub unit_code: Option<String>,
+
+	/// Unit of measurement, as free text.
+	pub unit_text: Option<String>
+}
+
+impl<T: Id> Node<T> {
+	/// Returns the coordinates of a `schema:GeoCoordinates` node, or of a GeoJSON-LD `Point`.
+	///
+	/// GeoJSON positions are `[longitude, latitude]` lists, with an optional altitude.
+	pub fn as_geo_coordinates(&self) -> Option<GeoCoordinates> {
+		if self.has_type(&schema::GEO_COORDINATES) {
+			Some(GeoCoordinates {
+				latitude: number(self.get_any(schema::LATITUDE)?.inner())?,
+				longitude: number(self.get_any(schema::LONGITUDE)?.inner())?,
+				elevation: self.get_any(schema::ELEVATION).and_then(|e| number(e.inner()))
+			})
+		} else if self.has_type(&geojson::POINT) {
+			match self.get_any(geojson::COORDINATES)?.inner() {
+				Object::List(position) if position.len() == 2 || position.len() == 3 => {
+					Some(GeoCoordinates {
+						longitude: number(position[0].inner())?,
+						latitude: number(position[1].inner())?,
+						elevation: match position.get(2) {
+							Some(altitude) => Some(number(altitude.inner())?),
+							None => None
+						}
+					})
+				},
+				_ => None
+			}
+		} else {
+			None
+		}
+	}
+
+	/// Returns the coordinates of the node itself, or of its `schema:geo` or `geojson:geometry`
+	/// property.
+	pub fn geo(&self) -> Option<GeoCoordinates> {
+		self.as_geo_coordinates()
+			.or_else(|| self.get(schema::GEO).chain(self.get(geojson::GEOMETRY)).find_map(|object| match object.inner() {
+				Object::Node(node) => node.as_geo_coordinates(),
+				_ => None
+			}))
+	}
+
+	/// Returns the components of a `schema:PostalAddress` node.
+	///
+	/// The country may be a string, or a `schema:Country` node, whose name is returned.
+	pub fn as_postal_address(&self) -> Option<PostalAddress> {
+		if self.has_type(&schema::POSTAL_ADDRESS) {
+			Some(PostalAddress {
+				street_address: text_of(self, schema::STREET_ADDRESS),
+				post_office_box_number: text_of(self, schema::POST_OFFICE_BOX_NUMBER),
+				address_locality: text_of(self, schema::ADDRESS_LOCALITY),
+				address_region: text_of(self, schema::ADDRESS_REGION),
+				postal_code: text_of(self, schema::POSTAL_CODE),
+				address_country: text_of(self, schema::ADDRESS_COUNTRY)
+			})
+		} else {
+			None
+		}
+	}
+
+	/// Returns the components of a `schema:QuantitativeValue` node.
+	pub fn as_quantitative_value(&self) -> Option<QuantitativeValue> {
+		if self.has_type(&schema::QUANTITATIVE_VALUE) {
+			Some(QuantitativeValue {
+				value: number_of(self, schema::VALUE),
+				min_value: number_of(self, schema::MIN_VALUE),
+				max_value: number_of(self, schema::MAX_VALUE),
+				unit_code: text_of(self, schema::UNIT_CODE),
+				unit_text: text_of(self, schema::UNIT_TEXT)
+			})
+		} else {
+			None
+		}
+	}
+}
+
+/// Numeric value of an object, which may be a number or a string.
+fn number<T: Id>(object: &Object<T>) -> Option<f64> {
+	match object {
+		Object::Value(Value::Literal(Literal::Number(n), _)) => Some((*n).into()),
+		Object::Value(value) => value.as_str()?.trim().parse().ok(),
+		_ => None
+	}
+}
+
+fn number_of<T: Id>(node: &Node<T>, prop: Constant) -> Option<f64> {
+	number(node.get_any(prop)?.inner())
+}
+
+/// Text of an object: a string, or the name of a node.
+fn text<T: Id>(object: &Object<T>) -> Option<String> {
+	match object {
+		Object::Value(value) => value.as_str().map(String::from),
+		Object::Node(node) => text_of(node, schema::NAME),
+		Object::List(_) => None
+	}
+}
+
+fn text_of<T: Id>(node: &Node<T>, prop: Constant) -> Option<String> {
+	text(node.get_any(prop)?.inner())
+}
@@ -11,6 +11,21 @@ pub enum ContainerType {
 	Type
 }
 
+impl ContainerType {
+	pub fn as_str(&self) -> &'static str {
+		use ContainerType::*;
+		match self {
+			Graph => "@graph",
+			Id => "@id",
+			Index => "@index",
+			Language => "@language",
+			List => "@list",
+			Set => "@set",
+			Type => "@type"
+		}
+	}
+}
+
 impl<'a> TryFrom<&'a str> for ContainerType {
 	type Error = &'a str;
 
@@ -49,6 +64,10 @@ impl Container {
 		self.0.contains(&c)
 	}
 
+	pub fn iter(&self) -> impl Iterator<Item = ContainerType> + '_ {
+		self.0.iter().cloned()
+	}
+
 	pub fn add(&mut self, c: ContainerType) -> bool {
 		if self.is_empty() {
 			self.0.push(c);
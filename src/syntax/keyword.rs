@@ -1,9 +1,19 @@
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 use json::JsonValue;
-use crate::util;
+use crate::{
+	ProcessingMode,
+	util
+};
 
 /// JSON-LD keywords.
+///
+/// This covers all the JSON-LD 1.1 keywords, some of which are not keywords in JSON-LD 1.0
+/// (see [`Keyword::is_valid_in`]).
+/// The `@annotation` keyword of the JSON-LD-star draft is not part of JSON-LD 1.1, and is
+/// handled as an extension entry instead (see
+/// [`unknown_keywords`](crate::expansion::Options::unknown_keywords)).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Keyword {
 	/// `@base`.
@@ -117,6 +127,49 @@ pub enum Keyword {
 }
 
 impl Keyword {
+	/// All the keywords, in alphabetical order.
+	pub const ALL: [Keyword; 23] = [
+		Keyword::Base,
+		Keyword::Container,
+		Keyword::Context,
+		Keyword::Direction,
+		Keyword::Graph,
+		Keyword::Id,
+		Keyword::Import,
+		Keyword::Included,
+		Keyword::Index,
+		Keyword::Json,
+		Keyword::Language,
+		Keyword::List,
+		Keyword::Nest,
+		Keyword::None,
+		Keyword::Prefix,
+		Keyword::Propagate,
+		Keyword::Protected,
+		Keyword::Reverse,
+		Keyword::Set,
+		Keyword::Type,
+		Keyword::Value,
+		Keyword::Version,
+		Keyword::Vocab
+	];
+
+	/// Keyword string, such as `@id`.
+	pub fn as_str(&self) -> &'static str {
+		self.into_str()
+	}
+
+	/// Checks if this is a keyword in the given processing mode.
+	///
+	/// The keywords introduced by JSON-LD 1.1 are not keywords in JSON-LD 1.0.
+	pub fn is_valid_in(&self, mode: ProcessingMode) -> bool {
+		use Keyword::*;
+		match mode {
+			ProcessingMode::JsonLd1_0 => !matches!(self, Direction | Import | Included | Json | Nest | None | Prefix | Propagate | Protected | Version),
+			ProcessingMode::JsonLd1_1 => true
+		}
+	}
+
 	pub fn into_str(self) -> &'static str {
 		use Keyword::*;
 		match self {
@@ -181,6 +234,26 @@ impl<'a> TryFrom<&'a str> for Keyword {
 	}
 }
 
+/// Error returned when parsing a string that is not a keyword.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InvalidKeyword(pub String);
+
+impl fmt::Display for InvalidKeyword {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid keyword `{}`", self.0)
+	}
+}
+
+impl std::error::Error for InvalidKeyword {}
+
+impl FromStr for Keyword {
+	type Err = InvalidKeyword;
+
+	fn from_str(str: &str) -> Result<Keyword, InvalidKeyword> {
+		Keyword::try_from(str).map_err(|str| InvalidKeyword(str.to_string()))
+	}
+}
+
 impl From<Keyword> for &'static str {
 	fn from(k: Keyword) -> &'static str {
 		k.into_str()
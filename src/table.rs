@@ -0,0 +1,548 @@
+//! Tabular projection.
+//!
+//! A [`Projection`] flattens the nodes of an expanded document into a [`Table`]: each row is a
+//! node, and each column the objects reached by following a [property path](crate::path) from
+//! this node, converted into a typed [`Cell`].
+//! The projected nodes are the top-level nodes of the document, or, if the projection has
+//! types, every node of the document having one of these types.
+//!
+//! A projection can be built column by column:
+//! ```ignore
+//! let projection = Projection::new()
+//!     .with_type(schema_person)
+//!     .column("name", Path::new().prop(schema_name))
+//!     .column("employer", Path::parse("worksFor/name", &context)?);
+//! let table = projection.project(&document);
+//! let csv = table.to_csv();
+//! ```
+//! or from a frame, listing the types of the projected nodes and their properties.
+//! Nested properties are joined with a `.` in the column names:
+//! ```json
+//! {
+//!   "@type": "Person",
+//!   "name": {},
+//!   "worksFor": { "name": {}, "url": {} }
+//! }
+//! ```
+
+use std::fmt;
+use std::collections::HashSet;
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Id,
+	Reference,
+	Lenient,
+	Indexed,
+	Object,
+	Node,
+	Value,
+	Context,
+	ExpandedDocument,
+	expansion,
+	object::Literal,
+	path::{
+		Error,
+		Path,
+		Step,
+		Selection,
+		DocumentIndex
+	},
+	syntax::Term
+};
+
+/// Table cell.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Cell {
+	/// No value.
+	Null,
+
+	/// Boolean value.
+	Boolean(bool),
+
+	/// Integer value, from a native integer or an `xsd` integer literal.
+	Integer(i64),
+
+	/// Other number.
+	Number(f64),
+
+	/// String value, or JSON literal.
+	String(String),
+
+	/// Node identifier.
+	Id(String),
+
+	/// Multiple values, or list.
+	Many(Vec<Cell>)
+}
+
+impl Cell {
+	/// Checks if the cell is empty.
+	pub fn is_null(&self) -> bool {
+		matches!(self, Cell::Null)
+	}
+
+	fn from_selections<T: Id>(selections: Vec<Selection<T>>) -> Cell {
+		let mut cells: Vec<_> = selections.into_iter().map(Cell::from_selection).collect();
+		match cells.len() {
+			0 => Cell::Null,
+			1 => cells.pop().unwrap(),
+			_ => Cell::Many(cells)
+		}
+	}
+
+	fn from_selection<T: Id>(selection: Selection<T>) -> Cell {
+		match selection {
+			Selection::Node(node) => Cell::from_node(node),
+			Selection::Value(value) => Cell::from_value(value),
+			Selection::List(items) => Cell::from_list(items)
+		}
+	}
+
+	fn from_object<T: Id>(object: &Object<T>) -> Cell {
+		match object {
+			Object::Node(node) => Cell::from_node(node),
+			Object::Value(value) => Cell::from_value(value),
+			Object::List(items) => Cell::from_list(items)
+		}
+	}
+
+	fn from_node<T: Id>(node: &Node<T>) -> Cell {
+		match node.id() {
+			Some(Lenient::Ok(id)) => Cell::Id(id.as_str().to_string()),
+			_ => Cell::Null
+		}
+	}
+
+	fn from_list<T: Id>(items: &[Indexed<Object<T>>]) -> Cell {
+		Cell::Many(items.iter().map(|item| Cell::from_object(item)).collect())
+	}
+
+	fn from_value<T: Id>(value: &Value<T>) -> Cell {
+		if let Some(i) = value.as_integer() {
+			return Cell::Integer(i)
+		}
+
+		match value {
+			Value::Literal(Literal::Null, _) => Cell::Null,
+			Value::Literal(Literal::Boolean(b), _) => Cell::Boolean(*b),
+			Value::Literal(Literal::Number(n), _) => Cell::Number((*n).into()),
+			Value::Literal(Literal::String(s), _) => Cell::String(s.clone()),
+			Value::Literal(Literal::Json(json), _) => Cell::String(json.to_string()),
+			Value::LangString(str) => Cell::String(str.as_str().to_string())
+		}
+	}
+}
+
+/// Cells are displayed as they appear in CSV output.
+///
+/// Multiple values are separated by `; `.
+impl fmt::Display for Cell {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Cell::Null => Ok(()),
+			Cell::Boolean(b) => b.fmt(f),
+			Cell::Integer(i) => i.fmt(f),
+			Cell::Number(n) => n.fmt(f),
+			Cell::String(s) => s.fmt(f),
+			Cell::Id(id) => id.fmt(f),
+			Cell::Many(cells) => {
+				for (i, cell) in cells.iter().enumerate() {
+					if i > 0 {
+						write!(f, "; ")?
+					}
+
+					cell.fmt(f)?
+				}
+
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Table column.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Column<T: Id = IriBuf> {
+	/// Name of the column.
+	pub name: String,
+
+	/// Path from the projected node to the column values.
+	pub path: Path<T>
+}
+
+/// Projection of nodes into rows.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Projection<T: Id = IriBuf> {
+	types: Vec<Reference<T>>,
+	columns: Vec<Column<T>>
+}
+
+impl<T: Id> Projection<T> {
+	/// Create a new projection of the top-level nodes, without columns.
+	pub fn new() -> Projection<T> {
+		Projection {
+			types: Vec::new(),
+			columns: Vec::new()
+		}
+	}
+
+	/// Build a projection from a frame, expanding its entries with the given active context.
+	///
+	/// The `@type` entry of the frame gives the types of the projected nodes,
+	/// and each property entry a column, or nested columns if its value is a non empty frame.
+	/// If the frame has an `@id` entry, the first column, named `@id`, holds the node
+	/// identifiers.
+	/// Other keywords are ignored.
+	pub fn from_frame<C: Context<T>>(frame: &JsonValue, context: &C) -> Result<Projection<T>, Error> {
+		let mut projection = Projection::new();
+		if let JsonValue::Object(frame) = frame {
+			if frame.get("@id").is_some() {
+				projection = projection.column("@id", Path::new())
+			}
+
+			match frame.get("@type") {
+				Some(JsonValue::Array(types)) => {
+					for ty in types {
+						projection = projection.with_type(frame_type(ty, context)?)
+					}
+				},
+				Some(ty) => projection = projection.with_type(frame_type(ty, context)?),
+				None => ()
+			}
+		}
+
+		projection.add_frame_columns(None, &[], frame, context)?;
+		Ok(projection)
+	}
+
+	fn add_frame_columns<C: Context<T>>(&mut self, prefix: Option<&str>, steps: &[Step<T>], frame: &JsonValue, context: &C) -> Result<(), Error> {
+		if let JsonValue::Object(frame) = frame {
+			for (key, value) in frame.iter() {
+				if key.starts_with('@') {
+					continue
+				}
+
+				let name = match prefix {
+					Some(prefix) => format!("{}.{}", prefix, key),
+					None => key.to_string()
+				};
+
+				let mut path_steps = steps.to_vec();
+				path_steps.extend(Path::parse(key, context)?.steps().iter().cloned());
+
+				let nested = match value {
+					JsonValue::Object(nested) => nested.iter().any(|(key, _)| !key.starts_with('@')),
+					_ => false
+				};
+
+				if nested {
+					self.add_frame_columns(Some(&name), &path_steps, value, context)?
+				} else {
+					let path = path_steps.into_iter().fold(Path::new(), |path, step| match step {
+						Step::Forward(prop) => path.prop(prop),
+						Step::Reverse(prop) => path.reverse(prop)
+					});
+
+					self.columns.push(Column { name, path })
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Only project the nodes having the given type, or one of the other types of the
+	/// projection.
+	pub fn with_type<R: Into<Reference<T>>>(mut self, ty: R) -> Self {
+		self.types.push(ty.into());
+		self
+	}
+
+	/// Append a column.
+	pub fn column<N: Into<String>>(mut self, name: N, path: Path<T>) -> Self {
+		self.columns.push(Column { name: name.into(), path });
+		self
+	}
+
+	/// Types of the projected nodes.
+	///
+	/// If empty, the top-level nodes are projected.
+	pub fn types(&self) -> &[Reference<T>] {
+		&self.types
+	}
+
+	/// Columns.
+	pub fn columns(&self) -> &[Column<T>] {
+		&self.columns
+	}
+
+	/// Project the nodes of the given document into a table.
+	///
+	/// Rows appear in the order of the (first description of the) nodes in the document.
+	pub fn rows(&self, document: &ExpandedDocument<T>) -> Vec<Vec<Cell>> {
+		let index = DocumentIndex::new(document);
+		let mut nodes = Vec::new();
+		let mut ids = HashSet::new();
+		for object in document {
+			if self.types.is_empty() {
+				if let Object::Node(node) = object.inner() {
+					add_row(&mut nodes, &mut ids, node)
+				}
+			} else {
+				self.collect_object(&mut nodes, &mut ids, object)
+			}
+		}
+
+		nodes.into_iter().map(|node| {
+			self.columns.iter().map(|column| {
+				Cell::from_selections(index.select(column.path.steps(), vec![index.description(node)]))
+			}).collect()
+		}).collect()
+	}
+
+	/// Project the nodes of the given document into a table.
+	pub fn project(&self, document: &ExpandedDocument<T>) -> Table {
+		Table {
+			columns: self.columns.iter().map(|column| column.name.clone()).collect(),
+			rows: self.rows(document)
+		}
+	}
+
+	fn collect_object<'a>(&self, nodes: &mut Vec<&'a Node<T>>, ids: &mut HashSet<&'a Reference<T>>, object: &'a Object<T>) {
+		match object {
+			Object::Node(node) => self.collect_node(nodes, ids, node),
+			Object::List(items) => {
+				for item in items {
+					self.collect_object(nodes, ids, item)
+				}
+			},
+			Object::Value(_) => ()
+		}
+	}
+
+	fn collect_node<'a>(&self, nodes: &mut Vec<&'a Node<T>>, ids: &mut HashSet<&'a Reference<T>>, node: &'a Node<T>) {
+		if node.types().iter().any(|ty| matches!(ty, Lenient::Ok(ty) if self.types.contains(ty))) {
+			add_row(nodes, ids, node)
+		}
+
+		if let Some(included) = node.included() {
+			for node in included {
+				self.collect_node(nodes, ids, node)
+			}
+		}
+
+		for values in node.properties.values() {
+			for value in values {
+				self.collect_object(nodes, ids, value)
+			}
+		}
+
+		for subjects in node.reverse_properties.values() {
+			for subject in subjects {
+				self.collect_node(nodes, ids, subject)
+			}
+		}
+	}
+}
+
+impl<T: Id> Default for Projection<T> {
+	fn default() -> Projection<T> {
+		Self::new()
+	}
+}
+
+/// Add a node to the projected nodes, unless a node with the same identifier already is.
+fn add_row<'a, T: Id>(nodes: &mut Vec<&'a Node<T>>, ids: &mut HashSet<&'a Reference<T>>, node: &'a Node<T>) {
+	match node.id() {
+		Some(Lenient::Ok(id)) => {
+			if ids.insert(id) {
+				nodes.push(node)
+			}
+		},
+		_ => nodes.push(node)
+	}
+}
+
+fn frame_type<T: Id, C: Context<T>>(ty: &JsonValue, context: &C) -> Result<Reference<T>, Error> {
+	let ty = ty.as_str().ok_or_else(|| Error::InvalidStep(ty.dump()))?;
+	match expansion::expand_iri(context, ty, false, true) {
+		Lenient::Ok(Term::Ref(ty)) => Ok(ty),
+		_ => Err(Error::InvalidStep(ty.to_string()))
+	}
+}
+
+/// Table of projected nodes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+	columns: Vec<String>,
+	rows: Vec<Vec<Cell>>
+}
+
+impl Table {
+	/// Column names.
+	pub fn columns(&self) -> &[String] {
+		&self.columns
+	}
+
+	/// Rows, each having a cell per column.
+	pub fn rows(&self) -> &[Vec<Cell>] {
+		&self.rows
+	}
+
+	/// Number of rows.
+	pub fn len(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// Checks if the table has no row.
+	pub fn is_empty(&self) -> bool {
+		self.rows.is_empty()
+	}
+
+	/// Returns the rows.
+	pub fn into_rows(self) -> Vec<Vec<Cell>> {
+		self.rows
+	}
+
+	/// Write the table in the CSV format ([RFC 4180](https://tools.ietf.org/html/rfc4180)),
+	/// with a header line.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::new();
+		write_csv_line(&mut csv, self.columns.iter().map(String::as_str));
+		for row in &self.rows {
+			let cells: Vec<_> = row.iter().map(Cell::to_string).collect();
+			write_csv_line(&mut csv, cells.iter().map(String::as_str))
+		}
+
+		csv
+	}
+
+	/// Convert the table into an Arrow record batch, with a nullable field per column.
+	///
+	/// The type of each field is inferred from its cells: integers and numbers are mixed into
+	/// `Float64`, multiple values give a `List` (single values being lists of one item), and
+	/// other mixes of types, or columns without values, give `Utf8` strings, as displayed in
+	/// CSV output.
+	#[cfg(feature = "arrow")]
+	pub fn to_record_batch(&self) -> Result<arrow_array::RecordBatch, arrow_schema::ArrowError> {
+		let mut fields = Vec::new();
+		let mut arrays = Vec::new();
+		for (i, name) in self.columns.iter().enumerate() {
+			let cells: Vec<_> = self.rows.iter().map(|row| &row[i]).collect();
+			let data_type = arrow_type(cells.iter().copied());
+			arrays.push(arrow_array(&data_type, &cells)?);
+			fields.push(arrow_schema::Field::new(name, data_type, true))
+		}
+
+		arrow_array::RecordBatch::try_new(std::sync::Arc::new(arrow_schema::Schema::new(fields)), arrays)
+	}
+}
+
+/// Arrow type of the given cells.
+#[cfg(feature = "arrow")]
+fn arrow_type<'a, I: Iterator<Item = &'a Cell>>(cells: I) -> arrow_schema::DataType {
+	use arrow_schema::DataType;
+	let mut data_type = None;
+	for cell in cells {
+		let cell_type = match cell {
+			Cell::Null => continue,
+			Cell::Boolean(_) => DataType::Boolean,
+			Cell::Integer(_) => DataType::Int64,
+			Cell::Number(_) => DataType::Float64,
+			Cell::String(_) | Cell::Id(_) => DataType::Utf8,
+			Cell::Many(cells) => arrow_list_type(arrow_type(cells.iter()))
+		};
+
+		data_type = Some(match data_type {
+			Some(data_type) => arrow_unify(data_type, cell_type),
+			None => cell_type
+		})
+	}
+
+	data_type.unwrap_or(DataType::Utf8)
+}
+
+#[cfg(feature = "arrow")]
+fn arrow_list_type(item_type: arrow_schema::DataType) -> arrow_schema::DataType {
+	arrow_schema::DataType::List(std::sync::Arc::new(arrow_schema::Field::new_list_field(item_type, true)))
+}
+
+/// Arrow type holding the values of both types.
+#[cfg(feature = "arrow")]
+fn arrow_unify(a: arrow_schema::DataType, b: arrow_schema::DataType) -> arrow_schema::DataType {
+	use arrow_schema::DataType::*;
+	match (a, b) {
+		(a, b) if a == b => a,
+		(Int64, Float64) | (Float64, Int64) => Float64,
+		(List(a), List(b)) => arrow_list_type(arrow_unify(a.data_type().clone(), b.data_type().clone())),
+		(List(a), b) | (b, List(a)) => arrow_list_type(arrow_unify(a.data_type().clone(), b)),
+		_ => Utf8
+	}
+}
+
+/// Build the Arrow array of the given cells, of the type given by [`arrow_type`].
+#[cfg(feature = "arrow")]
+fn arrow_array(data_type: &arrow_schema::DataType, cells: &[&Cell]) -> Result<arrow_array::ArrayRef, arrow_schema::ArrowError> {
+	use std::sync::Arc;
+	use arrow_array::{BooleanArray, Int64Array, Float64Array, StringArray, ListArray};
+	use arrow_schema::DataType;
+
+	Ok(match data_type {
+		DataType::Boolean => Arc::new(cells.iter().map(|cell| match cell {
+			Cell::Boolean(b) => Some(*b),
+			_ => None
+		}).collect::<BooleanArray>()),
+		DataType::Int64 => Arc::new(cells.iter().map(|cell| match cell {
+			Cell::Integer(i) => Some(*i),
+			_ => None
+		}).collect::<Int64Array>()),
+		DataType::Float64 => Arc::new(cells.iter().map(|cell| match cell {
+			Cell::Integer(i) => Some(*i as f64),
+			Cell::Number(n) => Some(*n),
+			_ => None
+		}).collect::<Float64Array>()),
+		DataType::List(field) => {
+			let mut items = Vec::new();
+			let mut lengths = Vec::new();
+			let mut valid = Vec::new();
+			for cell in cells {
+				let len = items.len();
+				match cell {
+					Cell::Null => (),
+					Cell::Many(cells) => items.extend(cells.iter()),
+					cell => items.push(*cell)
+				}
+
+				lengths.push(items.len() - len);
+				valid.push(!cell.is_null())
+			}
+
+			let values = arrow_array(field.data_type(), &items)?;
+			Arc::new(ListArray::try_new(field.clone(), arrow_buffer::OffsetBuffer::from_lengths(lengths), values, Some(valid.into()))?)
+		},
+		_ => Arc::new(cells.iter().map(|cell| if cell.is_null() {
+			None
+		} else {
+			Some(cell.to_string())
+		}).collect::<StringArray>())
+	})
+}
+
+fn write_csv_line<'a, I: Iterator<Item = &'a str>>(csv: &mut String, fields: I) {
+	for (i, field) in fields.enumerate() {
+		if i > 0 {
+			csv.push(',')
+		}
+
+		if field.contains([',', '"', '\r', '\n']) {
+			csv.push('"');
+			csv.push_str(&field.replace('"', "\"\""));
+			csv.push('"')
+		} else {
+			csv.push_str(field)
+		}
+	}
+
+	csv.push_str("\r\n")
+}
@@ -0,0 +1,225 @@
+//! Term usage analytics.
+//!
+//! Vocabulary maintainers need to know which terms of their contexts are actually used by a
+//! corpus, to prune them, and which keys silently expand through the vocabulary mapping
+//! (`@vocab`) instead of a term definition, which often are typos.
+//! Expansion reports each entry key and node object type it expands to the
+//! [`term_used`](crate::Loader::term_used) method of the loader.
+//! A [`UsageRecorder`] wrapped around the loader collects these reports into a
+//! [`TermUsage`] report, across as many documents as needed.
+//!
+//! ```ignore
+//! let mut loader = UsageRecorder::new(FsLoader::new());
+//! for doc in corpus {
+//!     doc.expand(&context, &mut loader).await?;
+//! }
+//!
+//! let usage = loader.usage();
+//! println!("unused terms: {:?}", usage.unused(&context));
+//! println!("possible typos: {:?}", usage.vocab_terms());
+//! ```
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use futures::future::BoxFuture;
+use iref::Iri;
+use crate::{
+	Id,
+	Error,
+	Loader,
+	RemoteDocument,
+	Context,
+	context,
+	syntax::{
+		Keyword,
+		is_keyword_like
+	},
+	expansion::is_compact_iri
+};
+
+/// How a key or type has been expanded.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TermUse {
+	/// Through the term definition of the key.
+	Term,
+
+	/// As a compact IRI, through the definition of its prefix.
+	///
+	/// The reported term is the prefix.
+	Prefix,
+
+	/// Through the vocabulary mapping, the key not being a defined term.
+	Vocab,
+
+	/// The key is not a defined term, and there is no vocabulary mapping: it does not expand
+	/// into an IRI.
+	Undefined
+}
+
+/// Report the use of the given key or type, expanded using the vocabulary mapping, in the
+/// given active context.
+///
+/// Keywords, absolute IRIs and blank node identifiers are not reported, nor are
+/// document-relative values (types) resolved against the base IRI.
+pub(crate) fn report<T: Id, C: Context<T>, L: context::Loader>(loader: &mut L, active_context: &C, value: &str, document_relative: bool) {
+	if Keyword::try_from(value).is_ok() || is_keyword_like(value) {
+		return
+	}
+
+	if active_context.get(value).is_some() {
+		loader.term_used(value, TermUse::Term)
+	} else if is_compact_iri(active_context, value, true) {
+		if let Some(index) = value.find(':') {
+			loader.term_used(&value[..index], TermUse::Prefix)
+		}
+	} else if !value.contains(':') {
+		if active_context.vocabulary().is_some() {
+			loader.term_used(value, TermUse::Vocab)
+		} else if !document_relative {
+			loader.term_used(value, TermUse::Undefined)
+		}
+	}
+}
+
+/// Term usage report.
+#[derive(Clone, Default, Debug)]
+pub struct TermUsage {
+	/// Number of uses of each term definition, including as a prefix.
+	terms: HashMap<String, usize>,
+
+	/// Number of uses of each key expanded through the vocabulary mapping.
+	vocab: HashMap<String, usize>,
+
+	/// Number of uses of each key that does not expand into an IRI.
+	undefined: HashMap<String, usize>
+}
+
+/// Sort usage counts by decreasing count, then by term.
+fn sorted(counts: &HashMap<String, usize>) -> Vec<(&str, usize)> {
+	let mut counts: Vec<_> = counts.iter().map(|(term, count)| (term.as_str(), *count)).collect();
+	counts.sort_by(|(a, m), (b, n)| n.cmp(m).then(a.cmp(b)));
+	counts
+}
+
+/// Add the given counts to the others.
+fn add(counts: &mut HashMap<String, usize>, other: &HashMap<String, usize>) {
+	for (term, count) in other {
+		*counts.entry(term.clone()).or_default() += count
+	}
+}
+
+impl TermUsage {
+	/// Create an empty report.
+	pub fn new() -> TermUsage {
+		TermUsage::default()
+	}
+
+	/// Record one use of the given term.
+	pub fn record(&mut self, term: &str, usage: TermUse) {
+		let counts = match usage {
+			TermUse::Term | TermUse::Prefix => &mut self.terms,
+			TermUse::Vocab => &mut self.vocab,
+			TermUse::Undefined => &mut self.undefined
+		};
+
+		*counts.entry(term.to_string()).or_default() += 1
+	}
+
+	/// Checks if nothing has been recorded.
+	pub fn is_empty(&self) -> bool {
+		self.terms.is_empty() && self.vocab.is_empty() && self.undefined.is_empty()
+	}
+
+	/// Number of uses of the definition of the given term, including as a prefix.
+	pub fn count(&self, term: &str) -> usize {
+		self.terms.get(term).copied().unwrap_or(0)
+	}
+
+	/// Used term definitions, with their number of uses, most used first.
+	pub fn terms(&self) -> Vec<(&str, usize)> {
+		sorted(&self.terms)
+	}
+
+	/// Keys expanded through the vocabulary mapping rather than a term definition, with their
+	/// number of uses, most used first.
+	///
+	/// Rarely used keys are likely typos of defined terms.
+	pub fn vocab_terms(&self) -> Vec<(&str, usize)> {
+		sorted(&self.vocab)
+	}
+
+	/// Keys that did not expand into an IRI (and have been dropped), with their number of
+	/// uses, most used first.
+	pub fn undefined_terms(&self) -> Vec<(&str, usize)> {
+		sorted(&self.undefined)
+	}
+
+	/// Terms defined by the given context that have never been used, sorted.
+	pub fn unused<T: Id, C: Context<T>>(&self, context: &C) -> Vec<String> {
+		let mut unused: Vec<String> = context.definitions().map(|(term, _)| term).filter(|term| !self.terms.contains_key(term.as_str())).cloned().collect();
+		unused.sort();
+		unused
+	}
+
+	/// Add the counts of another report.
+	pub fn merge(&mut self, other: &TermUsage) {
+		add(&mut self.terms, &other.terms);
+		add(&mut self.vocab, &other.vocab);
+		add(&mut self.undefined, &other.undefined)
+	}
+}
+
+/// Loader recording term usage.
+///
+/// Documents are loaded by the inner loader.
+pub struct UsageRecorder<L> {
+	inner: L,
+	usage: TermUsage
+}
+
+impl<L> UsageRecorder<L> {
+	/// Create a new recorder on top of the given loader.
+	pub fn new(inner: L) -> UsageRecorder<L> {
+		UsageRecorder {
+			inner,
+			usage: TermUsage::new()
+		}
+	}
+
+	/// Returns the usage recorded so far.
+	pub fn usage(&self) -> &TermUsage {
+		&self.usage
+	}
+
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.inner
+	}
+
+	/// Clear the recorded usage.
+	pub fn clear(&mut self) {
+		self.usage = TermUsage::new()
+	}
+
+	/// Consumes the recorder and returns the inner loader and recorded usage.
+	pub fn into_parts(self) -> (L, TermUsage) {
+		(self.inner, self.usage)
+	}
+}
+
+impl<L: Loader> Loader for UsageRecorder<L> {
+	type Document = L::Document;
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		self.inner.load(url)
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.inner.is_cancelled()
+	}
+
+	fn term_used(&mut self, term: &str, usage: TermUse) {
+		self.usage.record(term, usage);
+		self.inner.term_used(term, usage)
+	}
+}
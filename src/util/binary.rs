@@ -0,0 +1,123 @@
+use std::io;
+
+/// Error returned when decoding invalid data.
+pub fn invalid_data() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "invalid precompiled context")
+}
+
+/// Binary encoder.
+///
+/// Lengths are encoded as little-endian 32-bit integers, strings as their length followed by
+/// their UTF-8 bytes.
+pub struct Encoder {
+	bytes: Vec<u8>
+}
+
+impl Encoder {
+	/// Start encoding with the given header.
+	pub fn new(header: &[u8]) -> Encoder {
+		Encoder {
+			bytes: header.to_vec()
+		}
+	}
+
+	pub fn u8(&mut self, value: u8) {
+		self.bytes.push(value)
+	}
+
+	pub fn bool(&mut self, value: bool) {
+		self.u8(value as u8)
+	}
+
+	pub fn len(&mut self, len: usize) {
+		self.bytes.extend_from_slice(&(len as u32).to_le_bytes())
+	}
+
+	pub fn str(&mut self, value: &str) {
+		self.len(value.len());
+		self.bytes.extend_from_slice(value.as_bytes())
+	}
+
+	/// Encode an optional string, prefixed by a presence flag.
+	pub fn opt_str(&mut self, value: Option<&str>) {
+		match value {
+			Some(value) => {
+				self.bool(true);
+				self.str(value)
+			},
+			None => self.bool(false)
+		}
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+}
+
+/// Binary decoder, reading the output of an [`Encoder`].
+pub struct Decoder<'a> {
+	bytes: &'a [u8]
+}
+
+impl<'a> Decoder<'a> {
+	/// Start decoding, checking the given header.
+	pub fn new(bytes: &'a [u8], header: &[u8]) -> io::Result<Decoder<'a>> {
+		if bytes.starts_with(header) {
+			Ok(Decoder {
+				bytes: &bytes[header.len()..]
+			})
+		} else {
+			Err(invalid_data())
+		}
+	}
+
+	fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+		if self.bytes.len() < len {
+			return Err(invalid_data())
+		}
+
+		let (taken, rest) = self.bytes.split_at(len);
+		self.bytes = rest;
+		Ok(taken)
+	}
+
+	pub fn u8(&mut self) -> io::Result<u8> {
+		Ok(self.take(1)?[0])
+	}
+
+	pub fn bool(&mut self) -> io::Result<bool> {
+		match self.u8()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(invalid_data())
+		}
+	}
+
+	pub fn len(&mut self) -> io::Result<usize> {
+		let mut bytes = [0; 4];
+		bytes.copy_from_slice(self.take(4)?);
+		Ok(u32::from_le_bytes(bytes) as usize)
+	}
+
+	pub fn str(&mut self) -> io::Result<&'a str> {
+		let len = self.len()?;
+		std::str::from_utf8(self.take(len)?).map_err(|_| invalid_data())
+	}
+
+	pub fn opt_str(&mut self) -> io::Result<Option<&'a str>> {
+		if self.bool()? {
+			Ok(Some(self.str()?))
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Checks that every byte has been decoded.
+	pub fn end(self) -> io::Result<()> {
+		if self.bytes.is_empty() {
+			Ok(())
+		} else {
+			Err(invalid_data())
+		}
+	}
+}
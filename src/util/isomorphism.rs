@@ -0,0 +1,310 @@
+use std::hash::{Hash, Hasher};
+use std::collections::{HashSet, HashMap, hash_map::DefaultHasher};
+use iref::IriBuf;
+use json::JsonValue;
+use crate::{
+	Id,
+	BlankId,
+	Reference,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	context::Loader,
+	generator,
+	rdf::{
+		self,
+		Quad,
+		Term
+	}
+};
+
+/// Compare two JSON-LD documents for semantic equality.
+///
+/// Both documents are expanded (without base URL) and compared with [`assert_expanded_eq`].
+/// Without loader, [`NoLoader`](crate::NoLoader) is used.
+///
+/// ```ignore
+/// assert_expanded_eq!(json::parse(expected)?, json::parse(found)?);
+/// assert_expanded_eq!(expected, found, &mut loader);
+/// ```
+#[macro_export]
+macro_rules! assert_expanded_eq {
+	($expected:expr, $found:expr $(,)?) => {
+		$crate::util::assert_expanded_eq(&$expected, &$found, &mut $crate::NoLoader)
+	};
+	($expected:expr, $found:expr, $loader:expr $(,)?) => {
+		$crate::util::assert_expanded_eq(&$expected, &$found, $loader)
+	};
+}
+
+/// Expand two JSON-LD documents and checks that they are equal up to blank node renaming.
+///
+/// ## Panics
+///
+/// Panics if one of the documents cannot be expanded, or if they are not isomorphic.
+/// The panic message lists the N-Quads of both documents, marking the statements only found
+/// in the expected document with `-` and the statements only found in the other with `+`.
+pub fn assert_expanded_eq<L: Send + Sync + Loader>(expected: &JsonValue, found: &JsonValue, loader: &mut L) where JsonValue: From<L::Output>, L::Output: Into<JsonValue> {
+	let context: JsonContext<IriBuf> = JsonContext::new(None);
+	let expected = futures::executor::block_on(expected.expand(&context, loader)).unwrap_or_else(|e| panic!("unable to expand the expected document: {}", e));
+	let found = futures::executor::block_on(found.expand(&context, loader)).unwrap_or_else(|e| panic!("unable to expand the document: {}", e));
+	assert_isomorphic(&expected, &found)
+}
+
+/// Checks that two expanded documents are equal up to blank node renaming.
+///
+/// ## Panics
+///
+/// Panics if the documents are not isomorphic, with a diff of their N-Quads.
+pub fn assert_isomorphic<T: Id>(expected: &ExpandedDocument<T>, found: &ExpandedDocument<T>) {
+	let expected = quads(expected);
+	let found = quads(found);
+	if !isomorphic(&expected, &found) {
+		panic!("expanded documents are not isomorphic\n{}", diff(&expected, &found))
+	}
+}
+
+/// Checks if two expanded documents are equal up to blank node renaming.
+///
+/// Documents are compared through their RDF serialization (as generalized RDF, with the base
+/// direction of strings as compound literals), so that the order of unordered values and the
+/// way nodes are nested do not matter.
+pub fn is_isomorphic<T: Id>(a: &ExpandedDocument<T>, b: &ExpandedDocument<T>) -> bool {
+	isomorphic(&quads(a), &quads(b))
+}
+
+fn quads<T: Id>(document: &ExpandedDocument<T>) -> Vec<Quad<T>> {
+	let options = rdf::Options {
+		produce_generalized_rdf: true,
+		rdf_direction: Some(rdf::RdfDirection::CompoundLiteral),
+		exact_numbers: true
+	};
+
+	rdf::to_rdf(document, generator::Blank::new(), options).unwrap_or_else(|e| panic!("unable to serialize the expanded document: {}", e))
+}
+
+/// Merge the sorted N-Quads of both datasets, marking the differences.
+fn diff<T: Id>(expected: &[Quad<T>], found: &[Quad<T>]) -> String {
+	let mut expected: Vec<String> = expected.iter().map(|quad| quad.to_string()).collect();
+	let mut found: Vec<String> = found.iter().map(|quad| quad.to_string()).collect();
+	expected.sort();
+	expected.dedup();
+	found.sort();
+	found.dedup();
+
+	let mut out = String::new();
+	let (mut i, mut j) = (0, 0);
+	while i < expected.len() || j < found.len() {
+		if j >= found.len() || (i < expected.len() && expected[i] < found[j]) {
+			out.push_str(&format!("- {}\n", expected[i]));
+			i += 1
+		} else if i >= expected.len() || found[j] < expected[i] {
+			out.push_str(&format!("+ {}\n", found[j]));
+			j += 1
+		} else {
+			out.push_str(&format!("  {}\n", expected[i]));
+			i += 1;
+			j += 1
+		}
+	}
+
+	out
+}
+
+fn blank_id<T: Id>(r: &Reference<T>) -> Option<&BlankId> {
+	match r {
+		Reference::Blank(id) => Some(id),
+		Reference::Id(_) => None
+	}
+}
+
+/// Blank nodes of the given quad.
+fn blank_nodes<T: Id>(quad: &Quad<T>) -> impl Iterator<Item = &BlankId> {
+	let object = match &quad.object {
+		Term::Ref(r) => blank_id(r),
+		Term::Literal(_) => None
+	};
+
+	blank_id(&quad.subject).into_iter()
+		.chain(blank_id(&quad.predicate))
+		.chain(object)
+		.chain(quad.graph.as_ref().and_then(blank_id))
+}
+
+/// Checks if two RDF datasets are isomorphic: equal up to a renaming of their blank nodes.
+pub fn isomorphic<T: Id>(a: &[Quad<T>], b: &[Quad<T>]) -> bool {
+	let a: HashSet<&Quad<T>> = a.iter().collect();
+	let b: HashSet<&Quad<T>> = b.iter().collect();
+	if a.len() != b.len() {
+		return false
+	}
+
+	// Statements without blank nodes must be the same.
+	let (a_ground, a): (Vec<&Quad<T>>, Vec<&Quad<T>>) = a.into_iter().partition(|quad| blank_nodes(quad).next().is_none());
+	if a_ground.iter().any(|quad| !b.contains(quad)) {
+		return false
+	}
+
+	let b: Vec<&Quad<T>> = b.into_iter().filter(|quad| blank_nodes(quad).next().is_some()).collect();
+	if a.len() != b.len() {
+		return false
+	}
+
+	let a_colors = colors(&a);
+	let b_colors = colors(&b);
+
+	let mut a_classes: Vec<u64> = a_colors.values().cloned().collect();
+	let mut b_classes: Vec<u64> = b_colors.values().cloned().collect();
+	a_classes.sort_unstable();
+	b_classes.sort_unstable();
+	if a_classes != b_classes {
+		return false
+	}
+
+	// Map the blank nodes with the fewest candidates first.
+	let class_len = |color: u64| a_classes.iter().filter(|c| **c == color).count();
+	let mut blanks: Vec<&BlankId> = a_colors.keys().cloned().collect();
+	blanks.sort_by_key(|id| (class_len(a_colors[id]), id.as_str()));
+
+	let mut a_quads: HashMap<&BlankId, Vec<&Quad<T>>> = HashMap::new();
+	for quad in &a {
+		for id in blank_nodes(quad) {
+			a_quads.entry(id).or_default().push(quad)
+		}
+	}
+
+	let b_set: HashSet<&Quad<T>> = b.iter().cloned().collect();
+	let search = Search {
+		blanks: &blanks,
+		a_colors: &a_colors,
+		b_colors: &b_colors,
+		a_quads: &a_quads,
+		b: &b_set
+	};
+
+	search.run(0, &mut HashMap::new(), &mut HashSet::new())
+}
+
+/// Color of each blank node, refined from the statements it appears in and the colors of its
+/// neighbours until it distinguishes as many blank nodes as possible.
+fn colors<'a, T: Id>(quads: &[&'a Quad<T>]) -> HashMap<&'a BlankId, u64> {
+	let mut colors: HashMap<&'a BlankId, u64> = HashMap::new();
+	for quad in quads {
+		for id in blank_nodes(quad) {
+			colors.insert(id, 0);
+		}
+	}
+
+	let mut classes = 1;
+	for _ in 0..=colors.len() {
+		let mut signatures: HashMap<&'a BlankId, Vec<String>> = HashMap::new();
+		for quad in quads {
+			for id in blank_nodes(quad) {
+				signatures.entry(id).or_default().push(signature(quad, id, &colors))
+			}
+		}
+
+		let refined: HashMap<&'a BlankId, u64> = signatures.into_iter().map(|(id, mut signature)| {
+			signature.sort();
+			signature.dedup();
+			let mut hasher = DefaultHasher::new();
+			colors[id].hash(&mut hasher);
+			signature.hash(&mut hasher);
+			(id, hasher.finish())
+		}).collect();
+
+		let refined_classes = refined.values().collect::<HashSet<_>>().len();
+		colors = refined;
+		if refined_classes == classes {
+			break
+		}
+
+		classes = refined_classes
+	}
+
+	colors
+}
+
+/// Representation of a statement from the point of view of one of its blank nodes.
+fn signature<T: Id>(quad: &Quad<T>, id: &BlankId, colors: &HashMap<&BlankId, u64>) -> String {
+	let reference = |r: &Reference<T>| match r {
+		Reference::Blank(other) if other == id => "_:".to_string(),
+		Reference::Blank(other) => format!("_:{}", colors[other]),
+		Reference::Id(iri) => format!("<{}>", iri.as_iri())
+	};
+
+	let object = match &quad.object {
+		Term::Ref(r) => reference(r),
+		Term::Literal(lit) => lit.to_string()
+	};
+
+	let graph = quad.graph.as_ref().map(reference).unwrap_or_default();
+	format!("{} {} {} {}", reference(&quad.subject), reference(&quad.predicate), object, graph)
+}
+
+/// Backtracking search of a blank node mapping.
+struct Search<'s, 'a, T: Id> {
+	blanks: &'s [&'a BlankId],
+	a_colors: &'s HashMap<&'a BlankId, u64>,
+	b_colors: &'s HashMap<&'a BlankId, u64>,
+	a_quads: &'s HashMap<&'a BlankId, Vec<&'a Quad<T>>>,
+	b: &'s HashSet<&'a Quad<T>>
+}
+
+impl<'s, 'a, T: Id> Search<'s, 'a, T> {
+	fn run(&self, i: usize, mapping: &mut HashMap<&'a BlankId, &'a BlankId>, used: &mut HashSet<&'a BlankId>) -> bool {
+		let id = match self.blanks.get(i) {
+			Some(id) => *id,
+			None => return true
+		};
+
+		let color = self.a_colors[id];
+		for (&candidate, &candidate_color) in self.b_colors {
+			if candidate_color != color || used.contains(candidate) {
+				continue
+			}
+
+			mapping.insert(id, candidate);
+			used.insert(candidate);
+
+			if self.consistent(id, mapping) && self.run(i + 1, mapping, used) {
+				return true
+			}
+
+			mapping.remove(id);
+			used.remove(candidate);
+		}
+
+		false
+	}
+
+	/// Checks that the statements of the given blank node whose blank nodes are all mapped
+	/// are in the other dataset.
+	fn consistent(&self, id: &BlankId, mapping: &HashMap<&'a BlankId, &'a BlankId>) -> bool {
+		self.a_quads[id].iter().all(|quad| match map_quad(quad, mapping) {
+			Some(mapped) => self.b.contains(&mapped),
+			None => true
+		})
+	}
+}
+
+/// Rename the blank nodes of the given quad, if they are all mapped.
+fn map_quad<T: Id>(quad: &Quad<T>, mapping: &HashMap<&BlankId, &BlankId>) -> Option<Quad<T>> {
+	let reference = |r: &Reference<T>| match r {
+		Reference::Blank(id) => mapping.get(id).map(|id| Reference::Blank((*id).clone())),
+		Reference::Id(_) => Some(r.clone())
+	};
+
+	Some(Quad {
+		subject: reference(&quad.subject)?,
+		predicate: reference(&quad.predicate)?,
+		object: match &quad.object {
+			Term::Ref(r) => Term::Ref(reference(r)?),
+			Term::Literal(lit) => Term::Literal(lit.clone())
+		},
+		graph: match &quad.graph {
+			Some(graph) => Some(reference(graph)?),
+			None => None
+		}
+	})
+}
@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 use json::JsonValue;
 
+/// Conversion into JSON.
+///
+/// The output is deterministic: map entries are sorted by key and set items are sorted by their
+/// JSON representation, so equal values always produce the same JSON document.
 pub trait AsJson {
 	fn as_json(&self) -> JsonValue;
 }
@@ -53,10 +57,29 @@ impl<T: AsJson> AsJson for HashSet<T> {
 			ary.push(item.as_json())
 		}
 
+		sort_json_items(&mut ary);
 		JsonValue::Array(ary)
 	}
 }
 
+/// Sort the items of a JSON-LD set by their JSON representation.
+pub fn sort_json_items(items: &mut [JsonValue]) {
+	items.sort_by_cached_key(|item| item.dump())
+}
+
+/// Build a JSON object from the given entries, sorted by key.
+pub fn sorted_json_object<K: AsRef<str>, I: IntoIterator<Item = (K, JsonValue)>>(entries: I) -> JsonValue {
+	let mut entries: Vec<_> = entries.into_iter().collect();
+	entries.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+
+	let mut obj = json::object::Object::with_capacity(entries.len());
+	for (key, value) in entries {
+		obj.insert(key.as_ref(), value)
+	}
+
+	JsonValue::Object(obj)
+}
+
 pub fn json_ld_eq(a: &JsonValue, b: &JsonValue) -> bool {
 	match (a, b) {
 		(JsonValue::Array(a), JsonValue::Array(b)) if a.len() == b.len() => {
@@ -5,7 +5,17 @@ use std::collections::{HashSet, HashMap, hash_map::DefaultHasher};
 use ::json::{JsonValue, number::Number};
 
 mod json;
+mod parse;
+mod timer;
+mod snapshot;
+mod binary;
+mod isomorphism;
 pub use self::json::*;
+pub use self::parse::*;
+pub use self::snapshot::*;
+pub use self::isomorphism::*;
+pub(crate) use self::timer::Timer;
+pub(crate) use self::binary::{Encoder, Decoder, invalid_data};
 
 pub fn as_array(json: &JsonValue) -> &[JsonValue] {
 	match json {
@@ -0,0 +1,105 @@
+use std::fmt;
+use json::JsonValue;
+
+/// JSON parsing error.
+#[derive(Debug)]
+pub enum ParseError {
+	/// Error of the `json` parser.
+	Json(json::Error),
+
+	/// Error of the `simd-json` parser.
+	#[cfg(feature = "simd-json")]
+	Simd(simd_json::Error)
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParseError::Json(e) => e.fmt(f),
+			#[cfg(feature = "simd-json")]
+			ParseError::Simd(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a JSON document.
+///
+/// This is the parser used by the loaders and the string entry points of the crate.
+/// With the `simd-json` feature, the document is parsed by `simd-json`, then converted into a
+/// `JsonValue` preserving the order of object entries, where non-integer numbers are written in
+/// their shortest form (`1.50` becomes `1.5`). Otherwise, this is `json::parse`.
+#[cfg(not(feature = "simd-json"))]
+pub fn parse_json(source: &str) -> Result<JsonValue, ParseError> {
+	json::parse(source).map_err(ParseError::Json)
+}
+
+/// Parse a JSON document.
+///
+/// This is the parser used by the loaders and the string entry points of the crate.
+/// With the `simd-json` feature, the document is parsed by `simd-json`, then converted into a
+/// `JsonValue` preserving the order of object entries, where non-integer numbers are written in
+/// their shortest form (`1.50` becomes `1.5`). Otherwise, this is `json::parse`.
+#[cfg(feature = "simd-json")]
+pub fn parse_json(source: &str) -> Result<JsonValue, ParseError> {
+	let mut bytes = source.as_bytes().to_vec();
+	let tape = simd_json::to_tape(&mut bytes).map_err(ParseError::Simd)?;
+	Ok(from_tape(&mut tape.0.iter()))
+}
+
+/// Convert a finite float into its shortest decimal representation.
+///
+/// The `f64` conversion of the `json` crate is not always the shortest, hence not always the
+/// number written in the source.
+#[cfg(any(feature = "simd-json", feature = "python", feature = "cbor-ld"))]
+pub(crate) fn json_float(n: f64) -> JsonValue {
+	let repr = format!("{:e}", n.abs());
+	let (digits, exponent) = repr.split_at(repr.find('e').unwrap());
+	let mut exponent: i16 = exponent[1..].parse().unwrap();
+	let digits = match digits.find('.') {
+		Some(dot) => {
+			exponent -= (digits.len() - dot - 1) as i16;
+			digits.replacen('.', "", 1)
+		},
+		None => digits.to_string()
+	};
+
+	json::number::Number::from_parts(n.is_sign_positive(), digits.parse().unwrap(), exponent).into()
+}
+
+/// Convert the next value of a `simd-json` tape.
+#[cfg(feature = "simd-json")]
+fn from_tape(nodes: &mut std::slice::Iter<simd_json::Node>) -> JsonValue {
+	use simd_json::{Node, StaticNode};
+	match nodes.next().expect("truncated tape") {
+		Node::String(s) => (*s).into(),
+		Node::Static(StaticNode::Null) => JsonValue::Null,
+		Node::Static(StaticNode::Bool(b)) => (*b).into(),
+		Node::Static(StaticNode::I64(n)) => json::number::Number::from_parts(*n >= 0, n.unsigned_abs(), 0).into(),
+		Node::Static(StaticNode::U64(n)) => (*n).into(),
+		Node::Static(StaticNode::F64(n)) => json_float(*n),
+		Node::Array { len, .. } => {
+			let mut ary = Vec::with_capacity(*len);
+			for _ in 0..*len {
+				ary.push(from_tape(nodes))
+			}
+
+			JsonValue::Array(ary)
+		},
+		Node::Object { len, .. } => {
+			let mut obj = json::object::Object::with_capacity(*len);
+			for _ in 0..*len {
+				match nodes.next() {
+					Some(Node::String(key)) => {
+						let value = from_tape(nodes);
+						obj.insert(key, value)
+					},
+					_ => panic!("invalid object key in tape")
+				}
+			}
+
+			JsonValue::Object(obj)
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+use super::AsJson;
+
+/// Environment variable that, when set, makes [`assert_snapshot`] overwrite existing snapshots.
+pub const UPDATE_SNAPSHOTS: &str = "JSON_LD_UPDATE_SNAPSHOTS";
+
+/// Snapshot of a value, for snapshot testing.
+///
+/// This is the pretty-printed JSON representation of the value, indented with two spaces and
+/// ending with a newline.
+/// Since [`AsJson`] is deterministic, equal values always produce the same snapshot,
+/// which can then be committed and diffed.
+pub fn snapshot<T: AsJson + ?Sized>(value: &T) -> String {
+	let mut snapshot = value.as_json().pretty(2);
+	snapshot.push('\n');
+	snapshot
+}
+
+/// Compare the snapshot of a value with the snapshot stored in the given file.
+///
+/// If the file does not exist, or the `JSON_LD_UPDATE_SNAPSHOTS` environment variable is set,
+/// the snapshot is written to the file instead.
+///
+/// ## Panics
+///
+/// Panics if the snapshots differ, or if the file cannot be read or written.
+pub fn assert_snapshot<T: AsJson + ?Sized, P: AsRef<Path>>(value: &T, path: P) {
+	let path = path.as_ref();
+	let found = snapshot(value);
+
+	if std::env::var_os(UPDATE_SNAPSHOTS).is_some() || !path.exists() {
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent).unwrap_or_else(|e| panic!("unable to create snapshot directory `{}`: {}", parent.display(), e))
+		}
+
+		std::fs::write(path, found).unwrap_or_else(|e| panic!("unable to write snapshot `{}`: {}", path.display(), e))
+	} else {
+		let expected = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("unable to read snapshot `{}`: {}", path.display(), e));
+		if expected != found {
+			panic!("snapshot `{}` does not match (set `{}` to update it)\n--- expected\n{}--- found\n{}", path.display(), UPDATE_SNAPSHOTS, expected, found)
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Elapsed time measurement, for log messages.
+///
+/// Time is not measured on `wasm32` targets, where `std::time::Instant` is not available.
+pub struct Timer {
+	#[cfg(not(target_arch = "wasm32"))]
+	start: std::time::Instant
+}
+
+impl Timer {
+	/// Start measuring time.
+	pub fn start() -> Timer {
+		Timer {
+			#[cfg(not(target_arch = "wasm32"))]
+			start: std::time::Instant::now()
+		}
+	}
+
+	/// Time elapsed since the timer started, or `None` if time is not measured.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn elapsed(&self) -> Option<std::time::Duration> {
+		Some(self.start.elapsed())
+	}
+
+	/// Time elapsed since the timer started, or `None` if time is not measured.
+	#[cfg(target_arch = "wasm32")]
+	pub fn elapsed(&self) -> Option<std::time::Duration> {
+		None
+	}
+}
+
+impl fmt::Display for Timer {
+	#[cfg(not(target_arch = "wasm32"))]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?}", self.start.elapsed())
+	}
+
+	#[cfg(target_arch = "wasm32")]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "n/a")
+	}
+}
@@ -0,0 +1,189 @@
+//! Verifiable Credentials helpers.
+//!
+//! This module provides the plumbing needed to sign and verify
+//! [Verifiable Credentials](https://www.w3.org/TR/vc-data-model/) around this JSON-LD processor:
+//! extracting the proofs of an expanded credential (or presentation), computing the canonical
+//! signing input of the document without its proofs, and re-inserting a proof once it is built.
+//!
+//! Proofs are found under the `https://w3id.org/security#proof` property of the top-level nodes.
+//! With the standard credentials context, each proof is stored in its own graph (the property
+//! has a `@graph` container), which is how [`insert_proof`] re-inserts it.
+//!
+//! ## Canonicalization
+//!
+//! The signing input is the canonical N-Quads serialization of the RDF dataset of the document:
+//! the document is serialized into RDF ([`rdf::to_rdf`]), then canonicalized with the
+//! [RDF Dataset Canonicalization algorithm](https://www.w3.org/TR/rdf-canon/) (URDNA2015,
+//! standardized as RDFC-1.0) by [`rdf::canonical_nquads`].
+//! Documents denoting the same dataset, whatever their form and blank node labels, have the same
+//! signing input.
+//!
+//! ```ignore
+//! let data = vc::signing_input(&credential)?;
+//! let options = vc::proof_signing_input(&vc::proofs(&credential)[0])?;
+//! ```
+
+use std::fmt;
+use iref::Iri;
+use crate::{
+	Id,
+	Reference,
+	Object,
+	Node,
+	ExpandedDocument,
+	generator,
+	rdf
+};
+
+/// `proof` property.
+pub const PROOF: &str = "https://w3id.org/security#proof";
+
+/// `proofValue` property.
+pub const PROOF_VALUE: &str = "https://w3id.org/security#proofValue";
+
+/// `jws` property.
+pub const JWS: &str = "https://w3id.org/security#jws";
+
+/// Proof insertion error.
+#[derive(Debug)]
+pub enum Error {
+	/// The document has no top-level node to attach the proof to.
+	NoSubject,
+
+	/// The document has multiple top-level nodes, and the proof target is ambiguous.
+	AmbiguousSubject
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::NoSubject => write!(f, "no node to attach the proof to"),
+			Error::AmbiguousSubject => write!(f, "multiple nodes to attach the proof to")
+		}
+	}
+}
+
+fn property<T: Id>(iri: &'static str) -> Reference<T> {
+	Reference::Id(T::from_iri(Iri::new(iri).unwrap()))
+}
+
+/// Collect the proof nodes of the given proof property value.
+///
+/// The value is either a graph containing the proof nodes, or the proof node itself.
+fn proof_nodes<T: Id>(object: &Object<T>) -> Vec<&Node<T>> {
+	match object {
+		Object::Node(node) => match node.graph() {
+			Some(graph) => graph.iter().filter_map(|object| object.as_node()).collect(),
+			None => vec![node]
+		},
+		_ => Vec::new()
+	}
+}
+
+fn into_proof_nodes<T: Id>(object: Object<T>) -> Vec<Node<T>> {
+	match object {
+		Object::Node(node) => match node.into_unnamed_graph() {
+			Ok(graph) => graph.into_iter().filter_map(|object| object.into_inner().into_node()).collect(),
+			Err(node) => vec![node]
+		},
+		_ => Vec::new()
+	}
+}
+
+/// Get the proofs of every top-level node of the document.
+pub fn proofs<T: Id>(document: &ExpandedDocument<T>) -> Vec<&Node<T>> {
+	let prop = property(PROOF);
+	let mut result = Vec::new();
+	for object in document {
+		if let Object::Node(node) = object.inner() {
+			for value in node.get(&prop) {
+				result.extend(proof_nodes(value))
+			}
+		}
+	}
+
+	result
+}
+
+/// Remove the proofs of every top-level node of the document, and return them.
+pub fn extract_proofs<T: Id>(document: &mut ExpandedDocument<T>) -> Vec<Node<T>> {
+	let prop = property(PROOF);
+	let mut result = Vec::new();
+	let objects: Vec<_> = document.drain().collect();
+	for mut object in objects {
+		if let Object::Node(node) = &mut *object {
+			if let Some(values) = node.properties.remove(&prop) {
+				for value in values {
+					result.extend(into_proof_nodes(value.into_inner()))
+				}
+			}
+		}
+
+		document.push(object);
+	}
+
+	result
+}
+
+/// Insert a proof into the document.
+///
+/// The proof is attached, in its own graph, to the unique top-level node of the document.
+pub fn insert_proof<T: Id>(document: &mut ExpandedDocument<T>, proof: Node<T>) -> Result<(), Error> {
+	let mut objects: Vec<_> = document.drain().collect();
+	let mut subjects = objects.iter_mut().filter(|object| object.is_node());
+	let result = match (subjects.next(), subjects.next()) {
+		(Some(subject), None) => {
+			if let Object::Node(node) = &mut **subject {
+				let mut graph = Node::new();
+				graph.set_graph(Some(std::iter::once(Object::Node(Box::new(proof)).into()).collect()));
+				node.insert(property(PROOF), Object::Node(Box::new(graph)).into())
+			}
+
+			Ok(())
+		},
+		(None, _) => Err(Error::NoSubject),
+		_ => Err(Error::AmbiguousSubject)
+	};
+
+	document.extend(objects);
+	result
+}
+
+/// Get the proof options of a proof, that is the proof without its value.
+///
+/// This removes the `proofValue` and `jws` properties of the proof.
+pub fn proof_options<T: Id>(proof: &Node<T>) -> Node<T> {
+	let mut options = proof.clone();
+	options.properties.remove(&property(PROOF_VALUE));
+	options.properties.remove(&property(JWS));
+	options
+}
+
+/// Compute the signing input of a document.
+///
+/// This is the canonical serialization of the document without its proofs.
+pub fn signing_input<T: Id>(document: &ExpandedDocument<T>) -> Result<String, crate::Error> {
+	let mut document = document.clone();
+	extract_proofs(&mut document);
+	canonicalize(&document)
+}
+
+/// Compute the signing input of the options of a proof.
+///
+/// This is the canonical serialization of the proof without its value.
+pub fn proof_signing_input<T: Id>(proof: &Node<T>) -> Result<String, crate::Error> {
+	let mut document = ExpandedDocument::new();
+	document.insert(Object::Node(Box::new(proof_options(proof))).into());
+	canonicalize(&document)
+}
+
+/// Canonical N-Quads serialization of an expanded document.
+///
+/// See the [module documentation](self) for the details of the serialization.
+/// Fails if the document cannot be serialized into RDF (see [`rdf::to_rdf`]).
+pub fn canonicalize<T: Id>(document: &ExpandedDocument<T>) -> Result<String, crate::Error> {
+	let quads = rdf::to_rdf(document, generator::Blank::new(), rdf::Options::default())?;
+	Ok(rdf::canonical_nquads(&quads))
+}
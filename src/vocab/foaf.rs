@@ -0,0 +1,40 @@
+//! FOAF (Friend of a Friend) vocabulary.
+
+vocabulary! {
+	"http://xmlns.com/foaf/0.1/" {
+		AGENT: "Agent",
+		PERSON: "Person",
+		ORGANIZATION: "Organization",
+		GROUP: "Group",
+		DOCUMENT: "Document",
+		IMAGE: "Image",
+		ONLINE_ACCOUNT: "OnlineAccount",
+		PROJECT: "Project",
+		NAME: "name",
+		GIVEN_NAME: "givenName",
+		FAMILY_NAME: "familyName",
+		NICK: "nick",
+		TITLE: "title",
+		MBOX: "mbox",
+		MBOX_SHA1SUM: "mbox_sha1sum",
+		HOMEPAGE: "homepage",
+		WEBLOG: "weblog",
+		DEPICTION: "depiction",
+		IMG: "img",
+		KNOWS: "knows",
+		MEMBER: "member",
+		AGE: "age",
+		BIRTHDAY: "birthday",
+		GENDER: "gender",
+		ACCOUNT: "account",
+		ACCOUNT_NAME: "accountName",
+		ACCOUNT_SERVICE_HOMEPAGE: "accountServiceHomepage",
+		INTEREST: "interest",
+		TOPIC_INTEREST: "topic_interest",
+		MADE: "made",
+		MAKER: "maker",
+		BASED_NEAR: "based_near",
+		PAGE: "page",
+		PRIMARY_TOPIC: "primaryTopic"
+	}
+}
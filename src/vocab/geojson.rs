@@ -0,0 +1,20 @@
+//! GeoJSON-LD vocabulary.
+
+vocabulary! {
+	"https://purl.org/geojson/vocab#" {
+		FEATURE: "Feature",
+		FEATURE_COLLECTION: "FeatureCollection",
+		POINT: "Point",
+		MULTI_POINT: "MultiPoint",
+		LINE_STRING: "LineString",
+		MULTI_LINE_STRING: "MultiLineString",
+		POLYGON: "Polygon",
+		MULTI_POLYGON: "MultiPolygon",
+		GEOMETRY_COLLECTION: "GeometryCollection",
+		GEOMETRY: "geometry",
+		COORDINATES: "coordinates",
+		PROPERTIES: "properties",
+		FEATURES: "features",
+		BBOX: "bbox"
+	}
+}
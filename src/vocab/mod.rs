@@ -1,4 +1,33 @@
+//! Vocabularies.
+//!
+//! This module defines the [`Vocab`] trait and [`Lexicon`] identifier type used to build
+//! identifiers from a known vocabulary.
+//! It also provides feature-gated modules of IRI constants for well-known vocabularies:
+//!
+//! | Module      | Feature         | Prefix                                        |
+//! |-------------|-----------------|-----------------------------------------------|
+//! | [`rdf`]     | `vocab-rdf`     | `http://www.w3.org/1999/02/22-rdf-syntax-ns#` |
+//! | [`rdfs`]    | `vocab-rdfs`    | `http://www.w3.org/2000/01/rdf-schema#`       |
+//! | [`xsd`]     | `vocab-xsd`     | `http://www.w3.org/2001/XMLSchema#`           |
+//! | [`schema`]  | `vocab-schema`  | `http://schema.org/`                          |
+//! | [`foaf`]    | `vocab-foaf`    | `http://xmlns.com/foaf/0.1/`                  |
+//! | [`geojson`] | `vocab-geojson` | `https://purl.org/geojson/vocab#`             |
+//!
+//! The `vocab` feature enables all of them.
+//! Constants can be used wherever a reference is expected:
+//! ```ignore
+//! use json_ld::vocab::{schema, xsd};
+//!
+//! for name in node.get(schema::NAME) {
+//!     println!("name: {}", name.as_str().unwrap());
+//! }
+//!
+//! node.insert(schema::ALTERNATE_NAME.into(), value);
+//! let shape = PropertyShape::new(schema::BIRTH_DATE.into()).datatype(xsd::DATE.as_iri());
+//! ```
+
 use std::fmt;
+use std::ops::Deref;
 use std::hash::Hash;
 use std::convert::TryFrom;
 use iref::{Iri, IriBuf};
@@ -9,6 +38,124 @@ use crate::{
 	Lenient
 };
 
+#[allow(unused_macros)]
+macro_rules! vocabulary {
+	($prefix:literal { $($name:ident: $suffix:literal),* $(,)? }) => {
+		use super::Constant;
+
+		/// Prefix of every IRI defined in this vocabulary.
+		pub const PREFIX: &str = $prefix;
+
+		$(
+			#[doc = concat!("`", $prefix, $suffix, "`")]
+			pub const $name: Constant = Constant::new(concat!($prefix, $suffix));
+		)*
+	};
+}
+
+#[cfg(feature = "vocab-rdf")]
+pub mod rdf;
+#[cfg(feature = "vocab-rdfs")]
+pub mod rdfs;
+#[cfg(feature = "vocab-xsd")]
+pub mod xsd;
+#[cfg(feature = "vocab-schema")]
+pub mod schema;
+#[cfg(feature = "vocab-foaf")]
+pub mod foaf;
+#[cfg(feature = "vocab-geojson")]
+pub mod geojson;
+
+/// Statically known IRI.
+///
+/// Constants of this type are defined by the vocabulary modules (such as `vocab::xsd::INTEGER`).
+/// They can be converted into any identifier type, and used as [`Reference`] through the
+/// [`ToReference`] trait.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Constant(&'static str);
+
+impl Constant {
+	/// Create a new constant.
+	///
+	/// The given string must be a valid IRI, otherwise [`as_iri`](Constant::as_iri) will panic.
+	pub const fn new(iri: &'static str) -> Constant {
+		Constant(iri)
+	}
+
+	/// The IRI as a string slice.
+	pub fn as_str(&self) -> &'static str {
+		self.0
+	}
+
+	/// The IRI.
+	pub fn as_iri(&self) -> Iri<'static> {
+		Iri::new(self.0).unwrap()
+	}
+
+	/// Convert the constant into an identifier.
+	pub fn to_id<T: Id>(&self) -> T {
+		T::from_iri(self.as_iri())
+	}
+}
+
+impl Deref for Constant {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		self.0
+	}
+}
+
+impl AsRef<str> for Constant {
+	fn as_ref(&self) -> &str {
+		self.0
+	}
+}
+
+impl fmt::Display for Constant {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl From<Constant> for IriBuf {
+	fn from(c: Constant) -> IriBuf {
+		c.as_iri().into()
+	}
+}
+
+impl<T: Id> From<Constant> for Reference<T> {
+	fn from(c: Constant) -> Reference<T> {
+		Reference::Id(c.to_id())
+	}
+}
+
+impl<T: Id> ToReference<T> for Constant {
+	type Reference = Reference<T>;
+
+	fn to_ref(&self) -> Self::Reference {
+		(*self).into()
+	}
+}
+
+impl<T: Id> PartialEq<Constant> for Reference<T> {
+	fn eq(&self, other: &Constant) -> bool {
+		match self {
+			Reference::Id(id) => id.as_iri() == other.as_iri(),
+			_ => false
+		}
+	}
+}
+
+impl<T: Id> PartialEq<Constant> for Lenient<Reference<T>> {
+	fn eq(&self, other: &Constant) -> bool {
+		match self {
+			Lenient::Ok(r) => r == other,
+			_ => false
+		}
+	}
+}
+
 pub trait Vocab: Clone + PartialEq + Eq + Hash {
 	fn from_iri(iri: Iri) -> Option<Self>;
 
@@ -0,0 +1,28 @@
+//! RDF vocabulary.
+
+vocabulary! {
+	"http://www.w3.org/1999/02/22-rdf-syntax-ns#" {
+		TYPE: "type",
+		PROPERTY: "Property",
+		STATEMENT: "Statement",
+		SUBJECT: "subject",
+		PREDICATE: "predicate",
+		OBJECT: "object",
+		FIRST: "first",
+		REST: "rest",
+		NIL: "nil",
+		LIST: "List",
+		VALUE: "value",
+		LANG_STRING: "langString",
+		HTML: "HTML",
+		JSON: "JSON",
+		XML_LITERAL: "XMLLiteral",
+		PLAIN_LITERAL: "PlainLiteral",
+		COMPOUND_LITERAL: "CompoundLiteral",
+		DIRECTION: "direction",
+		LANGUAGE: "language",
+		SEQ: "Seq",
+		BAG: "Bag",
+		ALT: "Alt"
+	}
+}
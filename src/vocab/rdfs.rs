@@ -0,0 +1,21 @@
+//! RDF Schema vocabulary.
+
+vocabulary! {
+	"http://www.w3.org/2000/01/rdf-schema#" {
+		RESOURCE: "Resource",
+		CLASS: "Class",
+		LITERAL: "Literal",
+		DATATYPE: "Datatype",
+		CONTAINER: "Container",
+		CONTAINER_MEMBERSHIP_PROPERTY: "ContainerMembershipProperty",
+		SUB_CLASS_OF: "subClassOf",
+		SUB_PROPERTY_OF: "subPropertyOf",
+		DOMAIN: "domain",
+		RANGE: "range",
+		LABEL: "label",
+		COMMENT: "comment",
+		MEMBER: "member",
+		SEE_ALSO: "seeAlso",
+		IS_DEFINED_BY: "isDefinedBy"
+	}
+}
@@ -0,0 +1,87 @@
+//! Schema.org vocabulary (commonly used subset).
+
+vocabulary! {
+	"http://schema.org/" {
+		THING: "Thing",
+		PERSON: "Person",
+		ORGANIZATION: "Organization",
+		PLACE: "Place",
+		EVENT: "Event",
+		CREATIVE_WORK: "CreativeWork",
+		ARTICLE: "Article",
+		BLOG_POSTING: "BlogPosting",
+		BOOK: "Book",
+		WEB_PAGE: "WebPage",
+		WEB_SITE: "WebSite",
+		PRODUCT: "Product",
+		OFFER: "Offer",
+		POSTAL_ADDRESS: "PostalAddress",
+		IMAGE_OBJECT: "ImageObject",
+		MEDIA_OBJECT: "MediaObject",
+		REVIEW: "Review",
+		RATING: "Rating",
+		NAME: "name",
+		ALTERNATE_NAME: "alternateName",
+		DESCRIPTION: "description",
+		URL: "url",
+		IMAGE: "image",
+		IDENTIFIER: "identifier",
+		SAME_AS: "sameAs",
+		GIVEN_NAME: "givenName",
+		FAMILY_NAME: "familyName",
+		ADDITIONAL_NAME: "additionalName",
+		EMAIL: "email",
+		TELEPHONE: "telephone",
+		ADDRESS: "address",
+		BIRTH_DATE: "birthDate",
+		GENDER: "gender",
+		NATIONALITY: "nationality",
+		JOB_TITLE: "jobTitle",
+		WORKS_FOR: "worksFor",
+		MEMBER_OF: "memberOf",
+		AFFILIATION: "affiliation",
+		KNOWS: "knows",
+		AUTHOR: "author",
+		CREATOR: "creator",
+		PUBLISHER: "publisher",
+		CONTRIBUTOR: "contributor",
+		HEADLINE: "headline",
+		DATE_PUBLISHED: "datePublished",
+		DATE_CREATED: "dateCreated",
+		DATE_MODIFIED: "dateModified",
+		KEYWORDS: "keywords",
+		IN_LANGUAGE: "inLanguage",
+		LICENSE: "license",
+		TEXT: "text",
+		LOCATION: "location",
+		START_DATE: "startDate",
+		END_DATE: "endDate",
+		PRICE: "price",
+		PRICE_CURRENCY: "priceCurrency",
+		OFFERS: "offers",
+		BRAND: "brand",
+		SKU: "sku",
+		REVIEW_RATING: "reviewRating",
+		RATING_VALUE: "ratingValue",
+		STREET_ADDRESS: "streetAddress",
+		ADDRESS_LOCALITY: "addressLocality",
+		ADDRESS_REGION: "addressRegion",
+		ADDRESS_COUNTRY: "addressCountry",
+		POSTAL_CODE: "postalCode",
+		CONTACT_POINT: "contactPoint",
+		CONTACT_TYPE: "contactType",
+		LOGO: "logo",
+		POST_OFFICE_BOX_NUMBER: "postOfficeBoxNumber",
+		GEO_COORDINATES: "GeoCoordinates",
+		GEO: "geo",
+		LATITUDE: "latitude",
+		LONGITUDE: "longitude",
+		ELEVATION: "elevation",
+		QUANTITATIVE_VALUE: "QuantitativeValue",
+		VALUE: "value",
+		MIN_VALUE: "minValue",
+		MAX_VALUE: "maxValue",
+		UNIT_CODE: "unitCode",
+		UNIT_TEXT: "unitText"
+	}
+}
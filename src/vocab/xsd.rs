@@ -0,0 +1,45 @@
+//! XML Schema datatypes.
+
+vocabulary! {
+	"http://www.w3.org/2001/XMLSchema#" {
+		ANY_URI: "anyURI",
+		BASE64_BINARY: "base64Binary",
+		BOOLEAN: "boolean",
+		BYTE: "byte",
+		DATE: "date",
+		DATE_TIME: "dateTime",
+		DATE_TIME_STAMP: "dateTimeStamp",
+		DAY_TIME_DURATION: "dayTimeDuration",
+		DECIMAL: "decimal",
+		DOUBLE: "double",
+		DURATION: "duration",
+		FLOAT: "float",
+		G_DAY: "gDay",
+		G_MONTH: "gMonth",
+		G_MONTH_DAY: "gMonthDay",
+		G_YEAR: "gYear",
+		G_YEAR_MONTH: "gYearMonth",
+		HEX_BINARY: "hexBinary",
+		INT: "int",
+		INTEGER: "integer",
+		LANGUAGE: "language",
+		LONG: "long",
+		NAME: "Name",
+		NC_NAME: "NCName",
+		NEGATIVE_INTEGER: "negativeInteger",
+		NMTOKEN: "NMTOKEN",
+		NON_NEGATIVE_INTEGER: "nonNegativeInteger",
+		NON_POSITIVE_INTEGER: "nonPositiveInteger",
+		NORMALIZED_STRING: "normalizedString",
+		POSITIVE_INTEGER: "positiveInteger",
+		SHORT: "short",
+		STRING: "string",
+		TIME: "time",
+		TOKEN: "token",
+		UNSIGNED_BYTE: "unsignedByte",
+		UNSIGNED_INT: "unsignedInt",
+		UNSIGNED_LONG: "unsignedLong",
+		UNSIGNED_SHORT: "unsignedShort",
+		YEAR_MONTH_DURATION: "yearMonthDuration"
+	}
+}
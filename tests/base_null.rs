@@ -0,0 +1,103 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion,
+	util::AsJson
+};
+
+const BASE: &str = "http://example.org/documents/doc";
+
+fn expand_with(doc: &str, options: expansion::Options) -> Result<JsonValue, ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	let base_url = Iri::new(BASE).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand_with(Some(base_url), &JsonContext::new(Some(base_url)), &mut NoLoader, options)).map_err(|e| e.code())?;
+	Ok(expanded.as_json())
+}
+
+fn expand(doc: &str) -> JsonValue {
+	expand_with(doc, expansion::Options::default()).unwrap()
+}
+
+fn strict(doc: &str) -> Result<JsonValue, ErrorCode> {
+	expand_with(doc, expansion::Options {
+		relative_iris: false,
+		..expansion::Options::default()
+	})
+}
+
+#[test]
+fn document_base() {
+	let expanded = expand(r#"{"@id": "a", "http://example.org/p": {"@id": "../b"}}"#);
+	assert_eq!(expanded[0]["@id"], "http://example.org/documents/a");
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "http://example.org/b");
+}
+
+#[test]
+fn relative_iris_stay_relative() {
+	let expanded = expand(r##"{
+		"@context": {"@base": null, "p": {"@id": "http://example.org/p", "@type": "@id"}},
+		"@id": "a",
+		"http://example.org/q": {"@id": "../b"},
+		"p": "#c"
+	}"##);
+
+	assert_eq!(expanded[0]["@id"], "a");
+	assert_eq!(expanded[0]["http://example.org/q"][0]["@id"], "../b");
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "#c");
+}
+
+#[test]
+fn absolute_iris_are_unchanged() {
+	let doc = r#"{"@context": {"@base": null}, "@id": "http://example.org/a", "@type": "http://example.org/T"}"#;
+	assert_eq!(expand(doc)[0]["@id"], "http://example.org/a");
+	assert_eq!(strict(doc).unwrap()[0]["@type"][0], "http://example.org/T");
+}
+
+#[test]
+fn base_after_null() {
+	// An absolute `@base` can be set again, while a relative one has nothing to be resolved against.
+	let expanded = expand(r#"{"@context": [{"@base": null}, {"@base": "http://example.com/"}], "@id": "a", "http://example.org/p": 1}"#);
+	assert_eq!(expanded[0]["@id"], "http://example.com/a");
+
+	let relative = expand_with(r#"{"@context": [{"@base": null}, {"@base": "sub/"}], "@id": "a", "http://example.org/p": 1}"#, expansion::Options::default());
+	assert_eq!(relative, Err(ErrorCode::InvalidBaseIri));
+}
+
+#[test]
+fn embedded_base() {
+	let expanded = expand(r#"{
+		"@context": {"@base": null},
+		"http://example.org/p": {"@context": {"@base": "http://example.com/"}, "@id": "a"},
+		"http://example.org/q": {"@id": "a"}
+	}"#);
+
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "http://example.com/a");
+	assert_eq!(expanded[0]["http://example.org/q"][0]["@id"], "a");
+}
+
+#[test]
+fn null_vocab_base() {
+	// The vocabulary mapping is document-relative too.
+	assert_eq!(expand_with(r#"{"@context": {"@base": null, "@vocab": ""}, "p": 1}"#, expansion::Options::default()), Err(ErrorCode::InvalidVocabMapping));
+}
+
+#[test]
+fn strict_relative_iris() {
+	assert_eq!(strict(r#"{"@context": {"@base": null}, "@id": "a", "http://example.org/p": 1}"#), Err(ErrorCode::RelativeIri));
+	assert_eq!(strict(r#"{"@context": {"@base": null}, "@type": "T"}"#), Err(ErrorCode::RelativeIri));
+	assert_eq!(strict(r#"{"@context": {"@base": null, "p": {"@id": "http://example.org/p", "@type": "@id"}}, "p": "a"}"#), Err(ErrorCode::RelativeIri));
+	assert_eq!(strict(r#"{"@context": {"@base": null, "p": {"@id": "http://example.org/p", "@container": "@id"}}, "p": {"a": {"http://example.org/q": 1}}}"#), Err(ErrorCode::RelativeIri));
+
+	// Relative IRIs are fine as long as there is a base IRI.
+	assert!(strict(r#"{"@id": "a", "@type": "T", "http://example.org/p": 1}"#).is_ok());
+}
@@ -0,0 +1,181 @@
+#![cfg(feature="derive")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	binding::{
+		self,
+		Error,
+		FromLinkedData,
+		IntoLinkedData,
+		Options
+	},
+	util::AsJson
+};
+
+#[derive(FromLinkedData, IntoLinkedData, PartialEq, Debug)]
+#[ld(type = "http://xmlns.com/foaf/0.1/Person")]
+struct Person {
+	#[ld(id)]
+	id: Option<IriBuf>,
+
+	#[ld("http://xmlns.com/foaf/0.1/name")]
+	name: String,
+
+	#[ld("http://xmlns.com/foaf/0.1/age")]
+	age: Option<u32>,
+
+	#[ld("http://example.org/height")]
+	height: Option<f64>,
+
+	#[ld("http://example.org/admin")]
+	admin: Option<bool>,
+
+	#[ld("http://xmlns.com/foaf/0.1/knows")]
+	knows: Vec<Person>,
+
+	#[ld("http://example.org/favoriteColors", list)]
+	favorite_colors: Vec<String>
+}
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://xmlns.com/foaf/0.1/",
+	"xsd": "http://www.w3.org/2001/XMLSchema#",
+	"ex": "http://example.org/",
+	"height": "ex:height",
+	"admin": "ex:admin",
+	"favoriteColors": {"@id": "ex:favoriteColors", "@container": "@list"}
+}"#;
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(&format!(r#"{{"@context": {}, {}}}"#, CONTEXT, doc)).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn person(doc: &str) -> Result<Person, Error> {
+	let expanded = expand(&format!(r#""@type": "Person", {}"#, doc));
+	Person::from_object(expanded.iter().next().unwrap().inner(), Options::default())
+}
+
+#[test]
+fn round_trip() {
+	let alice = person(r#"
+		"@id": "http://example.org/alice",
+		"name": "Alice",
+		"age": 42,
+		"height": 1.7,
+		"admin": true,
+		"knows": {"@type": "Person", "name": "Bob", "age": {"@value": "36", "@type": "xsd:nonNegativeInteger"}},
+		"favoriteColors": ["red", "green"]
+	"#).unwrap();
+
+	assert_eq!(alice, Person {
+		id: Some(IriBuf::new("http://example.org/alice").unwrap()),
+		name: "Alice".to_string(),
+		age: Some(42),
+		height: Some(1.7),
+		admin: Some(true),
+		knows: vec![Person {
+			id: None,
+			name: "Bob".to_string(),
+			age: Some(36),
+			height: None,
+			admin: None,
+			knows: Vec::new(),
+			favorite_colors: Vec::new()
+		}],
+		favorite_colors: vec!["red".to_string(), "green".to_string()]
+	});
+
+	let object = IntoLinkedData::<IriBuf>::into_object(alice);
+	assert_eq!(object.as_json()["http://example.org/favoriteColors"][0]["@list"].len(), 2);
+	let back = Person::from_object(object.inner(), Options::default()).unwrap();
+	assert_eq!(back.name, "Alice");
+	assert_eq!(back.knows[0].age, Some(36));
+	assert_eq!(back.favorite_colors, vec!["red", "green"]);
+
+	let again = IntoLinkedData::<IriBuf>::into_object(back);
+	assert!(again == object)
+}
+
+#[test]
+fn typed_literals() {
+	let alice = person(r#"
+		"name": "Alice",
+		"age": {"@value": "42", "@type": "xsd:int"},
+		"height": {"@value": "1.5", "@type": "xsd:decimal"},
+		"admin": {"@value": "1", "@type": "xsd:boolean"}
+	"#).unwrap();
+
+	assert_eq!(alice.age, Some(42));
+	assert_eq!(alice.height, Some(1.5));
+	assert_eq!(alice.admin, Some(true));
+
+	let alice = person(r#""name": "Alice", "height": {"@value": "1.5E0", "@type": "xsd:double"}"#).unwrap();
+	assert_eq!(alice.height, Some(1.5));
+}
+
+fn invalid_literal(doc: &str, expected_prop: &str) {
+	match person(doc) {
+		Err(Error::Property(prop, e)) => {
+			assert_eq!(prop, expected_prop);
+			assert!(matches!(*e, Error::InvalidLiteral), "{}", e)
+		},
+		other => panic!("unexpected result {:?}", other.map(|_| ()))
+	}
+}
+
+#[test]
+fn wrong_datatype() {
+	invalid_literal(r#""name": "Alice", "age": {"@value": "42", "@type": "xsd:string"}"#, "http://xmlns.com/foaf/0.1/age");
+	invalid_literal(r#""name": "Alice", "age": {"@value": "42", "@type": "ex:number"}"#, "http://xmlns.com/foaf/0.1/age");
+	invalid_literal(r#""name": "Alice", "age": {"@value": "4.2", "@type": "xsd:decimal"}"#, "http://xmlns.com/foaf/0.1/age");
+	invalid_literal(r#""name": "Alice", "age": "42""#, "http://xmlns.com/foaf/0.1/age");
+	invalid_literal(r#""name": "Alice", "age": -1"#, "http://xmlns.com/foaf/0.1/age");
+	invalid_literal(r#""name": "Alice", "admin": {"@value": "true", "@type": "xsd:string"}"#, "http://example.org/admin");
+	invalid_literal(r#""name": "Alice", "admin": {"@value": "yes", "@type": "xsd:boolean"}"#, "http://example.org/admin");
+	invalid_literal(r#""name": "Alice", "height": {"@value": "1.5", "@type": "xsd:date"}"#, "http://example.org/height");
+	invalid_literal(r#""name": "Alice", "height": {"@value": "tall", "@type": "xsd:double"}"#, "http://example.org/height");
+}
+
+#[test]
+fn missing_property() {
+	match person(r#""age": 42"#) {
+		Err(Error::MissingProperty(prop)) => assert_eq!(prop, "http://xmlns.com/foaf/0.1/name"),
+		other => panic!("unexpected result {:?}", other.map(|_| ()))
+	}
+
+	// Missing properties of nested nodes are reported in their property.
+	match person(r#""name": "Alice", "knows": {"@type": "Person"}"#) {
+		Err(Error::Property(prop, e)) => {
+			assert_eq!(prop, "http://xmlns.com/foaf/0.1/knows");
+			assert!(matches!(*e, Error::MissingProperty(_)), "{}", e)
+		},
+		other => panic!("unexpected result {:?}", other.map(|_| ()))
+	}
+}
+
+#[test]
+fn missing_type() {
+	let expanded = expand(r#""@type": "Agent", "name": "Alice""#);
+	let mut node = match expanded.iter().next().unwrap().inner() {
+		json_ld::Object::Node(node) => node.as_ref().clone(),
+		_ => panic!("expected a node")
+	};
+
+	match Person::from_node(&node, Options::default()) {
+		Err(Error::MissingType(ty)) => assert_eq!(ty, "http://xmlns.com/foaf/0.1/Person"),
+		other => panic!("unexpected result {:?}", other.map(|_| ()))
+	}
+
+	binding::add_type(&mut node, "http://xmlns.com/foaf/0.1/Person");
+	assert_eq!(Person::from_node(&node, Options::default()).unwrap().name, "Alice")
+}
@@ -0,0 +1,138 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::time::Duration;
+use async_std::task;
+use futures::future;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ErrorCode,
+	ExpandedDocument,
+	FnLoader,
+	JsonContext,
+	Loader,
+	NoLoader,
+	cancel::{
+		CancelLayer,
+		Token
+	},
+	compaction,
+	context::{
+		Local,
+		ProcessingStack
+	},
+	layer::LoaderExt
+};
+
+/// Loader serving an empty context, cancelling the given token once it is loaded.
+fn cancelling(token: Token) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |_: Iri| {
+		token.cancel();
+		future::ready(Ok(r#"{"@context": {"@vocab": "http://schema.org/"}}"#.to_string()))
+	})
+}
+
+fn expand<L: Send + Sync + Loader<Document = JsonValue>>(doc: &str, loader: &mut L) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), loader)).map_err(|e| e.code())
+}
+
+const DOC: &str = r#"{"@context": {"@vocab": "http://schema.org/"}, "name": "Alice", "knows": {"name": "Bob"}}"#;
+
+#[test]
+fn token() {
+	let token = Token::new();
+	let clone = token.clone();
+	assert!(!token.is_cancelled());
+
+	// Clones share the same cancellation state.
+	clone.cancel();
+	assert!(token.is_cancelled() && clone.is_cancelled());
+	assert!(!Token::new().is_cancelled());
+}
+
+#[test]
+fn deadline() {
+	assert!(Token::with_timeout(Duration::from_secs(0)).is_cancelled());
+	assert!(!Token::with_timeout(Duration::from_secs(3600)).is_cancelled());
+
+	let token = Token::with_timeout(Duration::from_millis(5));
+	let clone = token.clone();
+	std::thread::sleep(Duration::from_millis(20));
+	assert!(token.is_cancelled() && clone.is_cancelled());
+
+	// A token can still be cancelled before its deadline.
+	let token = Token::with_timeout(Duration::from_secs(3600));
+	token.cancel();
+	assert!(token.is_cancelled());
+}
+
+#[test]
+fn layer() {
+	let token = Token::new();
+	let mut loader = NoLoader.with(CancelLayer::new(token.clone()));
+	assert!(!loader.is_cancelled());
+	assert!(!loader.token().is_cancelled());
+
+	token.cancel();
+	assert!(loader.is_cancelled());
+	let e = task::block_on(loader.load(Iri::new("https://example.org/context").unwrap())).err().unwrap();
+	assert_eq!(e.code(), ErrorCode::Cancelled);
+
+	// The cancellation of inner loaders is propagated.
+	let inner = Token::new();
+	let loader = NoLoader.with(CancelLayer::new(inner.clone())).with(CancelLayer::new(Token::new()));
+	inner.cancel();
+	assert!(loader.is_cancelled());
+	assert!(loader.inner().is_cancelled());
+	assert!(!loader.token().is_cancelled());
+}
+
+#[test]
+fn expansion() {
+	let token = Token::new();
+	assert!(expand(DOC, &mut NoLoader.with(CancelLayer::new(token.clone()))).is_ok());
+
+	token.cancel();
+	assert_eq!(expand(DOC, &mut NoLoader.with(CancelLayer::new(token))).err(), Some(ErrorCode::Cancelled));
+	assert_eq!(expand(DOC, &mut NoLoader.with(CancelLayer::new(Token::with_timeout(Duration::from_secs(0))))).err(), Some(ErrorCode::Cancelled));
+}
+
+#[test]
+fn abort_expansion() {
+	// The token is cancelled while loading the remote context, aborting the rest of the expansion.
+	let doc = r#"{"@context": "https://example.org/context", "name": "Alice"}"#;
+	let token = Token::new();
+	let mut loader = cancelling(token.clone()).with(CancelLayer::new(token.clone()));
+	assert_eq!(expand(doc, &mut loader).err(), Some(ErrorCode::Cancelled));
+	assert!(token.is_cancelled());
+
+	// Without the layer, the expansion is not aborted.
+	assert!(expand(doc, &mut cancelling(Token::new())).is_ok());
+}
+
+#[test]
+fn compaction() {
+	let expanded = expand(DOC, &mut NoLoader).unwrap();
+	let context = json::parse(r#"{"@vocab": "http://schema.org/"}"#).unwrap();
+	let active_context: JsonContext<IriBuf> = task::block_on(context.process_with(&JsonContext::new(None), ProcessingStack::new(), &mut NoLoader, None, Default::default())).unwrap();
+
+	let token = Token::new();
+	let mut loader = NoLoader.with(CancelLayer::new(token.clone()));
+	let compacted = task::block_on(compaction::compact_with(&expanded, &active_context, &mut loader, compaction::Options::default())).unwrap();
+	assert_eq!(compacted["knows"]["name"], "Bob");
+
+	token.cancel();
+	let e = task::block_on(compaction::compact_with(&expanded, &active_context, &mut loader, compaction::Options::default())).err().unwrap();
+	assert_eq!(e.code(), ErrorCode::Cancelled);
+
+	// Cancelling while loading the compaction context aborts the compaction.
+	let token = Token::new();
+	let mut loader = cancelling(token.clone()).with(CancelLayer::new(token));
+	let context = JsonValue::from("https://example.org/context");
+	let e = task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, &context, None, &mut loader, compaction::Options::default())).err().unwrap();
+	assert_eq!(e.code(), ErrorCode::Cancelled);
+}
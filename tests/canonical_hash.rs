@@ -0,0 +1,57 @@
+#![cfg(feature="integrity")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	integrity::{
+		Algorithm,
+		Hash
+	}
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+#[test]
+fn canonical_hash() {
+	let a = expand(r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@id": "http://example.org/alice",
+		"name": "Alice",
+		"knows": [{"@id": "http://example.org/bob"}, {"@id": "http://example.org/carol"}]
+	}"#);
+	let b = expand(r#"{
+		"http://schema.org/knows": [{"@id": "http://example.org/carol"}, {"@id": "http://example.org/bob"}],
+		"http://schema.org/name": {"@value": "Alice"},
+		"@id": "http://example.org/alice"
+	}"#);
+	let c = expand(r#"{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": "Alicia"
+	}"#);
+
+	assert_eq!(a.canonical_form(), b.canonical_form());
+	assert_eq!(a.canonical_hash(Algorithm::Sha256), b.canonical_hash(Algorithm::Sha256));
+	assert_ne!(a.canonical_hash(Algorithm::Sha256), c.canonical_hash(Algorithm::Sha256));
+
+	let hash = a.canonical_hash(Algorithm::Sha384);
+	assert_eq!(hash, Hash::of(Algorithm::Sha384, a.canonical_form().as_bytes()));
+	assert_eq!(hash.to_string().parse::<Hash>().unwrap(), hash);
+}
+
+#[test]
+fn list_order() {
+	let a = expand(r#"{"http://schema.org/steps": {"@list": ["one", "two"]}}"#);
+	let b = expand(r#"{"http://schema.org/steps": {"@list": ["two", "one"]}}"#);
+	assert_ne!(a.canonical_hash(Algorithm::Sha256), b.canonical_hash(Algorithm::Sha256));
+}
@@ -0,0 +1,115 @@
+#![cfg(feature="capi")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use json_ld::capi::*;
+
+/// Take the output string of an API call.
+fn take(output: *mut c_char) -> Option<String> {
+	if output.is_null() {
+		None
+	} else {
+		let value = unsafe { CStr::from_ptr(output) }.to_str().unwrap().to_string();
+		unsafe { jsonld_free(output) };
+		Some(value)
+	}
+}
+
+fn expand(input: &[u8], base_url: Option<&str>) -> (c_int, Option<String>) {
+	let base_url = base_url.map(|url| CString::new(url).unwrap());
+	let mut output = ptr::null_mut();
+	let status = unsafe {
+		jsonld_expand(input.as_ptr() as *const c_char, input.len(), base_url.as_ref().map(|url| url.as_ptr()).unwrap_or(ptr::null()), &mut output)
+	};
+
+	(status, take(output))
+}
+
+fn compact(input: &[u8], context: &[u8], base_url: Option<&str>) -> (c_int, Option<String>) {
+	let base_url = base_url.map(|url| CString::new(url).unwrap());
+	let mut output = ptr::null_mut();
+	let status = unsafe {
+		jsonld_compact(input.as_ptr() as *const c_char, input.len(), context.as_ptr() as *const c_char, context.len(), base_url.as_ref().map(|url| url.as_ptr()).unwrap_or(ptr::null()), &mut output)
+	};
+
+	(status, take(output))
+}
+
+fn json(value: Option<String>) -> json::JsonValue {
+	json::parse(&value.unwrap()).unwrap()
+}
+
+#[test]
+fn expand_document() {
+	let doc = br#"{"@context": {"name": "http://schema.org/name"}, "@id": "alice", "name": "Alice"}"#;
+	let (status, output) = expand(doc, Some("https://example.org/"));
+	assert_eq!(status, JSONLD_OK);
+	assert_eq!(json(output), json::parse(r#"[{"@id": "https://example.org/alice", "http://schema.org/name": [{"@value": "Alice"}]}]"#).unwrap());
+
+	// Without base URL, relative IRIs are kept.
+	let (status, output) = expand(doc, None);
+	assert_eq!(status, JSONLD_OK);
+	assert_eq!(json(output)[0]["@id"], "alice");
+}
+
+#[test]
+fn compact_document() {
+	let doc = br#"{"@id": "alice", "http://schema.org/name": "Alice", "http://schema.org/knows": {"@id": "bob"}}"#;
+	let context = br#"{"@context": {"name": "http://schema.org/name", "knows": {"@id": "http://schema.org/knows", "@type": "@id"}}}"#;
+	let (status, output) = compact(doc, context, Some("https://example.org/"));
+	assert_eq!(status, JSONLD_OK);
+	assert_eq!(json(output), json::parse(r#"{
+		"@context": {"name": "http://schema.org/name", "knows": {"@id": "http://schema.org/knows", "@type": "@id"}},
+		"@id": "alice",
+		"name": "Alice",
+		"knows": "bob"
+	}"#).unwrap());
+
+	// The context can also be given as a context definition.
+	let (status, output) = compact(doc, br#"{"name": "http://schema.org/name"}"#, None);
+	assert_eq!(status, JSONLD_OK);
+	assert_eq!(json(output)["name"], "Alice");
+}
+
+#[test]
+fn processing_errors() {
+	let (status, output) = expand(br#"{"@id": 1}"#, None);
+	assert_eq!(status, JSONLD_ERROR_PROCESSING);
+	assert_eq!(output.as_deref(), Some("invalid @id value"));
+
+	let (status, output) = expand(br#"{"@context": "https://example.org/context"}"#, None);
+	assert_eq!(status, JSONLD_ERROR_PROCESSING);
+	assert_eq!(output.as_deref(), Some("loading remote context failed"));
+
+	let (status, output) = compact(br#"{"http://schema.org/name": "Alice"}"#, br#"{"@context": {"@version": 2}}"#, None);
+	assert_eq!(status, JSONLD_ERROR_PROCESSING);
+	assert_eq!(output.as_deref(), Some("invalid @version value"));
+}
+
+#[test]
+fn invalid_input() {
+	assert_eq!(expand(b"{", None), (JSONLD_ERROR_INVALID_JSON, None));
+	assert_eq!(expand(b"\"\xff\"", None), (JSONLD_ERROR_INVALID_INPUT, None));
+	assert_eq!(expand(b"{}", Some("not an IRI")), (JSONLD_ERROR_INVALID_INPUT, None));
+	assert_eq!(compact(b"{}", b"[", None), (JSONLD_ERROR_INVALID_JSON, None));
+	assert_eq!(compact(b"{}", b"{}", Some("not an IRI")), (JSONLD_ERROR_INVALID_INPUT, None));
+}
+
+#[test]
+fn null_pointers() {
+	let mut output = ptr::null_mut();
+	unsafe {
+		assert_eq!(jsonld_expand(ptr::null(), 0, ptr::null(), &mut output), JSONLD_ERROR_NULL);
+		assert_eq!(jsonld_expand(b"{}".as_ptr() as *const c_char, 2, ptr::null(), ptr::null_mut()), JSONLD_ERROR_NULL);
+		assert_eq!(jsonld_compact(b"{}".as_ptr() as *const c_char, 2, ptr::null(), 0, ptr::null(), &mut output), JSONLD_ERROR_NULL);
+		assert_eq!(jsonld_compact(ptr::null(), 0, b"{}".as_ptr() as *const c_char, 2, ptr::null(), &mut output), JSONLD_ERROR_NULL);
+		jsonld_free(ptr::null_mut())
+	}
+
+	assert!(output.is_null())
+}
@@ -0,0 +1,323 @@
+#![cfg(feature="cbor-ld")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use ciborium::value::Value;
+use futures::future;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ErrorCode,
+	ExpandedDocument,
+	FnLoader,
+	JsonContext,
+	Loader,
+	compaction,
+	util::AsJson,
+	cbor_ld::{
+		self,
+		Dictionary,
+		Error
+	}
+};
+
+const CREDENTIALS_V1: &str = include_str!("../src/preset/contexts/credentials-v1.jsonld");
+
+const EXAMPLE_CONTEXT: &str = r#"{
+	"@context": {
+		"xsd": "http://www.w3.org/2001/XMLSchema#",
+		"ExampleCredential": "https://example.org/ExampleCredential",
+		"name": "https://schema.org/name",
+		"data": {"@id": "https://example.org/data", "@type": "@json"},
+		"issued": {"@id": "https://example.org/issued", "@type": "xsd:dateTime"},
+		"topic": {"@id": "https://example.org/topic", "@type": "@vocab"},
+		"birthDate": {"@id": "https://schema.org/birthDate", "@type": "xsd:dateTime"}
+	}
+}"#;
+
+fn loader() -> impl Send + Sync + Loader<Document = JsonValue> {
+	FnLoader::new(|url: Iri| future::ready(match url.as_str() {
+		"https://www.w3.org/2018/credentials/v1" => Ok(CREDENTIALS_V1.to_string()),
+		"https://example.org/context" => Ok(EXAMPLE_CONTEXT.to_string()),
+		_ => Err(ErrorCode::LoadingDocumentFailed)
+	}))
+}
+
+fn encode(doc: &JsonValue) -> Result<Vec<u8>, Error> {
+	task::block_on(cbor_ld::encode(doc, &mut loader()))
+}
+
+fn decode(bytes: &[u8]) -> Result<JsonValue, Error> {
+	task::block_on(cbor_ld::decode(bytes, &mut loader()))
+}
+
+/// Encode the given document, check that it decodes into the same document, and return the
+/// untagged CBOR value.
+fn round_trip(doc: &JsonValue) -> Value {
+	let bytes = encode(doc).unwrap();
+	assert_eq!(decode(&bytes).unwrap(), *doc);
+	match ciborium::de::from_reader(&bytes[..]).unwrap() {
+		Value::Tag(cbor_ld::COMPRESSED, value) => *value,
+		value => panic!("untagged value {:?}", value)
+	}
+}
+
+/// Value of the given key in a CBOR map.
+fn get(value: &Value, key: Value) -> &Value {
+	match value {
+		Value::Map(entries) => &entries.iter().find(|(k, _)| *k == key).unwrap_or_else(|| panic!("no {:?} in {:?}", key, value)).1,
+		_ => panic!("not a map: {:?}", value)
+	}
+}
+
+fn code(dictionary: &Dictionary, term: &str) -> Value {
+	Value::Integer(dictionary.code(term).unwrap().into())
+}
+
+fn credential() -> JsonValue {
+	json::parse(r#"{
+		"@context": ["https://www.w3.org/2018/credentials/v1", "https://example.org/context"],
+		"id": "https://example.org/credentials/1",
+		"type": ["VerifiableCredential", "ExampleCredential"],
+		"issuer": "did:example:issuer",
+		"issuanceDate": "2020-01-01T00:00:00Z",
+		"credentialSubject": {
+			"id": "http://example.org/alice",
+			"name": "Alice",
+			"birthDate": "1969-07-20T20:17:40.5Z",
+			"topic": ["ExampleCredential", "Other"],
+			"data": {"@context": "not a context", "id": 1, "nested": [1, 2.5, null, true]},
+			"https://example.org/score": 9.5
+		}
+	}"#).unwrap()
+}
+
+#[test]
+fn credential_round_trip() {
+	let doc = credential();
+	let bytes = encode(&doc).unwrap();
+	assert_eq!(&bytes[..3], &[0xd9, 0x05, 0x01]);
+	assert!(bytes.len() * 2 < doc.dump().len(), "{} bytes for {}", bytes.len(), doc.dump().len());
+
+	let value = round_trip(&doc);
+	let dictionary = task::block_on(Dictionary::new(&doc["@context"], &mut loader())).unwrap();
+
+	// Context URLs.
+	assert_eq!(*get(&value, Value::Integer(1.into())), Value::Array(vec![Value::Integer(0x11.into()), Value::Text("https://example.org/context".into())]));
+
+	// `id` and `type` are aliases of `@id` and `@type`.
+	assert_eq!(*get(&value, code(&dictionary, "id")), Value::Array(vec![Value::Integer(2.into()), Value::Text("example.org/credentials/1".into())]));
+	let types = Value::Integer((dictionary.code("type").unwrap() + 1).into());
+	assert_eq!(*get(&value, types), Value::Array(vec![code(&dictionary, "VerifiableCredential"), code(&dictionary, "ExampleCredential")]));
+
+	// Non-HTTP IRIs are kept.
+	assert_eq!(*get(&value, code(&dictionary, "issuer")), Value::Text("did:example:issuer".into()));
+
+	// Dates.
+	assert_eq!(*get(&value, code(&dictionary, "issuanceDate")), Value::Integer(1577836800.into()));
+
+	let subject = get(&value, code(&dictionary, "credentialSubject"));
+	assert_eq!(*get(subject, code(&dictionary, "id")), Value::Array(vec![Value::Integer(1.into()), Value::Text("example.org/alice".into())]));
+	assert_eq!(*get(subject, code(&dictionary, "name")), Value::Text("Alice".into()));
+
+	// Dates with fractional seconds are not compressed.
+	assert_eq!(*get(subject, code(&dictionary, "birthDate")), Value::Text("1969-07-20T20:17:40.5Z".into()));
+
+	// Terms typed `@vocab`.
+	let topics = Value::Integer((dictionary.code("topic").unwrap() + 1).into());
+	assert_eq!(*get(subject, topics), Value::Array(vec![code(&dictionary, "ExampleCredential"), Value::Text("Other".into())]));
+
+	// JSON literals are kept as they are.
+	let data = get(subject, code(&dictionary, "data"));
+	assert_eq!(*get(data, Value::Text("@context".into())), Value::Text("not a context".into()));
+	assert_eq!(*get(data, Value::Text("id".into())), Value::Integer(1.into()));
+
+	// Undefined keys.
+	assert_eq!(*get(subject, Value::Text("https://example.org/score".into())), Value::Float(9.5));
+}
+
+#[test]
+fn compacted_credential() {
+	let doc = credential();
+	let context = doc["@context"].clone();
+	let mut loader = loader();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut loader)).unwrap();
+	let compacted = task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, &context, None, &mut loader, compaction::Options::default())).unwrap();
+
+	let decoded = decode(&encode(&compacted).unwrap()).unwrap();
+	assert_eq!(decoded, compacted);
+	let reexpanded: ExpandedDocument<IriBuf> = task::block_on(decoded.expand(&JsonContext::new(None), &mut loader)).unwrap();
+	assert_eq!(reexpanded.as_json(), expanded.as_json());
+}
+
+#[test]
+fn dictionary() {
+	let context = json::parse(r#"{
+		"b": "https://example.org/b",
+		"a": "https://example.org/a",
+		"c": {"@id": "https://example.org/c", "@context": {"d": "https://example.org/d"}},
+		"@vocab": "https://example.org/"
+	}"#).unwrap();
+
+	let dictionary = task::block_on(Dictionary::new(&context, &mut loader())).unwrap();
+	assert_eq!(dictionary.code("@context"), Some(0));
+	assert_eq!(dictionary.code("@type"), Some(2));
+	assert_eq!(dictionary.code("@vocab"), Some(52));
+	assert_eq!(dictionary.code("a"), Some(100));
+	assert_eq!(dictionary.code("b"), Some(102));
+	assert_eq!(dictionary.code("c"), Some(104));
+	assert_eq!(dictionary.code("d"), Some(106));
+	assert_eq!(dictionary.code("e"), None);
+	assert_eq!(dictionary.term(4), Some("@id"));
+	assert_eq!(dictionary.term(104), Some("c"));
+	assert_eq!(dictionary.term(105), None);
+
+	match task::block_on(Dictionary::new(&"https://example.org/missing".into(), &mut loader())) {
+		Err(Error::Loading(e)) => assert_eq!(e.code(), ErrorCode::LoadingRemoteContextFailed),
+		_ => panic!("missing context should not load")
+	}
+}
+
+#[test]
+fn conflicting_definitions() {
+	// `date` is a date in the top-level context, but not in the scoped context.
+	let doc = json::parse(r#"{
+		"@context": {
+			"date": {"@id": "https://example.org/date", "@type": "http://www.w3.org/2001/XMLSchema#dateTime"},
+			"Event": {"@id": "https://example.org/Event", "@context": {"date": "https://example.org/date"}}
+		},
+		"@type": "Event",
+		"date": "2021-03-04T05:06:07Z"
+	}"#).unwrap();
+
+	let value = round_trip(&doc);
+	let dictionary = task::block_on(Dictionary::new(&doc["@context"], &mut loader())).unwrap();
+	assert_eq!(*get(&value, Value::Integer(2.into())), code(&dictionary, "Event"));
+	assert_eq!(*get(&value, code(&dictionary, "date")), Value::Text("2021-03-04T05:06:07Z".into()));
+}
+
+#[test]
+fn values() {
+	let doc = json::parse(r#"{
+		"@context": {"name": "https://schema.org/name", "knows": {"@id": "https://schema.org/knows", "@type": "@id"}},
+		"@graph": [
+			{"@id": "_:b0", "name": [{"@value": "Bob", "@language": "en"}, "Robert"], "knows": {"@id": "https://example.org/alice"}},
+			{"@id": "https://example.org/alice", "@type": "https://schema.org/Person", "name": {"@value": 1.5, "@type": "@json"}},
+			{"knows": [["https://example.org/a", "http://example.org/b"], "urn:x"], "name": [-1, 18446744073709551615, -9223372036854775808, 0.1, -0.0, 1e300]}
+		],
+		"@included": [],
+		"https://example.org/p": {}
+	}"#).unwrap();
+
+	round_trip(&doc);
+}
+
+#[test]
+fn embedded_contexts() {
+	let doc = json::parse(r#"{
+		"@context": "https://example.org/context",
+		"name": "Alice",
+		"https://schema.org/knows": {
+			"@context": [{"nick": "https://schema.org/alternateName"}, "https://www.w3.org/2018/credentials/v1"],
+			"nick": "Bob",
+			"name": "Robert"
+		}
+	}"#).unwrap();
+
+	let value = round_trip(&doc);
+	let dictionary = task::block_on(Dictionary::new(&doc["@context"], &mut loader())).unwrap();
+	let knows = get(&value, Value::Text("https://schema.org/knows".into()));
+
+	// Terms only defined by embedded contexts are kept.
+	assert_eq!(*get(knows, Value::Text("nick".into())), Value::Text("Bob".into()));
+	assert_eq!(*get(knows, code(&dictionary, "name")), Value::Text("Robert".into()));
+	assert_eq!(*get(knows, Value::Integer(1.into())), Value::Array(vec![
+		Value::Map(vec![(Value::Text("nick".into()), Value::Text("https://schema.org/alternateName".into()))]),
+		Value::Integer(0x11.into())
+	]));
+}
+
+#[test]
+fn ambiguous_values() {
+	let doc = json::parse(r#"{"@context": "https://example.org/context", "issued": 1577836800}"#).unwrap();
+	match encode(&doc) {
+		Err(Error::Ambiguous(key)) => assert_eq!(key, "issued"),
+		_ => panic!("integer dates are ambiguous")
+	}
+
+	let doc = json::parse(r#"{"@context": "https://example.org/context", "topic": 12}"#).unwrap();
+	match encode(&doc) {
+		Err(Error::Ambiguous(key)) => assert_eq!(key, "topic"),
+		_ => panic!("integer terms are ambiguous")
+	}
+
+	let doc = json::parse(r#"{"@context": "https://example.org/context", "@id": [[2, "example.org"]]}"#).unwrap();
+	match encode(&doc) {
+		Err(Error::Ambiguous(key)) => assert_eq!(key, "@id"),
+		_ => panic!("arrays looking like compressed IRIs are ambiguous")
+	}
+
+	// Non-integer numbers are not.
+	round_trip(&json::parse(r#"{"@context": "https://example.org/context", "issued": 1.5, "topic": true}"#).unwrap());
+}
+
+#[test]
+fn dates() {
+	let context = r#"{"d": {"@id": "https://example.org/d", "@type": "http://www.w3.org/2001/XMLSchema#dateTime"}}"#;
+	for (date, seconds) in &[
+		("1970-01-01T00:00:00Z", Some(0i64)),
+		("1969-12-31T23:59:59Z", Some(-1)),
+		("2000-02-29T12:00:00Z", Some(951825600)),
+		("0001-01-01T00:00:00Z", Some(-62135596800)),
+		("9999-12-31T23:59:59Z", Some(253402300799)),
+		("2001-02-29T00:00:00Z", None),
+		("2020-01-01T24:00:00Z", None),
+		("2020-01-01T00:00:00+01:00", None),
+		("2020-01-01", None)
+	] {
+		let doc = json::parse(&format!(r#"{{"@context": {}, "d": "{}"}}"#, context, date)).unwrap();
+		let value = round_trip(&doc);
+		let expected = match seconds {
+			Some(seconds) => Value::Integer((*seconds).into()),
+			None => Value::Text(date.to_string())
+		};
+
+		assert_eq!(*get(&value, Value::Integer(100.into())), expected, "{}", date)
+	}
+}
+
+#[test]
+fn invalid_input() {
+	fn cbor(value: &Value) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		ciborium::ser::into_writer(value, &mut bytes).unwrap();
+		bytes
+	}
+
+	assert!(matches!(decode(&[0xff, 0x00]), Err(Error::InvalidCbor)));
+	assert!(matches!(decode(&cbor(&Value::Map(vec![]))), Err(Error::NotCborLd)));
+
+	let unknown_context = Value::Tag(cbor_ld::COMPRESSED, Box::new(Value::Map(vec![(Value::Integer(0.into()), Value::Integer(0x7f.into()))])));
+	assert!(matches!(decode(&cbor(&unknown_context)), Err(Error::UnknownCode(0x7f))));
+
+	let unknown_term = Value::Tag(cbor_ld::COMPRESSED, Box::new(Value::Map(vec![(Value::Integer(100.into()), Value::Null)])));
+	assert!(matches!(decode(&cbor(&unknown_term)), Err(Error::UnknownCode(100))));
+
+	let bytes = Value::Tag(cbor_ld::COMPRESSED, Box::new(Value::Map(vec![(Value::Text("a".into()), Value::Bytes(vec![1]))])));
+	assert!(matches!(decode(&cbor(&bytes)), Err(Error::Invalid)));
+
+	let plural = Value::Tag(cbor_ld::COMPRESSED, Box::new(Value::Map(vec![(Value::Integer(5.into()), Value::Text("a".into()))])));
+	assert!(matches!(decode(&cbor(&plural)), Err(Error::Invalid)));
+
+	// Uncompressed documents.
+	let doc = json::parse(r#"{"@context": {"a": "https://example.org/a"}, "a": [1, "b"]}"#).unwrap();
+	let uncompressed = Value::Tag(cbor_ld::UNCOMPRESSED, Box::new(Value::Map(vec![
+		(Value::Text("@context".into()), Value::Map(vec![(Value::Text("a".into()), Value::Text("https://example.org/a".into()))])),
+		(Value::Text("a".into()), Value::Array(vec![Value::Integer(1.into()), Value::Text("b".into())]))
+	])));
+	assert_eq!(decode(&cbor(&uncompressed)).unwrap(), doc);
+}
@@ -0,0 +1,73 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	ErrorCode,
+	ProcessingMode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion
+};
+
+const CONTEXT: &str = r#"{
+	"id": "@id",
+	"index": "@index",
+	"value": "@value",
+	"language": "@language",
+	"type": "@type",
+	"list": "@list",
+	"set": "@set",
+	"graph": "@graph",
+	"reverse": "@reverse",
+	"included": "@included",
+	"nest": "@nest"
+}"#;
+
+fn expand(entries: &str, processing_mode: ProcessingMode) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let doc = json::parse(&format!("{{\"@context\": {}, {}}}", CONTEXT, entries)).unwrap();
+	let options = expansion::Options {
+		processing_mode,
+		..expansion::Options::default()
+	};
+
+	task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+#[test]
+fn colliding() {
+	for entries in &[
+		r#""@id": "http://example.org/a", "id": "http://example.org/b""#,
+		r#""@index": "a", "index": "b""#,
+		r#""http://example.org/p": {"@value": "a", "value": "b"}"#,
+		r#""http://example.org/p": {"@value": "a", "@language": "en", "language": "fr"}"#,
+		r#""http://example.org/p": {"@value": "a", "@index": "a", "index": "b"}"#,
+		r#""http://example.org/p": {"@list": ["a"], "list": ["b"]}"#,
+		r#""http://example.org/p": {"@list": ["a"], "@index": "a", "index": "b"}"#,
+		r#""http://example.org/p": {"@set": ["a"], "set": ["b"]}"#,
+		r#""@graph": {"@id": "http://example.org/a"}, "graph": {"@id": "http://example.org/b"}"#,
+		r#""@reverse": {"http://example.org/p": {"@id": "http://example.org/a"}}, "reverse": {"http://example.org/p": {"@id": "http://example.org/b"}}"#,
+		r#""@id": "http://example.org/a", "nest": {"id": "http://example.org/b"}"#
+	] {
+		assert_eq!(expand(entries, ProcessingMode::JsonLd1_1).err(), Some(ErrorCode::CollidingKeywords), "{}", entries)
+	}
+}
+
+#[test]
+fn merged() {
+	// The values of `@type`, `@included` and `@nest` entries are merged.
+	for entries in &[
+		r#""@type": "http://example.org/A", "type": "http://example.org/B""#,
+		r#""@id": "http://example.org/a", "@included": {"@id": "http://example.org/b"}, "included": {"@id": "http://example.org/c"}"#,
+		r#""@nest": {"http://example.org/p": "a"}, "nest": {"http://example.org/p": "b"}"#
+	] {
+		assert!(expand(entries, ProcessingMode::JsonLd1_1).is_ok(), "{}", entries)
+	}
+
+	// Except in JSON-LD 1.0.
+	assert_eq!(expand(r#""@type": "http://example.org/A", "type": "http://example.org/B""#, ProcessingMode::JsonLd1_0).err(), Some(ErrorCode::CollidingKeywords))
+}
@@ -0,0 +1,64 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	ErrorCode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion
+};
+
+const CONTEXT: &str = r#"{
+	"ex": "http://example.org/",
+	"name": "ex:name",
+	"knows": {"@id": "ex:knows", "@type": "@id"},
+	"Person": "ex:Person"
+}"#;
+
+fn expand(doc: &str, compact_iris: bool) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let doc = json::parse(&format!("{{\"@context\": {}, \"@graph\": [{}]}}", CONTEXT, doc)).unwrap();
+	let options = expansion::Options {
+		compact_iris,
+		..expansion::Options::default()
+	};
+
+	task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+fn positive(doc: &str) {
+	if let Err(code) = expand(doc, false) {
+		panic!("unexpected error `{}` for {}", code.as_str(), doc)
+	}
+}
+
+fn negative(doc: &str) {
+	assert!(expand(doc, true).is_ok());
+	assert_eq!(expand(doc, false).err(), Some(ErrorCode::CompactIri), "for {}", doc)
+}
+
+#[test]
+fn terms_and_absolute_iris() {
+	// Terms defined with compact IRIs are exact term matches.
+	positive(r#"{"@id": "http://example.org/a", "@type": "Person", "name": "A", "knows": "http://example.org/b"}"#);
+	positive(r#"{"http://example.org/name": "A", "@type": "http://example.org/Person"}"#);
+	positive(r#"{"_:b": "blank node property"}"#)
+}
+
+#[test]
+fn keys() {
+	negative(r#"{"ex:name": "A"}"#);
+	negative(r#"{"@reverse": {"ex:knows": {"@id": "http://example.org/b"}}}"#)
+}
+
+#[test]
+fn values() {
+	negative(r#"{"@id": "ex:a"}"#);
+	negative(r#"{"@type": "ex:Person"}"#);
+	negative(r#"{"knows": "ex:b"}"#);
+	negative(r#"{"name": {"@value": "A", "@type": "ex:Name"}}"#)
+}
@@ -0,0 +1,218 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::collections::HashSet;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Indexed,
+	Object,
+	Node,
+	Value,
+	Reference,
+	compaction,
+	expansion::{
+		self,
+		KeywordAction
+	},
+	object::Literal,
+	util::AsJson
+};
+
+fn expand(doc: &JsonValue, base_url: Option<Iri>, options: expansion::Options) -> ExpandedDocument<IriBuf> {
+	let initial_context: JsonContext<IriBuf> = JsonContext::new(base_url);
+	task::block_on(doc.expand_with(base_url, &initial_context, &mut NoLoader, options)).unwrap()
+}
+
+fn compact(expanded: &ExpandedDocument<IriBuf>, context: &str, base_url: Option<Iri>, options: compaction::Options) -> JsonValue {
+	let context = json::parse(context).unwrap();
+	task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(expanded, &context, base_url, &mut NoLoader, options)).unwrap()
+}
+
+/// Checks that compacting the expanded document then expanding the result again gives back
+/// the same expanded document.
+fn round_trip(doc: &str, context: &str, base_url: Option<&str>) -> JsonValue {
+	let base_url = base_url.map(|url| Iri::new(url).unwrap());
+	let expanded = expand(&json::parse(doc).unwrap(), base_url, expansion::Options::default());
+	let compacted = compact(&expanded, context, base_url, compaction::Options::default());
+	let reexpanded = expand(&compacted, base_url, expansion::Options::default());
+	assert_eq!(expanded.as_json(), reexpanded.as_json(), "compacted document: {}", compacted.dump());
+	compacted
+}
+
+#[test]
+fn terms_and_compact_iris() {
+	let compacted = round_trip(
+		r#"{"@id": "http://example.org/a", "@type": "http://xmlns.com/foaf/0.1/Person", "http://xmlns.com/foaf/0.1/name": "A", "http://schema.org/age": 3}"#,
+		r#"{"foaf": "http://xmlns.com/foaf/0.1/", "@vocab": "http://schema.org/"}"#,
+		None
+	);
+
+	assert_eq!(compacted["@type"], "foaf:Person");
+	assert_eq!(compacted["foaf:name"], "A");
+	assert_eq!(compacted["age"], 3);
+}
+
+#[test]
+fn containers() {
+	let context = r#"{
+		"@vocab": "http://example.org/",
+		"label": {"@container": "@language"},
+		"list": {"@container": "@list"},
+		"index": {"@container": "@index"},
+		"ids": {"@container": "@id"},
+		"types": {"@container": "@type"},
+		"graph": {"@container": "@graph"}
+	}"#;
+
+	let compacted = round_trip(
+		&format!(r#"{{
+			"@context": {},
+			"label": {{"en": "Hi", "fr": ["Salut", "Coucou"]}},
+			"list": [1, 2, "a"],
+			"index": {{"a": "A", "b": {{"@id": "http://example.org/b", "name": "B"}}}},
+			"ids": {{"http://example.org/x": {{"name": "X"}}}},
+			"types": {{"T": {{"@id": "http://example.org/t"}}}},
+			"graph": {{"name": "in graph"}}
+		}}"#, context),
+		context,
+		None
+	);
+
+	assert_eq!(compacted["list"].len(), 3);
+	assert_eq!(compacted["label"]["en"], "Hi");
+	assert_eq!(compacted["types"]["T"], "http://example.org/t");
+}
+
+#[test]
+fn scoped_contexts() {
+	let context = r#"{
+		"@vocab": "http://example.org/",
+		"Person": {"@context": {"name": "http://xmlns.com/foaf/0.1/name"}},
+		"knows": {"@context": {"age": "http://example.org/scoped-age"}}
+	}"#;
+
+	round_trip(
+		&format!(r#"{{"@context": {}, "@type": "Person", "name": "A", "knows": {{"age": 3}}}}"#, context),
+		context,
+		None
+	);
+}
+
+#[test]
+fn relative_iris() {
+	let compacted = round_trip(
+		r#"{"@id": "http://example.org/dir/doc#me", "http://example.org/p": {"@id": "http://example.org/dir/other"}}"#,
+		r#"{"@vocab": "http://example.org/"}"#,
+		Some("http://example.org/dir/doc")
+	);
+
+	assert_eq!(compacted["@id"], "#me");
+	assert_eq!(compacted["p"]["@id"], "other");
+}
+
+#[test]
+fn graph_option() {
+	let doc = json::parse(r#"{"@id": "_:a", "http://example.org/p": {"@id": "_:b", "http://example.org/q": {"@id": "_:a"}}}"#).unwrap();
+	let expanded = expand(&doc, None, expansion::Options::default());
+	let options = compaction::Options {
+		omit_graph: false,
+		prune_blank_node_identifiers: true,
+		..compaction::Options::default()
+	};
+
+	let compacted = compact(&expanded, r#"{"@vocab": "http://example.org/"}"#, None, options);
+	let node = &compacted["@graph"][0];
+	assert_eq!(node["@id"], "_:a");
+	assert!(node["p"]["@id"].is_null());
+	assert_eq!(node["p"]["q"]["@id"], "_:a");
+}
+
+#[test]
+fn preserved_values() {
+	fn keep(_: &str, _: &JsonValue) -> KeywordAction {
+		KeywordAction::Keep
+	}
+
+	let doc = json::parse(r#"{"@id": "http://example.org/a", "http://example.org/q": {"@preserve": {"@value": "v"}}}"#).unwrap();
+	let options = expansion::Options {
+		unknown_keywords: Some(keep),
+		..expansion::Options::default()
+	};
+
+	let mut expanded = expand(&doc, None, options);
+
+	// Expansion drops `null`, but framing preserves it.
+	let mut null = Node::new();
+	null.insert_extension(compaction::PRESERVE.to_string(), Indexed::new(Object::Value(Value::Literal(Literal::Null, HashSet::new())), None));
+	let mut node = Node::new();
+	node.insert(Reference::Id(IriBuf::new("http://example.org/p").unwrap()), Indexed::new(Object::Node(Box::new(null)), None));
+	expanded.push(Indexed::new(Object::Node(Box::new(node)), None));
+
+	let compacted = compact(&expanded, r#"{"@vocab": "http://example.org/"}"#, None, compaction::Options::default());
+	let graph = &compacted["@graph"];
+	assert_eq!(graph.len(), 2);
+	for node in graph.members() {
+		if node.has_key("p") {
+			assert!(node["p"].is_null())
+		} else {
+			assert_eq!(node["q"], "v")
+		}
+	}
+}
+
+#[test]
+fn value_indexes() {
+	let context = r#"{
+		"@vocab": "http://example.org/",
+		"index": {"@container": "@index"},
+		"dates": {"@container": "@index", "@type": "http://www.w3.org/2001/XMLSchema#date"},
+		"byKey": {"@container": "@index", "@index": "http://example.org/key"}
+	}"#;
+
+	// Value indexes become map keys, and values sharing an index are grouped.
+	// An explicit index takes precedence over the key of the map it is declared in.
+	let compacted = round_trip(
+		&format!(r#"{{
+			"@context": {},
+			"index": {{"a": ["A1", "A2", {{"@value": 3, "@index": "z"}}], "b": {{"@value": "B", "@language": "en"}}, "@none": "N"}},
+			"dates": {{"d": "2020-01-01"}},
+			"plain": {{"@value": "x", "@index": "i"}}
+		}}"#, context),
+		context,
+		None
+	);
+
+	assert_eq!(compacted["index"]["a"], json::array!["A1", "A2"]);
+	assert_eq!(compacted["index"]["z"], 3);
+	assert_eq!(compacted["index"]["b"]["@language"], "en");
+	assert_eq!(compacted["index"]["@none"], "N");
+	assert_eq!(compacted["dates"]["d"], "2020-01-01");
+	assert_eq!(compacted["plain"]["@value"], "x");
+	assert_eq!(compacted["plain"]["@index"], "i");
+
+	// Property-valued index maps do not hold the `@index` of their items, which is kept as an
+	// explicit entry.
+	let compacted = round_trip(
+		r#"{"http://example.org/byKey": [
+			{"@value": "A", "@index": "a"},
+			{"@id": "http://example.org/n", "@index": "b", "http://example.org/key": "K"}
+		]}"#,
+		context,
+		None
+	);
+
+	assert_eq!(compacted["byKey"]["@none"]["@index"], "a");
+	assert_eq!(compacted["byKey"]["K"]["@index"], "b");
+
+	// Values cannot be indexed by a property.
+	let doc = json::parse(&format!(r#"{{"@context": {}, "byKey": {{"a": "A"}}}}"#, context)).unwrap();
+	let result: Result<ExpandedDocument<IriBuf>, _> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader));
+	assert_eq!(result.err().map(|e| e.code()), Some(json_ld::ErrorCode::InvalidValueObject))
+}
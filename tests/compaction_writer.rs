@@ -0,0 +1,63 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	compaction,
+	context::Local
+};
+
+const CONTEXT: &str = r#"{
+	"schema": "http://schema.org/",
+	"name": "schema:name",
+	"knows": {"@id": "schema:knows", "@type": "@id"},
+	"data": "@graph"
+}"#;
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn stream(expanded: &ExpandedDocument<IriBuf>, context: Option<&json::JsonValue>) -> json::JsonValue {
+	let local = json::parse(CONTEXT).unwrap();
+	let active_context: JsonContext<IriBuf> = task::block_on(local.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	let mut writer = compaction::Writer::new(Vec::new(), &active_context, context, compaction::Options::default()).unwrap();
+	task::block_on(writer.write_all(expanded, &mut NoLoader)).unwrap();
+	assert_eq!(writer.len(), expanded.len());
+	json::parse(std::str::from_utf8(&writer.finish().unwrap()).unwrap()).unwrap()
+}
+
+#[test]
+fn same_as_compact() {
+	let expanded = expand(r#"[
+		{"@id": "http://example.org/alice", "http://schema.org/name": "Alice", "http://schema.org/knows": {"@id": "http://example.org/bob"}},
+		{"@id": "http://example.org/bob", "http://schema.org/name": ["Bob", "Robert"]}
+	]"#);
+
+	let context = json::parse(CONTEXT).unwrap();
+	let options = compaction::Options {
+		omit_graph: false,
+		..compaction::Options::default()
+	};
+	let compacted = task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, &context, None, &mut NoLoader, options)).unwrap();
+
+	assert_eq!(stream(&expanded, Some(&context)), compacted);
+	assert_eq!(compacted["data"].len(), 2);
+}
+
+#[test]
+fn without_context() {
+	let expanded = expand(r#"{"@id": "http://example.org/alice", "http://schema.org/name": "Alice"}"#);
+	assert_eq!(stream(&expanded, None), json::parse(r#"{
+		"data": [{"@id": "http://example.org/alice", "name": "Alice"}]
+	}"#).unwrap());
+
+	assert_eq!(stream(&ExpandedDocument::new(), None), json::parse(r#"{"data": []}"#).unwrap());
+}
@@ -0,0 +1,71 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	expansion,
+	util::is_isomorphic
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"id": "@id",
+	"type": "@type",
+	"knows": {"@type": "@id"},
+	"birthDate": {"@type": "http://www.w3.org/2001/XMLSchema#date"}
+}"#;
+
+fn process(context: &str) -> JsonContext<IriBuf> {
+	let context = json::parse(context).unwrap();
+	task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap()
+}
+
+fn records(n: usize) -> JsonValue {
+	let mut records = JsonValue::new_array();
+	for i in 0..n {
+		records.push(json::parse(&format!(r#"{{
+			"id": "http://example.org/people/{}",
+			"type": "Person",
+			"name": "Person {}",
+			"birthDate": "2000-01-01",
+			"knows": "http://example.org/people/{}"
+		}}"#, i, i, (i + 1) % n)).unwrap()).unwrap();
+	}
+
+	records
+}
+
+#[test]
+fn detection() {
+	let context = process(CONTEXT);
+	assert!(expansion::is_context_free(&context, &records(3)));
+
+	let embedded = json::parse(r#"{"name": "A", "knows": {"@context": {"name": "http://xmlns.com/foaf/0.1/name"}, "name": "B"}}"#).unwrap();
+	assert!(!expansion::is_context_free(&context, &embedded));
+
+	let scoped = process(r#"{"@vocab": "http://schema.org/", "Person": {"@context": {"name": "http://xmlns.com/foaf/0.1/name"}}}"#);
+	assert!(!expansion::is_context_free(&scoped, &records(3)));
+}
+
+#[test]
+fn same_result_as_embedded_context() {
+	let records = records(20);
+	let context = process(CONTEXT);
+	let fast: ExpandedDocument<IriBuf> = task::block_on(records.expand(&context, &mut NoLoader)).unwrap();
+
+	let mut document = json::object::Object::new();
+	document.insert("@context", json::parse(CONTEXT).unwrap());
+	document.insert("@graph", records);
+	let slow: ExpandedDocument<IriBuf> = task::block_on(JsonValue::Object(document).expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+
+	assert_eq!(fast.len(), 20);
+	assert!(is_isomorphic(&fast, &slow));
+}
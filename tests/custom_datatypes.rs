@@ -0,0 +1,127 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	object::datatype::{
+		Datatype,
+		Datatypes
+	},
+	generator,
+	rdf
+};
+
+const WKT_LITERAL: &str = "http://www.opengis.net/ont/geosparql#wktLiteral";
+const METERS: &str = "http://example.org/units#meters";
+
+/// Well-known text geometry, with normalized whitespace.
+struct WktLiteral;
+
+impl Datatype for WktLiteral {
+	type Value = String;
+
+	fn parse(&self, lexical: &str) -> Option<String> {
+		let lexical = lexical.trim();
+		if lexical.ends_with(')') && lexical.contains('(') {
+			Some(self.canonicalize(lexical))
+		} else {
+			None
+		}
+	}
+
+	fn canonicalize(&self, lexical: &str) -> String {
+		lexical.split_whitespace().collect::<Vec<_>>().join(" ").replace(" (", "(").to_uppercase()
+	}
+}
+
+/// Length in meters, written with a `m` suffix.
+struct Meters;
+
+impl Datatype for Meters {
+	type Value = f64;
+
+	fn parse(&self, lexical: &str) -> Option<f64> {
+		lexical.trim().strip_suffix('m')?.trim().parse().ok()
+	}
+
+	fn canonicalize(&self, lexical: &str) -> String {
+		format!("{}m", self.parse(lexical).unwrap())
+	}
+}
+
+fn datatypes() -> Datatypes {
+	Datatypes::new()
+		.with(Iri::new(WKT_LITERAL).unwrap(), WktLiteral)
+		.with(Iri::new(METERS).unwrap(), Meters)
+}
+
+fn expand() -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(r#"{
+		"@context": {
+			"geo": "http://www.opengis.net/ont/geosparql#",
+			"units": "http://example.org/units#",
+			"location": {"@id": "http://example.org/location", "@type": "geo:wktLiteral"},
+			"height": {"@id": "http://example.org/height", "@type": "units:meters"},
+			"depth": {"@id": "http://example.org/depth", "@type": "units:meters"}
+		},
+		"@id": "http://example.org/tower",
+		"location": "point  (2.29 48.86)",
+		"height": " 330.0 m",
+		"depth": "deep"
+	}"#).unwrap();
+
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn property(document: &ExpandedDocument<IriBuf>, name: &str) -> json_ld::Value<IriBuf> {
+	let node = document.iter().next().unwrap().as_node().unwrap();
+	let prop = Reference::Id(IriBuf::new(name).unwrap());
+	node.get(&prop).next().unwrap().as_value().unwrap().clone()
+}
+
+#[test]
+fn value_accessors() {
+	let datatypes = datatypes();
+	assert_eq!(datatypes.len(), 2);
+
+	let document = expand();
+	let height = property(&document, "http://example.org/height");
+	assert_eq!(height.parse_with::<f64>(&datatypes), Some(330.0));
+	assert_eq!(height.parse_with::<String>(&datatypes), None);
+	assert_eq!(height.validate_with(&datatypes), Some(true));
+	assert_eq!(height.canonical_with(&datatypes), Some("330m".to_string()));
+
+	let depth = property(&document, "http://example.org/depth");
+	assert_eq!(depth.validate_with(&datatypes), Some(false));
+	assert_eq!(depth.parse_with::<f64>(&datatypes), None);
+	assert_eq!(depth.canonical_with(&datatypes), None);
+
+	// Unregistered datatypes are not consulted.
+	assert_eq!(height.validate_with(&Datatypes::new()), None);
+
+	let location = property(&document, "http://example.org/location");
+	assert_eq!(location.parse_with::<String>(&datatypes), Some("POINT(2.29 48.86)".to_string()));
+}
+
+#[test]
+fn canonical_literals() {
+	let document = expand();
+	let quads = rdf::to_rdf_with_datatypes(&document, generator::Blank::new(), rdf::Options::default(), &datatypes()).unwrap();
+	let nquads: Vec<String> = quads.iter().map(ToString::to_string).collect();
+	assert_eq!(nquads, vec![
+		"<http://example.org/tower> <http://example.org/depth> \"deep\"^^<http://example.org/units#meters> .",
+		"<http://example.org/tower> <http://example.org/height> \"330m\"^^<http://example.org/units#meters> .",
+		"<http://example.org/tower> <http://example.org/location> \"POINT(2.29 48.86)\"^^<http://www.opengis.net/ont/geosparql#wktLiteral> ."
+	]);
+
+	// Without the registry, lexical forms are kept.
+	let quads = rdf::to_rdf(&document, generator::Blank::new(), rdf::Options::default()).unwrap();
+	assert_eq!(quads[1].to_string(), "<http://example.org/tower> <http://example.org/height> \" 330.0 m\"^^<http://example.org/units#meters> .");
+}
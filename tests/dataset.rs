@@ -0,0 +1,170 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	BlankId,
+	Dataset,
+	Document,
+	ErrorCode,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	generator,
+	util::AsJson
+};
+
+const CONTEXT: &str = r#"{"@vocab": "http://schema.org/", "knows": {"@type": "@id"}}"#;
+
+fn dataset(doc: &str) -> Dataset<IriBuf> {
+	let doc = json::parse(&format!(r#"{{"@context": {}, "@graph": {}}}"#, CONTEXT, doc)).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	Dataset::try_from(expanded).unwrap()
+}
+
+fn assert_json(dataset: &Dataset<IriBuf>, expected: &str) {
+	let expected = json::parse(expected).unwrap();
+	assert_eq!(dataset.as_json(), expected, "{}", dataset.as_json().pretty(2))
+}
+
+#[test]
+fn merge_nodes() {
+	let mut a = dataset(r#"[
+		{"@id": "http://example.org/alice", "@type": "Person", "name": "Alice", "knows": "http://example.org/bob"},
+		{"name": "anonymous"}
+	]"#);
+
+	a.merge(dataset(r#"[
+		{"@id": "http://example.org/alice", "@type": ["Person", "Agent"], "name": ["Alice", "Alicia"]},
+		{"@id": "http://example.org/bob", "name": "Bob"}
+	]"#)).unwrap();
+
+	assert_eq!(a.len(), 3);
+	assert_json(&a, r#"[
+		{
+			"@id": "http://example.org/alice",
+			"@type": ["http://schema.org/Person", "http://schema.org/Agent"],
+			"http://schema.org/name": [{"@value": "Alice"}, {"@value": "Alicia"}],
+			"http://schema.org/knows": [{"@id": "http://example.org/bob"}]
+		},
+		{"@id": "http://example.org/bob", "http://schema.org/name": [{"@value": "Bob"}]},
+		{"http://schema.org/name": [{"@value": "anonymous"}]}
+	]"#);
+}
+
+#[test]
+fn merge_within_document() {
+	// Top-level nodes of a single document are merged too.
+	let dataset = dataset(r#"[
+		{"@id": "http://example.org/alice", "name": "Alice"},
+		{"@id": "http://example.org/alice", "@reverse": {"knows": {"@id": "http://example.org/bob"}}},
+		{"@id": "http://example.org/alice", "@reverse": {"knows": [{"@id": "http://example.org/bob"}, {"@id": "http://example.org/carol"}]}}
+	]"#);
+
+	assert_json(&dataset, r#"[{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": [{"@value": "Alice"}],
+		"@reverse": {"http://schema.org/knows": [{"@id": "http://example.org/bob"}, {"@id": "http://example.org/carol"}]}
+	}]"#);
+}
+
+#[test]
+fn merge_named_graphs() {
+	let mut a = dataset(r#"[{
+		"@id": "http://example.org/graph",
+		"@graph": [
+			{"@id": "http://example.org/alice", "name": "Alice"},
+			{"@id": "http://example.org/bob", "name": "Bob"}
+		]
+	}]"#);
+
+	a.merge(dataset(r#"[{
+		"@id": "http://example.org/graph",
+		"@graph": {"@id": "http://example.org/alice", "knows": "http://example.org/bob"}
+	}]"#)).unwrap();
+
+	let graph = a.graph(&Reference::Id(IriBuf::new("http://example.org/graph").unwrap())).unwrap();
+	assert_eq!(graph.len(), 2);
+	assert_json(&a, r#"[{
+		"@id": "http://example.org/graph",
+		"@graph": [
+			{"@id": "http://example.org/alice", "http://schema.org/name": [{"@value": "Alice"}], "http://schema.org/knows": [{"@id": "http://example.org/bob"}]},
+			{"@id": "http://example.org/bob", "http://schema.org/name": [{"@value": "Bob"}]}
+		]
+	}]"#);
+}
+
+#[test]
+fn conflicting_indexes() {
+	let mut a = dataset(r#"[{"@id": "http://example.org/alice", "@index": "a"}]"#);
+	match a.merge(dataset(r#"[{"@id": "http://example.org/alice", "@index": "b"}]"#)) {
+		Err(e) => assert_eq!(e.code(), ErrorCode::ConflictingIndexes),
+		Ok(()) => panic!("indexes should conflict")
+	}
+
+	// The same index, or a missing index, is fine.
+	let mut a = dataset(r#"[{"@id": "http://example.org/alice", "@index": "a"}]"#);
+	a.merge(dataset(r#"[{"@id": "http://example.org/alice", "@index": "a", "name": "Alice"}, {"@id": "http://example.org/alice"}]"#)).unwrap();
+	assert_json(&a, r#"[{"@id": "http://example.org/alice", "@index": "a", "http://schema.org/name": [{"@value": "Alice"}]}]"#);
+}
+
+#[test]
+fn blank_node_renaming() {
+	let mut a = dataset(r#"[{"@id": "_:b0", "name": "A"}, {"@id": "http://example.org/a", "knows": "_:b0"}]"#);
+	let b = dataset(r#"[
+		{"@id": "_:b0", "name": "B"},
+		{"@id": "_:b1", "name": "C"},
+		{"@id": "http://example.org/b", "knows": ["_:b0", "_:b1"], "http://schema.org/about": {"@list": [{"@id": "_:b0"}]}}
+	]"#);
+
+	a.merge(b).unwrap();
+
+	// Only the colliding `_:b0` of the merged dataset is renamed, everywhere it is used.
+	assert_json(&a, r#"[
+		{"@id": "_:b0", "http://schema.org/name": [{"@value": "A"}]},
+		{"@id": "_:b1", "http://schema.org/name": [{"@value": "C"}]},
+		{"@id": "_:m0", "http://schema.org/name": [{"@value": "B"}]},
+		{"@id": "http://example.org/a", "http://schema.org/knows": [{"@id": "_:b0"}]},
+		{
+			"@id": "http://example.org/b",
+			"http://schema.org/knows": [{"@id": "_:m0"}, {"@id": "_:b1"}],
+			"http://schema.org/about": [{"@list": [{"@id": "_:m0"}]}]
+		}
+	]"#);
+
+	let mut ids: Vec<String> = a.blank_ids().iter().map(|id| id.as_str().to_string()).collect();
+	ids.sort();
+	assert_eq!(ids, vec!["_:b0", "_:b1", "_:m0"]);
+}
+
+#[test]
+fn blank_node_renaming_skips_used_ids() {
+	let mut a = dataset(r#"[{"@id": "_:b0", "name": "A"}, {"@id": "_:g0", "name": "G0"}]"#);
+	let b = dataset(r#"[{"@id": "_:b0", "name": "B"}, {"@id": "_:g1", "name": "G1"}]"#);
+
+	// `_:g0` is used by the dataset, and `_:g1` by the merged one.
+	a.merge_with(b, generator::Blank::new_with_prefix("g".to_string())).unwrap();
+	assert!(a.get(&Reference::Blank(BlankId::new("g2"))).is_some());
+	assert_json(&a, r#"[
+		{"@id": "_:b0", "http://schema.org/name": [{"@value": "A"}]},
+		{"@id": "_:g0", "http://schema.org/name": [{"@value": "G0"}]},
+		{"@id": "_:g1", "http://schema.org/name": [{"@value": "G1"}]},
+		{"@id": "_:g2", "http://schema.org/name": [{"@value": "B"}]}
+	]"#);
+}
+
+#[test]
+fn into_expanded() {
+	let dataset = dataset(r#"[
+		{"@id": "http://example.org/b", "name": "B"},
+		{"name": "anonymous"},
+		{"@id": "http://example.org/a", "name": "A"}
+	]"#);
+
+	let ids: Vec<Option<String>> = dataset.into_expanded().into_iter().map(|object| object.as_json()["@id"].as_str().map(String::from)).collect();
+	assert_eq!(ids, vec![None, Some("http://example.org/a".to_string()), Some("http://example.org/b".to_string())]);
+}
@@ -0,0 +1,73 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Deduplication,
+	util::AsJson
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let mut doc = json::parse(doc).unwrap();
+	let context = json::parse(r#"{"@vocab": "http://schema.org/", "ex": "http://example.org/"}"#).unwrap();
+	for object in doc.members_mut() {
+		object.insert("@context", context.clone()).unwrap()
+	}
+
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+#[test]
+fn dedup() {
+	let mut doc = expand(r#"[
+		{
+			"@id": "ex:alice",
+			"@type": ["Person", "Person"],
+			"name": ["Alice", "Alice"],
+			"knows": [{"@id": "ex:bob", "name": "Bob"}, {"@id": "ex:bob", "age": 42}, {"@id": "ex:bob", "name": "Bob"}],
+			"tags": {"@list": [1, 1]}
+		},
+		{"@id": "ex:alice", "name": "Alice", "email": "alice@example.org"},
+		{"name": "Anonymous"},
+		{"name": "Anonymous"}
+	]"#);
+
+	let report = doc.dedup();
+	assert_eq!(report, Deduplication {
+		duplicates: 5,
+		merged_nodes: 3
+	});
+	assert_eq!(report.removed(), 8);
+
+	let expected = expand(r#"[
+		{
+			"@id": "ex:alice",
+			"@type": "Person",
+			"name": "Alice",
+			"knows": {"@id": "ex:bob", "name": "Bob", "age": 42},
+			"tags": {"@list": [1, 1]},
+			"email": "alice@example.org"
+		},
+		{"name": "Anonymous"}
+	]"#);
+	assert_eq!(doc.as_json(), expected.as_json());
+
+	assert!(doc.dedup().is_empty())
+}
+
+#[test]
+fn indexes() {
+	// Nodes with different indexes are not merged.
+	let mut doc = expand(r#"[{
+		"@id": "ex:alice",
+		"knows": [{"@id": "ex:bob", "@index": "a"}, {"@id": "ex:bob", "@index": "b"}]
+	}]"#);
+
+	assert!(doc.dedup().is_empty())
+}
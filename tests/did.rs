@@ -0,0 +1,106 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	ExpandedDocument,
+	NoLoader,
+	Reference,
+	preset::did::{
+		self,
+		DidDocument
+	},
+	util::AsJson
+};
+
+const ED25519_2020_URL: &str = "https://w3id.org/security/suites/ed25519-2020/v1";
+
+/// `did:key` document of the `did:key` method specification.
+const DID_KEY: &str = r#"{
+	"@context": [
+		"https://www.w3.org/ns/did/v1",
+		"https://w3id.org/security/suites/ed25519-2020/v1"
+	],
+	"id": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+	"verificationMethod": [{
+		"id": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+		"type": "Ed25519VerificationKey2020",
+		"controller": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+		"publicKeyMultibase": "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+	}],
+	"authentication": [
+		"did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+	],
+	"assertionMethod": [
+		"did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+	],
+	"capabilityDelegation": [
+		"did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+	],
+	"capabilityInvocation": [
+		"did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+	]
+}"#;
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let ed25519 = json::parse(include_str!("contexts/ed25519-2020-v1.jsonld")).unwrap();
+	let mut loader = did::loader(NoLoader).with(Iri::new(ED25519_2020_URL).unwrap(), ed25519);
+	task::block_on(did::expand(&json::parse(doc).unwrap(), &mut loader)).unwrap()
+}
+
+fn id(iri: &str) -> Reference {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+#[test]
+fn did_key_document() {
+	let expanded = expand(DID_KEY);
+	let doc = DidDocument::from_document(&expanded).unwrap();
+	let key = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+	assert_eq!(doc.id(), Some(&id("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK")));
+
+	let methods = doc.verification_methods();
+	assert_eq!(methods.len(), 1);
+	assert_eq!(methods[0].id(), Some(&id(key)));
+	assert_eq!(methods[0].controller(), doc.id());
+	assert_eq!(methods[0].public_key_multibase(), Some("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"));
+
+	for methods in &[doc.authentication(), doc.assertion_method(), doc.capability_delegation(), doc.capability_invocation()] {
+		assert_eq!(methods.len(), 1);
+		assert!(!methods[0].is_reference());
+		assert_eq!(methods[0].id(), Some(&id(key)));
+	}
+
+	assert!(doc.key_agreement().is_empty());
+}
+
+#[test]
+fn single_verification_method() {
+	// A verification method given as a single object is one embedded node.
+	let expanded = expand(r#"{
+		"@context": "https://www.w3.org/ns/did/v1",
+		"id": "did:example:123",
+		"verificationMethod": {
+			"id": "did:example:123#key-1",
+			"type": "Ed25519VerificationKey2020",
+			"controller": "did:example:123"
+		}
+	}"#);
+
+	let json = expanded.as_json();
+	let methods = &json[0][did::VERIFICATION_METHOD];
+	assert_eq!(methods.len(), 1);
+	assert_eq!(methods[0]["@id"], "did:example:123#key-1");
+	assert_eq!(methods[0][did::CONTROLLER][0]["@id"], "did:example:123");
+}
+
+#[test]
+fn published_context() {
+	let context = json::parse(did::CONTEXT).unwrap();
+	let verification_method = &context["@context"]["verificationMethod"];
+	assert_eq!(verification_method["@id"], did::VERIFICATION_METHOD);
+	assert!(verification_method["@container"].is_null());
+}
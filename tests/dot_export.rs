@@ -0,0 +1,77 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Dataset,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	context::Local,
+	rdf::{
+		self,
+		DotOptions,
+		Prefixes
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"ex": "http://example.org/",
+	"schema": "http://schema.org/",
+	"knows": "http://schema.org/knows"
+}"#;
+
+fn dataset(doc: &str) -> Dataset<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	Dataset::try_from(expanded).unwrap()
+}
+
+#[test]
+fn compacted_labels() {
+	let context: JsonContext<IriBuf> = task::block_on(json::parse(CONTEXT).unwrap().process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	let dataset = dataset(r#"[
+		{
+			"@id": "http://example.org/alice",
+			"http://schema.org/knows": {"@id": "http://example.org/bob"},
+			"http://schema.org/name": {"@value": "Alice \"A\"", "@language": "en"}
+		},
+		{
+			"@id": "http://example.org/graph",
+			"@graph": {"@id": "http://example.org/bob", "http://schema.org/age": 42}
+		}
+	]"#);
+
+	assert_eq!(dataset.to_dot(&DotOptions::from_context(&context)).unwrap(), r#"digraph {
+	"http://example.org/alice" [label="ex:alice"];
+	"http://example.org/bob" [label="ex:bob"];
+	"http://example.org/alice" -> "http://example.org/bob" [label="knows"];
+	"literal 0" [label="\"Alice \"A\"\"@en", shape=box];
+	"http://example.org/alice" -> "literal 0" [label="schema:name"];
+	subgraph "cluster_1" {
+		label="ex:graph";
+		"1:http://example.org/bob" [label="ex:bob"];
+		"1:literal 1" [label="\"42\"^^http://www.w3.org/2001/XMLSchema#integer", shape=box];
+		"1:http://example.org/bob" -> "1:literal 1" [label="schema:age"];
+	}
+}
+"#);
+}
+
+#[test]
+fn full_iris() {
+	let quads: Vec<_> = dataset(r#"{"http://schema.org/knows": {"@id": "http://example.org/bob"}}"#).match_quads(None, None, None, None).unwrap().collect();
+	assert_eq!(rdf::to_dot(&quads, &DotOptions::new()), r#"digraph {
+	"_:b0" [label="_:b0"];
+	"http://example.org/bob" [label="http://example.org/bob"];
+	"_:b0" -> "http://example.org/bob" [label="http://schema.org/knows"];
+}
+"#);
+
+	let options = DotOptions::new().with_prefixes(Prefixes::new().with("schema", Iri::new("http://schema.org/").unwrap()));
+	assert_eq!(options.label(Iri::new("http://schema.org/knows").unwrap()), "schema:knows");
+}
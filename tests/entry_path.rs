@@ -0,0 +1,67 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Error,
+	ErrorCode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader
+};
+
+fn expand_err(doc: &str) -> Error {
+	let doc = json::parse(doc).unwrap();
+	let result: Result<ExpandedDocument<IriBuf>, Error> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader));
+	match result {
+		Ok(_) => panic!("expansion should fail"),
+		Err(e) => e
+	}
+}
+
+#[test]
+fn entry_path() {
+	let e = expand_err(r#"{
+		"@context": {"knows": "http://xmlns.com/foaf/0.1/knows"},
+		"@id": "http://example.org/alice",
+		"knows": {"@id": 12}
+	}"#);
+
+	assert_eq!(e.code(), ErrorCode::InvalidIdValue);
+	assert_eq!(e.entry_path(), ["knows", "@id"]);
+	assert_eq!(e.to_string(), "invalid @id value (in entry `knows` > `@id`)");
+}
+
+#[test]
+fn aliased_keyword() {
+	let e = expand_err(r#"{
+		"@context": {"type": "@type"},
+		"type": 12
+	}"#);
+
+	assert_eq!(e.code(), ErrorCode::InvalidTypeValue);
+	assert_eq!(e.entry_path(), ["type"]);
+}
+
+#[test]
+fn nested_entries() {
+	let e = expand_err(r#"{
+		"@context": {"@version": 1.1, "details": "@nest"},
+		"details": {"@id": true}
+	}"#);
+
+	assert_eq!(e.entry_path(), ["details", "@id"]);
+}
+
+#[test]
+fn no_entry() {
+	let e = expand_err(r#"{
+		"@context": {"@id": "http://example.org/"}
+	}"#);
+
+	assert!(e.entry_path().is_empty());
+	assert_eq!(e.to_string(), e.code().as_str());
+}
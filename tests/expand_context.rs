@@ -0,0 +1,65 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	RemoteDocument,
+	expansion,
+	preset::PresetLoader,
+	util::AsJson
+};
+
+const CONTEXT: &str = r#"{"@vocab": "http://schema.org/", "knows": {"@type": "@id"}}"#;
+
+fn expand_with_context<D: Send + Sync + Document<IriBuf, LocalContext = JsonValue>>(doc: &D, context: &str) -> JsonValue {
+	let context = json::parse(context).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(expansion::expand_with_context(doc, &context, &mut NoLoader, expansion::Options::default())).unwrap();
+	expanded.as_json()
+}
+
+#[test]
+fn same_as_embedded_context() {
+	let record = json::parse(r#"{"@id": "http://example.org/a", "name": "A", "knows": "http://example.org/b"}"#).unwrap();
+
+	let mut embedded = record.clone();
+	embedded.insert("@context", json::parse(CONTEXT).unwrap()).unwrap();
+	let expected: ExpandedDocument<IriBuf> = task::block_on(embedded.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+
+	assert_eq!(expand_with_context(&record, CONTEXT), expected.as_json());
+	assert_eq!(expand_with_context(&record, &format!(r#"{{"@context": {}}}"#, CONTEXT)), expected.as_json());
+}
+
+#[test]
+fn document_context_comes_next() {
+	let record = json::parse(r#"{"@context": {"name": "http://xmlns.com/foaf/0.1/name"}, "name": "A", "age": 3}"#).unwrap();
+	let expanded = expand_with_context(&record, CONTEXT);
+	assert_eq!(expanded[0]["http://xmlns.com/foaf/0.1/name"][0]["@value"], "A");
+	assert_eq!(expanded[0]["http://schema.org/age"][0]["@value"], 3);
+}
+
+#[test]
+fn document_base_url() {
+	let record = json::parse(r#"{"@id": "a", "knows": "b"}"#).unwrap();
+	let record = RemoteDocument::new(record, Iri::new("http://example.org/records/").unwrap());
+	let expanded = expand_with_context(&record, CONTEXT);
+	assert_eq!(expanded[0]["@id"], "http://example.org/records/a");
+	assert_eq!(expanded[0]["http://schema.org/knows"][0]["@id"], "http://example.org/records/b");
+}
+
+#[test]
+fn context_url() {
+	let url = Iri::new("http://example.org/context.jsonld").unwrap();
+	let mut loader = PresetLoader::new(NoLoader).with(url, json::parse(&format!(r#"{{"@context": {}}}"#, CONTEXT)).unwrap());
+
+	let record = json::parse(r#"{"name": "A"}"#).unwrap();
+	let context = JsonValue::from(url.as_str());
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(expansion::expand_with_context(&record, &context, &mut loader, expansion::Options::default())).unwrap();
+	assert_eq!(expanded.as_json()[0]["http://schema.org/name"][0]["@value"], "A");
+}
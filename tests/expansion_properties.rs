@@ -0,0 +1,249 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	ProcessingMode,
+	compaction,
+	expansion,
+	util::AsJson
+};
+use proptest::{
+	prelude::*,
+	collection::vec,
+	sample::{select, subsequence}
+};
+
+/// Number of generated documents per property.
+const CASES: u32 = 5000;
+
+const KEYWORDS: &[&str] = &[
+	"@id", "@type", "@value", "@language", "@direction", "@list", "@set", "@graph", "@index",
+	"@reverse", "@included", "@nest", "@json", "@none", "@vocab", "@base", "@version",
+	"@protected", "@propagate", "@container", "@context", "@prefix", "@import"
+];
+
+const TERMS: &[&str] = &[
+	"name", "knows", "type", "id", "ex", "ex:p", "ex:q", "_:b0", "_:b1", "http://example.org/p",
+	"http://example.org/q", "https://schema.org/name", "relative", "#fragment", "en", "ltr", "rtl", ""
+];
+
+const DEFINITION_KEYS: &[&str] = &[
+	"@id", "@type", "@container", "@language", "@direction", "@reverse", "@prefix", "@protected",
+	"@context", "@nest", "@index"
+];
+
+/// Well-formed term definitions, combined into the contexts used for compaction.
+const COMPACTION_DEFINITIONS: &[(&str, &str)] = &[
+	("@vocab", r#""http://example.org/""#),
+	("@language", r#""en""#),
+	("org", r#""http://example.org/""#),
+	("name", r#""http://example.org/p""#),
+	("knows", r#"{"@id": "http://example.org/q", "@type": "@id"}"#),
+	("tags", r#"{"@id": "http://example.org/p", "@container": "@set"}"#),
+	("items", r#"{"@id": "http://example.org/q", "@container": "@list"}"#),
+	("labels", r#"{"@id": "https://schema.org/name", "@container": "@language"}"#),
+	("indexed", r#"{"@id": "http://example.org/p", "@container": "@index"}"#),
+	("untagged", r#"{"@id": "http://example.org/q", "@language": null}"#)
+];
+
+fn key() -> impl Strategy<Value = &'static str> {
+	prop_oneof![1 => select(KEYWORDS), 2 => select(TERMS)]
+}
+
+fn string() -> impl Strategy<Value = &'static str> {
+	prop_oneof![1 => select(KEYWORDS), 3 => select(TERMS)]
+}
+
+fn object(entries: Vec<(&str, JsonValue)>) -> JsonValue {
+	let mut object = JsonValue::new_object();
+	for (key, value) in entries {
+		object.insert(key, value).unwrap();
+	}
+	object
+}
+
+fn scalar() -> impl Strategy<Value = JsonValue> {
+	prop_oneof![
+		1 => Just(JsonValue::Null),
+		1 => any::<bool>().prop_map(JsonValue::from),
+		1 => prop_oneof![(-50..50).prop_map(JsonValue::from), (0..100u8).prop_map(|n| JsonValue::from(n as f64 / 8.0))],
+		2 => string().prop_map(JsonValue::from)
+	]
+}
+
+fn definition() -> impl Strategy<Value = JsonValue> {
+	let value = prop_oneof![Just(JsonValue::Null), any::<bool>().prop_map(JsonValue::from), string().prop_map(JsonValue::from)];
+	prop_oneof![
+		1 => Just(JsonValue::Null),
+		1 => string().prop_map(JsonValue::from),
+		2 => vec((select(DEFINITION_KEYS), value), 0..4).prop_map(object)
+	]
+}
+
+/// Context entry, whose value depends on its key.
+fn context_entry() -> impl Strategy<Value = (&'static str, JsonValue)> {
+	key().prop_flat_map(|key| {
+		let value = match key {
+			"@version" => prop_oneof![Just(JsonValue::from(1.1)), scalar()].boxed(),
+			"@vocab" | "@base" | "@language" | "@direction" => scalar().boxed(),
+			"@protected" | "@propagate" => any::<bool>().prop_map(JsonValue::from).boxed(),
+			_ => definition().boxed()
+		};
+
+		(Just(key), value)
+	})
+}
+
+fn context() -> impl Strategy<Value = JsonValue> {
+	let local = prop_oneof![1 => Just(JsonValue::Null), 3 => vec(context_entry(), 0..5).prop_map(object)];
+	local.prop_recursive(2, 8, 3, |inner| vec(inner, 0..3).prop_map(JsonValue::from))
+}
+
+/// Object entry, with a generated context for `@context` keys.
+fn entry(value: BoxedStrategy<JsonValue>) -> impl Strategy<Value = (&'static str, JsonValue)> {
+	key().prop_flat_map(move |key| {
+		let value = if key == "@context" {
+			context().boxed()
+		} else {
+			value.clone()
+		};
+
+		(Just(key), value)
+	})
+}
+
+fn value() -> impl Strategy<Value = JsonValue> {
+	scalar().prop_recursive(3, 64, 5, |inner| prop_oneof![
+		1 => vec(inner.clone(), 0..4).prop_map(JsonValue::from),
+		1 => vec(entry(inner), 0..5).prop_map(object)
+	])
+}
+
+/// Top-level document: an object of arbitrary values.
+fn document() -> impl Strategy<Value = JsonValue> {
+	vec(entry(value().boxed()), 0..5).prop_map(object)
+}
+
+fn compaction_context() -> impl Strategy<Value = JsonValue> {
+	subsequence(COMPACTION_DEFINITIONS, 0..=COMPACTION_DEFINITIONS.len()).prop_map(|definitions| {
+		object(definitions.into_iter().map(|(term, definition)| (term, json::parse(definition).unwrap())).collect())
+	})
+}
+
+fn expand(doc: &JsonValue) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	expand_from(None, doc)
+}
+
+/// Base IRI of the documents compacted by `compaction_round_trip`.
+fn base_iri() -> Iri<'static> {
+	Iri::new("http://example.com/doc").unwrap()
+}
+
+/// Expand a document with the given base IRI.
+fn expand_from(base_url: Option<Iri>, doc: &JsonValue) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let initial_context: JsonContext<IriBuf> = JsonContext::new(base_url);
+	let options = expansion::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		ordered: true,
+		..expansion::Options::default()
+	};
+
+	task::block_on(doc.expand_with(base_url, &initial_context, &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+fn compact(document: &ExpandedDocument<IriBuf>, context: &JsonValue) -> Result<JsonValue, ErrorCode> {
+	let options = compaction::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		ordered: true,
+		..compaction::Options::default()
+	};
+
+	task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(document, context, None, &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+fn check_error(doc: &JsonValue, code: ErrorCode) -> Result<(), TestCaseError> {
+	prop_assert_eq!(ErrorCode::try_from(code.as_str()), Ok(code), "unknown error code for {}", doc.dump());
+	prop_assert!(code != ErrorCode::Cancelled, "unexpected cancellation for {}", doc.dump());
+	Ok(())
+}
+
+/// Expands twice, reaching the fixed point of the expansion.
+///
+/// One extra round is needed since the top-level `@graph` unwrapping may apply to the expanded
+/// form of a document but not to the document itself (e.g. `{"@graph": {"@graph": {}}}`
+/// expands into `[{"@graph": []}]`, which expands into `[]`).
+fn normalize(expanded: &ExpandedDocument<IriBuf>) -> Result<JsonValue, ErrorCode> {
+	Ok(expand(&expand(&expanded.as_json())?.as_json())?.as_json())
+}
+
+/// Sort the arrays of an expanded document, whose order is not significant, except lists.
+fn canonical(json: &JsonValue) -> JsonValue {
+	match json {
+		JsonValue::Array(items) => {
+			let mut items: Vec<_> = items.iter().map(canonical).collect();
+			items.sort_by_cached_key(|item| item.dump());
+			items.into()
+		},
+		JsonValue::Object(object) => {
+			let mut result = JsonValue::new_object();
+			for (key, value) in object.iter() {
+				let value = match (key, value) {
+					("@list", JsonValue::Array(items)) => items.iter().map(canonical).collect::<Vec<_>>().into(),
+					_ => canonical(value)
+				};
+				result.insert(key, value).unwrap();
+			}
+			result
+		},
+		json => json.clone()
+	}
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(CASES))]
+
+	#[test]
+	fn expansion_never_panics(doc in document()) {
+		if let Err(code) = expand(&doc) {
+			check_error(&doc, code)?
+		}
+	}
+
+	/// Expanding an expanded document must succeed and, after the first round, be a fixed point.
+	#[test]
+	fn expansion_is_a_fixed_point(doc in document()) {
+		if let Ok(expanded) = expand(&doc) {
+			let json = normalize(&expanded);
+			prop_assert!(json.is_ok(), "expanded form {} of {} is invalid: {:?}", expanded.as_json().dump(), doc.dump(), json);
+			let json = json.unwrap();
+			prop_assert_eq!(expand(&json).map(|doc| doc.as_json()), Ok(json.clone()), "expanding {} is not a fixed point", doc.dump())
+		}
+	}
+
+	/// Compacting an expanded document and expanding the result gives back the same expanded
+	/// document, up to the order of unordered values.
+	///
+	/// Documents are expanded against a base IRI, since relative IRIs of the expanded form could
+	/// otherwise be re-expanded as terms of the context.
+	#[test]
+	fn compaction_round_trip(doc in document(), context in compaction_context()) {
+		if let Ok(expanded) = expand_from(Some(base_iri()), &doc) {
+			let json = normalize(&expanded).unwrap();
+			let expanded = expand(&json).unwrap();
+			let compacted = compact(&expanded, &context);
+			prop_assert!(compacted.is_ok(), "compacting {} with {} failed: {:?}", json.dump(), context.dump(), compacted);
+			let compacted = compacted.unwrap();
+			prop_assert_eq!(expand(&compacted).map(|doc| canonical(&doc.as_json())), Ok(canonical(&json)), "{} is compacted into {}", json.dump(), compacted.dump())
+		}
+	}
+}
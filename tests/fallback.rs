@@ -0,0 +1,73 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::sync::{Arc, Mutex};
+use async_std::task;
+use futures::future;
+use iref::Iri;
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	FnLoader,
+	Loader,
+	layer::{
+		LoaderExt,
+		FallbackLayer,
+		RetryLayer
+	}
+};
+
+/// Loader failing for `https://www.w3.org/` URLs, recording every fetched URL.
+fn flaky(fetched: Arc<Mutex<Vec<String>>>) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |url: Iri| {
+		fetched.lock().unwrap().push(url.as_str().to_string());
+		future::ready(if url.as_str().starts_with("https://www.w3.org/") || url.as_str().contains("broken") {
+			Err(ErrorCode::LoadingDocumentFailed)
+		} else {
+			Ok(format!(r#"{{"@context": {{"from": "{}"}}}}"#, url))
+		})
+	})
+}
+
+#[test]
+fn fallback_mirrors() {
+	let layer = FallbackLayer::new()
+		.fallback(Iri::new("https://www.w3.org/").unwrap(), Iri::new("https://broken.example.org/").unwrap())
+		.fallback(Iri::new("https://www.w3.org/ns/").unwrap(), Iri::new("https://mirror.example.org/").unwrap());
+
+	let url = Iri::new("https://www.w3.org/ns/credentials/v2").unwrap();
+	assert_eq!(layer.fallbacks(url).iter().map(|iri| iri.as_str()).collect::<Vec<_>>(), vec![
+		"https://mirror.example.org/credentials/v2",
+		"https://broken.example.org/ns/credentials/v2"
+	]);
+
+	let fetched = Arc::new(Mutex::new(Vec::new()));
+	let mut loader = flaky(fetched.clone()).with(RetryLayer::new(2)).with(layer);
+	let doc = task::block_on(loader.load(url)).unwrap();
+	assert_eq!(doc.url().as_str(), "https://www.w3.org/ns/credentials/v2");
+	assert_eq!(doc["@context"]["from"], "https://mirror.example.org/credentials/v2");
+	assert_eq!(*fetched.lock().unwrap(), vec![
+		"https://www.w3.org/ns/credentials/v2",
+		"https://www.w3.org/ns/credentials/v2",
+		"https://mirror.example.org/credentials/v2"
+	]);
+
+	let doc = task::block_on(loader.load(Iri::new("https://example.org/context").unwrap())).unwrap();
+	assert_eq!(doc["@context"]["from"], "https://example.org/context");
+}
+
+#[test]
+fn every_mirror_fails() {
+	let fetched = Arc::new(Mutex::new(Vec::new()));
+	let mut loader = flaky(fetched.clone())
+		.with(FallbackLayer::new().fallback(Iri::new("https://www.w3.org/").unwrap(), Iri::new("https://broken.example.org/").unwrap()));
+
+	match task::block_on(loader.load(Iri::new("https://www.w3.org/ns/did/v1").unwrap())) {
+		Ok(_) => panic!("every location should fail"),
+		Err(e) => assert_eq!(e.code(), ErrorCode::LoadingDocumentFailed)
+	}
+
+	let count = fetched.lock().unwrap().len();
+	assert_eq!(count, 2)
+}
@@ -0,0 +1,43 @@
+extern crate iref;
+extern crate json_ld;
+
+use std::mem::size_of;
+use iref::IriBuf;
+use json_ld::{
+	Indexed,
+	Node,
+	Object,
+	Value,
+	Reference,
+	object::Literal,
+	syntax::Term
+};
+
+#[test]
+fn objects_are_not_larger_than_values() {
+	// Nodes are boxed: value objects only pay for the enum discriminant.
+	assert!(size_of::<Object>() <= size_of::<Value>() + size_of::<usize>());
+}
+
+#[test]
+fn indexes_are_small() {
+	assert!(size_of::<Indexed<Object>>() <= size_of::<Object>() + 2 * size_of::<usize>());
+}
+
+#[test]
+fn terms_are_not_larger_than_identifiers() {
+	// Keywords and `null` fit in the niche of the identifier.
+	assert!(size_of::<Term<IriBuf>>() <= size_of::<Reference<IriBuf>>());
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn sizes() {
+	// JSON literals are boxed: literals are not larger than a string.
+	assert!(size_of::<Literal>() <= 24);
+	assert!(size_of::<Value>() <= 72);
+	assert!(size_of::<Object>() <= 72);
+	assert!(size_of::<Indexed<Object>>() <= 88);
+	assert!(size_of::<Node>() <= 304);
+	assert!(size_of::<Term<IriBuf>>() <= 120);
+}
@@ -0,0 +1,85 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	Indexed,
+	JsonContext,
+	NoLoader,
+	Node,
+	flattening::{
+		self,
+		FlattenedDocument
+	},
+	generator
+};
+
+fn flatten(doc: &str) -> FlattenedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	let expanded = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	flattening::flatten(&expanded, generator::Blank::new()).unwrap()
+}
+
+fn ids(nodes: &[&Indexed<Node<IriBuf>>]) -> Vec<String> {
+	nodes.iter().map(|node| node.as_str().unwrap().trim_start_matches("http://example.org/").to_string()).collect()
+}
+
+const DOC: &str = r#"{
+	"@context": {
+		"@vocab": "http://schema.org/",
+		"@base": "http://example.org/",
+		"knows": {"@type": "@id"},
+		"follows": {"@type": "@id", "@container": "@list"}
+	},
+	"@graph": [
+		{"@id": "a", "name": "A", "knows": "b"},
+		{"@id": "b", "name": "B", "follows": ["c"]},
+		{"@id": "c", "name": "C"},
+		{"@id": "d", "name": "D"},
+		{"@id": "e", "name": "E", "knows": "d"},
+		{"@id": "f", "name": "F", "knows": "http://elsewhere.example/g"}
+	]
+}"#;
+
+#[test]
+fn connected_components() {
+	let flattened = flatten(DOC);
+	let components: Vec<_> = flattening::components(flattened.default_graph()).iter().map(|component| ids(component)).collect();
+	assert_eq!(components, vec![
+		vec!["a", "b", "c"],
+		vec!["d", "e"],
+		vec!["f"]
+	]);
+}
+
+#[test]
+fn bounded_chunks() {
+	let flattened = flatten(DOC);
+	let nodes = flattened.default_graph();
+
+	let chunks: Vec<_> = flattening::chunks(nodes, 3, 1).iter().map(|chunk| (ids(&chunk.nodes), ids(&chunk.closure))).collect();
+	assert_eq!(chunks, vec![
+		(vec!["a".to_string(), "b".to_string(), "c".to_string()], vec![]),
+		(vec!["d".to_string(), "e".to_string(), "f".to_string()], vec![])
+	]);
+
+	// The first component is split, and its chunks refer to each other.
+	let chunks = flattening::chunks(nodes, 1, 1);
+	assert_eq!(chunks.len(), nodes.len());
+	assert!(chunks.iter().all(|chunk| chunk.nodes.len() == 1));
+	assert_eq!(ids(&chunks[0].closure), vec!["b"]);
+	assert_eq!(ids(&chunks[1].closure), vec!["c"]);
+	assert_eq!(ids(&chunks[4].closure), vec!["d"]);
+
+	let chunks = flattening::chunks(nodes, 1, 2);
+	assert_eq!(ids(&chunks[0].closure), vec!["b", "c"]);
+	assert_eq!(ids(&chunks[0].iter().collect::<Vec<_>>()), vec!["a", "b", "c"]);
+
+	let chunks = flattening::chunks(nodes, 2, 0);
+	let sizes: Vec<_> = chunks.iter().map(|chunk| chunk.nodes.len()).collect();
+	assert_eq!(sizes, vec![2, 1, 2, 1]);
+	assert!(chunks.iter().all(|chunk| chunk.closure.is_empty()));
+}
@@ -0,0 +1,242 @@
+#![cfg(feature="html")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	ErrorCode,
+	JsonContext,
+	NoLoader,
+	html::{
+		self,
+		Warning
+	},
+	shape::Shape
+};
+
+fn extract(html: &str, url: &str, extract_all_scripts: bool) -> Result<(json::JsonValue, String), ErrorCode> {
+	let options = html::Options {
+		extract_all_scripts
+	};
+
+	let (doc, base_url) = html::extract(html, Iri::new(url).unwrap(), options).map_err(|e| e.code())?.into_parts();
+	Ok((doc, base_url.as_str().to_string()))
+}
+
+fn document(html: &str) -> json::JsonValue {
+	extract(html, "https://example.org/page", false).unwrap().0
+}
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+	<script type="application/ld+json">{"@id": "first"}</script>
+	<script id="second" type="application/ld+json;profile=http://www.w3.org/ns/json-ld#expanded">[{"@id": "second"}]</script>
+	<script id="js" type="text/javascript">var x = 1;</script>
+	<script id="third" type='APPLICATION/LD+JSON'>{"@id": "third"}</script>
+</head>
+<body></body>
+</html>"#;
+
+#[test]
+fn first_script() {
+	let (doc, base_url) = extract(PAGE, "https://example.org/page", false).unwrap();
+	assert_eq!(doc, json::parse(r#"{"@id": "first"}"#).unwrap());
+	assert_eq!(base_url, "https://example.org/page");
+}
+
+#[test]
+fn extract_all_scripts() {
+	// Array contents are concatenated.
+	let (doc, _) = extract(PAGE, "https://example.org/page", true).unwrap();
+	assert_eq!(doc, json::parse(r#"[{"@id": "first"}, {"@id": "second"}, {"@id": "third"}]"#).unwrap());
+
+	// Without JSON-LD script, the result is empty.
+	let (doc, _) = extract("<html><script>{}</script></html>", "https://example.org/page", true).unwrap();
+	assert_eq!(doc, json::JsonValue::Array(Vec::new()));
+}
+
+#[test]
+fn fragment_selection() {
+	let (doc, base_url) = extract(PAGE, "https://example.org/page#third", false).unwrap();
+	assert_eq!(doc, json::parse(r#"{"@id": "third"}"#).unwrap());
+	assert_eq!(base_url, "https://example.org/page#third");
+
+	// The fragment takes precedence over `extractAllScripts`.
+	let (doc, _) = extract(PAGE, "https://example.org/page#second", true).unwrap();
+	assert_eq!(doc, json::parse(r#"[{"@id": "second"}]"#).unwrap());
+
+	// The selected script must exist and be a JSON-LD script.
+	assert_eq!(extract(PAGE, "https://example.org/page#missing", false), Err(ErrorCode::LoadingDocumentFailed));
+	assert_eq!(extract(PAGE, "https://example.org/page#js", false), Err(ErrorCode::LoadingDocumentFailed));
+}
+
+#[test]
+fn other_script_types() {
+	let page = r#"
+		<script>{"@id": "untyped"}</script>
+		<script type="application/json">{"@id": "json"}</script>
+		<script type="application/ld+json5">{"@id": "ld+json5"}</script>
+		<script type=application/ld+json>{"@id": "unquoted"}</script>
+	"#;
+
+	assert_eq!(document(page), json::parse(r#"{"@id": "unquoted"}"#).unwrap());
+	assert_eq!(extract("<script type=\"text/plain\">{}</script>", "https://example.org/page", false), Err(ErrorCode::LoadingDocumentFailed));
+
+	let scripts = html::scripts(page);
+	assert_eq!(scripts.len(), 4);
+	assert_eq!(scripts.iter().filter(|script| script.is_json_ld()).count(), 1);
+	assert_eq!(scripts[1].media_type.as_deref(), Some("application/json"));
+}
+
+#[test]
+fn invalid_script() {
+	assert_eq!(extract(r#"<script type="application/ld+json">{"@id": </script>"#, "https://example.org/page", false), Err(ErrorCode::InvalidScriptElement));
+
+	// Invalid scripts are errors when extracted only.
+	let page = r#"<script type="application/ld+json">{}</script><script id="bad" type="application/ld+json">{</script>"#;
+	assert!(extract(page, "https://example.org/page", false).is_ok());
+	assert_eq!(extract(page, "https://example.org/page", true), Err(ErrorCode::InvalidScriptElement));
+	assert_eq!(extract(page, "https://example.org/page#bad", false), Err(ErrorCode::InvalidScriptElement));
+}
+
+#[test]
+fn base_element() {
+	let (_, base_url) = extract(r#"<head><base href="/documents/"><base href="https://other.example/"></head>
+		<script type="application/ld+json">{}</script>"#, "https://example.org/page", false).unwrap();
+	assert_eq!(base_url, "https://example.org/documents/");
+
+	let (_, base_url) = extract(r#"<BASE HREF='https://example.com/'><script type="application/ld+json">{}</script>"#, "https://example.org/page", false).unwrap();
+	assert_eq!(base_url, "https://example.com/");
+
+	// Without `href`, the document URL is used.
+	let (_, base_url) = extract(r#"<base target="_blank"><script type="application/ld+json">{}</script>"#, "https://example.org/page", false).unwrap();
+	assert_eq!(base_url, "https://example.org/page");
+}
+
+#[test]
+fn comments_and_cdata() {
+	let page = r#"
+		<!-- <script type="application/ld+json">{"@id": "commented"}</script> -->
+		<![CDATA[ <script type="application/ld+json">{"@id": "cdata"}</script> ]]>
+		<script type="application/ld+json">{"@id": "real", "http://example.org/p": "<!-- not a comment -->"}</script>
+	"#;
+
+	// A CDATA section is a bogus comment in HTML, ending at the first `>`, hence swallowing
+	// the start tag of the script.
+	let scripts: Vec<_> = html::scripts(page).into_iter().map(|script| script.content.to_string()).collect();
+	assert_eq!(scripts, vec![r#"{"@id": "real", "http://example.org/p": "<!-- not a comment -->"}"#]);
+	assert_eq!(document(page)["@id"], "real");
+
+	// Script contents are raw text: markup inside them is not interpreted.
+	let (doc, _) = extract(r#"<script type="application/ld+json">{"@id": "a", "http://example.org/p": "</p><script>"}</script>"#, "https://example.org/page", true).unwrap();
+	assert_eq!(doc[0]["http://example.org/p"], "</p><script>");
+
+	// Other raw text elements are skipped.
+	let doc = document(r#"<title><script type="application/ld+json">{"@id": "title"}</script></title><script type="application/ld+json">{"@id": "body"}</script>"#);
+	assert_eq!(doc["@id"], "body");
+}
+
+#[test]
+fn entities() {
+	// Character references are decoded in attributes.
+	let page = r#"<base href="https://example.org/a&amp;b/"><script id="a&#x26;b" type="application&#47;ld+json">{"@id": "x&amp;y"}</script>"#;
+	let (doc, base_url) = extract(page, "https://example.org/page#a&b", false).unwrap();
+	assert_eq!(base_url, "https://example.org/a&b/");
+
+	// But not in script contents.
+	assert_eq!(doc["@id"], "x&amp;y");
+
+	// Unknown or unterminated references are kept as they are.
+	let scripts = html::scripts(r#"<script id="&foo; &amp &#bad;">{}</script>"#);
+	assert_eq!(scripts[0].id.as_deref(), Some("&foo; &amp &#bad;"));
+}
+
+fn report(page: &str, shapes: &[Shape]) -> html::Report {
+	let context: JsonContext<IriBuf> = JsonContext::new(None);
+	task::block_on(html::report(page, Iri::new("https://example.org/page").unwrap(), &context, &mut NoLoader, shapes))
+}
+
+#[test]
+fn report_types() {
+	let report = report(r#"
+		<base href="https://example.org/">
+		<script id="person" type="application/ld+json">{
+			"@context": {"@vocab": "http://schema.org/"},
+			"@id": "https://example.org/alice",
+			"@type": "Person",
+			"knows": {"@type": ["Person", "Agent"]}
+		}</script>
+		<script type="text/javascript">{"@type": "Ignored"}</script>
+		<script type="application/ld+json">{"@context": {"@vocab": "http://schema.org/"}, "@type": "Event"}</script>
+	"#, &[]);
+
+	assert_eq!(report.base_url.as_str(), "https://example.org/");
+	assert_eq!(report.blocks.len(), 2);
+	assert_eq!(report.blocks[0].id.as_deref(), Some("person"));
+	assert!(report.blocks[0].expanded.is_some());
+	assert!(report.blocks[1].source.contains("Event"));
+
+	let types: Vec<_> = report.blocks[0].types.iter().map(|ty| ty.as_str()).collect();
+	assert_eq!(types, vec!["http://schema.org/Agent", "http://schema.org/Person"]);
+
+	let types: Vec<_> = report.types().into_iter().map(|ty| ty.as_str()).collect();
+	assert_eq!(types, vec!["http://schema.org/Agent", "http://schema.org/Event", "http://schema.org/Person"]);
+	assert!(report.has_type("http://schema.org/Event"));
+	assert!(!report.has_type("http://schema.org/Ignored"));
+	assert!(report.is_valid());
+
+	let block = &report.blocks[0].expanded.as_ref().unwrap();
+	assert_eq!(block.iter().next().unwrap().as_node().unwrap().id().unwrap().as_str(), "https://example.org/alice");
+}
+
+#[test]
+fn report_warnings() {
+	let report = report(r#"
+		<script type="application/ld+json">{"@id": </script>
+		<script type="application/ld+json">{"@context": "https://example.org/context", "@type": "Person"}</script>
+		<script type="application/ld+json">{"@type": "Person"}</script>
+		<script type="application/ld+json">{"name": "Alice"}</script>
+		<script type="application/ld+json">{"@context": {"@vocab": "http://schema.org/"}, "@id": "https://example.org/alice", "name": "Alice"}</script>
+	"#, &[]);
+
+	assert!(!report.is_valid());
+	let warnings: Vec<_> = report.blocks.iter().map(|block| block.warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>()).collect();
+	assert_eq!(warnings, vec![
+		vec!["invalid JSON".to_string()],
+		vec!["expansion failed: loading remote context failed".to_string()],
+		vec!["unknown type `Person`".to_string()],
+		vec!["no node defined".to_string()],
+		vec!["node `https://example.org/alice` has no type".to_string()]
+	]);
+
+	assert!(matches!(report.blocks[1].warnings[0], Warning::Expansion(ErrorCode::LoadingRemoteContextFailed)));
+	assert!(report.blocks[0].expanded.is_none());
+	assert_eq!(report.warnings().count(), 5);
+}
+
+#[test]
+fn report_shapes() {
+	let shape = Shape::from_json(&json::parse(r#"{
+		"targetClass": "http://schema.org/Person",
+		"properties": {
+			"http://schema.org/name": {"minCount": 1}
+		}
+	}"#).unwrap()).unwrap();
+
+	let page = r#"<script type="application/ld+json">[
+		{"@id": "https://example.org/alice", "@type": "http://schema.org/Person", "http://schema.org/name": "Alice"},
+		{"@id": "https://example.org/bob", "@type": "http://schema.org/Person"}
+	]</script>"#;
+
+	let report = report(page, &[shape]);
+	let violations: Vec<_> = report.warnings().filter_map(|warning| match warning {
+		Warning::Violation(violation) => Some(violation.node.as_ref().map(|id| id.as_str().to_string())),
+		_ => None
+	}).collect();
+
+	assert_eq!(violations, vec![Some("https://example.org/bob".to_string())]);
+}
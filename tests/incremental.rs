@@ -0,0 +1,175 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	ErrorCode,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion::{
+		self,
+		Incremental,
+		Options
+	},
+	util::AsJson
+};
+
+const BASE_URL: &str = "https://example.org/";
+
+fn load(doc: &str) -> Incremental<IriBuf, JsonContext<IriBuf>> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(Incremental::new(&doc, Some(Iri::new(BASE_URL).unwrap()), &JsonContext::new(None), &mut NoLoader, Options::default())).unwrap()
+}
+
+fn update(incremental: &mut Incremental<IriBuf, JsonContext<IriBuf>>, doc: &str) -> usize {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(incremental.update(&doc, &mut NoLoader)).unwrap()
+}
+
+fn expand(doc: &json::JsonValue) -> ExpandedDocument<IriBuf> {
+	task::block_on(expansion::expand(&JsonContext::new(None), doc, Some(Iri::new(BASE_URL).unwrap()), &mut NoLoader, Options::default())).unwrap()
+}
+
+/// Checks that the incremental expansion matches the expansion of its source.
+fn assert_consistent(incremental: &Incremental<IriBuf, JsonContext<IriBuf>>) {
+	let expected = expand(&incremental.source());
+	let document = incremental.document();
+	assert!(document == expected, "{} != {}", document.as_json().dump(), expected.as_json().dump())
+}
+
+const ARRAY: &str = r#"[
+	{"@id": "alice", "http://schema.org/name": "Alice"},
+	{"@id": "bob", "http://schema.org/name": "Bob"},
+	{"@id": "carol", "http://schema.org/name": "Carol"}
+]"#;
+
+const GRAPH: &str = r#"{
+	"@context": {"@vocab": "http://schema.org/"},
+	"@graph": [
+		{"@id": "alice", "name": "Alice"},
+		{"@id": "bob", "name": "Bob"}
+	]
+}"#;
+
+#[test]
+fn new() {
+	for doc in &[ARRAY, GRAPH, r#"{"@id": "alice", "http://schema.org/name": "Alice"}"#, "[]"] {
+		let incremental = load(doc);
+		assert_eq!(incremental.source(), json::parse(doc).unwrap());
+		assert_consistent(&incremental);
+	}
+
+	// A top-level `@graph` object is rebuilt as an array.
+	let incremental = load(r#"{"@graph": {"@id": "alice"}}"#);
+	assert_eq!(incremental.source(), json::parse(r#"{"@graph": [{"@id": "alice"}]}"#).unwrap());
+	assert_consistent(&incremental);
+
+	let incremental = load(GRAPH);
+	assert_eq!(incremental.len(), 2);
+	assert_eq!(incremental.objects().count(), 2);
+	let document = incremental.document();
+	assert!(incremental.into_document() == document);
+}
+
+#[test]
+fn update_items() {
+	let mut incremental = load(ARRAY);
+
+	// Unchanged.
+	assert_eq!(update(&mut incremental, ARRAY), 0);
+
+	// One modified item.
+	assert_eq!(update(&mut incremental, r#"[
+		{"@id": "alice", "http://schema.org/name": "Alice"},
+		{"@id": "bob", "http://schema.org/name": "Robert"},
+		{"@id": "carol", "http://schema.org/name": "Carol"}
+	]"#), 1);
+	assert_consistent(&incremental);
+
+	// Moved, removed and added items.
+	assert_eq!(update(&mut incremental, r#"[
+		{"@id": "carol", "http://schema.org/name": "Carol"},
+		{"@id": "dave", "http://schema.org/name": "Dave"},
+		{"@id": "alice", "http://schema.org/name": "Alice"}
+	]"#), 1);
+	assert_eq!(incremental.len(), 3);
+	assert_consistent(&incremental);
+
+	// Top-level arrays and single objects share their items.
+	assert_eq!(update(&mut incremental, r#"{"@id": "alice", "http://schema.org/name": "Alice"}"#), 0);
+	assert_consistent(&incremental);
+}
+
+#[test]
+fn update_context() {
+	let mut incremental = load(GRAPH);
+	assert_eq!(update(&mut incremental, GRAPH), 0);
+
+	// Items are expanded again when the context changes.
+	let doc = GRAPH.replace("http://schema.org/", "http://xmlns.com/foaf/0.1/");
+	assert_eq!(update(&mut incremental, &doc), 2);
+	assert_consistent(&incremental);
+	assert_eq!(incremental.document().as_json()[0]["http://xmlns.com/foaf/0.1/name"][0]["@value"], "Alice");
+
+	// Or when there no longer is a top-level graph.
+	assert_eq!(update(&mut incremental, r#"[{"@id": "alice", "name": "Alice"}, {"@id": "bob", "name": "Bob"}]"#), 2);
+	assert_consistent(&incremental);
+}
+
+#[test]
+fn update_error() {
+	let mut incremental = load(ARRAY);
+	let doc = json::parse(r#"[{"@id": "alice"}, {"@id": 1}]"#).unwrap();
+	match task::block_on(incremental.update(&doc, &mut NoLoader)) {
+		Err(e) => assert_eq!(e.code(), ErrorCode::InvalidIdValue),
+		Ok(_) => panic!("expected an error")
+	}
+}
+
+#[test]
+fn edit_items() {
+	let mut incremental = load(GRAPH);
+
+	task::block_on(incremental.replace(1, json::parse(r#"{"@id": "bob", "name": "Robert"}"#).unwrap(), &mut NoLoader)).unwrap();
+	task::block_on(incremental.insert(0, json::parse(r#"{"@id": "carol", "name": "Carol"}"#).unwrap(), &mut NoLoader)).unwrap();
+	task::block_on(incremental.insert(3, json::parse(r#"{"@id": "dave", "name": "Dave"}"#).unwrap(), &mut NoLoader)).unwrap();
+	assert_eq!(incremental.remove(1).unwrap()["@id"], "alice");
+
+	// Items are expanded with the top-level context, and the top-level graph is sorted.
+	assert_eq!(incremental.source()["@graph"].len(), 3);
+	assert_eq!(incremental.source()["@graph"][0]["@id"], "carol");
+	assert_eq!(incremental.document().as_json()[0]["http://schema.org/name"][0]["@value"], "Robert");
+	assert_consistent(&incremental);
+
+}
+
+#[test]
+fn single_to_array() {
+	// A single top-level object becomes an array.
+	let mut incremental = load(r#"{"@id": "alice"}"#);
+	task::block_on(incremental.insert(1, json::parse(r#"{"@id": "bob"}"#).unwrap(), &mut NoLoader)).unwrap();
+	assert_eq!(incremental.source(), json::parse(r#"[{"@id": "alice"}, {"@id": "bob"}]"#).unwrap());
+	assert_consistent(&incremental);
+}
+
+#[test]
+fn out_of_bounds() {
+	let mut incremental = load(ARRAY);
+	let item = json::parse(r#"{"@id": "dave"}"#).unwrap();
+
+	let e = task::block_on(incremental.replace(3, item.clone(), &mut NoLoader)).unwrap_err();
+	assert_eq!(e.code(), ErrorCode::ItemIndexOutOfBounds);
+	assert_eq!(std::error::Error::source(&e).unwrap().to_string(), "index 3 is out of bounds for 3 top-level items");
+
+	let e = task::block_on(incremental.insert(4, item, &mut NoLoader)).unwrap_err();
+	assert_eq!(e.code(), ErrorCode::ItemIndexOutOfBounds);
+
+	assert_eq!(incremental.remove(3).unwrap_err().code(), ErrorCode::ItemIndexOutOfBounds);
+
+	// The document is left unchanged.
+	assert_eq!(incremental.source(), json::parse(ARRAY).unwrap());
+	assert_consistent(&incremental);
+}
@@ -0,0 +1,120 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	BlankId,
+	Document,
+	ExpandedDocument,
+	IndexedDataset,
+	JsonContext,
+	NoLoader,
+	Reference,
+	generator
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	task::block_on(json::parse(doc).unwrap().expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn iri(iri: &str) -> Reference<IriBuf> {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+fn ids<'a, I: Iterator<Item = &'a json_ld::Indexed<json_ld::Node<IriBuf>>>>(nodes: I) -> Vec<String> {
+	let mut ids: Vec<_> = nodes.map(|node| node.id().unwrap().as_str().to_string()).collect();
+	ids.sort();
+	ids
+}
+
+const DOC: &str = r#"{
+	"@context": {
+		"@vocab": "http://schema.org/",
+		"@base": "http://example.org/",
+		"knows": {"@type": "@id"}
+	},
+	"@graph": [
+		{"@id": "alice", "@type": "Person", "knows": ["bob", "carol"], "follows": {"@list": [{"@id": "carol"}]}},
+		{"@id": "bob", "@type": ["Person", "Agent"], "knows": "carol"},
+		{"@id": "carol", "@type": "Person", "knows": "_:x"},
+		{"@id": "_:x", "@type": "Person", "name": "X"},
+		{"@id": "g", "@graph": {"@id": "dave", "@type": "Person", "knows": "alice"}}
+	]
+}"#;
+
+#[test]
+fn lookup() {
+	let dataset = IndexedDataset::new(&expand(DOC)).unwrap();
+
+	let alice = dataset.get(None, &iri("http://example.org/alice")).unwrap();
+	assert_eq!(alice.id().unwrap().as_str(), "http://example.org/alice");
+	assert!(dataset.get(None, &iri("http://example.org/dave")).is_none());
+	assert!(dataset.get(Some(&iri("http://example.org/g")), &iri("http://example.org/dave")).is_some());
+	assert!(dataset.get(Some(&iri("http://example.org/unknown")), &iri("http://example.org/dave")).is_none());
+}
+
+#[test]
+fn types() {
+	let dataset = IndexedDataset::new(&expand(DOC)).unwrap();
+
+	assert_eq!(ids(dataset.with_type(None, &iri("http://schema.org/Person"))), vec![
+		"_:b0",
+		"http://example.org/alice",
+		"http://example.org/bob",
+		"http://example.org/carol"
+	]);
+	assert_eq!(ids(dataset.with_type(None, &iri("http://schema.org/Agent"))), vec!["http://example.org/bob"]);
+	assert!(dataset.with_type(None, &iri("http://schema.org/Event")).next().is_none());
+	assert_eq!(ids(dataset.with_type(Some(&iri("http://example.org/g")), &iri("http://schema.org/Person"))), vec!["http://example.org/dave"]);
+}
+
+#[test]
+fn property_values() {
+	let dataset = IndexedDataset::new(&expand(DOC)).unwrap();
+	let knows = iri("http://schema.org/knows");
+
+	assert_eq!(ids(dataset.with_property_value(None, &knows, &iri("http://example.org/carol"))), vec![
+		"http://example.org/alice",
+		"http://example.org/bob"
+	]);
+	assert_eq!(ids(dataset.with_property_value(None, &iri("http://schema.org/follows"), &iri("http://example.org/carol"))), vec!["http://example.org/alice"]);
+	assert!(dataset.with_property_value(None, &knows, &iri("http://example.org/alice")).next().is_none());
+	assert_eq!(ids(dataset.with_property_value(Some(&iri("http://example.org/g")), &knows, &iri("http://example.org/alice"))), vec!["http://example.org/dave"]);
+}
+
+#[test]
+fn referencing() {
+	let dataset = IndexedDataset::new(&expand(DOC)).unwrap();
+
+	let mut referencing: Vec<_> = dataset.referencing(None, &iri("http://example.org/carol"))
+		.map(|(prop, node)| (node.id().unwrap().as_str().to_string(), prop.as_str().to_string()))
+		.collect();
+	referencing.sort();
+	assert_eq!(referencing, vec![
+		("http://example.org/alice".to_string(), "http://schema.org/follows".to_string()),
+		("http://example.org/alice".to_string(), "http://schema.org/knows".to_string()),
+		("http://example.org/bob".to_string(), "http://schema.org/knows".to_string())
+	]);
+
+	assert!(dataset.referencing(None, &iri("http://example.org/dave")).next().is_none());
+	assert_eq!(dataset.referencing(Some(&iri("http://example.org/g")), &iri("http://example.org/alice")).count(), 1);
+}
+
+#[test]
+fn renamed_blank_ids() {
+	// Blank node identifiers of the document are renamed.
+	let dataset = IndexedDataset::new(&expand(DOC)).unwrap();
+	let x = Reference::Blank(BlankId::new("x"));
+	assert!(dataset.get(None, &x).is_none());
+
+	let renamed = Reference::Blank(BlankId::new("b0"));
+	assert!(dataset.get(None, &renamed).is_some());
+	assert_eq!(ids(dataset.with_property_value(None, &iri("http://schema.org/knows"), &renamed)), vec!["http://example.org/carol"]);
+
+	// The renaming is given by the generator.
+	let dataset = IndexedDataset::new_with(&expand(DOC), generator::Blank::new_with_prefix("n".to_string())).unwrap();
+	assert!(dataset.get(None, &Reference::Blank(BlankId::new("n0"))).is_some());
+	assert_eq!(dataset.node_map().graph(None).unwrap().len(), 5);
+}
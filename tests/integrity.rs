@@ -0,0 +1,124 @@
+#![cfg(feature="integrity")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use futures::future;
+use iref::Iri;
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	FnLoader,
+	Loader,
+	RemoteDocument,
+	layer::LoaderExt,
+	integrity::{
+		Algorithm,
+		Hash,
+		IntegrityError,
+		IntegrityLayer
+	}
+};
+
+/// Pretty-printed document, whose raw content differs from its compact serialization.
+const CONTEXT: &str = "{\n\t\"@context\": {\n\t\t\"name\": \"http://schema.org/name\"\n\t}\n}\n";
+
+const URL: &str = "https://example.org/context";
+
+fn url() -> Iri<'static> {
+	Iri::new(URL).unwrap()
+}
+
+fn served(content: &'static str) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |_: Iri| future::ready(Ok(content.to_string())))
+}
+
+#[test]
+fn raw_content_is_hashed() {
+	let hash = Hash::of(Algorithm::Sha256, CONTEXT.as_bytes());
+	let mut loader = served(CONTEXT).with(IntegrityLayer::new().pin(url(), hash));
+	let doc = task::block_on(loader.load(url())).unwrap();
+	assert_eq!(doc.content(), Some(CONTEXT));
+	assert_eq!(doc["@context"]["name"], "http://schema.org/name");
+
+	// The compact serialization is not the served content.
+	let hash = Hash::of_document(Algorithm::Sha256, &json::parse(CONTEXT).unwrap());
+	let mut loader = served(CONTEXT).with(IntegrityLayer::new().pin(url(), hash));
+	assert_eq!(task::block_on(loader.load(url())).map(|_| ()).unwrap_err().code(), ErrorCode::LoadingDocumentFailed);
+}
+
+#[test]
+fn compact_serialization_without_content() {
+	let json = json::parse(CONTEXT).unwrap();
+	let doc = RemoteDocument::new(json.clone(), url());
+	let layer = IntegrityLayer::new().pin(url(), Hash::of_document(Algorithm::Sha384, &json));
+	assert!(layer.check(url(), &doc).is_ok());
+
+	let layer = IntegrityLayer::new().pin(url(), Hash::of(Algorithm::Sha384, CONTEXT.as_bytes()));
+	assert!(layer.check(url(), &doc).is_err());
+}
+
+#[test]
+fn mismatch() {
+	let expected = Hash::of(Algorithm::Sha256, CONTEXT.as_bytes());
+	let substituted = "{\"@context\": {\"name\": \"http://example.org/evil\"}}";
+	let layer = IntegrityLayer::new().pin(url(), expected.clone());
+	let doc = RemoteDocument::new(json::parse(substituted).unwrap(), url()).with_content(substituted.to_string());
+	match *layer.check(url(), &doc).unwrap_err() {
+		IntegrityError::Mismatch { url, expected: e, found } => {
+			assert_eq!(url.as_str(), URL);
+			assert_eq!(e, expected);
+			assert_eq!(found, Hash::of(Algorithm::Sha256, substituted.as_bytes()))
+		},
+		e => panic!("unexpected error: {}", e)
+	}
+
+	let mut loader = served(substituted).with(layer);
+	assert_eq!(task::block_on(loader.load(url())).map(|_| ()).unwrap_err().code(), ErrorCode::LoadingDocumentFailed);
+}
+
+#[test]
+fn strict_mode() {
+	let other = Iri::new("https://example.org/other").unwrap();
+	let layer = IntegrityLayer::new().pin(url(), Hash::of(Algorithm::Sha512, CONTEXT.as_bytes()));
+	let doc = RemoteDocument::new(json::parse(CONTEXT).unwrap(), other).with_content(CONTEXT.to_string());
+	assert!(layer.check(other, &doc).is_ok());
+
+	let layer = layer.strict(true);
+	match *layer.check(other, &doc).unwrap_err() {
+		IntegrityError::NotPinned(url) => assert_eq!(url, other),
+		e => panic!("unexpected error: {}", e)
+	}
+
+	let mut loader = served(CONTEXT).with(layer);
+	assert!(task::block_on(loader.load(url())).is_ok());
+	assert_eq!(task::block_on(loader.load(other)).map(|_| ()).unwrap_err().code(), ErrorCode::LoadingDocumentFailed);
+}
+
+#[test]
+fn parse_hash() {
+	// SHA-256 digest of `alert('Hello, world.');`, from the Subresource Integrity specification.
+	let hash: Hash = "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng=".parse().unwrap();
+	assert_eq!(hash.algorithm(), Algorithm::Sha256);
+	assert_eq!(hash, Hash::of(Algorithm::Sha256, b"alert('Hello, world.');"));
+	assert_eq!(hash.to_string(), "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng=");
+
+	let hash = Hash::of(Algorithm::Sha384, b"");
+	assert_eq!(hash.to_string().parse::<Hash>().unwrap(), hash);
+	assert_eq!(hash.digest(), "OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb");
+
+	for invalid in &[
+		"",
+		"sha256",
+		"sha256-",
+		"md5-1B2M2Y8AsgTpgAmY7PhCfg==",
+		"sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng",
+		"sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng=!",
+		"sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tn?=",
+		"sha512-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng="
+	] {
+		assert!(invalid.parse::<Hash>().is_err(), "{}", invalid)
+	}
+}
@@ -0,0 +1,86 @@
+extern crate async_std;
+extern crate iref;
+#[macro_use]
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	util::is_isomorphic
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn parse(doc: &str) -> JsonValue {
+	json::parse(doc).unwrap()
+}
+
+#[test]
+fn compact_and_expanded_forms() {
+	assert_expanded_eq!(
+		parse(r#"{
+			"@context": {"@vocab": "http://example.org/", "knows": {"@type": "@id"}},
+			"@id": "_:a",
+			"name": ["A", "B"],
+			"knows": {"@id": "_:b", "name": "C"}
+		}"#),
+		parse(r#"[
+			{"@id": "_:x", "http://example.org/name": [{"@value": "C"}]},
+			{"@id": "_:y", "http://example.org/knows": {"@id": "_:x"}, "http://example.org/name": ["B", "A"]}
+		]"#)
+	);
+}
+
+#[test]
+fn unlabeled_blank_nodes() {
+	assert_expanded_eq!(
+		parse(r#"{"http://example.org/p": [{"http://example.org/q": 1}, {"http://example.org/q": 2}]}"#),
+		parse(r#"{"http://example.org/p": [{"http://example.org/q": 2}, {"http://example.org/q": 1}]}"#),
+		&mut NoLoader
+	);
+}
+
+#[test]
+#[should_panic(expected = "not isomorphic")]
+fn different_values() {
+	assert_expanded_eq!(
+		parse(r#"{"@id": "_:a", "http://example.org/name": "A"}"#),
+		parse(r#"{"@id": "_:a", "http://example.org/name": "B"}"#)
+	);
+}
+
+#[test]
+fn blank_node_structure() {
+	// A cycle of four blank nodes and two cycles of two blank nodes look the same locally.
+	let cycle = expand(r#"[
+		{"@id": "_:a", "http://example.org/p": {"@id": "_:b"}},
+		{"@id": "_:b", "http://example.org/p": {"@id": "_:c"}},
+		{"@id": "_:c", "http://example.org/p": {"@id": "_:d"}},
+		{"@id": "_:d", "http://example.org/p": {"@id": "_:a"}}
+	]"#);
+
+	let renamed = expand(r#"[
+		{"@id": "_:w", "http://example.org/p": {"@id": "_:x"}},
+		{"@id": "_:z", "http://example.org/p": {"@id": "_:w"}},
+		{"@id": "_:y", "http://example.org/p": {"@id": "_:z"}},
+		{"@id": "_:x", "http://example.org/p": {"@id": "_:y"}}
+	]"#);
+
+	let two_cycles = expand(r#"[
+		{"@id": "_:a", "http://example.org/p": {"@id": "_:b"}},
+		{"@id": "_:b", "http://example.org/p": {"@id": "_:a"}},
+		{"@id": "_:c", "http://example.org/p": {"@id": "_:d"}},
+		{"@id": "_:d", "http://example.org/p": {"@id": "_:c"}}
+	]"#);
+
+	assert!(is_isomorphic(&cycle, &renamed));
+	assert!(!is_isomorphic(&cycle, &two_cycles));
+}
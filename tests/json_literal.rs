@@ -0,0 +1,110 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	compaction,
+	object::{
+		JsonLiteral,
+		Literal,
+		Value
+	}
+};
+
+const CONTEXT: &str = r#"{"data": {"@id": "http://example.org/data", "@type": "@json"}}"#;
+
+/// Same value, numbers having the same mantissa and exponent, and members the same order.
+fn assert_identical(a: &JsonValue, b: &JsonValue) {
+	match (a, b) {
+		(JsonValue::Number(a), JsonValue::Number(b)) => assert_eq!(a.as_parts(), b.as_parts()),
+		(JsonValue::Array(a), JsonValue::Array(b)) => {
+			assert_eq!(a.len(), b.len());
+			for (a, b) in a.iter().zip(b) {
+				assert_identical(a, b)
+			}
+		},
+		(JsonValue::Object(a), JsonValue::Object(b)) => {
+			let keys: Vec<_> = a.iter().map(|(key, _)| key).collect();
+			assert_eq!(keys, b.iter().map(|(key, _)| key).collect::<Vec<_>>());
+			for ((_, a), (_, b)) in a.iter().zip(b.iter()) {
+				assert_identical(a, b)
+			}
+		},
+		(a, b) => assert_eq!(a, b)
+	}
+}
+
+fn data(expanded: &ExpandedDocument<IriBuf>) -> JsonLiteral {
+	let node = expanded.iter().next().unwrap().as_node().unwrap();
+	let prop = Reference::Id(IriBuf::new("http://example.org/data").unwrap());
+	match node.get(&prop).next().unwrap().as_value().unwrap() {
+		Value::Literal(Literal::Json(literal), _) => literal.clone(),
+		_ => panic!("expected a JSON literal")
+	}
+}
+
+#[test]
+fn parsed_lexical_forms() {
+	let literal = JsonLiteral::parse(r#"{ "b": 1.50, "a": [1E+2, "x \" y", -0.0] }"#).unwrap();
+	assert_eq!(literal.as_str(), r#"{"b":1.50,"a":[1E+2,"x \" y",-0.0]}"#);
+	assert_eq!(literal.value()["a"][1], "x \" y");
+	assert!(JsonLiteral::parse("{").is_err());
+}
+
+#[test]
+fn value_lexical_forms() {
+	let value = json::parse(r#"{"z": 1e2, "a": 1.50, "m": [0.001, -3, 100, 2.5e-10, null, true]}"#).unwrap();
+	let literal = JsonLiteral::from(&value);
+	assert_eq!(literal.to_string(), r#"{"z":1e2,"a":1.50,"m":[0.001,-3,100,25e-11,null,true]}"#);
+	assert_identical(&json::parse(literal.as_str()).unwrap(), &value);
+
+	assert_eq!(JsonLiteral::from(json::parse("100").unwrap()), JsonLiteral::from(json::parse("100").unwrap()));
+}
+
+#[test]
+fn canonical_equality() {
+	use std::collections::HashSet;
+
+	// Literals are compared by their canonical form, whatever their member order and number
+	// lexical forms.
+	let a = JsonLiteral::parse(r#"{"b": [1.50, 1e2], "a": {"y": true, "x": null}}"#).unwrap();
+	let b = JsonLiteral::parse(r#"{"a": {"x": null, "y": true}, "b": [1.5, 100]}"#).unwrap();
+	assert_ne!(a.as_str(), b.as_str());
+	assert_eq!(a.canonical_form(), r#"{"a":{"x":null,"y":true},"b":[1.5,100]}"#);
+	assert_eq!(a, b);
+	assert_eq!(vec![a.clone(), b].into_iter().collect::<HashSet<_>>().len(), 1);
+
+	// Numbers are written as in ECMAScript.
+	let numbers = JsonLiteral::parse("[1.50, 1E+2, -0.0, 1e21, 1.5e-7, 0.000001, 2e25]").unwrap();
+	assert_eq!(numbers.canonical_form(), "[1.5,100,0,1e+21,1.5e-7,0.000001,2e+25]");
+
+	// Array order matters.
+	assert_ne!(a, JsonLiteral::parse(r#"{"b": [100, 1.5], "a": {"x": null, "y": true}}"#).unwrap());
+}
+
+#[test]
+fn round_trip() {
+	let source = r#"{"z": {"y": 1, "x": 2}, "a": 1e2, "n": [1.50, 12.0]}"#;
+	let doc = json::parse(&format!(r#"{{"@context": {}, "@id": "http://example.org/a", "data": {}}}"#, CONTEXT, source)).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+
+	let literal = data(&expanded);
+	assert_eq!(literal.as_str(), r#"{"z":{"y":1,"x":2},"a":1e2,"n":[1.50,12.0]}"#);
+	assert_identical(literal.value(), &json::parse(source).unwrap());
+
+	let context = json::parse(CONTEXT).unwrap();
+	let compacted = task::block_on(compaction::compact::<IriBuf, JsonContext<IriBuf>, _>(&expanded, &context, None, &mut NoLoader, compaction::Options::default())).unwrap();
+	assert_identical(&compacted["data"], &json::parse(source).unwrap());
+
+	// Identical literals are equal.
+	let again: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	assert!(Literal::Json(data(&again)) == Literal::Json(literal));
+}
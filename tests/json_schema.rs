@@ -0,0 +1,113 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	JsonContext,
+	NoLoader,
+	Reference,
+	context::Local,
+	schema,
+	shape::{
+		Shape,
+		PropertyShape,
+		NodeKind
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"id": "@id",
+	"type": "@type",
+	"schema": "http://schema.org/",
+	"Person": "http://schema.org/Person",
+	"name": "http://schema.org/name",
+	"email": "http://schema.org/email",
+	"knows": {"@id": "http://schema.org/knows", "@type": "@id"},
+	"age": {"@id": "http://schema.org/age", "@type": "http://www.w3.org/2001/XMLSchema#integer"},
+	"birthDate": {"@id": "http://schema.org/birthDate", "@type": "http://www.w3.org/2001/XMLSchema#date"},
+	"tags": {"@id": "http://schema.org/keywords", "@container": "@set"},
+	"label": {"@id": "http://www.w3.org/2000/01/rdf-schema#label", "@container": "@language"},
+	"children": {"@reverse": "http://schema.org/parent"},
+	"meta": "@nest",
+	"created": {"@id": "http://purl.org/dc/terms/created", "@nest": "meta"},
+	"removed": null
+}"#;
+
+fn context(context: &str) -> JsonContext<IriBuf> {
+	task::block_on(json::parse(context).unwrap().process(&JsonContext::new(None), &mut NoLoader, None)).unwrap()
+}
+
+fn string() -> JsonValue {
+	json::parse(r#"{"type": "string"}"#).unwrap()
+}
+
+#[test]
+fn terms() {
+	let schema = schema::json_schema(&context(CONTEXT), None);
+	assert_eq!(schema["$schema"], schema::DIALECT);
+	assert_eq!(schema["type"], "object");
+
+	let properties = &schema["properties"];
+	assert!(properties["@context"].is_object());
+	assert_eq!(properties["id"], string());
+	assert_eq!(properties["type"]["anyOf"][0], string());
+	assert_eq!(properties["type"]["anyOf"][1]["items"], string());
+
+	// Terms without type coercion accept any value.
+	assert_eq!(properties["name"], json::parse(r#"{"description": "http://schema.org/name"}"#).unwrap());
+
+	assert_eq!(properties["knows"]["anyOf"][0]["anyOf"][0], string());
+	assert_eq!(properties["knows"]["anyOf"][0]["anyOf"][1]["type"], "object");
+	assert_eq!(properties["knows"]["anyOf"][1]["type"], "array");
+	assert_eq!(properties["age"]["anyOf"][0]["type"], json::parse(r#"["integer", "string"]"#).unwrap());
+	assert_eq!(properties["birthDate"]["anyOf"][0]["format"], "date");
+	assert_eq!(properties["tags"]["type"], "array");
+	assert!(properties["tags"]["items"].is_empty());
+	assert_eq!(properties["label"]["type"], "object");
+	assert_eq!(properties["label"]["additionalProperties"]["anyOf"][0], string());
+	assert_eq!(properties["children"]["anyOf"][0]["type"], "object");
+
+	// Nested properties.
+	assert!(properties["created"].is_null());
+	assert_eq!(properties["meta"]["properties"]["created"]["description"], "http://purl.org/dc/terms/created");
+
+	// Null definitions are not properties.
+	assert!(properties["removed"].is_null());
+
+	// Without vocabulary mapping, only terms, keywords and IRIs are allowed.
+	assert_eq!(schema["additionalProperties"], false);
+	assert!(schema["patternProperties"][":"].is_object());
+	assert!(schema["required"].is_null());
+
+	let schema = schema::json_schema(&context(r#"{"@vocab": "http://schema.org/"}"#), None);
+	assert!(schema["additionalProperties"].is_null());
+}
+
+#[test]
+fn shape_hints() {
+	let schema_org = |name: &str| Reference::Id(IriBuf::new(&format!("http://schema.org/{}", name)).unwrap());
+	let shape = Shape::new()
+		.target_class(schema_org("Person"))
+		.with_type(schema_org("Agent"))
+		.with_property(PropertyShape::new(schema_org("name")).required().max_count(1).datatype(Iri::new("http://www.w3.org/2001/XMLSchema#string").unwrap()))
+		.with_property(PropertyShape::new(schema_org("email")).kind(NodeKind::Iri))
+		.with_property(PropertyShape::new(schema_org("keywords")).required().max_count(1));
+
+	let schema = schema::json_schema(&context(CONTEXT), Some(&shape));
+	let properties = &schema["properties"];
+	assert_eq!(properties["name"]["type"], "string");
+	assert_eq!(properties["email"]["anyOf"][0]["type"], "object");
+
+	// Sets are always arrays.
+	assert_eq!(properties["tags"]["type"], "array");
+
+	assert_eq!(schema["required"], json::parse(r#"["name", "tags", "type"]"#).unwrap());
+
+	// Required types, compacted.
+	assert_eq!(schema["allOf"][0]["properties"]["type"]["anyOf"][0]["const"], "Person");
+	assert_eq!(schema["allOf"][0]["properties"]["type"]["anyOf"][1]["contains"]["const"], "Person");
+	assert_eq!(schema["allOf"][1]["properties"]["type"]["anyOf"][0]["const"], "http://schema.org/Agent");
+}
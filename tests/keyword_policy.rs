@@ -0,0 +1,144 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	KeywordPolicy,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion::{
+		self,
+		KeywordAction
+	},
+	util::AsJson
+};
+
+fn expand(doc: &str, keyword_policy: KeywordPolicy) -> Result<JsonValue, ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	let options = expansion::Options {
+		keyword_policy,
+		..expansion::Options::default()
+	};
+
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options)).map_err(|e| e.code())?;
+	Ok(expanded.as_json())
+}
+
+const DOCUMENTS: &[&str] = &[
+	// Key.
+	r#"{"@id": "http://example.org/a", "@annotation": "x", "http://example.org/p": "v"}"#,
+	// Key of a nested node object.
+	r#"{"@id": "http://example.org/a", "http://example.org/p": "v", "http://example.org/q": {"@foo": "x"}}"#,
+	// `@id` value.
+	r#"{"@id": "@foo", "http://example.org/p": "v"}"#,
+	// Term definition.
+	r#"{"@context": {"@foo": "http://example.org/foo"}, "@id": "http://example.org/a", "http://example.org/p": "v"}"#,
+	// IRI mapping of a term definition.
+	r#"{"@context": {"foo": {"@id": "@foo"}}, "@id": "http://example.org/a", "http://example.org/p": "v"}"#,
+	// Reverse property.
+	r#"{"@context": {"foo": {"@reverse": "@foo"}}, "@id": "http://example.org/a", "http://example.org/p": "v"}"#
+];
+
+#[test]
+fn ignore_and_warn() {
+	for doc in DOCUMENTS {
+		let ignored = expand(doc, KeywordPolicy::Ignore).unwrap();
+		assert_eq!(expand(doc, KeywordPolicy::Warn).unwrap(), ignored);
+		assert_eq!(ignored[0]["http://example.org/p"][0]["@value"], "v");
+		assert!(ignored[0]["@annotation"].is_null());
+	}
+}
+
+#[test]
+fn error() {
+	for doc in DOCUMENTS {
+		assert_eq!(expand(doc, KeywordPolicy::Error), Err(ErrorCode::UnknownKeyword), "{}", doc);
+	}
+
+	assert_eq!(expand(r#"{"http://example.org/p": {"@value": "v", "@foo": "x"}}"#, KeywordPolicy::Error), Err(ErrorCode::UnknownKeyword));
+
+	// Keywords, aliases and keyword-like values of other entries are accepted.
+	assert!(expand(r#"{
+		"@context": {"id": "@id"},
+		"id": "http://example.org/a",
+		"http://example.org/p": [{"@value": "v", "@language": "en"}, "@foo"]
+	}"#, KeywordPolicy::Error).is_ok());
+
+	assert_eq!(KeywordPolicy::default(), KeywordPolicy::Ignore);
+	assert_eq!(ErrorCode::UnknownKeyword.as_str(), "unknown keyword");
+	assert_eq!(ErrorCode::try_from("unknown keyword"), Ok(ErrorCode::UnknownKeyword));
+}
+
+#[test]
+fn unknown_keywords_handler() {
+	let doc = json::parse(r#"{"@id": "http://example.org/a", "@annotation": "x"}"#).unwrap();
+	let options = expansion::Options {
+		keyword_policy: KeywordPolicy::Error,
+		unknown_keywords: Some(|_, _| KeywordAction::Keep),
+		..expansion::Options::default()
+	};
+
+	// The handler decides of the unknown keyword keys.
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options)).unwrap();
+	let node = expanded.iter().next().unwrap().as_node().unwrap();
+	assert!(node.extension("@annotation").is_some());
+}
+
+fn expand_with(doc: &str, unknown_keywords: Option<expansion::KeywordHandler>, keyword_policy: KeywordPolicy, dropped_entries: bool) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	let options = expansion::Options {
+		unknown_keywords,
+		keyword_policy,
+		dropped_entries,
+		..expansion::Options::default()
+	};
+
+	task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+#[test]
+fn precedence() {
+	let handlers: [(Option<expansion::KeywordHandler>, Option<bool>); 3] = [
+		(None, None),
+		(Some(|_, _| KeywordAction::Drop), Some(false)),
+		(Some(|_, _| KeywordAction::Keep), Some(true))
+	];
+
+	let key = r#"{"@id": "http://example.org/a", "@annotation": "x", "http://example.org/p": "v"}"#;
+	let id = r#"{"@id": "@foo", "http://example.org/p": "v"}"#;
+	for (handler, kept) in handlers.iter() {
+		for policy in [KeywordPolicy::Ignore, KeywordPolicy::Warn, KeywordPolicy::Error].iter() {
+			for dropped_entries in [true, false].iter() {
+				let combination = format!("handler: {:?}, policy: {:?}, dropped_entries: {}", kept, policy, dropped_entries);
+
+				// The handler decides of keys, then the policy, then `dropped_entries`.
+				let result = expand_with(key, *handler, *policy, *dropped_entries);
+				match kept {
+					Some(kept) => {
+						let expanded = result.unwrap();
+						let node = expanded.iter().next().unwrap().as_node().unwrap();
+						assert_eq!(node.extension("@annotation").is_some(), *kept, "{}", combination)
+					},
+					None if *policy == KeywordPolicy::Error => assert_eq!(result.map(|_| ()), Err(ErrorCode::UnknownKeyword), "{}", combination),
+					None if !dropped_entries => assert_eq!(result.map(|_| ()), Err(ErrorCode::DroppedEntry), "{}", combination),
+					None => assert!(result.is_ok(), "{}", combination)
+				}
+
+				// Other unknown keywords only depend on the policy.
+				let result = expand_with(id, *handler, *policy, *dropped_entries);
+				if *policy == KeywordPolicy::Error {
+					assert_eq!(result.map(|_| ()), Err(ErrorCode::UnknownKeyword), "{}", combination)
+				} else {
+					assert!(result.is_ok(), "{}", combination)
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,118 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	ErrorCode,
+	Lenient,
+	ProcessingMode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	context::Local,
+	expansion,
+	syntax::{
+		Keyword,
+		Term
+	}
+};
+
+fn expand(context: &str, processing_mode: ProcessingMode) -> Result<ExpandedDocument<IriBuf>, ErrorCode> {
+	let doc = json::parse(&format!("{{\"@context\": {}, \"http://example.org/p\": \"v\"}}", context)).unwrap();
+	let initial_context: JsonContext<IriBuf> = JsonContext::new(None);
+	let options = expansion::Options {
+		processing_mode,
+		ordered: false,
+		..expansion::Options::default()
+	};
+
+	task::block_on(doc.expand_with(None, &initial_context, &mut NoLoader, options)).map_err(|e| e.code())
+}
+
+fn positive(context: &str) {
+	if let Err(code) = expand(context, ProcessingMode::JsonLd1_1) {
+		panic!("unexpected error `{}` for context {}", code.as_str(), context)
+	}
+}
+
+fn negative(context: &str, processing_mode: ProcessingMode) {
+	match expand(context, processing_mode) {
+		Ok(_) => panic!("expected a keyword redefinition error for context {}", context),
+		Err(code) => assert_eq!(code, ErrorCode::KeywordRedefinition)
+	}
+}
+
+#[test]
+fn type_container_set() {
+	positive(r#"{"@type": {"@container": "@set"}}"#)
+}
+
+#[test]
+fn type_protected() {
+	positive(r#"{"@type": {"@protected": true}}"#)
+}
+
+#[test]
+fn type_container_set_protected() {
+	positive(r#"{"@type": {"@container": "@set", "@protected": true}}"#)
+}
+
+#[test]
+fn type_alias() {
+	positive(r#"{"type": "@type"}"#);
+	positive(r#"{"type": {"@id": "@type", "@container": "@set"}}"#);
+	positive(r#"{"@protected": true, "type": {"@id": "@type", "@container": "@set"}}"#)
+}
+
+#[test]
+fn type_container_set_json_ld_1_0() {
+	negative(r#"{"@type": {"@container": "@set"}}"#, ProcessingMode::JsonLd1_0)
+}
+
+#[test]
+fn type_other_container() {
+	negative(r#"{"@type": {"@container": "@list"}}"#, ProcessingMode::JsonLd1_1)
+}
+
+#[test]
+fn type_other_entry() {
+	negative(r#"{"@type": {"@id": "http://example.org/type"}}"#, ProcessingMode::JsonLd1_1);
+	negative(r#"{"@type": {"@container": "@set", "@id": "http://example.org/type"}}"#, ProcessingMode::JsonLd1_1)
+}
+
+#[test]
+fn type_empty_definition() {
+	negative(r#"{"@type": {}}"#, ProcessingMode::JsonLd1_1)
+}
+
+#[test]
+fn type_simple_definition() {
+	negative(r#"{"@type": "http://example.org/type"}"#, ProcessingMode::JsonLd1_1)
+}
+
+#[test]
+fn other_keywords() {
+	negative(r#"{"@id": "http://example.org/id"}"#, ProcessingMode::JsonLd1_1);
+	negative(r#"{"@graph": {"@id": "http://example.org/graph"}}"#, ProcessingMode::JsonLd1_1);
+	negative(r#"{"@list": {"@container": "@set"}}"#, ProcessingMode::JsonLd1_1)
+}
+
+#[test]
+fn keyword_aliases() {
+	let context = json::parse(r#"{"@vocab": "http://example.org/", "type": "@type", "p": "http://example.org/p"}"#).unwrap();
+	let context: JsonContext<IriBuf> = task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+
+	for key in &["@type", "type", "p", "q", "@unknown", "ex:q"] {
+		let keyword = match expansion::expand_iri(&context, key, false, true) {
+			Lenient::Ok(Term::Keyword(keyword)) => Some(keyword),
+			_ => None
+		};
+
+		assert_eq!(expansion::expand_keyword(&context, key), keyword, "key `{}`", key)
+	}
+
+	assert_eq!(expansion::expand_keyword(&context, "type"), Some(Keyword::Type))
+}
@@ -0,0 +1,40 @@
+extern crate json_ld;
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use json_ld::{
+	ProcessingMode,
+	syntax::{
+		Keyword,
+		InvalidKeyword,
+		is_keyword
+	}
+};
+
+#[test]
+fn string_mapping() {
+	let mut strings = HashSet::new();
+	for keyword in Keyword::ALL.iter() {
+		let str = keyword.as_str();
+		assert!(strings.insert(str));
+		assert!(is_keyword(str));
+		assert_eq!(str.parse::<Keyword>(), Ok(*keyword));
+		assert_eq!(Keyword::try_from(str), Ok(*keyword));
+		assert_eq!(keyword.to_string(), str)
+	}
+
+	assert_eq!(strings.len(), 23);
+	assert_eq!("@annotation".parse::<Keyword>(), Err(InvalidKeyword("@annotation".to_string())));
+	assert_eq!("id".parse::<Keyword>().unwrap_err().to_string(), "invalid keyword `id`");
+}
+
+#[test]
+fn processing_modes() {
+	assert!(Keyword::ALL.iter().all(|keyword| keyword.is_valid_in(ProcessingMode::JsonLd1_1)));
+
+	let json_ld_1_0: Vec<_> = Keyword::ALL.iter().filter(|keyword| keyword.is_valid_in(ProcessingMode::JsonLd1_0)).map(Keyword::as_str).collect();
+	assert_eq!(json_ld_1_0, [
+		"@base", "@container", "@context", "@graph", "@id", "@index", "@language", "@list",
+		"@reverse", "@set", "@type", "@value", "@vocab"
+	]);
+}
@@ -0,0 +1,25 @@
+extern crate json_ld;
+
+use json_ld::{
+	Direction,
+	LangString
+};
+
+fn string(value: &str, language: Option<&str>, direction: Option<Direction>) -> LangString {
+	LangString::new(value.to_string(), language.map(String::from), direction)
+}
+
+#[test]
+fn html() {
+	assert_eq!(string("שלום", Some("he"), Some(Direction::Rtl)).to_html(), "<span dir=\"rtl\" lang=\"he\">שלום</span>");
+	assert_eq!(string("<b>Tom & \"Jerry\"</b>", None, Some(Direction::Ltr)).to_html(), "<span dir=\"ltr\">&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;</span>");
+	assert_eq!(string("مرحبا", Some("ar"), None).to_html(), "<bdi lang=\"ar\">مرحبا</bdi>");
+	assert_eq!(string("hello", None, None).to_html(), "<bdi>hello</bdi>");
+}
+
+#[test]
+fn bidi_isolates() {
+	assert_eq!(string("שלום", Some("he"), Some(Direction::Rtl)).to_bidi_isolated(), "\u{2067}שלום\u{2069}");
+	assert_eq!(string("hello", None, Some(Direction::Ltr)).to_bidi_isolated(), "\u{2066}hello\u{2069}");
+	assert_eq!(string("hello", Some("en"), None).to_bidi_isolated(), "\u{2068}hello\u{2069}");
+}
@@ -0,0 +1,65 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	LangString,
+	NoLoader,
+	Node,
+	Reference,
+	lookup_language
+};
+
+fn expand(doc: &str) -> Node<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	expanded.into_iter().next().unwrap().into_inner().into_node().unwrap()
+}
+
+fn property(iri: &str) -> Reference<IriBuf> {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+fn preferred<'a>(node: &'a Node<IriBuf>, ranges: &[&str]) -> Option<&'a str> {
+	node.get_lang_preferred(&property("http://schema.org/name"), ranges).map(LangString::as_str)
+}
+
+#[test]
+fn lookup() {
+	let node = expand(r#"{
+		"@id": "http://example.org/london",
+		"http://schema.org/name": [
+			{"@value": "Londres", "@language": "fr"},
+			{"@value": "London", "@language": "en"},
+			{"@value": "Londra", "@language": "it-IT"},
+			"LDN"
+		],
+		"http://schema.org/population": 8982000
+	}"#);
+
+	assert_eq!(preferred(&node, &["en-GB", "en", "*"]), Some("London"));
+	assert_eq!(preferred(&node, &["EN-gb"]), Some("London"));
+	assert_eq!(preferred(&node, &["de", "fr"]), Some("Londres"));
+	assert_eq!(preferred(&node, &["it"]), None);
+	assert_eq!(preferred(&node, &["it-IT-x-rome"]), Some("Londra"));
+	assert_eq!(preferred(&node, &["de", "*"]), Some("Londres"));
+	assert_eq!(preferred(&node, &["de"]), None);
+	assert_eq!(node.get_lang_preferred(&property("http://schema.org/population"), &["*"]), None);
+}
+
+#[test]
+fn language_strings() {
+	let strings = [
+		LangString::new("color".to_string(), Some("en-US".to_string()), None),
+		LangString::new("colour".to_string(), Some("en-GB".to_string()), None)
+	];
+
+	assert_eq!(lookup_language(&strings, &["en-GB"]).map(LangString::as_str), Some("colour"));
+	assert_eq!(lookup_language(&strings, &["en", "*"]).map(LangString::as_str), Some("color"));
+	assert_eq!(lookup_language(&strings, &[]), None);
+}
@@ -0,0 +1,220 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use async_std::task;
+use futures::future::{self, FutureExt};
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	FnLoader,
+	Loader,
+	NoLoader,
+	SharedLoader,
+	layer::{
+		LoaderExt,
+		CacheLayer,
+		Fetch,
+		FixturesLayer,
+		RecordLayer,
+		RetryLayer,
+		RewriteLayer,
+		SharedCacheLayer
+	}
+};
+
+type Fetched = Arc<Mutex<Vec<String>>>;
+
+/// Loader recording every fetched URL, failing for URLs containing `broken` and for the first
+/// `failures` fetches.
+fn server(fetched: Fetched, failures: usize) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |url: Iri| {
+		let mut fetched = fetched.lock().unwrap();
+		fetched.push(url.as_str().to_string());
+		future::ready(if fetched.len() <= failures || url.as_str().contains("broken") {
+			Err(ErrorCode::LoadingDocumentFailed)
+		} else {
+			Ok(format!(r#"{{"@context": {{"from": "{}"}}}}"#, url))
+		})
+	})
+}
+
+fn iri(iri: &str) -> Iri<'_> {
+	Iri::new(iri).unwrap()
+}
+
+fn fetched(fetched: &Fetched) -> Vec<String> {
+	fetched.lock().unwrap().clone()
+}
+
+#[test]
+fn retry() {
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 2).with(RetryLayer::new(3));
+	let doc = task::block_on(loader.load(iri("https://example.org/context"))).unwrap();
+	assert_eq!(doc["@context"]["from"], "https://example.org/context");
+	assert_eq!(fetched(&urls).len(), 3);
+
+	// The last error is returned after the last attempt.
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 0).with(RetryLayer::new(3));
+	let e = task::block_on(loader.load(iri("https://example.org/broken"))).err().unwrap();
+	assert_eq!(e.code(), ErrorCode::LoadingDocumentFailed);
+	assert_eq!(fetched(&urls), vec!["https://example.org/broken"; 3]);
+
+	// Documents are loaded at least once.
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 0).with(RetryLayer::new(0));
+	assert!(task::block_on(loader.load(iri("https://example.org/broken"))).is_err());
+	assert_eq!(fetched(&urls).len(), 1);
+}
+
+#[test]
+fn retry_backoff() {
+	let delays = Arc::new(Mutex::new(Vec::new()));
+	let sleep = {
+		let delays = delays.clone();
+		Arc::new(move |delay: Duration| {
+			delays.lock().unwrap().push(delay);
+			future::ready(()).boxed()
+		})
+	};
+
+	let layer = RetryLayer::new(4).backoff(Duration::from_millis(10), sleep);
+	let mut loader = server(Fetched::default(), 0).with(layer.clone());
+	assert!(task::block_on(loader.load(iri("https://example.org/broken"))).is_err());
+
+	// The delay doubles after each attempt, and there is no delay after the last one.
+	let expected: Vec<_> = [10, 20, 40].iter().map(|ms| Duration::from_millis(*ms)).collect();
+	assert_eq!(*delays.lock().unwrap(), expected);
+
+	// Successful loads do not wait.
+	delays.lock().unwrap().clear();
+	let mut loader = server(Fetched::default(), 1).with(layer);
+	assert!(task::block_on(loader.load(iri("https://example.org/context"))).is_ok());
+	assert_eq!(*delays.lock().unwrap(), vec![Duration::from_millis(10)]);
+}
+
+#[test]
+fn cache() {
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 0).with(CacheLayer::new());
+
+	let a = iri("https://example.org/a");
+	task::block_on(loader.load(a)).unwrap();
+	let doc = task::block_on(loader.load(a)).unwrap();
+	assert_eq!(doc.url().as_str(), "https://example.org/a");
+	assert_eq!(doc["@context"]["from"], "https://example.org/a");
+	task::block_on(loader.load(iri("https://example.org/b"))).unwrap();
+	assert_eq!(fetched(&urls), vec!["https://example.org/a", "https://example.org/b"]);
+
+	// Failed loads are not cached.
+	let broken = iri("https://example.org/broken");
+	assert!(task::block_on(loader.load(broken)).is_err());
+	assert!(task::block_on(loader.load(broken)).is_err());
+	assert_eq!(fetched(&urls).len(), 4);
+
+	loader.clear();
+	task::block_on(loader.load(a)).unwrap();
+	assert_eq!(fetched(&urls).len(), 5);
+}
+
+#[test]
+fn shared_cache() {
+	let urls = Fetched::default();
+	let loader = server(urls.clone(), 0).with(SharedCacheLayer::new());
+
+	let a = iri("https://example.org/a");
+	task::block_on(future::join(loader.load_shared(a), loader.load_shared(a))).0.unwrap();
+	task::block_on(loader.load_shared(a)).unwrap();
+	assert_eq!(fetched(&urls), vec!["https://example.org/a"]);
+
+	loader.clear();
+	task::block_on(loader.load_shared(a)).unwrap();
+	assert_eq!(fetched(&urls).len(), 2);
+}
+
+#[test]
+fn rewrite() {
+	let layer = RewriteLayer::new()
+		.mirror(iri("https://www.w3.org/"), iri("https://mirror.example.org/w3c/"))
+		.mirror(iri("https://www.w3.org/ns/"), iri("https://ns.example.org/"));
+
+	// The longest prefix is used.
+	assert_eq!(layer.rewrite(iri("https://www.w3.org/2018/credentials/v1")).unwrap().as_str(), "https://mirror.example.org/w3c/2018/credentials/v1");
+	assert_eq!(layer.rewrite(iri("https://www.w3.org/ns/did/v1")).unwrap().as_str(), "https://ns.example.org/did/v1");
+	assert!(layer.rewrite(iri("https://example.org/context")).is_none());
+
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 0).with(layer);
+	let doc = task::block_on(loader.load(iri("https://www.w3.org/ns/did/v1"))).unwrap();
+
+	// The document keeps its original URL.
+	assert_eq!(doc.url().as_str(), "https://www.w3.org/ns/did/v1");
+	assert_eq!(doc["@context"]["from"], "https://ns.example.org/did/v1");
+
+	task::block_on(loader.load(iri("https://example.org/context"))).unwrap();
+	assert_eq!(fetched(&urls), vec!["https://ns.example.org/did/v1", "https://example.org/context"]);
+}
+
+#[test]
+fn record() {
+	let records = RecordLayer::new();
+	let mut loader = server(Fetched::default(), 0).with(records.clone());
+
+	task::block_on(loader.load(iri("https://example.org/a"))).unwrap();
+	assert!(task::block_on(loader.load(iri("https://example.org/broken"))).is_err());
+
+	let expected = vec![
+		Fetch { url: IriBuf::new("https://example.org/a").unwrap(), success: true },
+		Fetch { url: IriBuf::new("https://example.org/broken").unwrap(), success: false }
+	];
+
+	// The records are shared with the layer.
+	assert_eq!(loader.fetches(), expected);
+	assert_eq!(records.fetches(), expected);
+
+	records.clear();
+	assert!(loader.fetches().is_empty());
+}
+
+#[test]
+fn fixtures() {
+	let urls = Fetched::default();
+	let mut loader = server(urls.clone(), 0)
+		.with(FixturesLayer::new().fixture(iri("https://example.org/broken"), json::parse(r#"{"@context": {"fixture": true}}"#).unwrap()));
+
+	// Fixtures are served without calling the inner loader.
+	let doc = task::block_on(loader.load(iri("https://example.org/broken"))).unwrap();
+	assert_eq!(doc.url().as_str(), "https://example.org/broken");
+	assert_eq!(doc["@context"]["fixture"], true);
+	assert!(fetched(&urls).is_empty());
+
+	task::block_on(loader.load(iri("https://example.org/a"))).unwrap();
+	assert_eq!(fetched(&urls), vec!["https://example.org/a"]);
+
+	// Without inner loader, only fixtures can be loaded.
+	let mut loader = NoLoader.with(FixturesLayer::new().fixture(iri("https://example.org/a"), JsonValue::new_object()));
+	assert!(task::block_on(loader.load(iri("https://example.org/a"))).is_ok());
+	assert_eq!(task::block_on(loader.load(iri("https://example.org/b"))).err().unwrap().code(), ErrorCode::LoadingDocumentFailed);
+}
+
+#[test]
+fn stack() {
+	// The last layer is the outermost: cached documents are not fetched or recorded again.
+	let urls = Fetched::default();
+	let records = RecordLayer::new();
+	let mut loader = server(urls.clone(), 1)
+		.with(RetryLayer::new(2))
+		.with(records.clone())
+		.with(CacheLayer::new());
+
+	let a = iri("https://example.org/a");
+	task::block_on(loader.load(a)).unwrap();
+	task::block_on(loader.load(a)).unwrap();
+	assert_eq!(fetched(&urls).len(), 2);
+	assert_eq!(records.fetches().len(), 1);
+}
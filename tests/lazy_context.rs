@@ -0,0 +1,84 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion,
+	context::{
+		Local,
+		LazyContext,
+		ProcessingOptions
+	},
+	layer::{
+		LoaderExt,
+		FixturesLayer
+	},
+	util::AsJson
+};
+
+const CONTEXT_URL: &str = "http://example.org/context.jsonld";
+
+const CONTEXT: &str = r#"{
+	"@context": {
+		"@vocab": "http://example.org/vocab#",
+		"schema": "http://schema.org/",
+		"xsd": "http://www.w3.org/2001/XMLSchema#",
+		"name": "schema:name",
+		"date": {"@id": "schema:date", "@type": "xsd:date"},
+		"knows": {"@id": "schema:knows", "@type": "@id"},
+		"friend": {"@id": "knows", "@context": {"nick": "schema:alternateName"}},
+		"Person": {"@id": "schema:Person", "@context": {"age": {"@id": "schema:age", "@type": "xsd:integer"}}},
+		"id": "@id",
+		"unused": {"@id": "schema:unused", "@container": "@language"},
+		"invalid": {"@id": 1}
+	}
+}"#;
+
+fn expand_eager(doc: &str, context: &json::JsonValue) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	let active_context: JsonContext<IriBuf> = task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	task::block_on(doc.expand(&active_context, &mut NoLoader)).unwrap()
+}
+
+#[test]
+fn same_as_eager() {
+	let context = json::parse(CONTEXT).unwrap()["@context"].clone();
+	let mut valid_context = context.clone();
+	valid_context.remove("invalid");
+
+	let docs = [
+		r#"{"name": "A", "date": "2020-01-01", "knows": "http://example.org/b"}"#,
+		r#"{"@type": "Person", "age": "3", "friend": {"nick": "B", "schema:email": "b@example.org"}}"#,
+		r#"{"id": "http://example.org/a", "other": true, "@context": {"title": "schema:title"}, "title": "T"}"#
+	];
+
+	for doc in &docs {
+		let mut lazy: LazyContext<IriBuf> = task::block_on(LazyContext::new(&context, &JsonContext::new(None), &mut NoLoader, None, ProcessingOptions::default())).unwrap();
+		let expanded = task::block_on(lazy.expand(&json::parse(doc).unwrap(), None, &mut NoLoader, expansion::Options::default())).unwrap();
+		assert_eq!(expanded.as_json(), expand_eager(doc, &valid_context).as_json(), "document: {}", doc);
+		assert!(lazy.is_pending("unused"));
+		assert!(lazy.is_pending("invalid"))
+	}
+}
+
+#[test]
+fn remote_context() {
+	let mut loader = NoLoader.with(FixturesLayer::new().fixture(Iri::new(CONTEXT_URL).unwrap(), json::parse(CONTEXT).unwrap()));
+	let url = json::JsonValue::from(CONTEXT_URL);
+	let mut lazy: LazyContext<IriBuf> = task::block_on(LazyContext::new(&url, &JsonContext::new(None), &mut loader, None, ProcessingOptions::default())).unwrap();
+	assert_eq!(lazy.pending_len(), 10);
+
+	assert!(task::block_on(lazy.define("date", &mut loader)).unwrap());
+	assert!(!lazy.is_pending("date"));
+	assert!(!lazy.is_pending("schema"));
+	assert!(!lazy.is_pending("xsd"));
+	assert_eq!(lazy.pending_len(), 7);
+
+	assert!(task::block_on(lazy.define("invalid", &mut loader)).is_err());
+}
@@ -0,0 +1,53 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Lenient,
+	Reference
+};
+
+#[test]
+fn combinators() {
+	let ok: Lenient<u32> = Lenient::Ok(1);
+	let unknown: Lenient<u32> = Lenient::Unknown("foo".to_string());
+
+	assert!(ok.is_ok() && !ok.is_unknown());
+	assert!(unknown.is_unknown() && !unknown.is_ok());
+	assert_eq!(ok.as_ref(), Ok(&1));
+	assert_eq!(unknown.as_ref(), Err("foo"));
+	assert_eq!(ok.clone().map(|n| n + 1), Lenient::Ok(2));
+	assert_eq!(unknown.clone().map(|n| n + 1), Lenient::Unknown("foo".to_string()));
+	assert_eq!(ok.clone().and_then(|n| Err::<u32, _>(n.to_string())), Lenient::Unknown("1".to_string()));
+	assert_eq!(ok.clone().unwrap_or(0), 1);
+	assert_eq!(unknown.clone().unwrap_or(0), 0);
+	assert_eq!(ok.clone().ok(), Some(1));
+	assert_eq!(unknown.clone().unknown().as_deref(), Some("foo"));
+	assert_eq!(ok.to_string(), "1");
+	assert_eq!(unknown.to_string(), "foo");
+
+	assert_eq!(unknown.clone().into_result(), Err("foo".to_string()));
+	assert_eq!(Result::<u32, String>::from(ok.clone()), Ok(1));
+	assert_eq!(Lenient::<u32>::from(Ok(1)), ok);
+	assert_eq!(Lenient::<u32>::from(Err("foo".to_string())), unknown);
+}
+
+#[test]
+fn unknown_types() {
+	let doc = json::parse(r#"{
+		"@type": ["http://example.org/Person", "Agent"],
+		"http://example.org/name": "Alice"
+	}"#).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	let node = expanded.iter().next().unwrap().as_node().unwrap();
+
+	// Without base IRI, the relative type `Agent` cannot be resolved.
+	let types: Vec<_> = node.types().iter().map(|ty| ty.as_ref().map(Reference::as_str)).collect();
+	assert_eq!(types, [Ok("http://example.org/Person"), Err("Agent")]);
+}
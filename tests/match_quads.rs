@@ -0,0 +1,87 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Dataset,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	rdf::{
+		Literal,
+		Term
+	}
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn id(iri: &str) -> Reference {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+const DOCUMENT: &str = r#"{
+	"@context": {"@vocab": "http://schema.org/", "knows": {"@type": "@id"}},
+	"@id": "http://example.org/alice",
+	"@type": "Person",
+	"name": "Alice",
+	"knows": ["http://example.org/bob", "http://example.org/carol"],
+	"address": {"postalCode": "75001"},
+	"@included": [{
+		"@id": "http://example.org/graph",
+		"@graph": {"@id": "http://example.org/bob", "name": "Bob"}
+	}]
+}"#;
+
+#[test]
+fn patterns() {
+	let doc = expand(DOCUMENT);
+	let alice = id("http://example.org/alice");
+	let knows = id("http://schema.org/knows");
+	let name = id("http://schema.org/name");
+
+	assert_eq!(doc.match_quads(Some(&alice), Some(&knows), None, None).unwrap().count(), 2);
+	assert_eq!(doc.match_quads(None, Some(&knows), Some(&Term::Ref(id("http://example.org/carol"))), None).unwrap().count(), 1);
+	assert_eq!(doc.match_quads(Some(&alice), None, None, None).unwrap().count(), 5);
+	assert_eq!(doc.match_quads(None, None, None, None).unwrap().count(), 7);
+
+	let names: Vec<_> = doc.match_quads(None, Some(&name), None, None).unwrap().map(|quad| quad.object.to_string()).collect();
+	assert_eq!(names.len(), 2);
+	assert!(names.contains(&r#""Alice""#.to_string()));
+
+	let graph = id("http://example.org/graph");
+	let bob: Vec<_> = doc.match_quads(None, Some(&name), None, Some(Some(&graph))).unwrap().collect();
+	assert_eq!(bob.len(), 1);
+	assert_eq!(bob[0].subject, id("http://example.org/bob"));
+	assert_eq!(doc.match_quads(None, Some(&name), None, Some(None)).unwrap().count(), 1);
+
+	// Blank nodes found in matched quads can be used in the next patterns.
+	let address = doc.match_quads(Some(&alice), Some(&id("http://schema.org/address")), None, None).unwrap().next().unwrap();
+	let address = match address.object {
+		Term::Ref(r) => r,
+		_ => panic!("not a reference")
+	};
+	let postal_code = doc.match_quads(Some(&address), None, None, None).unwrap().next().unwrap();
+	assert_eq!(postal_code.object, Term::Literal(Literal::Typed("75001".to_string(), IriBuf::new("http://www.w3.org/2001/XMLSchema#string").unwrap())));
+}
+
+#[test]
+fn dataset() {
+	let mut dataset = Dataset::try_from(expand(DOCUMENT)).unwrap();
+	dataset.merge(Dataset::try_from(expand(r#"{
+		"@id": "http://example.org/alice",
+		"http://schema.org/email": "alice@example.org"
+	}"#)).unwrap()).unwrap();
+
+	let alice = id("http://example.org/alice");
+	assert_eq!(dataset.match_quads(Some(&alice), None, None, None).unwrap().count(), 6);
+	assert_eq!(dataset.match_quads(Some(&alice), Some(&id("http://schema.org/email")), None, None).unwrap().count(), 1);
+	assert_eq!(dataset.match_quads(Some(&id("http://example.org/nobody")), None, None, None).unwrap().count(), 0)
+}
@@ -0,0 +1,67 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	RemoteDocument,
+	Processor,
+	expansion,
+	layer::{
+		LoaderExt,
+		FixturesLayer
+	},
+	util::AsJson
+};
+
+#[test]
+fn merged_documents() {
+	let a = json::parse(r#"{"@id": "alice", "http://schema.org/knows": {"@id": "bob"}}"#).unwrap();
+	let b = json::parse(r#"{"@id": "alice", "http://schema.org/name": "Alice"}"#).unwrap();
+	let documents = vec![
+		RemoteDocument::new(a, Iri::new("http://a.example/people/").unwrap()),
+		RemoteDocument::new(b, Iri::new("http://b.example/").unwrap())
+	];
+
+	let mut processor = Processor::new(NoLoader, expansion::Options::default());
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(processor.expand_merged(documents)).unwrap();
+
+	assert_eq!(expanded.as_json(), json::parse(r#"[
+		{"@id": "http://a.example/people/alice", "http://schema.org/knows": [{"@id": "http://a.example/people/bob"}]},
+		{"@id": "http://b.example/alice", "http://schema.org/name": [{"@value": "Alice"}]}
+	]"#).unwrap());
+}
+
+#[test]
+fn imported_scoped_contexts() {
+	let mut loader = NoLoader.with(FixturesLayer::new()
+		.fixture(Iri::new("http://other.example/lib/base.jsonld").unwrap(), json::parse(r#"{
+			"@context": {
+				"@version": 1.1,
+				"knows": {"@id": "http://schema.org/knows", "@context": "scoped.jsonld"}
+			}
+		}"#).unwrap())
+		.fixture(Iri::new("http://other.example/lib/scoped.jsonld").unwrap(), json::parse(r#"{
+			"@context": {"name": "http://schema.org/name"}
+		}"#).unwrap()));
+
+	let doc = json::parse(r#"{
+		"@context": {"@version": 1.1, "@import": "http://other.example/lib/base.jsonld"},
+		"@id": "alice",
+		"knows": {"name": "Bob"}
+	}"#).unwrap();
+
+	// The scoped context of `knows` is resolved against the URL of the imported context,
+	// and the `@id` against the URL of the document.
+	let base_url = Iri::new("http://example.org/doc").unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand_with(Some(base_url), &JsonContext::new(Some(base_url)), &mut loader, expansion::Options::default())).unwrap();
+	assert_eq!(expanded.as_json(), json::parse(r#"[{
+		"@id": "http://example.org/alice",
+		"http://schema.org/knows": [{"http://schema.org/name": [{"@value": "Bob"}]}]
+	}]"#).unwrap());
+}
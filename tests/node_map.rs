@@ -0,0 +1,54 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	Lenient,
+	NoLoader,
+	Reference,
+	flattening,
+	generator,
+	util::AsJson
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	task::block_on(json::parse(doc).unwrap().expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+#[test]
+fn malformed_ids_are_kept() {
+	// Without base IRI, `bob` is not a valid node identifier.
+	let doc = expand(r#"[
+		{
+			"@id": "http://example.org/alice",
+			"http://schema.org/knows": [{"@id": "bob", "http://schema.org/name": "Bob"}, {"http://schema.org/name": "Anonymous"}]
+		},
+		{"@id": "bob", "http://schema.org/age": 42}
+	]"#);
+
+	let map = flattening::node_map(&doc, generator::Blank::new()).unwrap();
+	let graph = map.default_graph();
+	assert_eq!(graph.len(), 3);
+
+	// Both occurrences of `bob` are the same node, keeping its identifier.
+	let bob_id = Lenient::Unknown("bob".to_string());
+	let bob: Vec<_> = graph.nodes().filter(|node| node.id() == Some(&bob_id)).collect();
+	assert_eq!(bob.len(), 1);
+	let bob = bob[0].as_json();
+	assert_eq!(bob["http://schema.org/name"][0]["@value"], "Bob");
+	assert_eq!(bob["http://schema.org/age"][0]["@value"], 42);
+
+	// References to it keep its identifier too.
+	let alice = graph.get(&Reference::Id(IriBuf::new("http://example.org/alice").unwrap())).unwrap().as_json();
+	let knows = &alice["http://schema.org/knows"];
+	assert_eq!(knows[0]["@id"], "bob");
+
+	// Only the node without identifier is assigned a blank node identifier.
+	assert!(knows[1]["@id"].as_str().unwrap().starts_with("_:"));
+	assert!(graph.nodes().filter(|node| node.id() != Some(&bob_id)).all(|node| matches!(node.id(), Some(Lenient::Ok(_)))));
+}
@@ -0,0 +1,132 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::collections::BTreeMap;
+use async_std::task;
+use iref::IriBuf;
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	Indexed,
+	JsonContext,
+	NoLoader,
+	Node,
+	Reference,
+	flattening::{
+		self,
+		NodeStore
+	},
+	generator,
+	util::AsJson
+};
+
+type Key = (Option<String>, String);
+
+type GraphNode = (Option<Reference<IriBuf>>, Indexed<Node<IriBuf>>);
+
+/// Key-value store, reading and writing back a copy of the node on each access.
+#[derive(Default)]
+struct KeyValueStore {
+	graphs: Vec<Reference<IriBuf>>,
+	nodes: BTreeMap<Key, (Reference<IriBuf>, Indexed<Node<IriBuf>>)>,
+	writes: usize
+}
+
+fn key(graph: Option<&Reference<IriBuf>>, id: &Reference<IriBuf>) -> Key {
+	(graph.map(|name| name.as_str().to_string()), id.as_str().to_string())
+}
+
+impl NodeStore<IriBuf> for KeyValueStore {
+	fn insert_graph(&mut self, name: &Reference<IriBuf>) {
+		if !self.graphs.contains(name) {
+			self.graphs.push(name.clone())
+		}
+	}
+
+	fn update<R, F: FnOnce(&mut Indexed<Node<IriBuf>>) -> R>(&mut self, graph: Option<&Reference<IriBuf>>, id: &Reference<IriBuf>, f: F) -> R {
+		if let Some(name) = graph {
+			self.insert_graph(name)
+		}
+
+		let mut node = self.get(graph, id).unwrap_or_else(|| Node::with_id(id.clone()).into());
+
+		let result = f(&mut node);
+		self.nodes.insert(key(graph, id), (id.clone(), node));
+		self.writes += 1;
+		result
+	}
+
+	fn graph_names(&self) -> Vec<Reference<IriBuf>> {
+		self.graphs.clone()
+	}
+
+	fn node_ids(&self, graph: Option<&Reference<IriBuf>>) -> Vec<Reference<IriBuf>> {
+		let graph = graph.map(|name| name.as_str().to_string());
+		self.nodes.iter().filter(|((g, _), _)| *g == graph).map(|(_, (id, _))| id.clone()).collect()
+	}
+
+	fn get(&self, graph: Option<&Reference<IriBuf>>, id: &Reference<IriBuf>) -> Option<Indexed<Node<IriBuf>>> {
+		self.nodes.get(&key(graph, id)).map(|(_, node)| node.clone())
+	}
+}
+
+const DOC: &str = r#"[
+	{
+		"@id": "http://example.org/alice",
+		"http://schema.org/knows": [
+			{"@id": "http://example.org/bob", "http://schema.org/name": "Bob"},
+			{"http://schema.org/name": "Anonymous"}
+		]
+	},
+	{
+		"@id": "http://example.org/graph",
+		"@graph": {"@id": "http://example.org/carol", "@type": "http://schema.org/Person"}
+	},
+	{"@id": "http://example.org/alice", "@index": "a", "http://schema.org/name": "Alice"}
+]"#;
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn flattened_json(nodes: Vec<GraphNode>) -> Vec<(Option<String>, JsonValue)> {
+	nodes.into_iter().map(|(graph, node)| (graph.map(|name| name.as_str().to_string()), node.as_json())).collect()
+}
+
+#[test]
+fn custom_store() {
+	let expanded = expand(DOC);
+	let mut store = KeyValueStore::default();
+	flattening::node_map_into(&expanded, generator::Blank::new(), &mut store).unwrap();
+	assert!(store.writes > 0);
+
+	let map = flattening::node_map(&expanded, generator::Blank::new()).unwrap();
+	for (graph, nodes) in map.graphs() {
+		assert_eq!(store.node_ids(graph).len(), nodes.len());
+		for (id, node) in nodes.iter() {
+			assert_eq!(store.get(graph, id).unwrap().as_json(), node.as_json())
+		}
+	}
+
+	let mut streamed = Vec::new();
+	flattening::flatten_store(&store, |graph, node| streamed.push((graph.cloned(), node)));
+
+	let flattened = flattening::flatten(&expanded, generator::Blank::new()).unwrap();
+	let expected: Vec<_> = flattened.graphs().flat_map(|(graph, nodes)| nodes.iter().map(move |node| (graph.cloned(), node.clone()))).collect();
+	assert_eq!(streamed.len(), 5);
+	assert_eq!(flattened_json(streamed), flattened_json(expected));
+}
+
+#[test]
+fn conflicting_indexes() {
+	let expanded = expand(r#"[
+		{"@id": "http://example.org/alice", "@index": "a"},
+		{"@id": "http://example.org/alice", "@index": "b"}
+	]"#);
+
+	let mut store = KeyValueStore::default();
+	assert!(flattening::node_map_into(&expanded, generator::Blank::new(), &mut store).is_err());
+}
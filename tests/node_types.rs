@@ -0,0 +1,64 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Lenient,
+	Reference,
+	Node,
+	Object
+};
+
+fn ty(iri: &str) -> Lenient<Reference> {
+	Lenient::Ok(Reference::Id(IriBuf::new(iri).unwrap()))
+}
+
+fn names(node: &Node) -> Vec<&str> {
+	node.types().iter().map(|ty| ty.as_str()).collect()
+}
+
+#[test]
+fn declared_order() {
+	let doc = json::parse(r#"{
+		"@context": {"@vocab": "https://www.w3.org/2018/credentials#"},
+		"@type": ["VerifiableCredential", "AlumniCredential"]
+	}"#).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	let node = match expanded.objects()[0].inner() {
+		Object::Node(node) => node,
+		_ => panic!("not a node")
+	};
+
+	let credential = ty("https://www.w3.org/2018/credentials#VerifiableCredential");
+	let alumni = ty("https://www.w3.org/2018/credentials#AlumniCredential");
+	assert_eq!(names(node), ["https://www.w3.org/2018/credentials#VerifiableCredential", "https://www.w3.org/2018/credentials#AlumniCredential"]);
+	assert!(node.has_types(&[alumni.clone(), credential.clone()]));
+	assert!(node.type_set() == vec![&alumni, &credential].into_iter().collect());
+}
+
+#[test]
+fn insertion() {
+	let a = ty("http://example.org/A");
+	let b = ty("http://example.org/B");
+	let c = ty("http://example.org/C");
+
+	let mut node: Node = Node::new();
+	assert!(node.insert_type(b.clone()));
+	assert!(node.insert_type(a.clone()));
+	assert!(!node.insert_type(b.clone()));
+	assert_eq!(names(&node), ["http://example.org/B", "http://example.org/A"]);
+
+	assert!(node.remove_type(&b));
+	assert!(!node.remove_type(&b));
+	assert_eq!(names(&node), ["http://example.org/A"]);
+
+	node.set_types(vec![c.clone(), a.clone(), c.clone(), b.clone()]);
+	assert_eq!(names(&node), ["http://example.org/C", "http://example.org/A", "http://example.org/B"]);
+	assert!(!node.has_types(&[b, ty("http://example.org/D")]))
+}
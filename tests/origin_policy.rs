@@ -0,0 +1,134 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use async_std::task;
+use futures::future;
+use iref::Iri;
+use json::JsonValue;
+use json_ld::{
+	Error,
+	ErrorCode,
+	FnLoader,
+	Loader,
+	NoLoader,
+	layer::{
+		LoaderExt,
+		FixturesLayer,
+		OriginPolicy,
+		OriginRule,
+		PolicyLayer
+	}
+};
+
+type Fetched = Arc<Mutex<Vec<String>>>;
+
+/// Loader serving `{"@context": {}}` with the given padding, recording every fetched URL.
+fn server(fetched: Fetched, padding: usize) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |url: Iri| {
+		fetched.lock().unwrap().push(url.as_str().to_string());
+		future::ready(Ok(format!(r#"{{"@context":{}{{}}}}"#, " ".repeat(padding))))
+	})
+}
+
+fn iri(iri: &str) -> Iri<'_> {
+	Iri::new(iri).unwrap()
+}
+
+/// Source of a policy violation.
+fn violation(result: Result<json_ld::RemoteDocument, Error>) -> String {
+	match result {
+		Ok(_) => panic!("the policy should be violated"),
+		Err(e) => {
+			assert_eq!(e.code(), ErrorCode::LoadingDocumentFailed);
+			std::error::Error::source(&e).unwrap().to_string()
+		}
+	}
+}
+
+#[test]
+fn origins() {
+	assert_eq!(OriginPolicy::origin(iri("https://example.org/context")), "https://example.org");
+	assert_eq!(OriginPolicy::origin(iri("HTTPS://Example.ORG:8443/a/b?c#d")), "https://example.org:8443");
+	assert_eq!(OriginPolicy::origin(iri("http://user@example.org:8080/")), "http://example.org:8080");
+
+	// Default ports are omitted.
+	assert_eq!(OriginPolicy::origin(iri("https://example.org:443/context")), "https://example.org");
+	assert_eq!(OriginPolicy::origin(iri("http://example.org:80/context")), "http://example.org");
+	assert_eq!(OriginPolicy::origin(iri("http://example.org:443/context")), "http://example.org:443");
+	assert_eq!(OriginPolicy::origin(iri("urn:example:context")), "urn://");
+}
+
+#[test]
+fn allow_and_deny() {
+	let mut policy = OriginPolicy::allowlist(vec!["https://W3ID.org", "https://www.w3.org"]);
+	policy.origins.insert("https://www.w3.org".to_string(), OriginRule::deny());
+	let fetched = Fetched::default();
+	let mut loader = server(fetched.clone(), 0).with(PolicyLayer::new(policy));
+
+	// Allowlisted origins are lowercased, and specific rules apply whatever the URL case.
+	assert!(task::block_on(loader.load(iri("https://w3id.org/security/v1"))).is_ok());
+	assert!(task::block_on(loader.load(iri("https://W3ID.ORG:443/security/v2"))).is_ok());
+	assert_eq!(violation(task::block_on(loader.load(iri("https://www.w3.org/ns/did/v1")))), "loading `https://www.w3.org/ns/did/v1` is denied by the origin policy");
+	assert!(task::block_on(loader.load(iri("https://example.org/context"))).is_err());
+	assert!(task::block_on(loader.load(iri("http://w3id.org/security/v1"))).is_err());
+
+	// Denied documents are not fetched.
+	assert_eq!(*fetched.lock().unwrap(), vec!["https://w3id.org/security/v1", "https://W3ID.ORG:443/security/v2"]);
+	assert!(loader.policy().rule(iri("https://other.example.org/")) == &OriginRule::deny());
+}
+
+#[test]
+fn cache_ttl() {
+	let mut policy = OriginPolicy::new();
+	policy.origins.insert("https://cached.example.org".to_string(), OriginRule { cache_ttl: Some(Duration::from_secs(3600)), ..OriginRule::allow() });
+	policy.origins.insert("https://expired.example.org".to_string(), OriginRule { cache_ttl: Some(Duration::from_millis(1)), ..OriginRule::allow() });
+
+	let fetched = Fetched::default();
+	let mut loader = server(fetched.clone(), 0).with(PolicyLayer::new(policy));
+	let count = |fetched: &Fetched| fetched.lock().unwrap().len();
+
+	for _ in 0..2 {
+		task::block_on(loader.load(iri("https://cached.example.org/context"))).unwrap();
+	}
+	assert_eq!(count(&fetched), 1);
+
+	// Documents are not cached without TTL.
+	for _ in 0..2 {
+		task::block_on(loader.load(iri("https://example.org/context"))).unwrap();
+	}
+	assert_eq!(count(&fetched), 3);
+
+	// Expired documents are fetched again.
+	task::block_on(loader.load(iri("https://expired.example.org/context"))).unwrap();
+	std::thread::sleep(Duration::from_millis(10));
+	task::block_on(loader.load(iri("https://expired.example.org/context"))).unwrap();
+	assert_eq!(count(&fetched), 5);
+
+	loader.clear();
+	task::block_on(loader.load(iri("https://cached.example.org/context"))).unwrap();
+	assert_eq!(count(&fetched), 6);
+}
+
+#[test]
+fn max_size() {
+	let policy = OriginPolicy {
+		default: OriginRule { max_size: Some(20), ..OriginRule::allow() },
+		..OriginPolicy::new()
+	};
+
+	// The document is measured as served: `{"@context":{}}` is 15 bytes, with its padding.
+	let mut loader = server(Fetched::default(), 5).with(PolicyLayer::new(policy.clone()));
+	let doc = task::block_on(loader.load(iri("https://example.org/context"))).unwrap();
+	assert_eq!(doc.content().map(str::len), Some(20));
+
+	let mut loader = server(Fetched::default(), 6).with(PolicyLayer::new(policy.clone()));
+	assert_eq!(violation(task::block_on(loader.load(iri("https://example.org/context")))), "`https://example.org/context` exceeds the maximum size of its origin (20 bytes)");
+
+	// Without raw content, the compact JSON serialization is measured.
+	let padded = json::parse(&format!(r#"{{"@context": {{"a": "{}"}}}}"#, "x".repeat(8))).unwrap();
+	let mut loader = NoLoader.with(FixturesLayer::new().fixture(iri("https://example.org/context"), padded)).with(PolicyLayer::new(policy));
+	assert!(task::block_on(loader.load(iri("https://example.org/context"))).is_err());
+}
@@ -0,0 +1,59 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::fs;
+use json_ld::util::parse_json;
+
+/// Checks that `parse_json` agrees with `json::parse`, including on the order of entries.
+fn same(source: &str) {
+	let expected = json::parse(source).unwrap();
+	let parsed = parse_json(source).unwrap();
+	assert_eq!(parsed, expected, "{}", source);
+	assert_eq!(parsed.dump(), expected.dump(), "{}", source);
+}
+
+#[test]
+fn values() {
+	for source in &[
+		"null",
+		"true",
+		"[false, 0, -1, 18446744073709551615, -9223372036854775808]",
+		"[1.5, -0.25, 1e2, 2.5E-3, 1.7976931348623157e308, 0.14285714285714285, -0.0]",
+		r#""\"\\\/\b\f\n\r\t\u00e9\ud83d\ude00""#,
+		r#""é 😀""#,
+		"[[], {}, [[1], {\"a\": [2]}]]"
+	] {
+		same(source)
+	}
+}
+
+#[test]
+fn entry_order() {
+	// More entries than small map optimizations usually keep in order.
+	let entries: Vec<String> = (0..100).rev().map(|i| format!("\"k{}\": {}", i, i)).collect();
+	same(&format!("{{{}}}", entries.join(", ")));
+
+	// The last duplicate entry wins.
+	same(r#"{"a": 1, "b": 2, "a": 3}"#);
+}
+
+#[test]
+fn documents() {
+	for dir in &["src/preset/contexts", "tests/contexts", "tests/rdf-canon"] {
+		for entry in fs::read_dir(dir).unwrap() {
+			let path = entry.unwrap().path();
+			if path.extension().map(|ext| ext == "jsonld" || ext == "json").unwrap_or(false) {
+				same(&fs::read_to_string(path).unwrap())
+			}
+		}
+	}
+}
+
+#[test]
+fn invalid() {
+	for source in &["", "{", "[1,]", "{\"a\" 1}", "nul", "\"\\x\"", "1 2"] {
+		assert!(parse_json(source).is_err(), "{}", source);
+		assert!(json::parse(source).is_err(), "{}", source)
+	}
+}
@@ -0,0 +1,78 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	ErrorCode,
+	JsonContext,
+	NoLoader,
+	expansion::{
+		self,
+		Partial
+	},
+	util::AsJson
+};
+
+fn expand(doc: &str) -> Partial<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(expansion::expand_partial(&JsonContext::new(None), &doc, None, &mut NoLoader, expansion::Options::default())).unwrap()
+}
+
+fn failures(partial: &Partial<IriBuf>) -> Vec<(Vec<&str>, ErrorCode)> {
+	partial.failures.iter().map(|(path, e)| (path.iter().map(String::as_str).collect(), e.code())).collect()
+}
+
+#[test]
+fn salvaged_entries() {
+	let partial = expand(r#"[
+		{
+			"@id": "http://example.org/alice",
+			"http://schema.org/name": "Alice",
+			"http://schema.org/knows": {"@id": "http://example.org/bob", "http://schema.org/name": {"@value": "Bob", "@language": 12}}
+		},
+		{"@id": 12, "http://schema.org/name": "Invalid"},
+		{"@id": "http://example.org/carol", "http://schema.org/name": "Carol"}
+	]"#);
+
+	assert!(!partial.is_complete());
+	assert_eq!(failures(&partial), [
+		(vec!["0", "http://schema.org/knows", "http://schema.org/name"], ErrorCode::InvalidLanguageTaggedString),
+		(vec!["1", "@id"], ErrorCode::InvalidIdValue)
+	]);
+
+	assert_eq!(partial.document.as_json(), json::parse(r#"[
+		{"@id": "http://example.org/alice", "http://schema.org/name": [{"@value": "Alice"}]},
+		{"@id": "http://example.org/carol", "http://schema.org/name": [{"@value": "Carol"}]}
+	]"#).unwrap());
+}
+
+#[test]
+fn top_level_graph() {
+	let partial = expand(r#"{
+		"@context": {"name": "http://schema.org/name", "id": "@id"},
+		"@graph": [
+			{"id": "http://example.org/alice", "name": {"@value": "Alice", "@direction": "up"}, "http://schema.org/age": 42},
+			{"id": 12},
+			{"id": "http://example.org/bob", "name": "Bob"}
+		]
+	}"#);
+
+	assert_eq!(failures(&partial), [
+		(vec!["@graph", "0", "name"], ErrorCode::InvalidBaseDirection),
+		(vec!["@graph", "1", "id"], ErrorCode::InvalidIdValue)
+	]);
+
+	assert_eq!(partial.document.as_json(), json::parse(r#"[
+		{"@id": "http://example.org/alice", "http://schema.org/age": [{"@value": 42}]},
+		{"@id": "http://example.org/bob", "http://schema.org/name": [{"@value": "Bob"}]}
+	]"#).unwrap());
+}
+
+#[test]
+fn complete() {
+	let partial = expand(r#"{"@id": "http://example.org/alice", "http://schema.org/name": "Alice"}"#);
+	assert!(partial.is_complete());
+	assert_eq!(partial.document.len(), 1);
+}
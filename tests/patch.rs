@@ -0,0 +1,212 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	Indexed,
+	JsonContext,
+	NoLoader,
+	Object,
+	Reference,
+	patch::{
+		self,
+		Error,
+		Operation
+	},
+	util::AsJson
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn iri(iri: &str) -> Reference<IriBuf> {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+/// Expanded value of a property, for a given compact value.
+fn value(value: &str) -> Indexed<Object<IriBuf>> {
+	let doc = expand(&format!(r#"{{"@id": "http://example.org/tmp", "http://schema.org/p": {}}}"#, value));
+	doc.iter().next().unwrap().as_node().unwrap().get_any(&iri("http://schema.org/p")).unwrap().clone()
+}
+
+fn assert_json(document: &ExpandedDocument<IriBuf>, expected: &str) {
+	let expected = json::parse(expected).unwrap();
+	assert_eq!(document.as_json(), expected, "{}", document.as_json().pretty(2))
+}
+
+const DOC: &str = r#"[
+	{"@id": "http://example.org/alice", "@type": "http://schema.org/Person", "http://schema.org/name": ["Alice", "Alicia"]},
+	{"http://schema.org/name": "anonymous"},
+	{"@id": "http://example.org/bob", "http://schema.org/name": "Bob"}
+]"#;
+
+const EXPANDED: &str = r#"[
+	{"@id": "http://example.org/alice", "@type": ["http://schema.org/Person"], "http://schema.org/name": [{"@value": "Alice"}, {"@value": "Alicia"}]},
+	{"http://schema.org/name": [{"@value": "anonymous"}]},
+	{"@id": "http://example.org/bob", "http://schema.org/name": [{"@value": "Bob"}]}
+]"#;
+
+#[test]
+fn operations() {
+	let mut doc = expand(DOC);
+	let name = iri("http://schema.org/name");
+	let knows = iri("http://schema.org/knows");
+
+	patch::apply(&mut doc, vec![
+		Operation::Add(iri("http://example.org/alice"), knows.clone(), value(r#"{"@id": "http://example.org/bob"}"#)),
+		Operation::Add(iri("http://example.org/alice"), knows.clone(), value(r#"{"@id": "http://example.org/bob"}"#)),
+		Operation::Remove(iri("http://example.org/alice"), name.clone(), value(r#""Alicia""#)),
+		Operation::AddType(iri("http://example.org/bob"), iri("http://schema.org/Person")),
+		Operation::Clear(iri("http://example.org/bob"), name.clone()),
+		Operation::Add(iri("http://example.org/carol"), name.clone(), value(r#""Carol""#))
+	]).unwrap();
+
+	// Duplicate values are not added, and new nodes are added at the end.
+	assert_json(&doc, r#"[
+		{
+			"@id": "http://example.org/alice",
+			"@type": ["http://schema.org/Person"],
+			"http://schema.org/name": [{"@value": "Alice"}],
+			"http://schema.org/knows": [{"@id": "http://example.org/bob"}]
+		},
+		{"http://schema.org/name": [{"@value": "anonymous"}]},
+		{"@id": "http://example.org/bob", "@type": ["http://schema.org/Person"]},
+		{"@id": "http://example.org/carol", "http://schema.org/name": [{"@value": "Carol"}]}
+	]"#);
+
+	patch::apply(&mut doc, vec![
+		Operation::RemoveType(iri("http://example.org/alice"), iri("http://schema.org/Person")),
+		Operation::RemoveNode(iri("http://example.org/bob"))
+	]).unwrap();
+
+	assert_eq!(doc.len(), 3);
+	assert_json(&doc, r#"[
+		{
+			"@id": "http://example.org/alice",
+			"http://schema.org/name": [{"@value": "Alice"}],
+			"http://schema.org/knows": [{"@id": "http://example.org/bob"}]
+		},
+		{"http://schema.org/name": [{"@value": "anonymous"}]},
+		{"@id": "http://example.org/carol", "http://schema.org/name": [{"@value": "Carol"}]}
+	]"#);
+}
+
+#[test]
+fn operation_errors() {
+	let mut doc = expand(DOC);
+	let name = iri("http://schema.org/name");
+
+	let cases = vec![
+		Operation::Remove(iri("http://example.org/alice"), name.clone(), value(r#""Bob""#)),
+		Operation::Remove(iri("http://example.org/alice"), iri("http://schema.org/knows"), value(r#""Bob""#)),
+		Operation::RemoveType(iri("http://example.org/bob"), iri("http://schema.org/Person"))
+	];
+
+	for op in cases {
+		// Previous operations are not applied.
+		match patch::apply(&mut doc, vec![Operation::Clear(iri("http://example.org/alice"), name.clone()), op]) {
+			Err(Error::ValueNotFound) => (),
+			other => panic!("unexpected result {:?}", other)
+		}
+	}
+
+	for op in [Operation::Clear(iri("http://example.org/carol"), name.clone()), Operation::RemoveNode(iri("http://example.org/carol"))] {
+		match patch::apply(&mut doc, vec![op]) {
+			Err(Error::NodeNotFound(id)) => assert_eq!(id, "http://example.org/carol"),
+			other => panic!("unexpected result {:?}", other)
+		}
+	}
+
+	assert_json(&doc, EXPANDED);
+}
+
+fn json_patch(doc: &mut ExpandedDocument<IriBuf>, patch: &str) -> Result<(), Error> {
+	task::block_on(patch::apply_json_patch(doc, &json::parse(patch).unwrap()))
+}
+
+#[test]
+fn json_patch_order() {
+	let mut doc = expand(DOC);
+	json_patch(&mut doc, r#"[
+		{"op": "add", "path": "/http:~1~1example.org~1dave", "value": {"@id": "http://example.org/dave"}},
+		{"op": "add", "path": "/http:~1~1example.org~1dave/http:~1~1schema.org~1name", "value": [{"@value": "Dave"}]},
+		{"op": "add", "path": "/http:~1~1example.org~1alice/http:~1~1schema.org~1name/1", "value": {"@value": "Ally"}},
+		{"op": "replace", "path": "/http:~1~1example.org~1bob/http:~1~1schema.org~1name/0/@value", "value": "Robert"}
+	]"#).unwrap();
+
+	// Modified nodes keep their position, and their values are kept in order.
+	assert_json(&doc, r#"[
+		{"@id": "http://example.org/alice", "@type": ["http://schema.org/Person"], "http://schema.org/name": [{"@value": "Alice"}, {"@value": "Ally"}, {"@value": "Alicia"}]},
+		{"http://schema.org/name": [{"@value": "anonymous"}]},
+		{"@id": "http://example.org/bob", "http://schema.org/name": [{"@value": "Robert"}]},
+		{"@id": "http://example.org/dave", "http://schema.org/name": [{"@value": "Dave"}]}
+	]"#);
+}
+
+#[test]
+fn json_patch_operations() {
+	let mut doc = expand(DOC);
+	json_patch(&mut doc, r#"[
+		{"op": "test", "path": "/http:~1~1example.org~1bob/http:~1~1schema.org~1name/0/@value", "value": "Bob"},
+		{"op": "copy", "from": "/http:~1~1example.org~1bob/http:~1~1schema.org~1name", "path": "/http:~1~1example.org~1alice/http:~1~1schema.org~1nickname"},
+		{"op": "move", "from": "/http:~1~1example.org~1alice/http:~1~1schema.org~1name/1", "path": "/http:~1~1example.org~1alice/http:~1~1schema.org~1name/0"},
+		{"op": "remove", "path": "/http:~1~1example.org~1bob"}
+	]"#).unwrap();
+
+	assert_json(&doc, r#"[
+		{
+			"@id": "http://example.org/alice",
+			"@type": ["http://schema.org/Person"],
+			"http://schema.org/name": [{"@value": "Alicia"}, {"@value": "Alice"}],
+			"http://schema.org/nickname": [{"@value": "Bob"}]
+		},
+		{"http://schema.org/name": [{"@value": "anonymous"}]}
+	]"#);
+
+	// Changing the identifier of a node moves it.
+	json_patch(&mut doc, r#"[
+		{"op": "replace", "path": "/http:~1~1example.org~1alice/@id", "value": "http://example.org/alicia"}
+	]"#).unwrap();
+
+	assert_eq!(doc.len(), 2);
+	assert_eq!(doc.as_json()[0]["@id"], json::JsonValue::Null);
+	assert_eq!(doc.as_json()[1]["@id"], "http://example.org/alicia");
+}
+
+#[test]
+fn json_patch_errors() {
+	let mut doc = expand(DOC);
+
+	assert!(matches!(json_patch(&mut doc, r#"{"op": "remove", "path": "/http:~1~1example.org~1bob"}"#), Err(Error::InvalidPatch)));
+	assert!(matches!(json_patch(&mut doc, r#"[{"op": "delete", "path": "/http:~1~1example.org~1bob"}]"#), Err(Error::InvalidPatch)));
+	assert!(matches!(json_patch(&mut doc, r#"[{"op": "remove", "path": "http:~1~1example.org~1bob"}]"#), Err(Error::InvalidPatch)));
+	assert!(matches!(json_patch(&mut doc, r#"[{"op": "add", "path": "/http:~1~1example.org~1bob/@id"}]"#), Err(Error::InvalidPatch)));
+	assert!(matches!(json_patch(&mut doc, r#"[{"op": "replace", "path": "", "value": []}]"#), Err(Error::InvalidPatch)));
+
+	match json_patch(&mut doc, r#"[{"op": "remove", "path": "/http:~1~1example.org~1carol"}]"#) {
+		Err(Error::PathNotFound(path)) => assert_eq!(path, "/http:~1~1example.org~1carol"),
+		other => panic!("unexpected result {:?}", other)
+	}
+
+	// Operations are applied in order, and a failure leaves the document unchanged.
+	match json_patch(&mut doc, r#"[
+		{"op": "remove", "path": "/http:~1~1example.org~1bob"},
+		{"op": "test", "path": "/http:~1~1example.org~1bob", "value": {}}
+	]"#) {
+		Err(Error::TestFailed(path)) => assert_eq!(path, "/http:~1~1example.org~1bob"),
+		other => panic!("unexpected result {:?}", other)
+	}
+
+	match json_patch(&mut doc, r#"[{"op": "replace", "path": "/http:~1~1example.org~1bob/@id", "value": 1}]"#) {
+		Err(Error::InvalidNode(e)) => assert_eq!(e.code(), json_ld::ErrorCode::InvalidIdValue),
+		other => panic!("unexpected result {:?}", other)
+	}
+
+	assert_json(&doc, EXPANDED);
+}
@@ -0,0 +1,81 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	pointer::Pointer,
+	util::AsJson
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+const DOC: &str = r#"[
+	{
+		"@id": "http://example.org/alice",
+		"http://schema.org/knows": [
+			{"@id": "http://example.org/bob", "http://schema.org/name": "Bob"},
+			{"http://schema.org/name": "Carol"}
+		],
+		"http://schema.org/steps": {"@list": ["one", "two"]}
+	},
+	{
+		"http://schema.org/name": "Anonymous"
+	},
+	{
+		"@id": "http://example.org/graph",
+		"@graph": [{"@id": "http://example.org/dave", "http://schema.org/name": "Dave"}]
+	}
+]"#;
+
+fn resolve(doc: &ExpandedDocument<IriBuf>, pointer: &str) -> Option<json::JsonValue> {
+	doc.resolve(&pointer.parse().unwrap()).map(|object| object.as_json())
+}
+
+#[test]
+fn syntax() {
+	let pointer = Pointer::new().with("http://example.org/alice").with("a~b").with_index(0);
+	assert_eq!(pointer.to_string(), "/http:~1~1example.org~1alice/a~0b/0");
+	assert_eq!("/http:~1~1example.org~1alice/a~0b/0".parse::<Pointer>().unwrap(), pointer);
+	assert_eq!(pointer.tokens(), ["http://example.org/alice", "a~b", "0"]);
+	assert!("".parse::<Pointer>().unwrap().is_empty());
+	assert!("a/b".parse::<Pointer>().is_err());
+}
+
+#[test]
+fn resolve_pointers() {
+	let doc = expand(DOC);
+
+	assert_eq!(resolve(&doc, "/http:~1~1example.org~1alice/http:~1~1schema.org~1knows/1/http:~1~1schema.org~1name/0"), Some(json::parse(r#"{"@value": "Carol"}"#).unwrap()));
+	assert_eq!(resolve(&doc, "/http:~1~1example.org~1alice/http:~1~1schema.org~1steps/0/@list/1"), Some(json::parse(r#"{"@value": "two"}"#).unwrap()));
+	assert_eq!(resolve(&doc, "/1/http:~1~1schema.org~1name/0"), Some(json::parse(r#"{"@value": "Anonymous"}"#).unwrap()));
+	assert_eq!(resolve(&doc, "/http:~1~1example.org~1graph/@graph/http:~1~1example.org~1dave/http:~1~1schema.org~1name/0"), Some(json::parse(r#"{"@value": "Dave"}"#).unwrap()));
+
+	assert_eq!(resolve(&doc, ""), None);
+	assert_eq!(resolve(&doc, "/0"), None);
+	assert_eq!(resolve(&doc, "/http:~1~1example.org~1alice/http:~1~1schema.org~1knows/2"), None);
+	assert_eq!(resolve(&doc, "/http:~1~1example.org~1alice/http:~1~1schema.org~1knows"), None);
+}
+
+#[test]
+fn locate_objects() {
+	let doc = expand(DOC);
+	let pointers = doc.pointers();
+	assert_eq!(pointers.len(), 13);
+
+	for (pointer, object) in &pointers {
+		assert!(std::ptr::eq(doc.resolve(pointer).unwrap(), *object), "{}", pointer);
+		assert_eq!(doc.pointer_of(object).as_ref(), Some(pointer));
+	}
+
+	let dave = pointers.iter().find(|(_, object)| object.id().is_some_and(|id| id.as_str() == "http://example.org/dave")).unwrap();
+	assert_eq!(dave.0.to_string(), "/http:~1~1example.org~1graph/@graph/http:~1~1example.org~1dave");
+}
@@ -0,0 +1,74 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	compaction,
+	context::{
+		Local,
+		PrecompiledContext
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"@version": 1.1,
+	"@vocab": "http://example.org/",
+	"@language": "en",
+	"@direction": "ltr",
+	"ex": "http://example.org/",
+	"blank": "_:b",
+	"name": {"@id": "ex:name", "@language": null, "@protected": true},
+	"label": {"@id": "ex:label", "@container": ["@language", "@set"], "@direction": null},
+	"knows": {"@id": "ex:knows", "@type": "@id", "@context": {"age": "ex:age"}},
+	"parent": {"@reverse": "ex:child"},
+	"data": {"@type": "@json", "@nest": "meta"},
+	"meta": "@nest",
+	"byKey": {"@id": "ex:byKey", "@container": "@index", "@index": "ex:key"},
+	"date": {"@id": "ex:date", "@type": "http://www.w3.org/2001/XMLSchema#date"}
+}"#;
+
+fn process() -> JsonContext<IriBuf> {
+	let base_url = Iri::new("http://example.org/doc").unwrap();
+	let local = json::parse(CONTEXT).unwrap();
+	task::block_on(local.process(&JsonContext::new(Some(base_url)), &mut NoLoader, Some(base_url))).unwrap()
+}
+
+#[test]
+fn context_round_trip() {
+	let context = process();
+	let bytes = context.to_bytes();
+	assert!(bytes == process().to_bytes());
+
+	let reloaded: JsonContext<IriBuf> = JsonContext::from_bytes(&bytes).unwrap();
+	assert!(reloaded == context);
+	assert!(reloaded.to_bytes() == bytes)
+}
+
+#[test]
+fn precompiled_compaction() {
+	let doc = json::parse(r#"{"@id": "http://example.org/a", "http://example.org/name": "A", "http://example.org/label": {"@value": "B", "@language": "fr"}, "http://example.org/knows": {"@id": "http://example.org/b"}}"#).unwrap();
+	let initial_context: JsonContext<IriBuf> = JsonContext::new(None);
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&initial_context, &mut NoLoader)).unwrap();
+
+	let context = process();
+	let expected = task::block_on(compaction::compact_with(&expanded, &context, &mut NoLoader, compaction::Options::default())).unwrap();
+
+	let precompiled: PrecompiledContext<IriBuf> = PrecompiledContext::from_bytes(&PrecompiledContext::new(context).to_bytes()).unwrap();
+	let compacted = task::block_on(compaction::compact_with_inverse(&expanded, precompiled.context(), precompiled.inverse(), &mut NoLoader, compaction::Options::default())).unwrap();
+	assert_eq!(compacted, expected);
+	assert_eq!(compacted["knows"], "ex:b");
+}
+
+#[test]
+fn invalid_bytes() {
+	let bytes = process().to_bytes();
+	assert!(JsonContext::<IriBuf>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+	assert!(JsonContext::<IriBuf>::from_bytes(b"not a context").is_err());
+	assert!(PrecompiledContext::<IriBuf>::from_bytes(&bytes).is_err())
+}
@@ -0,0 +1,116 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Dataset,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	RemoteDocument,
+	provenance::Provenance
+};
+
+fn id(iri: &str) -> Reference {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+fn harvest(url: &str, doc: &str) -> (RemoteDocument, ExpandedDocument<IriBuf>) {
+	let doc = RemoteDocument::new(json::parse(doc).unwrap(), Iri::new(url).unwrap());
+	let expanded = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	(doc, expanded)
+}
+
+#[test]
+fn merged_sources() {
+	let (a, expanded_a) = harvest("http://a.example/data", r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@id": "http://example.org/alice",
+		"name": "Alice",
+		"knows": {"@id": "http://example.org/bob", "name": "Bob"}
+	}"#);
+	let (b, expanded_b) = harvest("http://b.example/data", r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@id": "http://example.org/alice",
+		"name": "Alice",
+		"email": "alice@example.org"
+	}"#);
+
+	let mut provenance = Provenance::new();
+	provenance.record_remote(&a, &expanded_a);
+	let mut provenance_b = Provenance::new();
+	provenance_b.record_remote(&b, &expanded_b);
+	provenance.merge(provenance_b);
+
+	let mut dataset = Dataset::try_from(expanded_a).unwrap();
+	dataset.merge(Dataset::try_from(expanded_b).unwrap()).unwrap();
+
+	let source_a = Iri::new("http://a.example/data").unwrap();
+	let source_b = Iri::new("http://b.example/data").unwrap();
+	let alice = id("http://example.org/alice");
+
+	assert_eq!(provenance.sources(&alice, &id("http://schema.org/name")), [source_a, source_b].iter().cloned().collect());
+	assert_eq!(provenance.sources(&alice, &id("http://schema.org/email")), [source_b].iter().cloned().collect());
+	assert_eq!(provenance.sources(&alice, &id("http://schema.org/age")), HashSet::new());
+	assert_eq!(provenance.sources(&id("http://example.org/bob"), &id("http://schema.org/name")), [source_a].iter().cloned().collect());
+	assert_eq!(provenance.subject_sources(&alice), [source_a, source_b].iter().cloned().collect());
+
+	let node = dataset.get(&alice).unwrap();
+	let email = node.get_any(&id("http://schema.org/email")).unwrap();
+	let sources = provenance.value_sources(&alice, &id("http://schema.org/email"), email).unwrap();
+	assert_eq!(sources.len(), 1);
+	assert!(sources.contains(&IriBuf::from(source_b)))
+}
+
+#[test]
+fn value_sources() {
+	let (a, expanded_a) = harvest("http://a.example/data", r#"{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": "Alice"
+	}"#);
+	let (b, expanded_b) = harvest("http://b.example/data", r#"{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": "Alicia"
+	}"#);
+
+	let mut provenance = Provenance::new();
+	provenance.record_remote(&a, &expanded_a);
+	provenance.record_remote(&b, &expanded_b);
+
+	let mut dataset = Dataset::try_from(expanded_a).unwrap();
+	dataset.merge(Dataset::try_from(expanded_b).unwrap()).unwrap();
+
+	let alice = id("http://example.org/alice");
+	let name = id("http://schema.org/name");
+	for value in dataset.get(&alice).unwrap().get(&name) {
+		let expected = match value.as_str() {
+			Some("Alice") => "http://a.example/data",
+			_ => "http://b.example/data"
+		};
+
+		let sources: Vec<_> = provenance.value_sources(&alice, &name, value).unwrap().iter().map(|s| s.as_str()).collect();
+		assert_eq!(sources, vec![expected])
+	}
+}
+
+#[test]
+fn anonymous_nodes() {
+	let (a, expanded) = harvest("http://a.example/data", r#"{
+		"http://schema.org/name": "Anonymous",
+		"http://schema.org/knows": {"@id": "http://example.org/bob", "http://schema.org/name": "Bob"}
+	}"#);
+
+	let mut provenance = Provenance::new();
+	provenance.record_remote(&a, &expanded);
+	assert_eq!(provenance.subject_sources(&id("http://example.org/bob")).len(), 1);
+
+	let mut empty = Provenance::<IriBuf>::new();
+	empty.record_remote(&a, &ExpandedDocument::new());
+	assert!(empty.is_empty())
+}
@@ -0,0 +1,188 @@
+#![cfg(feature="python")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::ffi::CString;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use json_ld::python;
+
+/// Run the given Python code, with the `json_ld` module in scope.
+fn run(code: &str) {
+	pyo3::prepare_freethreaded_python();
+	Python::with_gil(|py| {
+		let globals = PyDict::new(py);
+		globals.set_item("json_ld", pyo3::wrap_pymodule!(python::json_ld)(py)).unwrap();
+		if let Err(e) = py.run(&CString::new(code).unwrap(), Some(&globals), None) {
+			e.display(py);
+			panic!("{}", e)
+		}
+	})
+}
+
+const DOC: &str = r#"
+context = {
+	"name": "http://schema.org/name",
+	"knows": {"@id": "http://schema.org/knows", "@type": "@id"},
+	"age": "http://schema.org/age"
+}
+doc = {
+	"@context": context,
+	"@id": "alice",
+	"name": "Alice",
+	"knows": "bob",
+	"age": [42, 1.5, True, None]
+}
+"#;
+
+#[test]
+fn expand() {
+	run(&format!(r#"{}
+expanded = json_ld.expand(doc, {{"base": "https://example.org/"}})
+assert expanded == [{{
+	"@id": "https://example.org/alice",
+	"http://schema.org/name": [{{"@value": "Alice"}}],
+	"http://schema.org/knows": [{{"@id": "https://example.org/bob"}}],
+	"http://schema.org/age": [{{"@value": 42}}, {{"@value": 1.5}}, {{"@value": True}}]
+}}], expanded
+assert type(expanded[0]["http://schema.org/age"][0]["@value"]) is int
+"#, DOC))
+}
+
+#[test]
+fn compact() {
+	run(&format!(r#"{}
+expanded = json_ld.expand(doc, {{"base": "https://example.org/"}})
+compacted = json_ld.compact(expanded, {{"@context": context}}, {{"base": "https://example.org/"}})
+assert compacted == {{
+	"@context": context,
+	"@id": "alice",
+	"name": "Alice",
+	"knows": "bob",
+	"age": [42, 1.5, True]
+}}, compacted
+
+compacted = json_ld.compact({{"@id": "https://example.org/a", "http://schema.org/name": "A"}}, context, {{"compactArrays": False}})
+assert compacted == {{"@context": context, "@graph": [{{"@id": "https://example.org/a", "name": ["A"]}}]}}, compacted
+"#, DOC))
+}
+
+#[test]
+fn flatten() {
+	run(r#"
+doc = {
+	"@id": "https://example.org/alice",
+	"http://schema.org/knows": {"@id": "https://example.org/bob", "http://schema.org/name": "Bob"}
+}
+flattened = json_ld.flatten(doc)
+assert flattened == [
+	{"@id": "https://example.org/alice", "http://schema.org/knows": [{"@id": "https://example.org/bob"}]},
+	{"@id": "https://example.org/bob", "http://schema.org/name": [{"@value": "Bob"}]}
+], flattened
+
+context = {"name": "http://schema.org/name", "knows": {"@id": "http://schema.org/knows", "@type": "@id"}}
+flattened = json_ld.flatten(doc, context)
+assert flattened == {
+	"@context": context,
+	"@graph": [
+		{"@id": "https://example.org/alice", "knows": "https://example.org/bob"},
+		{"@id": "https://example.org/bob", "name": "Bob"}
+	]
+}, flattened
+"#)
+}
+
+#[test]
+fn to_rdf() {
+	run(r#"
+doc = {
+	"@id": "https://example.org/alice",
+	"http://schema.org/name": {"@value": "Alice", "@language": "en"},
+	"http://schema.org/knows": {"http://schema.org/age": 42}
+}
+assert json_ld.to_rdf(doc, {"format": "application/n-quads"}) == (
+	'_:b0 <http://schema.org/age> "42"^^<http://www.w3.org/2001/XMLSchema#integer> .\n'
+	'<https://example.org/alice> <http://schema.org/knows> _:b0 .\n'
+	'<https://example.org/alice> <http://schema.org/name> "Alice"@en .\n'
+)
+
+dataset = json_ld.to_rdf(doc)
+assert list(dataset.keys()) == ["@default"]
+triples = {t["predicate"]["value"]: t for t in dataset["@default"]}
+assert len(triples) == 3
+assert triples["http://schema.org/name"] == {
+	"subject": {"type": "IRI", "value": "https://example.org/alice"},
+	"predicate": {"type": "IRI", "value": "http://schema.org/name"},
+	"object": {"type": "literal", "value": "Alice", "datatype": "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString", "language": "en"}
+}, dataset
+assert triples["http://schema.org/knows"]["object"] == {"type": "blank node", "value": "_:b0"}
+assert triples["http://schema.org/age"]["object"] == {"type": "literal", "value": "42", "datatype": "http://www.w3.org/2001/XMLSchema#integer"}
+
+try:
+	json_ld.to_rdf(doc, {"format": "text/turtle"})
+	assert False
+except json_ld.JsonLdError:
+	pass
+"#)
+}
+
+#[test]
+fn document_loader() {
+	run(r#"
+loaded = []
+def loader(url, options):
+	loaded.append(url)
+	if url == "https://example.org/context":
+		return {"document": '{"@context": {"name": "http://schema.org/name"}}'}
+	if url == "https://example.org/doc":
+		return {
+			"document": {"@context": "https://example.org/context", "@id": "alice", "name": "Alice"},
+			"documentUrl": "https://example.org/people/"
+		}
+	raise ValueError("not found")
+
+expanded = json_ld.expand("https://example.org/doc", {"documentLoader": loader})
+assert expanded == [{
+	"@id": "https://example.org/people/alice",
+	"http://schema.org/name": [{"@value": "Alice"}]
+}], expanded
+assert loaded == ["https://example.org/doc", "https://example.org/context"], loaded
+
+try:
+	json_ld.expand({"@context": "https://example.org/missing"}, {"documentLoader": loader})
+	assert False
+except json_ld.JsonLdError as e:
+	assert str(e) == "loading remote context failed", e
+"#)
+}
+
+#[test]
+fn errors() {
+	run(r#"
+try:
+	json_ld.expand({"@context": "https://example.org/context"})
+	assert False
+except json_ld.JsonLdError as e:
+	assert str(e) == "loading remote context failed", e
+
+try:
+	json_ld.expand({"@id": 1})
+	assert False
+except json_ld.JsonLdError as e:
+	assert str(e) == "invalid @id value", e
+
+try:
+	json_ld.expand({"http://schema.org/name": {1, 2}})
+	assert False
+except TypeError:
+	pass
+
+try:
+	json_ld.expand({"http://schema.org/name": float("nan")})
+	assert False
+except ValueError:
+	pass
+"#)
+}
@@ -0,0 +1,108 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::convert::TryFrom;
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Dataset,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	task::block_on(json::parse(doc).unwrap().expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn referrers_in(dataset: &Dataset, graph: Option<&str>, id: &str) -> Vec<(String, String)> {
+	let graph = graph.map(|graph| Reference::Id(IriBuf::new(graph).unwrap()));
+	let id = Reference::Id(IriBuf::new(id).unwrap());
+	let mut referrers: Vec<_> = dataset.referrers(graph.as_ref(), &id).map(|(node, prop)| (node.as_str().to_string(), prop.as_str().to_string())).collect();
+	referrers.sort();
+	referrers
+}
+
+fn referrers(dataset: &Dataset, id: &str) -> Vec<(String, String)> {
+	referrers_in(dataset, None, id)
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+	pairs.iter().map(|(node, prop)| (node.to_string(), prop.to_string())).collect()
+}
+
+#[test]
+fn incoming_links() {
+	let mut dataset = Dataset::try_from(expand(r#"{
+		"@context": {"@vocab": "http://schema.org/", "@base": "http://example.org/"},
+		"@graph": [
+			{
+				"@id": "alice",
+				"knows": [{"@id": "bob", "knows": {"@id": "carol"}}, {"@id": "carol"}],
+				"follows": {"@list": [{"@id": "carol"}]},
+				"@reverse": {"parent": {"@id": "dave"}}
+			},
+			{
+				"@id": "g",
+				"@graph": {"@id": "erin", "knows": {"@id": "carol"}}
+			},
+			{
+				"name": "anonymous",
+				"knows": {"@id": "carol"}
+			}
+		]
+	}"#)).unwrap();
+
+	assert_eq!(referrers(&dataset, "http://example.org/carol"), pairs(&[
+		("http://example.org/alice", "http://schema.org/follows"),
+		("http://example.org/alice", "http://schema.org/knows"),
+		("http://example.org/bob", "http://schema.org/knows")
+	]));
+
+	// References are scoped to their graph.
+	assert_eq!(referrers_in(&dataset, Some("http://example.org/g"), "http://example.org/carol"), pairs(&[
+		("http://example.org/erin", "http://schema.org/knows")
+	]));
+	assert!(referrers_in(&dataset, Some("http://example.org/g"), "http://example.org/alice").is_empty());
+	assert!(referrers_in(&dataset, Some("http://example.org/alice"), "http://example.org/carol").is_empty());
+	assert_eq!(referrers(&dataset, "http://example.org/alice"), pairs(&[
+		("http://example.org/dave", "http://schema.org/parent")
+	]));
+	assert!(referrers(&dataset, "http://example.org/dave").is_empty());
+
+	// The index is rebuilt after the dataset is modified.
+	for object in expand(r#"{"@id": "http://example.org/dave", "http://schema.org/knows": {"@id": "http://example.org/dave"}}"#) {
+		dataset.insert(object).unwrap()
+	}
+
+	assert_eq!(referrers(&dataset, "http://example.org/dave"), pairs(&[
+		("http://example.org/dave", "http://schema.org/knows")
+	]));
+}
+
+#[test]
+fn named_graphs() {
+	let dataset = Dataset::try_from(expand(r#"{
+		"@context": {"@vocab": "http://schema.org/", "@base": "http://example.org/"},
+		"@id": "g",
+		"@graph": {
+			"@id": "alice",
+			"@reverse": {"parent": {"@id": "bob"}},
+			"@included": {"@id": "carol", "knows": {"@id": "alice"}}
+		},
+		"knows": {"@id": "alice"}
+	}"#)).unwrap();
+
+	// The graph node itself is in the default graph.
+	assert_eq!(referrers(&dataset, "http://example.org/alice"), pairs(&[
+		("http://example.org/g", "http://schema.org/knows")
+	]));
+	assert_eq!(referrers_in(&dataset, Some("http://example.org/g"), "http://example.org/alice"), pairs(&[
+		("http://example.org/bob", "http://schema.org/parent"),
+		("http://example.org/carol", "http://schema.org/knows")
+	]));
+	assert!(referrers_in(&dataset, Some("http://example.org/g"), "http://example.org/bob").is_empty());
+}
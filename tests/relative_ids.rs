@@ -0,0 +1,106 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	expansion,
+	util::AsJson
+};
+
+fn expand(doc: &str, base: Option<&str>) -> Result<JsonValue, ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	let base_url = base.map(|base| Iri::new(base).unwrap());
+	let options = expansion::Options {
+		relative_iris: false,
+		..expansion::Options::default()
+	};
+
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand_with(base_url, &JsonContext::new(base_url), &mut NoLoader, options)).map_err(|e| e.code())?;
+	Ok(expanded.as_json())
+}
+
+#[test]
+fn absolute_and_blank_ids() {
+	let expanded = expand(r#"{
+		"@context": {"ex": "http://example.org/"},
+		"@id": "ex:a",
+		"http://example.org/p": [{"@id": "_:b"}, {"@id": "http://example.org/c"}]
+	}"#, None).unwrap();
+
+	assert_eq!(expanded[0]["@id"], "http://example.org/a");
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "_:b");
+}
+
+#[test]
+fn resolved_ids() {
+	let expanded = expand(r#"{"@id": "a", "http://example.org/p": {"@id": "../b"}}"#, Some("http://example.org/documents/doc")).unwrap();
+	assert_eq!(expanded[0]["@id"], "http://example.org/documents/a");
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "http://example.org/b");
+}
+
+#[test]
+fn relative_ids() {
+	assert_eq!(expand(r#"{"@id": "a", "http://example.org/p": "v"}"#, None), Err(ErrorCode::RelativeIri));
+	assert_eq!(expand(r#"{"http://example.org/p": {"@id": "../b"}}"#, None), Err(ErrorCode::RelativeIri));
+	assert_eq!(expand(r#"{
+		"@context": {"@base": null},
+		"@id": "a",
+		"http://example.org/p": "v"
+	}"#, Some("http://example.org/doc")), Err(ErrorCode::RelativeIri));
+
+	// Relative types are rejected as well.
+	assert_eq!(expand(r#"{"@id": "_:a", "@type": "T", "http://example.org/p": "v"}"#, None), Err(ErrorCode::RelativeIri));
+}
+
+#[test]
+fn invalid_ids() {
+	// Node identifiers that are not IRI references are rejected even with a base IRI.
+	assert_eq!(expand(r#"{"@id": "a b", "http://example.org/p": "v"}"#, Some("http://example.org/doc")), Err(ErrorCode::RelativeIri));
+	assert_eq!(expand(r#"{
+		"@context": {"p": {"@id": "http://example.org/p", "@type": "@id"}},
+		"p": "a b"
+	}"#, Some("http://example.org/doc")), Err(ErrorCode::RelativeIri));
+}
+
+#[test]
+fn safe_mode() {
+	let doc = json::parse(r#"{
+		"@context": {"p": {"@id": "http://example.org/p", "@type": "@id"}},
+		"@id": "http://example.org/a",
+		"p": "b"
+	}"#).unwrap();
+
+	let options = json_ld::preset::safe::options();
+	let result: Result<ExpandedDocument<IriBuf>, _> = task::block_on(doc.expand_with(None, &JsonContext::new(None), &mut NoLoader, options));
+	assert_eq!(result.map(|_| ()).map_err(|e| e.code()), Err(ErrorCode::RelativeIri));
+}
+
+#[test]
+fn coerced_ids() {
+	assert_eq!(expand(r#"{
+		"@context": {"p": {"@id": "http://example.org/p", "@type": "@id"}},
+		"@id": "http://example.org/a",
+		"p": "b"
+	}"#, None), Err(ErrorCode::RelativeIri));
+
+	assert_eq!(expand(r#"{
+		"@context": {"p": {"@id": "http://example.org/p", "@container": "@id"}},
+		"@id": "http://example.org/a",
+		"p": {"b": {"http://example.org/q": "v"}}
+	}"#, None), Err(ErrorCode::RelativeIri));
+
+	let expanded = expand(r#"{
+		"@context": {"@vocab": "http://example.org/", "p": {"@type": "@vocab"}},
+		"@id": "http://example.org/a",
+		"p": "b"
+	}"#, None).unwrap();
+	assert_eq!(expanded[0]["http://example.org/p"][0]["@id"], "http://example.org/b");
+}
@@ -0,0 +1,115 @@
+#![cfg(feature="replay")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use async_std::task;
+use futures::future;
+use iref::Iri;
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	FnLoader,
+	Loader,
+	hash::{
+		Algorithm,
+		Hash
+	},
+	layer::LoaderExt,
+	replay::{
+		MANIFEST,
+		Manifest,
+		RecorderLayer,
+		Replayer
+	}
+};
+
+const CONTEXT: &str = "{\n  \"@context\": {\"name\": \"http://schema.org/name\"}\n}";
+
+const URL: &str = "https://example.org/context";
+
+/// Fresh recording directory.
+fn recording(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("json-ld-replay-{}-{}", name, std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	dir
+}
+
+/// Loader serving `CONTEXT`, counting the fetches.
+fn server(fetches: Arc<Mutex<usize>>) -> impl Loader<Document = JsonValue> {
+	FnLoader::new(move |_: Iri| {
+		*fetches.lock().unwrap() += 1;
+		future::ready(Ok(CONTEXT.to_string()))
+	})
+}
+
+/// Record `CONTEXT` at `URL` in the given directory.
+fn record(dir: &PathBuf) {
+	let fetches = Arc::new(Mutex::new(0));
+	let mut loader = server(fetches.clone()).with(RecorderLayer::new(dir));
+	let doc = task::block_on(loader.load(Iri::new(URL).unwrap())).unwrap();
+	assert_eq!(doc.content(), Some(CONTEXT));
+	assert_eq!(*fetches.lock().unwrap(), 1);
+}
+
+fn replay_error(replayer: &mut Replayer, url: &str) -> ErrorCode {
+	task::block_on(replayer.load(Iri::new(url).unwrap())).map(|_| ()).unwrap_err().code()
+}
+
+#[test]
+fn round_trip() {
+	let dir = recording("round-trip");
+	record(&dir);
+
+	let manifest = Manifest::open(&dir).unwrap();
+	assert_eq!(manifest.len(), 1);
+	let entry = manifest.get(Iri::new(URL).unwrap()).unwrap();
+	assert_eq!(entry.hash, Hash::of(Algorithm::Sha256, CONTEXT.as_bytes()));
+	assert_eq!(entry.base_url.as_str(), URL);
+	assert_eq!(fs::read_to_string(dir.join(&entry.file)).unwrap(), CONTEXT);
+
+	let manifest = json::parse(&fs::read_to_string(dir.join(MANIFEST)).unwrap()).unwrap();
+	assert_eq!(manifest[URL]["integrity"], entry.hash.to_string().as_str());
+
+	let mut replayer = Replayer::open(&dir).unwrap();
+	let doc = task::block_on(replayer.load(Iri::new(URL).unwrap())).unwrap();
+	assert_eq!(doc.url().as_str(), URL);
+	assert_eq!(doc.content(), Some(CONTEXT));
+	assert_eq!(doc["@context"]["name"], "http://schema.org/name");
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn replay_miss() {
+	let dir = recording("miss");
+	record(&dir);
+
+	let mut replayer = Replayer::open(&dir).unwrap();
+	assert_eq!(replay_error(&mut replayer, "https://example.org/other"), ErrorCode::LoadingDocumentFailed);
+
+	// A directory without recording has an empty manifest.
+	let mut replayer = Replayer::open(recording("empty")).unwrap();
+	assert!(replayer.manifest().is_empty());
+	assert_eq!(replay_error(&mut replayer, URL), ErrorCode::LoadingDocumentFailed);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tampered_recording() {
+	let dir = recording("tampered");
+	record(&dir);
+
+	let file = dir.join(&Manifest::open(&dir).unwrap().get(Iri::new(URL).unwrap()).unwrap().file);
+	fs::write(&file, r#"{"@context": {"name": "http://example.org/evil"}}"#).unwrap();
+
+	let mut replayer = Replayer::open(&dir).unwrap();
+	assert_eq!(replay_error(&mut replayer, URL), ErrorCode::LoadingDocumentFailed);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
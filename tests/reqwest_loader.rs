@@ -0,0 +1,125 @@
+#![cfg(feature = "reqwest-loader")]
+
+extern crate tokio;
+extern crate iref;
+extern crate json_ld;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use iref::Iri;
+use json_ld::{
+	ErrorCode,
+	reqwest::{
+		ContentTypes,
+		Loader
+	}
+};
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+
+/// Serve a single JSON-LD document, returning the server URL and the received request head.
+fn serve() -> (String, Arc<Mutex<String>>) {
+	serve_as(Some("application/ld+json"), r#"{"@context": {"name": "http://schema.org/name"}}"#)
+}
+
+/// Serve a single document with the given content type.
+fn serve_as(content_type: Option<&str>, body: &'static str) -> (String, Arc<Mutex<String>>) {
+	let content_type = match content_type {
+		Some(ty) => format!("content-type: {}\r\n", ty),
+		None => String::new()
+	};
+
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let url = format!("http://{}/doc.jsonld", listener.local_addr().unwrap());
+	let received = Arc::new(Mutex::new(String::new()));
+	let head = received.clone();
+
+	thread::spawn(move || {
+		if let Ok((mut stream, _)) = listener.accept() {
+			let mut request = Vec::new();
+			let mut buffer = [0; 1024];
+			while !request.ends_with(b"\r\n\r\n") {
+				match stream.read(&mut buffer) {
+					Ok(0) | Err(_) => break,
+					Ok(n) => request.extend_from_slice(&buffer[..n])
+				}
+			}
+
+			*head.lock().unwrap() = String::from_utf8_lossy(&request).to_lowercase();
+			let response = format!("HTTP/1.1 200 OK\r\n{}content-length: {}\r\nconnection: close\r\n\r\n{}", content_type, body.len(), body);
+			stream.write_all(response.as_bytes()).unwrap()
+		}
+	});
+
+	(url, received)
+}
+
+#[tokio::test]
+async fn authenticated_request() {
+	let (url, received) = serve();
+	let loader = Loader::new().with_request_hook(|request: &mut reqwest::Request| {
+		request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+		let signature = HeaderValue::from_str(request.url().path()).unwrap();
+		request.headers_mut().insert("x-signature", signature);
+		Ok(())
+	});
+
+	let doc = loader.load(Iri::new(&url).unwrap()).await.unwrap();
+	assert!(doc.has_key("@context"));
+
+	let head = received.lock().unwrap().clone();
+	assert!(head.contains("authorization: bearer secret\r\n"));
+	assert!(head.contains("x-signature: /doc.jsonld\r\n"))
+}
+
+#[tokio::test]
+async fn failing_hook() {
+	let loader = Loader::new().with_request_hook(|_: &mut reqwest::Request| Err(ErrorCode::Cancelled.into()));
+	match loader.load(Iri::new("http://127.0.0.1:9/doc.jsonld").unwrap()).await {
+		Ok(_) => panic!("the request should have been cancelled"),
+		Err(e) => assert_eq!(e.code(), ErrorCode::Cancelled)
+	}
+}
+
+async fn load(content_types: ContentTypes, content_type: Option<&str>, body: &'static str) -> Result<(), ErrorCode> {
+	let (url, _) = serve_as(content_type, body);
+	let loader = Loader::new().with_content_types(content_types);
+	match loader.load(Iri::new(&url).unwrap()).await {
+		Ok(_) => Ok(()),
+		Err(e) => Err(e.code())
+	}
+}
+
+#[tokio::test]
+async fn content_types() {
+	let doc = r#"{"@context": {}}"#;
+	for &ty in &[Some("application/ld+json; profile=\"http://www.w3.org/ns/json-ld#expanded\""), Some("Application/JSON"), Some("application/activity+json")] {
+		assert_eq!(load(ContentTypes::Strict, ty, doc).await, Ok(()), "{:?}", ty)
+	}
+
+	for &ty in &[Some("text/plain; charset=utf-8"), Some("application/octet-stream"), None] {
+		assert_eq!(load(ContentTypes::Strict, ty, doc).await, Err(ErrorCode::LoadingDocumentFailed), "{:?}", ty);
+		assert_eq!(load(ContentTypes::Lenient, ty, doc).await, Ok(()), "{:?}", ty)
+	}
+
+	assert_eq!(load(ContentTypes::Lenient, Some("text/html"), doc).await, Err(ErrorCode::LoadingDocumentFailed));
+	assert_eq!(load(ContentTypes::Lenient, Some("text/plain"), "not json").await, Err(ErrorCode::LoadingDocumentFailed));
+}
+
+#[tokio::test]
+async fn max_size() {
+	let doc = r#"{"@context": {}}"#;
+	for &(max_size, loaded) in &[(doc.len(), true), (doc.len() - 1, false)] {
+		let (url, _) = serve_as(Some("application/ld+json"), doc);
+		let result = Loader::new().with_max_size(max_size).load(Iri::new(&url).unwrap()).await;
+		match result {
+			Ok(_) => assert!(loaded, "{}", max_size),
+			Err(e) => {
+				assert!(!loaded, "{}", max_size);
+				assert_eq!(e.code(), ErrorCode::LoadingDocumentFailed);
+				assert_eq!(std::error::Error::source(&e).unwrap().to_string(), format!("`{}` exceeds the maximum size ({} bytes)", url, max_size))
+			}
+		}
+	}
+}
@@ -0,0 +1,133 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Error,
+	ErrorCode,
+	ExpandedDocument,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	preset::safe,
+	util::AsJson
+};
+
+const CONTEXT_URL: &str = "https://example.org/credentials/v1";
+
+const CONTEXT: &str = r#"{
+	"@context": {
+		"@protected": true,
+		"id": "@id",
+		"type": "@type",
+		"name": "https://schema.org/name",
+		"Person": "https://schema.org/Person"
+	}
+}"#;
+
+fn expand(doc: &str, content_type: Option<&str>) -> Result<json::JsonValue, Error> {
+	let mut loader = safe::loader().with(Iri::new(CONTEXT_URL).unwrap(), json::parse(CONTEXT).unwrap());
+	let doc = json::parse(doc).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(safe::expand(&doc, content_type, &JsonContext::new(None), &mut loader))?;
+	Ok(expanded.as_json())
+}
+
+fn error(doc: &str, content_type: Option<&str>) -> ErrorCode {
+	expand(doc, content_type).map(|_| ()).unwrap_err().code()
+}
+
+#[test]
+fn valid() {
+	let expanded = expand(r#"{
+		"@context": "https://example.org/credentials/v1",
+		"id": "https://example.org/alice",
+		"type": "Person",
+		"name": "Alice"
+	}"#, Some("application/ld+json; profile=\"https://www.w3.org/ns/json-ld#expanded\"")).unwrap();
+
+	assert_eq!(expanded, json::parse(r#"[{
+		"@id": "https://example.org/alice",
+		"@type": ["https://schema.org/Person"],
+		"https://schema.org/name": [{"@value": "Alice"}]
+	}]"#).unwrap());
+}
+
+#[test]
+fn content_type() {
+	let doc = r#"{"@id": "https://example.org/alice"}"#;
+	assert!(expand(doc, Some("application/vc+json")).is_ok());
+	assert_eq!(error(doc, Some("text/html")), ErrorCode::LoadingDocumentFailed);
+	assert_eq!(error(doc, None), ErrorCode::LoadingDocumentFailed);
+}
+
+#[test]
+fn dropped_entries() {
+	let e = expand(r#"{
+		"@context": "https://example.org/credentials/v1",
+		"id": "https://example.org/alice",
+		"revoked": true
+	}"#, Some("application/json")).unwrap_err();
+
+	assert_eq!(e.code(), ErrorCode::DroppedEntry);
+	assert_eq!(e.entry_path(), ["revoked"]);
+
+	assert_eq!(error(r#"{
+		"@context": ["https://example.org/credentials/v1", {"@version": 1.1, "age": null}],
+		"age": 12
+	}"#, Some("application/json")), ErrorCode::DroppedEntry);
+
+}
+
+#[test]
+fn unknown_keywords() {
+	assert_eq!(error(r#"{
+		"@annotation": "dropped"
+	}"#, Some("application/json")), ErrorCode::UnknownKeyword);
+
+	assert_eq!(error(r#"{
+		"@context": ["https://example.org/credentials/v1", {"foo": {"@id": "@foo"}}],
+		"id": "https://example.org/alice"
+	}"#, Some("application/json")), ErrorCode::UnknownKeyword);
+}
+
+#[test]
+fn relative_iris() {
+	assert_eq!(error(r#"{
+		"@context": "https://example.org/credentials/v1",
+		"id": "alice"
+	}"#, Some("application/json")), ErrorCode::RelativeIri);
+}
+
+#[test]
+fn protected_terms() {
+	assert_eq!(error(r#"{
+		"@context": ["https://example.org/credentials/v1", {"name": "https://attacker.example/name"}],
+		"name": "Alice"
+	}"#, Some("application/json")), ErrorCode::ProtectedTermRedefinition);
+}
+
+#[test]
+fn unpinned_contexts() {
+	assert_eq!(error(r#"{
+		"@context": "https://attacker.example/context",
+		"name": "Alice"
+	}"#, Some("application/json")), ErrorCode::LoadingRemoteContextFailed);
+}
+
+#[test]
+fn overrides() {
+	let overrides = json::parse(r#"{"name": "https://schema.org/name"}"#).unwrap();
+	let context: JsonContext<IriBuf> = task::block_on(overrides.enforce(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	let mut loader = safe::loader();
+	let doc = json::parse(r#"{
+		"@context": {"@version": 1.1, "name": "https://attacker.example/name"},
+		"name": "Alice"
+	}"#).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(safe::expand(&doc, Some("application/json"), &context, &mut loader)).unwrap();
+
+	assert_eq!(expanded.as_json(), json::parse(r#"[{
+		"https://schema.org/name": [{"@value": "Alice"}]
+	}]"#).unwrap());
+}
@@ -0,0 +1,220 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Node,
+	Reference,
+	shape::{
+		Error,
+		NodeKind,
+		PropertyShape,
+		Shape
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"xsd": "http://www.w3.org/2001/XMLSchema#",
+	"knows": {"@type": "@id"}
+}"#;
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(&format!(r#"{{"@context": {}, "@graph": {}}}"#, CONTEXT, doc)).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn node(doc: &str) -> Node<IriBuf> {
+	let doc = json::parse(&format!(r#"{{"@context": {}, {}}}"#, CONTEXT, doc)).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	expanded.into_iter().next().unwrap().into_inner().into_node().unwrap()
+}
+
+fn iri(iri: &str) -> Reference<IriBuf> {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+fn shape(json: &str) -> Shape<IriBuf> {
+	Shape::from_json(&json::parse(json).unwrap()).unwrap()
+}
+
+fn violations(shape: &Shape<IriBuf>, doc: &str) -> Vec<String> {
+	shape.validate(&node(doc)).iter().map(|v| v.to_string()).collect()
+}
+
+fn datatype(datatype: &str) -> Shape<IriBuf> {
+	Shape::new().with_property(PropertyShape::new(iri("http://schema.org/p")).datatype(Iri::new(datatype).unwrap()))
+}
+
+#[test]
+fn from_json() {
+	let shape = shape(r#"{
+		"targetClass": "http://xmlns.com/foaf/0.1/Person",
+		"type": "http://xmlns.com/foaf/0.1/Agent",
+		"properties": {
+			"http://xmlns.com/foaf/0.1/name": {"minCount": 1, "maxCount": 1, "datatype": "http://www.w3.org/2001/XMLSchema#string"},
+			"http://xmlns.com/foaf/0.1/knows": {"nodeKind": "IRI", "class": "http://xmlns.com/foaf/0.1/Person"}
+		}
+	}"#);
+
+	assert!(shape.target() == Some(&iri("http://xmlns.com/foaf/0.1/Person")));
+	let properties: Vec<_> = shape.properties().iter().map(|p| p.property().as_str()).collect();
+	assert_eq!(properties, vec!["http://xmlns.com/foaf/0.1/name", "http://xmlns.com/foaf/0.1/knows"]);
+
+	// An empty shape has no constraint.
+	let shape = self::shape("{}");
+	assert!(shape.target().is_none() && shape.properties().is_empty());
+	assert!(shape.conforms(&node(r#""name": "Alice""#)));
+}
+
+#[test]
+fn invalid_shapes() {
+	let invalid = |json: &str| Shape::<IriBuf>::from_json(&json::parse(json).unwrap()).err().unwrap();
+
+	assert!(matches!(invalid("[]"), Error::InvalidShape));
+	assert!(matches!(invalid(r#"{"targetClass": 1}"#), Error::InvalidShape));
+	assert!(matches!(invalid(r#"{"properties": []}"#), Error::InvalidShape));
+	assert!(matches!(invalid(r#"{"properties": {"http://schema.org/name": 1}}"#), Error::InvalidShape));
+	assert!(matches!(invalid(r#"{"properties": {"http://schema.org/name": {"minCount": -1}}}"#), Error::InvalidShape));
+	assert!(matches!(invalid(r#"{"properties": {"http://schema.org/name": {"maxCount": "1"}}}"#), Error::InvalidShape));
+
+	match invalid(r#"{"type": ["not an IRI"]}"#) {
+		Error::InvalidIri(iri) => assert_eq!(iri, "not an IRI"),
+		e => panic!("unexpected error {}", e)
+	}
+
+	match invalid(r#"{"properties": {"http://schema.org/name": {"datatype": "string"}}}"#) {
+		Error::InvalidIri(iri) => assert_eq!(iri, "string"),
+		e => panic!("unexpected error {}", e)
+	}
+
+	match invalid(r#"{"properties": {"http://schema.org/name": {"nodeKind": "Iri"}}}"#) {
+		Error::InvalidNodeKind(kind) => assert_eq!(kind, "Iri"),
+		e => panic!("unexpected error {}", e)
+	}
+}
+
+#[test]
+fn types() {
+	let shape = shape(r#"{"type": ["http://schema.org/Person", "http://schema.org/Agent"]}"#);
+	assert!(shape.conforms(&node(r#""@type": ["Agent", "Person", "Thing"]"#)));
+	assert_eq!(violations(&shape, r#""@id": "http://example.org/alice", "@type": "Person""#), vec![
+		"node `http://example.org/alice`: missing type `http://schema.org/Agent`"
+	]);
+	assert_eq!(violations(&shape, r#""name": "Alice""#), vec![
+		"anonymous node: missing type `http://schema.org/Person`",
+		"anonymous node: missing type `http://schema.org/Agent`"
+	]);
+}
+
+#[test]
+fn cardinality() {
+	let shape = shape(r#"{"properties": {"http://schema.org/name": {"minCount": 1, "maxCount": 2}}}"#);
+	assert!(shape.conforms(&node(r#""name": "Alice""#)));
+	assert!(shape.conforms(&node(r#""name": ["Alice", "Alicia"]"#)));
+	assert_eq!(violations(&shape, r#""knows": "http://example.org/bob""#), vec!["anonymous node: not enough values for `http://schema.org/name` (0)"]);
+	assert_eq!(violations(&shape, r#""name": ["A", "B", "C"]"#), vec!["anonymous node: too many values for `http://schema.org/name` (3)"]);
+
+	let shape = Shape::new().with_property(PropertyShape::new(iri("http://schema.org/name")).required());
+	assert!(!shape.conforms(&node(r#""@type": "Person""#)));
+}
+
+#[test]
+fn datatypes() {
+	// Native values are typed as in RDF.
+	assert!(datatype("http://www.w3.org/2001/XMLSchema#string").conforms(&node(r#""p": "a""#)));
+	assert!(datatype("http://www.w3.org/2001/XMLSchema#boolean").conforms(&node(r#""p": true"#)));
+	assert!(datatype("http://www.w3.org/2001/XMLSchema#integer").conforms(&node(r#""p": 42"#)));
+	assert!(datatype("http://www.w3.org/2001/XMLSchema#double").conforms(&node(r#""p": 4.2"#)));
+	assert!(datatype("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString").conforms(&node(r#""p": {"@value": "a", "@language": "en"}"#)));
+	assert!(datatype("http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON").conforms(&node(r#""p": {"@value": {"a": 1}, "@type": "@json"}"#)));
+
+	assert!(!datatype("http://www.w3.org/2001/XMLSchema#string").conforms(&node(r#""p": {"@value": "a", "@language": "en"}"#)));
+	assert!(!datatype("http://www.w3.org/2001/XMLSchema#integer").conforms(&node(r#""p": 4.2"#)));
+	assert!(!datatype("http://www.w3.org/2001/XMLSchema#double").conforms(&node(r#""p": 42"#)));
+	assert!(!datatype("http://www.w3.org/2001/XMLSchema#string").conforms(&node(r#""p": true"#)));
+
+	// Typed literals are checked against their datatype.
+	assert!(datatype("http://www.w3.org/2001/XMLSchema#date").conforms(&node(r#""p": {"@value": "2020-01-01", "@type": "xsd:date"}"#)));
+	assert!(!datatype("http://www.w3.org/2001/XMLSchema#string").conforms(&node(r#""p": {"@value": "2020-01-01", "@type": "xsd:date"}"#)));
+
+	// Nodes are not literals.
+	assert_eq!(violations(&datatype("http://www.w3.org/2001/XMLSchema#string"), r#""p": {"@id": "http://example.org/a"}"#), vec![
+		"anonymous node: invalid datatype for a value of `http://schema.org/p`"
+	]);
+}
+
+#[test]
+fn node_kinds() {
+	let kind = |kind| Shape::new().with_property(PropertyShape::new(iri("http://schema.org/p")).kind(kind));
+	let iri_value = r#""p": {"@id": "http://example.org/a"}"#;
+	let blank = r#""p": {"@id": "_:a"}"#;
+	let anonymous = r#""p": {"name": "A"}"#;
+	let list = r#""p": {"@list": ["a"]}"#;
+	let literal = r#""p": "a""#;
+
+	assert!(kind(NodeKind::Iri).conforms(&node(iri_value)));
+	assert!(kind(NodeKind::BlankNode).conforms(&node(blank)));
+	assert!(kind(NodeKind::BlankNode).conforms(&node(anonymous)));
+	assert!(kind(NodeKind::BlankNode).conforms(&node(list)));
+	assert!(kind(NodeKind::Literal).conforms(&node(literal)));
+
+	assert!(!kind(NodeKind::Iri).conforms(&node(blank)));
+	assert!(!kind(NodeKind::Literal).conforms(&node(iri_value)));
+	assert_eq!(violations(&kind(NodeKind::BlankNode), literal), vec!["anonymous node: invalid kind for a value of `http://schema.org/p`"]);
+
+	assert_eq!(NodeKind::Iri.to_string(), "IRI");
+}
+
+#[test]
+fn classes() {
+	let shape = Shape::new()
+		.with_property(PropertyShape::new(iri("http://schema.org/knows")).class(iri("http://schema.org/Person")));
+
+	assert!(shape.conforms(&node(r#""knows": {"@type": "Person"}"#)));
+	assert_eq!(violations(&shape, r#""knows": {"@type": "Organization"}"#), vec![
+		"anonymous node: invalid type for a value of `http://schema.org/knows`"
+	]);
+
+	// Referenced nodes are not resolved when validating a single node.
+	assert!(!shape.conforms(&node(r#""knows": "http://example.org/bob""#)));
+}
+
+#[test]
+fn validate_document() {
+	let shape = shape(r#"{
+		"targetClass": "http://schema.org/Person",
+		"properties": {
+			"http://schema.org/name": {"minCount": 1},
+			"http://schema.org/knows": {"class": "http://schema.org/Person"}
+		}
+	}"#);
+
+	let doc = expand(r#"[
+		{"@id": "http://example.org/alice", "@type": "Person", "name": "Alice", "knows": ["http://example.org/bob", "http://example.org/acme"]},
+		{"@id": "http://example.org/bob", "@type": "Person", "knows": "http://example.org/unknown"},
+		{"@id": "http://example.org/acme", "@type": "Organization"}
+	]"#);
+
+	// Only targeted nodes are validated, and class constraints are checked against top-level nodes.
+	let mut violations: Vec<_> = shape.validate_document(&doc).iter().map(|v| v.to_string()).collect();
+	violations.sort();
+	assert_eq!(violations, vec![
+		"node `http://example.org/alice`: invalid type for a value of `http://schema.org/knows`",
+		"node `http://example.org/bob`: invalid type for a value of `http://schema.org/knows`",
+		"node `http://example.org/bob`: not enough values for `http://schema.org/name` (0)"
+	]);
+
+	let violations = shape.validate_document(&doc);
+	assert!(violations.iter().all(|v| v.node.is_some()));
+
+	// Without target, every top-level node is validated.
+	let shape = Shape::new().with_property(PropertyShape::new(iri("http://schema.org/name")).required());
+	assert_eq!(shape.validate_document(&doc).len(), 2);
+}
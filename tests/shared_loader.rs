@@ -0,0 +1,44 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::sync::Arc;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	SharedLoader,
+	layer::{
+		LoaderExt,
+		FixturesLayer,
+		SharedCacheLayer
+	}
+};
+
+#[test]
+fn concurrent_expansion() {
+	let context = json::parse(r#"{"@context": {"name": "http://xmlns.com/foaf/0.1/name"}}"#).unwrap();
+	let loader: Arc<dyn SharedLoader<Document = JsonValue>> = Arc::new(
+		NoLoader
+			.with(FixturesLayer::new().fixture(Iri::new("http://example.org/context.jsonld").unwrap(), context))
+			.with(SharedCacheLayer::new())
+	);
+
+	let handles: Vec<_> = (0..4).map(|i| {
+		let mut loader = loader.clone();
+		task::spawn(async move {
+			let doc = json::parse(&format!(r#"{{"@context": "http://example.org/context.jsonld", "name": "{}"}}"#, i)).unwrap();
+			let initial_context: JsonContext<IriBuf> = JsonContext::new(None);
+			let expanded: ExpandedDocument<IriBuf> = doc.expand(&initial_context, &mut loader).await.map_err(|e| e.code()).unwrap();
+			expanded.len()
+		})
+	}).collect();
+
+	for handle in handles {
+		assert_eq!(task::block_on(handle), 1)
+	}
+}
@@ -0,0 +1,48 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	JsonContext,
+	NoLoader,
+	util::{
+		snapshot,
+		assert_snapshot
+	}
+};
+
+const DOCUMENT: &str = r#"{
+	"@context": {
+		"@vocab": "http://xmlns.com/foaf/0.1/",
+		"knows": { "@type": "@id", "@container": "@set" },
+		"nick": { "@container": "@list" }
+	},
+	"@id": "http://example.org/alice",
+	"@type": ["Person", "Agent"],
+	"name": [{ "@value": "Alice", "@language": "en" }, "Alicia"],
+	"nick": ["al", "ali"],
+	"knows": ["http://example.org/carol", "http://example.org/bob"],
+	"@reverse": { "knows": { "@id": "http://example.org/dave" } }
+}"#;
+
+#[test]
+fn expanded_document() {
+	let doc = json::parse(DOCUMENT).unwrap();
+	let context: JsonContext<IriBuf> = JsonContext::new(None);
+	let expanded = task::block_on(doc.expand(&context, &mut NoLoader)).unwrap();
+	assert_snapshot(&expanded, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/expanded_document.json"))
+}
+
+#[test]
+fn deterministic() {
+	let doc = json::parse(DOCUMENT).unwrap();
+	let context: JsonContext<IriBuf> = JsonContext::new(None);
+	let expected = snapshot(&task::block_on(doc.expand(&context, &mut NoLoader)).unwrap());
+	for _ in 0..16 {
+		let expanded = task::block_on(doc.expand(&context, &mut NoLoader)).unwrap();
+		assert_eq!(snapshot(&expanded), expected)
+	}
+}
@@ -0,0 +1,48 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::path::Path;
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	build,
+	context::{
+		Local,
+		PrecompiledContext
+	},
+	util::AsJson
+};
+
+const CONTEXT: &str = "tests/contexts/example.jsonld";
+
+#[test]
+fn precompiled_file() {
+	let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+	let target = build::precompile_context_into(CONTEXT, &mut NoLoader, out_dir).unwrap();
+	assert_eq!(target, out_dir.join("tests/contexts/example.jsonld.jldp"));
+
+	let precompiled: PrecompiledContext<IriBuf> = PrecompiledContext::from_bytes(&std::fs::read(&target).unwrap()).unwrap();
+	let local = json::parse(&std::fs::read_to_string(CONTEXT).unwrap()).unwrap();
+	let processed: JsonContext<IriBuf> = task::block_on(local["@context"].process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	assert!(precompiled.context().to_bytes() == processed.to_bytes());
+
+	let doc = json::parse(r#"{"id": "http://example.org/alice", "name": "Alice", "knows": "http://example.org/bob"}"#).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(precompiled.context(), &mut NoLoader)).unwrap();
+	assert_eq!(expanded.as_json(), json::parse(r#"[{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": [{"@value": "Alice", "@language": "en"}],
+		"http://schema.org/knows": [{"@id": "http://example.org/bob"}]
+	}]"#).unwrap());
+}
+
+#[test]
+fn invalid_files() {
+	let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+	assert!(matches!(build::precompile_context_into("tests/contexts/missing.jsonld", &mut NoLoader, out_dir), Err(build::Error::Io(_))));
+	assert!(matches!(build::precompile_context_into("README.md", &mut NoLoader, out_dir), Err(build::Error::InvalidJson(_))));
+}
@@ -0,0 +1,38 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader
+};
+
+#[test]
+fn document_stats() {
+	let doc = json::parse(r#"{
+		"@context": {"@vocab": "http://example.org/"},
+		"@id": "http://example.org/a",
+		"@type": ["Person", "Agent"],
+		"name": [{"@value": "A", "@language": "en"}, {"@value": "B", "@language": "fr"}, "C"],
+		"knows": {"@type": "Person", "tags": {"@list": [1, 2]}}
+	}"#).unwrap();
+
+	let initial_context: JsonContext<IriBuf> = JsonContext::new(None);
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&initial_context, &mut NoLoader)).unwrap();
+	let stats = expanded.stats();
+
+	assert_eq!(stats.nodes, 2);
+	assert_eq!(stats.blank_nodes, 1);
+	assert_eq!(stats.values, 5);
+	assert_eq!(stats.lists, 1);
+	assert_eq!(stats.distinct_types(), 2);
+	assert_eq!(stats.types["http://example.org/Person"], 2);
+	assert_eq!(stats.distinct_properties(), 3);
+	assert_eq!(stats.properties["http://example.org/name"], 3);
+	assert_eq!(stats.languages["en"], 1);
+	assert_eq!(stats.max_depth, 4);
+}
@@ -0,0 +1,161 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use futures::stream::StreamExt;
+use iref::{Iri, IriBuf};
+use json::JsonValue;
+use json_ld::{
+	ErrorCode,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	stream::{
+		Error,
+		Expander,
+		Format,
+		Records,
+		Writer
+	},
+	util::AsJson
+};
+
+fn records(input: &str, format: Format) -> Vec<Result<JsonValue, String>> {
+	Records::new(input.as_bytes(), format).map(|r| r.map_err(|e| e.to_string())).collect()
+}
+
+fn ok(json: &str) -> Result<JsonValue, String> {
+	Ok(json::parse(json).unwrap())
+}
+
+fn expander(shared_context: Option<&str>) -> Expander<JsonContext<IriBuf>> {
+	let shared_context = shared_context.map(|c| json::parse(c).unwrap());
+	task::block_on(Expander::new(&JsonContext::new(None), shared_context.as_ref(), Some(Iri::new("https://example.org/").unwrap()), &mut NoLoader, Default::default())).ok().unwrap()
+}
+
+fn expand_all(expander: &Expander<JsonContext<IriBuf>>, input: &str, format: Format) -> Vec<Result<ExpandedDocument<IriBuf>, Error>> {
+	let mut loader = NoLoader;
+	task::block_on(expander.expand_records(Records::new(input.as_bytes(), format), &mut loader).collect())
+}
+
+#[test]
+fn lines() {
+	// Blank lines are ignored, and the last line may not be terminated.
+	assert_eq!(records("{\"a\": 1}\n\n  \r\n[2]\n\"3\"", Format::Lines), vec![ok(r#"{"a": 1}"#), ok("[2]"), ok(r#""3""#)]);
+	assert_eq!(records("{\"a\": 1}\r\n{\"b\": 2}\r\n", Format::Lines), vec![ok(r#"{"a": 1}"#), ok(r#"{"b": 2}"#)]);
+	assert!(records("", Format::Lines).is_empty());
+	assert!(records("\n \n\t\n", Format::Lines).is_empty());
+}
+
+#[test]
+fn sequences() {
+	// Records are framed by `RS`, whatever their layout, and anything before the first `RS` is skipped.
+	let input = "garbage\x1e{\"a\": 1}\n\x1e{\n\"b\": 2\n}\n\x1e\n\x1e[3]";
+	assert_eq!(records(input, Format::Sequence), vec![ok(r#"{"a": 1}"#), ok(r#"{"b": 2}"#), ok("[3]")]);
+	assert!(records("", Format::Sequence).is_empty());
+	assert!(records("{\"a\": 1}\n", Format::Sequence).is_empty());
+
+	// Line feeds do not separate records.
+	assert_eq!(records("\x1e{\"a\": 1}\n{\"b\": 2}\n", Format::Sequence), vec![Err("record 1 is not valid JSON".to_string())]);
+}
+
+#[test]
+fn recovery() {
+	// Invalid records are reported with their position, and the following records are still read.
+	let lines = "{\"a\": 1}\n\n{\"b\": \n[2]\n\u{fffd}\n";
+	assert_eq!(records(lines, Format::Lines), vec![
+		ok(r#"{"a": 1}"#),
+		Err("record 3 is not valid JSON".to_string()),
+		ok("[2]"),
+		Err("record 5 is not valid JSON".to_string())
+	]);
+
+	let sequence = "\x1e{\"a\": 1}\n\x1e{\"b\"\n\x1e[2]\n";
+	assert_eq!(records(sequence, Format::Sequence), vec![ok(r#"{"a": 1}"#), Err("record 2 is not valid JSON".to_string()), ok("[2]")]);
+
+	// Invalid UTF-8.
+	let mut records = Records::new(&b"\xff\xfe\n{}\n"[..], Format::Lines);
+	assert!(matches!(records.next(), Some(Err(Error::InvalidJson(1)))));
+	assert!(matches!(records.next(), Some(Ok(_))));
+	assert!(records.next().is_none());
+}
+
+#[test]
+fn position() {
+	// With lines, the position is the line number.
+	let mut records = Records::new(&b"\n{}\n\n\n[]\n"[..], Format::Lines);
+	assert_eq!(records.position(), 0);
+	records.next().unwrap().unwrap();
+	assert_eq!(records.position(), 2);
+	records.next().unwrap().unwrap();
+	assert_eq!(records.position(), 5);
+	assert!(records.next().is_none());
+	assert_eq!(records.position(), 5);
+
+	let mut records = Records::new(&b"\x1e{}\n\x1e\n\x1e[]\n"[..], Format::Sequence);
+	records.next().unwrap().unwrap();
+	assert_eq!(records.position(), 1);
+	records.next().unwrap().unwrap();
+	assert_eq!(records.position(), 3);
+}
+
+#[test]
+fn expansion() {
+	let expander = expander(Some(r#"{"@vocab": "http://schema.org/"}"#));
+	assert_eq!(expander.base_url().unwrap().as_str(), "https://example.org/");
+
+	// Records are expanded with the shared context, and may define their own context.
+	let input = "{\"@id\": \"http://example.org/alice\", \"name\": \"Alice\"}\n{\"@id\": 1}\nnot json\n{\"@context\": {\"@vocab\": \"http://xmlns.com/foaf/0.1/\"}, \"name\": \"Bob\"}\n";
+	let results = expand_all(&expander, input, Format::Lines);
+	assert_eq!(results.len(), 4);
+
+	let alice = results[0].as_ref().ok().unwrap().as_json();
+	assert_eq!(alice[0]["@id"], "http://example.org/alice");
+	assert_eq!(alice[0]["http://schema.org/name"][0]["@value"], "Alice");
+
+	match &results[1] {
+		Err(Error::Expansion(2, e)) => assert_eq!(e.code(), ErrorCode::InvalidIdValue),
+		_ => panic!("expected an expansion error")
+	}
+
+	assert!(matches!(results[2], Err(Error::InvalidJson(3))));
+	assert_eq!(results[3].as_ref().ok().unwrap().as_json()[0]["http://xmlns.com/foaf/0.1/name"][0]["@value"], "Bob");
+
+	// Invalid shared contexts are reported at position 0.
+	let shared_context = json::parse(r#"{"@vocab": 1}"#).unwrap();
+	match task::block_on(Expander::new(&JsonContext::<IriBuf>::new(None), Some(&shared_context), None, &mut NoLoader, Default::default())) {
+		Err(e @ Error::Expansion(0, _)) => assert_eq!(e.to_string(), "invalid shared context: invalid vocab mapping"),
+		_ => panic!("expected a shared context error")
+	}
+}
+
+#[test]
+fn round_trip() {
+	let expander = expander(Some(r#"{"@vocab": "http://schema.org/"}"#));
+	let input = "{\"@id\": \"http://example.org/alice\", \"name\": \"Alice\\nAlicia\"}\n\n{\"@id\": \"http://example.org/bob\", \"knows\": {\"@id\": \"http://example.org/alice\"}}\n";
+
+	for &format in &[Format::Lines, Format::Sequence] {
+		let documents: Vec<_> = expand_all(&expander, input, Format::Lines).into_iter().map(|doc| doc.ok().unwrap()).collect();
+
+		let mut writer = Writer::new(Vec::new(), format);
+		for doc in &documents {
+			writer.write(doc).unwrap();
+		}
+		writer.flush().unwrap();
+		let output = String::from_utf8(writer.into_inner()).unwrap();
+
+		// Each record is written on a single line.
+		assert_eq!(output.lines().count(), 2);
+		assert_eq!(output.starts_with('\x1e'), format == Format::Sequence);
+
+		// Expanded records are expanded to themselves.
+		let records: Vec<_> = Records::new(output.as_bytes(), format).map(|r| r.unwrap()).collect();
+		let expected: Vec<_> = documents.iter().map(|doc| doc.as_json()).collect();
+		assert_eq!(records, expected);
+
+		let expanded = expand_all(&self::expander(None), &output, format);
+		let expanded: Vec<_> = expanded.into_iter().map(|doc| doc.ok().unwrap().as_json()).collect();
+		assert_eq!(expanded, expected);
+	}
+}
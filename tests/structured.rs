@@ -0,0 +1,110 @@
+#![cfg(feature = "structured")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Node,
+	Object,
+	structured::{
+		GeoCoordinates,
+		PostalAddress
+	},
+	vocab::schema
+};
+
+fn expand(doc: &str) -> Node {
+	let doc = json::parse(doc).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	match expanded.objects()[0].inner() {
+		Object::Node(node) => (**node).clone(),
+		_ => panic!("not a node")
+	}
+}
+
+#[test]
+fn schema_geo_coordinates() {
+	let place = expand(r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@type": "Place",
+		"name": "Eiffel Tower",
+		"geo": {"@type": "GeoCoordinates", "latitude": "48.8584", "longitude": 2.2945, "elevation": 330}
+	}"#);
+
+	assert_eq!(place.as_geo_coordinates(), None);
+	assert_eq!(place.geo(), Some(GeoCoordinates { latitude: 48.8584, longitude: 2.2945, elevation: Some(330.0) }));
+}
+
+#[test]
+fn geojson_point() {
+	let feature = expand(r#"{
+		"@context": {
+			"@vocab": "https://purl.org/geojson/vocab#",
+			"coordinates": {"@container": "@list"}
+		},
+		"@type": "Feature",
+		"geometry": {"@type": "Point", "coordinates": [2.2945, 48.8584]}
+	}"#);
+
+	assert_eq!(feature.geo(), Some(GeoCoordinates { latitude: 48.8584, longitude: 2.2945, elevation: None }));
+
+	let invalid = expand(r#"{
+		"@context": {"@vocab": "https://purl.org/geojson/vocab#", "coordinates": {"@container": "@list"}},
+		"@type": "Point",
+		"coordinates": [2.2945]
+	}"#);
+
+	assert_eq!(invalid.as_geo_coordinates(), None);
+}
+
+#[test]
+fn postal_address() {
+	let person = expand(r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@type": "Person",
+		"address": {
+			"@type": "PostalAddress",
+			"streetAddress": "1600 Amphitheatre Pkwy",
+			"addressLocality": "Mountain View",
+			"addressRegion": "CA",
+			"postalCode": "94043",
+			"addressCountry": {"@type": "Country", "name": "US"}
+		}
+	}"#);
+
+	let address = person.get_any(schema::ADDRESS).and_then(|a| a.as_node()?.as_postal_address()).unwrap();
+	assert_eq!(address, PostalAddress {
+		street_address: Some("1600 Amphitheatre Pkwy".to_string()),
+		post_office_box_number: None,
+		address_locality: Some("Mountain View".to_string()),
+		address_region: Some("CA".to_string()),
+		postal_code: Some("94043".to_string()),
+		address_country: Some("US".to_string())
+	});
+
+	assert_eq!(person.as_postal_address(), None);
+}
+
+#[test]
+fn quantitative_value() {
+	let value = expand(r#"{
+		"@context": {"@vocab": "http://schema.org/"},
+		"@type": "QuantitativeValue",
+		"minValue": 10,
+		"maxValue": "20.5",
+		"unitCode": "KGM"
+	}"#).as_quantitative_value().unwrap();
+
+	assert_eq!(value.value, None);
+	assert_eq!(value.min_value, Some(10.0));
+	assert_eq!(value.max_value, Some(20.5));
+	assert_eq!(value.unit_code.as_deref(), Some("KGM"));
+	assert_eq!(value.unit_text, None);
+}
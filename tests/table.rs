@@ -0,0 +1,149 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	Reference,
+	path::Path,
+	table::{
+		Cell,
+		Projection,
+		Table
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"xsd": "http://www.w3.org/2001/XMLSchema#",
+	"age": {"@type": "xsd:integer"},
+	"tags": {"@container": "@list"}
+}"#;
+
+const DOCUMENT: &str = r#"{
+	"@context": {
+		"@vocab": "http://schema.org/",
+		"xsd": "http://www.w3.org/2001/XMLSchema#",
+		"age": {"@type": "xsd:integer"},
+		"tags": {"@container": "@list"}
+	},
+	"@graph": [
+		{
+			"@id": "http://example.org/alice",
+			"@type": "Person",
+			"name": "Alice",
+			"age": "42",
+			"worksFor": {"@id": "http://example.org/acme"},
+			"tags": ["a", "b"]
+		},
+		{
+			"@id": "http://example.org/acme",
+			"@type": "Organization",
+			"name": "ACME, \"Inc\"",
+			"employee": {
+				"@id": "http://example.org/bob",
+				"@type": "Person",
+				"name": ["Bob", "Robert"],
+				"healthy": true
+			}
+		}
+	]
+}"#;
+
+fn process(context: &str) -> JsonContext<IriBuf> {
+	let context = json::parse(context).unwrap();
+	task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap()
+}
+
+fn expand() -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(DOCUMENT).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+/// Row of the given node, whose identifier is in the first column.
+fn row<'a>(table: &'a Table, id: &str) -> &'a [Cell] {
+	table.rows().iter().find(|row| row[0] == Cell::Id(id.to_string())).unwrap()
+}
+
+fn schema(name: &str) -> Reference {
+	Reference::Id(IriBuf::new(&format!("http://schema.org/{}", name)).unwrap())
+}
+
+#[test]
+fn columns() {
+	let context = process(CONTEXT);
+	let projection = Projection::new()
+		.with_type(schema("Person"))
+		.column("id", Path::new())
+		.column("name", Path::new().prop(schema("name")))
+		.column("age", Path::parse("age", &context).unwrap())
+		.column("employer", Path::parse("worksFor/name", &context).unwrap())
+		.column("healthy", Path::parse("healthy", &context).unwrap())
+		.column("tags", Path::parse("tags", &context).unwrap());
+
+	let table = projection.project(&expand());
+	assert_eq!(table.columns(), ["id", "name", "age", "employer", "healthy", "tags"]);
+	assert_eq!(table.len(), 2);
+
+	assert_eq!(row(&table, "http://example.org/alice"), [
+		Cell::Id("http://example.org/alice".to_string()),
+		Cell::String("Alice".to_string()),
+		Cell::Integer(42),
+		Cell::String("ACME, \"Inc\"".to_string()),
+		Cell::Null,
+		Cell::Many(vec![Cell::String("a".to_string()), Cell::String("b".to_string())])
+	]);
+
+	let bob = row(&table, "http://example.org/bob");
+	match &bob[1] {
+		Cell::Many(names) => assert_eq!(names.len(), 2),
+		cell => panic!("unexpected cell {:?}", cell)
+	}
+	assert_eq!(bob[4], Cell::Boolean(true));
+
+	let csv = Projection::new().with_type(schema("Person"))
+		.column("name", Path::new().prop(schema("name")))
+		.column("employer", Path::parse("worksFor/name", &context).unwrap())
+		.project(&expand())
+		.to_csv();
+	let lines: Vec<_> = csv.split("\r\n").collect();
+	assert_eq!(lines[0], "name,employer");
+	assert!(lines.contains(&"Alice,\"ACME, \"\"Inc\"\"\""));
+	assert!(lines.contains(&"Bob; Robert,") || lines.contains(&"Robert; Bob,"));
+}
+
+#[test]
+fn top_level_nodes() {
+	let context = process(CONTEXT);
+	let table = Projection::new().column("name", Path::parse("name", &context).unwrap()).project(&expand());
+	let mut names: Vec<_> = table.rows().iter().map(|row| row[0].to_string()).collect();
+	names.sort();
+	assert_eq!(names, ["ACME, \"Inc\"", "Alice"]);
+}
+
+#[test]
+fn frame() {
+	let context = process(CONTEXT);
+	let frame = json::parse(r#"{
+		"@id": {},
+		"@type": "Person",
+		"name": {},
+		"worksFor": {"name": {}, "@explicit": true}
+	}"#).unwrap();
+
+	let projection = Projection::from_frame(&frame, &context).unwrap();
+	assert_eq!(projection.types(), [schema("Person")]);
+
+	let table = projection.project(&expand());
+	assert_eq!(table.columns(), ["@id", "name", "worksFor.name"]);
+	assert_eq!(row(&table, "http://example.org/alice")[2], Cell::String("ACME, \"Inc\"".to_string()));
+	assert!(row(&table, "http://example.org/bob")[2].is_null());
+
+	assert!(Projection::<IriBuf>::from_frame(&json::parse(r#"{"@type": "@id"}"#).unwrap(), &context).is_err())
+}
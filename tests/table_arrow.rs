@@ -0,0 +1,129 @@
+#![cfg(feature="arrow")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use arrow_array::{
+	Array,
+	cast::AsArray,
+	types::{Float64Type, Int64Type}
+};
+use arrow_schema::{DataType, Field};
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	path::Path,
+	table::Projection
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"knows": {"@type": "@id"},
+	"tags": {"@container": "@list"}
+}"#;
+
+const DOCUMENT: &str = r#"[
+	{
+		"@id": "http://example.org/alice",
+		"name": "Alice",
+		"age": 42,
+		"score": 1.5,
+		"healthy": true,
+		"nick": "Ally",
+		"tags": ["a", "b"],
+		"knows": "http://example.org/bob",
+		"mixed": true
+	},
+	{
+		"@id": "http://example.org/bob",
+		"name": "Bob",
+		"age": 7,
+		"score": 2,
+		"nick": ["Bobby", "Rob"],
+		"tags": [],
+		"mixed": "yes"
+	}
+]"#;
+
+fn expand() -> ExpandedDocument<IriBuf> {
+	let doc = json::parse(&format!(r#"{{"@context": {}, "@graph": {}}}"#, CONTEXT, DOCUMENT)).unwrap();
+	task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+fn projection() -> Projection<IriBuf> {
+	let context = json::parse(CONTEXT).unwrap();
+	let context: JsonContext<IriBuf> = task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	["name", "age", "score", "healthy", "nick", "tags", "knows", "mixed", "missing"].iter().fold(Projection::new().column("@id", Path::new()), |projection, name| {
+		projection.column(*name, Path::parse(name, &context).unwrap())
+	})
+}
+
+fn list(item_type: DataType) -> DataType {
+	DataType::List(std::sync::Arc::new(Field::new_list_field(item_type, true)))
+}
+
+#[test]
+fn schema() {
+	let batch = projection().project(&expand()).to_record_batch().unwrap();
+	let types: Vec<_> = batch.schema().fields().iter().map(|field| (field.name().clone(), field.data_type().clone(), field.is_nullable())).collect();
+	let expected = vec![
+		("@id", DataType::Utf8),
+		("name", DataType::Utf8),
+		("age", DataType::Int64),
+		("score", DataType::Float64),
+		("healthy", DataType::Boolean),
+		("nick", list(DataType::Utf8)),
+		("tags", list(DataType::Utf8)),
+		("knows", DataType::Utf8),
+		("mixed", DataType::Utf8),
+		("missing", DataType::Utf8)
+	];
+
+	assert_eq!(types, expected.into_iter().map(|(name, ty)| (name.to_string(), ty, true)).collect::<Vec<_>>());
+	assert_eq!(batch.num_rows(), 2);
+}
+
+#[test]
+fn values() {
+	let batch = projection().project(&expand()).to_record_batch().unwrap();
+	let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+
+	assert_eq!(column("@id").as_string::<i32>().value(1), "http://example.org/bob");
+	assert_eq!(column("age").as_primitive::<Int64Type>().values().to_vec(), vec![42, 7]);
+	assert_eq!(column("score").as_primitive::<Float64Type>().values().to_vec(), vec![1.5, 2.0]);
+	assert_eq!(column("knows").as_string::<i32>().value(0), "http://example.org/bob");
+	assert_eq!(column("mixed").as_string::<i32>().iter().collect::<Vec<_>>(), vec![Some("true"), Some("yes")]);
+
+	// Missing values are nulls.
+	let healthy = column("healthy");
+	assert!(healthy.as_boolean().value(0) && healthy.is_null(1));
+	assert!(column("knows").is_null(1));
+	assert_eq!(column("missing").null_count(), 2);
+
+	// Single values are lists of one item.
+	let nick = column("nick");
+	let nick = nick.as_list::<i32>();
+	let items = |i: usize| nick.value(i).as_string::<i32>().iter().map(|s| s.unwrap().to_string()).collect::<Vec<_>>();
+	assert_eq!(items(0), vec!["Ally"]);
+	assert_eq!(items(1), vec!["Bobby", "Rob"]);
+
+	// Lists keep their order, and empty lists are not null.
+	let tags = column("tags");
+	let tags = tags.as_list::<i32>();
+	assert_eq!(tags.value(0).as_string::<i32>().iter().collect::<Vec<_>>(), vec![Some("a"), Some("b")]);
+	assert_eq!(tags.value_length(1), 0);
+	assert!(tags.is_valid(1));
+}
+
+#[test]
+fn empty() {
+	let batch = projection().project(&ExpandedDocument::new()).to_record_batch().unwrap();
+	assert_eq!(batch.num_rows(), 0);
+	assert_eq!(batch.num_columns(), 10);
+}
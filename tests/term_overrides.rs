@@ -0,0 +1,64 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	util::AsJson
+};
+
+const OVERRIDES: &str = r#"{
+	"id": "@id",
+	"name": "http://schema.org/name"
+}"#;
+
+fn expand(doc: &str) -> json::JsonValue {
+	let overrides = json::parse(OVERRIDES).unwrap();
+	let context: JsonContext<IriBuf> = task::block_on(overrides.enforce(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	let doc = json::parse(doc).unwrap();
+	task::block_on(doc.expand(&context, &mut NoLoader)).unwrap().as_json()
+}
+
+#[test]
+fn redefinitions_are_ignored() {
+	let expanded = expand(r#"{
+		"@context": {
+			"id": "http://attacker.example/id",
+			"name": {"@id": "http://attacker.example/name", "@type": "@id"},
+			"description": "http://schema.org/description"
+		},
+		"id": "http://example.org/alice",
+		"name": "Alice",
+		"description": "Someone"
+	}"#);
+
+	assert_eq!(expanded, json::parse(r#"[{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": [{"@value": "Alice"}],
+		"http://schema.org/description": [{"@value": "Someone"}]
+	}]"#).unwrap());
+}
+
+#[test]
+fn nullification_keeps_overrides() {
+	let expanded = expand(r#"{
+		"@context": [null, {"@vocab": "http://attacker.example/"}],
+		"id": "http://example.org/alice",
+		"name": "Alice",
+		"knows": {
+			"@context": {"name": "http://attacker.example/name"},
+			"name": "Bob"
+		}
+	}"#);
+
+	assert_eq!(expanded, json::parse(r#"[{
+		"@id": "http://example.org/alice",
+		"http://schema.org/name": [{"@value": "Alice"}],
+		"http://attacker.example/knows": [{"http://schema.org/name": [{"@value": "Bob"}]}]
+	}]"#).unwrap());
+}
@@ -0,0 +1,78 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	JsonContext,
+	NoLoader,
+	ExpandedDocument,
+	context::Local,
+	layer::{
+		LoaderExt,
+		CacheLayer
+	},
+	usage::{
+		TermUse,
+		TermUsage,
+		UsageRecorder
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"schema": "http://schema.org/",
+	"name": "http://schema.org/name",
+	"knows": {"@id": "http://schema.org/knows", "@type": "@id"},
+	"Person": "http://schema.org/Person",
+	"email": "http://schema.org/email"
+}"#;
+
+#[test]
+fn corpus_usage() {
+	let context: JsonContext<IriBuf> = task::block_on(json::parse(CONTEXT).unwrap().process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+	let mut loader = UsageRecorder::new(NoLoader);
+
+	let corpus = [
+		r#"{"@type": "Person", "name": "Alice", "knows": "http://example.org/bob", "schema:birthDate": "1990-01-01"}"#,
+		r#"{"@type": ["Person", "Organisation"], "name": "Bob", "nmae": "Bobby", "http://example.org/p": "v"}"#
+	];
+
+	for doc in &corpus {
+		let _: ExpandedDocument<IriBuf> = task::block_on(json::parse(doc).unwrap().expand_with(None, &context, &mut loader, Default::default())).unwrap();
+	}
+
+	let usage = loader.usage();
+	assert_eq!(usage.count("name"), 2);
+	assert_eq!(usage.count("Person"), 2);
+	assert_eq!(usage.count("knows"), 1);
+	assert_eq!(usage.count("schema"), 1);
+	assert_eq!(usage.terms(), vec![("Person", 2), ("name", 2), ("knows", 1), ("schema", 1)]);
+	assert_eq!(usage.vocab_terms(), vec![("Organisation", 1), ("nmae", 1)]);
+	assert!(usage.undefined_terms().is_empty());
+	assert_eq!(usage.unused(&context), vec!["email".to_string()]);
+
+	let (_, mut usage) = loader.into_parts();
+	let copy = usage.clone();
+	usage.merge(&copy);
+	assert_eq!(usage.count("name"), 4);
+}
+
+#[test]
+fn undefined_terms() {
+	let mut loader = UsageRecorder::new(NoLoader).with(CacheLayer);
+	let doc = json::parse(r#"{"@context": {"name": "http://schema.org/name"}, "name": "Alice", "age": 42}"#).unwrap();
+	let _: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut loader)).unwrap();
+
+	// Reports go through the layers of the loader.
+	let usage = loader.inner().usage();
+	assert_eq!(usage.terms(), vec![("name", 1)]);
+	assert_eq!(usage.undefined_terms(), vec![("age", 1)]);
+
+	let mut usage = TermUsage::new();
+	assert!(usage.is_empty());
+	usage.record("name", TermUse::Prefix);
+	assert_eq!(usage.count("name"), 1);
+}
@@ -0,0 +1,142 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	generator,
+	rdf::{
+		self,
+		RdfDirection
+	}
+};
+
+fn nquads(doc: &str, options: rdf::Options) -> Vec<String> {
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(json::parse(doc).unwrap().expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	rdf::to_rdf(&expanded, generator::Blank::new(), options).unwrap().into_iter().map(|quad| quad.to_string()).collect()
+}
+
+fn nquads_default(doc: &str) -> Vec<String> {
+	nquads(doc, rdf::Options::default())
+}
+
+#[test]
+fn relative_ids() {
+	// Without base IRI, relative IRIs are not well-formed and their triples are dropped.
+	assert_eq!(nquads_default(r#"{
+		"@id": "alice",
+		"http://schema.org/knows": {"@id": "http://example.org/bob"},
+		"http://schema.org/name": "Alice"
+	}"#), Vec::<String>::new());
+
+	assert_eq!(nquads_default(r#"{
+		"@id": "http://example.org/alice",
+		"@type": ["http://schema.org/Person", "Agent"],
+		"http://schema.org/knows": [{"@id": "bob"}, {"@id": "http://example.org/carol"}]
+	}"#), vec![
+		"<http://example.org/alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> .",
+		"<http://example.org/alice> <http://schema.org/knows> <http://example.org/carol> ."
+	]);
+
+	// Nodes with a relative identifier are not relabeled as blank nodes.
+	assert_eq!(nquads_default(r#"{
+		"@id": "g",
+		"@graph": {"@id": "http://example.org/alice", "http://schema.org/name": "Alice"}
+	}"#), Vec::<String>::new());
+}
+
+#[test]
+fn malformed_terms() {
+	// Invalid subject, object and type IRIs.
+	assert!(nquads_default(r#"{"@id": "http://example.com/a b", "http://example.com/foo": "bar"}"#).is_empty());
+	assert!(nquads_default(r#"{"@id": "http://example.com/foo", "http://example.com/bar": {"@id": "http://example.com/baz z"}}"#).is_empty());
+	assert_eq!(nquads_default(r#"{"@id": "http://example.com/foo", "@type": ["http://example.com/bar", "http://in valid"]}"#), vec![
+		"<http://example.com/foo> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/bar> ."
+	]);
+
+	// Invalid language tag.
+	assert!(nquads_default(r#"{"@id": "http://example.com/foo", "http://example.com/bar": {"@value": "bar", "@language": "a b"}}"#).is_empty());
+
+	// Invalid graph name.
+	assert!(nquads_default(r#"{
+		"@id": "http://example.com/invalid gn",
+		"@graph": {"@id": "http://example.com/foo", "http://example.com/bar": "baz"}
+	}"#).is_empty());
+}
+
+#[test]
+fn lists() {
+	assert_eq!(nquads_default(r#"{
+		"@id": "http://example.org/a",
+		"http://example.org/p": {"@list": [1, {"@id": "relative"}, {"@id": "http://example.org/b"}]},
+		"http://example.org/q": {"@list": []}
+	}"#), vec![
+		// The item with a relative identifier has no `rdf:first`.
+		"_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+		"_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:b2 .",
+		"_:b2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:b3 .",
+		"_:b3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> <http://example.org/b> .",
+		"_:b3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .",
+		"<http://example.org/a> <http://example.org/p> _:b1 .",
+		"<http://example.org/a> <http://example.org/q> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> ."
+	]);
+}
+
+const DIRECTION: &str = r#"{
+	"@id": "http://example.org/a",
+	"http://example.org/label": {"@value": "no", "@language": "en-US", "@direction": "rtl"}
+}"#;
+
+#[test]
+fn rdf_direction() {
+	assert_eq!(nquads_default(DIRECTION), vec![
+		"<http://example.org/a> <http://example.org/label> \"no\"@en-US ."
+	]);
+
+	let options = rdf::Options {
+		rdf_direction: Some(RdfDirection::I18nDatatype),
+		..rdf::Options::default()
+	};
+	assert_eq!(nquads(DIRECTION, options), vec![
+		"<http://example.org/a> <http://example.org/label> \"no\"^^<https://www.w3.org/ns/i18n#en-us_rtl> ."
+	]);
+
+	let options = rdf::Options {
+		rdf_direction: Some(RdfDirection::CompoundLiteral),
+		..rdf::Options::default()
+	};
+	assert_eq!(nquads(DIRECTION, options), vec![
+		"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> \"no\" .",
+		"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#language> \"en-us\" .",
+		"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#direction> \"rtl\" .",
+		"<http://example.org/a> <http://example.org/label> _:b0 ."
+	]);
+}
+
+#[test]
+fn generalized_rdf() {
+	const DOC: &str = r#"{
+		"@context": {"p": "_:p"},
+		"@id": "http://example.org/a",
+		"p": "value",
+		"http://example.org/q": "other"
+	}"#;
+
+	assert_eq!(nquads_default(DOC), vec![
+		"<http://example.org/a> <http://example.org/q> \"other\" ."
+	]);
+
+	let options = rdf::Options {
+		produce_generalized_rdf: true,
+		..rdf::Options::default()
+	};
+	assert_eq!(nquads(DOC, options), vec![
+		"<http://example.org/a> _:b0 \"value\" .",
+		"<http://example.org/a> <http://example.org/q> \"other\" ."
+	]);
+}
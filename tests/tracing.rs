@@ -0,0 +1,168 @@
+#![cfg(feature="tracing")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use async_std::task;
+use futures::future;
+use iref::{Iri, IriBuf};
+use tracing::{
+	Event,
+	Metadata,
+	Subscriber,
+	span,
+	field::{Field, Visit}
+};
+use json_ld::{
+	Document,
+	ErrorCode,
+	FnLoader,
+	JsonContext,
+	expansion
+};
+
+/// Recorded span.
+#[derive(Clone, Debug)]
+struct Span {
+	name: &'static str,
+	fields: String,
+	parent: Option<usize>
+}
+
+#[derive(Default)]
+struct Spans {
+	spans: Vec<Span>,
+	entered: Vec<usize>
+}
+
+/// Subscriber recording every span, with the span entered when it was created as parent.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Spans>>);
+
+impl Recorder {
+	fn spans(&self) -> Vec<Span> {
+		self.0.lock().unwrap().spans.clone()
+	}
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.record_debug(field, &format_args!("{}", value))
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		if !self.0.is_empty() {
+			self.0.push(' ')
+		}
+
+		self.0.push_str(&format!("{}={:?}", field.name(), value))
+	}
+}
+
+impl Subscriber for Recorder {
+	fn enabled(&self, _: &Metadata) -> bool {
+		true
+	}
+
+	fn new_span(&self, attrs: &span::Attributes) -> span::Id {
+		let mut fields = Fields(String::new());
+		attrs.record(&mut fields);
+		let mut spans = self.0.lock().unwrap();
+		let parent = spans.entered.last().cloned();
+		spans.spans.push(Span {
+			name: attrs.metadata().name(),
+			fields: fields.0,
+			parent
+		});
+		span::Id::from_u64(spans.spans.len() as u64)
+	}
+
+	fn record(&self, _: &span::Id, _: &span::Record) {}
+
+	fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+	fn event(&self, _: &Event) {}
+
+	fn enter(&self, id: &span::Id) {
+		self.0.lock().unwrap().entered.push(id.into_u64() as usize - 1)
+	}
+
+	fn exit(&self, _: &span::Id) {
+		self.0.lock().unwrap().entered.pop();
+	}
+}
+
+fn loader() -> FnLoader<impl Send + FnMut(Iri) -> future::Ready<Result<String, ErrorCode>>> {
+	FnLoader::new(|url: Iri| future::ready(match url.as_str() {
+		"https://example.org/context" => Ok(r#"{"@context": {"name": "http://schema.org/name"}}"#.to_string()),
+		_ => Err(ErrorCode::LoadingDocumentFailed)
+	}))
+}
+
+fn expand(recorder: &Recorder, doc: &str) -> Result<(), ErrorCode> {
+	let doc = json::parse(doc).unwrap();
+	let base_url = IriBuf::new("https://example.org/doc").unwrap();
+	tracing::subscriber::with_default(recorder.clone(), || {
+		let mut loader = loader();
+		task::block_on(doc.expand_with(Some(base_url.as_iri()), &JsonContext::<IriBuf>::new(Some(base_url.as_iri())), &mut loader, expansion::Options::default()))
+			.map(|_| ())
+			.map_err(|e| e.code())
+	})
+}
+
+/// Index of the only span with the given name and fields.
+fn find(spans: &[Span], name: &str, fields: &str) -> usize {
+	let found: Vec<usize> = spans.iter().enumerate().filter(|(_, span)| span.name == name && span.fields == fields).map(|(i, _)| i).collect();
+	assert_eq!(found.len(), 1, "{} {{{}}} in {:#?}", name, fields, spans);
+	found[0]
+}
+
+/// Checks that `span` is nested in `ancestor`.
+fn nested(spans: &[Span], span: usize, ancestor: usize) -> bool {
+	match spans[span].parent {
+		Some(parent) => parent == ancestor || nested(spans, parent, ancestor),
+		None => false
+	}
+}
+
+#[test]
+fn spans() {
+	let recorder = Recorder::default();
+	expand(&recorder, r#"{
+		"@context": "https://example.org/context",
+		"name": "Alice",
+		"http://schema.org/knows": {"name": "Bob"}
+	}"#).unwrap();
+
+	let spans = recorder.spans();
+	let expand = find(&spans, "expand", "base_url=https://example.org/doc");
+	assert_eq!(spans[expand].parent, None);
+
+	let load_context = find(&spans, "load_context", "url=https://example.org/context");
+	let load_document = find(&spans, "load_document", "url=https://example.org/context");
+	let process_context = find(&spans, "process_context", "url=https://example.org/context");
+	assert_eq!(spans[load_document].parent, Some(load_context));
+	assert!(nested(&spans, load_context, expand));
+	assert!(nested(&spans, process_context, expand));
+
+	let knows = find(&spans, "expand_element", "active_property=http://schema.org/knows");
+	assert!(nested(&spans, knows, expand));
+	assert!(spans.iter().filter(|span| span.name == "expand_element").count() >= 3);
+}
+
+#[test]
+fn failed_load() {
+	let recorder = Recorder::default();
+	assert_eq!(expand(&recorder, r#"{"@context": "https://example.org/missing"}"#), Err(ErrorCode::LoadingRemoteContextFailed));
+
+	let spans = recorder.spans();
+	let load_context = find(&spans, "load_context", "url=https://example.org/missing");
+	let load_document = find(&spans, "load_document", "url=https://example.org/missing");
+	assert_eq!(spans[load_document].parent, Some(load_context));
+	assert!(spans.iter().all(|span| span.name != "process_context"));
+}
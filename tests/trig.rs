@@ -0,0 +1,139 @@
+#![cfg(feature="trig")]
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use std::collections::HashSet;
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Reference,
+	BlankId,
+	generator,
+	rdf::{
+		self,
+		Literal,
+		Prefixes,
+		Quad,
+		Term
+	}
+};
+
+fn id(iri: &str) -> Reference {
+	Reference::Id(IriBuf::new(iri).unwrap())
+}
+
+fn typed(value: &str, ty: &str) -> Term {
+	Term::Literal(Literal::Typed(value.to_string(), IriBuf::new(ty).unwrap()))
+}
+
+fn parse(input: &str) -> Vec<Quad> {
+	rdf::parse_trig(input, None, generator::Blank::new_with_prefix("p".to_string())).unwrap()
+}
+
+#[test]
+fn round_trip() {
+	let doc = json::parse(r#"{
+		"@context": {"@vocab": "http://schema.org/", "ex": "http://example.org/", "knows": {"@type": "@id"}},
+		"@id": "ex:graph",
+		"name": "Graph \"one\"\n",
+		"@graph": [
+			{"@id": "ex:alice", "@type": "Person", "knows": "ex:bob", "age": 34, "height": 1.7},
+			{"name": {"@value": "Anonyme", "@language": "fr"}, "member": true}
+		]
+	}"#).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	let quads = rdf::to_rdf(&expanded, generator::Blank::new(), rdf::Options::default()).unwrap();
+
+	let prefixes = Prefixes::new()
+		.with("", Iri::new("http://schema.org/").unwrap())
+		.with("ex", Iri::new("http://example.org/").unwrap());
+	let trig = rdf::to_trig(&quads, &prefixes);
+	let parsed = parse(&trig);
+
+	let expected: HashSet<_> = quads.iter().map(|quad| quad.to_string()).collect();
+	let actual: HashSet<_> = parsed.iter().map(|quad| quad.to_string()).collect();
+	assert_eq!(parsed.len(), quads.len());
+	assert_eq!(actual, expected)
+}
+
+#[test]
+fn syntax() {
+	let quads = parse(r#"
+		# Directives of both styles.
+		@prefix ex: <http://example.org/> .
+		PREFIX : <http://schema.org/>
+		@base <http://example.org/base/> .
+
+		<alice> a :Person ;
+			:name "Alice", 'Alicia'@es ;
+			:description """Multi
+line""" ;
+			:score -1.5, 2e3, 7 ;
+			:knows [ :name "Anonymous" ], ex:bob ;
+			:likes ( ex:tea _:coffee ) .
+
+		GRAPH ex:g { ex:bob :name "Bob" }
+		ex:h { ex:carol :name "Carol"^^<http://www.w3.org/2001/XMLSchema#token> . }
+		{ ex:dave :name "Dave!" }
+	"#);
+
+	let alice = id("http://example.org/base/alice");
+	let object = |predicate: &str| -> Vec<Term> {
+		quads.iter().filter(|q| q.subject == alice && q.predicate == id(predicate)).map(|q| q.object.clone()).collect()
+	};
+
+	assert_eq!(object("http://www.w3.org/1999/02/22-rdf-syntax-ns#type"), vec![Term::Ref(id("http://schema.org/Person"))]);
+	assert_eq!(object("http://schema.org/name"), vec![
+		typed("Alice", "http://www.w3.org/2001/XMLSchema#string"),
+		Term::Literal(Literal::LangString("Alicia".to_string(), "es".to_string()))
+	]);
+	assert_eq!(object("http://schema.org/description"), vec![typed("Multi\nline", "http://www.w3.org/2001/XMLSchema#string")]);
+	assert_eq!(object("http://schema.org/score"), vec![
+		typed("-1.5", "http://www.w3.org/2001/XMLSchema#decimal"),
+		typed("2e3", "http://www.w3.org/2001/XMLSchema#double"),
+		typed("7", "http://www.w3.org/2001/XMLSchema#integer")
+	]);
+
+	let knows = object("http://schema.org/knows");
+	assert_eq!(knows.len(), 2);
+	assert_eq!(knows[1], Term::Ref(id("http://example.org/bob")));
+	let anonymous = match &knows[0] {
+		Term::Ref(r @ Reference::Blank(_)) => r.clone(),
+		_ => panic!("not a blank node")
+	};
+	assert!(quads.iter().any(|q| q.subject == anonymous && q.object == typed("Anonymous", "http://www.w3.org/2001/XMLSchema#string")));
+
+	let first = id("http://www.w3.org/1999/02/22-rdf-syntax-ns#first");
+	let items: Vec<_> = quads.iter().filter(|q| q.predicate == first).map(|q| q.object.clone()).collect();
+	assert_eq!(items, vec![Term::Ref(id("http://example.org/tea")), Term::Ref(Reference::Blank(BlankId::new("coffee")))]);
+
+	let graph_of = |subject: &str| quads.iter().find(|q| q.subject == id(subject)).unwrap().graph.clone();
+	assert_eq!(graph_of("http://example.org/bob"), Some(id("http://example.org/g")));
+	assert_eq!(graph_of("http://example.org/carol"), Some(id("http://example.org/h")));
+	assert_eq!(graph_of("http://example.org/dave"), None);
+	assert!(quads.iter().any(|q| q.object == typed("Dave!", "http://www.w3.org/2001/XMLSchema#string")));
+	assert!(quads.iter().any(|q| q.object == typed("Carol", "http://www.w3.org/2001/XMLSchema#token")));
+	assert_eq!(quads.len(), 18)
+}
+
+#[test]
+fn errors() {
+	let error = |input: &str| rdf::parse_trig::<IriBuf, _>(input, None, generator::Blank::new()).unwrap_err();
+
+	assert_eq!(error("ex:a ex:b ex:c .").message, "undefined prefix `ex`");
+	assert_eq!(error("<a> <http://b> <http://c> .").message, "relative IRI `a` without base IRI");
+	let unterminated = error("<http://a> <http://b>\n\"c .");
+	assert_eq!(unterminated.line, 2);
+	assert_eq!(unterminated.message, "unterminated string");
+	assert_eq!(error("<http://a> <http://b> <http://c>").message, "expected `.`");
+	assert_eq!(error("GRAPH <http://g> { <http://a> <http://b> <http://c> ").message, "expected `.` or `}`");
+
+	// Relative IRIs are resolved against the given base.
+	let quads = rdf::parse_trig::<IriBuf, _>("<a> <b> <c> .", Some(Iri::new("http://example.org/").unwrap()), generator::Blank::new()).unwrap();
+	assert_eq!(quads[0].object, Term::Ref(id("http://example.org/c")))
+}
@@ -0,0 +1,108 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	context::Local,
+	NoLoader,
+	generator,
+	rdf::{
+		self,
+		Prefixes
+	}
+};
+
+const CONTEXT: &str = r#"{
+	"@vocab": "http://schema.org/",
+	"ex": "http://example.org/",
+	"xsd": "http://www.w3.org/2001/XMLSchema#",
+	"knows": {"@type": "@id"},
+	"birthDate": {"@type": "xsd:date"}
+}"#;
+
+fn quads(doc: &str) -> (Vec<rdf::Quad>, Prefixes) {
+	let context = json::parse(CONTEXT).unwrap();
+	let context: JsonContext<IriBuf> = task::block_on(context.process(&JsonContext::new(None), &mut NoLoader, None)).unwrap();
+
+	let mut doc = json::parse(doc).unwrap();
+	doc.insert("@context", json::parse(CONTEXT).unwrap()).unwrap();
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	let quads = rdf::to_rdf(&expanded, generator::Blank::new(), rdf::Options::default()).unwrap();
+	(quads, Prefixes::from_context(&context))
+}
+
+fn turtle(doc: &str) -> String {
+	let (quads, prefixes) = quads(doc);
+	rdf::to_turtle(&quads, &prefixes)
+}
+
+#[test]
+fn pretty_turtle() {
+	let output = turtle(r#"{
+		"@id": "ex:alice",
+		"@type": "Person",
+		"name": ["Alice", {"@value": "Alicia", "@language": "es"}],
+		"knows": ["ex:bob", "http://other.example/carol"],
+		"birthDate": "1990-01-01",
+		"age": 34,
+		"member": true,
+		"description": "Says \"hi\"\n"
+	}"#);
+
+	assert_eq!(output, r#"@prefix : <http://schema.org/> .
+@prefix ex: <http://example.org/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:alice a :Person ;
+	:age 34 ;
+	:birthDate "1990-01-01"^^xsd:date ;
+	:description "Says \"hi\"\n" ;
+	:knows ex:bob, <http://other.example/carol> ;
+	:member true ;
+	:name "Alice", "Alicia"@es .
+"#);
+}
+
+#[test]
+fn named_graphs() {
+	let (quads, prefixes) = quads(r#"{
+		"@id": "ex:graph",
+		"name": "Graph",
+		"@graph": [{"@id": "ex:bob", "name": "Bob"}, {"name": "Anonymous"}]
+	}"#);
+
+	assert_eq!(rdf::to_turtle(&quads, &prefixes), r#"@prefix : <http://schema.org/> .
+@prefix ex: <http://example.org/> .
+
+ex:graph :name "Graph" .
+"#);
+
+	assert_eq!(rdf::to_trig(&quads, &prefixes), r#"@prefix : <http://schema.org/> .
+@prefix ex: <http://example.org/> .
+
+ex:graph :name "Graph" .
+
+ex:graph {
+	_:b0 :name "Anonymous" .
+
+	ex:bob :name "Bob" .
+}
+"#);
+}
+
+#[test]
+fn prefixes() {
+	let prefixes = Prefixes::new()
+		.with("ex", Iri::new("http://example.org/").unwrap())
+		.with("exv", Iri::new("http://example.org/vocab#").unwrap());
+
+	assert_eq!(prefixes.compact(Iri::new("http://example.org/vocab#name").unwrap()).as_deref(), Some("exv:name"));
+	assert_eq!(prefixes.compact(Iri::new("http://example.org/alice").unwrap()).as_deref(), Some("ex:alice"));
+	assert_eq!(prefixes.compact(Iri::new("http://example.org/a/b").unwrap()), None);
+	assert_eq!(prefixes.compact(Iri::new("http://other.example/").unwrap()), None);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "vc")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	vc
+};
+
+fn expand(doc: &str) -> ExpandedDocument<IriBuf> {
+	task::block_on(json::parse(doc).unwrap().expand(&JsonContext::new(None), &mut NoLoader)).unwrap()
+}
+
+const CREDENTIAL: &str = r#"{
+	"@id": "http://example.org/credentials/1",
+	"@type": "https://www.w3.org/2018/credentials#VerifiableCredential",
+	"https://www.w3.org/2018/credentials#credentialSubject": {
+		"@id": "_:subject",
+		"http://schema.org/name": "Alice"
+	},
+	"https://w3id.org/security#proof": {
+		"@graph": {
+			"@type": "https://w3id.org/security#Ed25519Signature2020",
+			"http://purl.org/dc/terms/created": "2020-01-01T00:00:00Z",
+			"https://w3id.org/security#proofValue": "z3FXQ"
+		}
+	}
+}"#;
+
+#[test]
+fn signing_input() {
+	let credential = expand(CREDENTIAL);
+	let input = vc::signing_input(&credential).unwrap();
+	assert_eq!(input, "<http://example.org/credentials/1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/2018/credentials#VerifiableCredential> .
+<http://example.org/credentials/1> <https://www.w3.org/2018/credentials#credentialSubject> _:c14n0 .
+_:c14n0 <http://schema.org/name> \"Alice\" .
+");
+
+	// Blank node labels and the document form do not change the signing input.
+	let other = expand(r#"[
+		{"@id": "_:someone", "http://schema.org/name": "Alice"},
+		{
+			"@id": "http://example.org/credentials/1",
+			"@type": "https://www.w3.org/2018/credentials#VerifiableCredential",
+			"https://www.w3.org/2018/credentials#credentialSubject": {"@id": "_:someone"}
+		}
+	]"#);
+	assert_eq!(vc::signing_input(&other).unwrap(), input);
+
+	// The proof options do not include the proof value.
+	let proofs = vc::proofs(&credential);
+	assert_eq!(proofs.len(), 1);
+	let options = vc::proof_signing_input(proofs[0]).unwrap();
+	assert_eq!(options, "_:c14n0 <http://purl.org/dc/terms/created> \"2020-01-01T00:00:00Z\" .
+_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://w3id.org/security#Ed25519Signature2020> .
+");
+}
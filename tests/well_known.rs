@@ -0,0 +1,97 @@
+#![cfg(feature = "well-known")]
+
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use sha2::{Digest, Sha256};
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	Loader,
+	NoLoader,
+	preset::{
+		activity_streams,
+		did,
+		well_known::{
+			self,
+			WellKnownLoader
+		}
+	},
+	util::AsJson
+};
+
+#[test]
+fn contexts_are_pinned() {
+	let mut loader = WellKnownLoader::new(NoLoader);
+	for (url, _) in well_known::CONTEXTS {
+		let url = Iri::new(url).unwrap();
+		assert!(loader.contains(url));
+		task::block_on(loader.load(url)).unwrap();
+	}
+
+	assert!(!loader.contains(Iri::new("https://example.org/context").unwrap()));
+	assert!(task::block_on(loader.load(Iri::new("https://example.org/context").unwrap())).is_err());
+}
+
+/// SHA-256 digests of the published contexts.
+const PUBLISHED: &[(&str, &str)] = &[
+	(well_known::CREDENTIALS_V1_URL, "ab4ddd9a531758807a79a5b450510d61ae8d147eab966cc9a200c07095b0cdcc"),
+	(did::CONTEXT_URL, "4f3eae5568c9c5f036a082088f9e192019ee06faa78973c87ff91d5421b88dad"),
+	(well_known::SECURITY_V1_URL, "abaf97e107d1925503c433dcaf3ffd2831697cacc1ed2a293e1bc8030d5bcb47"),
+	(well_known::SECURITY_V2_URL, "611bd81151d5efe53ec9aed78f5a813e577287bc9451ee26d6879b1b8b46bc3b")
+];
+
+#[test]
+fn contexts_are_published_files() {
+	for (url, context) in well_known::CONTEXTS {
+		// The ActivityStreams copy is not byte-identical to the published context (see `NOTICE.md`).
+		if *url == activity_streams::CONTEXT_URL {
+			continue
+		}
+
+		let (_, digest) = PUBLISHED.iter().find(|(published, _)| published == url).expect("unknown context");
+		assert_eq!(format!("{:x}", Sha256::digest(context.as_bytes())), *digest, "{} differs from the published context", url);
+	}
+}
+
+#[test]
+fn credential() {
+	let doc = json::parse(r#"{
+		"@context": "https://www.w3.org/2018/credentials/v1",
+		"id": "http://example.org/credentials/1",
+		"type": ["VerifiableCredential"],
+		"issuer": "did:example:issuer",
+		"issuanceDate": "2020-01-01T00:00:00Z",
+		"credentialSubject": {"id": "did:example:subject"}
+	}"#).unwrap();
+
+	let mut loader = WellKnownLoader::new(NoLoader);
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut loader)).unwrap();
+	let json = expanded.as_json();
+	let node = &json[0];
+	assert_eq!(node["@type"][0], "https://www.w3.org/2018/credentials#VerifiableCredential");
+	assert_eq!(node["https://www.w3.org/2018/credentials#issuer"][0]["@id"], "did:example:issuer");
+	assert_eq!(node["https://www.w3.org/2018/credentials#issuanceDate"][0]["@type"], "http://www.w3.org/2001/XMLSchema#dateTime");
+	assert_eq!(node["https://www.w3.org/2018/credentials#credentialSubject"][0]["@id"], "did:example:subject");
+}
+
+#[test]
+fn security_v2_includes_v1() {
+	let doc = json::parse(r#"{
+		"@context": "https://w3id.org/security/v2",
+		"id": "did:example:key",
+		"controller": "did:example:controller",
+		"publicKeyBase58": "abc"
+	}"#).unwrap();
+
+	let mut loader = WellKnownLoader::new(NoLoader);
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut loader)).unwrap();
+	let json = expanded.as_json();
+	let node = &json[0];
+	assert_eq!(node["https://w3id.org/security#controller"][0]["@id"], "did:example:controller");
+	assert_eq!(node["https://w3id.org/security#publicKeyBase58"][0]["@value"], "abc");
+}
@@ -0,0 +1,131 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json::number::Number;
+use json_ld::{
+	Document,
+	ExpandedDocument,
+	JsonContext,
+	NoLoader,
+	Object,
+	Value,
+	Reference
+};
+
+/// Expand the value of `http://example.org/p` in the given node.
+fn value(v: &str) -> Value {
+	let doc = json::parse(&format!(r#"{{
+		"@context": {{"xsd": "http://www.w3.org/2001/XMLSchema#"}},
+		"http://example.org/p": {}
+	}}"#, v)).unwrap();
+
+	let expanded: ExpandedDocument<IriBuf> = task::block_on(doc.expand(&JsonContext::new(None), &mut NoLoader)).unwrap();
+	match expanded.objects()[0].inner() {
+		Object::Node(node) => {
+			let p = Reference::Id(IriBuf::new("http://example.org/p").unwrap());
+			match node.get_any(&p).unwrap().inner() {
+				Object::Value(value) => value.clone(),
+				_ => panic!("not a value")
+			}
+		},
+		_ => panic!("not a node")
+	}
+}
+
+fn typed(lexical: &str, datatype: &str) -> Value {
+	value(&format!(r#"{{"@value": "{}", "@type": "xsd:{}"}}"#, lexical, datatype))
+}
+
+#[test]
+fn integers() {
+	assert_eq!(value("42").as_integer(), Some(42));
+	assert_eq!(value("-4.2e1").as_integer(), Some(-42));
+	assert_eq!(value("4.5").as_integer(), None);
+	assert_eq!(typed("-0042", "integer").as_integer(), Some(-42));
+	assert_eq!(typed("+7", "nonNegativeInteger").as_integer(), Some(7));
+	assert_eq!(typed("7", "decimal").as_integer(), None);
+	assert_eq!(typed("4 2", "integer").as_integer(), None);
+	assert_eq!(typed("99999999999999999999", "integer").as_integer(), None);
+	assert_eq!(value(r#""42""#).as_integer(), None);
+	assert_eq!(value(r#"{"@value": 42, "@type": "xsd:double"}"#).as_integer(), None);
+}
+
+#[test]
+fn decimals() {
+	assert_eq!(typed("12.50", "decimal").as_decimal(), Some(Number::from_parts(true, 125, -1)));
+	assert_eq!(typed("-.5", "decimal").as_decimal(), Some(Number::from_parts(false, 5, -1)));
+	assert_eq!(typed("3", "int").as_decimal(), Some(Number::from(3)));
+	assert_eq!(value("0.1").as_decimal(), Some(Number::from_parts(true, 1, -1)));
+	assert_eq!(typed(".", "decimal").as_decimal(), None);
+	assert_eq!(typed("1e3", "decimal").as_decimal(), None);
+	assert_eq!(typed("1.5", "double").as_decimal(), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn dates() {
+	use chrono::{Datelike, NaiveDate, TimeZone, FixedOffset};
+
+	let issued = typed("2010-01-01T19:23:24.5-05:00", "dateTime").as_datetime().unwrap();
+	assert_eq!(issued, FixedOffset::west_opt(5 * 3600).unwrap().with_ymd_and_hms(2010, 1, 1, 19, 23, 24).unwrap() + chrono::Duration::milliseconds(500));
+	assert_eq!(typed("2010-01-01T19:23:24Z", "dateTimeStamp").as_datetime().unwrap().year(), 2010);
+	assert_eq!(typed("2010-01-01T19:23:24", "dateTime").as_datetime(), None);
+	assert_eq!(typed("2010-01-01", "dateTime").as_datetime(), None);
+
+	assert_eq!(typed("2002-09-24", "date").as_date(), NaiveDate::from_ymd_opt(2002, 9, 24));
+	assert_eq!(typed("2002-09-24Z", "date").as_date(), NaiveDate::from_ymd_opt(2002, 9, 24));
+	assert_eq!(typed("2002-09-24-06:00", "date").as_date(), NaiveDate::from_ymd_opt(2002, 9, 24));
+	assert_eq!(typed("2002-09-24+06:00", "date").as_date(), NaiveDate::from_ymd_opt(2002, 9, 24));
+	assert_eq!(typed("2002-02-30", "date").as_date(), None);
+	assert_eq!(typed("2002-09-24", "string").as_date(), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn durations() {
+	let duration = typed("P1Y2M3DT4H5M6.5S", "duration").as_duration().unwrap();
+	assert_eq!(duration.months, 14);
+	assert_eq!(duration.time, chrono::Duration::milliseconds((((3 * 24 + 4) * 60 + 5) * 60 + 6) * 1000 + 500));
+
+	let duration = typed("-PT90M", "dayTimeDuration").as_duration().unwrap();
+	assert_eq!(duration.months, 0);
+	assert_eq!(duration.time, chrono::Duration::minutes(-90));
+
+	assert_eq!(typed("P2Y", "yearMonthDuration").as_duration().unwrap().months, 24);
+
+	for invalid in &["P", "PT", "P1H", "PT1D", "P1M1Y", "PT1.5H", "P-1D", "1D", "P1DT"] {
+		assert_eq!(typed(invalid, "duration").as_duration(), None, "{}", invalid)
+	}
+}
+
+#[test]
+fn semantic_equality() {
+	assert!(value("1").semantically_equals(&typed("1.0", "decimal")));
+	assert!(typed("01", "integer").semantically_equals(&typed("1E0", "double")));
+	assert!(typed("0.50", "decimal").semantically_equals(&value("0.5")));
+	assert!(typed("INF", "float").semantically_equals(&typed("+INF", "double")));
+	assert!(!typed("NaN", "double").semantically_equals(&typed("NaN", "float")));
+	assert!(!typed("1", "integer").semantically_equals(&typed("1.5", "decimal")));
+
+	assert!(value("true").semantically_equals(&typed("1", "boolean")));
+	assert!(typed("0", "boolean").semantically_equals(&typed("false", "boolean")));
+	assert!(!value("true").semantically_equals(&value("1")));
+
+	assert!(value(r#""a""#).semantically_equals(&typed("a", "string")));
+	assert!(!typed("1", "string").semantically_equals(&typed("1", "integer")));
+	assert!(value(r#"{"@value": "chat", "@language": "fr-FR"}"#).semantically_equals(&value(r#"{"@value": "chat", "@language": "fr-fr"}"#)));
+	assert!(!value(r#"{"@value": "chat", "@language": "fr"}"#).semantically_equals(&value(r#"{"@value": "chat", "@language": "en"}"#)));
+	assert!(!typed("a", "token").semantically_equals(&typed("a", "string")));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn semantic_equality_of_dates() {
+	assert!(typed("2010-01-01T19:00:00-05:00", "dateTime").semantically_equals(&typed("2010-01-02T00:00:00Z", "dateTime")));
+	assert!(!typed("2010-01-01T19:00:00Z", "dateTime").semantically_equals(&typed("2010-01-01T19:00:00+01:00", "dateTime")));
+	assert!(typed("PT1H", "duration").semantically_equals(&typed("PT60M", "dayTimeDuration")));
+	assert!(typed("P1Y", "yearMonthDuration").semantically_equals(&typed("P12M", "duration")));
+}